@@ -0,0 +1,20 @@
+//! Compiles the gRPC service definition in proto/mcp.proto when the `grpc` feature is on.
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    #[cfg(feature = "grpc")]
+    compile_protos();
+}
+
+/// Generate the service's messages and server, with the bundled protoc so building
+/// doesn't need one installed
+#[cfg(feature = "grpc")]
+fn compile_protos() {
+    println!("cargo:rerun-if-changed=proto/mcp.proto");
+    let protoc = protoc_bin_vendored::protoc_bin_path().unwrap_or_else(|e| panic!("No bundled protoc: {e}"));
+    std::env::set_var("PROTOC", protoc);
+    tonic_prost_build::configure()
+        .build_client(false)
+        .compile_protos(&["proto/mcp.proto"], &["proto"])
+        .unwrap_or_else(|e| panic!("Can't compile mcp.proto: {e}"));
+}
@@ -0,0 +1,765 @@
+//! Compatibility with WebDriver BiDi, for tools that speak it, with `Builder::bidi_compat(true)`.
+//!
+//! An endpoint two ports after the WebSocket server's (see [`crate::compat`]) takes BiDi commands
+//! like `{"id": 1, "method": "script.evaluate", "params": {...}}`. Browsing contexts are windows,
+//! with their labels as context IDs, and each command runs as the tauri-mcp command it translates
+//! to, in its context's window:
+//!
+//! - `script.evaluate` runs as `execute_js`, with values serialized as BiDi's `RemoteValue`
+//! - `browsingContext.captureScreenshot` runs as `screenshot`
+//! - `browsingContext.getTree` runs as `window_list`, with each window a top-level context
+//! - `input.performActions` runs as an `interact` sequence, one input source after another
+//! - `session.new`, `session.status`, `session.end`, and `input.releaseActions` are answered
+//!   right away
+//!
+//! Other commands get an "unknown command" error. It's a translation layer rather than a browser,
+//! so there are no events, handles, or shared node IDs, and realms are their context's label.
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Runtime};
+use tracing::warn;
+
+use crate::compat;
+
+/// Commands the endpoint translates, listed in "unknown command" errors
+pub const BIDI_COMMANDS: &[&str] = &[
+    "script.evaluate",
+    "browsingContext.captureScreenshot",
+    "browsingContext.getTree",
+    "input.performActions",
+    "input.releaseActions",
+    "session.new",
+    "session.status",
+    "session.end",
+];
+
+/// Longest pause the endpoint keeps between actions, the `interact` sequence's `delayMs` limit
+const MAX_PAUSE_MS: u64 = 5000;
+
+/// A BiDi command message
+#[derive(Debug, Deserialize)]
+struct BidiRequest {
+    id: u64,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// An error answer, with one of BiDi's error codes
+#[derive(Debug, PartialEq, Eq)]
+struct BidiError {
+    error: &'static str,
+    message: String,
+}
+
+impl BidiError {
+    fn invalid_argument(message: impl Into<String>) -> Self {
+        Self {
+            error: "invalid argument",
+            message: message.into(),
+        }
+    }
+
+    fn unsupported(message: impl Into<String>) -> Self {
+        Self {
+            error: "unsupported operation",
+            message: message.into(),
+        }
+    }
+}
+
+/// What a BiDi command does: run a tauri-mcp command, or answer without one
+#[derive(Debug, PartialEq)]
+enum Translation {
+    Command(&'static str, Value),
+    Answer(Value),
+}
+
+/// Run a BiDi message and build its answer
+pub async fn answer_message<R: Runtime>(app: &AppHandle<R>, text: &str) -> Value {
+    let request: BidiRequest = match serde_json::from_str(text) {
+        Ok(request) => request,
+        Err(e) => {
+            warn!("Invalid BiDi message: {e}");
+            return json!({
+                "type": "error",
+                "id": null,
+                "error": "invalid argument",
+                "message": format!("Invalid BiDi message: {e}"),
+            });
+        }
+    };
+
+    let result = match translate(&request.method, &request.params) {
+        Ok(Translation::Answer(result)) => Ok(result),
+        Ok(Translation::Command(command, args)) => {
+            let result = compat::run(app, format!("bidi-{}", request.id), command, args).await;
+            answer(&request.method, &request.params, result)
+        }
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(result) => json!({ "type": "success", "id": request.id, "result": result }),
+        Err(e) => json!({ "type": "error", "id": request.id, "error": e.error, "message": e.message }),
+    }
+}
+
+/// Translate a BiDi command and its params to a tauri-mcp command and its args
+fn translate(method: &str, params: &Value) -> Result<Translation, BidiError> {
+    match method {
+        "script.evaluate" => evaluate_args(params).map(|args| Translation::Command("execute_js", args)),
+        "browsingContext.captureScreenshot" => {
+            screenshot_args(params).map(|args| Translation::Command("screenshot", args))
+        }
+        "browsingContext.getTree" => Ok(Translation::Command("window_list", json!({ "sort": "label" }))),
+        "input.performActions" => actions_args(params),
+        // Every action is let go of by the end of its performActions, so nothing is held
+        "input.releaseActions" | "session.end" => Ok(Translation::Answer(json!({}))),
+        "session.new" => Ok(Translation::Answer(json!({
+            "sessionId": "tauri-mcp",
+            "capabilities": {
+                "acceptInsecureCerts": false,
+                "browserName": "tauri-mcp",
+                "browserVersion": env!("CARGO_PKG_VERSION"),
+                "platformName": std::env::consts::OS,
+                "setWindowRect": false,
+                "userAgent": "",
+                "proxy": {},
+            },
+        }))),
+        "session.status" => Ok(Translation::Answer(json!({
+            "ready": true,
+            "message": "The tauri-mcp BiDi endpoint is ready",
+        }))),
+        _ => Err(BidiError {
+            error: "unknown command",
+            message: format!(
+                "'{method}' isn't supported. The tauri-mcp BiDi endpoint translates {}.",
+                BIDI_COMMANDS.join(", ")
+            ),
+        }),
+    }
+}
+
+/// The window label of a command's `context`, or of `script.evaluate`'s `target`
+fn context_arg(params: &Value, name: &str) -> Result<Value, BidiError> {
+    params
+        .get(name)
+        .filter(|value| value.is_string())
+        .cloned()
+        .ok_or_else(|| BidiError::invalid_argument(format!("'{name}' must be a browsing context ID string")))
+}
+
+/// `execute_js` args for `script.evaluate`, running the expression in the target's window
+fn evaluate_args(params: &Value) -> Result<Value, BidiError> {
+    let expression = params
+        .get("expression")
+        .and_then(Value::as_str)
+        .ok_or_else(|| BidiError::invalid_argument("script.evaluate needs an 'expression' string"))?;
+    let await_promise = params
+        .get("awaitPromise")
+        .and_then(Value::as_bool)
+        .ok_or_else(|| BidiError::invalid_argument("script.evaluate needs an 'awaitPromise' boolean"))?;
+    let target = params.get("target").unwrap_or(&Value::Null);
+    // Realms are their context's label, so either works as the target
+    let context = context_arg(target, "context").or_else(|_| context_arg(target, "realm"))?;
+    let max_depth = params
+        .pointer("/serializationOptions/maxObjectDepth")
+        .cloned()
+        .unwrap_or(Value::Null);
+
+    let script = include_str!("scripts/bidi-evaluate.js");
+    let call_args = [json!(expression), json!(await_promise), max_depth, context.clone()]
+        .iter()
+        .map(Value::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    Ok(json!({
+        "script": format!("{script}\nreturn window.__tauriMcpBidiEvaluate({call_args})"),
+        "windowId": context,
+    }))
+}
+
+/// `screenshot` args for `browsingContext.captureScreenshot`'s `format` and `clip`
+fn screenshot_args(params: &Value) -> Result<Value, BidiError> {
+    let mut args = Map::new();
+    args.insert("windowId".to_string(), context_arg(params, "context")?);
+    if let Some(origin @ Value::String(_)) = params.get("origin").filter(|origin| *origin != "viewport") {
+        return Err(BidiError::unsupported(format!(
+            "Screenshots are of the viewport, not the {origin} origin"
+        )));
+    }
+    if let Some(format) = params.get("format").filter(|format| !format.is_null()) {
+        match format.get("type").and_then(Value::as_str) {
+            Some("image/png") => {}
+            Some("image/jpeg") => {
+                args.insert("format".to_string(), "jpeg".into());
+            }
+            _ => {
+                return Err(BidiError::invalid_argument(format!(
+                    "Screenshots can be image/png or image/jpeg, got: {format}"
+                )))
+            }
+        }
+        // BiDi's quality goes from 0 to 1, the plugin's from 0 to 100
+        if let Some(quality) = format.get("quality").and_then(Value::as_f64) {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)] // Clamped to 0..=100
+            let quality = (quality.clamp(0.0, 1.0) * 100.0).round() as u64;
+            args.insert("quality".to_string(), quality.into());
+        }
+    }
+    if let Some(clip) = params.get("clip").filter(|clip| !clip.is_null()) {
+        if clip.get("type").and_then(Value::as_str) != Some("box") {
+            return Err(BidiError::unsupported(
+                "Only box clips are supported, as there are no shared element IDs",
+            ));
+        }
+        let field = |name: &str| clip.get(name).cloned().unwrap_or(Value::Null);
+        args.insert(
+            "clip".to_string(),
+            json!({ "x": field("x"), "y": field("y"), "width": field("width"), "height": field("height") }),
+        );
+    }
+    Ok(Value::Object(args))
+}
+
+/// An `interact` sequence for `input.performActions`, or an empty answer if the actions do
+/// nothing the plugin can send
+fn actions_args(params: &Value) -> Result<Translation, BidiError> {
+    let context = context_arg(params, "context")?;
+    let sources = params
+        .get("actions")
+        .and_then(Value::as_array)
+        .ok_or_else(|| BidiError::invalid_argument("input.performActions needs an 'actions' array"))?;
+    let mut steps = Steps::default();
+    for source in sources {
+        let actions = source
+            .get("actions")
+            .and_then(Value::as_array)
+            .ok_or_else(|| BidiError::invalid_argument("Each input source needs an 'actions' array"))?;
+        match source.get("type").and_then(Value::as_str) {
+            Some("pointer") => pointer_steps(actions, &mut steps)?,
+            Some("key") => key_steps(actions, &mut steps)?,
+            Some("wheel") => wheel_steps(actions, &mut steps)?,
+            Some("none") => {
+                for action in actions {
+                    steps.pause(action);
+                }
+            }
+            _ => {
+                return Err(BidiError::invalid_argument(format!(
+                    "Input sources are pointer, key, wheel, or none, got: {}",
+                    source.get("type").unwrap_or(&Value::Null)
+                )))
+            }
+        }
+    }
+    if steps.steps.is_empty() {
+        return Ok(Translation::Answer(json!({})));
+    }
+    Ok(Translation::Command(
+        "interact",
+        json!({ "action": "sequence", "actions": steps.steps, "windowId": context }),
+    ))
+}
+
+/// `interact` steps built from input actions, with pauses kept for the step after them
+#[derive(Debug, Default)]
+struct Steps {
+    steps: Vec<Value>,
+    delay_ms: u64,
+}
+
+impl Steps {
+    fn push(&mut self, mut step: Value) {
+        if self.delay_ms > 0 {
+            step["delayMs"] = self.delay_ms.min(MAX_PAUSE_MS).into();
+            self.delay_ms = 0;
+        }
+        self.steps.push(step);
+    }
+
+    fn pause(&mut self, action: &Value) {
+        self.delay_ms += action.get("duration").and_then(Value::as_u64).unwrap_or(0);
+    }
+}
+
+/// Where a pointer is, and where it went down if a button is pressed
+#[derive(Debug, Default)]
+struct Pointer {
+    x: f64,
+    y: f64,
+    pressed: Option<(f64, f64, u64)>,
+    /// Whether it moved somewhere it hasn't hovered yet
+    moved: bool,
+}
+
+impl Pointer {
+    /// Hover where the pointer moved to, if it didn't press there
+    fn hover(&mut self, steps: &mut Steps) {
+        if std::mem::take(&mut self.moved) {
+            steps.push(json!({ "action": "hover", "x": self.x, "y": self.y }));
+        }
+    }
+}
+
+/// A pointer's moves hover, a press and release at one spot clicks, and one elsewhere drags
+fn pointer_steps(actions: &[Value], steps: &mut Steps) -> Result<(), BidiError> {
+    let mut pointer = Pointer::default();
+    for action in actions {
+        match action.get("type").and_then(Value::as_str) {
+            Some("pointerMove") => {
+                if pointer.pressed.is_none() {
+                    pointer.hover(steps);
+                    pointer.moved = true;
+                }
+                (pointer.x, pointer.y) = move_target(action, &pointer)?;
+            }
+            Some("pointerDown") => {
+                pointer.moved = false;
+                pointer.pressed = Some((pointer.x, pointer.y, button(action)));
+            }
+            Some("pointerUp") => {
+                let Some((x, y, button)) = pointer.pressed.take() else {
+                    continue;
+                };
+                steps.push(release_step((x, y), (pointer.x, pointer.y), button, steps.steps.last()));
+                if steps.steps.len() >= 2 && steps.steps.last().is_some_and(|step| step["action"] == "double_click") {
+                    // The second click of a double click replaces the first
+                    steps.steps.remove(steps.steps.len() - 2);
+                }
+            }
+            Some("pause") => {
+                pointer.hover(steps);
+                steps.pause(action);
+            }
+            _ => return Err(unknown_action("pointer", action)),
+        }
+    }
+    if pointer.pressed.is_some() {
+        return Err(BidiError::unsupported(
+            "A pointerDown needs its pointerUp in the same input.performActions, as buttons can't stay pressed between commands",
+        ));
+    }
+    pointer.hover(steps);
+    Ok(())
+}
+
+/// Where a `pointerMove` goes, from the viewport's corner or from the pointer
+fn move_target(action: &Value, pointer: &Pointer) -> Result<(f64, f64), BidiError> {
+    let coordinate = |name: &str| {
+        action
+            .get(name)
+            .and_then(Value::as_f64)
+            .ok_or_else(|| BidiError::invalid_argument(format!("pointerMove needs a numeric '{name}'")))
+    };
+    let (x, y) = (coordinate("x")?, coordinate("y")?);
+    match action.get("origin") {
+        None | Some(Value::Null) => Ok((x, y)),
+        Some(origin) if origin == "viewport" => Ok((x, y)),
+        Some(origin) if origin == "pointer" => Ok((pointer.x + x, pointer.y + y)),
+        Some(_) => Err(BidiError::unsupported(
+            "Element origins aren't supported, as there are no shared element IDs. Use viewport coordinates.",
+        )),
+    }
+}
+
+fn button(action: &Value) -> u64 {
+    action.get("button").and_then(Value::as_u64).unwrap_or(0)
+}
+
+/// The step for a release: a click where the button went down, or a drag from there
+fn release_step(from: (f64, f64), to: (f64, f64), button: u64, previous: Option<&Value>) -> Value {
+    #[allow(clippy::float_cmp)] // Positions are copied, never computed
+    if from != to {
+        return json!({
+            "action": "drag",
+            "from": { "x": from.0, "y": from.1 },
+            "to": { "x": to.0, "y": to.1 },
+        });
+    }
+    let click = json!({ "action": "click", "x": to.0, "y": to.1 });
+    let action = match button {
+        2 => "rightclick",
+        _ if previous == Some(&click) => "double_click",
+        _ => "click",
+    };
+    json!({ "action": action, "x": to.0, "y": to.1 })
+}
+
+/// Keys pressed become a `keyboard_sequence`, with the modifiers held at the time before each key
+fn key_steps(actions: &[Value], steps: &mut Steps) -> Result<(), BidiError> {
+    let mut held: Vec<&'static str> = Vec::new();
+    let mut keys: Vec<&str> = Vec::new();
+    let flush = |keys: &mut Vec<&str>, steps: &mut Steps| {
+        if !keys.is_empty() {
+            steps.push(json!({ "action": "keyboard_sequence", "keys": std::mem::take(keys) }));
+        }
+    };
+    for action in actions {
+        let kind = action.get("type").and_then(Value::as_str);
+        if kind == Some("pause") {
+            flush(&mut keys, steps);
+            steps.pause(action);
+            continue;
+        }
+        let value = action
+            .get("value")
+            .and_then(Value::as_str)
+            .ok_or_else(|| BidiError::invalid_argument("Key actions need a 'value' string"))?;
+        let key = key_name(value)?;
+        let modifier = ["shift", "control", "alt", "meta"]
+            .into_iter()
+            .find(|name| *name == key);
+        match (kind, modifier) {
+            (Some("keyDown"), Some(modifier)) => held.push(modifier),
+            (Some("keyUp"), Some(modifier)) => held.retain(|name| *name != modifier),
+            (Some("keyDown"), None) => {
+                keys.extend(&held);
+                keys.push(key);
+            }
+            (Some("keyUp"), None) => {}
+            _ => return Err(unknown_action("key", action)),
+        }
+    }
+    flush(&mut keys, steps);
+    Ok(())
+}
+
+/// The `keyboard_sequence` name for a key's value, which is a character or one of WebDriver's
+/// private-use code points for named keys
+fn key_name(value: &str) -> Result<&str, BidiError> {
+    let mut chars = value.chars();
+    let (Some(c), None) = (chars.next(), chars.next()) else {
+        return Err(BidiError::invalid_argument(format!(
+            "A key's value must be a single character, got: {value:?}"
+        )));
+    };
+    Ok(match c {
+        ' ' | '\u{E00D}' => "space",
+        '\u{E003}' => "backspace",
+        '\u{E004}' => "tab",
+        '\u{E006}' | '\u{E007}' => "enter",
+        '\u{E008}' | '\u{E050}' => "shift",
+        '\u{E009}' | '\u{E051}' => "control",
+        '\u{E00A}' | '\u{E052}' => "alt",
+        '\u{E00C}' => "escape",
+        '\u{E00E}' => "pageup",
+        '\u{E00F}' => "pagedown",
+        '\u{E010}' => "end",
+        '\u{E011}' => "home",
+        '\u{E012}' => "left",
+        '\u{E013}' => "up",
+        '\u{E014}' => "right",
+        '\u{E015}' => "down",
+        '\u{E016}' => "insert",
+        '\u{E017}' => "delete",
+        '\u{E031}' => "f1",
+        '\u{E032}' => "f2",
+        '\u{E033}' => "f3",
+        '\u{E034}' => "f4",
+        '\u{E035}' => "f5",
+        '\u{E036}' => "f6",
+        '\u{E037}' => "f7",
+        '\u{E038}' => "f8",
+        '\u{E039}' => "f9",
+        '\u{E03A}' => "f10",
+        '\u{E03B}' => "f11",
+        '\u{E03C}' => "f12",
+        '\u{E03D}' | '\u{E053}' => "meta",
+        '\u{E000}'..='\u{F8FF}' => {
+            return Err(BidiError::unsupported(format!(
+                "Key U+{:04X} isn't supported",
+                u32::from(c)
+            )))
+        }
+        _ => value,
+    })
+}
+
+/// Wheel scrolls become `interact` scrolls by their deltas
+fn wheel_steps(actions: &[Value], steps: &mut Steps) -> Result<(), BidiError> {
+    for action in actions {
+        match action.get("type").and_then(Value::as_str) {
+            Some("scroll") => steps.push(json!({
+                "action": "scroll",
+                "deltaX": action.get("deltaX").cloned().unwrap_or_else(|| json!(0)),
+                "deltaY": action.get("deltaY").cloned().unwrap_or_else(|| json!(0)),
+            })),
+            Some("pause") => steps.pause(action),
+            _ => return Err(unknown_action("wheel", action)),
+        }
+    }
+    Ok(())
+}
+
+fn unknown_action(source: &str, action: &Value) -> BidiError {
+    BidiError::invalid_argument(format!(
+        "Unknown {source} action: {}",
+        action.get("type").unwrap_or(&Value::Null)
+    ))
+}
+
+/// Turn a command's result into the BiDi answer for the command
+fn answer(method: &str, params: &Value, result: Result<Value, String>) -> Result<Value, BidiError> {
+    let result = result.map_err(|message| {
+        // Contexts are window labels, so a missing window is a missing context
+        let error = if message.starts_with("Window '") && message.contains("' not found") {
+            "no such frame"
+        } else {
+            "unknown error"
+        };
+        BidiError { error, message }
+    })?;
+    match method {
+        "script.evaluate" => {
+            let mut result = result;
+            let target = params.get("target").unwrap_or(&Value::Null);
+            result["realm"] = target.get("context").or_else(|| target.get("realm")).cloned().into();
+            Ok(result)
+        }
+        "browsingContext.captureScreenshot" => {
+            let image = result
+                .get("image")
+                .unwrap_or(&result)
+                .as_str()
+                .ok_or_else(|| BidiError {
+                    error: "unknown error",
+                    message: "The screenshot has no image".to_string(),
+                })?;
+            // BiDi gives bare base64, without the data URL's prefix
+            let data = image.split_once(',').map_or(image, |(_, data)| data);
+            Ok(json!({ "data": data }))
+        }
+        "browsingContext.getTree" => Ok(json!({ "contexts": contexts(&result, params.get("root")) })),
+        _ => Ok(json!({})),
+    }
+}
+
+/// Each window as a top-level browsing context, or only the `root` one
+fn contexts(windows: &Value, root: Option<&Value>) -> Vec<Value> {
+    windows
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|window| {
+            root.filter(|root| !root.is_null())
+                .map_or(true, |root| window["label"] == *root)
+        })
+        .map(|window| {
+            json!({
+                "context": window["label"],
+                "url": window["url"],
+                "children": [],
+                "parent": null,
+                "userContext": "default",
+                "originalOpener": null,
+                "clientWindow": window["label"],
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn sequence(params: &Value) -> Vec<Value> {
+        let Translation::Command("interact", args) = actions_args(params).unwrap() else {
+            panic!("input.performActions should run an interact sequence");
+        };
+        assert_eq!(args["windowId"], "main");
+        args["actions"].as_array().unwrap().clone()
+    }
+
+    fn pointer(actions: &Value) -> Vec<Value> {
+        sequence(&json!({ "context": "main", "actions": [{ "type": "pointer", "id": "mouse", "actions": actions }] }))
+    }
+
+    #[test]
+    fn evaluate_runs_in_the_target_window() {
+        let args = evaluate_args(&json!({
+            "expression": "document.title",
+            "awaitPromise": true,
+            "target": { "context": "main" },
+            "serializationOptions": { "maxObjectDepth": 2 },
+        }))
+        .unwrap();
+        assert_eq!(args["windowId"], "main");
+        assert!(args["script"]
+            .as_str()
+            .unwrap()
+            .ends_with(r#"return window.__tauriMcpBidiEvaluate("document.title", true, 2, "main")"#));
+        let missing = evaluate_args(&json!({ "expression": "1", "awaitPromise": true })).unwrap_err();
+        assert_eq!(missing.error, "invalid argument");
+
+        let params = json!({ "target": { "realm": "main" } });
+        let result = answer(
+            "script.evaluate",
+            &params,
+            Ok(json!({ "type": "success", "result": { "type": "number", "value": 2 } })),
+        );
+        assert_eq!(result.unwrap()["realm"], "main");
+        let gone = answer(
+            "script.evaluate",
+            &params,
+            Err("Window 'nope' not found. Available: main".to_string()),
+        );
+        assert_eq!(gone.unwrap_err().error, "no such frame");
+    }
+
+    #[test]
+    fn screenshots_take_bidi_formats_and_box_clips() {
+        let args = screenshot_args(&json!({
+            "context": "main",
+            "format": { "type": "image/jpeg", "quality": 0.7 },
+            "clip": { "type": "box", "x": 0, "y": 0, "width": 10, "height": 20 },
+        }))
+        .unwrap();
+        assert_eq!(
+            args,
+            json!({
+                "windowId": "main",
+                "format": "jpeg",
+                "quality": 70,
+                "clip": { "x": 0, "y": 0, "width": 10, "height": 20 },
+            })
+        );
+        let element = json!({ "context": "main", "clip": { "type": "element", "element": { "sharedId": "1" } } });
+        assert_eq!(screenshot_args(&element).unwrap_err().error, "unsupported operation");
+        let document = json!({ "context": "main", "origin": "document" });
+        assert_eq!(screenshot_args(&document).unwrap_err().error, "unsupported operation");
+        assert_eq!(
+            answer(
+                "browsingContext.captureScreenshot",
+                &json!({}),
+                Ok(json!("data:image/png;base64,iVBOR"))
+            ),
+            Ok(json!({ "data": "iVBOR" }))
+        );
+    }
+
+    #[test]
+    fn windows_are_top_level_contexts() {
+        let windows = json!([
+            { "label": "about", "url": "tauri://localhost/about" },
+            { "label": "main", "url": "tauri://localhost/" },
+        ]);
+        let all = answer("browsingContext.getTree", &json!({}), Ok(windows.clone())).unwrap();
+        assert_eq!(all["contexts"].as_array().unwrap().len(), 2);
+        assert_eq!(all["contexts"][0]["context"], "about");
+        assert_eq!(all["contexts"][0]["parent"], Value::Null);
+        let root = answer("browsingContext.getTree", &json!({ "root": "main" }), Ok(windows)).unwrap();
+        assert_eq!(
+            root["contexts"],
+            json!([contexts(&json!([{ "label": "main", "url": "tauri://localhost/" }]), None)[0]])
+        );
+    }
+
+    #[test]
+    fn pointer_actions_become_clicks_drags_and_hovers() {
+        let down_up = |button: u64| json!([{ "type": "pointerDown", "button": button }, { "type": "pointerUp", "button": button }]);
+        let mut click = vec![json!({ "type": "pointerMove", "x": 5, "y": 6 })];
+        click.extend(down_up(0).as_array().unwrap().clone());
+        assert_eq!(
+            pointer(&json!(click.clone())),
+            vec![json!({ "action": "click", "x": 5.0, "y": 6.0 })]
+        );
+
+        let mut double = click.clone();
+        double.extend(down_up(0).as_array().unwrap().clone());
+        assert_eq!(
+            pointer(&json!(double)),
+            vec![json!({ "action": "double_click", "x": 5.0, "y": 6.0 })]
+        );
+
+        let mut right = vec![json!({ "type": "pointerMove", "x": 5, "y": 6 })];
+        right.extend(down_up(2).as_array().unwrap().clone());
+        assert_eq!(pointer(&json!(right))[0]["action"], "rightclick");
+
+        let drag = pointer(&json!([
+            { "type": "pointerMove", "x": 5, "y": 6 },
+            { "type": "pointerDown", "button": 0 },
+            { "type": "pointerMove", "x": 10, "y": 0, "origin": "pointer" },
+            { "type": "pointerUp", "button": 0 },
+        ]));
+        assert_eq!(
+            drag,
+            vec![json!({ "action": "drag", "from": { "x": 5.0, "y": 6.0 }, "to": { "x": 15.0, "y": 6.0 } })]
+        );
+
+        let hover = pointer(&json!([
+            { "type": "pointerMove", "x": 1, "y": 2 },
+            { "type": "pause", "duration": 300 },
+            { "type": "pointerMove", "x": 3, "y": 4 },
+        ]));
+        assert_eq!(
+            hover,
+            vec![
+                json!({ "action": "hover", "x": 1.0, "y": 2.0 }),
+                json!({ "action": "hover", "x": 3.0, "y": 4.0, "delayMs": 300 }),
+            ]
+        );
+
+        let held = json!({ "context": "main", "actions": [{ "type": "pointer", "actions": [{ "type": "pointerDown", "button": 0 }] }] });
+        assert_eq!(actions_args(&held).unwrap_err().error, "unsupported operation");
+        let element = json!({
+            "context": "main",
+            "actions": [{ "type": "pointer", "actions": [{ "type": "pointerMove", "x": 0, "y": 0, "origin": { "type": "element" } }] }],
+        });
+        assert_eq!(actions_args(&element).unwrap_err().error, "unsupported operation");
+    }
+
+    #[test]
+    fn key_actions_become_a_keyboard_sequence() {
+        let keys = sequence(&json!({
+            "context": "main",
+            "actions": [{
+                "type": "key",
+                "id": "keyboard",
+                "actions": [
+                    { "type": "keyDown", "value": "\u{E009}" },
+                    { "type": "keyDown", "value": "a" },
+                    { "type": "keyUp", "value": "a" },
+                    { "type": "keyUp", "value": "\u{E009}" },
+                    { "type": "keyDown", "value": "b" },
+                    { "type": "keyUp", "value": "b" },
+                    { "type": "keyDown", "value": "\u{E007}" },
+                    { "type": "keyUp", "value": "\u{E007}" },
+                ],
+            }],
+        }));
+        assert_eq!(
+            keys,
+            vec![json!({ "action": "keyboard_sequence", "keys": ["control", "a", "b", "enter"] })]
+        );
+        assert_eq!(key_name("\u{E012}"), Ok("left"));
+        assert_eq!(key_name("\u{E040}").unwrap_err().error, "unsupported operation");
+        assert_eq!(key_name("ab").unwrap_err().error, "invalid argument");
+    }
+
+    #[test]
+    fn wheels_scroll_and_pauses_alone_do_nothing() {
+        let scroll = sequence(&json!({
+            "context": "main",
+            "actions": [{ "type": "wheel", "actions": [{ "type": "scroll", "x": 0, "y": 0, "deltaX": 0, "deltaY": 120 }] }],
+        }));
+        assert_eq!(scroll, vec![json!({ "action": "scroll", "deltaX": 0, "deltaY": 120 })]);
+        let pauses = json!({ "context": "main", "actions": [{ "type": "none", "actions": [{ "type": "pause", "duration": 10 }] }] });
+        assert_eq!(actions_args(&pauses).unwrap(), Translation::Answer(json!({})));
+    }
+
+    #[test]
+    fn unknown_commands_list_the_translated_ones() {
+        let error = translate("browsingContext.navigate", &json!({})).unwrap_err();
+        assert_eq!(error.error, "unknown command");
+        assert!(error.message.contains("script.evaluate"));
+        let Translation::Answer(session) = translate("session.new", &json!({ "capabilities": {} })).unwrap() else {
+            panic!("session.new should be answered right away");
+        };
+        assert_eq!(session["capabilities"]["browserName"], "tauri-mcp");
+    }
+}
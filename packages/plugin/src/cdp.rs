@@ -0,0 +1,469 @@
+//! Compatibility with CDP, Chrome's devtools protocol, for tools that speak it, with
+//! `Builder::cdp_compat(true)`.
+//!
+//! An endpoint on the port after the WebSocket server's (see [`crate::compat`]) takes CDP
+//! messages like `{"id": 1, "method": "Runtime.evaluate", "params": {"expression": "document.title"}}`.
+//! Each runs as the tauri-mcp command it translates to, in the focused window, and gets a CDP
+//! answer:
+//!
+//! - `Runtime.evaluate` runs as `execute_js`, with promises awaited and values returned by value
+//! - `Page.captureScreenshot` runs as `screenshot`
+//! - `DOM.getDocument` runs as a JSON structure `dom_snapshot` of the whole document
+//! - `Input.dispatchMouseEvent` runs as `interact`: moves hover, releases click, and wheels scroll
+//! - `Browser.getVersion` is answered right away
+//!
+//! Other methods get a "method not found" error. It's a translation layer rather than a browser,
+//! so there are no targets, events, or object handles, and node IDs only hold within one answer.
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Runtime};
+use tracing::warn;
+
+use crate::compat;
+
+/// Methods the endpoint translates, listed in "method not found" errors
+pub const CDP_METHODS: &[&str] = &[
+    "Runtime.evaluate",
+    "Page.captureScreenshot",
+    "DOM.getDocument",
+    "Input.dispatchMouseEvent",
+    "Browser.getVersion",
+];
+
+/// CDP's JSON-RPC error code for a method it doesn't have
+const METHOD_NOT_FOUND: i64 = -32601;
+
+/// CDP's JSON-RPC error code for params it can't use
+const INVALID_PARAMS: i64 = -32602;
+
+/// CDP's error code for a method that failed
+const SERVER_ERROR: i64 = -32000;
+
+/// Prefix of `execute_js` errors the script itself threw, as opposed to failures to run it
+const SCRIPT_ERROR: &str = "Script error: ";
+
+/// A CDP command message
+#[derive(Debug, Deserialize)]
+struct CdpRequest {
+    id: u64,
+    method: String,
+    #[serde(default)]
+    params: Value,
+    /// Echoed back, for clients that send flattened session IDs
+    #[serde(rename = "sessionId")]
+    session_id: Option<String>,
+}
+
+/// An error answer, with a JSON-RPC error code
+#[derive(Debug, PartialEq, Eq)]
+struct CdpError {
+    code: i64,
+    message: String,
+}
+
+impl CdpError {
+    fn invalid_params(message: impl Into<String>) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: message.into(),
+        }
+    }
+}
+
+/// What a CDP method does: run a tauri-mcp command, or answer without one
+#[derive(Debug, PartialEq)]
+enum Translation {
+    Command(&'static str, Value),
+    Answer(Value),
+}
+
+/// Run a CDP message and build its answer
+pub async fn answer_message<R: Runtime>(app: &AppHandle<R>, text: &str) -> Value {
+    let request: CdpRequest = match serde_json::from_str(text) {
+        Ok(request) => request,
+        Err(e) => {
+            warn!("Invalid CDP message: {e}");
+            return json!({ "error": { "code": INVALID_PARAMS, "message": format!("Invalid CDP message: {e}") } });
+        }
+    };
+
+    let result = match translate(&request.method, &request.params) {
+        Ok(Translation::Answer(result)) => Ok(result),
+        Ok(Translation::Command(command, args)) => {
+            let result = compat::run(app, format!("cdp-{}", request.id), command, args).await;
+            answer(&request.method, result)
+        }
+        Err(e) => Err(e),
+    };
+
+    let mut answer = match result {
+        Ok(result) => json!({ "id": request.id, "result": result }),
+        Err(e) => json!({ "id": request.id, "error": { "code": e.code, "message": e.message } }),
+    };
+    if let Some(session_id) = request.session_id {
+        answer["sessionId"] = session_id.into();
+    }
+    answer
+}
+
+/// Translate a CDP method and its params to a tauri-mcp command and its args
+fn translate(method: &str, params: &Value) -> Result<Translation, CdpError> {
+    match method {
+        "Runtime.evaluate" => {
+            let expression = params
+                .get("expression")
+                .and_then(Value::as_str)
+                .ok_or_else(|| CdpError::invalid_params("Runtime.evaluate needs an 'expression' string"))?;
+            let script = include_str!("scripts/cdp-evaluate.js");
+            let expression = serde_json::to_string(expression).map_err(|e| CdpError::invalid_params(e.to_string()))?;
+            Ok(Translation::Command(
+                "execute_js",
+                json!({ "script": format!("{script}\nreturn window.__tauriMcpCdpEvaluate({expression})") }),
+            ))
+        }
+        "Page.captureScreenshot" => screenshot_args(params).map(|args| Translation::Command("screenshot", args)),
+        "DOM.getDocument" => document_args(params).map(|args| Translation::Command("dom_snapshot", args)),
+        "Input.dispatchMouseEvent" => mouse_args(params),
+        "Browser.getVersion" => Ok(Translation::Answer(json!({
+            "protocolVersion": "1.3",
+            "product": format!("tauri-mcp/{}", env!("CARGO_PKG_VERSION")),
+            "revision": "",
+            "userAgent": "",
+            "jsVersion": "",
+        }))),
+        _ => Err(CdpError {
+            code: METHOD_NOT_FOUND,
+            message: format!(
+                "'{method}' wasn't found. The tauri-mcp CDP endpoint translates {}.",
+                CDP_METHODS.join(", ")
+            ),
+        }),
+    }
+}
+
+/// `screenshot` args for `Page.captureScreenshot`'s `format`, `quality`, and `clip`
+fn screenshot_args(params: &Value) -> Result<Value, CdpError> {
+    let mut args = Map::new();
+    match params.get("format").and_then(Value::as_str) {
+        None | Some("png") => {}
+        Some("jpeg") => {
+            args.insert("format".to_string(), "jpeg".into());
+        }
+        Some(format) => {
+            return Err(CdpError::invalid_params(format!(
+                "Screenshots can be png or jpeg, not {format}"
+            )))
+        }
+    }
+    if let Some(quality) = params.get("quality") {
+        args.insert("quality".to_string(), quality.clone());
+    }
+    if let Some(clip) = params.get("clip") {
+        let field = |name: &str| clip.get(name).cloned().unwrap_or(Value::Null);
+        args.insert(
+            "clip".to_string(),
+            json!({ "x": field("x"), "y": field("y"), "width": field("width"), "height": field("height") }),
+        );
+    }
+    Ok(Value::Object(args))
+}
+
+/// `dom_snapshot` args for `DOM.getDocument`, whose `depth` counts the `html` element as 1 and
+/// is -1 for the whole tree
+fn document_args(params: &Value) -> Result<Value, CdpError> {
+    let max_depth = match params.get("depth") {
+        None | Some(Value::Null) => Some(0),
+        Some(depth) => match depth.as_i64() {
+            Some(-1) => None,
+            Some(depth) if depth >= 1 => Some(depth - 1),
+            _ => {
+                return Err(CdpError::invalid_params(format!(
+                    "'depth' must be -1 or at least 1, got: {depth}"
+                )))
+            }
+        },
+    };
+    Ok(json!({
+        "type": "structure",
+        "format": "json",
+        "selector": "html",
+        "maxDepth": max_depth,
+    }))
+}
+
+/// `interact` args for `Input.dispatchMouseEvent`. A press does nothing on its own, and its
+/// release clicks where it happened.
+fn mouse_args(params: &Value) -> Result<Translation, CdpError> {
+    let coordinate = |name: &str| {
+        params
+            .get(name)
+            .filter(|value| value.is_number())
+            .cloned()
+            .ok_or_else(|| CdpError::invalid_params(format!("Input.dispatchMouseEvent needs a numeric '{name}'")))
+    };
+    let (x, y) = (coordinate("x")?, coordinate("y")?);
+    let args = match params.get("type").and_then(Value::as_str) {
+        Some("mousePressed") => return Ok(Translation::Answer(json!({}))),
+        Some("mouseMoved") => json!({ "action": "hover", "x": x, "y": y }),
+        Some("mouseReleased") => {
+            let action = match (
+                params.get("button").and_then(Value::as_str),
+                params.get("clickCount").and_then(Value::as_u64),
+            ) {
+                (Some("right"), _) => "rightclick",
+                (_, Some(count)) if count >= 2 => "double_click",
+                _ => "click",
+            };
+            json!({ "action": action, "x": x, "y": y })
+        }
+        Some("mouseWheel") => json!({
+            "action": "scroll",
+            "deltaX": params.get("deltaX").cloned().unwrap_or_else(|| json!(0)),
+            "deltaY": params.get("deltaY").cloned().unwrap_or_else(|| json!(0)),
+        }),
+        _ => {
+            return Err(CdpError::invalid_params(format!(
+                "'type' must be mousePressed, mouseReleased, mouseMoved, or mouseWheel, got: {}",
+                params.get("type").unwrap_or(&Value::Null)
+            )))
+        }
+    };
+    Ok(Translation::Command("interact", args))
+}
+
+/// Turn a command's result into the CDP answer for the method
+fn answer(method: &str, result: Result<Value, String>) -> Result<Value, CdpError> {
+    let server_error = |message: String| CdpError {
+        code: SERVER_ERROR,
+        message,
+    };
+    match (method, result) {
+        // A script that threw is a successful evaluation with exception details
+        ("Runtime.evaluate", Err(e)) if e.starts_with(SCRIPT_ERROR) => {
+            let exception = json!({
+                "type": "object",
+                "subtype": "error",
+                "description": e.trim_start_matches(SCRIPT_ERROR),
+            });
+            Ok(json!({
+                "result": exception,
+                "exceptionDetails": {
+                    "exceptionId": 1,
+                    "text": "Uncaught",
+                    "lineNumber": 0,
+                    "columnNumber": 0,
+                    "exception": exception,
+                },
+            }))
+        }
+        (_, Err(e)) => Err(server_error(e)),
+        ("Runtime.evaluate", Ok(remote)) => Ok(json!({ "result": remote })),
+        ("Page.captureScreenshot", Ok(result)) => {
+            let image = result
+                .get("image")
+                .unwrap_or(&result)
+                .as_str()
+                .ok_or_else(|| server_error("The screenshot has no image".to_string()))?;
+            // CDP gives bare base64, without the data URL's prefix
+            let data = image.split_once(',').map_or(image, |(_, data)| data);
+            Ok(json!({ "data": data }))
+        }
+        ("DOM.getDocument", Ok(tree)) => Ok(json!({ "root": document(tree) })),
+        (_, Ok(_)) => Ok(json!({})),
+    }
+}
+
+/// A CDP document node with a JSON structure snapshot of its `html` element as its child
+fn document(tree: Value) -> Value {
+    // A cut snapshot comes as { snapshot, truncated, ... }
+    let html = match tree {
+        Value::Object(mut result) if result.contains_key("snapshot") => result.remove("snapshot").unwrap_or_default(),
+        tree => tree,
+    };
+    let mut next_id = 1;
+    let mut document = json!({
+        "nodeId": 1,
+        "backendNodeId": 1,
+        "nodeType": 9,
+        "nodeName": "#document",
+        "localName": "",
+        "nodeValue": "",
+        "childNodeCount": 1,
+    });
+    document["children"] = json!([node(&html, &mut next_id)]);
+    document
+}
+
+/// A CDP element node, with the snapshot's attributes and children, and its shadow roots apart
+fn node(snapshot: &Value, next_id: &mut u64) -> Value {
+    *next_id += 1;
+    let id = *next_id;
+    let tag = snapshot["tag"].as_str().unwrap_or_default();
+    if tag == "#shadow-root" {
+        let children = element_children(snapshot, next_id);
+        return json!({
+            "nodeId": id,
+            "backendNodeId": id,
+            "nodeType": 11,
+            "nodeName": "#document-fragment",
+            "localName": "",
+            "nodeValue": "",
+            "shadowRootType": "open",
+            "childNodeCount": children.len(),
+            "children": children,
+        });
+    }
+
+    let attributes: Vec<&str> = snapshot["attrs"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(name, _)| matches!(name.as_str(), "id" | "class" | "data-testid"))
+        .filter_map(|(name, value)| Some([name.as_str(), value.as_str()?]))
+        .flatten()
+        .collect();
+    let (shadow_roots, children): (Vec<Value>, Vec<Value>) = element_children(snapshot, next_id)
+        .into_iter()
+        .partition(|child| child["nodeType"] == 11);
+    let mut node = json!({
+        "nodeId": id,
+        "backendNodeId": id,
+        "nodeType": 1,
+        "nodeName": tag.to_uppercase(),
+        "localName": tag,
+        "nodeValue": "",
+        "attributes": attributes,
+    });
+    // Cut children are left out, as CDP does beyond `depth`, with their count unknown
+    if snapshot.get("truncated").is_none() {
+        node["childNodeCount"] = children.len().into();
+    }
+    node["children"] = children.into();
+    if !shadow_roots.is_empty() {
+        node["shadowRoots"] = shadow_roots.into();
+    }
+    node
+}
+
+fn element_children(snapshot: &Value, next_id: &mut u64) -> Vec<Value> {
+    snapshot["children"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|child| node(child, next_id))
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluate_runs_the_expression_as_execute_js() {
+        let Translation::Command(command, args) =
+            translate("Runtime.evaluate", &json!({ "expression": "document.title" })).unwrap()
+        else {
+            panic!("Runtime.evaluate should run a command");
+        };
+        assert_eq!(command, "execute_js");
+        assert!(args["script"]
+            .as_str()
+            .unwrap()
+            .ends_with(r#"return window.__tauriMcpCdpEvaluate("document.title")"#));
+        assert_eq!(
+            translate("Runtime.evaluate", &json!({})).unwrap_err().code,
+            INVALID_PARAMS
+        );
+    }
+
+    #[test]
+    fn evaluate_answers_values_and_exceptions() {
+        let value = json!({ "type": "string", "value": "Test App" });
+        assert_eq!(
+            answer("Runtime.evaluate", Ok(value.clone())),
+            Ok(json!({ "result": value }))
+        );
+        let thrown = answer("Runtime.evaluate", Err("Script error: boom".to_string())).unwrap();
+        assert_eq!(thrown["exceptionDetails"]["exception"]["description"], "boom");
+        let failed = answer("Runtime.evaluate", Err("No window found".to_string())).unwrap_err();
+        assert_eq!(failed.code, SERVER_ERROR);
+    }
+
+    #[test]
+    fn mouse_events_become_interact_actions() {
+        let event = |params: Value| mouse_args(&params).unwrap();
+        assert_eq!(
+            event(json!({ "type": "mousePressed", "x": 5, "y": 6 })),
+            Translation::Answer(json!({}))
+        );
+        assert_eq!(
+            event(json!({ "type": "mouseReleased", "x": 5, "y": 6, "button": "left", "clickCount": 1 })),
+            Translation::Command("interact", json!({ "action": "click", "x": 5, "y": 6 }))
+        );
+        assert_eq!(
+            event(json!({ "type": "mouseReleased", "x": 5, "y": 6, "clickCount": 2 })),
+            Translation::Command("interact", json!({ "action": "double_click", "x": 5, "y": 6 }))
+        );
+        assert_eq!(
+            event(json!({ "type": "mouseReleased", "x": 5, "y": 6, "button": "right" })),
+            Translation::Command("interact", json!({ "action": "rightclick", "x": 5, "y": 6 }))
+        );
+        assert_eq!(
+            event(json!({ "type": "mouseWheel", "x": 5, "y": 6, "deltaY": 120 })),
+            Translation::Command("interact", json!({ "action": "scroll", "deltaX": 0, "deltaY": 120 }))
+        );
+        assert!(mouse_args(&json!({ "type": "mouseMoved", "x": 5 })).is_err());
+    }
+
+    #[test]
+    fn screenshots_come_back_as_bare_base64() {
+        assert_eq!(
+            screenshot_args(&json!({ "format": "jpeg", "quality": 70 })).unwrap(),
+            json!({ "format": "jpeg", "quality": 70 })
+        );
+        assert!(screenshot_args(&json!({ "format": "webp" })).is_err());
+        assert_eq!(
+            answer("Page.captureScreenshot", Ok(json!("data:image/png;base64,iVBOR"))),
+            Ok(json!({ "data": "iVBOR" }))
+        );
+    }
+
+    #[test]
+    fn documents_are_cdp_nodes() {
+        assert_eq!(document_args(&json!({})).unwrap()["maxDepth"], 0);
+        assert_eq!(document_args(&json!({ "depth": -1 })).unwrap()["maxDepth"], Value::Null);
+        assert_eq!(document_args(&json!({ "depth": 3 })).unwrap()["maxDepth"], 2);
+        assert!(document_args(&json!({ "depth": 0 })).is_err());
+
+        let tree = json!({
+            "tag": "html", "role": null, "name": null, "attrs": {},
+            "children": [{
+                "tag": "my-widget", "role": null, "name": null,
+                "attrs": { "id": "w", "shadowRoot": true },
+                "children": [{ "tag": "#shadow-root", "role": null, "name": null, "attrs": {}, "children": [] }],
+                "truncated": 2,
+            }],
+        });
+        let root = document(tree);
+        assert_eq!(root["nodeName"], "#document");
+        let html = &root["children"][0];
+        assert_eq!(
+            (html["nodeId"].clone(), html["childNodeCount"].clone()),
+            (json!(2), json!(1))
+        );
+        let widget = &html["children"][0];
+        assert_eq!(widget["nodeName"], "MY-WIDGET");
+        assert_eq!(widget["attributes"], json!(["id", "w"]));
+        assert_eq!(widget["shadowRoots"][0]["nodeType"], 11);
+        assert!(widget.get("childNodeCount").is_none());
+    }
+
+    #[test]
+    fn unknown_methods_list_the_translated_ones() {
+        let error = translate("Target.getTargets", &json!({})).unwrap_err();
+        assert_eq!(error.code, METHOD_NOT_FOUND);
+        assert!(error.message.contains("Runtime.evaluate"));
+    }
+}
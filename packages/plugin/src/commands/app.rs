@@ -0,0 +1,39 @@
+//! App-level commands that go through other Tauri plugins
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_notification::{Notification, NotificationExt};
+
+/// Send a system notification through `tauri-plugin-notification`.
+///
+/// Returns `{ sent, error }` rather than failing, so flows can assert on a notification that
+/// the OS refused to show. The app must register the notification plugin: it has no public
+/// API to show notifications without it.
+pub fn notify<R: Runtime>(app: &AppHandle<R>, args: &Value) -> Result<Value, String> {
+    let title = args
+        .get("title")
+        .and_then(Value::as_str)
+        .ok_or("Missing required 'title' argument")?;
+    let body = args.get("body").and_then(Value::as_str);
+    let icon = args.get("icon").and_then(Value::as_str);
+
+    if app.try_state::<Notification<R>>().is_none() {
+        return Ok(json!({
+            "sent": false,
+            "error": "Notification plugin not registered. Add .plugin(tauri_plugin_notification::init()) to your app.",
+        }));
+    }
+
+    let mut builder = app.notification().builder().title(title);
+    if let Some(body) = body {
+        builder = builder.body(body);
+    }
+    if let Some(icon) = icon {
+        builder = builder.icon(icon);
+    }
+
+    Ok(match builder.show() {
+        Ok(()) => json!({ "sent": true, "error": null }),
+        Err(e) => json!({ "sent": false, "error": e.to_string() }),
+    })
+}
@@ -0,0 +1,308 @@
+//! Console log commands
+
+use std::path::PathBuf;
+
+use regex::Regex;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::broadcast::error::RecvError;
+
+use super::wait::Progress;
+use crate::console::{parse_levels, ConsoleEntry, ConsolePage, ConsoleQuery, ConsoleStore, ExportFormat};
+
+/// Largest export returned inline when no `savePath` is given, in bytes
+pub const INLINE_EXPORT_MAX_BYTES: usize = 1024 * 1024;
+
+/// Value of `windowId` that selects all windows for console commands
+pub const ALL_WINDOWS: &str = "*";
+
+/// Console buffers a command reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target<'a> {
+    /// All windows' buffers merged by timestamp, including recently destroyed windows
+    All,
+    /// One window's buffer
+    Window(&'a str),
+}
+
+/// Get the target of a console command that doesn't need a live window: all windows for
+/// `windowId: "*"` or `allWindows: true`, or a destroyed window whose buffer is still kept.
+///
+/// Returns `None` if the command targets a live window as usual.
+pub fn detached_target<'a, R: Runtime>(app: &AppHandle<R>, args: &'a Value) -> Option<Target<'a>> {
+    let window_id = args.get("windowId").and_then(Value::as_str);
+    if window_id == Some(ALL_WINDOWS) || args.get("allWindows").and_then(Value::as_bool).unwrap_or(false) {
+        return Some(Target::All);
+    }
+
+    let label = window_id?;
+    let store = app.try_state::<ConsoleStore>()?;
+    (app.get_webview_window(label).is_none() && store.has_buffer(label)).then_some(Target::Window(label))
+}
+
+/// Get captured console logs from the Rust-side buffers.
+///
+/// Returns a plain array of entries, or `{ entries, total, dropped, oldestTimestamp }` when
+/// `offset`, `limit`, or `paged: true` is given.
+pub fn logs<R: Runtime>(app: &AppHandle<R>, target: Target<'_>, args: &Value) -> Result<Value, String> {
+    let query = parse_query(args)?;
+    let paged =
+        args.get("paged").and_then(Value::as_bool).unwrap_or(false) || query.offset > 0 || query.limit.is_some();
+
+    let page = query_store(app, target, &query)?;
+
+    if paged {
+        serde_json::to_value(page).map_err(|e| e.to_string())
+    } else {
+        serde_json::to_value(page.entries).map_err(|e| e.to_string())
+    }
+}
+
+/// Export captured console logs, filtered like `console_logs`.
+///
+/// Writes to `savePath` (which must be absolute) and returns `{ path, count, format }`, or
+/// returns `{ content, count, format }` inline if no path is given and the content is small enough.
+pub async fn export<R: Runtime>(app: &AppHandle<R>, target: Target<'_>, args: &Value) -> Result<Value, String> {
+    let query = parse_query(args)?;
+    let format = ExportFormat::parse(args.get("format").and_then(Value::as_str))?;
+    let save_path = args.get("savePath").and_then(Value::as_str).map(PathBuf::from);
+    if let Some(path) = &save_path {
+        if !path.is_absolute() {
+            return Err(format!("'savePath' must be an absolute path, got: {}", path.display()));
+        }
+    }
+
+    let entries = query_store(app, target, &query)?.entries;
+    let content = format.render(&entries)?;
+
+    let Some(path) = save_path else {
+        if content.len() > INLINE_EXPORT_MAX_BYTES {
+            return Err(format!(
+                "Export is {} bytes, over the {INLINE_EXPORT_MAX_BYTES} byte inline limit. Pass 'savePath' to write it to a file.",
+                content.len()
+            ));
+        }
+        return Ok(json!({ "content": content, "count": entries.len(), "format": format.as_str() }));
+    };
+
+    // Large buffers can take a while to write, so keep it off the async workers
+    let write_path = path.clone();
+    tokio::task::spawn_blocking(move || std::fs::write(&write_path, content))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to write '{}': {e}", path.display()))?;
+
+    Ok(json!({ "path": path, "count": entries.len(), "format": format.as_str() }))
+}
+
+/// Wait for the window to log an entry matching `textContains` or `regex` and `level`.
+///
+/// Watches the Rust-side buffers rather than the page, so it works while the page's JS thread
+/// is busy and across reloads. With `includeHistory: true`, the latest matching entry already
+/// captured counts too. Returns `{ success, message, elapsedMs, polls, condition, detail, entry }`,
+/// where each entry the window logs is a poll.
+pub async fn wait_for<R: Runtime>(
+    app: &AppHandle<R>,
+    window_label: &str,
+    args: &Value,
+    mut progress: Progress,
+) -> Result<Value, String> {
+    let (mut query, description) = parse_wait_query(args)?;
+    let condition = format!("a console entry {description}");
+    let include_history = args.get("includeHistory").and_then(Value::as_bool).unwrap_or(false);
+    let store = app
+        .try_state::<ConsoleStore>()
+        .ok_or("Console capture not initialized")?;
+
+    // Subscribe before reading the history, so nothing logged in between is missed
+    let mut rx = store.subscribe();
+    let mut last_seen = store.last_seq();
+    if include_history {
+        progress.poll();
+        if let Some(entry) = store.query(window_label, &query).entries.pop() {
+            return Ok(console_matched(&progress, &condition, &entry));
+        }
+    }
+
+    let mut logged = 0;
+    loop {
+        let Some(received) = progress.within(rx.recv()).await else {
+            let state = format!("{logged} entries logged by '{window_label}' since the wait started, none matching");
+            return Err(progress.timed_out(&condition, &state));
+        };
+        match received {
+            Ok(entry) => {
+                last_seen = entry.seq;
+                if entry.window_label == window_label {
+                    logged += 1;
+                    progress.poll();
+                    if query.matches(&entry) {
+                        return Ok(console_matched(&progress, &condition, &entry));
+                    }
+                }
+            }
+            // Entries the stream dropped are still in the buffer
+            Err(RecvError::Lagged(_)) => {
+                query.since_seq = Some(last_seen);
+                progress.poll();
+                if let Some(missed) = store.query(window_label, &query).entries.into_iter().next() {
+                    return Ok(console_matched(&progress, &condition, &missed));
+                }
+            }
+            Err(RecvError::Closed) => return Err("Console capture stopped".to_string()),
+        }
+    }
+}
+
+/// Result of a `console` wait that found its entry
+fn console_matched(progress: &Progress, condition: &str, entry: &ConsoleEntry) -> Value {
+    let mut result = progress.met(
+        condition,
+        &format!("Console entry logged: {}", entry.message),
+        &format!("entry {} logged at level {}", entry.seq, entry.level),
+    );
+    result["entry"] = json!(entry);
+    result
+}
+
+/// Parse the `textContains`, `regex`, and `level` arguments of a `console` wait, returning the
+/// query and a description of it for the timeout error
+fn parse_wait_query(args: &Value) -> Result<(ConsoleQuery, String), String> {
+    let text = args.get("textContains").and_then(Value::as_str);
+    let regex = args.get("regex").and_then(Value::as_str);
+    let levels = match args.get("level") {
+        None | Some(Value::Null) => None,
+        Some(Value::String(level)) => Some(vec![level.clone()]),
+        Some(Value::Array(_)) => Some(
+            parse_levels(&args["level"]).map_err(|_| "'level' must be a string or an array of strings".to_string())?,
+        ),
+        Some(other) => return Err(format!("'level' must be a string or an array of strings, got: {other}")),
+    };
+
+    let (filter, mut description) = match (text, regex) {
+        (Some(_), Some(_)) => return Err("Pass either 'textContains' or 'regex', not both".to_string()),
+        (Some(text), None) => (Some(regex::escape(text)), format!("containing '{text}'")),
+        (None, Some(regex)) => (Some(regex.to_string()), format!("matching /{regex}/")),
+        (None, None) if levels.is_some() => (None, String::new()),
+        (None, None) => {
+            return Err(
+                "'console' needs 'textContains', 'regex', or 'level' to say which entry to wait for".to_string(),
+            )
+        }
+    };
+    if let Some(levels) = &levels {
+        let at = format!("at level {}", levels.join(" or "));
+        description = if description.is_empty() {
+            at
+        } else {
+            format!("{description} {at}")
+        };
+    }
+
+    let filter = filter
+        .map(|filter| Regex::new(&filter))
+        .transpose()
+        .map_err(|e| format!("Invalid regex: {e}"))?;
+    let query = ConsoleQuery {
+        filter,
+        levels,
+        ..ConsoleQuery::default()
+    };
+    Ok((query, description))
+}
+
+/// Query the target's buffers
+fn query_store<R: Runtime>(
+    app: &AppHandle<R>,
+    target: Target<'_>,
+    query: &ConsoleQuery,
+) -> Result<ConsolePage, String> {
+    let store = app
+        .try_state::<ConsoleStore>()
+        .ok_or("Console capture not initialized")?;
+    Ok(match target {
+        Target::All => store.query_all(query),
+        Target::Window(label) => store.query(label, query),
+    })
+}
+
+/// Parse the filter, navigation, and paging arguments shared by `console_logs` and `console_export`
+fn parse_query(args: &Value) -> Result<ConsoleQuery, String> {
+    let filter = args.get("filter").and_then(|v| v.as_str());
+    // A string is a timestamp, a number is the sequence number of the last entry seen
+    let (since, since_seq) = match args.get("since") {
+        None | Some(Value::Null) => (None, None),
+        Some(Value::String(timestamp)) => (Some(timestamp.as_str()), None),
+        Some(value) => {
+            let seq = value
+                .as_u64()
+                .ok_or_else(|| format!("'since' must be an ISO timestamp or a sequence number, got: {value}"))?;
+            (None, Some(seq))
+        }
+    };
+    let clear = args.get("clear").and_then(Value::as_bool).unwrap_or(false);
+    let levels = args.get("levels").map(parse_levels).transpose()?;
+
+    let mut query = ConsoleQuery::parse(filter, since, levels, clear)?;
+    query.since_seq = since_seq;
+    query.current_navigation = match args.get("navigation").and_then(Value::as_str) {
+        None | Some("all") => false,
+        Some("current") => true,
+        Some(other) => return Err(format!("Invalid navigation: '{other}'. Use 'current' or 'all'.")),
+    };
+    query.offset = count_arg(args, "offset")?.unwrap_or(0);
+    query.limit = count_arg(args, "limit")?;
+    Ok(query)
+}
+
+/// Parse an optional non-negative integer argument like `offset` or `limit`
+fn count_arg(args: &Value, name: &str) -> Result<Option<usize>, String> {
+    args.get(name)
+        .map(|value| {
+            value
+                .as_u64()
+                .map(|n| usize::try_from(n).unwrap_or(usize::MAX))
+                .ok_or_else(|| format!("'{name}' must be a non-negative integer, got: {value}"))
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wait_query_matches_text_literally() {
+        let (query, description) = parse_wait_query(&json!({"textContains": "[app] ready (1/2)"})).unwrap();
+        assert_eq!(query.filter.unwrap().as_str(), r"\[app\] ready \(1/2\)");
+        assert_eq!(description, "containing '[app] ready (1/2)'");
+    }
+
+    #[test]
+    fn wait_query_takes_one_level_or_several() {
+        let (query, description) = parse_wait_query(&json!({"regex": "^done", "level": "info"})).unwrap();
+        assert_eq!(query.levels, Some(vec!["info".to_string()]));
+        assert_eq!(description, "matching /^done/ at level info");
+
+        let (query, description) = parse_wait_query(&json!({"level": ["error", "uncaught"]})).unwrap();
+        assert!(query.filter.is_none());
+        assert_eq!(description, "at level error or uncaught");
+    }
+
+    #[test]
+    fn wait_query_rejects_unclear_conditions() {
+        assert!(parse_wait_query(&json!({}))
+            .unwrap_err()
+            .contains("needs 'textContains'"));
+        assert!(parse_wait_query(&json!({"textContains": "a", "regex": "b"}))
+            .unwrap_err()
+            .contains("not both"));
+        assert!(parse_wait_query(&json!({"regex": "("}))
+            .unwrap_err()
+            .starts_with("Invalid regex"));
+        assert!(parse_wait_query(&json!({"textContains": "a", "level": 3}))
+            .unwrap_err()
+            .contains("'level' must be"));
+    }
+}
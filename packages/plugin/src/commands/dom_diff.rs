@@ -0,0 +1,589 @@
+//! `dom_diff`: what changed in the DOM since an earlier `dom_snapshot`.
+//!
+//! Both snapshots are compared in their JSON form. Children are paired up by `id` or
+//! `data-testid` where they have one, and otherwise in document order by tag and role, preferring
+//! ones whose name didn't change, so an item inserted into a list shows as one added node rather
+//! than every later item changing.
+//!
+//! The baseline comes back over the wire as `baseline`, or stays in the plugin: `dom_snapshot`
+//! with `store: true` keeps it and returns a `snapshotId` to pass as `baselineId`. Only the last
+//! few stored snapshots are kept.
+
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use serde_json::{json, Map, Value};
+use tauri::{Manager, Runtime, WebviewWindow};
+
+use super::execute_js;
+
+/// How many snapshots `dom_snapshot { store: true }` keeps, dropping the oldest first
+pub const MAX_STORED_SNAPSHOTS: usize = 10;
+
+/// Changes listed in a diff when no `maxChanges` is given
+const DEFAULT_MAX_CHANGES: u64 = 100;
+
+/// Largest number of child pairs compared to align two lists of children. Longer lists are only
+/// aligned where they start and end the same, and paired in order in between.
+const MAX_ALIGN_CELLS: usize = 1_000_000;
+
+/// `dom_snapshot` arguments a stored snapshot remembers, so diffing against it takes the current
+/// snapshot the same way
+const SNAPSHOT_OPTIONS: [&str; 10] = [
+    "type",
+    "selector",
+    "pierceShadow",
+    "maxDepth",
+    "maxNodes",
+    "maxFrameDepth",
+    "includeRects",
+    "computedStyles",
+    "interactiveOnly",
+    "roles",
+];
+
+/// Snapshots kept for `dom_diff`
+#[derive(Debug, Default)]
+pub struct SnapshotStore(Mutex<Stored>);
+
+#[derive(Debug, Default)]
+struct Stored {
+    next_id: u64,
+    snapshots: VecDeque<(String, StoredSnapshot)>,
+}
+
+#[derive(Debug, Clone)]
+struct StoredSnapshot {
+    tree: Value,
+    options: Map<String, Value>,
+}
+
+impl SnapshotStore {
+    /// Keep a snapshot's tree and the options it was taken with, returning its ID
+    fn insert(&self, tree: Value, options: Map<String, Value>) -> String {
+        let mut stored = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        stored.next_id += 1;
+        let id = format!("snapshot-{}", stored.next_id);
+        if stored.snapshots.len() == MAX_STORED_SNAPSHOTS {
+            stored.snapshots.pop_front();
+        }
+        stored
+            .snapshots
+            .push_back((id.clone(), StoredSnapshot { tree, options }));
+        id
+    }
+
+    /// Keep the tree of a snapshot script's result, returning the result with its `snapshotId`
+    fn keep(&self, args: &Value, result: Value) -> Result<Value, String> {
+        let Value::Object(mut result) = result else {
+            return Err("The snapshot script didn't return its tree".to_string());
+        };
+        // JSON snapshots are their own tree
+        let tree = result
+            .remove("tree")
+            .unwrap_or_else(|| result.get("snapshot").cloned().unwrap_or_default());
+        let id = self.insert(tree, snapshot_options(args));
+        result.insert("snapshotId".to_string(), id.into());
+        Ok(Value::Object(result))
+    }
+
+    fn get(&self, id: &str) -> Option<StoredSnapshot> {
+        let stored = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        stored
+            .snapshots
+            .iter()
+            .find(|(stored_id, _)| stored_id == id)
+            .map(|(_, snapshot)| snapshot.clone())
+    }
+}
+
+/// Check the optional `store` flag of `dom_snapshot` and `dom_diff`
+pub(super) fn store_arg(args: &Value) -> Result<bool, String> {
+    match args.get("store") {
+        None | Some(Value::Null) => Ok(false),
+        Some(Value::Bool(store)) => Ok(*store),
+        Some(value) => Err(format!("'store' must be true or false, got: {value}")),
+    }
+}
+
+/// Keep the tree of a snapshot taken with its tree, returning the snapshot with its `snapshotId`
+pub(super) fn store<R: Runtime>(window: &WebviewWindow<R>, args: &Value, result: Value) -> Result<Value, String> {
+    let store = window
+        .app_handle()
+        .try_state::<SnapshotStore>()
+        .ok_or("Snapshot store not initialized")?;
+    store.keep(args, result)
+}
+
+/// Compare the DOM now with a `baseline` snapshot or the stored one with `baselineId`.
+///
+/// The current snapshot is taken with the `dom_snapshot` options given, which default to the
+/// stored snapshot's. Returns `{ changed, summary, added, removed, modified }`, listing at most
+/// `maxChanges` changes, with `truncated: true` when some were left out. `store: true` keeps the
+/// current snapshot too and adds its `snapshotId`, ready for the next diff.
+pub async fn dom_diff<R: Runtime>(window: &WebviewWindow<R>, args: &Value, timeout: Duration) -> Result<Value, String> {
+    let max_changes = execute_js::limit_arg(args, "maxChanges", 1)?.unwrap_or(DEFAULT_MAX_CHANGES);
+    let max_changes = usize::try_from(max_changes).unwrap_or(usize::MAX);
+    let store = window
+        .app_handle()
+        .try_state::<SnapshotStore>()
+        .ok_or("Snapshot store not initialized")?;
+
+    let (baseline, mut options) = match (args.get("baseline"), args.get("baselineId")) {
+        (Some(_), Some(_)) => return Err("Pass either 'baseline' or 'baselineId', not both".to_string()),
+        (Some(baseline), None) => (baseline_arg(baseline)?, Map::new()),
+        (None, Some(Value::String(id))) => {
+            let stored = store.get(id).ok_or_else(|| {
+                format!(
+                    "Unknown baselineId '{id}'. Only the last {MAX_STORED_SNAPSHOTS} snapshots taken with store: true are kept."
+                )
+            })?;
+            (stored.tree, stored.options)
+        }
+        (None, Some(value)) => return Err(format!("'baselineId' must be a string, got: {value}")),
+        (None, None) => return Err("Missing 'baseline' or 'baselineId' argument".to_string()),
+    };
+    options.extend(snapshot_options(args));
+
+    let current = execute_js::snapshot_tree(window, &Value::Object(options.clone()), timeout).await?;
+    let mut result = diff(&baseline, &current, max_changes);
+    if store_arg(args)? {
+        result["snapshotId"] = store.insert(current, options).into();
+    }
+    Ok(result)
+}
+
+/// The `dom_snapshot` options among `args`
+fn snapshot_options(args: &Value) -> Map<String, Value> {
+    SNAPSHOT_OPTIONS
+        .iter()
+        .filter_map(|&name| {
+            args.get(name)
+                .filter(|value| !value.is_null())
+                .map(|value| (name.to_string(), value.clone()))
+        })
+        .collect()
+}
+
+/// Get the baseline tree from a JSON snapshot as `dom_snapshot` returned it: the tree itself,
+/// the result with a `snapshot` field, or either as a string
+fn baseline_arg(baseline: &Value) -> Result<Value, String> {
+    let parsed;
+    let mut baseline = baseline;
+    if let Value::String(text) = baseline {
+        parsed = serde_json::from_str::<Value>(text).ok();
+        baseline = parsed.as_ref().unwrap_or(&Value::Null);
+    }
+    if let Some(snapshot) = baseline.get("snapshot") {
+        baseline = snapshot;
+    }
+    if baseline.get("tag").is_some_and(Value::is_string) {
+        Ok(baseline.clone())
+    } else {
+        Err("'baseline' must be a snapshot taken with format: \"json\", or use 'baselineId'".to_string())
+    }
+}
+
+/// Changes found so far, of which the first `max` are listed
+struct Diff {
+    max: usize,
+    listed: usize,
+    added: Vec<Value>,
+    removed: Vec<Value>,
+    modified: Vec<Value>,
+    counts: [usize; 3],
+}
+
+impl Diff {
+    fn push(&mut self, kind: usize, entry: impl FnOnce() -> Value) {
+        self.counts[kind] += 1;
+        if self.listed < self.max {
+            self.listed += 1;
+            let list = match kind {
+                ADDED => &mut self.added,
+                REMOVED => &mut self.removed,
+                _ => &mut self.modified,
+            };
+            list.push(entry());
+        }
+    }
+}
+
+const ADDED: usize = 0;
+const REMOVED: usize = 1;
+const MODIFIED: usize = 2;
+
+/// Diff two snapshot trees, listing at most `max_changes` changes
+fn diff(baseline: &Value, current: &Value, max_changes: usize) -> Value {
+    let mut diff = Diff {
+        max: max_changes,
+        listed: 0,
+        added: Vec::new(),
+        removed: Vec::new(),
+        modified: Vec::new(),
+        counts: [0; 3],
+    };
+    if identity(baseline) == identity(current) {
+        compare(baseline, current, &label(current), &mut diff);
+    } else {
+        diff.push(REMOVED, || subtree_entry(&label(baseline), baseline));
+        diff.push(ADDED, || subtree_entry(&label(current), current));
+    }
+
+    let total: usize = diff.counts.iter().sum();
+    let mut result = json!({
+        "changed": total > 0,
+        "summary": {
+            "added": diff.counts[ADDED],
+            "removed": diff.counts[REMOVED],
+            "modified": diff.counts[MODIFIED],
+        },
+        "added": diff.added,
+        "removed": diff.removed,
+        "modified": diff.modified,
+    });
+    if diff.listed < total {
+        result["truncated"] = true.into();
+        result["shownChanges"] = diff.listed.into();
+    }
+    result
+}
+
+/// Compare two nodes that were paired up, and their children
+fn compare(old: &Value, new: &Value, path: &str, diff: &mut Diff) {
+    let changes = node_changes(old, new);
+    if !changes.is_empty() {
+        diff.push(MODIFIED, || {
+            let mut entry = json!({ "path": path, "changes": changes });
+            if let Some(selector) = new["attrs"].get("selector") {
+                entry["selector"] = selector.clone();
+            }
+            entry
+        });
+    }
+
+    let old_children = children(old);
+    let new_children = children(new);
+    let old_labels = sibling_labels(&old_children);
+    let new_labels = sibling_labels(&new_children);
+    let child_path = |label: &str| format!("{path} > {label}");
+
+    let mut old_next = 0;
+    let mut new_next = 0;
+    for (i, j) in pair_children(&old_children, &new_children) {
+        for k in old_next..i {
+            diff.push(REMOVED, || subtree_entry(&child_path(&old_labels[k]), old_children[k]));
+        }
+        for k in new_next..j {
+            diff.push(ADDED, || subtree_entry(&child_path(&new_labels[k]), new_children[k]));
+        }
+        compare(old_children[i], new_children[j], &child_path(&new_labels[j]), diff);
+        (old_next, new_next) = (i + 1, j + 1);
+    }
+    for k in old_next..old_children.len() {
+        diff.push(REMOVED, || subtree_entry(&child_path(&old_labels[k]), old_children[k]));
+    }
+    for k in new_next..new_children.len() {
+        diff.push(ADDED, || subtree_entry(&child_path(&new_labels[k]), new_children[k]));
+    }
+}
+
+fn children(node: &Value) -> Vec<&Value> {
+    node["children"]
+        .as_array()
+        .map(|children| children.iter().collect())
+        .unwrap_or_default()
+}
+
+/// What a node changed, as `{ field: { before, after } }`, where attributes and styles are
+/// `attrs.<name>` and `styles.<name>`
+fn node_changes(old: &Value, new: &Value) -> Map<String, Value> {
+    let mut changes = Map::new();
+    let mut compare = |field: String, before: &Value, after: &Value| {
+        if before != after {
+            changes.insert(field, json!({ "before": before, "after": after }));
+        }
+    };
+    for field in ["role", "name", "rect", "visible"] {
+        compare(field.to_string(), &old[field], &new[field]);
+    }
+    for group in ["attrs", "styles"] {
+        let names: BTreeSet<&String> = [&old[group], &new[group]]
+            .into_iter()
+            .filter_map(Value::as_object)
+            .flat_map(Map::keys)
+            .collect();
+        // Selectors with positions change whenever a sibling comes or goes
+        for name in names.into_iter().filter(|&name| name != "selector") {
+            compare(format!("{group}.{name}"), &old[group][name], &new[group][name]);
+        }
+    }
+    changes
+}
+
+/// A node that was added or removed, without its children, which are only counted
+fn subtree_entry(path: &str, node: &Value) -> Value {
+    json!({
+        "path": path,
+        "node": {
+            "tag": node["tag"],
+            "role": node["role"],
+            "name": node["name"],
+            "attrs": node["attrs"],
+        },
+        "descendants": descendants(node),
+    })
+}
+
+fn descendants(node: &Value) -> u64 {
+    let truncated = node["truncated"].as_u64().unwrap_or(0);
+    children(node)
+        .into_iter()
+        .fold(truncated, |sum, child| sum + 1 + descendants(child))
+}
+
+/// What a node must keep to be paired with its earlier self: its tag and `id` or `data-testid`
+/// if it has one, otherwise its tag and role
+fn identity(node: &Value) -> (&str, Option<&str>, Option<&str>) {
+    let tag = node["tag"].as_str().unwrap_or_default();
+    let key = ["id", "data-testid"]
+        .into_iter()
+        .find_map(|name| node["attrs"][name].as_str());
+    key.map_or_else(|| (tag, None, node["role"].as_str()), |key| (tag, Some(key), None))
+}
+
+/// How a node shows in paths: its tag with its `id` or `data-testid`
+#[allow(clippy::option_if_let_else)]
+fn label(node: &Value) -> String {
+    let tag = node["tag"].as_str().unwrap_or_default();
+    if let Some(id) = node["attrs"]["id"].as_str() {
+        format!("{tag}#{id}")
+    } else if let Some(test_id) = node["attrs"]["data-testid"].as_str() {
+        format!("{tag}[data-testid=\"{test_id}\"]")
+    } else {
+        tag.to_string()
+    }
+}
+
+/// Labels of siblings, numbered from 1 like `li[2]` where several have the same one
+fn sibling_labels(nodes: &[&Value]) -> Vec<String> {
+    let labels: Vec<String> = nodes.iter().map(|node| label(node)).collect();
+    let mut totals: HashMap<&str, usize> = HashMap::new();
+    for label in &labels {
+        *totals.entry(label).or_default() += 1;
+    }
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    labels
+        .iter()
+        .map(|label| {
+            if totals[label.as_str()] == 1 {
+                return label.clone();
+            }
+            let index = seen.entry(label).or_default();
+            *index += 1;
+            format!("{label}[{index}]")
+        })
+        .collect()
+}
+
+/// Pair up old and new children in order: first the ones that kept their name too, then, between
+/// those, the ones that only kept their identity
+fn pair_children(old: &[&Value], new: &[&Value]) -> Vec<(usize, usize)> {
+    let unchanged = align(old, new, |a, b| identity(a) == identity(b) && a["name"] == b["name"]);
+    let mut pairs = Vec::with_capacity(unchanged.len());
+    let (mut old_start, mut new_start) = (0, 0);
+    for (i, j) in unchanged.into_iter().chain([(old.len(), new.len())]) {
+        let gap = align(&old[old_start..i], &new[new_start..j], |a, b| {
+            identity(a) == identity(b)
+        });
+        pairs.extend(gap.into_iter().map(|(a, b)| (a + old_start, b + new_start)));
+        if i < old.len() {
+            pairs.push((i, j));
+        }
+        (old_start, new_start) = (i + 1, j + 1);
+    }
+    pairs
+}
+
+/// Pair up as many items of `old` and `new` as possible in order, where `same` says which can pair
+fn align(old: &[&Value], new: &[&Value], same: impl Fn(&Value, &Value) -> bool) -> Vec<(usize, usize)> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| same(a, b)).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| same(a, b))
+        .count();
+    let old_middle = &old[prefix..old.len() - suffix];
+    let new_middle = &new[prefix..new.len() - suffix];
+
+    let mut pairs: Vec<(usize, usize)> = (0..prefix).map(|i| (i, i)).collect();
+    pairs.extend(
+        longest_common(old_middle, new_middle, &same)
+            .into_iter()
+            .map(|(i, j)| (i + prefix, j + prefix)),
+    );
+    pairs.extend((0..suffix).rev().map(|k| (old.len() - 1 - k, new.len() - 1 - k)));
+    pairs
+}
+
+/// Pairs of the longest common subsequence of `old` and `new`, or of the items that line up by
+/// position when the lists are too long to compare every pair
+fn longest_common(old: &[&Value], new: &[&Value], same: &impl Fn(&Value, &Value) -> bool) -> Vec<(usize, usize)> {
+    if old.len().saturating_mul(new.len()) > MAX_ALIGN_CELLS {
+        return old
+            .iter()
+            .zip(new)
+            .enumerate()
+            .filter(|(_, (a, b))| same(a, b))
+            .map(|(i, _)| (i, i))
+            .collect();
+    }
+
+    // lengths[i][j] is the length of the longest common subsequence of old[i..] and new[j..]
+    let width = new.len() + 1;
+    let mut lengths = vec![0_u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i * width + j] = if same(old[i], new[j]) {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut pairs = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if same(old[i], new[j]) {
+            pairs.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * width + j] >= lengths[i * width + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str) -> Value {
+        json!({ "tag": "li", "role": "listitem", "name": name, "attrs": {}, "children": [] })
+    }
+
+    fn list(items: &[&str]) -> Value {
+        json!({
+            "tag": "ul",
+            "role": "list",
+            "name": null,
+            "attrs": { "id": "todos" },
+            "children": items.iter().map(|name| item(name)).collect::<Vec<_>>(),
+        })
+    }
+
+    #[test]
+    fn inserted_items_are_added_without_shifting_the_rest() {
+        let result = diff(&list(&["a", "b", "c"]), &list(&["new", "a", "b", "c"]), 100);
+        assert_eq!(result["summary"], json!({ "added": 1, "removed": 0, "modified": 0 }));
+        assert_eq!(result["added"][0]["path"], "ul#todos > li[1]");
+        assert_eq!(result["added"][0]["node"]["name"], "new");
+    }
+
+    #[test]
+    fn renamed_items_are_modified_and_dropped_ones_removed() {
+        let result = diff(&list(&["a", "b", "c"]), &list(&["a", "B"]), 100);
+        assert_eq!(result["summary"], json!({ "added": 0, "removed": 1, "modified": 1 }));
+        assert_eq!(
+            result["modified"][0],
+            json!({ "path": "ul#todos > li[2]", "changes": { "name": { "before": "b", "after": "B" } } })
+        );
+        assert_eq!(result["removed"][0]["path"], "ul#todos > li[3]");
+    }
+
+    #[test]
+    fn attribute_and_style_changes_are_listed_by_field() {
+        let old = json!({ "tag": "div", "role": null, "name": null,
+            "attrs": { "class": "a", "selector": "div:nth-of-type(2)" }, "styles": { "display": "block" }, "children": [] });
+        let new = json!({ "tag": "div", "role": null, "name": null,
+            "attrs": { "class": "b", "selector": "div:nth-of-type(3)" }, "styles": { "display": "none" }, "children": [] });
+        let changes = &diff(&old, &new, 100)["modified"][0]["changes"];
+        assert_eq!(changes["attrs.class"], json!({ "before": "a", "after": "b" }));
+        assert_eq!(changes["styles.display"], json!({ "before": "block", "after": "none" }));
+        assert!(changes.get("attrs.selector").is_none());
+        assert_eq!(diff(&old, &old, 100)["changed"], false);
+    }
+
+    #[test]
+    fn diffs_list_at_most_max_changes() {
+        let result = diff(&list(&[]), &list(&["a", "b", "c"]), 2);
+        assert_eq!(result["summary"]["added"], 3);
+        assert_eq!(result["added"].as_array().unwrap().len(), 2);
+        assert_eq!(result["truncated"], true);
+        assert_eq!(result["shownChanges"], 2);
+    }
+
+    #[test]
+    fn baselines_are_json_snapshots() {
+        let tree = list(&["a"]);
+        assert_eq!(baseline_arg(&tree).unwrap(), tree);
+        assert_eq!(
+            baseline_arg(&json!({ "snapshot": tree, "truncated": true })).unwrap(),
+            tree
+        );
+        assert_eq!(baseline_arg(&tree.to_string().into()).unwrap(), tree);
+        assert!(baseline_arg(&"- tag: ul".into())
+            .unwrap_err()
+            .contains("format: \"json\""));
+    }
+
+    #[test]
+    fn stored_snapshots_are_diffed_against_the_kept_tree() {
+        let store = SnapshotStore::default();
+        let args = json!({ "type": "structure", "selector": "#todos", "store": true });
+        // What the snapshot script returns for a YAML snapshot with keepTree
+        let result = json!({ "snapshot": "ul#todos\n  li\n  li", "tree": list(&["a", "b"]) });
+        let stored = store.keep(&args, result).unwrap();
+        assert_eq!(stored["snapshot"], "ul#todos\n  li\n  li");
+        assert!(stored.get("tree").is_none());
+
+        let id = stored["snapshotId"].as_str().unwrap();
+        let baseline = store.get(id).unwrap();
+        assert_eq!(
+            Value::Object(baseline.options),
+            json!({ "type": "structure", "selector": "#todos" })
+        );
+        let result = diff(&baseline.tree, &list(&["a", "b", "c"]), 100);
+        assert_eq!(result["summary"], json!({ "added": 1, "removed": 0, "modified": 0 }));
+
+        // JSON snapshots are kept as they are
+        let json_args = json!({ "format": "json", "store": true });
+        let stored = store.keep(&json_args, json!({ "snapshot": list(&["a"]) })).unwrap();
+        let baseline = store.get(stored["snapshotId"].as_str().unwrap()).unwrap();
+        assert_eq!(baseline.tree, list(&["a"]));
+
+        assert!(store
+            .keep(&args, Value::Null)
+            .unwrap_err()
+            .contains("didn't return its tree"));
+    }
+
+    #[test]
+    fn the_store_keeps_the_latest_snapshots() {
+        let store = SnapshotStore::default();
+        let first = store.insert(list(&["a"]), Map::new());
+        let ids: Vec<String> = (0..MAX_STORED_SNAPSHOTS)
+            .map(|_| store.insert(list(&[]), Map::new()))
+            .collect();
+        assert!(store.get(&first).is_none());
+        assert!(store.get(&ids[0]).is_some());
+        assert_ne!(first, ids[0]);
+    }
+}
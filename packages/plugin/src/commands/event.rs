@@ -0,0 +1,30 @@
+//! Event injection command
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Emitter, EventTarget, Manager, Runtime};
+
+/// Emit a Tauri event into the app, as if the backend sent it.
+///
+/// With a `window` label, only listeners of that window (and global listeners) get it;
+/// otherwise it goes to every listener.
+pub fn emit<R: Runtime>(app: &AppHandle<R>, args: &Value) -> Result<Value, String> {
+    let event = args
+        .get("event")
+        .and_then(Value::as_str)
+        .ok_or("Missing required 'event' argument")?;
+    let payload = args.get("payload").cloned().unwrap_or(Value::Null);
+    let window = args.get("window").and_then(Value::as_str);
+
+    if let Some(label) = window {
+        if app.get_webview_window(label).is_none() {
+            return Err(format!("Window '{label}' not found"));
+        }
+        // `WebviewWindow::emit` would reach all windows, so target the window explicitly
+        app.emit_to(EventTarget::webview_window(label), event, payload)
+    } else {
+        app.emit(event, payload)
+    }
+    .map_err(|e| format!("Failed to emit '{event}': {e}"))?;
+
+    Ok(json!({ "event": event, "window": window }))
+}
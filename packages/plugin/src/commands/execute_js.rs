@@ -3,12 +3,18 @@
 use serde::Deserialize;
 use serde_json::Value;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tauri::{Listener, Runtime, WebviewWindow};
 use tokio::sync::{oneshot, Mutex};
 use uuid::Uuid;
 
-/// Default timeout for script execution in seconds
-const DEFAULT_TIMEOUT_SECS: u64 = 5;
+/// Extra time given to `wait_for` on top of its JS-level timeout, so the condition's own timeout
+/// error always comes first
+const WAIT_FOR_EXTRA_TIME: Duration = Duration::from_secs(2);
+
+/// `wait_for` timeout in milliseconds when none is given, the same for conditions checked in the
+/// page and in Rust
+const DEFAULT_WAIT_TIMEOUT_MS: u64 = 5000;
 
 /// Payload for script result events from JavaScript
 #[derive(Debug, Clone, Deserialize)]
@@ -19,56 +25,144 @@ struct ScriptResultPayload {
     error: Option<String>,
 }
 
+/// Get the script timeout a command will wait for, given the configured default.
+///
+/// Used by the transport to make sure its own timeout never fires first.
+pub fn script_timeout_for(command: &str, args: &Value, default: Duration) -> Duration {
+    match command {
+        #[cfg(feature = "js-execution")]
+        "execute_js" => execute_timeout(args, default),
+        "wait_for" => wait_for_timeout(args),
+        #[cfg(feature = "interact")]
+        "interact" => super::interact::interact_timeout(args, default),
+        _ => default,
+    }
+}
+
+/// Get a command's optional `timeoutMs`.
+///
+/// Falls back to the deprecated `timeout`, which is in seconds for `execute_js` and milliseconds
+/// for `wait_for`, so `legacy_unit_ms` says which.
+fn timeout_ms_arg(args: &Value, legacy_unit_ms: u64) -> Option<u64> {
+    args.get("timeoutMs").and_then(Value::as_u64).or_else(|| {
+        args.get("timeout")
+            .and_then(Value::as_u64)
+            .map(|timeout| timeout.saturating_mul(legacy_unit_ms))
+    })
+}
+
+/// `execute_js` takes an optional `timeoutMs`, or the deprecated `timeout` in seconds
+#[cfg(feature = "js-execution")]
+fn execute_timeout(args: &Value, default: Duration) -> Duration {
+    timeout_ms_arg(args, 1000).map_or(default, Duration::from_millis)
+}
+
+/// Get how long a `wait_for` condition is waited for in milliseconds, from `timeoutMs` or the
+/// deprecated `timeout`
+pub fn wait_timeout_ms(args: &Value) -> u64 {
+    timeout_ms_arg(args, 1).unwrap_or(DEFAULT_WAIT_TIMEOUT_MS)
+}
+
+/// `wait_for` gets its condition's timeout plus extra time, since the JS-level timeout decides
+/// when the wait fails
+fn wait_for_timeout(args: &Value) -> Duration {
+    Duration::from_millis(wait_timeout_ms(args)) + WAIT_FOR_EXTRA_TIME
+}
+
 /// Execute arbitrary JavaScript in the webview
-pub async fn execute<R: Runtime>(window: &WebviewWindow<R>, args: &Value) -> Result<Value, String> {
+#[cfg(feature = "js-execution")]
+pub async fn execute<R: Runtime>(window: &WebviewWindow<R>, args: &Value, timeout: Duration) -> Result<Value, String> {
     let script = args
         .get("script")
         .and_then(|v| v.as_str())
         .ok_or("Missing required 'script' argument")?;
 
-    let timeout_secs = args
-        .get("timeout")
-        .and_then(Value::as_u64)
-        .unwrap_or(DEFAULT_TIMEOUT_SECS);
+    eval_with_result(window, script, execute_timeout(args, timeout)).await
+}
 
-    eval_with_result(window, script, timeout_secs).await
+/// Get DOM snapshot.
+///
+/// `maxDepth` and `maxNodes` cut the tree breadth-first, so the top of it is always complete.
+/// A cut snapshot has markers where nodes were left out and comes back as
+/// `{ snapshot, truncated: true, totalNodes, shownNodes }` instead of a plain string.
+///
+/// `format: "json"` gives a tree of `{ tag, role, name, attrs, children }` nodes instead of the
+/// YAML or text tree. `format: "html"` gives the scoped element's HTML without scripts or event
+/// handlers, and `format: "markdown"` its content as Markdown, both cut at `maxLength` characters.
+/// `includeRects: true` adds each node's bounding rect and whether it's visible, and
+/// `computedStyles` adds the computed values of up to 20 CSS properties. Same-origin iframes are
+/// descended into `maxFrameDepth` frames deep (1 by default).
+///
+/// `store: true` keeps the snapshot for `dom_diff` and adds its `snapshotId`, making the result
+/// `{ snapshot, snapshotId }`.
+///
+/// `pageSize` splits the snapshot into pages of that many nodes, returning the first as
+/// `{ snapshot, page, pages, nextPage }`. Passing `nextPage` as `page` returns the next one of the
+/// same snapshot, without taking it again.
+#[cfg(feature = "dom-snapshot")]
+pub async fn dom_snapshot<R: Runtime>(
+    window: &WebviewWindow<R>,
+    args: &Value,
+    timeout: Duration,
+) -> Result<Value, String> {
+    if let Some(page) = super::snapshot_pages::page_arg(args)? {
+        return super::snapshot_pages::page(window, page);
+    }
+    let page_size = super::snapshot_pages::page_size_arg(args)?;
+    let result = if super::dom_diff::store_arg(args)? {
+        let result = snapshot(window, args, timeout, true).await?;
+        super::dom_diff::store(window, args, result)?
+    } else {
+        snapshot(window, args, timeout, false).await?
+    };
+    match page_size {
+        Some(page_size) => super::snapshot_pages::first_page(window, args, result, page_size),
+        None => Ok(result),
+    }
 }
 
-/// Get console logs from the webview
-pub async fn console_logs<R: Runtime>(window: &WebviewWindow<R>, args: &Value) -> Result<Value, String> {
-    let filter = args.get("filter").and_then(|v| v.as_str());
-    let since = args.get("since").and_then(|v| v.as_str());
-    let clear = args.get("clear").and_then(Value::as_bool).unwrap_or(false);
+/// Take a DOM snapshot in the JSON format, as a tree without the truncation details
+#[cfg(feature = "dom-snapshot")]
+pub(super) async fn snapshot_tree<R: Runtime>(
+    window: &WebviewWindow<R>,
+    args: &Value,
+    timeout: Duration,
+) -> Result<Value, String> {
+    let mut args = args.clone();
+    args["format"] = "json".into();
+    match snapshot(window, &args, timeout, false).await? {
+        Value::Object(mut result) if result.get("truncated") == Some(&Value::Bool(true)) => {
+            Ok(result.remove("snapshot").unwrap_or_default())
+        }
+        tree => Ok(tree),
+    }
+}
 
-    // Use JSON serialization for proper escaping of special characters
-    let filter_arg = filter.map_or_else(
-        || "null".to_string(),
-        |f| serde_json::to_string(f).unwrap_or_else(|_| "null".to_string()),
-    );
-    let since_arg = since.map_or_else(
-        || "null".to_string(),
-        |s| serde_json::to_string(s).unwrap_or_else(|_| "null".to_string()),
-    );
+/// Forget the refs `dom_snapshot` handed out, so selectors with them fail as stale
+#[cfg(feature = "dom-snapshot")]
+pub async fn refs_clear<R: Runtime>(window: &WebviewWindow<R>, timeout: Duration) -> Result<Value, String> {
+    let a11y = include_str!("../scripts/a11y.js");
+    let selectors = include_str!("../scripts/selector.js");
 
-    let script = format!(
+    let full_script = format!(
         r"
-        (function() {{
-            if (!window.__tauriMcpConsole) {{
-                return {{ error: 'Console capture not initialized' }};
-            }}
-            const logs = window.__tauriMcpConsole.getLogs({filter_arg}, {since_arg});
-            {clear_code}
-            return logs;
-        }})()
-        ",
-        clear_code = if clear { "window.__tauriMcpConsole.clear();" } else { "" }
+        {a11y}
+        {selectors}
+        return window.__tauriMcpSelector.clearRefs()
+        "
     );
 
-    eval_with_result(window, &script, DEFAULT_TIMEOUT_SECS).await
+    eval_with_result(window, &full_script, timeout).await
 }
 
-/// Get DOM snapshot
-pub async fn dom_snapshot<R: Runtime>(window: &WebviewWindow<R>, args: &Value) -> Result<Value, String> {
+/// Take a DOM snapshot, with `keep_tree` making the result `{ snapshot, tree }` in any format
+#[cfg(feature = "dom-snapshot")]
+async fn snapshot<R: Runtime>(
+    window: &WebviewWindow<R>,
+    args: &Value,
+    timeout: Duration,
+    keep_tree: bool,
+) -> Result<Value, String> {
     let snapshot_type = args.get("type").and_then(|v| v.as_str()).unwrap_or("accessibility");
 
     // Validate snapshot type
@@ -78,104 +172,789 @@ pub async fn dom_snapshot<R: Runtime>(window: &WebviewWindow<R>, args: &Value) -
         ));
     }
 
-    let selector = args.get("selector").and_then(|v| v.as_str());
+    let selector = if selector_arg(args, "selector")? {
+        &args["selector"]
+    } else {
+        &Value::Null
+    };
+    let format = snapshot_format_arg(args)?;
+    let include_rects = include_rects_arg(args)?;
+    let computed_styles = computed_styles_arg(args)?;
+    if matches!(format, "html" | "markdown") && (include_rects || !computed_styles.is_empty() || keep_tree) {
+        return Err(format!(
+            "'includeRects', 'computedStyles', and 'store' need the yaml or json format, not {format}"
+        ));
+    }
+    let interactive_only = interactive_only_arg(args)?;
+    let roles = roles_arg(args)?;
+    if interactive_only && !roles.is_empty() {
+        return Err("Use either 'interactiveOnly' or 'roles', not both".to_string());
+    }
+    if (interactive_only || !roles.is_empty())
+        && (snapshot_type != "accessibility" || format == "html" || format == "markdown")
+    {
+        return Err(format!(
+            "'interactiveOnly' and 'roles' need an accessibility snapshot in the yaml or json format, not {snapshot_type} as {format}"
+        ));
+    }
+    let options = serde_json::json!({
+        "pierceShadow": pierce_shadow_arg(args)?,
+        "maxDepth": limit_arg(args, "maxDepth", 0)?,
+        "maxNodes": limit_arg(args, "maxNodes", 1)?,
+        "maxFrameDepth": limit_arg(args, "maxFrameDepth", 0)?,
+        "maxLength": limit_arg(args, "maxLength", 1)?,
+        "format": format,
+        "includeRects": include_rects,
+        "computedStyles": computed_styles,
+        "interactiveOnly": interactive_only,
+        "roles": roles,
+        "keepTree": keep_tree,
+    });
+
+    eval_with_result(window, &snapshot_script(snapshot_type, selector, &options), timeout).await
+}
 
+/// Build the `dom_snapshot` script, with the type, selector, and options as JSON literals
+#[cfg(feature = "dom-snapshot")]
+fn snapshot_script(snapshot_type: &str, selector: &Value, options: &Value) -> String {
+    let a11y = include_str!("../scripts/a11y.js");
+    let selectors = include_str!("../scripts/selector.js");
+    let serialize = include_str!("../scripts/serialize.js");
     let script = include_str!("../scripts/dom-snapshot.js");
-    // Use JSON serialization for proper escaping of special characters in selector
-    let selector_arg = selector.map_or_else(
-        || "null".to_string(),
-        |s| serde_json::to_string(s).unwrap_or_else(|_| "null".to_string()),
+    let type_json = js_string(snapshot_type);
+    let selector_json = js_value(selector);
+    let options_json = js_value(options);
+
+    format!(
+        r"
+        {a11y}
+        {selectors}
+        {serialize}
+        {script}
+        return window.__tauriMcpDomSnapshot({type_json}, {selector_json}, {options_json})
+        "
+    )
+}
+
+/// Find elements by ARIA role and accessible name
+pub async fn accessibility_find<R: Runtime>(
+    window: &WebviewWindow<R>,
+    args: &Value,
+    timeout: Duration,
+) -> Result<Value, String> {
+    let has_role = args.get("role").and_then(Value::as_str).is_some();
+    let has_name = args.get("name").and_then(Value::as_str).is_some();
+    if !has_role && !has_name {
+        return Err("Missing 'role' or 'name' argument. Provide at least one.".to_string());
+    }
+
+    eval_with_result(window, &accessibility_find_script(args), timeout).await
+}
+
+/// Build the `accessibility_find` script, with the args as a JSON literal
+fn accessibility_find_script(args: &Value) -> String {
+    let a11y = include_str!("../scripts/a11y.js");
+    let script = include_str!("../scripts/accessibility-find.js");
+    let args_json = js_value(args);
+
+    format!(
+        r"
+        {a11y}
+        {script}
+        return window.__tauriMcpAccessibilityFind({args_json})
+        "
+    )
+}
+
+/// List form controls and their state
+pub async fn form_fields<R: Runtime>(
+    window: &WebviewWindow<R>,
+    args: &Value,
+    timeout: Duration,
+) -> Result<Value, String> {
+    let a11y = include_str!("../scripts/a11y.js");
+    let script = include_str!("../scripts/form-fields.js");
+    let args_json = serde_json::to_string(args).map_err(|e| e.to_string())?;
+
+    let full_script = format!(
+        r"
+        {a11y}
+        {script}
+        return window.__tauriMcpFormFields({args_json})
+        "
     );
 
+    eval_with_result(window, &full_script, timeout).await
+}
+
+/// Get the focused element and the keyboard tab order
+pub async fn focus_info<R: Runtime>(window: &WebviewWindow<R>, timeout: Duration) -> Result<Value, String> {
+    let a11y = include_str!("../scripts/a11y.js");
+    let script = include_str!("../scripts/focus-info.js");
+
     let full_script = format!(
         r"
+        {a11y}
         {script}
-        window.__tauriMcpDomSnapshot('{snapshot_type}', {selector_arg})
+        return window.__tauriMcpFocusInfo()
         "
     );
 
-    eval_with_result(window, &full_script, DEFAULT_TIMEOUT_SECS).await
+    eval_with_result_fast(window, &full_script, timeout).await
 }
 
-/// Perform UI interaction
-pub async fn interact<R: Runtime>(window: &WebviewWindow<R>, args: &Value) -> Result<Value, String> {
-    // Validate action is present (used in the JS script)
-    let _action = args
-        .get("action")
-        .and_then(|v| v.as_str())
-        .ok_or("Missing required 'action' argument")?;
+/// Get the focused element with its value length and text selection
+pub async fn active_element<R: Runtime>(window: &WebviewWindow<R>, timeout: Duration) -> Result<Value, String> {
+    let a11y = include_str!("../scripts/a11y.js");
+    let script = include_str!("../scripts/active-element.js");
+
+    let full_script = format!(
+        r"
+        {a11y}
+        {script}
+        return window.__tauriMcpActiveElement()
+        "
+    );
+
+    eval_with_result_fast(window, &full_script, timeout).await
+}
+
+/// Extract headers and rows from a table
+pub async fn table_data<R: Runtime>(
+    window: &WebviewWindow<R>,
+    args: &Value,
+    timeout: Duration,
+) -> Result<Value, String> {
+    // Validate selector is present (used in the JS script)
+    let _selector = args
+        .get("selector")
+        .and_then(Value::as_str)
+        .ok_or("Missing required 'selector' argument")?;
+
+    let script = include_str!("../scripts/table-data.js");
+    let args_json = serde_json::to_string(args).map_err(|e| e.to_string())?;
+
+    let full_script = format!(
+        r"
+        {script}
+        return window.__tauriMcpTableData({args_json})
+        "
+    );
+
+    eval_with_result(window, &full_script, timeout).await
+}
+
+/// Check whether an element is visible and how much of it is in the viewport
+pub async fn is_visible<R: Runtime>(
+    window: &WebviewWindow<R>,
+    args: &Value,
+    timeout: Duration,
+) -> Result<Value, String> {
+    // Validate selector is present (used in the JS script)
+    let _selector = args
+        .get("selector")
+        .and_then(Value::as_str)
+        .ok_or("Missing required 'selector' argument")?;
+
+    let script = include_str!("../scripts/is-visible.js");
+    let args_json = serde_json::to_string(args).map_err(|e| e.to_string())?;
+
+    let full_script = format!(
+        r"
+        {script}
+        return await window.__tauriMcpIsVisible({args_json})
+        "
+    );
+
+    eval_with_result_fast(window, &full_script, timeout).await
+}
+
+/// Get an element's geometry, visibility, disabled state, occlusion, styles, and attributes
+pub async fn element_info<R: Runtime>(
+    window: &WebviewWindow<R>,
+    args: &Value,
+    timeout: Duration,
+) -> Result<Value, String> {
+    validate_element_info_args(args)?;
+
+    let a11y = include_str!("../scripts/a11y.js");
+    let selectors = include_str!("../scripts/selector.js");
+    let script = include_str!("../scripts/element-info.js");
+    let args_json = serde_json::to_string(args).map_err(|e| e.to_string())?;
+
+    let full_script = format!(
+        r"
+        {a11y}
+        {selectors}
+        {script}
+        return window.__tauriMcpElementInfo({args_json})
+        "
+    );
+
+    eval_with_result_fast(window, &full_script, timeout).await
+}
+
+/// Check `element_info` args: a selector, plus optional `styles`, `all`, and `pierceShadow`
+fn validate_element_info_args(args: &Value) -> Result<(), String> {
+    if !selector_arg(args, "selector")? {
+        return Err("Missing required 'selector' argument".to_string());
+    }
+    match args.get("styles") {
+        None | Some(Value::Null) => {}
+        Some(Value::Array(styles)) if styles.iter().all(Value::is_string) => {}
+        Some(value) => {
+            return Err(format!(
+                "'styles' must be a list of CSS property names like [\"display\", \"z-index\"], got: {value}"
+            ))
+        }
+    }
+    if args.get("all").is_some_and(|all| !all.is_null() && !all.is_boolean()) {
+        return Err(format!("'all' must be true or false, got: {}", args["all"]));
+    }
+    pierce_shadow_arg(args)?;
+    Ok(())
+}
+
+/// Find where the page shows some text, with each match's element, excerpt, and rect
+pub async fn find_text<R: Runtime>(
+    window: &WebviewWindow<R>,
+    args: &Value,
+    timeout: Duration,
+) -> Result<Value, String> {
+    validate_find_text_args(args)?;
+
+    let a11y = include_str!("../scripts/a11y.js");
+    let selectors = include_str!("../scripts/selector.js");
+    let script = include_str!("../scripts/find-text.js");
+    let args_json = serde_json::to_string(args).map_err(|e| e.to_string())?;
+
+    let full_script = format!(
+        r"
+        {a11y}
+        {selectors}
+        {script}
+        return window.__tauriMcpFindText({args_json})
+        "
+    );
+
+    eval_with_result(window, &full_script, timeout).await
+}
+
+/// Check `find_text` args: a non-empty `query`, and a `maxResults` of at least 1. A regex `query`
+/// is checked by the page, whose syntax it's in.
+fn validate_find_text_args(args: &Value) -> Result<(), String> {
+    match args.get("query") {
+        Some(Value::String(query)) if !query.is_empty() => {}
+        Some(Value::String(_)) => return Err("'query' can't be empty".to_string()),
+        _ => return Err("Missing required 'query' argument".to_string()),
+    }
+    limit_arg(args, "maxResults", 1)?;
+    Ok(())
+}
+
+/// Longest `highlight` the `durationMs` argument allows
+const MAX_HIGHLIGHT_DURATION_MS: u64 = 60_000;
+
+/// Outline an element with an overlay that removes itself after `durationMs`
+pub async fn highlight<R: Runtime>(
+    window: &WebviewWindow<R>,
+    args: &Value,
+    timeout: Duration,
+) -> Result<Value, String> {
+    validate_highlight_args(args)?;
+
+    let a11y = include_str!("../scripts/a11y.js");
+    let selectors = include_str!("../scripts/selector.js");
+    let script = include_str!("../scripts/highlight.js");
+    let args_json = serde_json::to_string(args).map_err(|e| e.to_string())?;
+
+    let full_script = format!(
+        r"
+        {a11y}
+        {selectors}
+        {script}
+        return window.__tauriMcpHighlight({args_json})
+        "
+    );
+
+    eval_with_result(window, &full_script, timeout).await
+}
+
+/// Remove all `highlight` overlays
+pub async fn highlight_clear<R: Runtime>(window: &WebviewWindow<R>, timeout: Duration) -> Result<Value, String> {
+    let script = include_str!("../scripts/highlight.js");
+
+    let full_script = format!(
+        r"
+        {script}
+        return window.__tauriMcpHighlightClear()
+        "
+    );
+
+    eval_with_result(window, &full_script, timeout).await
+}
+
+/// Check `highlight` args: a selector, plus optional `durationMs`, `color`, `label`, and `pierceShadow`
+fn validate_highlight_args(args: &Value) -> Result<(), String> {
+    if !selector_arg(args, "selector")? {
+        return Err("Missing required 'selector' argument".to_string());
+    }
+    match args.get("durationMs") {
+        None | Some(Value::Null) => {}
+        Some(value)
+            if value
+                .as_u64()
+                .is_some_and(|ms| ms > 0 && ms <= MAX_HIGHLIGHT_DURATION_MS) => {}
+        Some(value) => {
+            return Err(format!(
+                "'durationMs' must be between 1 and {MAX_HIGHLIGHT_DURATION_MS}, got: {value}"
+            ))
+        }
+    }
+    if args
+        .get("color")
+        .is_some_and(|color| !color.is_null() && !color.is_string())
+    {
+        return Err(format!("'color' must be a CSS color string, got: {}", args["color"]));
+    }
+    if args
+        .get("label")
+        .is_some_and(|label| !label.is_null() && !label.is_string() && !label.is_number())
+    {
+        return Err(format!("'label' must be a string or a number, got: {}", args["label"]));
+    }
+    pierce_shadow_arg(args)?;
+    Ok(())
+}
+
+/// Emulate a `prefers-color-scheme` value in the page
+pub async fn color_scheme_set<R: Runtime>(
+    window: &WebviewWindow<R>,
+    args: &Value,
+    timeout: Duration,
+) -> Result<Value, String> {
+    // Validate scheme is present (checked against the allowed values in the JS script)
+    let _scheme = args
+        .get("scheme")
+        .and_then(Value::as_str)
+        .ok_or("Missing required 'scheme' argument. Use 'dark', 'light', or 'no-preference'.")?;
+
+    let script = include_str!("../scripts/color-scheme.js");
+    let args_json = serde_json::to_string(args).map_err(|e| e.to_string())?;
+
+    let full_script = format!(
+        r"
+        {script}
+        return window.__tauriMcpColorSchemeSet({args_json})
+        "
+    );
+
+    eval_with_result(window, &full_script, timeout).await
+}
+
+/// Get a summary of the page's JS heap usage, where the webview exposes it
+pub async fn heap_snapshot<R: Runtime>(window: &WebviewWindow<R>, timeout: Duration) -> Result<Value, String> {
+    let script = include_str!("../scripts/heap-snapshot.js");
+
+    let full_script = format!(
+        r"
+        {script}
+        return await window.__tauriMcpHeapSnapshot()
+        "
+    );
+
+    eval_with_result(window, &full_script, timeout).await
+}
+
+/// Get the scroll position and size of the page or an element
+pub async fn scroll_info<R: Runtime>(
+    window: &WebviewWindow<R>,
+    args: &Value,
+    timeout: Duration,
+) -> Result<Value, String> {
+    let script = include_str!("../scripts/scroll.js");
+    let args_json = serde_json::to_string(args).map_err(|e| e.to_string())?;
+
+    let full_script = format!(
+        r"
+        {script}
+        return window.__tauriMcpScrollInfo({args_json})
+        "
+    );
+
+    eval_with_result_fast(window, &full_script, timeout).await
+}
+
+/// Scroll the page to a position or an element into view, waiting for smooth scrolling to finish
+pub async fn scroll_to<R: Runtime>(
+    window: &WebviewWindow<R>,
+    args: &Value,
+    timeout: Duration,
+) -> Result<Value, String> {
+    let has_position = args.get("x").is_some_and(Value::is_number) || args.get("y").is_some_and(Value::is_number);
+    let has_selector = args.get("selector").and_then(Value::as_str).is_some();
+    if !has_position && !has_selector {
+        return Err(
+            "Missing 'x'/'y' or 'selector' argument. Provide a position or an element to scroll to.".to_string(),
+        );
+    }
 
-    let script = include_str!("../scripts/interact.js");
+    let script = include_str!("../scripts/scroll.js");
     let args_json = serde_json::to_string(args).map_err(|e| e.to_string())?;
 
     let full_script = format!(
         r"
         {script}
-        window.__tauriMcpInteract({args_json})
+        return await window.__tauriMcpScrollTo({args_json})
         "
     );
 
-    eval_with_result(window, &full_script, DEFAULT_TIMEOUT_SECS).await
+    eval_with_result(window, &full_script, timeout).await
 }
 
+/// Keys that pick a selector object's engine, one per object
+pub(super) const SELECTOR_ENGINES: &[&str] = &["css", "xpath", "text", "role", "ref"];
+
+/// Check an optional selector argument: a CSS string, or an object like `{ "text": "Save" }`,
+/// `{ "role": "button", "name": "Save" }`, or `{ "ref": "e12" }`. Returns whether one was given (blank strings don't count).
+pub(super) fn selector_arg(args: &Value, name: &str) -> Result<bool, String> {
+    let selector = match args.get(name) {
+        None | Some(Value::Null) => return Ok(false),
+        Some(Value::String(selector)) => return Ok(!selector.trim().is_empty()),
+        Some(Value::Object(selector)) => selector,
+        Some(value) => {
+            return Err(format!(
+                "'{name}' must be a CSS selector or a selector object like {{ \"text\": \"Save\" }}, got: {value}"
+            ))
+        }
+    };
+
+    let engines = SELECTOR_ENGINES
+        .iter()
+        .filter(|engine| selector.contains_key(**engine))
+        .collect::<Vec<_>>();
+    let [engine] = engines.as_slice() else {
+        return Err(format!(
+            "'{name}' must have exactly one of {}, got: {}",
+            SELECTOR_ENGINES.join(", "),
+            args[name]
+        ));
+    };
+    if !selector[**engine]
+        .as_str()
+        .is_some_and(|value| !value.trim().is_empty())
+    {
+        return Err(format!("'{name}.{engine}' must be a non-empty string"));
+    }
+
+    for (key, value) in selector {
+        match key.as_str() {
+            "name" if **engine == "role" && value.is_string() => {}
+            "exact" if matches!(**engine, "text" | "role") && value.is_boolean() => {}
+            key if SELECTOR_ENGINES.contains(&key) => {}
+            _ => return Err(format!("Unexpected '{name}.{key}' for a '{engine}' selector: {value}")),
+        }
+    }
+
+    Ok(true)
+}
+
+/// Check an optional limit like `maxNodes`, an integer of at least `min`
+pub(super) fn limit_arg(args: &Value, name: &str, min: u64) -> Result<Option<u64>, String> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .filter(|&limit| limit >= min)
+            .map(Some)
+            .ok_or_else(|| format!("'{name}' must be an integer of at least {min}, got: {value}")),
+    }
+}
+
+/// Check the optional `format` of a `dom_snapshot`: `yaml` by default, `json`, `html`, or `markdown`
+#[cfg(feature = "dom-snapshot")]
+fn snapshot_format_arg(args: &Value) -> Result<&str, String> {
+    match args.get("format") {
+        None | Some(Value::Null) => Ok("yaml"),
+        Some(Value::String(format)) if matches!(format.as_str(), "yaml" | "json" | "html" | "markdown") => Ok(format),
+        Some(value) => Err(format!(
+            "'format' must be \"yaml\", \"json\", \"html\", or \"markdown\", got: {value}"
+        )),
+    }
+}
+
+/// Check the optional `includeRects` flag of a `dom_snapshot`
+#[cfg(feature = "dom-snapshot")]
+fn include_rects_arg(args: &Value) -> Result<bool, String> {
+    match args.get("includeRects") {
+        None | Some(Value::Null) => Ok(false),
+        Some(Value::Bool(include)) => Ok(*include),
+        Some(value) => Err(format!("'includeRects' must be true or false, got: {value}")),
+    }
+}
+
+/// Most CSS properties a `dom_snapshot` reads per node
+#[cfg(feature = "dom-snapshot")]
+const MAX_COMPUTED_STYLES: usize = 20;
+
+/// Check the optional `interactiveOnly` flag of a `dom_snapshot`
+#[cfg(feature = "dom-snapshot")]
+fn interactive_only_arg(args: &Value) -> Result<bool, String> {
+    match args.get("interactiveOnly") {
+        None | Some(Value::Null) => Ok(false),
+        Some(Value::Bool(interactive_only)) => Ok(*interactive_only),
+        Some(value) => Err(format!("'interactiveOnly' must be true or false, got: {value}")),
+    }
+}
+
+/// Check the optional `roles` of a `dom_snapshot`: ARIA roles like `button` or `menuitemcheckbox`,
+/// without duplicates
+#[cfg(feature = "dom-snapshot")]
+fn roles_arg(args: &Value) -> Result<Vec<&str>, String> {
+    let names = match args.get("roles") {
+        None | Some(Value::Null) => return Ok(Vec::new()),
+        Some(Value::Array(names)) => names,
+        Some(value) => return Err(format!("'roles' must be an array of ARIA roles, got: {value}")),
+    };
+    let mut roles: Vec<&str> = Vec::with_capacity(names.len());
+    for name in names {
+        let role = name
+            .as_str()
+            .filter(|role| !role.is_empty() && role.chars().all(|c| c.is_ascii_alphabetic()))
+            .ok_or_else(|| format!("Invalid role in 'roles': {name}. Use ARIA roles like \"button\" or \"link\"."))?;
+        if !roles.iter().any(|known| known.eq_ignore_ascii_case(role)) {
+            roles.push(role);
+        }
+    }
+    Ok(roles)
+}
+
+/// Check the optional `computedStyles` of a `dom_snapshot`: CSS property names like `z-index`,
+/// `-webkit-line-clamp`, or `--accent-color`, without duplicates
+#[cfg(feature = "dom-snapshot")]
+fn computed_styles_arg(args: &Value) -> Result<Vec<&str>, String> {
+    let names = match args.get("computedStyles") {
+        None | Some(Value::Null) => return Ok(Vec::new()),
+        Some(Value::Array(names)) => names,
+        Some(value) => {
+            return Err(format!(
+                "'computedStyles' must be an array of CSS property names, got: {value}"
+            ))
+        }
+    };
+    if names.len() > MAX_COMPUTED_STYLES {
+        return Err(format!(
+            "'computedStyles' can have at most {MAX_COMPUTED_STYLES} properties, got {}",
+            names.len()
+        ));
+    }
+    let mut properties: Vec<&str> = Vec::with_capacity(names.len());
+    for name in names {
+        let property = name.as_str().filter(|name| is_css_property(name)).ok_or_else(|| {
+            format!(
+                "Invalid CSS property in 'computedStyles': {name}. Use names as written in CSS: lowercase \
+                 letters, digits, and hyphens like \"z-index\" or \"-webkit-line-clamp\", or custom \
+                 properties like \"--accent-color\"."
+            )
+        })?;
+        if !properties.contains(&property) {
+            properties.push(property);
+        }
+    }
+    Ok(properties)
+}
+
+/// Whether `name` is written like a CSS property, without checking that the browser knows it
+#[cfg(feature = "dom-snapshot")]
+fn is_css_property(name: &str) -> bool {
+    if let Some(custom) = name.strip_prefix("--") {
+        return !custom.is_empty()
+            && custom
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    }
+    let standard = name.strip_prefix('-').unwrap_or(name);
+    standard.starts_with(|c: char| c.is_ascii_lowercase())
+        && standard
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+/// Check the optional `pierceShadow` flag, which makes selectors search open shadow roots
+pub(super) fn pierce_shadow_arg(args: &Value) -> Result<bool, String> {
+    match args.get("pierceShadow") {
+        None | Some(Value::Null) => Ok(false),
+        Some(Value::Bool(pierce)) => Ok(*pierce),
+        Some(value) => Err(format!("'pierceShadow' must be true or false, got: {value}")),
+    }
+}
+
+/// Shortest and longest time the `pollIntervalMs` argument allows between predicate checks
+const PREDICATE_POLL_INTERVAL_RANGE_MS: (u64, u64) = (10, 10_000);
+
 /// Wait for a condition
 pub async fn wait_for<R: Runtime>(window: &WebviewWindow<R>, args: &Value) -> Result<Value, String> {
-    // wait_for can have longer timeouts, use the timeout from args or default
-    let timeout_secs = args
-        .get("timeout")
-        .and_then(Value::as_u64)
-        .map_or(DEFAULT_TIMEOUT_SECS, |ms| (ms / 1000).max(1));
+    let args = validate_wait_for_args(args)?;
 
+    let a11y = include_str!("../scripts/a11y.js");
+    let selectors = include_str!("../scripts/selector.js");
     let script = include_str!("../scripts/wait-for.js");
-    let args_json = serde_json::to_string(args).map_err(|e| e.to_string())?;
+    let args_json = serde_json::to_string(&args).map_err(|e| e.to_string())?;
 
     let full_script = format!(
         r"
+        {a11y}
+        {selectors}
         {script}
-        window.__tauriMcpWaitFor({args_json})
+        return await window.__tauriMcpWaitFor({args_json})
         "
     );
 
-    // Add extra time for the JS-level timeout
-    eval_with_result(window, &full_script, timeout_secs + 2).await
+    eval_with_result(window, &full_script, wait_for_timeout(&args)).await
 }
 
-/// Initial wait time before starting fallback polling (milliseconds)
-const INITIAL_WAIT_MS: u64 = 500;
+/// Check `wait_for` args, returning them with `function` predicates' `script` ready to run as a
+/// function body
+fn validate_wait_for_args(args: &Value) -> Result<Value, String> {
+    let Some(kind @ ("all" | "any")) = args.get("type").and_then(Value::as_str) else {
+        let mut args = validate_wait_condition(args)?;
+        args["timeoutMs"] = Value::from(wait_timeout_ms(&args));
+        return Ok(args);
+    };
+    let conditions = args
+        .get("conditions")
+        .and_then(Value::as_array)
+        .filter(|conditions| !conditions.is_empty())
+        .ok_or_else(|| {
+            format!("'{kind}' needs a non-empty 'conditions' array of conditions like {{ \"type\": \"selector\", \"value\": \".row\" }}")
+        })?
+        .iter()
+        .enumerate()
+        .map(|(i, condition)| validate_wait_condition(condition).map_err(|e| format!("conditions[{i}]: {e}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    pierce_shadow_arg(args)?;
+
+    let mut args = args.clone();
+    args["conditions"] = Value::Array(conditions);
+    args["timeoutMs"] = Value::from(wait_timeout_ms(&args));
+    Ok(args)
+}
+
+/// Check one condition that's waited for in the page, on its own or in `all` or `any`
+fn validate_wait_condition(args: &Value) -> Result<Value, String> {
+    match args.get("type").and_then(Value::as_str) {
+        None => return Err("Missing 'type' argument".to_string()),
+        Some("all" | "any") => {
+            return Err("'all' and 'any' can't be nested. List single conditions in 'conditions'.".to_string())
+        }
+        Some(kind @ ("console" | "windowCount" | "tauriEvent")) => {
+            return Err(format!(
+                "'{kind}' is checked outside the page, so it can't be combined with others in 'all' or 'any'"
+            ))
+        }
+        // Text conditions take a string, the others a selector
+        Some("text") => {
+            if args
+                .get("value")
+                .is_some_and(|value| !value.is_null() && !value.is_string())
+            {
+                return Err(format!("'value' must be a string for 'text', got: {}", args["value"]));
+            }
+        }
+        Some("function") => {
+            let Some(script) = args
+                .get("script")
+                .and_then(Value::as_str)
+                .filter(|script| !script.trim().is_empty())
+            else {
+                return Err(
+                    "'function' needs a 'script' that returns a truthy value when the condition is met".to_string(),
+                );
+            };
+            let (min, max) = PREDICATE_POLL_INTERVAL_RANGE_MS;
+            if let Some(interval) = args.get("pollIntervalMs").filter(|interval| !interval.is_null()) {
+                if !interval.as_u64().is_some_and(|ms| (min..=max).contains(&ms)) {
+                    return Err(format!(
+                        "'pollIntervalMs' must be between {min} and {max}, got: {interval}"
+                    ));
+                }
+            }
+            // Same auto-return as execute_js, so `store.ready` works like `return store.ready`
+            let mut args = args.clone();
+            args["script"] = Value::String(prepare_script(script));
+            return Ok(args);
+        }
+        _ => {
+            selector_arg(args, "value")?;
+        }
+    }
+    pierce_shadow_arg(args)?;
+    Ok(args.clone())
+}
 
-/// Interval for fallback polling in milliseconds
+/// Longest interval for fallback polling in milliseconds
 const FALLBACK_POLL_INTERVAL_MS: u64 = 100;
 
+/// First interval for fallback polling, doubled after each poll up to [`FALLBACK_POLL_INTERVAL_MS`]
+const FIRST_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// First fallback polling interval for [`eval_with_result_fast`]
+const FAST_FIRST_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Get the fallback polling interval after `interval`: twice as long, up to the longest interval
+fn next_poll_interval(interval: Duration) -> Duration {
+    (interval * 2).min(Duration::from_millis(FALLBACK_POLL_INTERVAL_MS))
+}
+
 /// Evaluate JavaScript and retrieve the result via Tauri events
-async fn eval_with_result<R: Runtime>(
+pub(super) async fn eval_with_result<R: Runtime>(
+    window: &WebviewWindow<R>,
+    script: &str,
+    timeout: Duration,
+) -> Result<Value, String> {
+    eval_with_first_poll(window, script, timeout, FIRST_POLL_INTERVAL).await
+}
+
+/// Like [`eval_with_result`] for scripts that only read a bit of page state, like `is_visible`'s,
+/// so their results are polled for sooner
+pub(super) async fn eval_with_result_fast<R: Runtime>(
+    window: &WebviewWindow<R>,
+    script: &str,
+    timeout: Duration,
+) -> Result<Value, String> {
+    eval_with_first_poll(window, script, timeout, FAST_FIRST_POLL_INTERVAL).await
+}
+
+/// Evaluate JavaScript, polling for the result from `first_poll` on if its event doesn't arrive
+async fn eval_with_first_poll<R: Runtime>(
     window: &WebviewWindow<R>,
     script: &str,
-    timeout_secs: u64,
+    timeout: Duration,
+    first_poll: Duration,
 ) -> Result<Value, String> {
     let exec_id = Uuid::new_v4().to_string();
     let (tx, rx) = oneshot::channel::<Value>();
     let tx = Arc::new(Mutex::new(Some(tx)));
 
     // Set up event listener for the result
-    let unlisten = setup_result_listener(window, &exec_id, Arc::clone(&tx));
+    let event = result_event_name(window.label());
+    let unlisten = setup_result_listener(window, &event, &exec_id, Arc::clone(&tx));
 
     // Create and execute the wrapped script
     let prepared_script = prepare_script(script);
-    let wrapped_script = create_wrapped_script(&exec_id, &prepared_script);
+    let wrapped_script = create_wrapped_script(&exec_id, &prepared_script, &event);
 
     if let Err(e) = window.eval(&wrapped_script) {
         window.unlisten(unlisten);
         return Err(format!("Script execution failed: {e}"));
     }
 
-    // Wait for result with timeout and lazy fallback polling
-    let timeout = std::time::Duration::from_secs(timeout_secs);
-    let result = wait_for_result(window, rx, &exec_id, timeout).await;
+    // Wait for result with timeout and fallback polling
+    let result = wait_for_result(window, rx, &exec_id, &event, timeout, first_poll).await;
 
     // Clean up
     window.unlisten(unlisten);
-    let cleanup = format!(r"if (window.__tauriMcpResults) {{ delete window.__tauriMcpResults['{exec_id}']; }}");
-    let _ = window.eval(&cleanup);
+    let _ = window.eval(clear_result_script(&exec_id));
 
     // Extract the actual result or error
     match result {
@@ -195,14 +974,23 @@ async fn eval_with_result<R: Runtime>(
     }
 }
 
+/// Name of the event that carries script results from a window.
+///
+/// Scoped to the window, so a window's listeners don't see results of scripts run in other
+/// windows. Labels only contain characters that event names allow.
+fn result_event_name(window_label: &str) -> String {
+    format!("__tauri_mcp_script_result_{window_label}")
+}
+
 /// Set up the event listener for script results
 fn setup_result_listener<R: Runtime>(
     window: &WebviewWindow<R>,
+    event: &str,
     exec_id: &str,
     tx: Arc<Mutex<Option<oneshot::Sender<Value>>>>,
 ) -> tauri::EventId {
     let exec_id_clone = exec_id.to_string();
-    window.listen("__tauri_mcp_script_result", move |event| {
+    window.listen(event, move |event| {
         let payload_str = event.payload();
         if let Ok(payload) = serde_json::from_str::<ScriptResultPayload>(payload_str) {
             if payload.exec_id == exec_id_clone {
@@ -231,30 +1019,32 @@ fn setup_result_listener<R: Runtime>(
 }
 
 /// Create the wrapped JavaScript that stores results and emits events
-fn create_wrapped_script(exec_id: &str, prepared_script: &str) -> String {
+fn create_wrapped_script(exec_id: &str, prepared_script: &str, event: &str) -> String {
+    let exec_id = js_string(exec_id);
+    let event = js_string(event);
     format!(
         r"
         (function() {{
             window.__tauriMcpResults = window.__tauriMcpResults || {{}};
 
             function __storeResult(success, data, error) {{
-                window.__tauriMcpResults['{exec_id}'] = {{ success: success, data: data, error: error }};
+                window.__tauriMcpResults[{exec_id}] = {{ success: success, data: data, error: error }};
                 setTimeout(function() {{
-                    if (window.__tauriMcpResults) {{ delete window.__tauriMcpResults['{exec_id}']; }}
+                    if (window.__tauriMcpResults) {{ delete window.__tauriMcpResults[{exec_id}]; }}
                 }}, 10000);
             }}
 
             function __sendResult(success, data, error) {{
                 __storeResult(success, data, error);
-                const payload = {{ exec_id: '{exec_id}', success: success, data: data, error: error }};
+                const payload = {{ exec_id: {exec_id}, success: success, data: data, error: error }};
 
                 if (window.__TAURI__ && window.__TAURI__.event && window.__TAURI__.event.emit) {{
-                    window.__TAURI__.event.emit('__tauri_mcp_script_result', payload);
+                    window.__TAURI__.event.emit({event}, payload);
                     return;
                 }}
                 if (window.__TAURI_INTERNALS__ && window.__TAURI_INTERNALS__.invoke) {{
                     window.__TAURI_INTERNALS__.invoke('plugin:event|emit', {{
-                        event: '__tauri_mcp_script_result',
+                        event: {event},
                         payload: payload
                     }});
                     return;
@@ -279,73 +1069,111 @@ fn create_wrapped_script(exec_id: &str, prepared_script: &str) -> String {
     )
 }
 
-/// Wait for result via event channel with lazy fallback polling
+/// Wait for result via event channel with fallback polling
 ///
-/// This function trusts events first and only starts polling after an initial wait period
-/// if no event is received. This is more efficient than polling from the start.
+/// Events usually bring the result, but one can get lost, so the result store is polled too.
+/// Polls start `first_poll` after the script was run and back off, so a quick result whose event
+/// got lost is still picked up soon without polling slow scripts over and over.
 async fn wait_for_result<R: Runtime>(
     window: &WebviewWindow<R>,
     mut rx: oneshot::Receiver<Value>,
     exec_id: &str,
-    timeout: std::time::Duration,
+    event: &str,
+    timeout: Duration,
+    first_poll: Duration,
 ) -> Result<Value, String> {
-    let initial_wait = std::time::Duration::from_millis(INITIAL_WAIT_MS).min(timeout);
-    let poll_interval = std::time::Duration::from_millis(FALLBACK_POLL_INTERVAL_MS);
-    let start = std::time::Instant::now();
-
-    // Phase 1: Wait for event without polling (trust the event mechanism)
-    tokio::select! {
-        biased;
-        result = &mut rx => {
-            return result.map_err(|_| "Result channel closed".to_string());
-        }
-        () = tokio::time::sleep(initial_wait) => {
-            // Event didn't arrive in initial wait, start fallback polling
-        }
-    }
-
-    // Phase 2: Fallback polling - check result store and re-emit events
-    while start.elapsed() < timeout {
-        // Poll the result store and re-emit as event
-        let poll_script = format!(
-            r"(function() {{
-                if (window.__tauriMcpResults && window.__tauriMcpResults['{exec_id}']) {{
-                    var r = window.__tauriMcpResults['{exec_id}'];
-                    var payload = {{
-                        exec_id: '{exec_id}',
-                        success: r.success,
-                        data: r.data,
-                        error: r.error
-                    }};
-                    if (window.__TAURI__ && window.__TAURI__.event && window.__TAURI__.event.emit) {{
-                        window.__TAURI__.event.emit('__tauri_mcp_script_result', payload);
-                    }} else if (window.__TAURI_INTERNALS__ && window.__TAURI_INTERNALS__.invoke) {{
-                        window.__TAURI_INTERNALS__.invoke('plugin:event|emit', {{
-                            event: '__tauri_mcp_script_result',
-                            payload: payload
-                        }});
-                    }}
-                }}
-            }})()"
-        );
-        let _ = window.eval(&poll_script);
+    let deadline = Instant::now() + timeout;
+    let mut poll_interval = first_poll;
 
-        // Wait for either event or poll interval
+    loop {
+        // Wait for either the event or the next poll
+        let wait = poll_interval.min(deadline.saturating_duration_since(Instant::now()));
         tokio::select! {
             biased;
             result = &mut rx => {
                 return result.map_err(|_| "Result channel closed".to_string());
             }
-            () = tokio::time::sleep(poll_interval) => {
-                // Continue polling
-            }
+            () = tokio::time::sleep(wait) => {}
+        }
+        if Instant::now() >= deadline {
+            break;
         }
+
+        // Poll the result store, which re-emits a stored result as its event
+        let _ = window.eval(poll_result_script(exec_id, event));
+        poll_interval = next_poll_interval(poll_interval);
     }
 
     Err(format!("Script execution timeout after {}ms", timeout.as_millis()))
 }
 
-/// Prepare script by adding return statement if needed
+/// Script that re-emits a stored result as its event, for when the first event got lost
+fn poll_result_script(exec_id: &str, event: &str) -> String {
+    let exec_id = js_string(exec_id);
+    let event = js_string(event);
+    format!(
+        r"(function() {{
+            if (window.__tauriMcpResults && window.__tauriMcpResults[{exec_id}]) {{
+                var r = window.__tauriMcpResults[{exec_id}];
+                var payload = {{
+                    exec_id: {exec_id},
+                    success: r.success,
+                    data: r.data,
+                    error: r.error
+                }};
+                if (window.__TAURI__ && window.__TAURI__.event && window.__TAURI__.event.emit) {{
+                    window.__TAURI__.event.emit({event}, payload);
+                }} else if (window.__TAURI_INTERNALS__ && window.__TAURI_INTERNALS__.invoke) {{
+                    window.__TAURI_INTERNALS__.invoke('plugin:event|emit', {{
+                        event: {event},
+                        payload: payload
+                    }});
+                }}
+            }}
+        }})()"
+    )
+}
+
+/// Script that drops a result from the page's store once it's been read
+fn clear_result_script(exec_id: &str) -> String {
+    let exec_id = js_string(exec_id);
+    format!(r"if (window.__tauriMcpResults) {{ delete window.__tauriMcpResults[{exec_id}]; }}")
+}
+
+/// Encode a string as a JavaScript string literal.
+///
+/// Values put into generated scripts go through this or [`js_value`], never between quotes, so
+/// whatever they contain can't end up as code. Line and paragraph separators are escaped too,
+/// since engines before ES2019 end string literals at them.
+fn js_string(value: &str) -> String {
+    js_value(&Value::from(value))
+}
+
+/// Encode a JSON value as a JavaScript literal, like [`js_string`]
+fn js_value(value: &Value) -> String {
+    value
+        .to_string()
+        .replace('\u{2028}', "\\u2028")
+        .replace('\u{2029}', "\\u2029")
+}
+
+/// Statement keywords that start a script that shouldn't get an auto-return
+const STATEMENT_KEYWORDS: &[&str] = &[
+    "const ",
+    "let ",
+    "var ",
+    "if ",
+    "for ",
+    "while ",
+    "function ",
+    "class ",
+    "try ",
+];
+
+/// Prepare script by adding return statement if needed.
+///
+/// Single expressions, IIFEs included, get a `return`. Scripts with several statements, ones that
+/// start with a statement keyword, and bare arrow functions like `(x) => x * 2` are left as they are.
 fn prepare_script(script: &str) -> String {
     let trimmed = script.trim();
 
@@ -354,44 +1182,411 @@ fn prepare_script(script: &str) -> String {
         return script.to_string();
     }
 
-    // Check if it's a multi-statement script that shouldn't get auto-return
-    let has_real_semicolons = trimmed.strip_suffix(';').map_or_else(
-        || trimmed.contains(';'),
-        |without_trailing| without_trailing.contains(';'),
-    );
+    let syntax = TopLevelSyntax::scan(trimmed.strip_suffix(';').unwrap_or(trimmed));
+    let is_multi_statement =
+        syntax.has_semicolon || STATEMENT_KEYWORDS.iter().any(|keyword| trimmed.starts_with(keyword));
 
-    let is_multi_statement = has_real_semicolons
-        || trimmed.starts_with("const ")
-        || trimmed.starts_with("let ")
-        || trimmed.starts_with("var ")
-        || trimmed.starts_with("if ")
-        || trimmed.starts_with("for ")
-        || trimmed.starts_with("while ")
-        || trimmed.starts_with("function ")
-        || trimmed.starts_with("class ")
-        || trimmed.starts_with("try ");
-
-    // Single expression patterns that should get auto-return
-    let is_single_expression = trimmed.starts_with("await ")
-        || trimmed.starts_with('(')
-        || trimmed.starts_with("JSON.")
-        || trimmed.starts_with('{')
-        || trimmed.starts_with('[')
-        || trimmed.ends_with(")()")
-        || trimmed.starts_with("document.")
-        || trimmed.starts_with("window.")
-        || trimmed.starts_with("new ");
-
-    if is_single_expression || !is_multi_statement {
-        format!("return {trimmed}")
-    } else {
+    if is_multi_statement || syntax.has_arrow {
         script.to_string()
+    } else {
+        format!("return {trimmed}")
+    }
+}
+
+/// What a script has outside of brackets, strings, template literals, and comments
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TopLevelSyntax {
+    /// A `;` separating statements
+    has_semicolon: bool,
+    /// A `=>`, so the script is an arrow function rather than a call of one
+    has_arrow: bool,
+}
+
+/// Where [`TopLevelSyntax::scan`] is in the script
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Lexeme {
+    Code,
+    /// In a string or template literal opened with this quote
+    Quoted(char),
+    LineComment,
+    BlockComment,
+}
+
+impl TopLevelSyntax {
+    fn scan(script: &str) -> Self {
+        let mut syntax = Self::default();
+        let mut depth = 0usize;
+        // Bracket depths at which `${` placeholders were opened, innermost last
+        let mut placeholders = Vec::new();
+        let mut lexeme = Lexeme::Code;
+        let mut chars = script.chars().peekable();
+        while let Some(c) = chars.next() {
+            match lexeme {
+                Lexeme::Quoted(_) if c == '\\' => {
+                    chars.next();
+                }
+                Lexeme::Quoted('`') if c == '$' && chars.peek() == Some(&'{') => {
+                    chars.next();
+                    placeholders.push(depth);
+                    depth += 1;
+                    lexeme = Lexeme::Code;
+                }
+                Lexeme::Quoted(quote) if c == quote => lexeme = Lexeme::Code,
+                Lexeme::LineComment if c == '\n' => lexeme = Lexeme::Code,
+                Lexeme::BlockComment if c == '*' && chars.peek() == Some(&'/') => {
+                    chars.next();
+                    lexeme = Lexeme::Code;
+                }
+                Lexeme::Quoted(_) | Lexeme::LineComment | Lexeme::BlockComment => {}
+                Lexeme::Code => match c {
+                    '\'' | '"' | '`' => lexeme = Lexeme::Quoted(c),
+                    '/' if chars.peek() == Some(&'/') => lexeme = Lexeme::LineComment,
+                    '/' if chars.peek() == Some(&'*') => {
+                        chars.next();
+                        lexeme = Lexeme::BlockComment;
+                    }
+                    '(' | '[' | '{' => depth += 1,
+                    ')' | ']' | '}' => {
+                        depth = depth.saturating_sub(1);
+                        // The `}` closing a placeholder goes back into its template literal
+                        if c == '}' && placeholders.last() == Some(&depth) {
+                            placeholders.pop();
+                            lexeme = Lexeme::Quoted('`');
+                        }
+                    }
+                    ';' if depth == 0 => syntax.has_semicolon = true,
+                    '=' if depth == 0 && chars.peek() == Some(&'>') => syntax.has_arrow = true,
+                    _ => {}
+                },
+            }
+        }
+        syntax
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use super::*;
+    use serde_json::json;
+
+    #[test]
+    #[cfg(feature = "js-execution")]
+    fn script_timeout_uses_configured_default() {
+        let default = Duration::from_secs(20);
+        assert_eq!(script_timeout_for("execute_js", &json!({}), default), default);
+        assert_eq!(script_timeout_for("dom_snapshot", &json!({}), default), default);
+    }
+
+    #[test]
+    #[cfg(feature = "js-execution")]
+    fn script_timeout_prefers_execute_js_arg_in_seconds() {
+        let timeout = script_timeout_for("execute_js", &json!({"timeout": 30}), Duration::from_secs(5));
+        assert_eq!(timeout, Duration::from_secs(30));
+    }
+
+    #[test]
+    fn script_timeout_converts_wait_for_arg_from_milliseconds() {
+        let timeout = script_timeout_for("wait_for", &json!({"timeout": 15000}), Duration::from_secs(5));
+        assert_eq!(timeout, Duration::from_secs(15) + WAIT_FOR_EXTRA_TIME);
+    }
+
+    #[test]
+    #[cfg(feature = "dom-snapshot")]
+    fn snapshot_limits_must_be_big_enough_integers() {
+        assert_eq!(limit_arg(&json!({}), "maxNodes", 1), Ok(None));
+        assert_eq!(limit_arg(&json!({"maxDepth": 0}), "maxDepth", 0), Ok(Some(0)));
+        assert!(limit_arg(&json!({"maxNodes": 0}), "maxNodes", 1)
+            .unwrap_err()
+            .contains("'maxNodes' must be an integer of at least 1"));
+        assert!(limit_arg(&json!({"maxDepth": 2.5}), "maxDepth", 0).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "dom-snapshot")]
+    fn snapshot_format_is_yaml_or_json() {
+        assert_eq!(snapshot_format_arg(&json!({})), Ok("yaml"));
+        assert_eq!(snapshot_format_arg(&json!({"format": "json"})), Ok("json"));
+        assert_eq!(snapshot_format_arg(&json!({"format": "markdown"})), Ok("markdown"));
+        assert!(snapshot_format_arg(&json!({"format": "xml"}))
+            .unwrap_err()
+            .contains("'format' must be \"yaml\", \"json\", \"html\", or \"markdown\""));
+        assert_eq!(include_rects_arg(&json!({"includeRects": true})), Ok(true));
+        assert!(include_rects_arg(&json!({"includeRects": "yes"})).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "dom-snapshot")]
+    fn computed_styles_are_css_property_names() {
+        assert_eq!(computed_styles_arg(&json!({})), Ok(vec![]));
+        assert_eq!(
+            computed_styles_arg(
+                &json!({"computedStyles": ["display", "z-index", "display", "-webkit-line-clamp", "--accent_2"]})
+            ),
+            Ok(vec!["display", "z-index", "-webkit-line-clamp", "--accent_2"])
+        );
+        for garbage in [
+            json!("zIndex"),
+            json!(""),
+            json!("color; background: red"),
+            json!("--"),
+            json!(3),
+        ] {
+            assert!(computed_styles_arg(&json!({"computedStyles": [garbage]}))
+                .unwrap_err()
+                .contains("Use names as written in CSS"));
+        }
+        assert!(computed_styles_arg(&json!({"computedStyles": "display"}))
+            .unwrap_err()
+            .contains("must be an array"));
+        let too_many = vec!["color"; MAX_COMPUTED_STYLES + 1];
+        assert!(computed_styles_arg(&json!({"computedStyles": too_many}))
+            .unwrap_err()
+            .contains("at most 20 properties"));
+    }
+
+    #[test]
+    #[cfg(feature = "dom-snapshot")]
+    fn roles_are_aria_role_names() {
+        assert_eq!(roles_arg(&json!({})), Ok(vec![]));
+        assert_eq!(
+            roles_arg(&json!({"roles": ["button", "link", "Button", "menuitemcheckbox"]})),
+            Ok(vec!["button", "link", "menuitemcheckbox"])
+        );
+        for garbage in [json!(""), json!("menu-item"), json!("button link"), json!(1)] {
+            assert!(roles_arg(&json!({"roles": [garbage]}))
+                .unwrap_err()
+                .contains("Use ARIA roles like"));
+        }
+        assert!(roles_arg(&json!({"roles": "button"}))
+            .unwrap_err()
+            .contains("must be an array"));
+        assert!(interactive_only_arg(&json!({"interactiveOnly": "yes"}))
+            .unwrap_err()
+            .contains("must be true or false"));
+    }
+
+    #[test]
+    #[cfg(feature = "js-execution")]
+    fn timeout_ms_wins_over_legacy_timeout() {
+        let default = Duration::from_secs(5);
+        let timeout = script_timeout_for("execute_js", &json!({"timeoutMs": 1500, "timeout": 30}), default);
+        assert_eq!(timeout, Duration::from_millis(1500));
+        let timeout = script_timeout_for("wait_for", &json!({"timeoutMs": 250}), default);
+        assert_eq!(timeout, Duration::from_millis(250) + WAIT_FOR_EXTRA_TIME);
+    }
+
+    #[test]
+    fn wait_for_script_outlasts_the_condition_timeout() {
+        // The script timeout must never fire before the condition's own, whatever the config says
+        for args in [
+            json!({"type": "selector", "value": "body"}),
+            json!({"type": "selector", "value": "body", "timeoutMs": 1999}),
+            json!({"type": "selector", "value": "body", "timeout": 999}),
+            json!({"type": "all", "conditions": [{"type": "text", "value": "a"}], "timeoutMs": 60_000}),
+        ] {
+            let prepared = validate_wait_for_args(&args).unwrap();
+            let condition_timeout = Duration::from_millis(prepared["timeoutMs"].as_u64().unwrap());
+            let script_timeout = script_timeout_for("wait_for", &args, Duration::from_secs(1));
+            assert!(script_timeout > condition_timeout, "{args}");
+        }
+    }
+
+    #[test]
+    fn find_text_checks_args() {
+        assert!(validate_find_text_args(&json!({"query": "Payment failed"})).is_ok());
+        assert!(validate_find_text_args(&json!({"query": "fail(ed|ure)", "regex": true, "maxResults": 5})).is_ok());
+        let err = validate_find_text_args(&json!({})).unwrap_err();
+        assert!(err.contains("'query'"));
+        let err = validate_find_text_args(&json!({"query": ""})).unwrap_err();
+        assert!(err.contains("empty"));
+        let err = validate_find_text_args(&json!({"query": "a", "maxResults": 0})).unwrap_err();
+        assert!(err.contains("maxResults"));
+    }
+
+    #[test]
+    fn element_info_checks_args() {
+        assert!(validate_element_info_args(&json!({"selector": "#save"})).is_ok());
+        assert!(validate_element_info_args(&json!({
+            "selector": {"role": "button", "name": "Save"},
+            "styles": ["display", "z-index"],
+            "all": true,
+            "pierceShadow": true
+        }))
+        .is_ok());
+
+        let err = validate_element_info_args(&json!({})).unwrap_err();
+        assert!(err.contains("Missing required 'selector'"));
+        let err = validate_element_info_args(&json!({"selector": "#save", "styles": "display"})).unwrap_err();
+        assert!(err.contains("'styles' must be a list"));
+        let err = validate_element_info_args(&json!({"selector": "#save", "styles": [1]})).unwrap_err();
+        assert!(err.contains("'styles' must be a list"));
+        let err = validate_element_info_args(&json!({"selector": "#save", "all": "yes"})).unwrap_err();
+        assert!(err.contains("'all' must be true or false"));
+        assert!(validate_element_info_args(&json!({"selector": {"text": ""}})).is_err());
+    }
+
+    #[test]
+    fn highlight_checks_args() {
+        assert!(validate_highlight_args(&json!({"selector": "#save"})).is_ok());
+        assert!(validate_highlight_args(&json!({
+            "selector": {"text": "Save"},
+            "durationMs": 5000,
+            "color": "orange",
+            "label": 1
+        }))
+        .is_ok());
+
+        let err = validate_highlight_args(&json!({"durationMs": 100})).unwrap_err();
+        assert!(err.contains("Missing required 'selector'"));
+        let err = validate_highlight_args(&json!({"selector": "#save", "durationMs": 0})).unwrap_err();
+        assert!(err.contains("'durationMs' must be between 1 and 60000"));
+        let err = validate_highlight_args(&json!({"selector": "#save", "durationMs": 120_000})).unwrap_err();
+        assert!(err.contains("'durationMs' must be between"));
+        let err = validate_highlight_args(&json!({"selector": "#save", "color": 3})).unwrap_err();
+        assert!(err.contains("'color' must be a CSS color"));
+        let err = validate_highlight_args(&json!({"selector": "#save", "label": ["a"]})).unwrap_err();
+        assert!(err.contains("'label' must be a string or a number"));
+    }
+
+    #[test]
+    fn poll_interval_doubles_up_to_the_longest_interval() {
+        let mut interval = FIRST_POLL_INTERVAL;
+        let mut intervals = Vec::new();
+        for _ in 0..6 {
+            intervals.push(interval.as_millis());
+            interval = next_poll_interval(interval);
+        }
+        assert_eq!(intervals, [10, 20, 40, 80, 100, 100]);
+    }
+
+    #[test]
+    fn script_results_use_an_event_per_window() {
+        assert_eq!(result_event_name("main"), "__tauri_mcp_script_result_main");
+
+        let script = create_wrapped_script("abc", "return 1", &result_event_name("settings"));
+        assert!(script.contains(r#"emit("__tauri_mcp_script_result_settings", payload)"#));
+        assert!(script.contains(r#"event: "__tauri_mcp_script_result_settings""#));
+    }
+
+    /// Strings that would get out of a quoted literal in a generated script
+    const HOSTILE_STRINGS: &[&str] = &[
+        "'); alert(1); ('",
+        "\"]; alert(1); //",
+        "\\'; alert(1); //",
+        "`${alert(1)}`",
+        "x\n}); alert(1); ({",
+        "\u{2028}alert(1)",
+    ];
+
+    /// Check that `build` puts `value` in its script only as whole literals, where it puts a
+    /// harmless value
+    fn assert_only_in_literals(value: &str, build: impl Fn(&str) -> String) {
+        let literal = js_string(value);
+        assert_eq!(serde_json::from_str::<String>(&literal).unwrap(), value);
+        assert!(!literal.contains(['\n', '\u{2028}']));
+
+        let script = build(value).replace(&literal, "VALUE");
+        let harmless = build("harmless").replace(&js_string("harmless"), "VALUE");
+        assert_eq!(script, harmless, "{value:?} got out of its literal");
+    }
+
+    #[test]
+    fn result_scripts_keep_ids_and_events_in_literals() {
+        for value in HOSTILE_STRINGS {
+            assert_only_in_literals(value, |v| create_wrapped_script(v, "return 1", v));
+            assert_only_in_literals(value, |v| poll_result_script(v, v));
+            assert_only_in_literals(value, clear_result_script);
+        }
+    }
+
+    /// Check that a command's script, once prepared to run, ends by returning what `call` gives
+    fn assert_returns(script: &str, call: &str) {
+        let prepared = prepare_script(script);
+        let last = prepared.trim_end().lines().last().unwrap().trim();
+        let returned = last
+            .strip_prefix("return ")
+            .map(|value| value.trim_start_matches("await "));
+        assert!(
+            returned.is_some_and(|value| value.starts_with(call)),
+            "the script ends with `{last}` rather than returning {call}"
+        );
+    }
+
+    #[test]
+    fn command_scripts_return_their_result() {
+        let args = json!({ "role": "button" });
+        assert_returns(&accessibility_find_script(&args), "window.__tauriMcpAccessibilityFind(");
+        #[cfg(feature = "dom-snapshot")]
+        assert_returns(
+            &snapshot_script("structure", &Value::Null, &json!({})),
+            "window.__tauriMcpDomSnapshot(",
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "dom-snapshot")]
+    fn snapshot_script_keeps_args_in_literals() {
+        for value in HOSTILE_STRINGS {
+            assert_only_in_literals(value, |v| snapshot_script(v, &json!(v), &json!({ "format": v })));
+        }
+    }
+
+    #[test]
+    fn wait_for_prepares_predicate_scripts() {
+        let args = validate_wait_for_args(&json!({
+            "type": "function",
+            "script": "store.getState().sync.done",
+            "pollIntervalMs": 50
+        }))
+        .unwrap();
+        assert_eq!(args["script"], "return store.getState().sync.done");
+
+        let script = "const state = store.getState(); return state.sync.done && state";
+        let args = validate_wait_for_args(&json!({"type": "function", "script": script})).unwrap();
+        assert_eq!(args["script"], script);
+    }
+
+    #[test]
+    fn wait_for_rejects_invalid_predicates() {
+        let err = validate_wait_for_args(&json!({"type": "function"})).unwrap_err();
+        assert!(err.contains("'function' needs a 'script'"));
+        let err = validate_wait_for_args(&json!({"type": "function", "script": "  "})).unwrap_err();
+        assert!(err.contains("'function' needs a 'script'"));
+        let err =
+            validate_wait_for_args(&json!({"type": "function", "script": "ready", "pollIntervalMs": 1})).unwrap_err();
+        assert!(err.contains("'pollIntervalMs' must be between 10 and 10000"));
+    }
+
+    #[test]
+    fn wait_for_checks_compound_conditions() {
+        let args = validate_wait_for_args(&json!({
+            "type": "all",
+            "conditions": [
+                { "type": "selector", "value": ".list-item" },
+                { "type": "function", "script": "window.idle" }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(args["conditions"][1]["script"], "return window.idle");
+
+        let err = validate_wait_for_args(&json!({"type": "any", "conditions": []})).unwrap_err();
+        assert!(err.contains("'any' needs a non-empty 'conditions' array"));
+        let err = validate_wait_for_args(&json!({
+            "type": "all",
+            "conditions": [{ "type": "selector", "value": "#a" }, { "type": "any", "conditions": [] }]
+        }))
+        .unwrap_err();
+        assert!(err.starts_with("conditions[1]: 'all' and 'any' can't be nested"));
+        let err = validate_wait_for_args(&json!({
+            "type": "any",
+            "conditions": [{ "type": "console", "textContains": "ready" }]
+        }))
+        .unwrap_err();
+        assert!(err.contains("'console' is checked outside the page"));
+        let err = validate_wait_for_args(&json!({"type": "all", "conditions": [{ "value": "#a" }]})).unwrap_err();
+        assert_eq!(err, "conditions[0]: Missing 'type' argument");
+    }
 
     #[test]
     fn prepare_script_adds_return_to_expression() {
@@ -467,6 +1662,74 @@ mod tests {
         assert_eq!(prepare_script("window.location.href"), "return window.location.href");
     }
 
+    #[test]
+    fn prepare_script_adds_return_to_arrow_iife() {
+        assert_eq!(
+            prepare_script("(async () => { await tick(); return 1; })()"),
+            "return (async () => { await tick(); return 1; })()"
+        );
+        assert_eq!(
+            prepare_script("items.map((x) => x * 2)"),
+            "return items.map((x) => x * 2)"
+        );
+    }
+
+    #[test]
+    fn prepare_script_no_return_for_arrow_function() {
+        let script = "(x) => x * 2";
+        assert_eq!(prepare_script(script), script);
+        let script = "async (x) => { await x; }";
+        assert_eq!(prepare_script(script), script);
+    }
+
+    #[test]
+    fn prepare_script_no_return_for_statements_ending_in_iife() {
+        let script = "const x = 1; (function() { console.log(x); })()";
+        assert_eq!(prepare_script(script), script);
+        let script = "await setup(); (() => check())()";
+        assert_eq!(prepare_script(script), script);
+    }
+
+    #[test]
+    fn prepare_script_ignores_semicolons_in_strings_and_blocks() {
+        assert_eq!(prepare_script("'a;b'.split(';')"), "return 'a;b'.split(';')");
+        assert_eq!(
+            prepare_script("(function() { const a = 1; return a; })()"),
+            "return (function() { const a = 1; return a; })()"
+        );
+        assert_eq!(prepare_script("`x => ${y};`"), "return `x => ${y};`");
+        assert_eq!(prepare_script("document.title;"), "return document.title;");
+    }
+
+    #[test]
+    fn prepare_script_ignores_semicolons_in_template_literals() {
+        assert_eq!(prepare_script("`Hello; World`"), "return `Hello; World`");
+        assert_eq!(
+            prepare_script("`Total: ${items.map((i) => i.price).join('; ')};`"),
+            "return `Total: ${items.map((i) => i.price).join('; ')};`"
+        );
+        // A backtick inside a placeholder's string doesn't end the template literal
+        assert_eq!(
+            prepare_script("`a ${flag ? '`' : \"b\"}; c`"),
+            "return `a ${flag ? '`' : \"b\"}; c`"
+        );
+        let script = "const s = `a; b`; s";
+        assert_eq!(prepare_script(script), script);
+    }
+
+    #[test]
+    fn prepare_script_ignores_semicolons_and_arrows_in_comments() {
+        assert_eq!(
+            prepare_script("document.title /* a; b => c */"),
+            "return document.title /* a; b => c */"
+        );
+        assert_eq!(
+            prepare_script("window.x // done; x => y\n.y"),
+            "return window.x // done; x => y\n.y"
+        );
+        assert_eq!(prepare_script("'it\\'s; fine'"), "return 'it\\'s; fine'");
+    }
+
     #[test]
     fn prepare_script_trims_whitespace() {
         assert_eq!(prepare_script("  document.title  "), "return document.title");
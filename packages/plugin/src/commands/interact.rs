@@ -0,0 +1,1160 @@
+//! UI interaction commands: DOM events, or native OS input with `native: true`
+
+use std::time::Duration;
+
+use base64::Engine;
+use serde_json::Value;
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js::{eval_with_result, pierce_shadow_arg, selector_arg, SELECTOR_ENGINES};
+use super::native_input;
+
+/// `interact` drags take `durationMs` on top of the usual time, and waiting for the selector can
+/// take `waitTimeoutMs` twice (once more if the element is replaced). Sequences take their steps'
+/// extra time and `delayMs` pauses, and native input the time to type and confirm it arrived.
+pub(super) fn interact_timeout(args: &Value, default: Duration) -> Duration {
+    let ms = |args: &Value, name: &str| args.get(name).and_then(Value::as_u64).unwrap_or(0);
+    let extra_ms = |args: &Value| {
+        ms(args, "durationMs")
+            .saturating_add(ms(args, "holdMs"))
+            .saturating_add(selector_wait_ms(args).saturating_mul(2))
+    };
+    let extra_ms = sequence_actions(args).map_or_else(
+        || extra_ms(args),
+        |actions| {
+            actions
+                .iter()
+                .map(|step| extra_ms(step).saturating_add(ms(step, "delayMs")))
+                .fold(0, u64::saturating_add)
+        },
+    );
+
+    let native_extra = if native_arg(args) == Ok(true) {
+        native_input::input_time(args)
+    } else {
+        Duration::ZERO
+    };
+
+    default + Duration::from_millis(extra_ms) + native_extra
+}
+
+/// How long an `interact` action waits for its selector to match a ready element
+fn selector_wait_ms(args: &Value) -> u64 {
+    let has_selector = selector_arg(args, "selector").unwrap_or(false);
+    if !has_selector || args.get("waitForSelector").and_then(Value::as_bool) == Some(false) {
+        return 0;
+    }
+    args.get("waitTimeoutMs")
+        .and_then(Value::as_u64)
+        .unwrap_or(DEFAULT_SELECTOR_WAIT_MS)
+}
+
+/// The steps of an `interact` sequence, if that's what the args are
+fn sequence_actions(args: &Value) -> Option<&Vec<Value>> {
+    if args.get("action").and_then(Value::as_str) == Some("sequence") {
+        args.get("actions").and_then(Value::as_array)
+    } else {
+        None
+    }
+}
+
+/// Actions `interact` supports
+const INTERACT_ACTIONS: &[&str] = &[
+    "click",
+    "double_click",
+    "dblclick",
+    "rightclick",
+    "hover",
+    "drag",
+    "type",
+    "clear",
+    "scroll",
+    "set_value",
+    "setValue",
+    "set_checked",
+    "setChecked",
+    "select",
+    "upload",
+    "press",
+    "keyboard_sequence",
+    "focus",
+    "blur",
+    "tap",
+    "longpress",
+    "swipe",
+    "pinch",
+    "sequence",
+];
+
+/// Longest `drag`, `swipe`, or `pinch` the `durationMs` argument allows
+const MAX_DRAG_DURATION_MS: u64 = 60_000;
+
+/// Most intermediate positions the `steps` argument allows for a `drag`, `swipe`, or `pinch`
+const MAX_DRAG_STEPS: u64 = 1000;
+
+/// Longest `longpress` the `holdMs` argument allows
+const MAX_HOLD_MS: u64 = 10_000;
+
+/// Scroll behaviors `scroll` accepts
+const SCROLL_BEHAVIORS: &[&str] = &["smooth", "instant", "auto"];
+
+/// Alignments `scroll` accepts for `block` and `inline`
+const SCROLL_ALIGNMENTS: &[&str] = &["start", "center", "end", "nearest"];
+
+/// Most actions a `sequence` can run
+const MAX_SEQUENCE_ACTIONS: usize = 50;
+
+/// Longest pause the `delayMs` argument allows before a `sequence` step
+const MAX_SEQUENCE_DELAY_MS: u64 = 5000;
+
+/// How long `interact` waits for its selector by default
+const DEFAULT_SELECTOR_WAIT_MS: u64 = 2000;
+
+/// Longest wait the `waitTimeoutMs` argument allows
+const MAX_SELECTOR_WAIT_MS: u64 = 30_000;
+
+/// Most times the `repeat` argument allows a `press`
+const MAX_PRESS_REPEAT: u64 = 100;
+
+/// Modifier names `press` accepts (case-insensitive)
+const PRESS_MODIFIERS: &[&str] = &["ctrl", "control", "shift", "alt", "option", "meta", "cmd", "command"];
+
+/// Actions `native` input can do
+const NATIVE_ACTIONS: &[&str] = &["click", "type", "scroll"];
+
+/// Spaces `x` and `y` can be given in: CSS pixels from the viewport's or the page's top left
+/// corner, or device pixels like a screenshot's
+const COORDINATE_SPACES: &[&str] = &["css-viewport", "css-page", "device"];
+
+/// Actions that move the pointer, so they need a selector or coordinates
+const POINTER_ACTIONS: &[&str] = &[
+    "click",
+    "double_click",
+    "dblclick",
+    "rightclick",
+    "hover",
+    "tap",
+    "longpress",
+];
+
+/// Check the optional `native` flag, which makes `interact` send real OS input
+pub(super) fn native_arg(args: &Value) -> Result<bool, String> {
+    match args.get("native") {
+        None | Some(Value::Null) => Ok(false),
+        Some(Value::Bool(native)) => Ok(*native),
+        Some(value) => Err(format!("'native' must be true or false, got: {value}")),
+    }
+}
+
+/// Perform UI interaction with DOM events. `upload_max_bytes` caps the total size of the files
+/// in an `upload`.
+pub async fn interact<R: Runtime>(
+    window: &WebviewWindow<R>,
+    args: &Value,
+    timeout: Duration,
+    upload_max_bytes: u64,
+) -> Result<Value, String> {
+    validate_interact_args(args)?;
+    let is_upload = |args: &Value| args.get("action").and_then(Value::as_str) == Some("upload");
+    if is_upload(args) {
+        validate_upload_args(args, upload_max_bytes)?;
+    }
+    // Each upload in a sequence gets the full limit, like a separate request would
+    if let Some(actions) = sequence_actions(args) {
+        for (i, step) in actions.iter().enumerate().filter(|(_, step)| is_upload(step)) {
+            validate_upload_args(step, upload_max_bytes).map_err(|e| format!("actions[{i}]: {e}"))?;
+        }
+    }
+
+    let mut result = eval_with_result(window, &interact_script(args)?, timeout).await?;
+    if let Some(result) = result.as_object_mut() {
+        result.insert("inputMode".to_string(), Value::from("synthetic"));
+    }
+    Ok(result)
+}
+
+/// Build the script that runs an `interact` action in the page
+pub(super) fn interact_script(args: &Value) -> Result<String, String> {
+    let a11y = include_str!("../scripts/a11y.js");
+    let selectors = include_str!("../scripts/selector.js");
+    let script = include_str!("../scripts/interact.js");
+    let args_json = serde_json::to_string(args).map_err(|e| e.to_string())?;
+
+    Ok(format!(
+        r"
+        {a11y}
+        {selectors}
+        {script}
+        return await window.__tauriMcpInteract({args_json})
+        "
+    ))
+}
+
+/// Check the action and the pointer target before running the script
+pub(super) fn validate_interact_args(args: &Value) -> Result<(), String> {
+    let action = args
+        .get("action")
+        .and_then(Value::as_str)
+        .ok_or("Missing required 'action' argument")?;
+    if !INTERACT_ACTIONS.contains(&action) {
+        return Err(format!(
+            "Unknown action: '{action}'. Available: {}",
+            INTERACT_ACTIONS.join(", ")
+        ));
+    }
+    if let Some(value) = args.get("waitForSelector").filter(|value| !value.is_null()) {
+        if !value.is_boolean() {
+            return Err(format!("'waitForSelector' must be true or false, got: {value}"));
+        }
+    }
+    pierce_shadow_arg(args)?;
+    if native_arg(args)? {
+        validate_native_args(action, args)?;
+    }
+    if let Some(value) = args.get("waitTimeoutMs").filter(|value| !value.is_null()) {
+        value.as_u64().filter(|ms| *ms <= MAX_SELECTOR_WAIT_MS).ok_or_else(|| {
+            format!("'waitTimeoutMs' must be an integer from 0 to {MAX_SELECTOR_WAIT_MS}, got: {value}")
+        })?;
+    }
+    match action {
+        "drag" | "swipe" => return validate_drag_args(action, args),
+        "pinch" => return validate_pinch_args(args),
+        "longpress" => validate_hold_arg(args)?,
+        "select" => return validate_select_args(args),
+        "press" => return validate_press_args(args),
+        "set_checked" | "setChecked" => return validate_set_checked_args(args),
+        "focus" => return validate_focus_args(args),
+        "clear" if !selector_arg(args, "selector")? => {
+            return Err("The 'clear' action needs a 'selector'".to_string());
+        }
+        "sequence" => return validate_sequence_args(args),
+        "scroll" => validate_scroll_args(args)?,
+        _ => {}
+    }
+
+    let coordinate = |name: &str| match args.get(name) {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_f64()
+            .map(Some)
+            .ok_or_else(|| format!("'{name}' must be a number, got: {value}")),
+    };
+    let has_coordinates = match (coordinate("x")?, coordinate("y")?) {
+        (Some(_), Some(_)) => true,
+        (None, None) => false,
+        _ => return Err("'x' and 'y' must be given together".to_string()),
+    };
+    validate_coordinate_space(args, has_coordinates)?;
+
+    let has_selector = selector_arg(args, "selector")?;
+    if POINTER_ACTIONS.contains(&action) && !has_selector && !has_coordinates {
+        return Err(format!(
+            "The '{action}' action needs a 'selector' or 'x' and 'y' coordinates"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check that a `native` action is one the OS can do: a click, typing, or a wheel scroll
+fn validate_native_args(action: &str, args: &Value) -> Result<(), String> {
+    if !NATIVE_ACTIONS.contains(&action) {
+        return Err(format!(
+            "'native' works with {}, got: '{action}'",
+            NATIVE_ACTIONS.join(", ")
+        ));
+    }
+    let given = |name: &str| args.get(name).is_some_and(|value| !value.is_null());
+    match action {
+        "type"
+            if !args
+                .get("text")
+                .and_then(Value::as_str)
+                .is_some_and(|text| !text.is_empty()) =>
+        {
+            Err("A native 'type' needs non-empty 'text'".to_string())
+        }
+        "type" if !selector_arg(args, "selector")? => Err("A native 'type' needs a 'selector' to focus".to_string()),
+        "scroll" if given("to") || given("intoView") => {
+            Err("A native 'scroll' turns the wheel, so it takes 'deltaX'/'deltaY', not 'to' or 'intoView'".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Check that `select` has a selector and exactly one of `value`, `label`, or `index`
+fn validate_select_args(args: &Value) -> Result<(), String> {
+    if !selector_arg(args, "selector")? {
+        return Err("The 'select' action needs a 'selector'".to_string());
+    }
+
+    let given = ["value", "label", "index"]
+        .into_iter()
+        .filter(|name| args.get(*name).is_some_and(|value| !value.is_null()))
+        .collect::<Vec<_>>();
+    match given.as_slice() {
+        [] => Err("The 'select' action needs 'value', 'label', or 'index'".to_string()),
+        ["index"] => {
+            let index = &args["index"];
+            let valid = match index {
+                Value::Array(items) => !items.is_empty() && items.iter().all(|item| item.as_u64().is_some()),
+                _ => index.as_u64().is_some(),
+            };
+            if valid {
+                Ok(())
+            } else {
+                Err(format!(
+                    "'index' must be a non-negative integer or an array of them, got: {index}"
+                ))
+            }
+        }
+        [name] => match &args[*name] {
+            Value::Array(items) if items.is_empty() => Err(format!("'{name}' must not be an empty array")),
+            Value::Object(_) | Value::Bool(_) => Err(format!(
+                "'{name}' must be a string or an array of strings, got: {}",
+                args[*name]
+            )),
+            _ => Ok(()),
+        },
+        _ => Err(format!(
+            "Give only one of 'value', 'label', or 'index', got: {}",
+            given.join(", ")
+        )),
+    }
+}
+
+/// Check that `scroll` has exactly one of deltas, `to`, or `intoView`, and valid options
+fn validate_scroll_args(args: &Value) -> Result<(), String> {
+    let given = |name: &str| args.get(name).is_some_and(|value| !value.is_null());
+
+    for name in ["deltaX", "deltaY", "scrollX", "scrollY"] {
+        if let Some(value) = args.get(name).filter(|value| !value.is_null()) {
+            if !value.is_number() {
+                return Err(format!("'{name}' must be a number, got: {value}"));
+            }
+        }
+    }
+    let by_delta = ["deltaX", "deltaY", "scrollX", "scrollY"].into_iter().any(given);
+    let modes = [by_delta, given("to"), given("intoView")];
+    if modes.into_iter().filter(|given| *given).count() != 1 {
+        return Err(
+            "The 'scroll' action needs one of 'deltaX'/'deltaY', 'to': { \"top\": ..., \"left\": ... }, or 'intoView'"
+                .to_string(),
+        );
+    }
+
+    if let Some(to) = args.get("to").filter(|value| !value.is_null()) {
+        let valid = to.as_object().is_some_and(|to| {
+            (to.contains_key("top") || to.contains_key("left"))
+                && to
+                    .iter()
+                    .all(|(key, value)| matches!(key.as_str(), "top" | "left") && value.is_number())
+        });
+        if !valid {
+            return Err(format!(
+                "'to' must be {{ \"top\": ..., \"left\": ... }} with numbers, got: {to}"
+            ));
+        }
+    }
+    if given("intoView") && !selector_arg(args, "intoView")? {
+        return Err(format!("'intoView' must be a selector, got: {}", args["intoView"]));
+    }
+
+    for (name, allowed) in [
+        ("behavior", SCROLL_BEHAVIORS),
+        ("block", SCROLL_ALIGNMENTS),
+        ("inline", SCROLL_ALIGNMENTS),
+    ] {
+        if let Some(value) = args.get(name).filter(|value| !value.is_null()) {
+            if !value.as_str().is_some_and(|value| allowed.contains(&value)) {
+                return Err(format!("Invalid '{name}': {value}. Available: {}", allowed.join(", ")));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Check each step of a `sequence`, which can't nest
+fn validate_sequence_args(args: &Value) -> Result<(), String> {
+    let actions = match args.get("actions") {
+        Some(Value::Array(actions)) if !actions.is_empty() => actions,
+        _ => {
+            return Err(
+                "The 'sequence' action needs 'actions', like [{ \"action\": \"click\", \"selector\": \"#name\" }]"
+                    .to_string(),
+            )
+        }
+    };
+    if actions.len() > MAX_SEQUENCE_ACTIONS {
+        return Err(format!(
+            "A sequence can run up to {MAX_SEQUENCE_ACTIONS} actions, got: {}",
+            actions.len()
+        ));
+    }
+
+    for (i, step) in actions.iter().enumerate() {
+        if !step.is_object() {
+            return Err(format!("'actions[{i}]' must be an object, got: {step}"));
+        }
+        if step.get("action").and_then(Value::as_str) == Some("sequence") {
+            return Err(format!("'actions[{i}]' is a sequence, and sequences can't be nested"));
+        }
+        if step.get("native").is_some_and(|value| !value.is_null()) {
+            return Err(format!(
+                "'actions[{i}].native' isn't supported, native input runs one action at a time"
+            ));
+        }
+        if let Some(value) = step.get("delayMs").filter(|value| !value.is_null()) {
+            value
+                .as_u64()
+                .filter(|delay| *delay <= MAX_SEQUENCE_DELAY_MS)
+                .ok_or_else(|| {
+                    format!("'actions[{i}].delayMs' must be an integer from 0 to {MAX_SEQUENCE_DELAY_MS}, got: {value}")
+                })?;
+        }
+        validate_interact_args(step).map_err(|e| format!("actions[{i}]: {e}"))?;
+    }
+
+    Ok(())
+}
+
+/// Check `coordinateSpace`, and that an `origin` element has `x` and `y` offsets to go with it
+fn validate_coordinate_space(args: &Value, has_coordinates: bool) -> Result<(), String> {
+    let space = match args.get("coordinateSpace") {
+        None | Some(Value::Null) => None,
+        Some(value) => Some(
+            value
+                .as_str()
+                .filter(|space| COORDINATE_SPACES.contains(space))
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid 'coordinateSpace': {value}. Use one of: {}",
+                        COORDINATE_SPACES.join(", ")
+                    )
+                })?,
+        ),
+    };
+    if !selector_arg(args, "origin")? {
+        return Ok(());
+    }
+    if !has_coordinates {
+        return Err("'origin' needs 'x' and 'y' offsets from the element's top left corner".to_string());
+    }
+    if space == Some("css-page") {
+        return Err(
+            "'origin' offsets are relative to the element, so use 'css-viewport' or 'device' as 'coordinateSpace'"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Check that `focus` has a selector and a boolean `preventScroll`
+fn validate_focus_args(args: &Value) -> Result<(), String> {
+    if !selector_arg(args, "selector")? {
+        return Err("The 'focus' action needs a 'selector'".to_string());
+    }
+    match args.get("preventScroll") {
+        None | Some(Value::Null | Value::Bool(_)) => Ok(()),
+        Some(value) => Err(format!("'preventScroll' must be true or false, got: {value}")),
+    }
+}
+
+/// Check that `set_checked` has a selector and a boolean target state
+fn validate_set_checked_args(args: &Value) -> Result<(), String> {
+    if !selector_arg(args, "selector")? {
+        return Err("The 'set_checked' action needs a 'selector'".to_string());
+    }
+    match args.get("checked") {
+        Some(Value::Bool(_)) => Ok(()),
+        None | Some(Value::Null) => Err("The 'set_checked' action needs 'checked': true or false".to_string()),
+        Some(value) => Err(format!("'checked' must be true or false, got: {value}")),
+    }
+}
+
+/// Check the `press` key, modifiers, and repeat count
+fn validate_press_args(args: &Value) -> Result<(), String> {
+    if !args
+        .get("key")
+        .and_then(Value::as_str)
+        .is_some_and(|key| !key.is_empty())
+    {
+        return Err("The 'press' action needs a 'key', like 'Escape', 'ArrowDown', or 's'".to_string());
+    }
+
+    match args.get("modifiers") {
+        None | Some(Value::Null) => {}
+        Some(Value::Array(modifiers)) => {
+            for modifier in modifiers {
+                let known = modifier
+                    .as_str()
+                    .is_some_and(|name| PRESS_MODIFIERS.contains(&name.to_lowercase().as_str()));
+                if !known {
+                    return Err(format!(
+                        "Unknown modifier: {modifier}. Available: {}",
+                        PRESS_MODIFIERS.join(", ")
+                    ));
+                }
+            }
+        }
+        Some(value) => return Err(format!("'modifiers' must be an array of strings, got: {value}")),
+    }
+
+    if let Some(value) = args.get("repeat").filter(|value| !value.is_null()) {
+        value
+            .as_u64()
+            .filter(|repeat| (1..=MAX_PRESS_REPEAT).contains(repeat))
+            .ok_or_else(|| format!("'repeat' must be an integer from 1 to {MAX_PRESS_REPEAT}, got: {value}"))?;
+    }
+
+    Ok(())
+}
+
+/// Check that `upload` has a selector and well-formed files within the size limit
+fn validate_upload_args(args: &Value, max_bytes: u64) -> Result<(), String> {
+    if !selector_arg(args, "selector")? {
+        return Err("The 'upload' action needs a 'selector'".to_string());
+    }
+
+    let files =
+        match args.get("files") {
+            Some(Value::Array(files)) if !files.is_empty() => files,
+            _ => return Err(
+                "The 'upload' action needs 'files': [{ \"name\": ..., \"mimeType\": ..., \"contentsBase64\": ... }]"
+                    .to_string(),
+            ),
+        };
+
+    let mut total_bytes: u64 = 0;
+    for (i, file) in files.iter().enumerate() {
+        let name = file
+            .get("name")
+            .and_then(Value::as_str)
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| format!("'files[{i}].name' must be a non-empty string"))?;
+        if file
+            .get("mimeType")
+            .is_some_and(|value| !value.is_null() && !value.is_string())
+        {
+            return Err(format!("'files[{i}].mimeType' must be a string"));
+        }
+        let contents = file
+            .get("contentsBase64")
+            .and_then(Value::as_str)
+            .ok_or_else(|| format!("'files[{i}].contentsBase64' must be a base64 string"))?;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(contents)
+            .map_err(|e| format!("'files[{i}].contentsBase64' of '{name}' is not valid base64: {e}"))?;
+        total_bytes = total_bytes.saturating_add(bytes.len() as u64);
+    }
+
+    if total_bytes > max_bytes {
+        return Err(format!(
+            "Upload of {total_bytes} bytes exceeds the {max_bytes}-byte limit (see Builder::upload_max_bytes)"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Check the `drag` or `swipe` endpoints and timing arguments
+fn validate_drag_args(action: &str, args: &Value) -> Result<(), String> {
+    for name in ["from", "to"] {
+        if !point_arg(args, name)? {
+            return Err(format!(
+                "The '{action}' action needs '{name}': a selector or {{ \"x\": ..., \"y\": ... }}"
+            ));
+        }
+    }
+    validate_motion_args(args)
+}
+
+/// Check the `pinch` center, finger distances, and timing arguments
+fn validate_pinch_args(args: &Value) -> Result<(), String> {
+    let has_center = point_arg(args, "center")?
+        || selector_arg(args, "selector")?
+        || (args["x"].is_number() && args["y"].is_number());
+    if !has_center {
+        return Err(
+            "The 'pinch' action needs a 'center' (a selector or { \"x\": ..., \"y\": ... }), a 'selector', or 'x' and 'y'"
+                .to_string(),
+        );
+    }
+    for name in ["startDistance", "endDistance"] {
+        match args.get(name) {
+            Some(value) if value.as_f64().is_some_and(|distance| distance > 0.0) => {}
+            None | Some(Value::Null) => {
+                return Err(format!(
+                    "The 'pinch' action needs '{name}': the distance between the fingers in CSS pixels"
+                ))
+            }
+            Some(value) => return Err(format!("'{name}' must be a number greater than 0, got: {value}")),
+        }
+    }
+    validate_motion_args(args)
+}
+
+/// Check a point argument, a selector or `{ x, y }`, returning whether it's there
+fn point_arg(args: &Value, name: &str) -> Result<bool, String> {
+    match args.get(name) {
+        None | Some(Value::Null) => Ok(false),
+        Some(point @ Value::Object(_)) if point["x"].is_number() && point["y"].is_number() => Ok(true),
+        Some(Value::String(_)) if selector_arg(args, name)? => Ok(true),
+        Some(Value::Object(selector)) if SELECTOR_ENGINES.iter().any(|engine| selector.contains_key(*engine)) => {
+            selector_arg(args, name)
+        }
+        Some(value) => Err(format!(
+            "'{name}' must be a selector or {{ \"x\": ..., \"y\": ... }}, got: {value}"
+        )),
+    }
+}
+
+/// Check the `steps` and `durationMs` of a gesture that moves
+fn validate_motion_args(args: &Value) -> Result<(), String> {
+    if let Some(value) = args.get("steps").filter(|value| !value.is_null()) {
+        value
+            .as_u64()
+            .filter(|steps| (1..=MAX_DRAG_STEPS).contains(steps))
+            .ok_or_else(|| format!("'steps' must be an integer from 1 to {MAX_DRAG_STEPS}, got: {value}"))?;
+    }
+    if let Some(value) = args.get("durationMs").filter(|value| !value.is_null()) {
+        value
+            .as_u64()
+            .filter(|ms| *ms <= MAX_DRAG_DURATION_MS)
+            .ok_or_else(|| format!("'durationMs' must be an integer from 0 to {MAX_DRAG_DURATION_MS}, got: {value}"))?;
+    }
+
+    Ok(())
+}
+
+/// Check how long a `longpress` holds
+fn validate_hold_arg(args: &Value) -> Result<(), String> {
+    if let Some(value) = args.get("holdMs").filter(|value| !value.is_null()) {
+        value
+            .as_u64()
+            .filter(|ms| *ms <= MAX_HOLD_MS)
+            .ok_or_else(|| format!("'holdMs' must be an integer from 0 to {MAX_HOLD_MS}, got: {value}"))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::commands::execute_js::script_timeout_for;
+    use serde_json::json;
+
+    #[test]
+    fn interact_accepts_selector_or_coordinates() {
+        assert!(validate_interact_args(&json!({ "action": "hover", "selector": "#toolbar" })).is_ok());
+        assert!(validate_interact_args(&json!({ "action": "rightclick", "x": 10, "y": 20.5 })).is_ok());
+        assert!(validate_interact_args(&json!({ "action": "dblclick", "selector": "li", "x": 1, "y": 2 })).is_ok());
+    }
+
+    #[test]
+    fn interact_rejects_unknown_action() {
+        let err = validate_interact_args(&json!({ "action": "shake", "selector": "body" })).unwrap_err();
+        assert!(err.contains("Unknown action: 'shake'"));
+        assert!(err.contains("hover"));
+
+        let err = validate_interact_args(&json!({ "selector": "body" })).unwrap_err();
+        assert!(err.contains("Missing required 'action'"));
+    }
+
+    #[test]
+    fn interact_pointer_actions_need_a_target() {
+        let err = validate_interact_args(&json!({ "action": "hover" })).unwrap_err();
+        assert!(err.contains("needs a 'selector' or 'x' and 'y'"));
+
+        let err = validate_interact_args(&json!({ "action": "rightclick", "selector": "  " })).unwrap_err();
+        assert!(err.contains("needs a 'selector'"));
+
+        // Keyboard input goes to the focused element, so it needs no target
+        assert!(validate_interact_args(&json!({ "action": "keyboard_sequence", "keys": ["a"] })).is_ok());
+    }
+
+    #[test]
+    fn interact_rejects_partial_or_invalid_coordinates() {
+        let err = validate_interact_args(&json!({ "action": "hover", "x": 10 })).unwrap_err();
+        assert!(err.contains("'x' and 'y' must be given together"));
+
+        let err = validate_interact_args(&json!({ "action": "dblclick", "x": "10", "y": 20 })).unwrap_err();
+        assert!(err.contains("'x' must be a number"));
+    }
+
+    #[test]
+    fn interact_drag_accepts_selectors_and_points() {
+        let args = json!({ "action": "drag", "from": "#handle", "to": { "x": 200, "y": 40.5 }, "steps": 5 });
+        assert!(validate_interact_args(&args).is_ok());
+    }
+
+    #[test]
+    fn interact_drag_rejects_invalid_endpoints() {
+        let err = validate_interact_args(&json!({ "action": "drag", "from": "#handle" })).unwrap_err();
+        assert!(err.contains("needs 'to'"));
+
+        let err = validate_interact_args(&json!({ "action": "drag", "from": { "x": 1 }, "to": "#slot" })).unwrap_err();
+        assert!(err.contains("'from' must be a selector or"));
+    }
+
+    #[test]
+    fn interact_drag_limits_steps_and_duration() {
+        let err = validate_interact_args(&json!({ "action": "drag", "from": "a", "to": "b", "steps": 0 })).unwrap_err();
+        assert!(err.contains("'steps' must be an integer from 1"));
+
+        let err = validate_interact_args(&json!({ "action": "drag", "from": "a", "to": "b", "durationMs": 120_000 }))
+            .unwrap_err();
+        assert!(err.contains("'durationMs' must be an integer from 0"));
+    }
+
+    #[test]
+    fn interact_checks_touch_gestures() {
+        assert!(validate_interact_args(&json!({ "action": "tap", "selector": "#item" })).is_ok());
+        assert!(validate_interact_args(&json!({ "action": "longpress", "x": 10, "y": 20, "holdMs": 1500 })).is_ok());
+        let swipe = json!({ "action": "swipe", "from": "#card", "to": { "x": 0, "y": 300 }, "steps": 20 });
+        assert!(validate_interact_args(&swipe).is_ok());
+        let pinch = json!({ "action": "pinch", "center": "#map", "startDistance": 200, "endDistance": 50.5 });
+        assert!(validate_interact_args(&pinch).is_ok());
+        let pinch = json!({ "action": "pinch", "selector": "#map", "startDistance": 50, "endDistance": 200 });
+        assert!(validate_interact_args(&pinch).is_ok());
+
+        let err = validate_interact_args(&json!({ "action": "tap" })).unwrap_err();
+        assert!(err.contains("The 'tap' action needs a 'selector' or 'x' and 'y'"));
+        let err =
+            validate_interact_args(&json!({ "action": "longpress", "selector": "#a", "holdMs": 60_000 })).unwrap_err();
+        assert!(err.contains("'holdMs' must be an integer from 0 to 10000"));
+        let err = validate_interact_args(&json!({ "action": "swipe", "from": "#card" })).unwrap_err();
+        assert!(err.contains("The 'swipe' action needs 'to'"));
+        let err =
+            validate_interact_args(&json!({ "action": "pinch", "startDistance": 10, "endDistance": 20 })).unwrap_err();
+        assert!(err.contains("The 'pinch' action needs a 'center'"));
+        let err =
+            validate_interact_args(&json!({ "action": "pinch", "center": "#map", "startDistance": 10 })).unwrap_err();
+        assert!(err.contains("needs 'endDistance'"));
+        let err = validate_interact_args(
+            &json!({ "action": "pinch", "center": "#map", "startDistance": 0, "endDistance": 5 }),
+        )
+        .unwrap_err();
+        assert!(err.contains("'startDistance' must be a number greater than 0"));
+    }
+
+    #[test]
+    fn script_timeout_extends_interact_by_hold() {
+        let timeout = script_timeout_for(
+            "interact",
+            &json!({"action": "longpress", "holdMs": 2000}),
+            Duration::from_secs(5),
+        );
+        assert_eq!(timeout, Duration::from_secs(7));
+    }
+
+    #[test]
+    fn interact_select_needs_one_option_matcher() {
+        assert!(validate_interact_args(&json!({ "action": "select", "selector": "#size", "value": "m" })).is_ok());
+        assert!(
+            validate_interact_args(&json!({ "action": "select", "selector": "#tags", "label": ["A", "B"] })).is_ok()
+        );
+        assert!(validate_interact_args(&json!({ "action": "select", "selector": "#size", "index": 0 })).is_ok());
+
+        let err = validate_interact_args(&json!({ "action": "select", "selector": "#size" })).unwrap_err();
+        assert!(err.contains("needs 'value', 'label', or 'index'"));
+
+        let err = validate_interact_args(&json!({ "action": "select", "selector": "#size", "value": "m", "index": 1 }))
+            .unwrap_err();
+        assert!(err.contains("Give only one of"));
+
+        let err = validate_interact_args(&json!({ "action": "select", "value": "m" })).unwrap_err();
+        assert!(err.contains("needs a 'selector'"));
+    }
+
+    #[test]
+    fn interact_select_rejects_invalid_matchers() {
+        let err = validate_interact_args(&json!({ "action": "select", "selector": "#size", "index": -1 })).unwrap_err();
+        assert!(err.contains("'index' must be a non-negative integer"));
+
+        let err = validate_interact_args(&json!({ "action": "select", "selector": "#tags", "value": [] })).unwrap_err();
+        assert!(err.contains("must not be an empty array"));
+    }
+
+    #[test]
+    fn interact_scroll_accepts_one_mode() {
+        assert!(validate_interact_args(&json!({ "action": "scroll", "scrollY": 100 })).is_ok());
+        assert!(validate_interact_args(&json!({ "action": "scroll", "selector": "#list", "deltaY": 300 })).is_ok());
+        assert!(
+            validate_interact_args(&json!({ "action": "scroll", "selector": "#list", "to": { "top": 0 } })).is_ok()
+        );
+        assert!(validate_interact_args(&json!({
+            "action": "scroll",
+            "selector": "#list",
+            "intoView": "#row-40",
+            "block": "center",
+            "behavior": "instant"
+        }))
+        .is_ok());
+    }
+
+    #[test]
+    fn interact_scroll_rejects_missing_or_mixed_modes() {
+        let err = validate_interact_args(&json!({ "action": "scroll", "selector": "#list" })).unwrap_err();
+        assert!(err.contains("needs one of 'deltaX'/'deltaY'"));
+
+        let err = validate_interact_args(&json!({ "action": "scroll", "deltaY": 10, "intoView": "#row" })).unwrap_err();
+        assert!(err.contains("needs one of"));
+    }
+
+    #[test]
+    fn interact_scroll_rejects_invalid_options() {
+        let err = validate_interact_args(&json!({ "action": "scroll", "to": { "bottom": 0 } })).unwrap_err();
+        assert!(err.contains("'to' must be"));
+
+        let err = validate_interact_args(&json!({ "action": "scroll", "deltaY": "down" })).unwrap_err();
+        assert!(err.contains("'deltaY' must be a number"));
+
+        let err =
+            validate_interact_args(&json!({ "action": "scroll", "intoView": "#row", "block": "top" })).unwrap_err();
+        assert!(err.contains("Invalid 'block': \"top\". Available: start, center, end, nearest"));
+    }
+
+    #[test]
+    fn interact_press_accepts_key_with_modifiers() {
+        assert!(validate_interact_args(&json!({ "action": "press", "key": "Escape" })).is_ok());
+        assert!(validate_interact_args(&json!({
+            "action": "press",
+            "key": "p",
+            "modifiers": ["Ctrl", "Shift"],
+            "selector": "#editor",
+            "repeat": 3
+        }))
+        .is_ok());
+    }
+
+    #[test]
+    fn interact_press_rejects_invalid_args() {
+        let err = validate_interact_args(&json!({ "action": "press" })).unwrap_err();
+        assert!(err.contains("needs a 'key'"));
+
+        let err =
+            validate_interact_args(&json!({ "action": "press", "key": "s", "modifiers": ["Hyper"] })).unwrap_err();
+        assert!(err.contains("Unknown modifier: \"Hyper\""));
+
+        let err = validate_interact_args(&json!({ "action": "press", "key": "s", "modifiers": "Meta" })).unwrap_err();
+        assert!(err.contains("'modifiers' must be an array"));
+
+        let err = validate_interact_args(&json!({ "action": "press", "key": "Tab", "repeat": 0 })).unwrap_err();
+        assert!(err.contains("'repeat' must be an integer from 1 to 100"));
+    }
+
+    #[test]
+    fn interact_set_checked_needs_selector_and_boolean() {
+        assert!(
+            validate_interact_args(&json!({ "action": "setChecked", "selector": "#agree", "checked": true })).is_ok()
+        );
+
+        let err = validate_interact_args(&json!({ "action": "set_checked", "checked": false })).unwrap_err();
+        assert!(err.contains("needs a 'selector'"));
+
+        let err = validate_interact_args(&json!({ "action": "set_checked", "selector": "#agree" })).unwrap_err();
+        assert!(err.contains("needs 'checked'"));
+
+        let err = validate_interact_args(&json!({ "action": "set_checked", "selector": "#agree", "checked": "yes" }))
+            .unwrap_err();
+        assert!(err.contains("'checked' must be true or false"));
+    }
+
+    #[test]
+    fn interact_upload_accepts_files_within_limit() {
+        let args = json!({
+            "action": "upload",
+            "selector": "#attachment",
+            "files": [
+                { "name": "notes.txt", "mimeType": "text/plain", "contentsBase64": "aGVsbG8=" },
+                { "name": "empty.bin", "contentsBase64": "" }
+            ]
+        });
+        assert!(validate_interact_args(&args).is_ok());
+        assert!(validate_upload_args(&args, 5).is_ok());
+    }
+
+    #[test]
+    fn interact_upload_rejects_files_over_limit() {
+        let args = json!({
+            "action": "upload",
+            "selector": "#attachment",
+            "files": [{ "name": "notes.txt", "contentsBase64": "aGVsbG8=" }]
+        });
+        let err = validate_upload_args(&args, 4).unwrap_err();
+        assert!(err.contains("Upload of 5 bytes exceeds the 4-byte limit"));
+    }
+
+    #[test]
+    fn interact_upload_rejects_malformed_files() {
+        let err = validate_upload_args(&json!({ "action": "upload", "files": [] }), 10).unwrap_err();
+        assert!(err.contains("needs a 'selector'"));
+
+        let err = validate_upload_args(&json!({ "selector": "#a", "files": [] }), 10).unwrap_err();
+        assert!(err.contains("needs 'files'"));
+
+        let err =
+            validate_upload_args(&json!({ "selector": "#a", "files": [{ "contentsBase64": "" }] }), 10).unwrap_err();
+        assert!(err.contains("'files[0].name' must be a non-empty string"));
+
+        let err = validate_upload_args(
+            &json!({ "selector": "#a", "files": [{ "name": "a.txt", "contentsBase64": "not base64!" }] }),
+            10,
+        )
+        .unwrap_err();
+        assert!(err.contains("'files[0].contentsBase64' of 'a.txt' is not valid base64"));
+    }
+
+    #[test]
+    fn script_timeout_extends_interact_by_drag_duration() {
+        let timeout = script_timeout_for("interact", &json!({"durationMs": 3000}), Duration::from_secs(5));
+        assert_eq!(timeout, Duration::from_secs(8));
+    }
+
+    #[test]
+    fn script_timeout_extends_interact_by_selector_wait() {
+        let default = Duration::from_secs(5);
+        let timeout = |args: Value| script_timeout_for("interact", &args, default);
+
+        assert_eq!(
+            timeout(json!({ "action": "click", "selector": "#a" })),
+            Duration::from_secs(9)
+        );
+        assert_eq!(
+            timeout(json!({ "action": "click", "selector": "#a", "waitTimeoutMs": 500 })),
+            Duration::from_secs(6)
+        );
+        assert_eq!(
+            timeout(json!({ "action": "click", "selector": "#a", "waitForSelector": false })),
+            default
+        );
+        assert_eq!(timeout(json!({ "action": "click", "x": 1, "y": 2 })), default);
+    }
+
+    #[test]
+    fn interact_rejects_invalid_wait_args() {
+        assert!(validate_interact_args(&json!({
+            "action": "click",
+            "selector": "#a",
+            "waitForSelector": true,
+            "waitTimeoutMs": 5000
+        }))
+        .is_ok());
+
+        let err = validate_interact_args(&json!({ "action": "click", "selector": "#a", "waitForSelector": "yes" }))
+            .unwrap_err();
+        assert!(err.contains("'waitForSelector' must be true or false"));
+
+        let err =
+            validate_interact_args(&json!({ "action": "press", "key": "a", "waitTimeoutMs": 60_000 })).unwrap_err();
+        assert!(err.contains("'waitTimeoutMs' must be an integer from 0 to 30000"));
+    }
+
+    #[test]
+    fn script_timeout_extends_sequence_by_step_delays_and_drags() {
+        let args = json!({
+            "action": "sequence",
+            "actions": [
+                { "action": "click", "selector": "#a", "delayMs": 500, "waitTimeoutMs": 1000 },
+                { "action": "drag", "from": "#a", "to": "#b", "durationMs": 1500 }
+            ]
+        });
+        let timeout = script_timeout_for("interact", &args, Duration::from_secs(5));
+        assert_eq!(timeout, Duration::from_secs(9));
+    }
+
+    #[test]
+    fn interact_sequence_validates_each_step() {
+        assert!(validate_interact_args(&json!({
+            "action": "sequence",
+            "actions": [
+                { "action": "click", "selector": "#name" },
+                { "action": "set_value", "selector": "#name", "value": "" },
+                { "action": "type", "selector": "#name", "text": "Ada" },
+                { "action": "press", "key": "Enter", "delayMs": 100 }
+            ]
+        }))
+        .is_ok());
+
+        let err = validate_interact_args(&json!({ "action": "sequence" })).unwrap_err();
+        assert!(err.contains("needs 'actions'"));
+
+        let err = validate_interact_args(&json!({
+            "action": "sequence",
+            "actions": [{ "action": "click", "selector": "#a" }, { "action": "press" }]
+        }))
+        .unwrap_err();
+        assert!(err.starts_with("actions[1]: The 'press' action needs a 'key'"));
+
+        let err = validate_interact_args(&json!({
+            "action": "sequence",
+            "actions": [{ "action": "sequence", "actions": [] }]
+        }))
+        .unwrap_err();
+        assert!(err.contains("can't be nested"));
+
+        let err = validate_interact_args(&json!({
+            "action": "sequence",
+            "actions": [{ "action": "click", "selector": "#a", "delayMs": 60_000 }]
+        }))
+        .unwrap_err();
+        assert!(err.contains("'actions[0].delayMs' must be an integer from 0 to 5000"));
+
+        let actions = vec![json!({ "action": "click", "selector": "#a" }); 51];
+        let err = validate_interact_args(&json!({ "action": "sequence", "actions": actions })).unwrap_err();
+        assert!(err.contains("up to 50 actions"));
+    }
+
+    #[test]
+    fn interact_accepts_selector_objects() {
+        for selector in [
+            json!({ "css": "#save" }),
+            json!({ "xpath": "//button[2]" }),
+            json!({ "text": "Save changes", "exact": false }),
+            json!({ "role": "button", "name": "Save", "exact": true }),
+            json!({ "ref": "e12" }),
+        ] {
+            assert!(validate_interact_args(&json!({ "action": "click", "selector": selector })).is_ok());
+        }
+        let args = json!({ "action": "drag", "from": { "text": "Card" }, "to": { "role": "list", "name": "Done" } });
+        assert!(validate_interact_args(&args).is_ok());
+        let args = json!({ "action": "scroll", "selector": "#list", "intoView": { "text": "Row 40" } });
+        assert!(validate_interact_args(&args).is_ok());
+        assert_eq!(selector_wait_ms(&json!({ "selector": { "role": "button" } })), 2000);
+    }
+
+    #[test]
+    fn interact_rejects_invalid_selector_objects() {
+        let err = validate_interact_args(&json!({ "action": "click", "selector": {} })).unwrap_err();
+        assert!(err.contains("'selector' must have exactly one of css, xpath, text, role"));
+
+        let err =
+            validate_interact_args(&json!({ "action": "click", "selector": { "css": "a", "text": "b" } })).unwrap_err();
+        assert!(err.contains("exactly one of"));
+
+        let err = validate_interact_args(&json!({ "action": "click", "selector": { "text": " " } })).unwrap_err();
+        assert!(err.contains("'selector.text' must be a non-empty string"));
+
+        let err = validate_interact_args(&json!({ "action": "click", "selector": { "css": "a", "exact": true } }))
+            .unwrap_err();
+        assert!(err.contains("Unexpected 'selector.exact' for a 'css' selector"));
+
+        let err = validate_interact_args(&json!({ "action": "click", "selector": 42 })).unwrap_err();
+        assert!(err.contains("'selector' must be a CSS selector or a selector object"));
+
+        let err = validate_interact_args(&json!({ "action": "drag", "from": { "role": "" }, "to": "#b" })).unwrap_err();
+        assert!(err.contains("'from.role' must be a non-empty string"));
+    }
+
+    #[test]
+    fn interact_checks_pierce_shadow() {
+        assert!(validate_interact_args(&json!({
+            "action": "click",
+            "selector": "mcp-settings-panel >>> button",
+            "pierceShadow": true
+        }))
+        .is_ok());
+
+        let err =
+            validate_interact_args(&json!({ "action": "click", "selector": "#a", "pierceShadow": "yes" })).unwrap_err();
+        assert!(err.contains("'pierceShadow' must be true or false"));
+
+        let err = validate_interact_args(&json!({
+            "action": "sequence",
+            "actions": [{ "action": "click", "selector": "#a", "pierceShadow": 1 }]
+        }))
+        .unwrap_err();
+        assert!(err.contains("'pierceShadow' must be true or false"));
+    }
+
+    #[test]
+    fn interact_limits_native_to_click_type_and_scroll() {
+        for args in [
+            json!({ "action": "click", "selector": "#save", "native": true }),
+            json!({ "action": "type", "selector": "#name", "text": "Ada", "native": true }),
+            json!({ "action": "scroll", "deltaY": 200, "native": true }),
+            json!({ "action": "hover", "selector": "#save", "native": false }),
+        ] {
+            assert!(validate_interact_args(&args).is_ok(), "{args}");
+        }
+
+        let err = validate_interact_args(&json!({ "action": "hover", "selector": "#a", "native": true })).unwrap_err();
+        assert!(err.contains("'native' works with click, type, scroll"));
+        let err = validate_interact_args(&json!({ "action": "type", "selector": "#a", "text": "", "native": true }))
+            .unwrap_err();
+        assert!(err.contains("needs non-empty 'text'"));
+        let err =
+            validate_interact_args(&json!({ "action": "scroll", "to": { "top": 0 }, "native": true })).unwrap_err();
+        assert!(err.contains("not 'to' or 'intoView'"));
+        let err = validate_interact_args(&json!({
+            "action": "sequence",
+            "actions": [{ "action": "click", "selector": "#a", "native": true }]
+        }))
+        .unwrap_err();
+        assert!(err.contains("'actions[0].native' isn't supported"));
+    }
+
+    #[test]
+    fn interact_checks_focus_args() {
+        assert!(
+            validate_interact_args(&json!({ "action": "focus", "selector": "#email", "preventScroll": true })).is_ok()
+        );
+        assert!(validate_interact_args(&json!({ "action": "blur" })).is_ok());
+        assert!(validate_interact_args(&json!({ "action": "blur", "selector": "#email" })).is_ok());
+
+        let err = validate_interact_args(&json!({ "action": "focus" })).unwrap_err();
+        assert!(err.contains("The 'focus' action needs a 'selector'"));
+        let err = validate_interact_args(&json!({ "action": "focus", "selector": "#a", "preventScroll": "yes" }))
+            .unwrap_err();
+        assert!(err.contains("'preventScroll' must be true or false"));
+    }
+
+    #[test]
+    fn interact_checks_coordinate_space() {
+        for space in ["css-viewport", "css-page", "device"] {
+            let args = json!({ "action": "click", "x": 200, "y": 100, "coordinateSpace": space });
+            assert!(validate_interact_args(&args).is_ok(), "{space}");
+        }
+        assert!(validate_interact_args(&json!({ "action": "click", "x": 1, "y": 2, "coordinateSpace": null })).is_ok());
+
+        let err = validate_interact_args(&json!({ "action": "click", "x": 1, "y": 2, "coordinateSpace": "pixels" }))
+            .unwrap_err();
+        assert!(err.contains("Invalid 'coordinateSpace': \"pixels\". Use one of: css-viewport, css-page, device"));
+        let err =
+            validate_interact_args(&json!({ "action": "click", "x": 1, "y": 2, "coordinateSpace": 2 })).unwrap_err();
+        assert!(err.contains("Invalid 'coordinateSpace'"));
+    }
+
+    #[test]
+    fn interact_checks_origin() {
+        let args = json!({ "action": "click", "origin": "#canvas", "x": 10, "y": 20, "coordinateSpace": "device" });
+        assert!(validate_interact_args(&args).is_ok());
+        let args = json!({ "action": "hover", "origin": { "role": "slider" }, "x": 0, "y": 5 });
+        assert!(validate_interact_args(&args).is_ok());
+
+        let err = validate_interact_args(&json!({ "action": "click", "origin": "#canvas" })).unwrap_err();
+        assert!(err.contains("'origin' needs 'x' and 'y' offsets"));
+        let args = json!({ "action": "click", "origin": "#canvas", "x": 1, "y": 2, "coordinateSpace": "css-page" });
+        assert!(validate_interact_args(&args)
+            .unwrap_err()
+            .contains("use 'css-viewport' or 'device'"));
+        let err = validate_interact_args(&json!({ "action": "click", "origin": 5, "x": 1, "y": 2 })).unwrap_err();
+        assert!(err.contains("'origin' must be a CSS selector"));
+    }
+
+    #[test]
+    fn interact_clear_needs_a_selector() {
+        assert!(validate_interact_args(&json!({ "action": "clear", "selector": "#search" })).is_ok());
+        let err = validate_interact_args(&json!({ "action": "clear", "x": 10, "y": 20 })).unwrap_err();
+        assert!(err.contains("The 'clear' action needs a 'selector'"));
+
+        let sequence = json!({ "action": "sequence", "actions": [
+            { "action": "clear", "selector": "#search" },
+            { "action": "type", "selector": "#search", "text": "invoices" },
+        ] });
+        assert!(validate_interact_args(&sequence).is_ok());
+    }
+}
@@ -0,0 +1,205 @@
+//! App menu commands.
+//!
+//! Both commands go through the handler the app registered with [`crate::on_menu_event`].
+//! `menu_click` finds the item in the app or window menus first and skips disabled ones, like
+//! a real click would. `menu_emit` sends the event for any ID, for items that aren't in a
+//! menu bar (like tray menus).
+
+use serde_json::{json, Value};
+use tauri::menu::{Menu, MenuItemKind};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::menu;
+
+/// A menu item with the submenus it's nested in
+struct Entry<R: Runtime> {
+    /// Enclosing submenus, outermost first
+    ancestors: Vec<Ancestor>,
+    item: MenuItemKind<R>,
+    text: String,
+}
+
+/// A submenu on the path to an item
+#[derive(Clone)]
+struct Ancestor {
+    id: String,
+    text: String,
+    enabled: bool,
+}
+
+/// Click a menu item, found by ID or label, and optionally within a submenu
+pub fn click<R: Runtime>(app: &AppHandle<R>, args: &Value) -> Result<Value, String> {
+    let item_query = item_id_arg(args)?;
+    let menu_query = args.get("menu_id").and_then(Value::as_str);
+
+    let mut entries = Vec::new();
+    for menu in app_menus(app) {
+        collect_entries(menu.items().map_err(|e| e.to_string())?, &[], &mut entries)?;
+    }
+
+    let entry = entries
+        .into_iter()
+        .find(|entry| {
+            matches_label(entry.item.id().as_ref(), &entry.text, item_query)
+                && menu_query.map_or(true, |query| {
+                    entry
+                        .ancestors
+                        .iter()
+                        .any(|ancestor| matches_label(&ancestor.id, &ancestor.text, query))
+                })
+        })
+        .ok_or_else(|| {
+            menu_query.map_or_else(
+                || format!("Menu item '{item_query}' not found"),
+                |query| format!("Menu item '{item_query}' not found in menu '{query}'"),
+            )
+        })?;
+
+    let item_id = entry.item.id().as_ref().to_string();
+    let menu_id = entry.ancestors.last().map(|ancestor| ancestor.id.clone());
+
+    let enabled = match &entry.item {
+        MenuItemKind::MenuItem(item) => item.is_enabled(),
+        MenuItemKind::Check(item) => item.is_enabled(),
+        MenuItemKind::Icon(item) => item.is_enabled(),
+        MenuItemKind::Submenu(_) => return Err(format!("'{item_id}' is a submenu, not a menu item")),
+        MenuItemKind::Predefined(_) => {
+            return Err(format!(
+                "'{item_id}' is a predefined item (like Copy or Quit), which the OS handles without a menu event"
+            ))
+        }
+    }
+    .map_err(|e| e.to_string())?
+        && entry.ancestors.iter().all(|ancestor| ancestor.enabled);
+
+    if !enabled {
+        return Ok(json!({ "clicked": false, "menu_id": menu_id, "item_id": item_id, "reason": "disabled" }));
+    }
+
+    // A real click toggles check items before the event fires
+    if let MenuItemKind::Check(item) = &entry.item {
+        let checked = item.is_checked().map_err(|e| e.to_string())?;
+        item.set_checked(!checked).map_err(|e| e.to_string())?;
+    }
+
+    menu::dispatch(app, &item_id)?;
+
+    Ok(json!({ "clicked": true, "menu_id": menu_id, "item_id": item_id }))
+}
+
+/// Send a menu event for an item ID without looking it up
+pub fn emit<R: Runtime>(app: &AppHandle<R>, args: &Value) -> Result<Value, String> {
+    let item_id = item_id_arg(args)?;
+    let menu_id = args.get("menu_id").and_then(Value::as_str);
+
+    menu::dispatch(app, item_id)?;
+
+    Ok(json!({ "clicked": true, "menu_id": menu_id, "item_id": item_id }))
+}
+
+/// Get the required `item_id` argument
+fn item_id_arg(args: &Value) -> Result<&str, String> {
+    args.get("item_id")
+        .and_then(Value::as_str)
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| "Missing required 'item_id' argument".to_string())
+}
+
+/// The app menu and any window menus, each once
+fn app_menus<R: Runtime>(app: &AppHandle<R>) -> Vec<Menu<R>> {
+    let mut menus: Vec<Menu<R>> = Vec::new();
+    let window_menus = app.webview_windows().into_values().filter_map(|window| window.menu());
+    for menu in app.menu().into_iter().chain(window_menus) {
+        if !menus.iter().any(|known| known.id() == menu.id()) {
+            menus.push(menu);
+        }
+    }
+    menus
+}
+
+/// Flatten menu items, depth first, remembering the submenus they're in
+fn collect_entries<R: Runtime>(
+    items: Vec<MenuItemKind<R>>,
+    ancestors: &[Ancestor],
+    entries: &mut Vec<Entry<R>>,
+) -> Result<(), String> {
+    for item in items {
+        let text = match &item {
+            MenuItemKind::MenuItem(item) => item.text(),
+            MenuItemKind::Submenu(item) => item.text(),
+            MenuItemKind::Predefined(item) => item.text(),
+            MenuItemKind::Check(item) => item.text(),
+            MenuItemKind::Icon(item) => item.text(),
+        }
+        .map_err(|e| e.to_string())?;
+
+        if let MenuItemKind::Submenu(submenu) = &item {
+            let mut path = ancestors.to_vec();
+            path.push(Ancestor {
+                id: submenu.id().as_ref().to_string(),
+                text: text.clone(),
+                enabled: submenu.is_enabled().map_err(|e| e.to_string())?,
+            });
+            collect_entries(submenu.items().map_err(|e| e.to_string())?, &path, entries)?;
+        }
+
+        entries.push(Entry {
+            ancestors: ancestors.to_vec(),
+            item,
+            text,
+        });
+    }
+    Ok(())
+}
+
+/// Whether a menu ID or label matches the query. Labels also match without their `&` mnemonic markers.
+fn matches_label(id: &str, text: &str, query: &str) -> bool {
+    id == query || text == query || strip_mnemonic(text) == query
+}
+
+/// Remove `&` mnemonic markers from a menu label, keeping `&&` as a literal `&`
+fn strip_mnemonic(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '&' {
+            if chars.peek() == Some(&'&') {
+                result.push('&');
+                chars.next();
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_mnemonic_removes_markers() {
+        assert_eq!(strip_mnemonic("&File"), "File");
+        assert_eq!(strip_mnemonic("Save &As..."), "Save As...");
+        assert_eq!(strip_mnemonic("Find && Replace"), "Find & Replace");
+    }
+
+    #[test]
+    fn matches_label_accepts_id_or_label() {
+        assert!(matches_label("file-save", "&Save", "file-save"));
+        assert!(matches_label("file-save", "&Save", "Save"));
+        assert!(matches_label("file-save", "&Save", "&Save"));
+        assert!(!matches_label("file-save", "&Save", "Save As"));
+    }
+
+    #[test]
+    fn item_id_is_required() {
+        assert!(item_id_arg(&json!({ "menu_id": "File" }))
+            .unwrap_err()
+            .contains("Missing required 'item_id'"));
+        assert!(item_id_arg(&json!({ "item_id": "" })).is_err());
+        assert_eq!(item_id_arg(&json!({ "item_id": "save" })).unwrap(), "save");
+    }
+}
@@ -2,22 +2,71 @@
 //!
 //! Each command corresponds to a tool in the MCP server:
 //! - `app_info` - Get application metadata
-//! - `screenshot` - Capture webview screenshot
+//! - `server_info` - Get plugin version, effective timeouts, and opt-ins
+//! - `screenshot` - Capture webview screenshot, optionally masking elements
+//! - `screenshot_composite` - Capture all visible windows laid out as on the desktop
 //! - `execute_js` - Run JavaScript in the webview
 //! - `console_logs` - Get captured console output
+//! - `console_export` - Write captured console output to a file
 //! - `dom_snapshot` - Get DOM tree as YAML
-//! - `interact` - Click, type, scroll
+//! - `dom_diff` - Compare the DOM with an earlier snapshot
+//! - `refs_clear` - Forget the element refs `dom_snapshot` handed out
+//! - `accessibility_find` - Find elements by ARIA role and name
+//! - `form_fields` - List form controls and their state
+//! - `table_data` - Extract headers and rows from a table
+//! - `focus_info` - Get the focused element and tab order
+//! - `active_element` - Get the focused element's value length and selection
+//! - `is_visible` - Check element visibility and viewport intersection
+//! - `element_info` - Get an element's geometry, visibility, occlusion, styles, and attributes
+//! - `find_text` - Find where the page shows some text
+//! - `scroll_info` / `scroll_to` - Read and set scroll positions
+//! - `highlight` / `highlight_clear` - Outline an element for people watching, and remove outlines
+//! - `interact` - Click, hover, right-click, drag, type, scroll, focus, blur, with DOM events or native OS input
 //! - `wait_for` - Wait for conditions
 //! - `window_list` / `window_info` / `window_resize` - Window management
+//! - `viewport_set` / `viewport_get` - Viewport emulation
+//! - `color_scheme_set` - Emulate a `prefers-color-scheme` value
+//! - `heap_snapshot` - Get JS heap usage
+//! - `emit_event` - Emit a Tauri event into the app
+//! - `notify` - Send a system notification (`notifications` feature)
+//! - `menu_click` / `menu_emit` - Trigger app menu items
+//! - `describe_command` - List the commands' arguments
+//!
+//! Args are checked against the command's schema (see [`schema`]) before it runs.
+//!
+//! The `screenshot`, `window-management`, `js-execution`, `dom-snapshot`, and `interact` features
+//! (all on by default) gate their commands. Without one, its commands say which feature they need.
 
+#[cfg(feature = "notifications")]
+mod app;
+mod console;
+#[cfg(feature = "dom-snapshot")]
+pub mod dom_diff;
+mod event;
 mod execute_js;
+#[cfg(feature = "interact")]
+mod interact;
+mod menu;
+#[cfg(feature = "interact")]
+mod native_input;
+mod schema;
+#[cfg(feature = "screenshot")]
 mod screenshot;
+#[cfg(feature = "dom-snapshot")]
+pub mod snapshot_pages;
+mod viewport;
+mod wait;
+#[cfg(feature = "window-management")]
 mod window;
 
+use std::time::Duration;
+
 use serde_json::{json, Value};
 use tauri::{Manager, Runtime};
 
+use crate::config::{ConfigState, PluginConfig};
 use crate::websocket::{Request, WindowContext};
+use crate::windows::{Candidate, WindowRegistry};
 
 /// Route a request to the appropriate command handler.
 ///
@@ -25,6 +74,18 @@ use crate::websocket::{Request, WindowContext};
 pub async fn execute<R: Runtime>(
     app: &tauri::AppHandle<R>,
     request: Request,
+) -> Result<(Value, Option<WindowContext>), String> {
+    schema::validate(&request.command, &request.args)?;
+    match without_window(app, &request).await {
+        Some(result) => result.map(|data| (data, None)),
+        None => in_window(app, &request).await,
+    }
+}
+
+/// Run a request in the window its `windowId` resolves to
+async fn in_window<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    request: &Request,
 ) -> Result<(Value, Option<WindowContext>), String> {
     let window_label = request.args.get("windowId").and_then(|v| v.as_str()).map(String::from);
 
@@ -34,52 +95,184 @@ pub async fn execute<R: Runtime>(
         total_windows: app.webview_windows().len(),
     });
 
+    let config = plugin_config(app);
+    let timeout = config.script_timeout;
+
     let result = match request.command.as_str() {
         "app_info" => app_info(app),
-        "screenshot" => screenshot::execute(&window, &request.args),
-        "execute_js" => execute_js::execute(&window, &request.args).await,
-        "console_logs" => execute_js::console_logs(&window, &request.args).await,
-        "dom_snapshot" => execute_js::dom_snapshot(&window, &request.args).await,
-        "interact" => execute_js::interact(&window, &request.args).await,
-        "wait_for" => execute_js::wait_for(&window, &request.args).await,
-        "window_list" => window::list(app),
+        "server_info" => server_info(&config),
+        #[cfg(feature = "screenshot")]
+        "screenshot" => screenshot::execute(&window, &request.args, timeout).await,
+        #[cfg(feature = "screenshot")]
+        "screenshot_composite" => screenshot::composite(app, &request.args).await,
+        #[cfg(feature = "js-execution")]
+        "execute_js" => execute_js::execute(&window, &request.args, timeout).await,
+        "console_logs" => console::logs(app, console::Target::Window(window.label()), &request.args),
+        "console_export" => console::export(app, console::Target::Window(window.label()), &request.args).await,
+        #[cfg(feature = "dom-snapshot")]
+        "dom_snapshot" | "dom_diff" | "refs_clear" => {
+            dom_snapshot(&window, &request.command, &request.args, timeout).await
+        }
+        "accessibility_find" => execute_js::accessibility_find(&window, &request.args, timeout).await,
+        "form_fields" => execute_js::form_fields(&window, &request.args, timeout).await,
+        "table_data" => execute_js::table_data(&window, &request.args, timeout).await,
+        "focus_info" => execute_js::focus_info(&window, timeout).await,
+        "active_element" => execute_js::active_element(&window, timeout).await,
+        "is_visible" => execute_js::is_visible(&window, &request.args, timeout).await,
+        "element_info" => execute_js::element_info(&window, &request.args, timeout).await,
+        "find_text" => execute_js::find_text(&window, &request.args, timeout).await,
+        "scroll_info" => execute_js::scroll_info(&window, &request.args, timeout).await,
+        "scroll_to" => execute_js::scroll_to(&window, &request.args, timeout).await,
+        "highlight" => execute_js::highlight(&window, &request.args, timeout).await,
+        "highlight_clear" => execute_js::highlight_clear(&window, timeout).await,
+        #[cfg(feature = "interact")]
+        "interact" => interact(&window, &request.args, &config).await,
+        "wait_for" => wait::wait_for(&window, &request.args).await,
+        #[cfg(feature = "window-management")]
+        "window_list" => window::list(app, &request.args),
+        #[cfg(feature = "window-management")]
         "window_info" => window::info(&window),
+        #[cfg(feature = "window-management")]
         "window_resize" => window::resize(&window, &request.args),
-        _ => Err(format!(
-            "Unknown command: '{}'. Available: app_info, screenshot, execute_js, console_logs, dom_snapshot, interact, wait_for, window_list, window_info, window_resize",
-            request.command
-        )),
+        "viewport_set" => viewport::set(&window, &request.args, timeout).await,
+        "viewport_get" => viewport::get(&window, timeout).await,
+        "color_scheme_set" => execute_js::color_scheme_set(&window, &request.args, timeout).await,
+        "heap_snapshot" => execute_js::heap_snapshot(&window, timeout).await,
+        "emit_event" => event::emit(app, &request.args),
+        "menu_click" => menu::click(app, &request.args),
+        "menu_emit" => menu::emit(app, &request.args),
+        #[cfg(feature = "notifications")]
+        "notify" => app::notify(app, &request.args),
+        _ => Err(unavailable(&request.command)),
     }?;
 
     Ok((result, context))
 }
 
-/// Resolve a window by label or get the focused/first window
+/// Take a DOM snapshot, compare the DOM with an earlier one for `dom_diff`, or forget the
+/// snapshots' refs for `refs_clear`
+#[cfg(feature = "dom-snapshot")]
+async fn dom_snapshot<R: Runtime>(
+    window: &tauri::WebviewWindow<R>,
+    command: &str,
+    args: &Value,
+    timeout: Duration,
+) -> Result<Value, String> {
+    match command {
+        "dom_diff" => dom_diff::dom_diff(window, args, timeout).await,
+        "refs_clear" => execute_js::refs_clear(window, timeout).await,
+        _ => execute_js::dom_snapshot(window, args, timeout).await,
+    }
+}
+
+/// Run the request if it doesn't need a window: `describe_command`, or `console_logs` or
+/// `console_export` for all windows or a destroyed window
+async fn without_window<R: Runtime>(app: &tauri::AppHandle<R>, request: &Request) -> Option<Result<Value, String>> {
+    match request.command.as_str() {
+        schema::DESCRIBE_COMMAND => Some(schema::describe(&request.args)),
+        "console_logs" | "console_export" => detached_console(app, request).await,
+        _ => None,
+    }
+}
+
+/// Run `console_logs` or `console_export` for all windows or a destroyed window, if the request
+/// asks for one. Console buffers outlive their windows, so these don't resolve a window.
+async fn detached_console<R: Runtime>(app: &tauri::AppHandle<R>, request: &Request) -> Option<Result<Value, String>> {
+    let target = console::detached_target(app, &request.args)?;
+    Some(if request.command == "console_logs" {
+        console::logs(app, target, &request.args)
+    } else {
+        console::export(app, target, &request.args).await
+    })
+}
+
+/// Error for a command this build doesn't have: left out by a feature, or unknown
+#[allow(clippy::option_if_let_else)]
+fn unavailable(command: &str) -> String {
+    match disabled_feature(command) {
+        Some(feature) => format!("The {command} command needs tauri-mcp built with the '{feature}' feature"),
+        None => format!(
+            "Unknown command: '{command}'. Available: app_info, server_info, screenshot, screenshot_composite, execute_js, console_logs, console_export, dom_snapshot, dom_diff, refs_clear, accessibility_find, form_fields, table_data, focus_info, active_element, is_visible, element_info, find_text, scroll_info, scroll_to, highlight, highlight_clear, interact, wait_for, window_list, window_info, window_resize, viewport_set, viewport_get, color_scheme_set, heap_snapshot, emit_event, notify, menu_click, menu_emit, describe_command"
+        ),
+    }
+}
+
+/// Get the feature a command needs if this build leaves it out
+fn disabled_feature(command: &str) -> Option<&'static str> {
+    match command {
+        "screenshot" | "screenshot_composite" if !cfg!(feature = "screenshot") => Some("screenshot"),
+        "window_list" | "window_info" | "window_resize" if !cfg!(feature = "window-management") => {
+            Some("window-management")
+        }
+        "execute_js" if !cfg!(feature = "js-execution") => Some("js-execution"),
+        "dom_snapshot" | "dom_diff" | "refs_clear" if !cfg!(feature = "dom-snapshot") => Some("dom-snapshot"),
+        "interact" if !cfg!(feature = "interact") => Some("interact"),
+        "notify" if !cfg!(feature = "notifications") => Some("notifications"),
+        _ => None,
+    }
+}
+
+/// Run `interact` with native OS input if it asks for it, or with DOM events
+#[cfg(feature = "interact")]
+async fn interact<R: Runtime>(
+    window: &tauri::WebviewWindow<R>,
+    args: &Value,
+    config: &PluginConfig,
+) -> Result<Value, String> {
+    if interact::native_arg(args)? {
+        native_input::interact(window, args, config.script_timeout, config.native_input).await
+    } else {
+        interact::interact(window, args, config.script_timeout, config.upload_max_bytes).await
+    }
+}
+
+/// Get the plugin config from app state, falling back to defaults
+pub fn plugin_config<R: Runtime>(app: &tauri::AppHandle<R>) -> PluginConfig {
+    app.try_state::<ConfigState>()
+        .map_or_else(PluginConfig::default, |config| config.get())
+}
+
+/// Get the transport timeout for a request, accounting for the script timeout it will use
+pub fn command_timeout(request: &Request, config: &PluginConfig) -> Duration {
+    let script_timeout = execute_js::script_timeout_for(&request.command, &request.args, config.script_timeout);
+    config.command_timeout_for(script_timeout)
+}
+
+/// Run a request like [`execute`], failing it once its transport timeout has passed
+pub async fn execute_with_timeout<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    request: Request,
+) -> Result<(Value, Option<WindowContext>), String> {
+    let timeout = command_timeout(&request, &plugin_config(app));
+    tokio::time::timeout(timeout, execute(app, request))
+        .await
+        .unwrap_or_else(|_| Err(format!("Command timed out after {}ms", timeout.as_millis())))
+}
+
+/// Resolve a window by label, alias, or glob pattern, or get the focused/oldest window
 #[allow(clippy::option_if_let_else)]
 fn resolve_window<R: Runtime>(
     app: &tauri::AppHandle<R>,
     label: Option<&str>,
 ) -> Result<tauri::WebviewWindow<R>, String> {
     let windows = app.webview_windows();
+    let candidates = windows
+        .values()
+        .map(|window| Candidate {
+            label: window.label(),
+            focused: window.is_focused().unwrap_or(false),
+        })
+        .collect::<Vec<_>>();
 
-    if windows.is_empty() {
-        return Err("No windows available".to_string());
-    }
+    let resolved = match app.try_state::<WindowRegistry>() {
+        Some(registry) => registry.resolve(label, &candidates)?,
+        None => WindowRegistry::default().resolve(label, &candidates)?,
+    };
 
-    if let Some(label) = label {
-        windows.get(label).cloned().ok_or_else(|| {
-            let available: Vec<&str> = windows.keys().map(String::as_str).collect();
-            format!("Window '{label}' not found. Available: {}", available.join(", "))
-        })
-    } else {
-        // Try to find focused window, fall back to first
-        windows
-            .values()
-            .find(|w| w.is_focused().unwrap_or(false))
-            .or_else(|| windows.values().next())
-            .cloned()
-            .ok_or_else(|| "No window available".to_string())
-    }
+    windows
+        .get(resolved)
+        .cloned()
+        .ok_or_else(|| format!("Window '{resolved}' not found"))
 }
 
 /// Get application information including the app name
@@ -94,3 +287,55 @@ fn app_info<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<Value, String> {
         "version": version,
     }))
 }
+
+/// Get plugin version, effective timeouts, and limits
+#[allow(clippy::unnecessary_wraps)] // Keep Result for consistent command signature
+fn server_info(config: &PluginConfig) -> Result<Value, String> {
+    Ok(json!({
+        "version": env!("CARGO_PKG_VERSION"),
+        "scriptTimeoutMs": duration_millis(config.script_timeout),
+        "commandTimeoutMs": duration_millis(config.command_timeout_for(config.script_timeout)),
+        "nativeInput": config.native_input,
+        "serverRestart": config.server_restart,
+        "cdpCompat": config.cdp_compat,
+        "bidiCompat": config.bidi_compat,
+        "grpcPort": config.grpc_port,
+        "requestQueueDepth": config.request_queue_depth,
+        "maxMessageBytes": config.max_message_bytes,
+        "pingIntervalMs": duration_millis(config.ping_interval),
+        "pingTimeoutMs": config.ping_timeout.map(duration_millis),
+    }))
+}
+
+/// Convert a duration to whole milliseconds for JSON output
+fn duration_millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unavailable_commands_name_their_feature() {
+        assert!(unavailable("nope").starts_with("Unknown command: 'nope'"));
+        let screenshot = unavailable("screenshot");
+        if cfg!(feature = "screenshot") {
+            assert!(screenshot.starts_with("Unknown command"));
+        } else {
+            assert_eq!(
+                screenshot,
+                "The screenshot command needs tauri-mcp built with the 'screenshot' feature"
+            );
+        }
+        let notify = unavailable("notify");
+        if cfg!(feature = "notifications") {
+            assert!(notify.starts_with("Unknown command"));
+        } else {
+            assert_eq!(
+                notify,
+                "The notify command needs tauri-mcp built with the 'notifications' feature"
+            );
+        }
+    }
+}
@@ -0,0 +1,191 @@
+//! `interact` with `native: true`, which sends real OS input instead of DOM events
+
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tauri::{Runtime, WebviewWindow};
+
+use super::execute_js;
+use super::interact::{interact_script, validate_interact_args};
+use crate::native_input::{self, Input, ScreenPoint, KEY_INTERVAL};
+
+/// How long to wait for the page to receive the event the input should cause
+const NATIVE_EVENT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Where the interact script found the input should go
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Aim {
+    /// Point in CSS pixels, or `None` for typing, which goes to the focused element
+    point: Option<CssPoint>,
+    target: String,
+    device_pixel_ratio: f64,
+    /// How `x` and `y` were converted to the point, when they were given
+    coordinates: Option<Value>,
+    wait: Option<Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CssPoint {
+    x: f64,
+    y: f64,
+}
+
+/// Whether the page received the event, as reported by `native-event.js`
+#[derive(Debug, Deserialize)]
+struct Received {
+    received: bool,
+    trusted: Option<bool>,
+}
+
+/// Extra time a native action takes on top of the script: typing each character, and waiting for
+/// the page to receive the event
+pub fn input_time(args: &Value) -> Duration {
+    let chars = args
+        .get("text")
+        .and_then(Value::as_str)
+        .map_or(0, |text| text.encode_utf16().count());
+    KEY_INTERVAL * u32::try_from(chars).unwrap_or(u32::MAX) + NATIVE_EVENT_TIMEOUT
+}
+
+/// Click, type, or scroll with real OS input. `enabled` is the app's `Builder::native_input`
+/// opt-in.
+pub async fn interact<R: Runtime>(
+    window: &WebviewWindow<R>,
+    args: &Value,
+    timeout: Duration,
+    enabled: bool,
+) -> Result<Value, String> {
+    if !enabled {
+        return Err(
+            "Native input is off. It moves the real cursor and types into the focused window, so the \
+             app has to opt in with tauri_plugin_mcp::Builder::new().native_input(true). Leave out 'native' to \
+             use DOM events."
+                .to_string(),
+        );
+    }
+    validate_interact_args(args)?;
+    native_input::check_supported()?;
+
+    // OS input goes to whatever is in front, so bring the window there first
+    window
+        .set_focus()
+        .map_err(|e| format!("Failed to focus the window for native input: {e}"))?;
+
+    let aim = execute_js::eval_with_result(window, &interact_script(args)?, timeout).await?;
+    let aim: Aim = serde_json::from_value(aim).map_err(|e| format!("Unexpected interact script result: {e}"))?;
+
+    let action = args.get("action").and_then(Value::as_str).unwrap_or_default();
+    let screen = aim
+        .point
+        .as_ref()
+        .map(|point| native_input::screen_point(window, point.x, point.y, aim.device_pixel_ratio))
+        .transpose()?;
+    let input = input_for(action, args, screen)?;
+    tokio::task::spawn_blocking(move || native_input::send(&input))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    let received = execute_js::eval_with_result(window, &confirm_script(), timeout).await?;
+    let received: Received =
+        serde_json::from_value(received).map_err(|e| format!("Unexpected native event result: {e}"))?;
+
+    let mut result = json!({
+        "success": true,
+        "inputMode": "native",
+        "message": format!("Sent native {action} to {}", aim.target),
+        "target": aim.target,
+        "eventReceived": received.received,
+        "eventTrusted": received.trusted,
+    });
+    if let (Some(point), Some(screen)) = (&aim.point, screen) {
+        result["x"] = json!(point.x);
+        result["y"] = json!(point.y);
+        result["screenX"] = json!(screen.x.round());
+        result["screenY"] = json!(screen.y.round());
+    }
+    if !received.received {
+        result["hint"] = json!(
+            "The page didn't receive the input. Another window may be covering this one, or on macOS the app \
+             may need Accessibility permission in System Settings > Privacy & Security."
+        );
+    }
+    if let Some(coordinates) = aim.coordinates {
+        result["coordinates"] = coordinates;
+    }
+    if let Some(wait) = aim.wait {
+        result["wait"] = wait;
+    }
+
+    Ok(result)
+}
+
+/// Turn the action into OS input, at the point the script found
+fn input_for(action: &str, args: &Value, point: Option<ScreenPoint>) -> Result<Input, String> {
+    let number = |names: [&str; 2]| names.iter().find_map(|name| args.get(*name).and_then(Value::as_f64));
+    match (action, point) {
+        ("click", Some(point)) => Ok(Input::Click(point)),
+        ("type", _) => Ok(Input::Text(
+            args.get("text").and_then(Value::as_str).unwrap_or_default().to_string(),
+        )),
+        ("scroll", Some(point)) => Ok(Input::Scroll {
+            point,
+            delta_x: number(["deltaX", "scrollX"]).unwrap_or(0.0),
+            delta_y: number(["deltaY", "scrollY"]).unwrap_or(0.0),
+        }),
+        _ => Err(format!("The interact script found no point for a native '{action}'")),
+    }
+}
+
+/// Build the script that waits for the event the input should cause
+fn confirm_script() -> String {
+    let script = include_str!("../scripts/native-event.js");
+    let timeout_ms = NATIVE_EVENT_TIMEOUT.as_millis();
+
+    format!(
+        r"
+        {script}
+        return await window.__tauriMcpNativeEventReceived({timeout_ms})
+        "
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point() -> ScreenPoint {
+        ScreenPoint {
+            x: 10.0,
+            y: 20.0,
+            scale_factor: 1.0,
+        }
+    }
+
+    #[test]
+    fn input_for_reads_scroll_deltas_and_their_aliases() {
+        let args = json!({ "action": "scroll", "deltaY": 120, "scrollX": -40 });
+        assert_eq!(
+            input_for("scroll", &args, Some(point())),
+            Ok(Input::Scroll {
+                point: point(),
+                delta_x: -40.0,
+                delta_y: 120.0
+            })
+        );
+    }
+
+    #[test]
+    fn input_for_needs_a_point_to_click() {
+        let args = json!({ "action": "click", "selector": "#save" });
+        assert_eq!(input_for("click", &args, Some(point())), Ok(Input::Click(point())));
+        assert!(input_for("click", &args, None).is_err());
+    }
+
+    #[test]
+    fn input_time_covers_typing_and_confirming() {
+        let args = json!({ "action": "type", "native": true, "text": "hello" });
+        assert_eq!(input_time(&args), KEY_INTERVAL * 5 + NATIVE_EVENT_TIMEOUT);
+    }
+}
@@ -0,0 +1,716 @@
+//! Argument schemas of the commands, checked before a command runs and listed by `describe_command`.
+//!
+//! A schema names each argument with its type and whether it's required. Checking it catches a
+//! misspelled type or a missing argument with one consistent error before any script runs, while
+//! ranges, allowed values, and combinations of arguments stay with the command. Arguments a schema
+//! doesn't list are passed through, like the deprecated `timeout`.
+
+use serde_json::{json, Value};
+
+use super::execute_js;
+
+/// Command that lists the commands with a schema, or describes one
+pub const DESCRIBE_COMMAND: &str = "describe_command";
+
+/// Prefix of the errors for arguments that don't match their schema
+const INVALID_ARGUMENT: &str = "INVALID_ARGUMENT";
+
+/// A command's arguments
+pub struct CommandSchema {
+    pub command: &'static str,
+    pub description: &'static str,
+    pub args: &'static [Arg],
+}
+
+/// One argument of a command
+pub struct Arg {
+    pub name: &'static str,
+    pub kind: ArgKind,
+    pub required: bool,
+    pub description: &'static str,
+}
+
+/// The JSON an argument takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgKind {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    Array,
+    Object,
+    /// A CSS selector string or a selector object like `{ "role": "button", "name": "Save" }`
+    Selector,
+    /// Any JSON, for arguments whose type depends on other arguments
+    Any,
+}
+
+impl ArgKind {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::String => "string",
+            Self::Integer => "integer",
+            Self::Number => "number",
+            Self::Boolean => "boolean",
+            Self::Array => "array",
+            Self::Object => "object",
+            Self::Selector => "selector",
+            Self::Any => "any",
+        }
+    }
+
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+            Self::Number => value.is_number(),
+            Self::Boolean => value.is_boolean(),
+            Self::Array => value.is_array(),
+            Self::Object => value.is_object(),
+            Self::Selector => value.is_string() || value.is_object(),
+            Self::Any => true,
+        }
+    }
+}
+
+const fn required(name: &'static str, kind: ArgKind, description: &'static str) -> Arg {
+    Arg {
+        name,
+        kind,
+        required: true,
+        description,
+    }
+}
+
+const fn optional(name: &'static str, kind: ArgKind, description: &'static str) -> Arg {
+    Arg {
+        name,
+        kind,
+        required: false,
+        description,
+    }
+}
+
+const WINDOW_ID: Arg = optional("windowId", ArgKind::String, "Target window label (default: focused)");
+const PIERCE_SHADOW: Arg = optional(
+    "pierceShadow",
+    ArgKind::Boolean,
+    "Also search open shadow roots (default: false)",
+);
+
+/// Snapshot options `dom_snapshot` and `dom_diff` share
+const SNAPSHOT_TYPE: Arg = optional("type", ArgKind::String, "accessibility or structure");
+const SNAPSHOT_SELECTOR: Arg = optional("selector", ArgKind::Selector, "Element to scope the snapshot to");
+const MAX_DEPTH: Arg = optional("maxDepth", ArgKind::Integer, "Deepest level to include");
+const MAX_NODES: Arg = optional("maxNodes", ArgKind::Integer, "Most nodes to include");
+const MAX_FRAME_DEPTH: Arg = optional(
+    "maxFrameDepth",
+    ArgKind::Integer,
+    "How many same-origin iframes deep to include (default: 1)",
+);
+const INCLUDE_RECTS: Arg = optional(
+    "includeRects",
+    ArgKind::Boolean,
+    "Add each element's bounding rect and visibility",
+);
+const COMPUTED_STYLES: Arg = optional(
+    "computedStyles",
+    ArgKind::Array,
+    "CSS properties whose computed values to add",
+);
+const INTERACTIVE_ONLY: Arg = optional(
+    "interactiveOnly",
+    ArgKind::Boolean,
+    "Only elements with interactive roles, each with a ref",
+);
+const ROLES: Arg = optional(
+    "roles",
+    ArgKind::Array,
+    "Only elements with these ARIA roles, each with a ref",
+);
+const STORE: Arg = optional("store", ArgKind::Boolean, "Keep the snapshot for dom_diff");
+
+/// The commands with a schema, in the order `describe_command` lists them
+pub const SCHEMAS: &[CommandSchema] = &[
+    CommandSchema {
+        command: "app_info",
+        description: "Get application metadata",
+        args: &[],
+    },
+    CommandSchema {
+        command: "server_info",
+        description: "Get the plugin version, effective timeouts, and opt-ins",
+        args: &[],
+    },
+    CommandSchema {
+        command: DESCRIBE_COMMAND,
+        description: "List the commands with an argument schema, or describe one",
+        args: &[optional(
+            "command",
+            ArgKind::String,
+            "Command to describe (default: list all)",
+        )],
+    },
+    CommandSchema {
+        command: "screenshot",
+        description: "Capture the webview as PNG or JPEG",
+        args: &[
+            optional("format", ArgKind::String, "png or jpeg"),
+            optional("quality", ArgKind::Number, "JPEG quality from 0 to 100"),
+            optional(
+                "preferSmaller",
+                ArgKind::Boolean,
+                "Return whichever of PNG and JPEG is smaller",
+            ),
+            optional("clip", ArgKind::Object, "Region to capture: { x, y, width, height }"),
+            optional("maskSelectors", ArgKind::Array, "Selectors of elements to hide"),
+            optional(
+                "watermark",
+                ArgKind::Object,
+                "Label to draw: { text, position, opacity }",
+            ),
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "screenshot_composite",
+        description: "Capture all visible windows laid out as on the desktop",
+        args: &[optional(
+            "padding",
+            ArgKind::Integer,
+            "Border around each window in pixels",
+        )],
+    },
+    CommandSchema {
+        command: "execute_js",
+        description: "Run JavaScript in the webview and return its JSON result",
+        args: &[
+            required("script", ArgKind::String, "JavaScript to run"),
+            optional("timeoutMs", ArgKind::Integer, "How long to wait for the result"),
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "console_logs",
+        description: "Get captured console output",
+        args: &[
+            optional("filter", ArgKind::String, "Regex the messages match"),
+            optional(
+                "since",
+                ArgKind::Any,
+                "ISO timestamp string, or the seq of the last entry seen",
+            ),
+            optional(
+                "levels",
+                ArgKind::Array,
+                "Levels to include, like [\"error\", \"uncaught\"]",
+            ),
+            optional("clear", ArgKind::Boolean, "Clear the logs after reading"),
+            optional("navigation", ArgKind::String, "current or all page loads"),
+            optional("offset", ArgKind::Integer, "Matching entries to skip"),
+            optional("limit", ArgKind::Integer, "Most matching entries to return"),
+            optional("allWindows", ArgKind::Boolean, "Merge the logs of all windows"),
+            optional(
+                "windowId",
+                ArgKind::String,
+                "Target window label, or \"*\" for all windows",
+            ),
+            optional(
+                "paged",
+                ArgKind::Boolean,
+                "Return { entries, total, dropped, oldestTimestamp }",
+            ),
+        ],
+    },
+    CommandSchema {
+        command: "console_export",
+        description: "Write captured console output to a file, or return it",
+        args: &[
+            optional("filter", ArgKind::String, "Regex the messages match"),
+            optional(
+                "since",
+                ArgKind::Any,
+                "ISO timestamp string, or the seq of the last entry seen",
+            ),
+            optional(
+                "levels",
+                ArgKind::Array,
+                "Levels to include, like [\"error\", \"uncaught\"]",
+            ),
+            optional("clear", ArgKind::Boolean, "Clear the logs after reading"),
+            optional("navigation", ArgKind::String, "current or all page loads"),
+            optional("offset", ArgKind::Integer, "Matching entries to skip"),
+            optional("limit", ArgKind::Integer, "Most matching entries to return"),
+            optional("allWindows", ArgKind::Boolean, "Merge the logs of all windows"),
+            optional(
+                "windowId",
+                ArgKind::String,
+                "Target window label, or \"*\" for all windows",
+            ),
+            optional(
+                "savePath",
+                ArgKind::String,
+                "Absolute path to write to, else returned inline",
+            ),
+            optional("format", ArgKind::String, "jsonl or text"),
+        ],
+    },
+    CommandSchema {
+        command: "dom_snapshot",
+        description: "Get the accessibility or structure tree of the DOM",
+        args: &[
+            SNAPSHOT_TYPE,
+            SNAPSHOT_SELECTOR,
+            PIERCE_SHADOW,
+            MAX_DEPTH,
+            MAX_NODES,
+            MAX_FRAME_DEPTH,
+            optional("format", ArgKind::String, "yaml, json, html, or markdown"),
+            optional("maxLength", ArgKind::Integer, "Longest html or markdown output"),
+            INCLUDE_RECTS,
+            COMPUTED_STYLES,
+            INTERACTIVE_ONLY,
+            ROLES,
+            STORE,
+            optional(
+                "pageSize",
+                ArgKind::Integer,
+                "Nodes per page, to get a big snapshot in pages",
+            ),
+            optional("page", ArgKind::String, "nextPage token of the previous page"),
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "dom_diff",
+        description: "Compare the DOM with an earlier snapshot",
+        args: &[
+            optional("baseline", ArgKind::Any, "A JSON snapshot from dom_snapshot"),
+            optional("baselineId", ArgKind::String, "snapshotId of a stored snapshot"),
+            SNAPSHOT_TYPE,
+            SNAPSHOT_SELECTOR,
+            PIERCE_SHADOW,
+            MAX_DEPTH,
+            MAX_NODES,
+            MAX_FRAME_DEPTH,
+            INCLUDE_RECTS,
+            COMPUTED_STYLES,
+            INTERACTIVE_ONLY,
+            ROLES,
+            optional("maxChanges", ArgKind::Integer, "Most changes to list"),
+            STORE,
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "refs_clear",
+        description: "Forget the element refs dom_snapshot handed out",
+        args: &[WINDOW_ID],
+    },
+    CommandSchema {
+        command: "accessibility_find",
+        description: "Find elements by ARIA role and name",
+        args: &[
+            optional("role", ArgKind::String, "ARIA role, like button"),
+            optional("name", ArgKind::String, "Accessible name to match"),
+            optional("exact", ArgKind::Boolean, "Match the name exactly"),
+            optional("includeHidden", ArgKind::Boolean, "Include hidden elements"),
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "form_fields",
+        description: "List form controls and their state",
+        args: &[
+            optional("formSelector", ArgKind::String, "CSS selector of the form"),
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "table_data",
+        description: "Extract headers and rows from a table",
+        args: &[
+            required("selector", ArgKind::String, "CSS selector of the table"),
+            optional("includeHtml", ArgKind::Boolean, "Add each cell's HTML"),
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "focus_info",
+        description: "Get the focused element and tab order",
+        args: &[WINDOW_ID],
+    },
+    CommandSchema {
+        command: "active_element",
+        description: "Get the focused element's value length and selection",
+        args: &[WINDOW_ID],
+    },
+    CommandSchema {
+        command: "is_visible",
+        description: "Check an element's visibility and viewport intersection",
+        args: &[
+            required("selector", ArgKind::String, "CSS selector of the element"),
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "element_info",
+        description: "Get an element's geometry, visibility, occlusion, styles, and attributes",
+        args: &[
+            required("selector", ArgKind::Selector, "Element to describe"),
+            optional("styles", ArgKind::Array, "Computed style properties to return"),
+            optional("all", ArgKind::Boolean, "Describe every match"),
+            PIERCE_SHADOW,
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "find_text",
+        description: "Find where the page shows some text",
+        args: &[
+            required("query", ArgKind::String, "Text or regex to find"),
+            optional("regex", ArgKind::Boolean, "Treat the query as a regex"),
+            optional("caseSensitive", ArgKind::Boolean, "Match case"),
+            optional("maxResults", ArgKind::Integer, "Most matches to return"),
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "scroll_info",
+        description: "Get the scroll position of the page or a container",
+        args: &[
+            optional("selector", ArgKind::String, "CSS selector of the container"),
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "scroll_to",
+        description: "Scroll the page to a position or an element into view",
+        args: &[
+            optional("x", ArgKind::Number, "Horizontal page position in pixels"),
+            optional("y", ArgKind::Number, "Vertical page position in pixels"),
+            optional("behavior", ArgKind::String, "smooth, instant, or auto"),
+            optional("selector", ArgKind::String, "CSS selector of the element to scroll to"),
+            optional("block", ArgKind::String, "start, center, end, or nearest"),
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "highlight",
+        description: "Outline an element with a labeled overlay",
+        args: &[
+            required("selector", ArgKind::Selector, "Element to outline"),
+            optional("durationMs", ArgKind::Integer, "How long the outline stays"),
+            optional("color", ArgKind::String, "CSS color of the outline"),
+            optional("label", ArgKind::Any, "String or number shown above the outline"),
+            PIERCE_SHADOW,
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "highlight_clear",
+        description: "Remove all highlight outlines",
+        args: &[WINDOW_ID],
+    },
+    CommandSchema {
+        command: "interact",
+        description: "Click, type, scroll, drag, press keys, and more, or run a sequence of steps",
+        args: &[
+            optional("action", ArgKind::String, "Action to perform, like click or type"),
+            optional("actions", ArgKind::Array, "Steps of a sequence action"),
+            optional("selector", ArgKind::Selector, "Target element"),
+            optional("x", ArgKind::Number, "X coordinate, instead of a selector"),
+            optional("y", ArgKind::Number, "Y coordinate, instead of a selector"),
+            optional("coordinateSpace", ArgKind::String, "css, css-page, or device"),
+            optional("origin", ArgKind::Selector, "Element the coordinates are offsets from"),
+            optional("text", ArgKind::String, "Text to type"),
+            optional("waitTimeoutMs", ArgKind::Integer, "How long to wait for the target"),
+            optional("native", ArgKind::Boolean, "Send real OS input"),
+            PIERCE_SHADOW,
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "wait_for",
+        description: "Wait for a condition",
+        args: &[
+            required(
+                "type",
+                ArgKind::String,
+                "selector, text, visible, hidden, detached, function, console, windowCount, tauriEvent, all, or any",
+            ),
+            optional("value", ArgKind::Any, "Selector, or text for the text type"),
+            optional("script", ArgKind::String, "Predicate for the function type"),
+            optional("conditions", ArgKind::Array, "Conditions for the all and any types"),
+            optional("timeoutMs", ArgKind::Integer, "How long to wait"),
+            optional("pollIntervalMs", ArgKind::Integer, "How often to check"),
+            PIERCE_SHADOW,
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "window_list",
+        description: "List the app's windows",
+        args: &[
+            optional("sort", ArgKind::String, "label, title, or focused_first"),
+            optional("filter", ArgKind::Object, "Properties to match, like { visible: true }"),
+        ],
+    },
+    CommandSchema {
+        command: "window_info",
+        description: "Get a window's size, position, and state",
+        args: &[WINDOW_ID],
+    },
+    CommandSchema {
+        command: "window_resize",
+        description: "Resize a window",
+        args: &[
+            required("width", ArgKind::Integer, "New width in pixels"),
+            required("height", ArgKind::Integer, "New height in pixels"),
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "viewport_set",
+        description: "Emulate a viewport size",
+        args: &[
+            required("width", ArgKind::Integer, "Viewport width in CSS pixels"),
+            required("height", ArgKind::Integer, "Viewport height in CSS pixels"),
+            optional("deviceScaleFactor", ArgKind::Number, "Device pixel ratio"),
+            optional("mobile", ArgKind::Boolean, "Emulate a mobile device"),
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "viewport_get",
+        description: "Get the viewport size and emulation",
+        args: &[WINDOW_ID],
+    },
+    CommandSchema {
+        command: "color_scheme_set",
+        description: "Emulate a prefers-color-scheme value",
+        args: &[
+            required("scheme", ArgKind::String, "dark, light, or no-preference"),
+            WINDOW_ID,
+        ],
+    },
+    CommandSchema {
+        command: "heap_snapshot",
+        description: "Get JS heap usage",
+        args: &[WINDOW_ID],
+    },
+    CommandSchema {
+        command: "emit_event",
+        description: "Emit a Tauri event into the app",
+        args: &[
+            required("event", ArgKind::String, "Event name"),
+            optional("payload", ArgKind::Any, "JSON payload"),
+            optional("window", ArgKind::String, "Only emit to this window's listeners"),
+        ],
+    },
+    CommandSchema {
+        command: "notify",
+        description: "Send a system notification",
+        args: &[
+            required("title", ArgKind::String, "Notification title"),
+            optional("body", ArgKind::String, "Notification body"),
+            optional("icon", ArgKind::String, "Icon name or path"),
+        ],
+    },
+    CommandSchema {
+        command: "menu_click",
+        description: "Trigger an app menu item",
+        args: &[
+            required("item_id", ArgKind::String, "ID or label of the menu item"),
+            optional("menu_id", ArgKind::String, "ID or label of the submenu it's in"),
+        ],
+    },
+    CommandSchema {
+        command: "menu_emit",
+        description: "Send the menu event of an item ID",
+        args: &[
+            required("item_id", ArgKind::String, "Menu item ID"),
+            optional("menu_id", ArgKind::String, "Echoed back in the result"),
+        ],
+    },
+];
+
+/// Get a command's schema, if it has one
+pub fn find(command: &str) -> Option<&'static CommandSchema> {
+    SCHEMAS.iter().find(|schema| schema.command == command)
+}
+
+/// Check a request's args against its command's schema. Commands without one pass.
+pub fn validate(command: &str, args: &Value) -> Result<(), String> {
+    find(command).map_or(Ok(()), |schema| schema.validate(args))
+}
+
+impl CommandSchema {
+    /// Check that `args` is an object with every required argument and the listed ones of the
+    /// right type, where null counts as left out
+    pub fn validate(&self, args: &Value) -> Result<(), String> {
+        let fields = match args {
+            Value::Null => None,
+            Value::Object(fields) => Some(fields),
+            _ => return Err(self.invalid(format_args!("args must be an object, got: {args}"))),
+        };
+        for arg in self.args {
+            match fields.and_then(|fields| fields.get(arg.name)) {
+                None | Some(Value::Null) if arg.required => {
+                    return Err(self.invalid(format_args!("Missing required '{}' argument", arg.name)));
+                }
+                None | Some(Value::Null) => {}
+                Some(value) if !arg.kind.matches(value) => {
+                    return Err(self.invalid(format_args!(
+                        "'{}' must be {}, got: {value}",
+                        arg.name,
+                        expected(arg.kind)
+                    )));
+                }
+                // Selector objects have their own shape, checked the way the commands do
+                Some(_) if arg.kind == ArgKind::Selector => {
+                    execute_js::selector_arg(args, arg.name).map_err(|e| self.invalid(format_args!("{e}")))?;
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn invalid(&self, problem: std::fmt::Arguments) -> String {
+        format!(
+            "{INVALID_ARGUMENT}: {problem}. {DESCRIBE_COMMAND} with {{ \"command\": \"{}\" }} lists its arguments.",
+            self.command
+        )
+    }
+
+    fn to_json(&self) -> Value {
+        let args: Vec<Value> = self
+            .args
+            .iter()
+            .map(|arg| {
+                json!({
+                    "name": arg.name,
+                    "type": arg.kind.name(),
+                    "required": arg.required,
+                    "description": arg.description,
+                })
+            })
+            .collect();
+        json!({ "command": self.command, "description": self.description, "args": args })
+    }
+}
+
+/// How an error names the type an argument takes
+const fn expected(kind: ArgKind) -> &'static str {
+    match kind {
+        ArgKind::String => "a string",
+        ArgKind::Integer => "an integer",
+        ArgKind::Number => "a number",
+        ArgKind::Boolean => "true or false",
+        ArgKind::Array => "an array",
+        ArgKind::Object => "an object",
+        ArgKind::Selector => "a CSS selector or a selector object like { \"text\": \"Save\" }",
+        ArgKind::Any => "any JSON",
+    }
+}
+
+/// Describe the command in `command`, or list the commands with a schema
+pub fn describe(args: &Value) -> Result<Value, String> {
+    match args.get("command") {
+        None | Some(Value::Null) => {
+            let commands: Vec<Value> = SCHEMAS
+                .iter()
+                .map(|schema| json!({ "command": schema.command, "description": schema.description }))
+                .collect();
+            Ok(json!({ "commands": commands }))
+        }
+        Some(Value::String(command)) => find(command).map(CommandSchema::to_json).ok_or_else(|| {
+            format!(
+                "No schema for '{command}'. Commands with one: {}",
+                SCHEMAS
+                    .iter()
+                    .map(|schema| schema.command)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        }),
+        Some(value) => Err(format!("'command' must be a command name, got: {value}")),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn args_are_checked_for_presence_and_type() {
+        assert_eq!(validate("execute_js", &json!({ "script": "return 1" })), Ok(()));
+        assert_eq!(validate("app_info", &Value::Null), Ok(()));
+        assert_eq!(validate("not_a_command", &json!(7)), Ok(()));
+        // Args a schema doesn't list are passed through
+        assert_eq!(validate("heap_snapshot", &json!({ "timeoutMs": 100 })), Ok(()));
+
+        let missing = validate("execute_js", &json!({ "script": null })).unwrap_err();
+        assert!(missing.starts_with("INVALID_ARGUMENT: Missing required 'script' argument."));
+        assert!(missing.contains("{ \"command\": \"execute_js\" }"));
+
+        let wrong = validate("dom_snapshot", &json!({ "maxDepth": "3" })).unwrap_err();
+        assert!(wrong.starts_with("INVALID_ARGUMENT: 'maxDepth' must be an integer, got: \"3\""));
+        assert!(validate("dom_snapshot", &json!({ "maxDepth": 1.5 })).is_err());
+        assert!(validate("execute_js", &json!(["return 1"]))
+            .unwrap_err()
+            .contains("args must be an object"));
+    }
+
+    #[test]
+    fn selector_args_take_strings_and_selector_objects() {
+        assert_eq!(validate("element_info", &json!({ "selector": "#save" })), Ok(()));
+        assert_eq!(
+            validate("element_info", &json!({ "selector": { "ref": "e3" } })),
+            Ok(())
+        );
+        let bad = validate("element_info", &json!({ "selector": { "id": "save" } })).unwrap_err();
+        assert!(bad.starts_with("INVALID_ARGUMENT: 'selector' must have exactly one of"));
+        assert!(validate("highlight", &json!({ "selector": 3 }))
+            .unwrap_err()
+            .contains("a CSS selector or a selector object"));
+    }
+
+    #[test]
+    fn describe_lists_and_describes_commands() {
+        let list = describe(&json!({})).unwrap();
+        let commands = list["commands"].as_array().unwrap();
+        assert_eq!(commands.len(), SCHEMAS.len());
+        assert!(commands.iter().any(|command| command["command"] == "dom_snapshot"));
+
+        let execute = describe(&json!({ "command": "execute_js" })).unwrap();
+        assert_eq!(
+            execute["args"][0],
+            json!({ "name": "script", "type": "string", "required": true, "description": "JavaScript to run" })
+        );
+        assert!(describe(&json!({ "command": "nope" }))
+            .unwrap_err()
+            .starts_with("No schema for 'nope'. Commands with one: app_info, server_info, describe_command"));
+    }
+
+    #[test]
+    fn schemas_have_unique_commands_and_args() {
+        for (i, schema) in SCHEMAS.iter().enumerate() {
+            assert!(
+                SCHEMAS[..i].iter().all(|other| other.command != schema.command),
+                "{}",
+                schema.command
+            );
+            for (j, arg) in schema.args.iter().enumerate() {
+                assert!(
+                    schema.args[..j].iter().all(|other| other.name != arg.name),
+                    "{}.{}",
+                    schema.command,
+                    arg.name
+                );
+            }
+        }
+    }
+}
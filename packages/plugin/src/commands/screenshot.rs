@@ -1,25 +1,511 @@
 //! Screenshot capture command
 
-use serde_json::Value;
-use tauri::{Runtime, WebviewWindow};
+use std::time::Duration;
 
+use base64::Engine;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime, WebviewWindow};
+
+use super::execute_js;
 use crate::screenshot as screenshot_impl;
+use crate::screenshot::composite::{self, Layer};
+use crate::screenshot::watermark::{self, Position, Watermark};
+use crate::screenshot::ClipRect;
+
+/// Widest border `screenshot_composite` draws around each window
+const MAX_COMPOSITE_PADDING: u64 = 200;
+
+/// JPEG quality `preferSmaller` compares the PNG against, unless `quality` is given
+const PREFER_SMALLER_JPEG_QUALITY: u8 = 85;
+
+/// JPEG quality of watermarked screenshots, unless `quality` is given, like the platforms default to
+const DEFAULT_JPEG_QUALITY: u8 = 80;
+
+/// Longest `watermark.text`, which has to fit on one line
+const MAX_WATERMARK_CHARS: usize = 200;
+
+/// Opacity of the watermark, unless `watermark.opacity` is given
+const DEFAULT_WATERMARK_OPACITY: f64 = 0.7;
+
+/// Execute screenshot command, hiding the elements `maskSelectors` matches while capturing
+pub async fn execute<R: Runtime>(window: &WebviewWindow<R>, args: &Value, timeout: Duration) -> Result<Value, String> {
+    let Some(mask_selectors) = mask_selectors_arg(args)? else {
+        return capture(window, args).await;
+    };
+
+    let masked = execute_js::eval_with_result(window, &mask_script(mask_selectors)?, timeout).await;
+    let masked_elements = match masked {
+        Ok(masked) => masked.get("maskedElements").cloned().unwrap_or(Value::Null),
+        Err(e) => {
+            // Some selectors may have matched before one failed
+            let _ = execute_js::eval_with_result(window, &unmask_script(), timeout).await;
+            return Err(format!("Failed to mask elements: {e}"));
+        }
+    };
+    let captured = capture(window, args).await;
+    // Show the elements again even if the capture failed, but never return an unmasked image
+    execute_js::eval_with_result(window, &unmask_script(), timeout)
+        .await
+        .map_err(|e| format!("Failed to unmask elements after the screenshot: {e}"))?;
 
-/// Execute screenshot command
-pub fn execute<R: Runtime>(window: &WebviewWindow<R>, args: &Value) -> Result<Value, String> {
-    let format = args.get("format").and_then(|v| v.as_str()).unwrap_or("png");
+    let mut result = match captured? {
+        Value::String(image) => json!({ "image": image }),
+        result => result,
+    };
+    result["maskedElements"] = masked_elements;
+    Ok(result)
+}
+
+/// Capture the screenshot the args describe
+async fn capture<R: Runtime>(window: &WebviewWindow<R>, args: &Value) -> Result<Value, String> {
+    let format = args.get("format").and_then(|v| v.as_str()).unwrap_or("png").to_string();
 
     let quality = args
         .get("quality")
         .and_then(Value::as_u64)
         .map(|q| u8::try_from(q.min(100)).unwrap_or(100));
 
-    let data = screenshot_impl::capture(window, format, quality)?;
+    let prefer_smaller = match args.get("preferSmaller") {
+        None | Some(Value::Null) => false,
+        Some(Value::Bool(prefer_smaller)) => *prefer_smaller,
+        Some(value) => return Err(format!("'preferSmaller' must be true or false, got: {value}")),
+    };
+    let clip = clip_arg(args)?.map(|clip| clamp_clip(window, clip)).transpose()?;
+    let watermark = watermark_arg(args)?;
+
+    if prefer_smaller {
+        let capture_window = window.clone();
+        let jpeg_quality = quality.unwrap_or(PREFER_SMALLER_JPEG_QUALITY);
+        let mut result = tokio::task::spawn_blocking(move || {
+            capture_smaller(&capture_window, jpeg_quality, clip, watermark.as_ref())
+        })
+        .await
+        .map_err(|e| e.to_string())??;
+        if let (Some(clip), Some(fields)) = (clip, result.as_object_mut()) {
+            fields.insert("clipped".to_string(), Value::Bool(true));
+            fields.insert("clip".to_string(), clip_json(clip));
+        }
+        return Ok(result);
+    }
 
-    let mime = match format {
+    // Capturing blocks until the webview delivers the image, and JPEG and base64 encoding of
+    // Retina-sized images can take 50-100 ms, so keep both off the async workers
+    let capture_window = window.clone();
+    let capture_format = format.clone();
+    let data = tokio::task::spawn_blocking(move || {
+        watermark.as_ref().map_or_else(
+            || screenshot_impl::capture(&capture_window, &capture_format, quality, clip),
+            |watermark| capture_watermarked(&capture_window, &capture_format, quality, clip, watermark),
+        )
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    let mime = match format.as_str() {
         "jpeg" | "jpg" => "image/jpeg",
         _ => "image/png",
     };
+    let image = format!("data:{mime};base64,{data}");
+
+    Ok(match clip {
+        Some(clip) => json!({ "image": image, "clipped": true, "clip": clip_json(clip) }),
+        None => Value::String(image),
+    })
+}
+
+/// Get the optional `maskSelectors` argument: the elements to hide, as CSS selectors or selector
+/// objects
+fn mask_selectors_arg(args: &Value) -> Result<Option<&Value>, String> {
+    let selectors = match args.get("maskSelectors") {
+        None | Some(Value::Null) => return Ok(None),
+        Some(Value::Array(selectors)) => selectors,
+        Some(value) => return Err(format!("'maskSelectors' must be an array of selectors, got: {value}")),
+    };
+    for (i, selector) in selectors.iter().enumerate() {
+        let name = format!("maskSelectors[{i}]");
+        if !execute_js::selector_arg(&json!({ name.as_str(): selector }), &name)? {
+            return Err(format!(
+                "'maskSelectors[{i}]' must be a non-empty selector, got: {selector}"
+            ));
+        }
+    }
+    Ok(Some(&args["maskSelectors"]))
+}
+
+/// Build the script that hides the elements the selectors match
+fn mask_script(selectors: &Value) -> Result<String, String> {
+    let a11y = include_str!("../scripts/a11y.js");
+    let selector_script = include_str!("../scripts/selector.js");
+    let script = include_str!("../scripts/screenshot-mask.js");
+    let selectors_json = serde_json::to_string(selectors).map_err(|e| e.to_string())?;
+
+    Ok(format!(
+        r"
+        {a11y}
+        {selector_script}
+        {script}
+        return await window.__tauriMcpMask({selectors_json})
+        "
+    ))
+}
+
+/// Build the script that shows the masked elements again
+fn unmask_script() -> String {
+    let script = include_str!("../scripts/screenshot-mask.js");
+
+    format!(
+        r"
+        {script}
+        return window.__tauriMcpUnmask()
+        "
+    )
+}
+
+/// Get the optional `clip` argument: `{ x, y, width, height }` in logical pixels
+fn clip_arg(args: &Value) -> Result<Option<ClipRect>, String> {
+    let clip = match args.get("clip") {
+        None | Some(Value::Null) => return Ok(None),
+        Some(clip) => clip,
+    };
+    let field = |name: &str| clip.get(name).and_then(Value::as_f64);
+    match (field("x"), field("y"), field("width"), field("height")) {
+        (Some(x), Some(y), Some(width), Some(height)) if width > 0.0 && height > 0.0 => {
+            Ok(Some(ClipRect { x, y, width, height }))
+        }
+        _ => Err(format!(
+            "'clip' must be {{ \"x\": ..., \"y\": ..., \"width\": ..., \"height\": ... }} in logical pixels, \
+             with a positive width and height, got: {clip}"
+        )),
+    }
+}
+
+/// Get the optional `watermark` argument: `{ text, position?, opacity? }`
+fn watermark_arg(args: &Value) -> Result<Option<Watermark>, String> {
+    let watermark = match args.get("watermark") {
+        None | Some(Value::Null) => return Ok(None),
+        Some(Value::Object(watermark)) => watermark,
+        Some(value) => {
+            return Err(format!(
+                "'watermark' must be {{ \"text\": ..., \"position\": ..., \"opacity\": ... }}, got: {value}"
+            ))
+        }
+    };
+
+    let text = match watermark.get("text").and_then(Value::as_str).map(str::trim) {
+        Some(text) if !text.is_empty() && text.chars().count() <= MAX_WATERMARK_CHARS => text,
+        _ => {
+            return Err(format!(
+                "'watermark.text' must be non-empty text of up to {MAX_WATERMARK_CHARS} characters"
+            ))
+        }
+    };
+    let position = match watermark.get("position") {
+        None | Some(Value::Null) => Position::BottomRight,
+        Some(value) => value.as_str().and_then(Position::parse).ok_or_else(|| {
+            format!(
+                "Invalid 'watermark.position': {value}. Available: {}",
+                Position::NAMES.join(", ")
+            )
+        })?,
+    };
+    let opacity = match watermark.get("opacity") {
+        None | Some(Value::Null) => DEFAULT_WATERMARK_OPACITY,
+        Some(value) => value
+            .as_f64()
+            .filter(|opacity| (0.0..=1.0).contains(opacity))
+            .ok_or_else(|| format!("'watermark.opacity' must be a number from 0 to 1, got: {value}"))?,
+    };
+    if let Some(key) = watermark
+        .keys()
+        .find(|key| !matches!(key.as_str(), "text" | "position" | "opacity"))
+    {
+        return Err(format!(
+            "Unexpected 'watermark.{key}'. Use text, position, and opacity."
+        ));
+    }
+
+    Ok(Some(Watermark {
+        // Control characters like newlines have no glyphs, and the label is one line
+        text: text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect(),
+        position,
+        #[allow(clippy::cast_possible_truncation)]
+        opacity: opacity as f32,
+    }))
+}
+
+/// Clamp the clip to the window's webview area, since parts outside it can't be captured
+fn clamp_clip<R: Runtime>(window: &WebviewWindow<R>, clip: ClipRect) -> Result<ClipRect, String> {
+    let size = screenshot_impl::logical_inner_size(window)?;
+    clip.clamp_to(size.width, size.height).ok_or_else(|| {
+        format!(
+            "'clip' is entirely outside the {}x{} viewport",
+            size.width.round(),
+            size.height.round()
+        )
+    })
+}
+
+/// The clip actually applied, for the response
+fn clip_json(clip: ClipRect) -> Value {
+    json!({ "x": clip.x, "y": clip.y, "width": clip.width, "height": clip.height })
+}
+
+/// Capture as PNG and draw the watermark on it, then encode in the requested format. Blocks
+/// until the capture is done.
+fn capture_watermarked<R: Runtime>(
+    window: &WebviewWindow<R>,
+    format: &str,
+    quality: Option<u8>,
+    clip: Option<ClipRect>,
+    watermark: &Watermark,
+) -> Result<String, String> {
+    let png = capture_png(window, clip, Some(watermark))?;
+    let data = match format {
+        "jpeg" | "jpg" => screenshot_impl::png_to_jpeg(&png, quality.unwrap_or(DEFAULT_JPEG_QUALITY))?,
+        _ => png,
+    };
+    Ok(base64::engine::general_purpose::STANDARD.encode(data))
+}
+
+/// Capture as PNG bytes, with the watermark drawn on if there is one. Blocks until the capture
+/// is done.
+fn capture_png<R: Runtime>(
+    window: &WebviewWindow<R>,
+    clip: Option<ClipRect>,
+    watermark: Option<&Watermark>,
+) -> Result<Vec<u8>, String> {
+    let png = base64::engine::general_purpose::STANDARD
+        .decode(screenshot_impl::capture(window, "png", None, clip)?)
+        .map_err(|e| format!("Screenshot isn't valid base64: {e}"))?;
+    let Some(watermark) = watermark else {
+        return Ok(png);
+    };
+
+    let mut image = composite::decode_png(&png)?;
+    #[allow(clippy::cast_possible_truncation)]
+    let scale = window.scale_factor().map_err(|e| e.to_string())? as f32;
+    watermark::draw(&mut image, watermark, scale)?;
+    composite::encode_png(&image)
+}
+
+/// Capture as PNG, re-encode as JPEG, and return whichever is smaller. Blocks until the capture
+/// is done.
+fn capture_smaller<R: Runtime>(
+    window: &WebviewWindow<R>,
+    jpeg_quality: u8,
+    clip: Option<ClipRect>,
+    watermark: Option<&Watermark>,
+) -> Result<Value, String> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let png = capture_png(window, clip, watermark)?;
+    let jpeg = screenshot_impl::png_to_jpeg(&png, jpeg_quality)?;
+
+    let (format, data) = smaller_encoding(&png, &jpeg);
+    let mime = if format == "jpeg" { "image/jpeg" } else { "image/png" };
+
+    Ok(json!({
+        "image": format!("data:{mime};base64,{}", engine.encode(data)),
+        "formatChosen": format,
+        "pngBytes": png.len(),
+        "jpegBytes": jpeg.len(),
+    }))
+}
+
+/// Pick the smaller of the two encodings, preferring lossless PNG on a tie
+const fn smaller_encoding<'a>(png: &'a [u8], jpeg: &'a [u8]) -> (&'static str, &'a [u8]) {
+    if jpeg.len() < png.len() {
+        ("jpeg", jpeg)
+    } else {
+        ("png", png)
+    }
+}
+
+/// Capture all visible windows and lay them out as they are on the desktop, in one PNG. Windows
+/// that fail to capture are listed under `skipped`; the focused window is painted on top.
+pub async fn composite<R: Runtime>(app: &AppHandle<R>, args: &Value) -> Result<Value, String> {
+    let padding = composite_padding(args)?;
+
+    // Like `execute`, the captures and the compositing run on a blocking thread
+    let app = app.clone();
+    tokio::task::spawn_blocking(move || capture_composite(&app, padding))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Get the optional `padding` argument of `screenshot_composite`
+fn composite_padding(args: &Value) -> Result<u32, String> {
+    match args.get("padding") {
+        None | Some(Value::Null) => Ok(0),
+        Some(value) => value
+            .as_u64()
+            .filter(|padding| *padding <= MAX_COMPOSITE_PADDING)
+            .and_then(|padding| u32::try_from(padding).ok())
+            .ok_or_else(|| format!("'padding' must be an integer from 0 to {MAX_COMPOSITE_PADDING}, got: {value}")),
+    }
+}
+
+/// Capture the windows and composite them. Blocks until every capture is done.
+fn capture_composite<R: Runtime>(app: &AppHandle<R>, padding: u32) -> Result<Value, String> {
+    let mut windows: Vec<_> = app
+        .webview_windows()
+        .into_values()
+        .filter(|window| window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false))
+        .collect();
+    windows.sort_by_key(|window| (window.is_focused().unwrap_or(false), window.label().to_string()));
+
+    let mut layers = Vec::new();
+    let mut skipped = Vec::new();
+    for window in &windows {
+        match capture_layer(window) {
+            Ok(layer) => layers.push(layer),
+            Err(reason) => skipped.push(json!({ "label": window.label(), "reason": reason })),
+        }
+    }
+    if layers.is_empty() {
+        return Err(if windows.is_empty() {
+            "No visible windows to capture".to_string()
+        } else {
+            format!("Couldn't capture any window: {}", Value::Array(skipped))
+        });
+    }
+
+    let (canvas, placements) = composite::compose(&layers, padding)?;
+    let data = base64::engine::general_purpose::STANDARD.encode(composite::encode_png(&canvas)?);
+    let windows: Vec<Value> = placements
+        .iter()
+        .map(|placed| {
+            json!({
+                "label": placed.label,
+                "x": placed.x,
+                "y": placed.y,
+                "width": placed.width,
+                "height": placed.height,
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "image": format!("data:image/png;base64,{data}"),
+        "width": canvas.width,
+        "height": canvas.height,
+        "windows": windows,
+        "skipped": skipped,
+    }))
+}
+
+/// Capture a window as a PNG and decode it, with its position on the desktop
+fn capture_layer<R: Runtime>(window: &WebviewWindow<R>) -> Result<Layer, String> {
+    let position = window.outer_position().map_err(|e| e.to_string())?;
+    let png = base64::engine::general_purpose::STANDARD
+        .decode(screenshot_impl::capture(window, "png", None, None)?)
+        .map_err(|e| format!("Screenshot isn't valid base64: {e}"))?;
+
+    Ok(Layer {
+        label: window.label().to_string(),
+        x: position.x,
+        y: position.y,
+        image: composite::decode_png(&png)?,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smaller_encoding_picks_fewer_bytes() {
+        assert_eq!(smaller_encoding(&[0; 10], &[1; 4]), ("jpeg", &[1u8; 4][..]));
+        assert_eq!(smaller_encoding(&[0; 4], &[1; 10]), ("png", &[0u8; 4][..]));
+    }
+
+    #[test]
+    fn smaller_encoding_prefers_png_on_tie() {
+        assert_eq!(smaller_encoding(&[0; 4], &[1; 4]).0, "png");
+    }
+
+    #[test]
+    fn mask_selectors_arg_takes_css_and_selector_objects() {
+        assert_eq!(mask_selectors_arg(&json!({})).unwrap(), None);
+        let args = json!({ "maskSelectors": [".password-field", { "role": "textbox", "name": "Card number" }] });
+        assert_eq!(mask_selectors_arg(&args).unwrap(), Some(&args["maskSelectors"]));
+
+        let err = mask_selectors_arg(&json!({ "maskSelectors": "#card" })).unwrap_err();
+        assert!(err.contains("must be an array of selectors"));
+        let err = mask_selectors_arg(&json!({ "maskSelectors": ["#card", " "] })).unwrap_err();
+        assert!(err.contains("'maskSelectors[1]' must be a non-empty selector"));
+        let err = mask_selectors_arg(&json!({ "maskSelectors": [{ "css": "a", "text": "b" }] })).unwrap_err();
+        assert!(err.contains("'maskSelectors[0]' must have exactly one of"));
+    }
+
+    #[test]
+    fn watermark_arg_fills_in_defaults() {
+        assert_eq!(watermark_arg(&json!({})).unwrap(), None);
+        assert_eq!(
+            watermark_arg(&json!({ "watermark": { "text": "CI Build #1234\nmain" } })).unwrap(),
+            Some(Watermark {
+                text: "CI Build #1234 main".to_string(),
+                position: Position::BottomRight,
+                opacity: 0.7
+            })
+        );
+        assert_eq!(
+            watermark_arg(&json!({ "watermark": { "text": "PR 42", "position": "top_left", "opacity": 0.5 } }))
+                .unwrap()
+                .map(|watermark| (watermark.position, watermark.opacity)),
+            Some((Position::TopLeft, 0.5))
+        );
+    }
+
+    #[test]
+    fn watermark_arg_rejects_bad_fields() {
+        for (watermark, message) in [
+            (json!("CI"), "'watermark' must be"),
+            (json!({ "text": "  " }), "'watermark.text' must be non-empty"),
+            (
+                json!({ "text": "CI", "position": "middle" }),
+                "Invalid 'watermark.position'",
+            ),
+            (
+                json!({ "text": "CI", "opacity": 1.5 }),
+                "'watermark.opacity' must be a number from 0 to 1",
+            ),
+            (json!({ "text": "CI", "color": "red" }), "Unexpected 'watermark.color'"),
+        ] {
+            let err = watermark_arg(&json!({ "watermark": watermark })).unwrap_err();
+            assert!(err.contains(message), "{err}");
+        }
+    }
+
+    #[test]
+    fn clip_arg_reads_the_rectangle() {
+        assert_eq!(clip_arg(&json!({})).unwrap(), None);
+        assert_eq!(
+            clip_arg(&json!({ "clip": { "x": 100, "y": 50.5, "width": 400, "height": 300 } })).unwrap(),
+            Some(ClipRect {
+                x: 100.0,
+                y: 50.5,
+                width: 400.0,
+                height: 300.0
+            })
+        );
+    }
+
+    #[test]
+    fn clip_arg_rejects_incomplete_or_empty_rectangles() {
+        let err = clip_arg(&json!({ "clip": { "x": 0, "y": 0, "width": 10 } })).unwrap_err();
+        assert!(err.contains("'clip' must be"));
+        let err = clip_arg(&json!({ "clip": { "x": 0, "y": 0, "width": 0, "height": 10 } })).unwrap_err();
+        assert!(err.contains("positive width and height"));
+        assert!(clip_arg(&json!({ "clip": [0, 0, 10, 10] })).is_err());
+    }
 
-    Ok(Value::String(format!("data:{mime};base64,{data}")))
+    #[test]
+    fn composite_padding_is_optional_and_bounded() {
+        assert_eq!(composite_padding(&json!({})).unwrap(), 0);
+        assert_eq!(composite_padding(&json!({ "padding": 20 })).unwrap(), 20);
+        assert!(composite_padding(&json!({ "padding": 201 }))
+            .unwrap_err()
+            .contains("'padding' must be an integer from 0 to 200"));
+    }
 }
@@ -0,0 +1,336 @@
+//! Paging for `dom_snapshot` results too big for one response.
+//!
+//! With `pageSize`, the snapshot is taken once and split into pages of that many nodes, which stay
+//! in the plugin. Each page but the last has a `nextPage` token, and `dom_snapshot` with that token
+//! as `page` returns the next page of the same snapshot, so the pages fit together even if the DOM
+//! changes in between. Only the last few paged snapshots are kept.
+//!
+//! The YAML format is split between its `- ` items and the structure format between its lines, so
+//! the pages joined with newlines are the whole snapshot. The JSON tree becomes a list of its nodes
+//! in document order, each with its `depth` instead of its `children`.
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, PoisonError};
+
+use serde_json::{Map, Value};
+use tauri::{Manager, Runtime, WebviewWindow};
+
+use super::execute_js;
+
+/// How many paged snapshots are kept, dropping the oldest first
+pub const MAX_PAGED_SNAPSHOTS: usize = 5;
+
+/// Snapshots split into pages, for `dom_snapshot` with `page`
+#[derive(Debug, Default)]
+pub struct SnapshotPages(Mutex<Paged>);
+
+#[derive(Debug, Default)]
+struct Paged {
+    next_id: u64,
+    snapshots: VecDeque<(u64, PagedSnapshot)>,
+}
+
+#[derive(Debug, Clone)]
+struct PagedSnapshot {
+    pages: Vec<Value>,
+    /// The rest of the result, like `truncated` or `snapshotId`, which comes with every page
+    details: Map<String, Value>,
+}
+
+impl SnapshotPages {
+    /// Keep a snapshot's pages, returning its ID
+    fn insert(&self, snapshot: PagedSnapshot) -> u64 {
+        let mut paged = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        paged.next_id += 1;
+        let id = paged.next_id;
+        if paged.snapshots.len() == MAX_PAGED_SNAPSHOTS {
+            paged.snapshots.pop_front();
+        }
+        paged.snapshots.push_back((id, snapshot));
+        id
+    }
+
+    fn get(&self, id: u64) -> Option<PagedSnapshot> {
+        let paged = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        paged
+            .snapshots
+            .iter()
+            .find(|(paged_id, _)| *paged_id == id)
+            .map(|(_, snapshot)| snapshot.clone())
+    }
+
+    /// Split a snapshot script's result into pages and keep them, returning the first
+    fn split_and_keep(&self, args: &Value, result: Value, page_size: usize) -> Result<Value, String> {
+        // Snapshots that weren't cut or stored are the plain snapshot, and JSON ones their tree
+        let (snapshot, details) = match result {
+            Value::Object(mut details) if details.contains_key("snapshot") => {
+                (details.remove("snapshot").unwrap_or_default(), details)
+            }
+            snapshot => (snapshot, Map::new()),
+        };
+        if snapshot.is_null() {
+            return Err("The snapshot script didn't return a snapshot to page".to_string());
+        }
+        let yaml = args.get("type").and_then(Value::as_str).unwrap_or("accessibility") == "accessibility";
+        let snapshot = PagedSnapshot {
+            pages: split(snapshot, page_size, yaml),
+            details,
+        };
+        let id = self.insert(snapshot.clone());
+        Ok(page_of(&snapshot, id, 1))
+    }
+
+    /// Get the page of a kept snapshot that a `nextPage` token points to
+    fn page(&self, (id, page): (u64, usize)) -> Result<Value, String> {
+        let snapshot = self.get(id).ok_or_else(|| {
+            format!(
+                "Page {} is gone, as only the last {MAX_PAGED_SNAPSHOTS} paged snapshots are kept. Take the snapshot again with 'pageSize'.",
+                token(id, page)
+            )
+        })?;
+        if page > snapshot.pages.len() {
+            return Err(format!(
+                "The snapshot of page {} only has {} pages",
+                token(id, page),
+                snapshot.pages.len()
+            ));
+        }
+        Ok(page_of(&snapshot, id, page))
+    }
+}
+
+/// Check the optional `pageSize` of a `dom_snapshot`, which the html and markdown formats don't
+/// take as they have no nodes to count
+pub(super) fn page_size_arg(args: &Value) -> Result<Option<usize>, String> {
+    let Some(page_size) = execute_js::limit_arg(args, "pageSize", 1)? else {
+        return Ok(None);
+    };
+    if let Some(format @ ("html" | "markdown")) = args.get("format").and_then(Value::as_str) {
+        return Err(format!(
+            "'pageSize' needs the yaml or json format, not {format}. Use 'maxLength' to cut it instead."
+        ));
+    }
+    Ok(Some(usize::try_from(page_size).unwrap_or(usize::MAX)))
+}
+
+/// Check the optional `page` of a `dom_snapshot`, a `nextPage` token from an earlier page
+pub(super) fn page_arg(args: &Value) -> Result<Option<(u64, usize)>, String> {
+    match args.get("page") {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_str()
+            .and_then(parse_token)
+            .map(Some)
+            .ok_or_else(|| format!("'page' must be the nextPage token of an earlier page, got: {value}")),
+    }
+}
+
+/// A token is `pages-<id>:<page>`, counting pages from 1
+fn token(id: u64, page: usize) -> String {
+    format!("pages-{id}:{page}")
+}
+
+fn parse_token(token: &str) -> Option<(u64, usize)> {
+    let (id, page) = token.strip_prefix("pages-")?.split_once(':')?;
+    Some((id.parse().ok()?, page.parse().ok().filter(|&page| page >= 1)?))
+}
+
+/// Split a snapshot's result into pages of `page_size` nodes and keep them, returning the first
+pub(super) fn first_page<R: Runtime>(
+    window: &WebviewWindow<R>,
+    args: &Value,
+    result: Value,
+    page_size: usize,
+) -> Result<Value, String> {
+    let store = window
+        .app_handle()
+        .try_state::<SnapshotPages>()
+        .ok_or("Snapshot pages not initialized")?;
+    store.split_and_keep(args, result, page_size)
+}
+
+/// Get a page of a paged snapshot from a `nextPage` token
+pub(super) fn page<R: Runtime>(window: &WebviewWindow<R>, token: (u64, usize)) -> Result<Value, String> {
+    let store = window
+        .app_handle()
+        .try_state::<SnapshotPages>()
+        .ok_or("Snapshot pages not initialized")?;
+    store.page(token)
+}
+
+/// `{ snapshot, page, pages, nextPage }` with the rest of the snapshot's result, where the last
+/// page has no `nextPage`
+fn page_of(snapshot: &PagedSnapshot, id: u64, page: usize) -> Value {
+    let mut result = snapshot.details.clone();
+    result.insert("snapshot".to_string(), snapshot.pages[page - 1].clone());
+    result.insert("page".to_string(), page.into());
+    result.insert("pages".to_string(), snapshot.pages.len().into());
+    if page < snapshot.pages.len() {
+        result.insert("nextPage".to_string(), token(id, page + 1).into());
+    }
+    Value::Object(result)
+}
+
+/// Split a snapshot into pages of `page_size` nodes, always at least one
+fn split(snapshot: Value, page_size: usize, yaml: bool) -> Vec<Value> {
+    let pages: Vec<Value> = match snapshot {
+        Value::String(text) if yaml => text_pages(&text, page_size, |line| line.starts_with("- ")),
+        Value::String(text) => text_pages(&text, page_size, |_| true),
+        tree => {
+            let mut nodes = Vec::new();
+            flatten(tree, 0, &mut nodes);
+            nodes
+                .chunks(page_size)
+                .map(|nodes| Value::Array(nodes.to_vec()))
+                .collect()
+        }
+    };
+    if pages.is_empty() {
+        return vec![Value::Array(Vec::new())];
+    }
+    pages
+}
+
+/// Split text between lines, at most `page_size` of them starting a node per page
+fn text_pages(text: &str, page_size: usize, starts_node: impl Fn(&str) -> bool) -> Vec<Value> {
+    let mut pages = Vec::new();
+    let mut page: Vec<&str> = Vec::new();
+    let mut nodes = 0;
+    for line in text.split('\n') {
+        if starts_node(line) {
+            if nodes == page_size {
+                pages.push(page.join("\n").into());
+                page.clear();
+                nodes = 0;
+            }
+            nodes += 1;
+        }
+        page.push(line);
+    }
+    pages.push(page.join("\n").into());
+    pages
+}
+
+/// Add a JSON node and the nodes under it in document order, each with its depth instead of its
+/// children
+fn flatten(node: Value, depth: u64, nodes: &mut Vec<Value>) {
+    let Value::Object(mut node) = node else {
+        return;
+    };
+    let children = node.remove("children");
+    node.insert("depth".to_string(), depth.into());
+    nodes.push(Value::Object(node));
+    if let Some(Value::Array(children)) = children {
+        for child in children {
+            flatten(child, depth + 1, nodes);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn yaml_pages_keep_items_whole() {
+        let yaml = "- tag: main\n  role: main\n- tag: button\n  name: Save\n- tag: a\n  role: link";
+        let pages = split(yaml.into(), 2, true);
+        assert_eq!(
+            pages,
+            vec![
+                json!("- tag: main\n  role: main\n- tag: button\n  name: Save"),
+                json!("- tag: a\n  role: link"),
+            ]
+        );
+        let joined: Vec<&str> = pages.iter().map(|page| page.as_str().unwrap()).collect();
+        assert_eq!(joined.join("\n"), yaml);
+    }
+
+    #[test]
+    fn structure_pages_are_lines() {
+        let tree = "main\n├─ h1\n└─ ul\n   └─ li";
+        assert_eq!(
+            split(tree.into(), 3, false),
+            vec![json!("main\n├─ h1\n└─ ul"), json!("   └─ li")]
+        );
+    }
+
+    #[test]
+    fn json_pages_list_nodes_with_their_depth() {
+        let tree = json!({
+            "tag": "ul",
+            "children": [
+                { "tag": "li", "name": "One", "children": [] },
+                { "tag": "li", "name": "Two", "children": [{ "tag": "a", "children": [] }] },
+            ],
+        });
+        let pages = split(tree, 2, true);
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0][1], json!({ "tag": "li", "name": "One", "depth": 1 }));
+        assert_eq!(pages[1][1], json!({ "tag": "a", "depth": 2 }));
+    }
+
+    #[test]
+    fn pages_have_tokens_up_to_the_last() {
+        let snapshot = PagedSnapshot {
+            pages: vec![json!("a"), json!("b")],
+            details: Map::from_iter([("truncated".to_string(), json!(true))]),
+        };
+        let first = page_of(&snapshot, 4, 1);
+        assert_eq!(first["nextPage"], "pages-4:2");
+        assert_eq!(first["truncated"], true);
+        assert_eq!(parse_token("pages-4:2"), Some((4, 2)));
+        let last = page_of(&snapshot, 4, 2);
+        assert_eq!(
+            (last["snapshot"].clone(), last["pages"].clone()),
+            (json!("b"), json!(2))
+        );
+        assert!(last.get("nextPage").is_none());
+        for garbage in ["pages-4:0", "pages-x:1", "snapshot-4", ""] {
+            assert_eq!(parse_token(garbage), None);
+        }
+    }
+
+    #[test]
+    fn snapshots_are_paged_through_with_their_tokens() {
+        let pages = SnapshotPages::default();
+        let args = json!({ "type": "structure", "pageSize": 2 });
+        // What the snapshot script returns for a structure snapshot cut by maxNodes
+        let tree = "main\n├─ h1\n├─ ul\n│  └─ li\n└─ footer";
+        let result = json!({ "snapshot": tree, "truncated": true, "totalNodes": 9, "shownNodes": 5 });
+
+        let mut response = pages.split_and_keep(&args, result, 2).unwrap();
+        let mut snapshots = Vec::new();
+        loop {
+            assert_eq!(response["truncated"], true);
+            snapshots.push(response["snapshot"].as_str().unwrap().to_string());
+            assert_eq!(response["page"], snapshots.len());
+            let Some(next) = response.get("nextPage") else {
+                break;
+            };
+            response = pages.page(parse_token(next.as_str().unwrap()).unwrap()).unwrap();
+        }
+        assert_eq!(snapshots.len(), 3);
+        assert_eq!(snapshots.join("\n"), tree);
+        assert!(pages.page((1, 4)).unwrap_err().contains("only has 3 pages"));
+        assert!(pages.page((2, 1)).unwrap_err().contains("is gone"));
+
+        assert!(pages
+            .split_and_keep(&args, Value::Null, 2)
+            .unwrap_err()
+            .contains("didn't return a snapshot"));
+    }
+
+    #[test]
+    fn page_args_are_checked() {
+        assert_eq!(page_size_arg(&json!({ "pageSize": 100 })), Ok(Some(100)));
+        assert!(page_size_arg(&json!({ "pageSize": 0 })).is_err());
+        assert!(page_size_arg(&json!({ "pageSize": 10, "format": "html" }))
+            .unwrap_err()
+            .contains("yaml or json"));
+        assert_eq!(page_arg(&json!({ "page": "pages-1:3" })), Ok(Some((1, 3))));
+        assert!(page_arg(&json!({ "page": 3 })).unwrap_err().contains("nextPage token"));
+    }
+}
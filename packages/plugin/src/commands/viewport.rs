@@ -0,0 +1,191 @@
+//! Viewport emulation for responsive testing.
+//!
+//! `viewport_set` resizes the window to the requested logical size and overrides the page's
+//! viewport meta tag and `devicePixelRatio`. The page overrides last until the next page load;
+//! the window size stays.
+
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tauri::{LogicalSize, Runtime, Size, WebviewWindow};
+
+use super::execute_js::{eval_with_result, eval_with_result_fast};
+
+/// Parsed `viewport_set` arguments
+#[derive(Debug, PartialEq)]
+struct ViewportArgs {
+    /// Viewport width in logical (CSS) pixels
+    width: u32,
+    /// Viewport height in logical (CSS) pixels
+    height: u32,
+    /// Value reported as `devicePixelRatio` (the real one if `None`)
+    device_scale_factor: Option<f64>,
+    /// Emulate a mobile viewport meta tag
+    mobile: bool,
+}
+
+impl ViewportArgs {
+    fn parse(args: &Value) -> Result<Self, String> {
+        let width = dimension_arg(args, "width")?;
+        let height = dimension_arg(args, "height")?;
+
+        let device_scale_factor = match args.get("deviceScaleFactor") {
+            None | Some(Value::Null) => None,
+            Some(value) => Some(
+                value
+                    .as_f64()
+                    .filter(|factor| *factor > 0.0)
+                    .ok_or_else(|| format!("'deviceScaleFactor' must be a positive number, got: {value}"))?,
+            ),
+        };
+
+        let mobile = match args.get("mobile") {
+            None | Some(Value::Null) => false,
+            Some(value) => value
+                .as_bool()
+                .ok_or_else(|| format!("'mobile' must be a boolean, got: {value}"))?,
+        };
+
+        Ok(Self {
+            width,
+            height,
+            device_scale_factor,
+            mobile,
+        })
+    }
+}
+
+/// Get a required positive integer dimension
+fn dimension_arg(args: &Value, name: &str) -> Result<u32, String> {
+    let value = args
+        .get(name)
+        .ok_or_else(|| format!("Missing required '{name}' argument"))?;
+    value
+        .as_u64()
+        .filter(|n| *n > 0)
+        .and_then(|n| u32::try_from(n).ok())
+        .ok_or_else(|| format!("'{name}' must be a positive integer, got: {value}"))
+}
+
+/// Emulate a viewport size, device scale factor, and mobile mode
+pub async fn set<R: Runtime>(window: &WebviewWindow<R>, args: &Value, timeout: Duration) -> Result<Value, String> {
+    let viewport = ViewportArgs::parse(args)?;
+
+    window
+        .set_size(Size::Logical(LogicalSize::new(
+            f64::from(viewport.width),
+            f64::from(viewport.height),
+        )))
+        .map_err(|e| e.to_string())?;
+
+    // A leftover pinch zoom would make the CSS viewport differ from the window size
+    #[cfg(target_os = "macos")]
+    reset_magnification(window)?;
+
+    let script = include_str!("../scripts/viewport.js");
+    let args_json = json!({
+        "width": viewport.width,
+        "height": viewport.height,
+        "deviceScaleFactor": viewport.device_scale_factor,
+        "mobile": viewport.mobile,
+    });
+
+    let full_script = format!(
+        r"
+        {script}
+        return await window.__tauriMcpViewportSet({args_json})
+        "
+    );
+
+    eval_with_result(window, &full_script, timeout).await
+}
+
+/// Get the effective viewport as the page sees it, plus the window's logical size
+pub async fn get<R: Runtime>(window: &WebviewWindow<R>, timeout: Duration) -> Result<Value, String> {
+    let script = include_str!("../scripts/viewport.js");
+
+    let full_script = format!(
+        r"
+        {script}
+        return window.__tauriMcpViewportGet()
+        "
+    );
+
+    let mut viewport = eval_with_result_fast(window, &full_script, timeout).await?;
+
+    let scale_factor = window.scale_factor().map_err(|e| e.to_string())?;
+    let size = window
+        .inner_size()
+        .map_err(|e| e.to_string())?
+        .to_logical::<f64>(scale_factor);
+
+    if let Some(object) = viewport.as_object_mut() {
+        object.insert("windowWidth".to_string(), json!(size.width));
+        object.insert("windowHeight".to_string(), json!(size.height));
+        object.insert("nativeScaleFactor".to_string(), json!(scale_factor));
+    }
+
+    Ok(viewport)
+}
+
+/// Reset the `WKWebView` magnification to 1
+#[cfg(target_os = "macos")]
+#[allow(unsafe_code)]
+fn reset_magnification<R: Runtime>(window: &WebviewWindow<R>) -> Result<(), String> {
+    use objc2_web_kit::WKWebView;
+
+    window
+        .with_webview(|webview| {
+            // Safety: Tauri's webview handle on macOS is a valid WKWebView, and with_webview
+            // runs the callback on the main thread (see screenshot/macos.rs)
+            unsafe {
+                let wkwebview: &WKWebView = &*(webview.inner().cast::<WKWebView>());
+                wkwebview.setMagnification(1.0);
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_applies_defaults() {
+        let viewport = ViewportArgs::parse(&json!({ "width": 375, "height": 667 })).unwrap();
+        assert_eq!(
+            viewport,
+            ViewportArgs {
+                width: 375,
+                height: 667,
+                device_scale_factor: None,
+                mobile: false,
+            }
+        );
+    }
+
+    #[test]
+    fn parse_reads_scale_factor_and_mobile() {
+        let viewport =
+            ViewportArgs::parse(&json!({ "width": 390, "height": 844, "deviceScaleFactor": 3, "mobile": true }))
+                .unwrap();
+        assert_eq!(viewport.device_scale_factor, Some(3.0));
+        assert!(viewport.mobile);
+    }
+
+    #[test]
+    fn parse_requires_positive_dimensions() {
+        let err = ViewportArgs::parse(&json!({ "height": 667 })).unwrap_err();
+        assert!(err.contains("Missing required 'width'"));
+
+        let err = ViewportArgs::parse(&json!({ "width": 0, "height": 667 })).unwrap_err();
+        assert!(err.contains("'width' must be a positive integer"));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_scale_factor() {
+        let err = ViewportArgs::parse(&json!({ "width": 375, "height": 667, "deviceScaleFactor": -1 })).unwrap_err();
+        assert!(err.contains("'deviceScaleFactor' must be a positive number"));
+    }
+}
@@ -0,0 +1,257 @@
+//! `wait_for` conditions checked on the Rust side.
+//!
+//! Some readiness signals only exist in the app, not the page: windows opening or closing,
+//! Tauri events, console entries in the Rust-side buffers. Waiting for them doesn't involve the
+//! webview, so it works while the page is busy or reloading. Everything else goes to the page
+//! through `execute_js::wait_for`.
+//!
+//! The waits end when the condition is met or `timeout` passes, and stop listening when the
+//! request is dropped, like when the connection closes. Like the ones in the page, they return
+//! `{ success, message, elapsedMs, polls, condition, detail }`, and their timeout errors say
+//! what the last check saw.
+
+use std::future::Future;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, EventId, Listener, Manager, Runtime, WebviewWindow};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::oneshot;
+use tokio::time::Instant;
+
+use super::{console, execute_js};
+use crate::subscriptions::event_arg;
+use crate::windows::WindowRegistry;
+
+/// Wait for a condition, on the Rust side if it's about the app and in the page otherwise
+pub async fn wait_for<R: Runtime>(window: &WebviewWindow<R>, args: &Value) -> Result<Value, String> {
+    let app = window.app_handle();
+    let wait_timeout = Duration::from_millis(execute_js::wait_timeout_ms(args));
+    match args.get("type").and_then(Value::as_str) {
+        Some("console") => console::wait_for(app, window.label(), args, Progress::new(wait_timeout)).await,
+        Some("windowCount") => window_count(app, WindowCount::parse(args)?, Progress::new(wait_timeout)).await,
+        Some("tauriEvent") => tauri_event(app, event_arg(args)?, Progress::new(wait_timeout)).await,
+        _ => execute_js::wait_for(window, args).await,
+    }
+}
+
+/// How a Rust-side wait is going: when it started and how often it checked the condition
+pub struct Progress {
+    started: Instant,
+    timeout: Duration,
+    polls: u64,
+}
+
+impl Progress {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            started: Instant::now(),
+            timeout,
+            polls: 0,
+        }
+    }
+
+    /// Count a check of the condition
+    pub const fn poll(&mut self) {
+        self.polls += 1;
+    }
+
+    /// Wait for `future`, or get `None` once the timeout passes
+    pub async fn within<T>(&self, future: impl Future<Output = T>) -> Option<T> {
+        tokio::time::timeout_at(self.started + self.timeout, future).await.ok()
+    }
+
+    /// Result of a wait whose condition was met, where `detail` is what the last check saw
+    pub fn met(&self, condition: &str, message: &str, detail: &str) -> Value {
+        json!({
+            "success": true,
+            "message": message,
+            "elapsedMs": millis(self.started.elapsed()),
+            "polls": self.polls,
+            "condition": condition,
+            "detail": detail,
+        })
+    }
+
+    /// Error for a wait that timed out, where `state` is what the last check saw
+    pub fn timed_out(&self, condition: &str, state: &str) -> String {
+        format!(
+            "Timeout after {}ms waiting for {condition} (waited {}ms over {} polls; {state})",
+            millis(self.timeout),
+            millis(self.started.elapsed()),
+            self.polls
+        )
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// How many windows a `windowCount` wait wants
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WindowCount {
+    AtLeast(usize),
+    Exactly(usize),
+}
+
+impl WindowCount {
+    fn parse(args: &Value) -> Result<Self, String> {
+        let count = |name: &str| {
+            args.get(name)
+                .filter(|value| !value.is_null())
+                .map(|value| {
+                    value
+                        .as_u64()
+                        .and_then(|n| usize::try_from(n).ok())
+                        .ok_or_else(|| format!("'{name}' must be a non-negative integer, got: {value}"))
+                })
+                .transpose()
+        };
+        match (count("atLeast")?, count("exactly")?) {
+            (Some(n), None) => Ok(Self::AtLeast(n)),
+            (None, Some(n)) => Ok(Self::Exactly(n)),
+            (Some(_), Some(_)) => Err("Pass either 'atLeast' or 'exactly', not both".to_string()),
+            (None, None) => Err("'windowCount' needs 'atLeast' or 'exactly'".to_string()),
+        }
+    }
+
+    const fn is_met(self, count: usize) -> bool {
+        match self {
+            Self::AtLeast(n) => count >= n,
+            Self::Exactly(n) => count == n,
+        }
+    }
+}
+
+impl std::fmt::Display for WindowCount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AtLeast(n) => write!(f, "at least {n}"),
+            Self::Exactly(n) => write!(f, "exactly {n}"),
+        }
+    }
+}
+
+/// Wait until the number of open webview windows is right, rechecking as windows open and close
+async fn window_count<R: Runtime>(
+    app: &AppHandle<R>,
+    target: WindowCount,
+    mut progress: Progress,
+) -> Result<Value, String> {
+    let registry = app
+        .try_state::<WindowRegistry>()
+        .ok_or("Window registry not initialized")?;
+    let condition = format!("{target} windows");
+    // Subscribe before counting, so a window opening in between isn't missed
+    let mut lifecycle = registry.subscribe();
+    loop {
+        progress.poll();
+        let windows = registry.labels();
+        let open = format!("{} windows open: {}", windows.len(), windows.join(", "));
+        if target.is_met(windows.len()) {
+            let mut result = progress.met(&condition, &format!("{} windows open", windows.len()), &open);
+            result["count"] = json!(windows.len());
+            result["windows"] = json!(windows);
+            return Ok(result);
+        }
+        match progress.within(lifecycle.recv()).await {
+            None => return Err(progress.timed_out(&condition, &open)),
+            // Missed events are fine, since the count is read again anyway
+            Some(Ok(_) | Err(RecvError::Lagged(_))) => {}
+            Some(Err(RecvError::Closed)) => return Err("Window events stopped".to_string()),
+        }
+    }
+}
+
+/// Wait for the next time the app emits `event`, returning its payload
+async fn tauri_event<R: Runtime>(app: &AppHandle<R>, event: &str, mut progress: Progress) -> Result<Value, String> {
+    let (tx, rx) = oneshot::channel();
+    // The listener is called for every emit, but there's only one payload to pass on
+    let tx = Mutex::new(Some(tx));
+    let id = app.listen_any(event, move |tauri_event| {
+        let payload = serde_json::from_str(tauri_event.payload())
+            .unwrap_or_else(|_| Value::String(tauri_event.payload().to_string()));
+        let tx = tx.lock().unwrap_or_else(PoisonError::into_inner).take();
+        if let Some(tx) = tx {
+            let _ = tx.send(payload);
+        }
+    });
+    let _listener = Unlisten { app, id };
+
+    let condition = format!("event '{event}'");
+    let payload = progress
+        .within(rx)
+        .await
+        .ok_or_else(|| progress.timed_out(&condition, "not emitted"))?
+        .map_err(|_| format!("Stopped listening for '{event}'"))?;
+    // The event is the only thing checked
+    progress.poll();
+    let mut result = progress.met(&condition, &format!("Event '{event}' emitted"), "event emitted");
+    result["payload"] = payload;
+    Ok(result)
+}
+
+/// Removes an event listener when dropped, so a wait that times out or is cancelled doesn't
+/// leave it behind
+struct Unlisten<'a, R: Runtime> {
+    app: &'a AppHandle<R>,
+    id: EventId,
+}
+
+impl<R: Runtime> Drop for Unlisten<'_, R> {
+    fn drop(&mut self) {
+        self.app.unlisten(self.id);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_count_takes_at_least_or_exactly() {
+        assert_eq!(
+            WindowCount::parse(&json!({"atLeast": 2})).unwrap(),
+            WindowCount::AtLeast(2)
+        );
+        assert_eq!(
+            WindowCount::parse(&json!({"exactly": 1})).unwrap(),
+            WindowCount::Exactly(1)
+        );
+        assert!(WindowCount::parse(&json!({})).unwrap_err().contains("needs"));
+        assert!(WindowCount::parse(&json!({"atLeast": 1, "exactly": 1}))
+            .unwrap_err()
+            .contains("not both"));
+        assert!(WindowCount::parse(&json!({"atLeast": -1}))
+            .unwrap_err()
+            .contains("non-negative integer"));
+    }
+
+    #[tokio::test]
+    async fn progress_reports_polls_and_last_state() {
+        let mut progress = Progress::new(Duration::from_millis(20));
+        progress.poll();
+        assert_eq!(progress.within(std::future::pending::<()>()).await, None);
+        progress.poll();
+
+        let error = progress.timed_out("event 'ready'", "not emitted");
+        assert!(error.starts_with("Timeout after 20ms waiting for event 'ready' (waited "));
+        assert!(error.ends_with("ms over 2 polls; not emitted)"));
+
+        let result = progress.met("event 'ready'", "Event 'ready' emitted", "event emitted");
+        assert_eq!(result["polls"], 2);
+        assert_eq!(result["condition"], "event 'ready'");
+        assert!(result["elapsedMs"].as_u64().unwrap() >= 20);
+    }
+
+    #[test]
+    fn window_count_is_met() {
+        assert!(WindowCount::AtLeast(2).is_met(3));
+        assert!(!WindowCount::AtLeast(2).is_met(1));
+        assert!(WindowCount::Exactly(0).is_met(0));
+        assert!(!WindowCount::Exactly(1).is_met(2));
+    }
+}
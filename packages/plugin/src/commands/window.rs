@@ -3,9 +3,86 @@
 use serde_json::{json, Value};
 use tauri::{Manager, Runtime, WebviewWindow};
 
-/// List all windows
-#[allow(clippy::unnecessary_wraps)] // Keep Result for consistent command signature
-pub fn list<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<Value, String> {
+/// Orders `window_list` can sort by
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortOrder {
+    Label,
+    Title,
+    FocusedFirst,
+}
+
+/// Parsed `window_list` arguments
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ListOptions {
+    /// Sort order (Tauri's registry order if `None`, which isn't stable)
+    sort: Option<SortOrder>,
+    /// Only windows with this visibility
+    visible: Option<bool>,
+}
+
+impl ListOptions {
+    fn parse(args: &Value) -> Result<Self, String> {
+        let sort = match args.get("sort") {
+            None | Some(Value::Null) => None,
+            Some(value) => Some(match value.as_str() {
+                Some("label") => SortOrder::Label,
+                Some("title") => SortOrder::Title,
+                Some("focused_first") => SortOrder::FocusedFirst,
+                _ => {
+                    return Err(format!(
+                        "Invalid 'sort': {value}. Available: label, title, focused_first"
+                    ))
+                }
+            }),
+        };
+
+        let visible = match args.get("filter") {
+            None | Some(Value::Null) => None,
+            Some(Value::Object(filter)) => {
+                if let Some(key) = filter.keys().find(|key| *key != "visible") {
+                    return Err(format!("Unknown filter: '{key}'. Available: visible"));
+                }
+                match filter.get("visible") {
+                    None | Some(Value::Null) => None,
+                    Some(value) => Some(
+                        value
+                            .as_bool()
+                            .ok_or_else(|| format!("'filter.visible' must be a boolean, got: {value}"))?,
+                    ),
+                }
+            }
+            Some(value) => return Err(format!("'filter' must be an object, got: {value}")),
+        };
+
+        Ok(Self { sort, visible })
+    }
+
+    /// Drop filtered-out windows and sort the rest. Ties break by label.
+    fn apply(&self, windows: &mut Vec<Value>) {
+        if let Some(visible) = self.visible {
+            windows.retain(|window| window["visible"].as_bool() == Some(visible));
+        }
+
+        let Some(sort) = self.sort else {
+            return;
+        };
+        let label = |window: &Value| window["label"].as_str().unwrap_or_default().to_string();
+        match sort {
+            SortOrder::Label => windows.sort_by_key(label),
+            SortOrder::Title => {
+                windows.sort_by_key(|window| (window["title"].as_str().unwrap_or_default().to_string(), label(window)));
+            }
+            SortOrder::FocusedFirst => {
+                windows.sort_by_key(|window| (!window["focused"].as_bool().unwrap_or(false), label(window)));
+            }
+        }
+    }
+}
+
+/// List all windows, optionally filtered by visibility and sorted
+pub fn list<R: Runtime>(app: &tauri::AppHandle<R>, args: &Value) -> Result<Value, String> {
+    let options = ListOptions::parse(args)?;
+
     let windows = app.webview_windows();
     let mut result = Vec::new();
 
@@ -13,15 +90,19 @@ pub fn list<R: Runtime>(app: &tauri::AppHandle<R>) -> Result<Value, String> {
         let title = window.title().unwrap_or_default();
         let focused = window.is_focused().unwrap_or(false);
         let visible = window.is_visible().unwrap_or(false);
+        let url = window.url().map(|url| url.to_string()).unwrap_or_default();
 
         result.push(json!({
             "label": label,
             "title": title,
             "focused": focused,
             "visible": visible,
+            "url": url,
         }));
     }
 
+    options.apply(&mut result);
+
     Ok(Value::Array(result))
 }
 
@@ -73,3 +154,79 @@ pub fn resize<R: Runtime>(window: &WebviewWindow<R>, args: &Value) -> Result<Val
 
     Ok(Value::String(format!("Resized to {width}x{height}")))
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn windows() -> Vec<Value> {
+        vec![
+            json!({ "label": "settings", "title": "B", "focused": false, "visible": false }),
+            json!({ "label": "main", "title": "B", "focused": false, "visible": true }),
+            json!({ "label": "about", "title": "A", "focused": true, "visible": true }),
+        ]
+    }
+
+    fn labels(windows: &[Value]) -> Vec<&str> {
+        windows.iter().map(|window| window["label"].as_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn parse_defaults_to_no_sort_or_filter() {
+        assert_eq!(ListOptions::parse(&json!({})).unwrap(), ListOptions::default());
+    }
+
+    #[test]
+    fn sorts_by_label_title_or_focus() {
+        let mut result = windows();
+        ListOptions::parse(&json!({ "sort": "label" }))
+            .unwrap()
+            .apply(&mut result);
+        assert_eq!(labels(&result), ["about", "main", "settings"]);
+
+        let mut result = windows();
+        ListOptions::parse(&json!({ "sort": "title" }))
+            .unwrap()
+            .apply(&mut result);
+        assert_eq!(labels(&result), ["about", "main", "settings"]);
+
+        let mut result = windows();
+        result.swap(0, 2);
+        ListOptions::parse(&json!({ "sort": "focused_first" }))
+            .unwrap()
+            .apply(&mut result);
+        assert_eq!(labels(&result), ["about", "main", "settings"]);
+    }
+
+    #[test]
+    fn title_sort_breaks_ties_by_label() {
+        let mut result = windows();
+        result.remove(2);
+        ListOptions::parse(&json!({ "sort": "title" }))
+            .unwrap()
+            .apply(&mut result);
+        assert_eq!(labels(&result), ["main", "settings"]);
+    }
+
+    #[test]
+    fn filters_by_visibility() {
+        let mut result = windows();
+        ListOptions::parse(&json!({ "sort": "label", "filter": { "visible": false } }))
+            .unwrap()
+            .apply(&mut result);
+        assert_eq!(labels(&result), ["settings"]);
+    }
+
+    #[test]
+    fn parse_rejects_invalid_options() {
+        let err = ListOptions::parse(&json!({ "sort": "size" })).unwrap_err();
+        assert!(err.contains("Available: label, title, focused_first"));
+
+        let err = ListOptions::parse(&json!({ "filter": { "focused": true } })).unwrap_err();
+        assert!(err.contains("Unknown filter: 'focused'"));
+
+        let err = ListOptions::parse(&json!({ "filter": { "visible": "yes" } })).unwrap_err();
+        assert!(err.contains("'filter.visible' must be a boolean"));
+    }
+}
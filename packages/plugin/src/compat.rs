@@ -0,0 +1,153 @@
+//! WebSocket endpoints for other automation protocols, which translate their messages to plugin
+//! commands: CDP with `Builder::cdp_compat(true)` (see [`crate::cdp`]) and WebDriver BiDi with
+//! `Builder::bidi_compat(true)` (see [`crate::bidi`]).
+//!
+//! Each listens on its own port after the WebSocket server's, on the same host, and checks
+//! `allowed_origins` the same way. A connection's messages are answered one at a time, in the
+//! order they arrived. The endpoints keep listening when `server_restart` moves the MCP server.
+
+use std::net::SocketAddr;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::Value;
+use tauri::{AppHandle, Runtime};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, error, info};
+
+use crate::websocket::{self, Request};
+use crate::{bidi, cdp, commands};
+
+/// A protocol an endpoint speaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Cdp,
+    Bidi,
+}
+
+impl Protocol {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Cdp => "CDP",
+            Self::Bidi => "WebDriver BiDi",
+        }
+    }
+
+    /// How far after the WebSocket server's port the endpoint listens
+    const fn port_offset(self) -> u16 {
+        match self {
+            Self::Cdp => 1,
+            Self::Bidi => 2,
+        }
+    }
+
+    /// The endpoint's port for the WebSocket server's, which is 0 when the OS picks both
+    const fn port(self, server_port: u16) -> Option<u16> {
+        if server_port == 0 {
+            return Some(0);
+        }
+        server_port.checked_add(self.port_offset())
+    }
+
+    async fn answer<R: Runtime>(self, app: &AppHandle<R>, text: &str) -> Value {
+        match self {
+            Self::Cdp => cdp::answer_message(app, text).await,
+            Self::Bidi => bidi::answer_message(app, text).await,
+        }
+    }
+}
+
+/// Listen for `protocol` in the background, on its port after the WebSocket server's
+pub fn spawn<R: Runtime>(app: &AppHandle<R>, protocol: Protocol, server_port: u16, host: String) {
+    let name = protocol.name();
+    let Some(port) = protocol.port(server_port) else {
+        error!(
+            "Can't start the {name} endpoint: there's no port {} after {server_port}",
+            protocol.port_offset()
+        );
+        return;
+    };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match TcpListener::bind(format!("{host}:{port}")).await {
+            Ok(listener) => serve(app, protocol, listener).await,
+            Err(e) => error!("Can't start the {name} endpoint on {host}:{port}: {e}"),
+        }
+    });
+}
+
+/// Accept connections for as long as the app runs
+async fn serve<R: Runtime>(app: AppHandle<R>, protocol: Protocol, listener: TcpListener) {
+    let name = protocol.name();
+    if let Ok(addr) = listener.local_addr() {
+        info!("tauri-mcp {name} endpoint listening on {addr}");
+    }
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                let app = app.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = serve_connection(app, protocol, stream, peer).await {
+                        error!("{name} connection error from {peer}: {e}");
+                    }
+                });
+            }
+            Err(e) => error!("Failed to accept {name} connection: {e}"),
+        }
+    }
+}
+
+/// Answer a connection's messages one at a time, in the order they arrived
+async fn serve_connection<R: Runtime>(
+    app: AppHandle<R>,
+    protocol: Protocol,
+    stream: TcpStream,
+    peer: SocketAddr,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let name = protocol.name();
+    let config = commands::plugin_config(&app);
+    let ws_config = WebSocketConfig::default()
+        .max_message_size(Some(config.max_message_bytes))
+        .max_frame_size(Some(config.max_message_bytes));
+    let mut ws_stream = websocket::accept(stream, peer, &app, ws_config).await?;
+    info!("New {name} connection from {peer}");
+
+    while let Some(message) = ws_stream.next().await {
+        match message? {
+            Message::Text(text) => {
+                debug!("{name} received: {text}");
+                let answer = protocol.answer(&app, &text).await;
+                ws_stream.send(Message::Text(answer.to_string().into())).await?;
+            }
+            Message::Close(_) => break,
+            _ => {}
+        }
+    }
+    info!("{name} connection closed from {peer}");
+    Ok(())
+}
+
+/// Run a plugin command the way the MCP server would, with the same timeout
+pub async fn run<R: Runtime>(app: &AppHandle<R>, id: String, command: &str, args: Value) -> Result<Value, String> {
+    let request = Request {
+        id,
+        command: command.to_string(),
+        args,
+        dedup_key: None,
+    };
+    commands::execute_with_timeout(app, request).await.map(|(data, _)| data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoints_go_on_the_ports_after_the_server() {
+        assert_eq!(Protocol::Cdp.port(9223), Some(9224));
+        assert_eq!(Protocol::Bidi.port(9223), Some(9225));
+        assert_eq!(Protocol::Bidi.port(0), Some(0));
+        assert_eq!(Protocol::Bidi.port(65534), None);
+    }
+}
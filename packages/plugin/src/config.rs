@@ -0,0 +1,142 @@
+//! Plugin configuration shared with command handlers via Tauri managed state.
+
+use std::sync::{PoisonError, RwLock};
+use std::time::Duration;
+
+use crate::{
+    DEFAULT_MAX_MESSAGE_BYTES, DEFAULT_PING_INTERVAL, DEFAULT_REQUEST_QUEUE_DEPTH, DEFAULT_SCRIPT_TIMEOUT,
+    DEFAULT_UPLOAD_MAX_BYTES,
+};
+
+/// Default transport-level timeout for a whole command
+pub const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Extra time the transport allows on top of the script timeout, so the script's own
+/// timeout error reaches the client instead of a generic transport timeout.
+pub const COMMAND_TIMEOUT_MARGIN: Duration = Duration::from_secs(2);
+
+/// Runtime configuration, managed as app state so commands can read it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)] // Each is a separate opt-in
+pub struct PluginConfig {
+    /// Default timeout for eval-based commands when the request doesn't specify one
+    pub script_timeout: Duration,
+    /// Base transport timeout for a command (from `TAURI_MCP_TIMEOUT`)
+    pub command_timeout: Duration,
+    /// Maximum total size in bytes of the files in one `interact` upload
+    pub upload_max_bytes: u64,
+    /// Whether `interact` may send real OS input with `native: true`
+    pub native_input: bool,
+    /// Whether clients may move the server with `server_restart`
+    pub server_restart: bool,
+    /// Whether the CDP endpoint is on, next to the WebSocket server
+    pub cdp_compat: bool,
+    /// Whether the WebDriver BiDi endpoint is on, next to the CDP one
+    pub bidi_compat: bool,
+    /// Port of the gRPC server, if it's on (`grpc` feature)
+    pub grpc_port: Option<u16>,
+    /// How many requests a connection can have waiting before new ones are turned away
+    pub request_queue_depth: usize,
+    /// Largest incoming WebSocket message or frame, in bytes
+    pub max_message_bytes: usize,
+    /// Time between keep-alive pings on each connection
+    pub ping_interval: Duration,
+    /// How long to wait for a ping's pong before closing the connection, if at all
+    pub ping_timeout: Option<Duration>,
+}
+
+impl Default for PluginConfig {
+    fn default() -> Self {
+        Self {
+            script_timeout: DEFAULT_SCRIPT_TIMEOUT,
+            command_timeout: DEFAULT_COMMAND_TIMEOUT,
+            upload_max_bytes: DEFAULT_UPLOAD_MAX_BYTES,
+            native_input: false,
+            server_restart: false,
+            cdp_compat: false,
+            bidi_compat: false,
+            grpc_port: None,
+            request_queue_depth: DEFAULT_REQUEST_QUEUE_DEPTH,
+            max_message_bytes: DEFAULT_MAX_MESSAGE_BYTES,
+            ping_interval: DEFAULT_PING_INTERVAL,
+            ping_timeout: None,
+        }
+    }
+}
+
+impl PluginConfig {
+    /// Compute the transport timeout for a command whose script may run for `script_timeout`.
+    ///
+    /// Never shorter than the script timeout plus [`COMMAND_TIMEOUT_MARGIN`].
+    #[must_use]
+    pub fn command_timeout_for(&self, script_timeout: Duration) -> Duration {
+        self.command_timeout.max(script_timeout + COMMAND_TIMEOUT_MARGIN)
+    }
+}
+
+/// The current [`PluginConfig`], managed as app state.
+///
+/// Replaced as a whole when the config file changes. Readers get a copy, so a command keeps the
+/// settings it started with.
+#[derive(Debug)]
+pub struct ConfigState(RwLock<PluginConfig>);
+
+impl ConfigState {
+    pub const fn new(config: PluginConfig) -> Self {
+        Self(RwLock::new(config))
+    }
+
+    pub fn get(&self) -> PluginConfig {
+        *self.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn set(&self, config: PluginConfig) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = config;
+    }
+}
+
+/// Get command timeout from `TAURI_MCP_TIMEOUT` env var (in ms) or default to 10s
+pub fn command_timeout_from_env() -> Duration {
+    std::env::var("TAURI_MCP_TIMEOUT")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .map_or(DEFAULT_COMMAND_TIMEOUT, Duration::from_millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_timeout_keeps_transport_timeout_for_short_scripts() {
+        let config = PluginConfig::default();
+        assert_eq!(
+            config.command_timeout_for(DEFAULT_SCRIPT_TIMEOUT),
+            DEFAULT_COMMAND_TIMEOUT
+        );
+    }
+
+    #[test]
+    fn command_timeout_extends_for_long_scripts() {
+        let config = PluginConfig {
+            script_timeout: Duration::from_secs(20),
+            command_timeout: Duration::from_secs(10),
+            ..PluginConfig::default()
+        };
+
+        let timeout = config.command_timeout_for(config.script_timeout);
+        assert_eq!(timeout, Duration::from_secs(20) + COMMAND_TIMEOUT_MARGIN);
+    }
+
+    #[test]
+    fn command_timeout_always_exceeds_script_timeout() {
+        let config = PluginConfig {
+            script_timeout: Duration::from_secs(9),
+            command_timeout: Duration::from_secs(10),
+            ..PluginConfig::default()
+        };
+
+        // 9s + margin is more than the 10s transport timeout
+        assert!(config.command_timeout_for(config.script_timeout) > Duration::from_secs(10));
+    }
+}
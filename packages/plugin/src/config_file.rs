@@ -0,0 +1,274 @@
+//! Config file read at startup and reloaded when it changes (`Builder::watch_config`).
+//!
+//! The file's settings go on top of the builder's, so removing one from the file brings back
+//! the builder's value. Settings that only take effect on restart, like the port, are read at
+//! startup and only warned about afterwards.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::Deserialize;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::config::{ConfigState, PluginConfig};
+use crate::console::ConsoleStore;
+use crate::MIN_PING_INTERVAL;
+
+/// How long to wait for more changes before reloading, since editors often save in several writes
+const RELOAD_DELAY: Duration = Duration::from_millis(100);
+
+/// Settings a config file can set, all optional
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    port: Option<u16>,
+    host: Option<String>,
+    native_input: Option<bool>,
+    log_level: Option<String>,
+    console_log_limit: Option<u32>,
+    default_script_timeout_ms: Option<u64>,
+    upload_max_bytes: Option<u64>,
+    request_queue_depth: Option<usize>,
+    max_message_bytes: Option<usize>,
+    ping_interval_ms: Option<u64>,
+    ping_timeout_ms: Option<u64>,
+}
+
+impl ConfigFile {
+    /// Read and parse the file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        toml::from_str(&text).map_err(|e| e.to_string())
+    }
+
+    /// The builder's settings with the file's on top
+    pub fn apply(&self, base: &Settings) -> Settings {
+        let millis = |ms: Option<u64>| ms.map(Duration::from_millis);
+        let config = base.config;
+        Settings {
+            port: self.port.unwrap_or(base.port),
+            host: self.host.clone().unwrap_or_else(|| base.host.clone()),
+            log_level: self.log_level.clone().unwrap_or_else(|| base.log_level.clone()),
+            console_log_limit: self.console_log_limit.unwrap_or(base.console_log_limit),
+            config: PluginConfig {
+                native_input: self.native_input.unwrap_or(config.native_input),
+                script_timeout: millis(self.default_script_timeout_ms).unwrap_or(config.script_timeout),
+                upload_max_bytes: self.upload_max_bytes.unwrap_or(config.upload_max_bytes),
+                request_queue_depth: self.request_queue_depth.unwrap_or(config.request_queue_depth),
+                max_message_bytes: self.max_message_bytes.unwrap_or(config.max_message_bytes),
+                ping_interval: millis(self.ping_interval_ms)
+                    .map_or(config.ping_interval, |interval| interval.max(MIN_PING_INTERVAL)),
+                ping_timeout: millis(self.ping_timeout_ms).or(config.ping_timeout),
+                ..config
+            },
+        }
+    }
+}
+
+/// Everything the builder or config file sets that the plugin uses after startup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Settings {
+    pub port: u16,
+    pub host: String,
+    pub log_level: String,
+    pub console_log_limit: u32,
+    pub config: PluginConfig,
+}
+
+impl Settings {
+    /// Names of the settings that differ from `running` but only take effect on restart
+    fn restart_only_changes(&self, running: &Self) -> Vec<&'static str> {
+        [
+            ("port", self.port != running.port),
+            ("host", self.host != running.host),
+            ("native_input", self.config.native_input != running.config.native_input),
+            // A limit of 0 leaves the capture script out of new pages
+            (
+                "console_log_limit",
+                (self.console_log_limit == 0) != (running.console_log_limit == 0),
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(name, changed)| changed.then_some(name))
+        .collect()
+    }
+
+    /// Apply new settings to the running plugin, keeping the ones that need a restart.
+    ///
+    /// Returns the settings now in effect.
+    fn reload<R: Runtime>(mut self, running: &Self, app: &AppHandle<R>, path: &Path) -> Self {
+        for name in self.restart_only_changes(running) {
+            warn!(
+                "'{name}' changed in {}, but only takes effect after a restart",
+                path.display()
+            );
+        }
+        self.port = running.port;
+        self.host.clone_from(&running.host);
+        self.config.native_input = running.config.native_input;
+        if (self.console_log_limit == 0) != (running.console_log_limit == 0) {
+            self.console_log_limit = running.console_log_limit;
+        }
+
+        if self.log_level != running.log_level {
+            if let Err(e) = crate::set_log_level(&self.log_level) {
+                warn!("Can't change the log level to '{}': {e}", self.log_level);
+                self.log_level.clone_from(&running.log_level);
+            }
+        }
+        if self.console_log_limit != running.console_log_limit {
+            if let Some(store) = app.try_state::<ConsoleStore>() {
+                store.set_limit(usize::try_from(self.console_log_limit).unwrap_or(usize::MAX));
+            }
+        }
+        // Connections read the queue depth, message size, and ping settings when they open
+        if let Some(state) = app.try_state::<ConfigState>() {
+            state.set(self.config);
+        }
+        info!("Reloaded config from {}", path.display());
+        self
+    }
+}
+
+/// A config file to watch, with the settings it goes on top of
+pub struct ConfigWatch {
+    path: PathBuf,
+    base: Settings,
+    /// Settings in effect
+    pub current: Settings,
+}
+
+impl ConfigWatch {
+    /// Read the file for the settings to start with, falling back to the builder's if it can't
+    /// be read
+    pub fn start(path: PathBuf, base: Settings) -> Self {
+        let current = match ConfigFile::load(&path) {
+            Ok(file) => file.apply(&base),
+            Err(e) => {
+                // Logging isn't set up yet, since the file can set the log level
+                eprintln!(
+                    "tauri-mcp: Can't read config file {}, using the builder's settings: {e}",
+                    path.display()
+                );
+                base.clone()
+            }
+        };
+        Self { path, base, current }
+    }
+
+    /// Reload the file whenever it changes, for as long as the app runs
+    pub async fn run<R: Runtime>(mut self, app: AppHandle<R>) {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let file_name = self.path.file_name().map(ToOwned::to_owned);
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else { return };
+            let ours = event.paths.iter().any(|path| path.file_name() == file_name.as_deref());
+            if ours && !matches!(event.kind, EventKind::Access(_)) {
+                let _ = tx.send(());
+            }
+        });
+        // Watch the directory, since editors often replace the file instead of writing to it
+        let dir = self
+            .path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        let _watcher = match watcher.and_then(|mut watcher| {
+            watcher.watch(dir, RecursiveMode::NonRecursive)?;
+            Ok(watcher)
+        }) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                warn!("Can't watch config file {}: {e}", self.path.display());
+                return;
+            }
+        };
+
+        while rx.recv().await.is_some() {
+            tokio::time::sleep(RELOAD_DELAY).await;
+            while rx.try_recv().is_ok() {}
+
+            match ConfigFile::load(&self.path) {
+                Ok(file) => {
+                    let next = file.apply(&self.base);
+                    if next != self.current {
+                        self.current = next.reload(&self.current, &app, &self.path);
+                    }
+                }
+                Err(e) => warn!("Not reloading config file {}: {e}", self.path.display()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn base() -> Settings {
+        Settings {
+            port: 9223,
+            host: "localhost".to_string(),
+            log_level: "info".to_string(),
+            console_log_limit: 100,
+            config: PluginConfig::default(),
+        }
+    }
+
+    #[test]
+    fn file_settings_go_on_top_of_the_builders() {
+        let file: ConfigFile = toml::from_str(
+            r#"
+            log_level = "debug"
+            console_log_limit = 500
+            default_script_timeout_ms = 10000
+            ping_interval_ms = 10
+            "#,
+        )
+        .unwrap();
+
+        let settings = file.apply(&base());
+        assert_eq!(settings.log_level, "debug");
+        assert_eq!(settings.console_log_limit, 500);
+        assert_eq!(settings.config.script_timeout, Duration::from_secs(10));
+        assert_eq!(settings.config.ping_interval, MIN_PING_INTERVAL);
+        // Left out of the file
+        assert_eq!(settings.port, 9223);
+        assert_eq!(settings.config.upload_max_bytes, base().config.upload_max_bytes);
+    }
+
+    #[test]
+    fn unknown_settings_are_rejected() {
+        assert!(toml::from_str::<ConfigFile>("log_levle = \"debug\"")
+            .unwrap_err()
+            .to_string()
+            .contains("log_levle"));
+    }
+
+    #[test]
+    fn restart_only_changes_are_listed() {
+        let running = base();
+        let file: ConfigFile = toml::from_str("port = 9300\nnative_input = true\nlog_level = \"debug\"").unwrap();
+        assert_eq!(
+            file.apply(&base()).restart_only_changes(&running),
+            ["port", "native_input"]
+        );
+        assert!(base().restart_only_changes(&running).is_empty());
+    }
+
+    #[test]
+    fn turning_console_capture_on_or_off_needs_a_restart() {
+        let running = base();
+        let off: ConfigFile = toml::from_str("console_log_limit = 0").unwrap();
+        assert_eq!(
+            off.apply(&running).restart_only_changes(&running),
+            ["console_log_limit"]
+        );
+        let resized: ConfigFile = toml::from_str("console_log_limit = 5").unwrap();
+        assert!(resized.apply(&running).restart_only_changes(&running).is_empty());
+    }
+}
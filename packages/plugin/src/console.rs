@@ -0,0 +1,1018 @@
+//! Console log storage.
+//!
+//! The injected console capture script forwards each entry over a Tauri event. Entries are
+//! kept here in a ring buffer per window, so they survive page reloads and can be read even
+//! when the page's JS thread is stuck. Each entry is also broadcast to clients subscribed to
+//! the `console` topic.
+//!
+//! Every page load in a window starts a new navigation, and entries are tagged with the
+//! window's current navigation ID so output from before and after a reload can be told apart.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use chrono::DateTime;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Listener, Manager, Runtime};
+use tokio::sync::broadcast;
+use tracing::debug;
+
+/// Event name the console capture script emits entries on
+pub const CONSOLE_EVENT: &str = "__tauri_mcp_console";
+
+/// How many entries a streaming subscriber may fall behind before entries are dropped for it
+pub const STREAM_HIGH_WATER_MARK: usize = 256;
+
+/// Console capture settings, for the injected script and the Rust-side store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CaptureConfig {
+    /// Entries kept per window (and pending in the webview before IPC is ready)
+    pub max_entries: u32,
+    /// How deep logged objects are serialized before being summarized as `[Object]`
+    pub serialization_depth: u32,
+    /// Maximum size of one entry's message in UTF-8 bytes before it's truncated
+    pub entry_max_bytes: u32,
+    /// How long buffers of destroyed windows are kept for reading
+    pub closed_window_ttl: Duration,
+    /// Attach the source location of each `console.*` call (costs an `Error` per entry)
+    pub capture_source: bool,
+}
+
+impl CaptureConfig {
+    /// Whether console entries are captured at all. A limit of 0 turns capture off.
+    pub const fn enabled(&self) -> bool {
+        self.max_entries > 0
+    }
+
+    /// Build the init script: the config global followed by the capture script itself.
+    pub fn init_script(&self) -> String {
+        format!(
+            "window.__TAURI_MCP_CONFIG__ = {{ maxConsoleEntries: {}, serializationDepth: {}, entryMaxBytes: {}, captureSource: {} }};\n{}",
+            self.max_entries,
+            self.serialization_depth,
+            self.entry_max_bytes,
+            self.capture_source,
+            include_str!("console_capture.js")
+        )
+    }
+}
+
+/// A single captured console entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsoleEntry {
+    /// ISO 8601 timestamp from the webview
+    pub timestamp: String,
+    /// `timestamp` in milliseconds since the Unix epoch (set on the Rust side)
+    #[serde(default)]
+    pub timestamp_ms: i64,
+    /// Sequence number, increasing across all windows in capture order (set on the Rust side).
+    ///
+    /// Unlike timestamps, it's unique, so polling clients should track it and pass it as `since`.
+    #[serde(default)]
+    pub seq: u64,
+    /// Console method (`log`, `warn`, `error`, `debug`, `info`), or `uncaught` for uncaught
+    /// errors and unhandled promise rejections
+    pub level: String,
+    /// Formatted message
+    pub message: String,
+    /// Label of the window that logged the entry
+    pub window_label: String,
+    /// Page load the entry was logged in, increasing with every load of the window (set on the Rust side)
+    #[serde(default)]
+    pub navigation_id: u64,
+    /// Stack trace, for uncaught errors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack: Option<String>,
+    /// Source file URL, for uncaught errors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    /// Line number in `filename`, for uncaught errors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u32>,
+    /// Column number in `filename`, for uncaught errors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<u32>,
+    /// Where the `console.*` call was made, if source capture is on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<SourceLocation>,
+}
+
+/// Location in a script.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceLocation {
+    /// Script URL
+    pub url: String,
+    /// 1-based line number
+    pub line: u32,
+    /// 1-based column number
+    pub column: u32,
+}
+
+/// Filters for reading console entries.
+#[derive(Debug, Default)]
+pub struct ConsoleQuery {
+    /// Only entries whose message matches this regex
+    pub filter: Option<Regex>,
+    /// Only entries logged after this time (milliseconds since Unix epoch)
+    pub since_ms: Option<i64>,
+    /// Only entries with a sequence number greater than this
+    pub since_seq: Option<u64>,
+    /// Only entries with one of these levels
+    pub levels: Option<Vec<String>>,
+    /// Clear the window's buffer after reading
+    pub clear: bool,
+    /// Only entries from the window's current navigation
+    pub current_navigation: bool,
+    /// Number of matching entries to skip
+    pub offset: usize,
+    /// Maximum number of matching entries to return
+    pub limit: Option<usize>,
+}
+
+impl ConsoleQuery {
+    /// Build a query from raw command arguments, validating the regex and timestamp.
+    pub fn parse(
+        filter: Option<&str>,
+        since: Option<&str>,
+        levels: Option<Vec<String>>,
+        clear: bool,
+    ) -> Result<Self, String> {
+        let filter = filter
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| format!("Invalid filter regex: {e}"))?;
+        let since_ms = since
+            .map(|s| parse_timestamp_ms(s).ok_or_else(|| format!("Invalid 'since' timestamp: '{s}'. Use ISO 8601.")))
+            .transpose()?;
+
+        Ok(Self {
+            filter,
+            since_ms,
+            levels,
+            clear,
+            ..Self::default()
+        })
+    }
+
+    /// Check whether an entry passes the level, time, and message filters.
+    pub fn matches(&self, entry: &ConsoleEntry) -> bool {
+        if let Some(levels) = &self.levels {
+            if !levels.contains(&entry.level) {
+                return false;
+            }
+        }
+        if self.since_seq.is_some_and(|seq| entry.seq <= seq) {
+            return false;
+        }
+        if let Some(since_ms) = self.since_ms {
+            // Entries with unparseable timestamps are kept rather than silently dropped
+            if parse_timestamp_ms(&entry.timestamp).is_some_and(|ms| ms <= since_ms) {
+                return false;
+            }
+        }
+        self.filter.as_ref().map_or(true, |re| re.is_match(&entry.message))
+    }
+}
+
+/// Entries matching a query, plus metadata about the window's buffer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConsolePage {
+    /// Matching entries after `offset` and `limit`, oldest first
+    pub entries: Vec<ConsoleEntry>,
+    /// Number of matching entries before `offset` and `limit`
+    pub total: usize,
+    /// Entries evicted from the buffer because it was full, since the last clear
+    pub dropped: u64,
+    /// Timestamp of the oldest entry still in the buffer
+    pub oldest_timestamp: Option<String>,
+}
+
+/// One window's ring buffer
+#[derive(Debug, Default)]
+struct WindowBuffer {
+    entries: VecDeque<ConsoleEntry>,
+    /// Entries evicted since the last clear
+    dropped: u64,
+    /// ID of the current page load
+    navigation_id: u64,
+    /// When the window was destroyed, if it was
+    closed_at: Option<Instant>,
+}
+
+impl WindowBuffer {
+    /// Entries matching the query, oldest first
+    fn matching<'a>(&'a self, query: &'a ConsoleQuery) -> impl Iterator<Item = &'a ConsoleEntry> {
+        self.entries
+            .iter()
+            .filter(|entry| !query.current_navigation || entry.navigation_id == self.navigation_id)
+            .filter(|entry| query.matches(entry))
+    }
+
+    fn clear(&mut self) {
+        self.entries.clear();
+        self.dropped = 0;
+    }
+
+    /// Drop the oldest entries past `limit`
+    fn trim(&mut self, limit: usize) {
+        while self.entries.len() > limit {
+            self.entries.pop_front();
+            self.dropped += 1;
+        }
+    }
+}
+
+/// Per-window ring buffers of console entries, managed as app state.
+///
+/// Buffers of destroyed windows are kept for `closed_window_ttl` so their output can still be
+/// read after the fact, then removed.
+#[derive(Debug)]
+pub struct ConsoleStore {
+    limit: AtomicUsize,
+    /// Sequence number of the last pushed entry
+    last_seq: AtomicU64,
+    closed_window_ttl: Duration,
+    buffers: Mutex<HashMap<String, WindowBuffer>>,
+    stream: broadcast::Sender<ConsoleEntry>,
+}
+
+impl ConsoleStore {
+    /// Create a store that keeps at most `limit` entries per window.
+    pub fn new(limit: usize) -> Self {
+        let (stream, _) = broadcast::channel(STREAM_HIGH_WATER_MARK);
+        Self {
+            limit: AtomicUsize::new(limit),
+            last_seq: AtomicU64::new(0),
+            closed_window_ttl: crate::DEFAULT_CONSOLE_CLOSED_WINDOW_TTL,
+            buffers: Mutex::new(HashMap::new()),
+            stream,
+        }
+    }
+
+    /// Set how long buffers of destroyed windows are kept. Zero removes them right away.
+    #[must_use]
+    pub const fn with_closed_window_ttl(mut self, ttl: Duration) -> Self {
+        self.closed_window_ttl = ttl;
+        self
+    }
+
+    /// Change how many entries are kept per window, dropping the oldest ones from buffers that
+    /// are over the new limit.
+    pub fn set_limit(&self, limit: usize) {
+        let mut buffers = self.lock();
+        self.limit.store(limit, Ordering::Relaxed);
+        for buffer in buffers.values_mut() {
+            buffer.trim(limit);
+        }
+        drop(buffers);
+    }
+
+    /// Receive every entry pushed from now on, across all windows.
+    ///
+    /// A receiver that falls more than [`STREAM_HIGH_WATER_MARK`] entries behind gets
+    /// `RecvError::Lagged` with the number of entries it missed.
+    pub fn subscribe(&self) -> broadcast::Receiver<ConsoleEntry> {
+        self.stream.subscribe()
+    }
+
+    /// Sequence number of the last entry pushed, or 0 if there's none yet
+    pub fn last_seq(&self) -> u64 {
+        self.last_seq.load(Ordering::Relaxed)
+    }
+
+    /// Start a new navigation for a window. Entries pushed afterwards get the new navigation ID.
+    ///
+    /// The buffer is kept, so entries from earlier page loads stay readable.
+    pub fn begin_navigation(&self, window_label: &str) {
+        let mut buffers = self.lock();
+        let buffer = buffers.entry(window_label.to_string()).or_default();
+        buffer.navigation_id += 1;
+        // A new window may reuse the label of a destroyed one
+        buffer.closed_at = None;
+        drop(buffers);
+    }
+
+    /// Mark a window as destroyed. Its buffer is removed once the closed-window TTL passes.
+    pub fn window_closed(&self, window_label: &str) {
+        let mut buffers = self.lock();
+        if let Some(buffer) = buffers.get_mut(window_label) {
+            buffer.closed_at = Some(Instant::now());
+        }
+        self.expire_closed(&mut buffers);
+        drop(buffers);
+    }
+
+    /// Add an entry, dropping the oldest one for that window if the buffer is full.
+    pub fn push(&self, mut entry: ConsoleEntry) {
+        let mut buffers = self.lock();
+        self.expire_closed(&mut buffers);
+        let buffer = buffers.entry(entry.window_label.clone()).or_default();
+        entry.navigation_id = buffer.navigation_id;
+        // Assigned under the lock, so sequence order matches buffer and stream order
+        entry.seq = self.last_seq.fetch_add(1, Ordering::Relaxed) + 1;
+        entry.timestamp_ms = parse_timestamp_ms(&entry.timestamp).unwrap_or_else(now_ms);
+
+        // No receivers is the common case, not an error
+        let _ = self.stream.send(entry.clone());
+
+        buffer.entries.push_back(entry);
+        buffer.trim(self.limit.load(Ordering::Relaxed));
+        drop(buffers);
+    }
+
+    /// Get a window's entries matching the query, oldest first.
+    pub fn query(&self, window_label: &str, query: &ConsoleQuery) -> ConsolePage {
+        let mut buffers = self.lock();
+        self.expire_closed(&mut buffers);
+        let Some(buffer) = buffers.get_mut(window_label) else {
+            return ConsolePage::from_matching(Vec::new(), query, 0, None);
+        };
+
+        let page = ConsolePage::from_matching(
+            buffer.matching(query).collect(),
+            query,
+            buffer.dropped,
+            buffer.entries.front().map(|entry| entry.timestamp.clone()),
+        );
+
+        if query.clear {
+            buffer.clear();
+        }
+        drop(buffers);
+        page
+    }
+
+    /// Get matching entries from all windows, including recently destroyed ones, merged by timestamp.
+    ///
+    /// `dropped` is the sum over all buffers, and `oldestTimestamp` the oldest entry in any of them.
+    pub fn query_all(&self, query: &ConsoleQuery) -> ConsolePage {
+        let mut buffers = self.lock();
+        self.expire_closed(&mut buffers);
+
+        let mut matching: Vec<&ConsoleEntry> = buffers.values().flat_map(|buffer| buffer.matching(query)).collect();
+        // Entries logged in the same millisecond stay in capture order
+        matching.sort_by_key(|entry| (entry.timestamp_ms, entry.seq));
+
+        let dropped = buffers.values().map(|buffer| buffer.dropped).sum();
+        let oldest_timestamp = buffers
+            .values()
+            .filter_map(|buffer| buffer.entries.front())
+            .min_by_key(|entry| (entry.timestamp_ms, entry.seq))
+            .map(|entry| entry.timestamp.clone());
+        let page = ConsolePage::from_matching(matching, query, dropped, oldest_timestamp);
+
+        if query.clear {
+            buffers.values_mut().for_each(WindowBuffer::clear);
+        }
+        drop(buffers);
+        page
+    }
+
+    /// Whether there's a buffer for the window, possibly one that was destroyed within the TTL
+    pub fn has_buffer(&self, window_label: &str) -> bool {
+        let mut buffers = self.lock();
+        self.expire_closed(&mut buffers);
+        let has_buffer = buffers.contains_key(window_label);
+        drop(buffers);
+        has_buffer
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, WindowBuffer>> {
+        self.buffers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Remove buffers of windows destroyed longer than the TTL ago
+    fn expire_closed(&self, buffers: &mut HashMap<String, WindowBuffer>) {
+        buffers.retain(|_, buffer| {
+            buffer
+                .closed_at
+                .map_or(true, |closed_at| closed_at.elapsed() < self.closed_window_ttl)
+        });
+    }
+}
+
+impl ConsolePage {
+    /// Build a page from all matching entries, applying the query's offset and limit
+    fn from_matching(
+        matching: Vec<&ConsoleEntry>,
+        query: &ConsoleQuery,
+        dropped: u64,
+        oldest_timestamp: Option<String>,
+    ) -> Self {
+        Self {
+            total: matching.len(),
+            entries: matching
+                .into_iter()
+                .skip(query.offset)
+                .take(query.limit.unwrap_or(usize::MAX))
+                .cloned()
+                .collect(),
+            dropped,
+            oldest_timestamp,
+        }
+    }
+}
+
+/// Listen for console entries from all webviews and store them.
+pub fn register_listener<R: Runtime>(app: &AppHandle<R>) {
+    let handle = app.clone();
+    app.listen_any(CONSOLE_EVENT, move |event| {
+        match serde_json::from_str::<ConsoleEntry>(event.payload()) {
+            Ok(entry) => {
+                if let Some(store) = handle.try_state::<ConsoleStore>() {
+                    store.push(entry);
+                }
+            }
+            Err(e) => debug!("Ignoring malformed console entry: {e}"),
+        }
+    });
+}
+
+/// Parse a `levels` argument, an array of level names like `["error", "uncaught"]`
+pub fn parse_levels(value: &Value) -> Result<Vec<String>, String> {
+    let items = value.as_array().ok_or("'levels' must be an array of strings")?;
+    items
+        .iter()
+        .map(|v| {
+            v.as_str()
+                .map(str::to_string)
+                .ok_or_else(|| "'levels' must be an array of strings".to_string())
+        })
+        .collect()
+}
+
+/// File format for exported console entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One JSON entry per line
+    Jsonl,
+    /// `[timestamp] [level] [window #navigation] message`, with stack traces indented below
+    Text,
+}
+
+impl ExportFormat {
+    /// Parse a `format` argument, defaulting to JSONL
+    pub fn parse(value: Option<&str>) -> Result<Self, String> {
+        match value {
+            None | Some("jsonl") => Ok(Self::Jsonl),
+            Some("text") => Ok(Self::Text),
+            Some(other) => Err(format!("Invalid format: '{other}'. Use 'jsonl' or 'text'.")),
+        }
+    }
+
+    /// Name as used in the `format` argument
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Jsonl => "jsonl",
+            Self::Text => "text",
+        }
+    }
+
+    /// Render entries in this format, one line (or block, for stacks) per entry
+    pub fn render(self, entries: &[ConsoleEntry]) -> Result<String, String> {
+        let mut out = String::new();
+        for entry in entries {
+            match self {
+                Self::Jsonl => out.push_str(&serde_json::to_string(entry).map_err(|e| e.to_string())?),
+                Self::Text => {
+                    write!(
+                        out,
+                        "[{}] [{}] [{} #{}] {}",
+                        entry.timestamp, entry.level, entry.window_label, entry.navigation_id, entry.message
+                    )
+                    .map_err(|e| e.to_string())?;
+                    if let (Some(filename), Some(line)) = (&entry.filename, entry.line) {
+                        write!(out, " ({filename}:{line}:{})", entry.column.unwrap_or(0)).map_err(|e| e.to_string())?;
+                    } else if let Some(source) = &entry.source {
+                        write!(out, " ({}:{}:{})", source.url, source.line, source.column)
+                            .map_err(|e| e.to_string())?;
+                    }
+                    for frame in entry.stack.iter().flat_map(|stack| stack.lines()) {
+                        out.push_str("\n    ");
+                        out.push_str(frame);
+                    }
+                }
+            }
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+/// Current time in milliseconds since the Unix epoch
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| i64::try_from(elapsed.as_millis()).unwrap_or(i64::MAX))
+}
+
+/// Parse an ISO 8601 / RFC 3339 timestamp to milliseconds since the Unix epoch
+fn parse_timestamp_ms(timestamp: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|dt| dt.timestamp_millis())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn entry(window: &str, timestamp: &str, message: &str) -> ConsoleEntry {
+        ConsoleEntry {
+            timestamp: timestamp.to_string(),
+            timestamp_ms: 0,
+            seq: 0,
+            level: "log".to_string(),
+            message: message.to_string(),
+            window_label: window.to_string(),
+            navigation_id: 0,
+            stack: None,
+            filename: None,
+            line: None,
+            column: None,
+            source: None,
+        }
+    }
+
+    fn messages(page: &ConsolePage) -> Vec<&str> {
+        page.entries.iter().map(|e| e.message.as_str()).collect()
+    }
+
+    #[test]
+    fn buffer_drops_oldest_entries_on_overflow() {
+        let store = ConsoleStore::new(3);
+        for i in 0..5 {
+            store.push(entry("main", "2026-01-01T00:00:00.000Z", &format!("msg {i}")));
+        }
+
+        let entries = store.query("main", &ConsoleQuery::default());
+        assert_eq!(messages(&entries), ["msg 2", "msg 3", "msg 4"]);
+    }
+
+    #[test]
+    fn buffer_overflow_is_per_window() {
+        let store = ConsoleStore::new(2);
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "main 1"));
+        for i in 0..4 {
+            store.push(entry("settings", "2026-01-01T00:00:00.000Z", &format!("settings {i}")));
+        }
+
+        assert_eq!(messages(&store.query("main", &ConsoleQuery::default())), ["main 1"]);
+        assert_eq!(
+            messages(&store.query("settings", &ConsoleQuery::default())),
+            ["settings 2", "settings 3"]
+        );
+    }
+
+    #[test]
+    fn lowering_the_limit_trims_buffers() {
+        let store = ConsoleStore::new(5);
+        for i in 0..4 {
+            store.push(entry("main", "2026-01-01T00:00:00.000Z", &format!("msg {i}")));
+        }
+
+        store.set_limit(2);
+        let page = store.query("main", &ConsoleQuery::default());
+        assert_eq!(messages(&page), ["msg 2", "msg 3"]);
+        assert_eq!(page.dropped, 2);
+
+        store.set_limit(3);
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "msg 4"));
+        assert_eq!(
+            messages(&store.query("main", &ConsoleQuery::default())),
+            ["msg 2", "msg 3", "msg 4"]
+        );
+    }
+
+    #[test]
+    fn zero_limit_stores_nothing() {
+        let store = ConsoleStore::new(0);
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "dropped"));
+
+        assert!(store.query("main", &ConsoleQuery::default()).entries.is_empty());
+    }
+
+    #[test]
+    fn query_unknown_window_returns_empty() {
+        let store = ConsoleStore::new(10);
+        let page = store.query("missing", &ConsoleQuery::default());
+        assert!(page.entries.is_empty());
+        assert_eq!(page.total, 0);
+        assert_eq!(page.oldest_timestamp, None);
+    }
+
+    #[test]
+    fn query_filters_by_regex() {
+        let store = ConsoleStore::new(10);
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "user logged in"));
+        store.push(entry("main", "2026-01-01T00:00:01.000Z", "fetch failed"));
+
+        let query = ConsoleQuery::parse(Some("^fetch"), None, None, false).unwrap();
+        assert_eq!(messages(&store.query("main", &query)), ["fetch failed"]);
+    }
+
+    #[test]
+    fn query_filters_by_since() {
+        let store = ConsoleStore::new(10);
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "old"));
+        store.push(entry("main", "2026-01-01T00:00:05.000Z", "new"));
+
+        let query = ConsoleQuery::parse(None, Some("2026-01-01T00:00:02Z"), None, false).unwrap();
+        assert_eq!(messages(&store.query("main", &query)), ["new"]);
+    }
+
+    #[test]
+    fn query_clear_empties_only_that_window() {
+        let store = ConsoleStore::new(10);
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "main"));
+        store.push(entry("settings", "2026-01-01T00:00:00.000Z", "settings"));
+
+        let query = ConsoleQuery::parse(None, None, None, true).unwrap();
+        assert_eq!(messages(&store.query("main", &query)), ["main"]);
+        assert!(store.query("main", &ConsoleQuery::default()).entries.is_empty());
+        assert_eq!(
+            messages(&store.query("settings", &ConsoleQuery::default())),
+            ["settings"]
+        );
+    }
+
+    #[test]
+    fn query_filters_by_levels() {
+        let store = ConsoleStore::new(10);
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "info message"));
+        let mut error = entry("main", "2026-01-01T00:00:01.000Z", "console error");
+        error.level = "error".to_string();
+        store.push(error);
+        let mut uncaught = entry("main", "2026-01-01T00:00:02.000Z", "TypeError: x is undefined");
+        uncaught.level = "uncaught".to_string();
+        store.push(uncaught);
+
+        let levels = vec!["error".to_string(), "uncaught".to_string()];
+        let query = ConsoleQuery::parse(None, None, Some(levels), false).unwrap();
+        assert_eq!(
+            messages(&store.query("main", &query)),
+            ["console error", "TypeError: x is undefined"]
+        );
+    }
+
+    #[test]
+    fn query_paginates_after_filtering() {
+        let store = ConsoleStore::new(10);
+        for i in 0..6 {
+            let kind = if i % 2 == 0 { "even" } else { "odd" };
+            store.push(entry("main", "2026-01-01T00:00:00.000Z", &format!("{kind} {i}")));
+        }
+
+        let mut query = ConsoleQuery::parse(Some("^even"), None, None, false).unwrap();
+        query.offset = 1;
+        query.limit = Some(1);
+        let page = store.query("main", &query);
+
+        assert_eq!(messages(&page), ["even 2"]);
+        assert_eq!(page.total, 3);
+    }
+
+    #[test]
+    fn query_reports_dropped_count_and_oldest_timestamp() {
+        let store = ConsoleStore::new(2);
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "first"));
+        store.push(entry("main", "2026-01-01T00:00:01.000Z", "second"));
+        store.push(entry("main", "2026-01-01T00:00:02.000Z", "third"));
+
+        let page = store.query("main", &ConsoleQuery::default());
+        assert_eq!(page.dropped, 1);
+        assert_eq!(page.oldest_timestamp.as_deref(), Some("2026-01-01T00:00:01.000Z"));
+    }
+
+    #[test]
+    fn clear_resets_dropped_count() {
+        let store = ConsoleStore::new(1);
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "first"));
+        store.push(entry("main", "2026-01-01T00:00:01.000Z", "second"));
+
+        let clear = ConsoleQuery::parse(None, None, None, true).unwrap();
+        assert_eq!(store.query("main", &clear).dropped, 1);
+        assert_eq!(store.query("main", &ConsoleQuery::default()).dropped, 0);
+    }
+
+    #[test]
+    fn page_serializes_in_camel_case() {
+        let store = ConsoleStore::new(10);
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "hi"));
+
+        let json = serde_json::to_value(store.query("main", &ConsoleQuery::default())).unwrap();
+        assert_eq!(json["total"], 1);
+        assert_eq!(json["dropped"], 0);
+        assert_eq!(json["oldestTimestamp"], "2026-01-01T00:00:00.000Z");
+        assert_eq!(json["entries"][0]["message"], "hi");
+    }
+
+    #[test]
+    fn entries_are_tagged_with_the_window_navigation() {
+        let store = ConsoleStore::new(10);
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "before load"));
+        store.begin_navigation("main");
+        store.push(entry("main", "2026-01-01T00:00:01.000Z", "first page"));
+        store.begin_navigation("main");
+        store.begin_navigation("settings");
+        store.push(entry("main", "2026-01-01T00:00:02.000Z", "after reload"));
+
+        let page = store.query("main", &ConsoleQuery::default());
+        let ids: Vec<u64> = page.entries.iter().map(|e| e.navigation_id).collect();
+        assert_eq!(ids, [0, 1, 2]);
+    }
+
+    #[test]
+    fn query_filters_by_current_navigation() {
+        let store = ConsoleStore::new(10);
+        store.begin_navigation("main");
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "old page"));
+        store.begin_navigation("main");
+        store.push(entry("main", "2026-01-01T00:00:01.000Z", "new page"));
+
+        let query = ConsoleQuery {
+            current_navigation: true,
+            ..ConsoleQuery::default()
+        };
+        assert_eq!(messages(&store.query("main", &query)), ["new page"]);
+        assert_eq!(store.query("main", &ConsoleQuery::default()).total, 2);
+    }
+
+    #[test]
+    fn navigation_id_from_the_webview_is_ignored() {
+        let store = ConsoleStore::new(10);
+        let mut spoofed = entry("main", "2026-01-01T00:00:00.000Z", "hi");
+        spoofed.navigation_id = 99;
+        store.push(spoofed);
+
+        assert_eq!(
+            store.query("main", &ConsoleQuery::default()).entries[0].navigation_id,
+            0
+        );
+    }
+
+    #[test]
+    fn export_renders_jsonl() {
+        let entries = [
+            entry("main", "2026-01-01T00:00:00.000Z", "one"),
+            entry("main", "2026-01-01T00:00:01.000Z", "two"),
+        ];
+        let out = ExportFormat::Jsonl.render(&entries).unwrap();
+
+        let lines: Vec<ConsoleEntry> = out.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(lines, entries);
+        assert!(out.ends_with('\n'));
+    }
+
+    #[test]
+    fn export_renders_text_with_location_and_stack() {
+        let mut error = entry("main", "2026-01-01T00:00:00.000Z", "Error: boom");
+        error.level = "uncaught".to_string();
+        error.navigation_id = 2;
+        error.filename = Some("app.js".to_string());
+        error.line = Some(10);
+        error.column = Some(5);
+        error.stack = Some("run@app.js:10:5\nmain@app.js:20:1".to_string());
+
+        let out = ExportFormat::Text.render(&[error]).unwrap();
+        assert_eq!(
+            out,
+            "[2026-01-01T00:00:00.000Z] [uncaught] [main #2] Error: boom (app.js:10:5)\n    run@app.js:10:5\n    main@app.js:20:1\n"
+        );
+    }
+
+    #[test]
+    fn export_renders_text_with_source() {
+        let mut warning = entry("main", "2026-01-01T00:00:00.000Z", "slow render");
+        warning.level = "warn".to_string();
+        warning.source = Some(SourceLocation {
+            url: "http://localhost:1420/assets/chunk-ABC.js".to_string(),
+            line: 1,
+            column: 48231,
+        });
+
+        let out = ExportFormat::Text.render(&[warning]).unwrap();
+        assert_eq!(
+            out,
+            "[2026-01-01T00:00:00.000Z] [warn] [main #0] slow render (http://localhost:1420/assets/chunk-ABC.js:1:48231)\n"
+        );
+    }
+
+    #[test]
+    fn entry_deserializes_source_location() {
+        let entry: ConsoleEntry = serde_json::from_value(serde_json::json!({
+            "timestamp": "2026-01-01T00:00:00.000Z",
+            "level": "log",
+            "message": "hi",
+            "windowLabel": "main",
+            "source": { "url": "app.js", "line": 3, "column": 7 },
+        }))
+        .unwrap();
+
+        let source = entry.source.unwrap();
+        assert_eq!((source.url.as_str(), source.line, source.column), ("app.js", 3, 7));
+    }
+
+    #[test]
+    fn export_format_defaults_to_jsonl() {
+        assert_eq!(ExportFormat::parse(None).unwrap(), ExportFormat::Jsonl);
+        assert_eq!(ExportFormat::parse(Some("text")).unwrap(), ExportFormat::Text);
+        assert!(ExportFormat::parse(Some("csv")).unwrap_err().contains("Invalid format"));
+    }
+
+    #[test]
+    fn query_all_merges_windows_by_timestamp() {
+        let store = ConsoleStore::new(10);
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "main 1"));
+        store.push(entry("worker", "2026-01-01T00:00:01.000Z", "worker 1"));
+        store.push(entry("main", "2026-01-01T00:00:02.000Z", "main 2"));
+        store.push(entry("worker", "2026-01-01T00:00:00.500Z", "worker 0"));
+
+        let page = store.query_all(&ConsoleQuery::default());
+        assert_eq!(messages(&page), ["main 1", "worker 0", "worker 1", "main 2"]);
+        assert_eq!(page.total, 4);
+        assert_eq!(page.oldest_timestamp.as_deref(), Some("2026-01-01T00:00:00.000Z"));
+    }
+
+    #[test]
+    fn query_all_clears_every_buffer() {
+        let store = ConsoleStore::new(10);
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "main"));
+        store.push(entry("worker", "2026-01-01T00:00:01.000Z", "worker"));
+
+        let query = ConsoleQuery {
+            clear: true,
+            ..ConsoleQuery::default()
+        };
+        assert_eq!(store.query_all(&query).total, 2);
+        assert_eq!(store.query_all(&ConsoleQuery::default()).total, 0);
+    }
+
+    #[test]
+    fn closed_window_buffer_is_kept_until_ttl() {
+        let store = ConsoleStore::new(10).with_closed_window_ttl(Duration::from_secs(60));
+        store.push(entry("worker", "2026-01-01T00:00:00.000Z", "last words"));
+        store.window_closed("worker");
+
+        assert_eq!(
+            messages(&store.query("worker", &ConsoleQuery::default())),
+            ["last words"]
+        );
+    }
+
+    #[test]
+    fn closed_window_buffer_is_removed_after_ttl() {
+        let store = ConsoleStore::new(10).with_closed_window_ttl(Duration::from_millis(10));
+        store.push(entry("worker", "2026-01-01T00:00:00.000Z", "last words"));
+        store.push(entry("main", "2026-01-01T00:00:01.000Z", "still here"));
+        store.window_closed("worker");
+        std::thread::sleep(Duration::from_millis(20));
+
+        assert!(!store.has_buffer("worker"));
+        assert_eq!(messages(&store.query_all(&ConsoleQuery::default())), ["still here"]);
+    }
+
+    #[test]
+    fn reopened_window_keeps_its_buffer() {
+        let store = ConsoleStore::new(10).with_closed_window_ttl(Duration::from_millis(10));
+        store.push(entry("worker", "2026-01-01T00:00:00.000Z", "first run"));
+        store.window_closed("worker");
+        store.begin_navigation("worker");
+        std::thread::sleep(Duration::from_millis(20));
+
+        assert_eq!(store.query("worker", &ConsoleQuery::default()).total, 1);
+    }
+
+    #[test]
+    fn entries_get_increasing_seq_and_timestamp_ms() {
+        let store = ConsoleStore::new(10);
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "one"));
+        store.push(entry("worker", "2026-01-01T00:00:00.000Z", "two"));
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "three"));
+
+        let page = store.query_all(&ConsoleQuery::default());
+        let seqs: Vec<u64> = page.entries.iter().map(|e| e.seq).collect();
+        assert_eq!(seqs, [1, 2, 3]);
+        assert!(page.entries.iter().all(|e| e.timestamp_ms == 1_767_225_600_000));
+    }
+
+    #[test]
+    fn polling_by_seq_returns_same_millisecond_entries_exactly_once() {
+        let store = ConsoleStore::new(10);
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "first"));
+
+        let first_poll = store.query("main", &ConsoleQuery::default());
+        assert_eq!(messages(&first_poll), ["first"]);
+        let last_seq = first_poll.entries.last().unwrap().seq;
+
+        // Logged in the same millisecond as the entry already seen
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "second"));
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "third"));
+
+        let query = ConsoleQuery {
+            since_seq: Some(last_seq),
+            ..ConsoleQuery::default()
+        };
+        assert_eq!(messages(&store.query("main", &query)), ["second", "third"]);
+    }
+
+    #[test]
+    fn query_rejects_invalid_regex() {
+        let err = ConsoleQuery::parse(Some("(unclosed"), None, None, false).unwrap_err();
+        assert!(err.contains("Invalid filter regex"));
+    }
+
+    #[test]
+    fn query_rejects_invalid_since() {
+        let err = ConsoleQuery::parse(None, Some("yesterday"), None, false).unwrap_err();
+        assert!(err.contains("Invalid 'since' timestamp"));
+    }
+
+    #[test]
+    fn init_script_injects_config_before_capture_script() {
+        let config = CaptureConfig {
+            max_entries: 500,
+            serialization_depth: 2,
+            entry_max_bytes: 1024,
+            closed_window_ttl: Duration::ZERO,
+            capture_source: true,
+        };
+
+        let script = config.init_script();
+        assert!(script.starts_with(
+            "window.__TAURI_MCP_CONFIG__ = { maxConsoleEntries: 500, serializationDepth: 2, entryMaxBytes: 1024, captureSource: true };"
+        ));
+        assert!(script.contains("window.__tauriMcpConsole"));
+    }
+
+    #[test]
+    fn subscribers_receive_entries_from_all_windows() {
+        let store = ConsoleStore::new(10);
+        let mut rx = store.subscribe();
+        store.push(entry("main", "2026-01-01T00:00:00.000Z", "from main"));
+        store.push(entry("settings", "2026-01-01T00:00:00.000Z", "from settings"));
+
+        assert_eq!(rx.try_recv().unwrap().message, "from main");
+        assert_eq!(rx.try_recv().unwrap().message, "from settings");
+    }
+
+    #[test]
+    fn slow_subscriber_is_told_how_many_entries_it_missed() {
+        let store = ConsoleStore::new(10);
+        let mut rx = store.subscribe();
+        for i in 0..STREAM_HIGH_WATER_MARK + 3 {
+            store.push(entry("main", "2026-01-01T00:00:00.000Z", &format!("msg {i}")));
+        }
+
+        assert!(matches!(rx.try_recv(), Err(broadcast::error::TryRecvError::Lagged(3))));
+        assert_eq!(rx.try_recv().unwrap().message, "msg 3");
+    }
+
+    #[test]
+    fn parse_levels_rejects_non_string_items() {
+        let err = parse_levels(&serde_json::json!(["error", 1])).unwrap_err();
+        assert!(err.contains("'levels' must be an array of strings"));
+    }
+
+    #[test]
+    fn entry_serializes_window_label_in_camel_case() {
+        let json = serde_json::to_value(entry("main", "2026-01-01T00:00:00.000Z", "hi")).unwrap();
+        assert_eq!(json["windowLabel"], "main");
+        assert_eq!(json["navigationId"], 0);
+        assert!(json.get("window_label").is_none());
+    }
+
+    #[test]
+    fn entry_deserializes_uncaught_error_details() {
+        let json = r#"{
+            "timestamp": "2026-01-01T00:00:00.000Z",
+            "level": "uncaught",
+            "message": "Uncaught ReferenceError: foo is not defined",
+            "windowLabel": "main",
+            "stack": "ReferenceError: foo is not defined\n    at app.js:3:5",
+            "filename": "http://localhost:5173/app.js",
+            "line": 3,
+            "column": 5
+        }"#;
+        let entry: ConsoleEntry = serde_json::from_str(json).unwrap();
+        assert_eq!(entry.level, "uncaught");
+        assert_eq!(entry.filename.as_deref(), Some("http://localhost:5173/app.js"));
+        assert_eq!(entry.line, Some(3));
+        assert_eq!(entry.column, Some(5));
+    }
+
+    #[test]
+    fn entry_omits_missing_error_details() {
+        let json = serde_json::to_value(entry("main", "2026-01-01T00:00:00.000Z", "hi")).unwrap();
+        assert!(json.get("stack").is_none());
+        assert!(json.get("line").is_none());
+    }
+}
@@ -1,5 +1,7 @@
 // Console capture script - injected into webview on load
-// Captures console.log, warn, error, debug, info and stores in memory
+// Captures console.log, warn, error, debug, info, plus uncaught errors and unhandled promise
+// rejections, and forwards each entry to the Rust plugin, which stores them per window
+// (so they survive reloads and stuck pages)
 (function() {
   'use strict';
 
@@ -7,8 +9,23 @@
 
   // Read config from injected global (set by Rust plugin before this script)
   const config = window.__TAURI_MCP_CONFIG__ || {};
-  const maxEntries = config.maxConsoleEntries || 25;
-  const logs = [];
+  const maxPending = config.maxConsoleEntries || 25;
+  const maxDepth = config.serializationDepth ?? 4;
+  const maxEntryBytes = config.entryMaxBytes ?? 8192;
+  const captureSourceEnabled = config.captureSource === true;
+  const eventName = '__tauri_mcp_console';
+
+  // Matches the sourceURL comment at the end of this script, so its own stack frames can be skipped
+  const captureScriptUrl = 'tauri-mcp-console-capture.js';
+  // One stack frame's location, in Chromium (`    at fn (url:1:2)`, `    at url:1:2`) and
+  // WebKit (`fn@url:1:2`, `@url:1:2`) formats
+  const stackFramePattern = /^\s*(?:at\s+)?(?:[^@(]*[@(])?(.+?):(\d+):(\d+)\)?\s*$/;
+
+  const encoder = new TextEncoder();
+  const decoder = new TextDecoder();
+
+  // Entries logged before the Tauri IPC is available, flushed on the next successful send
+  const pending = [];
 
   const originalConsole = {
     log: console.log.bind(console),
@@ -18,25 +35,193 @@
     info: console.info.bind(console),
   };
 
+  function getWindowLabel() {
+    const metadata = window.__TAURI_INTERNALS__ && window.__TAURI_INTERNALS__.metadata;
+    return (metadata && (metadata.currentWebview || metadata.currentWindow || {}).label) || 'main';
+  }
+
+  function emit(entry) {
+    let result;
+    if (window.__TAURI__ && window.__TAURI__.event && window.__TAURI__.event.emit) {
+      result = window.__TAURI__.event.emit(eventName, entry);
+    } else if (window.__TAURI_INTERNALS__ && window.__TAURI_INTERNALS__.invoke) {
+      result = window.__TAURI_INTERNALS__.invoke('plugin:event|emit', { event: eventName, payload: entry });
+    } else {
+      return false;
+    }
+    // Swallow IPC rejections so they don't surface as unhandled rejections in the app
+    if (result && typeof result.catch === 'function') {
+      result.catch(() => {});
+    }
+    return true;
+  }
+
+  function send(entry) {
+    pending.push(entry);
+    if (pending.length > maxPending) {
+      pending.shift();
+    }
+
+    try {
+      while (pending.length > 0 && emit(pending[0])) {
+        pending.shift();
+      }
+    } catch {
+      // IPC not ready yet - keep entries pending
+    }
+  }
+
+  // Turn any value into something JSON.stringify can handle without throwing or losing
+  // the useful parts. `ancestors` holds the objects on the current path, so shared
+  // references are serialized twice but cycles become "[Circular]".
+  function toSerializable(value, depth, ancestors) {
+    switch (typeof value) {
+      case 'string':
+      case 'boolean':
+        return value;
+      case 'number':
+        return Number.isFinite(value) ? value : String(value);
+      case 'undefined':
+        return '[undefined]';
+      case 'bigint':
+        return `${value}n`;
+      case 'symbol':
+        return value.toString();
+      case 'function':
+        return `[Function ${value.name || 'anonymous'}]`;
+    }
+    if (value === null) return null;
+
+    if (value instanceof Error) {
+      return { name: value.name, message: value.message, stack: value.stack || null };
+    }
+    if (typeof Node !== 'undefined' && value instanceof Node) return describeNode(value);
+    if (value instanceof Date) return isNaN(value) ? 'Invalid Date' : value.toISOString();
+    if (value instanceof RegExp) return value.toString();
+
+    if (ancestors.includes(value)) return '[Circular]';
+    if (depth >= maxDepth) {
+      if (Array.isArray(value)) return `[Array(${value.length})]`;
+      if (value instanceof Map) return `[Map(${value.size})]`;
+      if (value instanceof Set) return `[Set(${value.size})]`;
+      return '[Object]';
+    }
+
+    ancestors.push(value);
+    try {
+      const child = item => toSerializable(item, depth + 1, ancestors);
+      if (value instanceof Map) return Array.from(value, ([key, item]) => [child(key), child(item)]);
+      if (value instanceof Set || Array.isArray(value)) return Array.from(value, child);
+
+      const result = {};
+      for (const key of Object.keys(value)) {
+        try {
+          result[key] = child(value[key]);
+        } catch {
+          result[key] = '[Getter threw]';
+        }
+      }
+      return result;
+    } finally {
+      ancestors.pop();
+    }
+  }
+
+  // Summarize DOM nodes as <tag#id.class> instead of an empty object
+  function describeNode(node) {
+    if (node.nodeType === Node.ELEMENT_NODE) {
+      const id = node.id ? `#${node.id}` : '';
+      const classes = typeof node.className === 'string'
+        ? node.className.trim().split(/\s+/).filter(Boolean).map(c => `.${c}`).join('')
+        : '';
+      return `<${node.tagName.toLowerCase()}${id}${classes}>`;
+    }
+    if (node.nodeType === Node.TEXT_NODE) return `#text "${node.textContent.slice(0, 50)}"`;
+    return node.nodeName;
+  }
+
+  function formatArg(arg) {
+    if (typeof arg === 'string') return arg;
+    try {
+      const serializable = toSerializable(arg, 0, []);
+      return typeof serializable === 'string' ? serializable : JSON.stringify(serializable);
+    } catch {
+      return String(arg);
+    }
+  }
+
+  // Cap the message at maxEntryBytes of UTF-8, marking how much was cut
+  function truncate(message) {
+    const bytes = encoder.encode(message);
+    if (bytes.length <= maxEntryBytes) return message;
+    // A cut multi-byte character decodes as U+FFFD, so drop it
+    const kept = decoder.decode(bytes.slice(0, maxEntryBytes)).replace(/\uFFFD$/, '');
+    return `${kept}… [truncated ${bytes.length - maxEntryBytes} bytes]`;
+  }
+
+  function formatMessage(args) {
+    return truncate(Array.from(args).map(formatArg).join(' '));
+  }
+
+  // Location of the first stack frame outside this script, or null if there's none
+  function parseSource(stack) {
+    for (const line of String(stack).split('\n')) {
+      const match = stackFramePattern.exec(line);
+      if (!match || match[1].endsWith(captureScriptUrl)) continue;
+      return { url: match[1], line: Number(match[2]), column: Number(match[3]) };
+    }
+    return null;
+  }
+
   function captureLog(level, args) {
     const entry = {
       timestamp: new Date().toISOString(),
       level,
-      message: Array.from(args).map(arg => {
-        try {
-          return typeof arg === 'object' ? JSON.stringify(arg) : String(arg);
-        } catch {
-          return String(arg);
-        }
-      }).join(' '),
+      message: formatMessage(args),
+      windowLabel: getWindowLabel(),
     };
-
-    logs.push(entry);
-    if (logs.length > maxEntries) {
-      logs.shift();
+    if (captureSourceEnabled) {
+      const source = parseSource(new Error().stack);
+      if (source) entry.source = source;
     }
+    send(entry);
+  }
+
+  function captureUncaught(message, error, filename, line, column) {
+    const entry = {
+      timestamp: new Date().toISOString(),
+      level: 'uncaught',
+      message: truncate(message),
+      windowLabel: getWindowLabel(),
+    };
+    if (error && typeof error.stack === 'string') entry.stack = error.stack;
+    if (filename) entry.filename = filename;
+    if (line) entry.line = line;
+    if (column) entry.column = column;
+    send(entry);
   }
 
+  function describeReason(reason) {
+    if (reason instanceof Error) return `${reason.name}: ${reason.message}`;
+    return formatArg(reason);
+  }
+
+  window.addEventListener('error', (event) => {
+    // Resource load errors (img, script tags) bubble here too but have no message
+    if (!event.message && !event.error) return;
+    captureUncaught(
+      event.message || describeReason(event.error),
+      event.error,
+      event.filename,
+      event.lineno,
+      event.colno,
+    );
+  });
+
+  window.addEventListener('unhandledrejection', (event) => {
+    captureUncaught(`Unhandled promise rejection: ${describeReason(event.reason)}`, event.reason);
+  });
+
   console.log = (...args) => { captureLog('log', args); originalConsole.log(...args); };
   console.warn = (...args) => { captureLog('warn', args); originalConsole.warn(...args); };
   console.error = (...args) => { captureLog('error', args); originalConsole.error(...args); };
@@ -44,26 +229,11 @@
   console.info = (...args) => { captureLog('info', args); originalConsole.info(...args); };
 
   window.__tauriMcpConsole = {
-    getLogs: (filter, since) => {
-      let result = logs;
-
-      if (since) {
-        const sinceDate = new Date(since);
-        result = result.filter(entry => new Date(entry.timestamp) > sinceDate);
-      }
-
-      if (filter) {
-        try {
-          const regex = new RegExp(filter);
-          result = result.filter(entry => regex.test(entry.message));
-        } catch (e) {
-          throw new Error(`Invalid filter regex: ${e.message}`);
-        }
-      }
-
-      return result;
-    },
-    clear: () => { logs.length = 0; },
-    getCount: () => logs.length,
+    getPendingCount: () => pending.length,
+    // Format console arguments the way captured entries are formatted
+    format: (...args) => formatMessage(args),
+    // Find the caller's location in a stack trace, skipping this script's frames
+    parseSource,
   };
 })();
+//# sourceURL=tauri-mcp-console-capture.js
@@ -0,0 +1,136 @@
+//! Deduplication of identical requests that are in flight at the same time.
+//!
+//! A request can carry a `dedup_key`. While one request with that key is queued or running, later
+//! requests with the same key don't run the command again: they wait for the first one's response
+//! and get a copy of it under their own ID. Saves capturing the same screenshot or walking the same
+//! DOM twice when an agent sends a request again before the first one finished.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use tokio::sync::broadcast;
+
+use crate::websocket::Response;
+
+/// Requests with a `dedup_key` that are queued or running, shared by all connections
+#[derive(Debug, Default)]
+pub struct InFlightRequests {
+    requests: Mutex<HashMap<String, broadcast::Sender<Response>>>,
+}
+
+/// What to do with a request that has a `dedup_key`
+#[derive(Debug)]
+pub enum Dedup {
+    /// Nothing with this key is in flight, so run the request and pass its response on
+    Lead(Leader),
+    /// A request with this key is in flight, so wait for its response instead
+    Join(broadcast::Receiver<Response>),
+}
+
+impl InFlightRequests {
+    /// Join the request in flight with this key, or register a new one
+    pub fn join_or_lead(self: &Arc<Self>, key: String) -> Dedup {
+        let mut requests = self.lock();
+        if let Some(sender) = requests.get(&key) {
+            return Dedup::Join(sender.subscribe());
+        }
+        // Each request answers once, so one slot is enough
+        let (sender, _) = broadcast::channel(1);
+        requests.insert(key.clone(), sender.clone());
+        drop(requests);
+        Dedup::Lead(Leader {
+            key,
+            sender,
+            registry: Arc::clone(self),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, broadcast::Sender<Response>>> {
+        self.requests.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// The request that runs the command for its `dedup_key`.
+///
+/// Frees the key when dropped, whether the request finished or was thrown away. Requests that
+/// joined it get a closed channel if it never finished.
+#[derive(Debug)]
+pub struct Leader {
+    key: String,
+    sender: broadcast::Sender<Response>,
+    registry: Arc<InFlightRequests>,
+}
+
+impl Leader {
+    /// Pass the response on to the requests that joined this one
+    pub fn finish(self, response: &Response) {
+        // Fails only if nobody joined
+        let _ = self.sender.send(response.clone());
+    }
+}
+
+impl Drop for Leader {
+    fn drop(&mut self) {
+        self.registry.lock().remove(&self.key);
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn response(id: &str) -> Response {
+        Response {
+            id: id.to_string(),
+            success: true,
+            data: Some(serde_json::json!(["main"])),
+            error: None,
+            window_context: None,
+            session_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn joined_requests_get_the_leaders_response() {
+        let registry = Arc::new(InFlightRequests::default());
+        let Dedup::Lead(leader) = registry.join_or_lead("windows".to_string()) else {
+            panic!("first request should lead");
+        };
+        let Dedup::Join(mut first) = registry.join_or_lead("windows".to_string()) else {
+            panic!("second request should join");
+        };
+        let Dedup::Join(mut second) = registry.join_or_lead("windows".to_string()) else {
+            panic!("third request should join");
+        };
+
+        leader.finish(&response("req_1"));
+
+        assert_eq!(first.recv().await.unwrap().id, "req_1");
+        assert_eq!(second.recv().await.unwrap().data, Some(serde_json::json!(["main"])));
+    }
+
+    #[test]
+    fn keys_are_freed_once_the_leader_is_done() {
+        let registry = Arc::new(InFlightRequests::default());
+        let Dedup::Lead(leader) = registry.join_or_lead("a".to_string()) else {
+            panic!("first request should lead");
+        };
+        assert!(matches!(registry.join_or_lead("b".to_string()), Dedup::Lead(_)));
+
+        leader.finish(&response("req_1"));
+        assert!(matches!(registry.join_or_lead("a".to_string()), Dedup::Lead(_)));
+    }
+
+    #[tokio::test]
+    async fn joined_requests_see_a_dropped_leader() {
+        let registry = Arc::new(InFlightRequests::default());
+        let leader = registry.join_or_lead("a".to_string());
+        let Dedup::Join(mut joined) = registry.join_or_lead("a".to_string()) else {
+            panic!("second request should join");
+        };
+
+        drop(leader);
+        assert!(joined.recv().await.is_err());
+    }
+}
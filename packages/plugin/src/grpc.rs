@@ -0,0 +1,175 @@
+//! gRPC transport, with the `grpc` feature and `Builder::grpc_port`.
+//!
+//! The `Mcp` service defined in `proto/mcp.proto` runs the same commands as the WebSocket server:
+//! `Execute` takes a request with JSON arguments and answers with the command's JSON result, and
+//! `Subscribe` streams a topic's events, or a Tauri event's, until the client cancels the call.
+//! It listens on its own port, on the same host as the WebSocket server, and keeps listening when
+//! `server_restart` moves that server. There's no origin check, since browsers can't make gRPC
+//! calls.
+
+use std::pin::Pin;
+
+use futures_util::{stream, Stream};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Runtime};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tonic::transport::server::TcpIncoming;
+use tonic::Status;
+use tracing::{error, info};
+
+use crate::commands;
+use crate::console::STREAM_HIGH_WATER_MARK;
+use crate::subscriptions::{Event, EventSink, Subscriptions};
+use crate::websocket::{Request, WindowContext};
+
+/// Code generated from `proto/mcp.proto`
+#[allow(missing_docs, clippy::all, clippy::pedantic, clippy::nursery)]
+mod proto {
+    tonic::include_proto!("tauri_mcp");
+}
+
+use proto::mcp_server::{Mcp, McpServer};
+
+/// Serve the gRPC service in the background on `host:port`
+pub fn spawn<R: Runtime>(app: &AppHandle<R>, port: u16, host: String) {
+    let service = McpServer::new(Service { app: app.clone() });
+    tauri::async_runtime::spawn(async move {
+        let listener = match TcpListener::bind(format!("{host}:{port}")).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("Can't start the gRPC server on {host}:{port}: {e}");
+                return;
+            }
+        };
+        if let Ok(addr) = listener.local_addr() {
+            info!("gRPC server listening on {addr}");
+        }
+        if let Err(e) = tonic::transport::Server::builder()
+            .add_service(service)
+            .serve_with_incoming(TcpIncoming::from(listener))
+            .await
+        {
+            error!("gRPC server stopped: {e}");
+        }
+    });
+}
+
+/// The `Mcp` service, running commands in the app
+struct Service<R: Runtime> {
+    app: AppHandle<R>,
+}
+
+/// Events pushed to one `Subscribe` call
+type PushStream = Pin<Box<dyn Stream<Item = Result<proto::PushMessage, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl<R: Runtime> Mcp for Service<R> {
+    async fn execute(
+        &self,
+        request: tonic::Request<proto::Request>,
+    ) -> Result<tonic::Response<proto::Response>, Status> {
+        let proto::Request { id, command, args } = request.into_inner();
+        let request = Request {
+            id: id.clone(),
+            command,
+            args: Value::Object(json_args(&args).map_err(Status::invalid_argument)?),
+            dedup_key: None,
+        };
+
+        let response = match commands::execute_with_timeout(&self.app, request).await {
+            Ok((data, context)) => proto::Response {
+                id,
+                success: true,
+                data: Some(data.to_string()),
+                error: None,
+                window_context: context.map(window_context),
+            },
+            Err(e) => proto::Response {
+                id,
+                success: false,
+                data: None,
+                error: Some(e),
+                window_context: None,
+            },
+        };
+        Ok(tonic::Response::new(response))
+    }
+
+    type SubscribeStream = PushStream;
+
+    async fn subscribe(
+        &self,
+        request: tonic::Request<proto::SubscribeRequest>,
+    ) -> Result<tonic::Response<PushStream>, Status> {
+        let proto::SubscribeRequest { topic, event, args } = request.into_inner();
+        let mut args = json_args(&args).map_err(Status::invalid_argument)?;
+
+        let (tx, rx) = mpsc::channel(STREAM_HIGH_WATER_MARK);
+        let sink = EventSink::Channel(tx);
+        let mut subscriptions = Subscriptions::default();
+        match (topic.is_empty(), event.is_empty()) {
+            (false, true) => {
+                args.insert("topic".to_string(), Value::String(topic));
+                subscriptions.subscribe(&self.app, &Value::Object(args), &sink)
+            }
+            (true, false) => {
+                args.insert("event".to_string(), Value::String(event));
+                subscriptions.subscribe_event(&self.app, &Value::Object(args), &sink)
+            }
+            _ => Err("Set either 'topic' or 'event'".to_string()),
+        }
+        .map_err(Status::invalid_argument)?;
+
+        // The stream owns the subscription, so it ends when the client cancels the call
+        let messages = stream::unfold((rx, subscriptions), |(mut rx, subscriptions)| async move {
+            let event = rx.recv().await?;
+            Some((Ok(push_message(&event)), (rx, subscriptions)))
+        });
+        Ok(tonic::Response::new(Box::pin(messages)))
+    }
+}
+
+/// Parse arguments sent as a JSON object, where an empty string means none
+fn json_args(args: &str) -> Result<Map<String, Value>, String> {
+    if args.trim().is_empty() {
+        return Ok(Map::new());
+    }
+    match serde_json::from_str(args) {
+        Ok(Value::Object(args)) => Ok(args),
+        Ok(other) => Err(format!("'args' must be a JSON object, got: {other}")),
+        Err(e) => Err(format!("'args' isn't valid JSON: {e}")),
+    }
+}
+
+fn window_context(context: WindowContext) -> proto::WindowContext {
+    proto::WindowContext {
+        window_label: context.window_label,
+        total_windows: u32::try_from(context.total_windows).unwrap_or(u32::MAX),
+    }
+}
+
+fn push_message(event: &Event) -> proto::PushMessage {
+    proto::PushMessage {
+        event: event.event.to_string(),
+        data: event.data.to_string(),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn args_are_a_json_object_or_nothing() {
+        assert!(json_args("").unwrap().is_empty());
+        assert_eq!(
+            Value::Object(json_args(r#"{"script": "1 + 1"}"#).unwrap()),
+            json!({ "script": "1 + 1" })
+        );
+        assert!(json_args("[1]").unwrap_err().contains("must be a JSON object"));
+        assert!(json_args("{").unwrap_err().contains("isn't valid JSON"));
+    }
+}
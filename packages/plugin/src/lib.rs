@@ -23,16 +23,47 @@
 //!     .build()
 //! ```
 
+mod bidi;
+mod cdp;
 mod commands;
+mod compat;
+mod config;
+mod config_file;
+mod console;
+mod dedup;
+#[cfg(feature = "grpc")]
+mod grpc;
+mod menu;
+#[cfg(feature = "interact")]
+mod native_input;
+mod navigation;
+mod origins;
+mod restart;
+#[cfg(feature = "screenshot")]
 mod screenshot;
+mod subscriptions;
 mod websocket;
+mod windows;
 
-use tauri::{plugin::TauriPlugin, Manager, RunEvent, Runtime};
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use config::{ConfigState, PluginConfig};
+use config_file::{ConfigWatch, Settings};
+use console::{CaptureConfig, ConsoleStore};
+use navigation::{Navigation, NavigationEvent};
+use origins::AllowedOrigins;
+use tauri::{plugin::TauriPlugin, webview::PageLoadEvent, Manager, RunEvent, Runtime, WindowEvent};
 use tokio::sync::oneshot;
-use tracing::info;
-use tracing_subscriber::EnvFilter;
+use tracing::{info, warn};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+use windows::WindowRegistry;
 
-pub use websocket::ShutdownHandle;
+pub use menu::on_menu_event;
+pub use websocket::{McpServerAddr, ShutdownHandle, READY_EVENT};
 
 /// Default WebSocket server port
 pub const DEFAULT_PORT: u16 = 9223;
@@ -43,9 +74,36 @@ pub const DEFAULT_HOST: &str = "localhost";
 /// Default console log limit (protects against runaway scripts flooding logs)
 pub const DEFAULT_CONSOLE_LOG_LIMIT: u32 = 100;
 
+/// Default depth to which console-logged objects are serialized
+pub const DEFAULT_CONSOLE_SERIALIZATION_DEPTH: u32 = 4;
+
+/// Default maximum size of a console entry's message in bytes (longer messages are truncated)
+pub const DEFAULT_CONSOLE_ENTRY_MAX_BYTES: u32 = 8 * 1024;
+
+/// Default time console buffers of destroyed windows are kept for reading
+pub const DEFAULT_CONSOLE_CLOSED_WINDOW_TTL: Duration = Duration::from_secs(5 * 60);
+
 /// Default log level
 pub const DEFAULT_LOG_LEVEL: &str = "info";
 
+/// Default timeout for eval-based commands (`execute_js`, `dom_snapshot`, etc.)
+pub const DEFAULT_SCRIPT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default maximum total size in bytes of the files in one `interact` upload
+pub const DEFAULT_UPLOAD_MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Default number of requests a connection can have waiting to run
+pub const DEFAULT_REQUEST_QUEUE_DEPTH: usize = 50;
+
+/// Default maximum size of one incoming WebSocket message
+pub const DEFAULT_MAX_MESSAGE_BYTES: usize = 10 * 1024 * 1024;
+
+/// Default time between keep-alive pings on each connection
+pub const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Shortest time between keep-alive pings, whatever `Builder::ping_interval` says
+const MIN_PING_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Plugin builder for customizing WebSocket server configuration.
 ///
 /// # Example
@@ -58,11 +116,48 @@ pub const DEFAULT_LOG_LEVEL: &str = "info";
 ///     .build()
 /// ```
 #[derive(Debug, Clone)]
+#[allow(clippy::struct_excessive_bools)] // Each is a separate opt-in
 pub struct Builder {
     port: u16,
     host: String,
     console_log_limit: u32,
+    console_serialization_depth: u32,
+    console_entry_max_bytes: u32,
+    console_closed_window_ttl: Duration,
+    console_capture_source: bool,
     log_level: Option<String>,
+    script_timeout: Duration,
+    upload_max_bytes: u64,
+    native_input: bool,
+    server_restart: bool,
+    cdp_compat: bool,
+    bidi_compat: bool,
+    grpc_port: Option<u16>,
+    request_queue_depth: usize,
+    max_message_bytes: usize,
+    ping_interval: Duration,
+    ping_timeout: Option<Duration>,
+    window_aliases: Vec<(String, String)>,
+    allowed_origins: Option<Vec<String>>,
+    injected_js: Vec<InjectedJs>,
+    config_path: Option<PathBuf>,
+}
+
+/// A script added with [`Builder::inject_js`] or [`Builder::inject_js_file`]
+#[derive(Debug, Clone)]
+enum InjectedJs {
+    Script(String),
+    File(PathBuf),
+}
+
+impl InjectedJs {
+    fn load(self) -> Result<String, String> {
+        match self {
+            Self::Script(script) => Ok(script),
+            Self::File(path) => std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read injected script '{}': {e}", path.display())),
+        }
+    }
 }
 
 impl Default for Builder {
@@ -79,11 +174,34 @@ impl Builder {
             port: DEFAULT_PORT,
             host: String::new(), // Will use DEFAULT_HOST
             console_log_limit: DEFAULT_CONSOLE_LOG_LIMIT,
+            console_serialization_depth: DEFAULT_CONSOLE_SERIALIZATION_DEPTH,
+            console_entry_max_bytes: DEFAULT_CONSOLE_ENTRY_MAX_BYTES,
+            console_closed_window_ttl: DEFAULT_CONSOLE_CLOSED_WINDOW_TTL,
+            console_capture_source: false,
             log_level: None,
+            script_timeout: DEFAULT_SCRIPT_TIMEOUT,
+            upload_max_bytes: DEFAULT_UPLOAD_MAX_BYTES,
+            native_input: false,
+            server_restart: false,
+            cdp_compat: false,
+            bidi_compat: false,
+            grpc_port: None,
+            request_queue_depth: DEFAULT_REQUEST_QUEUE_DEPTH,
+            max_message_bytes: DEFAULT_MAX_MESSAGE_BYTES,
+            ping_interval: DEFAULT_PING_INTERVAL,
+            ping_timeout: None,
+            window_aliases: Vec::new(),
+            allowed_origins: None,
+            injected_js: Vec::new(),
+            config_path: None,
         }
     }
 
-    /// Set the WebSocket server port
+    /// Set the WebSocket server port.
+    ///
+    /// Port 0 lets the OS pick a free port, which keeps parallel test runs from fighting over
+    /// 9223. The port it picked is logged once the server is listening, and the app can read it
+    /// from the [`McpServerAddr`] state.
     #[must_use]
     pub const fn port(mut self, port: u16) -> Self {
         self.port = port;
@@ -99,14 +217,52 @@ impl Builder {
 
     /// Set the maximum number of console log entries to capture.
     ///
-    /// Default (100) balances history with memory. Increase if you need more.
-    /// Increase if you need more debug history.
+    /// Default (100) balances history with memory. Increase if you need more debug history.
+    /// 0 turns console capture off: the capture script isn't injected, so `console_logs` is always
+    /// empty.
     #[must_use]
     pub const fn console_log_limit(mut self, limit: u32) -> Self {
         self.console_log_limit = limit;
         self
     }
 
+    /// Set how deep objects passed to `console.log` and friends are serialized.
+    ///
+    /// Nested objects past this depth show up as `[Object]` (or `[Array(n)]`). Default: 4.
+    #[must_use]
+    pub const fn console_serialization_depth(mut self, depth: u32) -> Self {
+        self.console_serialization_depth = depth;
+        self
+    }
+
+    /// Set the maximum size of one console entry's message in bytes.
+    ///
+    /// Longer messages are cut and end with a `[truncated N bytes]` marker. Default: 8 KB.
+    #[must_use]
+    pub const fn console_entry_max_bytes(mut self, max_bytes: u32) -> Self {
+        self.console_entry_max_bytes = max_bytes;
+        self
+    }
+
+    /// Set how long console logs of a destroyed window stay readable.
+    ///
+    /// Lets you read what a window logged right before it closed. Use `Duration::ZERO` to
+    /// discard them right away. Default: 5 minutes.
+    #[must_use]
+    pub const fn console_closed_window_ttl(mut self, ttl: Duration) -> Self {
+        self.console_closed_window_ttl = ttl;
+        self
+    }
+
+    /// Attach the source location (`url`, `line`, `column`) of each `console.*` call to its entry.
+    ///
+    /// Off by default, since it creates an `Error` for every logged entry to read the stack.
+    #[must_use]
+    pub const fn console_capture_source(mut self, enabled: bool) -> Self {
+        self.console_capture_source = enabled;
+        self
+    }
+
     /// Set the log level for tauri-mcp.
     ///
     /// Valid levels: `error`, `warn`, `info`, `debug`, `trace`
@@ -123,21 +279,272 @@ impl Builder {
         self
     }
 
+    /// Set the default timeout for eval-based commands.
+    ///
+    /// Applies to `execute_js`, `console_logs`, `dom_snapshot`, `interact`, and `wait_for`
+    /// when the request doesn't specify its own timeout. The transport-level command
+    /// timeout (`TAURI_MCP_TIMEOUT`) is extended as needed so it never cuts a script short.
+    #[must_use]
+    pub const fn default_script_timeout(mut self, timeout: Duration) -> Self {
+        self.script_timeout = timeout;
+        self
+    }
+
+    /// Set the maximum total size of the files in one `interact` upload (default: 10 MB).
+    ///
+    /// The files travel base64-encoded through the WebSocket and the webview, so larger
+    /// uploads are slow and memory-hungry. Base64 makes them a third bigger, so uploads near the
+    /// limit also need a higher [`max_message_bytes`](Self::max_message_bytes).
+    #[must_use]
+    pub const fn upload_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.upload_max_bytes = max_bytes;
+        self
+    }
+
+    /// Let `interact` send real OS mouse and keyboard input with `native: true`.
+    ///
+    /// Off by default, since native input moves the real cursor and types into whatever window
+    /// has focus. Supported on macOS, where the app needs Accessibility permission, and Windows.
+    #[must_use]
+    pub const fn native_input(mut self, enabled: bool) -> Self {
+        self.native_input = enabled;
+        self
+    }
+
+    /// Let clients move the WebSocket server to another port or host with `server_restart`.
+    ///
+    /// Off by default, since it drops every connected client, which then has to reconnect to
+    /// the new address. Useful in long test sessions that need to reconfigure the server.
+    #[must_use]
+    pub const fn server_restart(mut self, enabled: bool) -> Self {
+        self.server_restart = enabled;
+        self
+    }
+
+    /// Also listen for CDP messages, Chrome's devtools protocol, on the port after the WebSocket
+    /// server's.
+    ///
+    /// Off by default. Lets tools that speak CDP evaluate scripts, take screenshots, read the
+    /// document, and send mouse events through the plugin's commands. Only those methods are
+    /// translated, so it's not a full browser endpoint (see the README). It binds to the same host,
+    /// and port 0 lets the OS pick its port too.
+    #[must_use]
+    pub const fn cdp_compat(mut self, enabled: bool) -> Self {
+        self.cdp_compat = enabled;
+        self
+    }
+
+    /// Also listen for WebDriver BiDi messages two ports after the WebSocket server's.
+    ///
+    /// Off by default. Lets BiDi clients evaluate scripts, take screenshots, list windows as
+    /// browsing contexts, and perform pointer, key, and wheel actions through the plugin's
+    /// commands. Only those commands are translated, so it's not a full WebDriver endpoint (see the
+    /// README). It binds to the same host, and port 0 lets the OS pick its port too.
+    #[must_use]
+    pub const fn bidi_compat(mut self, enabled: bool) -> Self {
+        self.bidi_compat = enabled;
+        self
+    }
+
+    /// Also serve the plugin's commands over gRPC on this port, with the service in
+    /// `proto/mcp.proto`.
+    ///
+    /// Off by default. `Execute` runs a command like a WebSocket request, and `Subscribe` streams a
+    /// topic's events or a Tauri event's. It binds to the same host as the WebSocket server, and
+    /// port 0 lets the OS pick, with the port it picked logged.
+    #[cfg(feature = "grpc")]
+    #[must_use]
+    pub const fn grpc_port(mut self, port: u16) -> Self {
+        self.grpc_port = Some(port);
+        self
+    }
+
+    /// Set how many requests a connection can have waiting to run (default: 50).
+    ///
+    /// Each connection runs its requests one at a time, in order. When the queue is full, new
+    /// requests get a "Server busy, queue full" error right away instead of waiting.
+    #[must_use]
+    pub const fn request_queue_depth(mut self, depth: usize) -> Self {
+        self.request_queue_depth = depth;
+        self
+    }
+
+    /// Set the maximum size of one incoming WebSocket message or frame (default: 10 MB).
+    ///
+    /// Larger messages close the connection with close code 1009 (message too big) before
+    /// they're read into memory, so a runaway client can't exhaust the app's memory.
+    #[must_use]
+    pub const fn max_message_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_message_bytes = max_bytes;
+        self
+    }
+
+    /// Set the time between keep-alive pings on each connection (default: 30 seconds).
+    ///
+    /// Lower it for clients that drop connections that stay quiet for long. Connections that go two
+    /// intervals without a pong are closed. Intervals under a second are raised to one second.
+    #[must_use]
+    pub const fn ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = interval;
+        self
+    }
+
+    /// Close a connection when a ping's pong doesn't arrive within `timeout`.
+    ///
+    /// Notices clients that went away without closing the connection, like after a network drop,
+    /// sooner than the two ping intervals it otherwise takes. Off by default.
+    #[must_use]
+    pub const fn ping_timeout(mut self, timeout: Duration) -> Self {
+        self.ping_timeout = Some(timeout);
+        self
+    }
+
+    /// Only accept WebSocket connections from these origins, like `http://localhost:1420`.
+    ///
+    /// Browsers send an `Origin` header when a page opens a WebSocket, so this stops web pages
+    /// from connecting to the plugin and driving the app. Clients that send no `Origin`, like the
+    /// MCP server, are always accepted.
+    ///
+    /// This can also be set via the `TAURI_MCP_ALLOWED_ORIGINS` environment variable, as a
+    /// comma-separated list. The builder method takes precedence over the environment variable,
+    /// and without either, all origins are allowed.
+    ///
+    /// Each origin must look like `scheme://host[:port]`, without a path. `build` panics on
+    /// malformed ones, from here or from the environment variable.
+    #[must_use]
+    pub fn allowed_origins<I, S>(mut self, origins: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_origins = Some(origins.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Run `script` in every page before the app's own scripts, like a polyfill, a test helper, or
+    /// an API mock.
+    ///
+    /// Can be called more than once, and the scripts run in the order they were added, after the
+    /// plugin's console capture, so what they log is captured. They share one init script with
+    /// the plugin, so a syntax error in one stops the others and the console capture too.
+    #[must_use]
+    pub fn inject_js(mut self, script: impl Into<String>) -> Self {
+        self.injected_js.push(InjectedJs::Script(script.into()));
+        self
+    }
+
+    /// Like [`Builder::inject_js`], with the script read from a file.
+    ///
+    /// The file is read once, by `build`, which panics if it can't be read.
+    #[must_use]
+    pub fn inject_js_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.injected_js.push(InjectedJs::File(path.into()));
+        self
+    }
+
+    /// Let requests refer to a window by an alias instead of its label.
+    ///
+    /// `label` can also be a glob pattern like `main-*`, for windows whose labels vary
+    /// between runs. Requests can use glob patterns in `windowId` directly as well.
+    #[must_use]
+    pub fn window_alias(mut self, alias: impl Into<String>, label: impl Into<String>) -> Self {
+        self.window_aliases.push((alias.into(), label.into()));
+        self
+    }
+
+    /// Read settings from a TOML config file, and reload them whenever it changes.
+    ///
+    /// The file's settings go on top of the builder's, and take effect without restarting the
+    /// app or the WebSocket server:
+    ///
+    /// ```toml
+    /// log_level = "debug"
+    /// console_log_limit = 500
+    /// default_script_timeout_ms = 10000
+    /// upload_max_bytes = 20971520
+    /// request_queue_depth = 100
+    /// max_message_bytes = 20971520
+    /// ping_interval_ms = 10000
+    /// ping_timeout_ms = 5000
+    /// ```
+    ///
+    /// `port`, `host`, and `native_input` can be set in the file too, but are only read at
+    /// startup. Changing them later logs a warning and keeps the running values. A file that's
+    /// missing or invalid is skipped with a warning.
+    #[must_use]
+    pub fn watch_config(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config_path = Some(path.into());
+        self
+    }
+
     /// Build the Tauri plugin
+    ///
+    /// # Panics
+    ///
+    /// If an allowed origin, from [`Builder::allowed_origins`] or `TAURI_MCP_ALLOWED_ORIGINS`,
+    /// is malformed, or if a file from [`Builder::inject_js_file`] can't be read.
     #[must_use]
     pub fn build<R: Runtime>(self) -> TauriPlugin<R> {
+        // Origins: builder > env var > all allowed
+        let origins = AllowedOrigins::new(self.allowed_origins.or_else(AllowedOrigins::from_env))
+            .unwrap_or_else(|e| panic!("tauri-mcp: {e}"));
+        let injected_js = self
+            .injected_js
+            .into_iter()
+            .map(InjectedJs::load)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_or_else(|e| panic!("tauri-mcp: {e}"));
+
         let host = if self.host.is_empty() {
             DEFAULT_HOST.to_string()
         } else {
             self.host
         };
 
-        // Determine log level: builder value > env var > default
+        // Determine log level: config file > builder value > env var > default
         let log_level = self
             .log_level
             .unwrap_or_else(|| std::env::var("TAURI_MCP_LOG_LEVEL").unwrap_or_else(|_| DEFAULT_LOG_LEVEL.to_string()));
 
-        build_plugin(self.port, host, self.console_log_limit, &log_level)
+        let base = Settings {
+            port: self.port,
+            host,
+            log_level,
+            console_log_limit: self.console_log_limit,
+            config: PluginConfig {
+                script_timeout: self.script_timeout,
+                command_timeout: config::command_timeout_from_env(),
+                upload_max_bytes: self.upload_max_bytes,
+                native_input: self.native_input,
+                server_restart: self.server_restart,
+                cdp_compat: self.cdp_compat,
+                bidi_compat: self.bidi_compat,
+                grpc_port: self.grpc_port,
+                request_queue_depth: self.request_queue_depth,
+                max_message_bytes: self.max_message_bytes,
+                ping_interval: self.ping_interval.max(MIN_PING_INTERVAL),
+                ping_timeout: self.ping_timeout,
+            },
+        };
+        let watch = self.config_path.map(|path| ConfigWatch::start(path, base.clone()));
+        let settings = watch.as_ref().map_or(base, |watch| watch.current.clone());
+
+        let capture = CaptureConfig {
+            max_entries: settings.console_log_limit,
+            serialization_depth: self.console_serialization_depth,
+            entry_max_bytes: self.console_entry_max_bytes,
+            closed_window_ttl: self.console_closed_window_ttl,
+            capture_source: self.console_capture_source,
+        };
+
+        build_plugin(
+            settings,
+            capture,
+            WindowRegistry::new(self.window_aliases),
+            origins,
+            &injected_js,
+            watch,
+        )
     }
 }
 
@@ -149,64 +556,145 @@ pub fn init<R: Runtime>() -> TauriPlugin<R> {
     Builder::new().build()
 }
 
+/// Handle to change the log level of the tracing subscriber the plugin set up, if it did
+static LOG_FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
 /// Initialize tracing subscriber with the specified log level.
 ///
 /// If a global subscriber is already set, this does nothing (no error).
 /// The subscriber filters logs to only show `tauri_mcp` module logs at the
 /// specified level or higher.
 fn init_tracing(log_level: &str) {
-    // Build a filter directive for the tauri_mcp module
-    // Format: "tauri_mcp=<level>" to only filter our logs
-    let directive = format!("tauri_mcp={log_level}");
-
-    let filter = EnvFilter::try_new(&directive).unwrap_or_else(|_| {
+    let filter = log_filter(log_level).unwrap_or_else(|e| {
         // Fall back to info level if the provided level is invalid
-        eprintln!(
-            "tauri-mcp: Invalid log level '{log_level}', using 'info'. Valid levels: error, warn, info, debug, trace"
-        );
+        eprintln!("tauri-mcp: {e}. Using 'info'.");
         EnvFilter::new("tauri_mcp=info")
     });
 
     // Try to set the global subscriber. If one is already set, this will
     // silently do nothing (which is fine - the app controls logging).
-    let _ = tracing_subscriber::fmt().with_env_filter(filter).try_init();
+    let (filter, handle) = reload::Layer::new(filter);
+    if tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer())
+        .try_init()
+        .is_ok()
+    {
+        let _ = LOG_FILTER.set(handle);
+    }
+}
+
+/// Change the log level of the tracing subscriber the plugin set up
+fn set_log_level(log_level: &str) -> Result<(), String> {
+    let handle = LOG_FILTER
+        .get()
+        .ok_or("the app set up its own tracing subscriber, so its filter decides the log level")?;
+    handle.reload(log_filter(log_level)?).map_err(|e| e.to_string())
 }
 
-fn build_plugin<R: Runtime>(port: u16, host: String, console_log_limit: u32, log_level: &str) -> TauriPlugin<R> {
+/// Build a filter that only lets through `tauri_mcp` logs at `log_level` or higher
+fn log_filter(log_level: &str) -> Result<EnvFilter, String> {
+    EnvFilter::try_new(format!("tauri_mcp={log_level}"))
+        .map_err(|_| format!("Invalid log level '{log_level}'. Valid levels: error, warn, info, debug, trace"))
+}
+
+fn build_plugin<R: Runtime>(
+    settings: Settings,
+    capture: CaptureConfig,
+    windows: WindowRegistry,
+    origins: AllowedOrigins,
+    injected_js: &[String],
+    watch: Option<ConfigWatch>,
+) -> TauriPlugin<R> {
+    let Settings {
+        port,
+        host,
+        log_level,
+        config,
+        ..
+    } = settings;
+
     // Initialize tracing subscriber if none is set
     // This allows TAURI_MCP_LOG_LEVEL to work out of the box
-    init_tracing(log_level);
+    init_tracing(&log_level);
 
-    // Inject config into console capture script
-    let console_script = format!(
-        "window.__TAURI_MCP_CONFIG__ = {{ maxConsoleEntries: {} }};\n{}",
-        console_log_limit,
-        include_str!("console_capture.js")
-    );
+    // Inject config into console capture script. The builder takes a single init script, so the
+    // closed shadow root tracking and the app's own scripts are appended to it.
+    let shadow_roots = include_str!("scripts/shadow-roots.js");
+    let mut init_script = if capture.enabled() {
+        format!("{}\n{shadow_roots}", capture.init_script())
+    } else {
+        warn!("console_log_limit is 0, so console capture is off and console_logs will return no entries");
+        shadow_roots.to_string()
+    };
+    for script in injected_js {
+        init_script.push('\n');
+        init_script.push_str(script);
+    }
 
     tauri::plugin::Builder::new("mcp")
         .setup(move |app, _api| {
-            let app_handle = app.clone();
             let (ready_tx, ready_rx) = oneshot::channel();
-            let host_for_log = host.clone();
 
-            // Create shutdown channel for graceful server termination
-            let (shutdown_handle, shutdown_rx) = ShutdownHandle::new();
+            // Store shutdown handle in app state for lifecycle management. Each server started
+            // subscribes to it, including the ones server_restart starts.
+            app.manage(ShutdownHandle::default());
+            app.manage(restart::ServerTask::default());
+
+            // Make timeouts and other settings available to command handlers
+            app.manage(ConfigState::new(config));
+
+            // Resolve `windowId` aliases and patterns, tracking creation order
+            app.manage(windows);
+
+            // Checked by the WebSocket handshake
+            app.manage(origins);
+
+            // Store console entries forwarded from webviews
+            app.manage(
+                ConsoleStore::new(usize::try_from(capture.max_entries).unwrap_or(usize::MAX))
+                    .with_closed_window_ttl(capture.closed_window_ttl),
+            );
+            console::register_listener(app);
+
+            // Apply changes to the config file as it's edited
+            if let Some(watch) = watch {
+                tauri::async_runtime::spawn(watch.run(app.clone()));
+            }
 
-            // Store shutdown handle in app state for lifecycle management
-            app.manage(shutdown_handle);
+            // Broadcast page loads to subscribed connections
+            app.manage(Navigation::default());
+
+            // Keep snapshots taken with store: true for dom_diff
+            #[cfg(feature = "dom-snapshot")]
+            app.manage(commands::dom_diff::SnapshotStore::default());
+            // Keep snapshots taken with pageSize for their later pages
+            #[cfg(feature = "dom-snapshot")]
+            app.manage(commands::snapshot_pages::SnapshotPages::default());
 
             // Start WebSocket server in background
-            tauri::async_runtime::spawn(async move {
-                if let Err(e) = websocket::start_server(app_handle, port, &host, ready_tx, shutdown_rx).await {
-                    tracing::error!("WebSocket server error: {e}");
-                }
-            });
+            restart::spawn(app, port, host.clone(), ready_tx);
+
+            // Translate CDP messages on the next port
+            if config.cdp_compat {
+                compat::spawn(app, compat::Protocol::Cdp, port, host.clone());
+            }
+
+            // And WebDriver BiDi messages on the one after
+            if config.bidi_compat {
+                compat::spawn(app, compat::Protocol::Bidi, port, host.clone());
+            }
+
+            // Serve the same commands over gRPC
+            #[cfg(feature = "grpc")]
+            if let Some(grpc_port) = config.grpc_port {
+                grpc::spawn(app, grpc_port, host.clone());
+            }
 
             // Wait for server to be ready (with timeout)
             tauri::async_runtime::spawn(async move {
                 match tokio::time::timeout(std::time::Duration::from_secs(5), ready_rx).await {
-                    Ok(Ok(())) => info!("tauri-mcp WebSocket server ready on {host_for_log}:{port}"),
+                    Ok(Ok(addr)) => info!("tauri-mcp WebSocket server ready on {addr}"),
                     Ok(Err(_)) => tracing::error!("WebSocket server startup cancelled"),
                     Err(_) => tracing::error!("WebSocket server startup timed out"),
                 }
@@ -214,7 +702,35 @@ fn build_plugin<R: Runtime>(port: u16, host: String, console_log_limit: u32, log
 
             Ok(())
         })
+        .on_webview_ready(|webview| {
+            if let Some(windows) = webview.try_state::<WindowRegistry>() {
+                windows.created(webview.label());
+            }
+        })
+        .on_page_load(|webview, payload| {
+            // Tag console entries from here on with a new navigation ID
+            if payload.event() == PageLoadEvent::Started {
+                if let Some(store) = webview.try_state::<ConsoleStore>() {
+                    store.begin_navigation(webview.label());
+                }
+            }
+            if let Some(navigation) = webview.try_state::<Navigation>() {
+                navigation.page_load(NavigationEvent::from_page_load(webview.label(), payload));
+            }
+        })
         .on_event(|app, event| {
+            if let RunEvent::WindowEvent { label, event, .. } = event {
+                if matches!(event, WindowEvent::Destroyed) {
+                    // Keep the window's console buffer readable for a while
+                    if let Some(store) = app.try_state::<ConsoleStore>() {
+                        store.window_closed(label);
+                    }
+                }
+                if let Some(windows) = app.try_state::<WindowRegistry>() {
+                    windows.window_event(label, event);
+                }
+            }
+
             if matches!(event, RunEvent::Exit) {
                 // Trigger graceful shutdown when app exits
                 if let Some(handle) = app.try_state::<ShutdownHandle>() {
@@ -223,6 +739,6 @@ fn build_plugin<R: Runtime>(port: u16, host: String, console_log_limit: u32, log
                 }
             }
         })
-        .js_init_script(console_script)
+        .js_init_script(init_script)
         .build()
 }
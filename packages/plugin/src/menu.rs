@@ -0,0 +1,63 @@
+//! Menu event forwarding for the `menu_click` and `menu_emit` commands.
+//!
+//! Tauri has no API to activate a menu item programmatically, so the app hands the plugin the
+//! same handler it passes to `on_menu_event`, and the commands call it with a synthetic event.
+
+use tauri::menu::{MenuEvent, MenuId};
+use tauri::{AppHandle, Manager, Runtime};
+
+/// A menu event handler, as passed to Tauri's `on_menu_event`
+type Handler<R> = dyn Fn(&AppHandle<R>, MenuEvent) + Send + Sync;
+
+/// The app's menu event handler, managed as app state
+struct MenuHandler<R: Runtime>(Box<Handler<R>>);
+
+/// Let the `menu_click` and `menu_emit` commands trigger the app's menu event handler.
+///
+/// Pass the same handler you give to Tauri's `on_menu_event`. Only the first call takes effect,
+/// like other managed state.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// fn handle_menu_event<R: tauri::Runtime>(app: &tauri::AppHandle<R>, event: tauri::menu::MenuEvent) {
+///     // ...
+/// }
+///
+/// tauri::Builder::default()
+///     .plugin(tauri_mcp::init())
+///     .on_menu_event(handle_menu_event)
+///     .setup(|app| {
+///         tauri_mcp::on_menu_event(app.handle(), handle_menu_event);
+///         Ok(())
+///     })
+/// ```
+pub fn on_menu_event<R, F>(app: &AppHandle<R>, handler: F)
+where
+    R: Runtime,
+    F: Fn(&AppHandle<R>, MenuEvent) + Send + Sync + 'static,
+{
+    app.manage(MenuHandler(Box::new(handler)));
+}
+
+/// Call the registered handler with a menu event for `id` on the main thread, like Tauri does.
+///
+/// Returns an error if the app didn't register a handler.
+pub fn dispatch<R: Runtime>(app: &AppHandle<R>, id: &str) -> Result<(), String> {
+    if app.try_state::<MenuHandler<R>>().is_none() {
+        return Err(
+            "No menu handler registered. Call tauri_mcp::on_menu_event(app.handle(), handler) in your app's setup, \
+             with the handler you pass to on_menu_event."
+                .to_string(),
+        );
+    }
+
+    let handle = app.clone();
+    let event = MenuEvent { id: MenuId::new(id) };
+    app.run_on_main_thread(move || {
+        if let Some(handler) = handle.try_state::<MenuHandler<R>>() {
+            (handler.0)(&handle, event);
+        }
+    })
+    .map_err(|e| e.to_string())
+}
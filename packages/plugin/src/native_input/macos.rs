@@ -0,0 +1,99 @@
+//! macOS native input using Core Graphics events
+//!
+//! macOS drops posted events unless the app has Accessibility permission (System Settings >
+//! Privacy & Security > Accessibility). Setting a keyboard event's text needs one unsafe call,
+//! which reads a UTF-16 buffer through a raw pointer.
+
+#![allow(unsafe_code)]
+
+use std::ffi::c_ulong;
+use std::thread;
+
+use objc2_core_foundation::{CFRetained, CGPoint};
+use objc2_core_graphics::{CGEvent, CGEventField, CGEventTapLocation, CGEventType, CGMouseButton, CGScrollEventUnit};
+
+use super::{Input, ScreenPoint, KEY_INTERVAL};
+
+/// Send native input by posting Core Graphics events
+pub fn send(input: &Input) -> Result<(), String> {
+    match input {
+        Input::Click(point) => click(*point),
+        Input::Text(text) => type_text(text),
+        Input::Scroll {
+            point,
+            delta_x,
+            delta_y,
+        } => scroll(*point, *delta_x, *delta_y),
+    }
+}
+
+fn click(point: ScreenPoint) -> Result<(), String> {
+    move_to(point)?;
+    thread::sleep(KEY_INTERVAL);
+    for event_type in [CGEventType::LeftMouseDown, CGEventType::LeftMouseUp] {
+        let event = mouse_event(event_type, point)?;
+        // Single click, so the page sees detail 1
+        CGEvent::set_integer_value_field(Some(&*event), CGEventField::MouseEventClickState, 1);
+        post(&event);
+    }
+    Ok(())
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn type_text(text: &str) -> Result<(), String> {
+    let mut buffer = [0u16; 2];
+    for character in text.chars() {
+        let units = character.encode_utf16(&mut buffer);
+        for key_down in [true, false] {
+            // The key code doesn't matter once the event carries its own text
+            let event = CGEvent::new_keyboard_event(None, 0, key_down).ok_or("Failed to create a keyboard event")?;
+            // Safety: `units` holds `units.len()` UTF-16 code units and outlives the call
+            unsafe {
+                CGEvent::keyboard_set_unicode_string(Some(&*event), units.len() as c_ulong, units.as_ptr());
+            }
+            post(&event);
+        }
+        thread::sleep(KEY_INTERVAL);
+    }
+    Ok(())
+}
+
+fn scroll(point: ScreenPoint, delta_x: f64, delta_y: f64) -> Result<(), String> {
+    move_to(point)?;
+    thread::sleep(KEY_INTERVAL);
+    // Positive wheel values scroll up and left, the opposite of DOM deltas
+    let event = CGEvent::new_scroll_wheel_event2(
+        None,
+        CGScrollEventUnit::Pixel,
+        2,
+        wheel_pixels(-delta_y),
+        wheel_pixels(-delta_x),
+        0,
+    )
+    .ok_or("Failed to create a scroll wheel event")?;
+    post(&event);
+    Ok(())
+}
+
+fn move_to(point: ScreenPoint) -> Result<(), String> {
+    let event = mouse_event(CGEventType::MouseMoved, point)?;
+    post(&event);
+    Ok(())
+}
+
+fn mouse_event(event_type: CGEventType, point: ScreenPoint) -> Result<CFRetained<CGEvent>, String> {
+    // Core Graphics places events in points from the top-left of the main display
+    let (x, y) = point.logical();
+    CGEvent::new_mouse_event(None, event_type, CGPoint::new(x, y), CGMouseButton::Left)
+        .ok_or_else(|| "Failed to create a mouse event".to_string())
+}
+
+fn post(event: &CGEvent) {
+    CGEvent::post(CGEventTapLocation::HIDEventTap, Some(event));
+}
+
+/// Round a scroll distance to whole pixels, kept in range for the event
+#[allow(clippy::cast_possible_truncation)]
+fn wheel_pixels(pixels: f64) -> i32 {
+    pixels.round().clamp(f64::from(i32::MIN), f64::from(i32::MAX)) as i32
+}
@@ -0,0 +1,133 @@
+//! Platform-specific native input: real mouse and keyboard events sent through the OS, which the
+//! webview treats like a user's (`event.isTrusted`, `:active`, native scrolling, IME)
+//!
+//! Native input moves the real cursor and types into whichever window has focus, so `interact`
+//! only uses it when the app opts in with `Builder::native_input`.
+
+#[cfg(target_os = "macos")]
+mod macos;
+
+#[cfg(target_os = "windows")]
+mod windows;
+
+use std::time::Duration;
+
+use tauri::{PhysicalPosition, Runtime, WebviewWindow};
+
+/// Pause between typed characters, and between moving the cursor and clicking
+pub const KEY_INTERVAL: Duration = Duration::from_millis(5);
+
+/// A point on the screen in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScreenPoint {
+    pub x: f64,
+    pub y: f64,
+    /// Scale factor of the window the point is in, for platforms that place events in logical
+    /// pixels
+    pub scale_factor: f64,
+}
+
+impl ScreenPoint {
+    /// Translate a point in the webview's CSS pixels to the screen. `origin` is where the
+    /// webview's top-left corner is on the screen, and `device_pixel_ratio` the page's
+    /// `window.devicePixelRatio`, which is the scale factor times the page zoom.
+    pub fn from_css(origin: PhysicalPosition<i32>, scale_factor: f64, device_pixel_ratio: f64, x: f64, y: f64) -> Self {
+        Self {
+            x: x.mul_add(device_pixel_ratio, f64::from(origin.x)),
+            y: y.mul_add(device_pixel_ratio, f64::from(origin.y)),
+            scale_factor,
+        }
+    }
+
+    /// The point in logical pixels, like macOS places events in
+    #[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+    pub fn logical(self) -> (f64, f64) {
+        (self.x / self.scale_factor, self.y / self.scale_factor)
+    }
+}
+
+/// OS input to send
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(not(any(target_os = "macos", target_os = "windows")), allow(dead_code))]
+pub enum Input {
+    /// Move the cursor to the point and click the left button
+    Click(ScreenPoint),
+    /// Type the text into the focused window, one character at a time
+    Text(String),
+    /// Move the cursor to the point and turn the wheel by CSS pixels, positive for right and down
+    Scroll {
+        point: ScreenPoint,
+        delta_x: f64,
+        delta_y: f64,
+    },
+}
+
+/// Get where a point in the window's webview, in CSS pixels, is on the screen
+pub fn screen_point<R: Runtime>(
+    window: &WebviewWindow<R>,
+    x: f64,
+    y: f64,
+    device_pixel_ratio: f64,
+) -> Result<ScreenPoint, String> {
+    // The outer position includes the title bar and borders, and the webview fills the rest
+    let origin = window.inner_position().map_err(|e| e.to_string())?;
+    let scale_factor = window.scale_factor().map_err(|e| e.to_string())?;
+    Ok(ScreenPoint::from_css(origin, scale_factor, device_pixel_ratio, x, y))
+}
+
+/// Check that this platform can send native input
+pub fn check_supported() -> Result<(), String> {
+    if cfg!(any(target_os = "macos", target_os = "windows")) {
+        Ok(())
+    } else {
+        Err(unsupported())
+    }
+}
+
+/// Send the input. Blocks while it types, so run it on a blocking thread.
+pub fn send(input: &Input) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::send(input)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows::send(input)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    {
+        let _ = input;
+        Err(unsupported())
+    }
+}
+
+fn unsupported() -> String {
+    "Native input isn't supported on this platform yet, only on macOS and Windows. Leave out 'native' to use DOM events."
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_css_offsets_by_the_webview_origin() {
+        let point = ScreenPoint::from_css(PhysicalPosition::new(100, 50), 1.0, 1.0, 10.0, 20.0);
+        assert!((point.x - 110.0).abs() < f64::EPSILON);
+        assert!((point.y - 70.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn from_css_scales_by_the_device_pixel_ratio() {
+        // A 2x display with the page zoomed to 150%
+        let point = ScreenPoint::from_css(PhysicalPosition::new(200, 100), 2.0, 3.0, 10.0, 20.0);
+        assert!((point.x - 230.0).abs() < f64::EPSILON);
+        assert!((point.y - 160.0).abs() < f64::EPSILON);
+
+        let (x, y) = point.logical();
+        assert!((x - 115.0).abs() < f64::EPSILON);
+        assert!((y - 80.0).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,138 @@
+//! Windows native input using `SendInput`
+//!
+//! `SendInput` and `SetCursorPos` are Win32 calls, so they need unsafe blocks. Windows blocks
+//! input into windows of elevated processes and while a UAC prompt is showing.
+
+#![allow(unsafe_code)]
+
+use std::thread;
+
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, INPUT_MOUSE, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+    KEYEVENTF_UNICODE, MOUSEEVENTF_HWHEEL, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, MOUSEEVENTF_WHEEL, MOUSEINPUT,
+    MOUSE_EVENT_FLAGS, VIRTUAL_KEY,
+};
+use windows::Win32::UI::WindowsAndMessaging::{SetCursorPos, WHEEL_DELTA};
+
+use super::{Input, ScreenPoint, KEY_INTERVAL};
+
+/// CSS pixels `WebView2` scrolls per wheel notch (`WHEEL_DELTA`) with the default system settings
+const PIXELS_PER_WHEEL_NOTCH: f64 = 100.0;
+
+/// Send native input with `SendInput`
+pub fn send(input: &Input) -> Result<(), String> {
+    match input {
+        Input::Click(point) => click(*point),
+        Input::Text(text) => type_text(text),
+        Input::Scroll {
+            point,
+            delta_x,
+            delta_y,
+        } => scroll(*point, *delta_x, *delta_y),
+    }
+}
+
+fn click(point: ScreenPoint) -> Result<(), String> {
+    move_to(point)?;
+    thread::sleep(KEY_INTERVAL);
+    send_inputs(&[mouse_input(MOUSEEVENTF_LEFTDOWN, 0), mouse_input(MOUSEEVENTF_LEFTUP, 0)])
+}
+
+fn type_text(text: &str) -> Result<(), String> {
+    let mut buffer = [0u16; 2];
+    for character in text.chars() {
+        let inputs = character
+            .encode_utf16(&mut buffer)
+            .iter()
+            .flat_map(|unit| {
+                [
+                    key_input(*unit, KEYEVENTF_UNICODE),
+                    key_input(*unit, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP),
+                ]
+            })
+            .collect::<Vec<_>>();
+        send_inputs(&inputs)?;
+        thread::sleep(KEY_INTERVAL);
+    }
+    Ok(())
+}
+
+fn scroll(point: ScreenPoint, delta_x: f64, delta_y: f64) -> Result<(), String> {
+    move_to(point)?;
+    thread::sleep(KEY_INTERVAL);
+    let mut inputs = Vec::new();
+    // The vertical wheel scrolls up for positive values, the opposite of DOM deltas
+    if delta_y != 0.0 {
+        inputs.push(mouse_input(MOUSEEVENTF_WHEEL, wheel_data(-delta_y)));
+    }
+    if delta_x != 0.0 {
+        inputs.push(mouse_input(MOUSEEVENTF_HWHEEL, wheel_data(delta_x)));
+    }
+    send_inputs(&inputs)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn move_to(point: ScreenPoint) -> Result<(), String> {
+    // Tauri apps are per-monitor DPI aware, so the cursor is placed in physical pixels
+    let x = point.x.round().clamp(f64::from(i32::MIN), f64::from(i32::MAX)) as i32;
+    let y = point.y.round().clamp(f64::from(i32::MIN), f64::from(i32::MAX)) as i32;
+    // Safety: SetCursorPos takes no pointers
+    unsafe { SetCursorPos(x, y) }.map_err(|e| format!("Failed to move the cursor: {e}"))
+}
+
+/// A wheel turn of `pixels` CSS pixels in the signed `WHEEL_DELTA` units `mouseData` holds
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn wheel_data(pixels: f64) -> u32 {
+    let delta = (pixels / PIXELS_PER_WHEEL_NOTCH * f64::from(WHEEL_DELTA))
+        .round()
+        .clamp(f64::from(i32::MIN), f64::from(i32::MAX)) as i32;
+    delta as u32
+}
+
+const fn mouse_input(flags: MOUSE_EVENT_FLAGS, data: u32) -> INPUT {
+    INPUT {
+        r#type: INPUT_MOUSE,
+        Anonymous: INPUT_0 {
+            mi: MOUSEINPUT {
+                dx: 0,
+                dy: 0,
+                mouseData: data,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
+const fn key_input(unit: u16, flags: KEYBD_EVENT_FLAGS) -> INPUT {
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: VIRTUAL_KEY(0),
+                wScan: unit,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
+fn send_inputs(inputs: &[INPUT]) -> Result<(), String> {
+    if inputs.is_empty() {
+        return Ok(());
+    }
+    let size = i32::try_from(std::mem::size_of::<INPUT>()).map_err(|e| e.to_string())?;
+    // Safety: `inputs` is a live slice of initialized INPUT structs and `size` is their size
+    let sent = unsafe { SendInput(inputs, size) };
+    if usize::try_from(sent).ok() == Some(inputs.len()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Windows accepted {sent} of {} input events. Input may be blocked by an elevated window or a UAC prompt.",
+            inputs.len()
+        ))
+    }
+}
@@ -0,0 +1,102 @@
+//! Page loads in the app's webviews, broadcast to connections subscribed to the `navigation`
+//! topic (see [`crate::subscriptions`]).
+
+use serde::Serialize;
+use tauri::webview::{PageLoadEvent, PageLoadPayload};
+use tokio::sync::broadcast;
+
+/// How many navigation events a subscriber can fall behind before it misses some
+const NAVIGATION_HIGH_WATER_MARK: usize = 256;
+
+/// A webview starting or finishing loading a page
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NavigationEvent {
+    /// `started` or `finished`
+    pub event: &'static str,
+    pub url: String,
+    pub window_label: String,
+}
+
+impl NavigationEvent {
+    pub fn from_page_load(window_label: &str, payload: &PageLoadPayload<'_>) -> Self {
+        Self {
+            event: match payload.event() {
+                PageLoadEvent::Started => "started",
+                PageLoadEvent::Finished => "finished",
+            },
+            url: payload.url().to_string(),
+            window_label: window_label.to_string(),
+        }
+    }
+}
+
+/// Broadcasts navigation events, managed as app state
+#[derive(Debug)]
+pub struct Navigation {
+    sender: broadcast::Sender<NavigationEvent>,
+}
+
+impl Default for Navigation {
+    fn default() -> Self {
+        Self {
+            sender: broadcast::channel(NAVIGATION_HIGH_WATER_MARK).0,
+        }
+    }
+}
+
+impl Navigation {
+    /// Tell subscribers about a page load
+    pub fn page_load(&self, event: NavigationEvent) {
+        // No receivers just means nobody is subscribed
+        let _ = self.sender.send(event);
+    }
+
+    /// Receive navigation events from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<NavigationEvent> {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn navigation_event_serializes_in_camel_case() {
+        let event = NavigationEvent {
+            event: "finished",
+            url: "tauri://localhost/settings".to_string(),
+            window_label: "main".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({ "event": "finished", "url": "tauri://localhost/settings", "windowLabel": "main" })
+        );
+    }
+
+    #[test]
+    fn subscribers_receive_page_loads_in_order() {
+        let navigation = Navigation::default();
+        // Sending without subscribers is fine
+        navigation.page_load(NavigationEvent {
+            event: "started",
+            url: "tauri://localhost/".to_string(),
+            window_label: "main".to_string(),
+        });
+
+        let mut rx = navigation.subscribe();
+        for event in ["started", "finished"] {
+            navigation.page_load(NavigationEvent {
+                event,
+                url: "tauri://localhost/about".to_string(),
+                window_label: "about".to_string(),
+            });
+        }
+        assert_eq!(rx.try_recv().unwrap().event, "started");
+        assert_eq!(rx.try_recv().unwrap().event, "finished");
+        assert!(rx.try_recv().is_err());
+    }
+}
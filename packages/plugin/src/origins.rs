@@ -0,0 +1,135 @@
+//! Origins allowed to open WebSocket connections.
+//!
+//! Browsers send an `Origin` header with WebSocket handshakes, so a page the user happens to visit
+//! could otherwise connect to the plugin on localhost and drive the app. With allowed origins set,
+//! handshakes from other origins are refused. Clients that send no `Origin`, like the MCP server,
+//! aren't browsers and are always let in.
+
+/// Environment variable with comma-separated allowed origins, read when the builder sets none
+pub const ALLOWED_ORIGINS_ENV: &str = "TAURI_MCP_ALLOWED_ORIGINS";
+
+/// Origins allowed to connect, or all of them
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AllowedOrigins(Option<Vec<String>>);
+
+impl AllowedOrigins {
+    /// Allow only `origins`, or all origins for `None`. Fails on the first malformed origin.
+    pub fn new(origins: Option<Vec<String>>) -> Result<Self, String> {
+        origins
+            .map(|origins| origins.iter().map(|origin| parse(origin)).collect())
+            .transpose()
+            .map(Self)
+    }
+
+    /// Read the origins from `TAURI_MCP_ALLOWED_ORIGINS`, if it's set and not blank
+    pub fn from_env() -> Option<Vec<String>> {
+        std::env::var(ALLOWED_ORIGINS_ENV).ok().and_then(|value| split(&value))
+    }
+
+    /// Whether a handshake with this `Origin` header may connect
+    pub fn allows(&self, origin: Option<&str>) -> bool {
+        match (&self.0, origin) {
+            (None, _) | (Some(_), None) => true,
+            (Some(allowed), Some(origin)) => allowed.iter().any(|allowed| allowed.eq_ignore_ascii_case(origin)),
+        }
+    }
+}
+
+/// Split a comma-separated list of origins, or get `None` if it has none
+fn split(value: &str) -> Option<Vec<String>> {
+    let origins: Vec<String> = value
+        .split(',')
+        .map(str::trim)
+        .filter(|origin| !origin.is_empty())
+        .map(String::from)
+        .collect();
+    (!origins.is_empty()).then_some(origins)
+}
+
+/// Check that `origin` looks like `scheme://host[:port]`, like `http://localhost:1420` or
+/// `tauri://localhost`, and lowercase it to compare with `Origin` headers
+fn parse(origin: &str) -> Result<String, String> {
+    let malformed = |reason: &str| format!("Invalid allowed origin '{origin}': {reason}");
+    let (scheme, authority) = origin
+        .split_once("://")
+        .ok_or_else(|| malformed("expected scheme://host[:port]"))?;
+    let scheme_ok = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c));
+    if !scheme_ok {
+        return Err(malformed("the scheme isn't valid"));
+    }
+    if authority.contains(['/', '?', '#']) {
+        return Err(malformed("an origin has no path, query, or fragment"));
+    }
+    if authority.contains('@') || authority.chars().any(char::is_whitespace) {
+        return Err(malformed("the host isn't valid"));
+    }
+    // The last colon starts the port, unless it's inside an IPv6 address like [::1]
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) if !port.ends_with(']') => (host, Some(port)),
+        _ => (authority, None),
+    };
+    if host.is_empty() {
+        return Err(malformed("the host is missing"));
+    }
+    if let Some(port) = port {
+        port.parse::<u16>()
+            .map_err(|_| malformed("the port must be a number from 0 to 65535"))?;
+    }
+    Ok(origin.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn origins_parse_and_lowercase() {
+        assert_eq!(parse("http://localhost:1420"), Ok("http://localhost:1420".to_string()));
+        assert_eq!(parse("HTTPS://Example.com"), Ok("https://example.com".to_string()));
+        assert_eq!(parse("tauri://localhost"), Ok("tauri://localhost".to_string()));
+        assert_eq!(parse("http://[::1]:8080"), Ok("http://[::1]:8080".to_string()));
+        assert_eq!(parse("http://[::1]"), Ok("http://[::1]".to_string()));
+    }
+
+    #[test]
+    fn malformed_origins_are_rejected() {
+        for (origin, reason) in [
+            ("localhost:1420", "scheme://host"),
+            ("http://localhost:1420/", "no path"),
+            ("http://example.com/app", "no path"),
+            ("http://:80", "host is missing"),
+            ("http://localhost:99999", "port"),
+            ("1http://localhost", "scheme"),
+            ("http://user@example.com", "host isn't valid"),
+        ] {
+            let error = parse(origin).unwrap_err();
+            assert!(error.contains(reason), "{origin}: {error}");
+        }
+    }
+
+    #[test]
+    fn env_values_split_on_commas() {
+        assert_eq!(
+            split(" http://localhost:1420, tauri://localhost ,,"),
+            Some(vec![
+                "http://localhost:1420".to_string(),
+                "tauri://localhost".to_string()
+            ])
+        );
+        assert_eq!(split(" , "), None);
+    }
+
+    #[test]
+    fn only_listed_origins_and_non_browsers_are_allowed() {
+        let origins = AllowedOrigins::new(Some(vec!["http://localhost:1420".to_string()])).unwrap();
+        assert!(origins.allows(Some("http://LOCALHOST:1420")));
+        assert!(origins.allows(None));
+        assert!(!origins.allows(Some("https://evil.example")));
+        assert!(AllowedOrigins::default().allows(Some("https://evil.example")));
+        assert!(AllowedOrigins::new(Some(vec!["nope".to_string()]))
+            .unwrap_err()
+            .contains("Invalid allowed origin 'nope'"));
+    }
+}
@@ -0,0 +1,177 @@
+//! `server_restart`: move the WebSocket server to a new port or host without restarting the app.
+//!
+//! The old accept loop stops and frees its address, then the server listens on the new one
+//! before answering, so the answer has the actual address even for port 0. Open connections,
+//! including the one that asked, answer the requests they already took and then close, and the
+//! client reconnects to the new address. If the new address can't be bound, the server goes back
+//! to the old one.
+//!
+//! Off unless the app opts in with `Builder::server_restart(true)`, since any client could
+//! otherwise move the server away from the others.
+
+use std::net::SocketAddr;
+use std::sync::{Mutex, PoisonError};
+
+use serde_json::{json, Value};
+use tauri::async_runtime::JoinHandle;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tracing::{error, info, warn};
+
+use crate::commands;
+use crate::websocket::{self, McpServerAddr, ShutdownHandle};
+
+/// Command that moves the server to a new address
+pub const RESTART_COMMAND: &str = "server_restart";
+
+/// The server's accept loop and where it was asked to listen, managed as app state so
+/// `server_restart` can stop it and start another
+#[derive(Default)]
+pub struct ServerTask(Mutex<Option<Running>>);
+
+struct Running {
+    host: String,
+    /// The port asked for, which is 0 if the OS picked one
+    port: u16,
+    task: JoinHandle<()>,
+}
+
+impl ServerTask {
+    fn take(&self) -> Option<Running> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).take()
+    }
+
+    fn put(&self, running: Running) {
+        *self.0.lock().unwrap_or_else(PoisonError::into_inner) = Some(running);
+    }
+}
+
+/// Start the server in the background, keeping its task for `server_restart`
+pub fn spawn<R: Runtime>(app: &AppHandle<R>, port: u16, host: String, ready_tx: oneshot::Sender<SocketAddr>) {
+    let shutdown_rx = app.state::<ShutdownHandle>().subscribe();
+    let app_handle = app.clone();
+    let bind_host = host.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        if let Err(e) = websocket::start_server(app_handle, port, &bind_host, ready_tx, shutdown_rx).await {
+            error!("WebSocket server error: {e}");
+        }
+    });
+    app.state::<ServerTask>().put(Running { host, port, task });
+}
+
+/// Serve an already bound listener in the background
+fn serve<R: Runtime>(app: &AppHandle<R>, listener: TcpListener, host: String, port: u16) {
+    let shutdown_rx = app.state::<ShutdownHandle>().subscribe();
+    let task = tauri::async_runtime::spawn(websocket::serve(app.clone(), listener, shutdown_rx));
+    app.state::<ServerTask>().put(Running { host, port, task });
+}
+
+/// Stop listening and listen on `port` and `host` from the args instead, each defaulting to the
+/// current one
+pub async fn restart<R: Runtime>(app: &AppHandle<R>, args: &Value) -> Result<Value, String> {
+    if !commands::plugin_config(app).server_restart {
+        return Err(
+            "server_restart is off. It moves the server away from every connected client, so the app has \
+             to opt in with tauri_plugin_mcp::Builder::new().server_restart(true)."
+                .to_string(),
+        );
+    }
+    let port = port_arg(args)?;
+    let host = host_arg(args)?;
+
+    let tasks = app
+        .try_state::<ServerTask>()
+        .ok_or("The WebSocket server isn't running")?;
+    let running = tasks.take().ok_or("The server is already restarting")?;
+    let previous = app.try_state::<McpServerAddr>().map(|addr| addr.get());
+    // Without a new port, stay on the one in use, even if the OS picked it
+    let old_port = previous.map_or(running.port, |addr| addr.port());
+    let port = port.unwrap_or(old_port);
+    let host = host.unwrap_or_else(|| running.host.clone());
+
+    // Stop the old accept loop, which frees its address and tells its connections to wind down
+    app.state::<ShutdownHandle>().shutdown();
+    if let Err(e) = running.task.await {
+        warn!("The WebSocket server's task failed: {e}");
+    }
+
+    match websocket::listen(app, port, &host).await {
+        Ok(listener) => {
+            let addr = listener.local_addr().map_err(|e| e.to_string())?;
+            info!("tauri-mcp WebSocket server restarted on {addr}");
+            serve(app, listener, host.clone(), port);
+            Ok(json!({
+                "address": addr.to_string(),
+                "host": host,
+                "port": addr.port(),
+                "previousAddress": previous.map(|addr| addr.to_string()),
+            }))
+        }
+        Err(e) => {
+            let failed = format!("Can't listen on {host}:{port}: {e}");
+            warn!("{failed}, going back to {}:{old_port}", running.host);
+            let listener = websocket::listen(app, old_port, &running.host).await.map_err(|back| {
+                error!("Can't go back to {}:{old_port} either: {back}", running.host);
+                format!(
+                    "{failed}. Going back to {}:{old_port} failed too ({back}), so the server is stopped.",
+                    running.host
+                )
+            })?;
+            serve(app, listener, running.host.clone(), running.port);
+            Err(format!("{failed}. The server is still on {}:{old_port}.", running.host))
+        }
+    }
+}
+
+/// Check the optional `port`: 0 to 65535, where 0 lets the OS pick
+fn port_arg(args: &Value) -> Result<Option<u16>, String> {
+    match args.get("port") {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => value
+            .as_u64()
+            .and_then(|port| u16::try_from(port).ok())
+            .map(Some)
+            .ok_or_else(|| format!("'port' must be an integer from 0 to 65535, got: {value}")),
+    }
+}
+
+/// Check the optional `host`, a bind address like `localhost` or `0.0.0.0`
+fn host_arg(args: &Value) -> Result<Option<String>, String> {
+    match args.get("host") {
+        None | Some(Value::Null) => Ok(None),
+        Some(Value::String(host)) if !host.trim().is_empty() => Ok(Some(host.trim().to_string())),
+        Some(value) => Err(format!(
+            "'host' must be a bind address like \"localhost\" or \"0.0.0.0\", got: {value}"
+        )),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn port_is_optional_and_fits_in_u16() {
+        assert_eq!(port_arg(&json!({})), Ok(None));
+        assert_eq!(port_arg(&json!({ "port": 0 })), Ok(Some(0)));
+        assert_eq!(port_arg(&json!({ "port": 9300 })), Ok(Some(9300)));
+        for garbage in [json!(65536), json!(-1), json!("9300"), json!(1.5)] {
+            assert!(port_arg(&json!({ "port": garbage }))
+                .unwrap_err()
+                .contains("from 0 to 65535"));
+        }
+    }
+
+    #[test]
+    fn host_is_a_non_blank_string() {
+        assert_eq!(host_arg(&json!({})), Ok(None));
+        assert_eq!(
+            host_arg(&json!({ "host": " 0.0.0.0 " })),
+            Ok(Some("0.0.0.0".to_string()))
+        );
+        assert!(host_arg(&json!({ "host": "" })).unwrap_err().contains("bind address"));
+        assert!(host_arg(&json!({ "host": 1 })).unwrap_err().contains("bind address"));
+    }
+}
@@ -0,0 +1,309 @@
+//! Compositing window screenshots into one image laid out like the desktop
+
+/// Largest composite canvas, in pixels (64 megapixels, 256 MB of RGBA)
+const MAX_CANVAS_PIXELS: u64 = 64 * 1024 * 1024;
+
+/// Color of the border `padding` draws around each window (opaque mid-gray)
+const BORDER_COLOR: [u8; 4] = [128, 128, 128, 255];
+
+/// An 8-bit RGBA image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    /// Row-major RGBA pixels, 4 bytes each
+    pub pixels: Vec<u8>,
+}
+
+impl RgbaImage {
+    /// A fully transparent image
+    fn transparent(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; width as usize * height as usize * 4],
+        }
+    }
+
+    /// Fill a rectangle with one color, clipped to the image
+    fn fill(&mut self, x: u32, y: u32, width: u32, height: u32, color: [u8; 4]) {
+        for row in y..(y + height).min(self.height) {
+            for col in x..(x + width).min(self.width) {
+                let i = (row as usize * self.width as usize + col as usize) * 4;
+                self.pixels[i..i + 4].copy_from_slice(&color);
+            }
+        }
+    }
+
+    /// Copy out a rectangle, clipped to the image
+    #[cfg_attr(not(target_os = "windows"), allow(dead_code))] // Only Windows crops clipped screenshots itself
+    pub fn crop(&self, x: u32, y: u32, width: u32, height: u32) -> Self {
+        let x = x.min(self.width);
+        let y = y.min(self.height);
+        let mut cropped = Self::transparent(width.min(self.width - x), height.min(self.height - y));
+        let columns = cropped.width as usize * 4;
+        for row in 0..cropped.height {
+            let from = ((y + row) as usize * self.width as usize + x as usize) * 4;
+            let to = row as usize * columns;
+            cropped.pixels[to..to + columns].copy_from_slice(&self.pixels[from..from + columns]);
+        }
+        cropped
+    }
+
+    /// Copy another image in with its top-left corner at (x, y), clipped to this image
+    fn paste(&mut self, other: &Self, x: u32, y: u32) {
+        let columns = other.width.min(self.width.saturating_sub(x)) as usize * 4;
+        for row in 0..other.height.min(self.height.saturating_sub(y)) {
+            let from = row as usize * other.width as usize * 4;
+            let to = ((y + row) as usize * self.width as usize + x as usize) * 4;
+            self.pixels[to..to + columns].copy_from_slice(&other.pixels[from..from + columns]);
+        }
+    }
+}
+
+/// A window's screenshot and where the window is on the desktop
+#[derive(Debug)]
+pub struct Layer {
+    pub label: String,
+    /// Outer position of the window in physical pixels
+    pub x: i32,
+    pub y: i32,
+    pub image: RgbaImage,
+}
+
+/// Where a window ended up in the composite
+#[derive(Debug, PartialEq, Eq)]
+pub struct Placement {
+    pub label: String,
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Paint the layers onto a canvas covering all of them, in order (later layers on top), each with
+/// a `padding`-wide border. The canvas origin is the top-left of the bounding box of all windows
+/// and their borders.
+pub fn compose(layers: &[Layer], padding: u32) -> Result<(RgbaImage, Vec<Placement>), String> {
+    if layers.is_empty() {
+        return Err("No window screenshots to composite".to_string());
+    }
+
+    let pad = i64::from(padding);
+    let left = layers.iter().map(|layer| i64::from(layer.x) - pad).min().unwrap_or(0);
+    let top = layers.iter().map(|layer| i64::from(layer.y) - pad).min().unwrap_or(0);
+    let right = layers
+        .iter()
+        .map(|layer| i64::from(layer.x) + i64::from(layer.image.width) + pad)
+        .max()
+        .unwrap_or(0);
+    let bottom = layers
+        .iter()
+        .map(|layer| i64::from(layer.y) + i64::from(layer.image.height) + pad)
+        .max()
+        .unwrap_or(0);
+
+    let (width, height) = (right - left, bottom - top);
+    if width.unsigned_abs() * height.unsigned_abs() > MAX_CANVAS_PIXELS {
+        return Err(format!(
+            "Composite would be {width}x{height} pixels, over the {MAX_CANVAS_PIXELS}-pixel limit. \
+             Move the windows closer together or capture them one by one with screenshot."
+        ));
+    }
+    let to_u32 = |value: i64| u32::try_from(value).map_err(|_| format!("Composite coordinate out of range: {value}"));
+    let mut canvas = RgbaImage::transparent(to_u32(width)?, to_u32(height)?);
+
+    let mut placements = Vec::with_capacity(layers.len());
+    for layer in layers {
+        let x = to_u32(i64::from(layer.x) - left)?;
+        let y = to_u32(i64::from(layer.y) - top)?;
+        let (w, h) = (layer.image.width, layer.image.height);
+        if padding > 0 {
+            canvas.fill(x - padding, y - padding, w + 2 * padding, h + 2 * padding, BORDER_COLOR);
+        }
+        canvas.paste(&layer.image, x, y);
+        placements.push(Placement {
+            label: layer.label.clone(),
+            x,
+            y,
+            width: w,
+            height: h,
+        });
+    }
+
+    Ok((canvas, placements))
+}
+
+/// Decode a PNG into 8-bit RGBA
+pub fn decode_png(data: &[u8]) -> Result<RgbaImage, String> {
+    let mut decoder = png::Decoder::new(data);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|e| format!("Failed to decode PNG: {e}"))?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader
+        .next_frame(&mut buf)
+        .map_err(|e| format!("Failed to decode PNG: {e}"))?;
+    buf.truncate(info.buffer_size());
+
+    let pixels = match info.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::Rgb => buf.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+        png::ColorType::GrayscaleAlpha => buf.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+        png::ColorType::Grayscale => buf.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+        png::ColorType::Indexed => return Err("Failed to decode PNG: palette wasn't expanded".to_string()),
+    };
+
+    Ok(RgbaImage {
+        width: info.width,
+        height: info.height,
+        pixels,
+    })
+}
+
+/// Encode an RGBA image as PNG
+pub fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+    let mut encoder = png::Encoder::new(&mut data, image.width, image.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder
+        .write_header()
+        .map_err(|e| format!("Failed to encode PNG: {e}"))?;
+    writer
+        .write_image_data(&image.pixels)
+        .map_err(|e| format!("Failed to encode PNG: {e}"))?;
+    writer.finish().map_err(|e| format!("Failed to encode PNG: {e}"))?;
+    Ok(data)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn solid(width: u32, height: u32, color: [u8; 4]) -> RgbaImage {
+        let mut image = RgbaImage::transparent(width, height);
+        image.fill(0, 0, width, height, color);
+        image
+    }
+
+    fn pixel(image: &RgbaImage, x: u32, y: u32) -> [u8; 4] {
+        let i = (y as usize * image.width as usize + x as usize) * 4;
+        image.pixels[i..i + 4].try_into().unwrap()
+    }
+
+    fn layer(label: &str, x: i32, y: i32, image: RgbaImage) -> Layer {
+        Layer {
+            label: label.to_string(),
+            x,
+            y,
+            image,
+        }
+    }
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+    #[test]
+    fn compose_places_windows_relative_to_bounding_box() {
+        let layers = [
+            layer("main", 100, 50, solid(4, 3, RED)),
+            layer("settings", 110, 40, solid(2, 2, BLUE)),
+        ];
+
+        let (canvas, placements) = compose(&layers, 0).unwrap();
+
+        assert_eq!((canvas.width, canvas.height), (12, 13));
+        assert_eq!(
+            placements[0],
+            Placement {
+                label: "main".to_string(),
+                x: 0,
+                y: 10,
+                width: 4,
+                height: 3
+            }
+        );
+        assert_eq!(
+            placements[1],
+            Placement {
+                label: "settings".to_string(),
+                x: 10,
+                y: 0,
+                width: 2,
+                height: 2
+            }
+        );
+        assert_eq!(pixel(&canvas, 0, 10), RED);
+        assert_eq!(pixel(&canvas, 11, 1), BLUE);
+        assert_eq!(pixel(&canvas, 5, 5), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn compose_handles_negative_positions_and_overlap() {
+        let layers = [
+            layer("left", -20, 0, solid(30, 10, RED)),
+            layer("right", 0, 0, solid(10, 10, BLUE)),
+        ];
+
+        let (canvas, placements) = compose(&layers, 0).unwrap();
+
+        assert_eq!(canvas.width, 30);
+        assert_eq!((placements[1].x, placements[1].y), (20, 0));
+        // Later layers are painted on top
+        assert_eq!(pixel(&canvas, 25, 5), BLUE);
+        assert_eq!(pixel(&canvas, 5, 5), RED);
+    }
+
+    #[test]
+    fn compose_draws_padding_border() {
+        let (canvas, placements) = compose(&[layer("main", 0, 0, solid(2, 2, RED))], 3).unwrap();
+
+        assert_eq!((canvas.width, canvas.height), (8, 8));
+        assert_eq!((placements[0].x, placements[0].y), (3, 3));
+        assert_eq!(pixel(&canvas, 0, 0), BORDER_COLOR);
+        assert_eq!(pixel(&canvas, 3, 3), RED);
+        assert_eq!(pixel(&canvas, 7, 7), BORDER_COLOR);
+    }
+
+    #[test]
+    fn compose_rejects_empty_and_oversized_layouts() {
+        assert!(compose(&[], 0).unwrap_err().contains("No window screenshots"));
+
+        let layers = [
+            layer("a", 0, 0, solid(1, 1, RED)),
+            layer("b", 100_000, 100_000, solid(1, 1, BLUE)),
+        ];
+        assert!(compose(&layers, 0).unwrap_err().contains("pixel limit"));
+    }
+
+    #[test]
+    fn crop_copies_the_rectangle_and_clips_to_the_image() {
+        let mut image = solid(4, 3, RED);
+        image.fill(2, 1, 2, 2, BLUE);
+
+        let cropped = image.crop(1, 1, 2, 2);
+        assert_eq!((cropped.width, cropped.height), (2, 2));
+        assert_eq!(pixel(&cropped, 0, 0), RED);
+        assert_eq!(pixel(&cropped, 1, 1), BLUE);
+
+        let clipped = image.crop(3, 2, 10, 10);
+        assert_eq!((clipped.width, clipped.height), (1, 1));
+        assert_eq!(pixel(&clipped, 0, 0), BLUE);
+    }
+
+    #[test]
+    fn png_round_trip_keeps_pixels() {
+        let mut image = solid(3, 2, RED);
+        image.fill(1, 1, 1, 1, [1, 2, 3, 4]);
+
+        let decoded = decode_png(&encode_png(&image).unwrap()).unwrap();
+
+        assert_eq!(decoded, image);
+    }
+
+    #[test]
+    fn decode_png_rejects_garbage() {
+        assert!(decode_png(b"not a png").unwrap_err().contains("Failed to decode PNG"));
+    }
+}
@@ -1,8 +1,174 @@
-//! Linux screenshot implementation (stub)
+//! Linux screenshot implementation using `WebKitGTK`'s snapshot API
+//!
+//! `webkit_web_view_get_snapshot` renders the visible part of the webview. If it fails, the
+//! window's area is copied from the screen with `gdk_pixbuf_get_from_window` on the root window
+//! instead. That fallback only works on X11, and it includes anything overlapping the window.
+//! A clip crops the snapshot, or narrows the screen area the fallback copies.
 
+use std::sync::mpsc;
+use std::time::Duration;
+
+use base64::Engine;
+use gtk::cairo::{ImageSurface, Surface};
+use gtk::gdk::prelude::WindowExtManual;
+use gtk::gdk_pixbuf::prelude::PixbufLoaderExt;
+use gtk::gdk_pixbuf::{Pixbuf, PixbufLoader};
 use tauri::{Runtime, WebviewWindow};
+use webkit2gtk::{SnapshotOptions, SnapshotRegion, WebViewExt};
+
+use super::ClipRect;
+
+/// Screen area of the window (or the clip) in logical pixels, for the root window fallback
+#[derive(Debug, Clone, Copy)]
+struct ScreenRect {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+}
+
+/// Capture screenshot on Linux using native `WebKitGTK` API
+pub fn capture<R: Runtime>(
+    window: &WebviewWindow<R>,
+    format: &str,
+    quality: Option<u8>,
+    clip: Option<ClipRect>,
+) -> Result<String, String> {
+    // Check if window is visible
+    if !window.is_visible().unwrap_or(false) {
+        return Err("Window is not visible. Cannot capture screenshot of hidden window.".to_string());
+    }
+
+    if window.is_minimized().unwrap_or(false) {
+        return Err("Window is minimized. Cannot capture screenshot of minimized window.".to_string());
+    }
+
+    let format_lower = format.to_lowercase();
+    let jpeg_quality = (format_lower == "jpeg" || format_lower == "jpg").then(|| quality.unwrap_or(80));
+
+    // Read the window geometry up front, since the snapshot callback runs on the main thread
+    let rect = window_rect(window, clip)?;
+    let viewport_width = super::logical_inner_size(window)?.width;
+
+    // Create channel for async result
+    let (tx, rx) = mpsc::channel::<Result<Vec<u8>, String>>();
+
+    // Use Tauri's with_webview to access the platform-specific webview (on the GTK main thread)
+    window
+        .with_webview(move |webview| {
+            webview.inner().snapshot(
+                SnapshotRegion::Visible,
+                SnapshotOptions::NONE,
+                None::<&gtk::gio::Cancellable>,
+                move |result| {
+                    let pixbuf = match result {
+                        Ok(surface) => pixbuf_from_surface(surface).map(|pixbuf| crop(&pixbuf, clip, viewport_width)),
+                        Err(e) => Err(format!("WebKitGTK snapshot failed: {e}")),
+                    }
+                    .or_else(|snapshot_error| {
+                        capture_screen_area(rect).map_err(|e| format!("{snapshot_error}. Fallback failed too: {e}"))
+                    });
+                    let _ = tx.send(pixbuf.and_then(|pixbuf| encode(&pixbuf, jpeg_quality)));
+                },
+            );
+        })
+        .map_err(|e| format!("Failed to access webview: {e}"))?;
+
+    // Wait for result with timeout
+    let data = match rx.recv_timeout(Duration::from_secs(10)) {
+        Ok(result) => result?,
+        Err(_) => return Err("Screenshot capture timed out after 10 seconds.".to_string()),
+    };
+
+    Ok(base64::engine::general_purpose::STANDARD.encode(data))
+}
+
+/// Get the window's outer frame in logical pixels, which is what GDK's root window uses, or the
+/// clip's area on the screen
+#[allow(clippy::cast_possible_truncation)]
+fn window_rect<R: Runtime>(window: &WebviewWindow<R>, clip: Option<ClipRect>) -> Result<ScreenRect, String> {
+    let scale_factor = window.scale_factor().map_err(|e| e.to_string())?;
+    if let Some(clip) = clip {
+        let inner = window
+            .inner_position()
+            .map_err(|e| e.to_string())?
+            .to_logical::<f64>(scale_factor);
+        return Ok(ScreenRect {
+            x: (inner.x + clip.x).round() as i32,
+            y: (inner.y + clip.y).round() as i32,
+            width: clip.width.round().max(1.0) as i32,
+            height: clip.height.round().max(1.0) as i32,
+        });
+    }
+
+    let position = window
+        .outer_position()
+        .map_err(|e| e.to_string())?
+        .to_logical::<i32>(scale_factor);
+    let size = window
+        .outer_size()
+        .map_err(|e| e.to_string())?
+        .to_logical::<i32>(scale_factor);
+
+    Ok(ScreenRect {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+/// Convert the snapshot's cairo surface to a pixbuf
+fn pixbuf_from_surface(surface: Surface) -> Result<Pixbuf, String> {
+    let image =
+        ImageSurface::try_from(surface).map_err(|_| "WebKitGTK snapshot is not an image surface.".to_string())?;
+    let (width, height) = (image.width(), image.height());
+    if width == 0 || height == 0 {
+        return Err("WebKitGTK snapshot returned an empty image. The webview may be empty.".to_string());
+    }
+
+    gtk::gdk::pixbuf_get_from_surface(&image, 0, 0, width, height)
+        .ok_or_else(|| "Failed to convert the WebKitGTK snapshot to a pixbuf.".to_string())
+}
+
+/// Crop a snapshot of a viewport `viewport_width` logical pixels wide to the clip
+#[allow(clippy::cast_possible_wrap)] // Inside the pixbuf, whose dimensions are i32
+fn crop(pixbuf: &Pixbuf, clip: Option<ClipRect>, viewport_width: f64) -> Pixbuf {
+    let Some(clip) = clip else {
+        return pixbuf.clone();
+    };
+    let (width, height) = (pixbuf.width().unsigned_abs(), pixbuf.height().unsigned_abs());
+    let (x, y, width, height) = clip.to_pixels(f64::from(width) / viewport_width, width, height);
+    pixbuf.new_subpixbuf(x as i32, y as i32, width as i32, height as i32)
+}
+
+/// Copy the window's area from the screen (X11 only)
+fn capture_screen_area(rect: ScreenRect) -> Result<Pixbuf, String> {
+    gtk::gdk::Window::default_root_window()
+        .pixbuf(rect.x, rect.y, rect.width, rect.height)
+        .ok_or_else(|| "Could not read the window's area from the screen (not supported on Wayland).".to_string())
+}
+
+/// Re-encode PNG bytes as JPEG with gdk-pixbuf
+pub fn png_to_jpeg(png: &[u8], quality: u8) -> Result<Vec<u8>, String> {
+    let loader = PixbufLoader::with_type("png").map_err(|e| format!("Failed to decode PNG: {e}"))?;
+    loader
+        .write(png)
+        .and_then(|()| loader.close())
+        .map_err(|e| format!("Failed to decode PNG: {e}"))?;
+    let pixbuf = loader
+        .pixbuf()
+        .ok_or_else(|| "Failed to decode PNG: no image in the data.".to_string())?;
+
+    encode(&pixbuf, Some(quality))
+}
 
-/// Capture screenshot on Linux (not yet implemented)
-pub fn capture<R: Runtime>(_window: &WebviewWindow<R>, _format: &str, _quality: Option<u8>) -> Result<String, String> {
-    Err("Screenshot not implemented on Linux yet. This feature is planned for a future release.".to_string())
+/// Encode a pixbuf as PNG, or as JPEG if a quality is given
+fn encode(pixbuf: &Pixbuf, jpeg_quality: Option<u8>) -> Result<Vec<u8>, String> {
+    jpeg_quality
+        .map_or_else(
+            || pixbuf.save_to_bufferv("png", &[]),
+            |quality| pixbuf.save_to_bufferv("jpeg", &[("quality", &quality.min(100).to_string())]),
+        )
+        .map_err(|e| format!("Failed to encode screenshot: {e}"))
 }
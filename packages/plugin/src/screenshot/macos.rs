@@ -14,12 +14,26 @@ use std::time::Duration;
 use base64::Engine;
 use block2::RcBlock;
 use objc2_app_kit::{NSBitmapImageFileType, NSBitmapImageRep, NSImage};
+use objc2_core_foundation::{CGPoint, CGRect, CGSize};
 use objc2_foundation::{MainThreadMarker, NSDictionary, NSError, NSNumber, NSString};
 use objc2_web_kit::{WKSnapshotConfiguration, WKWebView};
 use tauri::{Runtime, WebviewWindow};
 
+use super::ClipRect;
+
+/// How many times to take the snapshot when it comes back without an image
+const SNAPSHOT_ATTEMPTS: u32 = 3;
+
+/// Wait between snapshot attempts
+const SNAPSHOT_RETRY_DELAY: Duration = Duration::from_millis(200);
+
 /// Capture screenshot on macOS using native `WKWebView` API
-pub fn capture<R: Runtime>(window: &WebviewWindow<R>, format: &str, quality: Option<u8>) -> Result<String, String> {
+pub fn capture<R: Runtime>(
+    window: &WebviewWindow<R>,
+    format: &str,
+    quality: Option<u8>,
+    clip: Option<ClipRect>,
+) -> Result<String, String> {
     // Check if window is visible
     if !window.is_visible().unwrap_or(false) {
         return Err("Window is not visible. Cannot capture screenshot of hidden window.".to_string());
@@ -29,8 +43,40 @@ pub fn capture<R: Runtime>(window: &WebviewWindow<R>, format: &str, quality: Opt
         return Err("Window is minimized. Cannot capture screenshot of minimized window.".to_string());
     }
 
+    // During window manager transitions, the snapshot sometimes has neither an image nor an
+    // error, so retry a few times before giving up
+    let mut attempt = 1;
+    let png_data = loop {
+        match take_snapshot(window, clip)? {
+            Some(data) => break data,
+            None if attempt < SNAPSHOT_ATTEMPTS => {
+                attempt += 1;
+                std::thread::sleep(SNAPSHOT_RETRY_DELAY);
+            }
+            None => {
+                return Err(format!(
+                    "WKWebView snapshot returned no image after {SNAPSHOT_ATTEMPTS} attempts. The webview may be empty."
+                ))
+            }
+        }
+    };
+
+    // Convert to requested format
+    let format_lower = format.to_lowercase();
+    let final_data = if format_lower == "jpeg" || format_lower == "jpg" {
+        convert_png_to_jpeg(&png_data, quality.unwrap_or(80))?
+    } else {
+        png_data
+    };
+
+    Ok(base64::engine::general_purpose::STANDARD.encode(final_data))
+}
+
+/// Take one snapshot of the visible viewport or the clip as PNG bytes, or `None` if `WKWebView`
+/// returned no image and no error
+fn take_snapshot<R: Runtime>(window: &WebviewWindow<R>, clip: Option<ClipRect>) -> Result<Option<Vec<u8>>, String> {
     // Create channel for async result
-    let (tx, rx) = mpsc::channel::<Result<Vec<u8>, String>>();
+    let (tx, rx) = mpsc::channel::<Result<Option<Vec<u8>>, String>>();
     let tx = Arc::new(Mutex::new(Some(tx)));
 
     // Use Tauri's with_webview to access the platform-specific webview
@@ -48,8 +94,16 @@ pub fn capture<R: Runtime>(window: &WebviewWindow<R>, format: &str, quality: Opt
                 // Get the WKWebView from Tauri's webview handle
                 let wkwebview: &WKWebView = &*(webview.inner().cast::<WKWebView>());
 
-                // Create snapshot configuration (captures visible viewport)
+                // Create snapshot configuration (captures visible viewport unless clipped). The
+                // rect is in view coordinates, which are flipped in WKWebView, so the origin is
+                // the top-left corner like the clip's.
                 let config = WKSnapshotConfiguration::new(mtm);
+                if let Some(clip) = clip {
+                    config.setRect(CGRect::new(
+                        CGPoint::new(clip.x, clip.y),
+                        CGSize::new(clip.width, clip.height),
+                    ));
+                }
 
                 // Create completion handler block
                 let handler = RcBlock::new(move |image: *mut NSImage, error: *mut NSError| {
@@ -70,18 +124,9 @@ pub fn capture<R: Runtime>(window: &WebviewWindow<R>, format: &str, quality: Opt
                         let _ = tx.send(Err(format!("WKWebView snapshot failed: {error_string}")));
                     } else if !image.is_null() {
                         let img = &*image;
-                        match convert_nsimage_to_png(img) {
-                            Ok(data) => {
-                                let _ = tx.send(Ok(data));
-                            }
-                            Err(e) => {
-                                let _ = tx.send(Err(e));
-                            }
-                        }
+                        let _ = tx.send(convert_nsimage_to_png(img).map(Some));
                     } else {
-                        let _ = tx.send(Err(
-                            "WKWebView snapshot returned no image. The webview may be empty.".to_string()
-                        ));
+                        let _ = tx.send(Ok(None));
                     }
                 });
 
@@ -92,20 +137,8 @@ pub fn capture<R: Runtime>(window: &WebviewWindow<R>, format: &str, quality: Opt
         .map_err(|e| format!("Failed to access webview: {e}"))?;
 
     // Wait for result with timeout
-    let png_data = match rx.recv_timeout(Duration::from_secs(10)) {
-        Ok(result) => result?,
-        Err(_) => return Err("Screenshot capture timed out after 10 seconds.".to_string()),
-    };
-
-    // Convert to requested format
-    let format_lower = format.to_lowercase();
-    let final_data = if format_lower == "jpeg" || format_lower == "jpg" {
-        convert_png_to_jpeg(&png_data, quality.unwrap_or(80))?
-    } else {
-        png_data
-    };
-
-    Ok(base64::engine::general_purpose::STANDARD.encode(final_data))
+    rx.recv_timeout(Duration::from_secs(10))
+        .unwrap_or_else(|_| Err("Screenshot capture timed out after 10 seconds.".to_string()))
 }
 
 /// Convert `NSImage` to PNG bytes
@@ -132,7 +165,7 @@ unsafe fn convert_nsimage_to_png(image: &NSImage) -> Result<Vec<u8>, String> {
 }
 
 /// Convert PNG bytes to JPEG with specified quality
-fn convert_png_to_jpeg(png_data: &[u8], quality: u8) -> Result<Vec<u8>, String> {
+pub fn convert_png_to_jpeg(png_data: &[u8], quality: u8) -> Result<Vec<u8>, String> {
     // Load PNG data into NSData and create bitmap rep
     // Safety: We're creating NSData from valid PNG bytes and using safe AppKit encoding APIs
     unsafe {
@@ -9,28 +9,167 @@ mod windows;
 #[cfg(target_os = "linux")]
 mod linux;
 
-use tauri::{Runtime, WebviewWindow};
+pub mod composite;
+pub mod watermark;
 
-/// Capture a screenshot of the webview
-pub fn capture<R: Runtime>(window: &WebviewWindow<R>, format: &str, quality: Option<u8>) -> Result<String, String> {
+use tauri::{LogicalSize, Runtime, WebviewWindow};
+
+/// A region of the webview to capture, in logical pixels from its top-left corner
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClipRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl ClipRect {
+    /// The part of the rectangle inside a viewport of the given logical size, or `None` if none
+    /// of it is
+    pub fn clamp_to(self, viewport_width: f64, viewport_height: f64) -> Option<Self> {
+        let x = self.x.clamp(0.0, viewport_width);
+        let y = self.y.clamp(0.0, viewport_height);
+        let width = (self.x + self.width).min(viewport_width) - x;
+        let height = (self.y + self.height).min(viewport_height) - y;
+        (width > 0.0 && height > 0.0).then_some(Self { x, y, width, height })
+    }
+
+    /// The rectangle in an image of the viewport with `scale` image pixels per logical pixel, as
+    /// `(x, y, width, height)`. Rounded outwards, kept inside the image, and at least 1x1.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub fn to_pixels(self, scale: f64, image_width: u32, image_height: u32) -> (u32, u32, u32, u32) {
+        // Clamped to the image size first, so the casts can't truncate or lose a sign
+        let pixel = |value: f64, max: u32| (value * scale).clamp(0.0, f64::from(max));
+        let left = pixel(self.x, image_width.saturating_sub(1)).floor() as u32;
+        let top = pixel(self.y, image_height.saturating_sub(1)).floor() as u32;
+        let right = pixel(self.x + self.width, image_width).ceil() as u32;
+        let bottom = pixel(self.y + self.height, image_height).ceil() as u32;
+        (
+            left,
+            top,
+            right.saturating_sub(left).max(1),
+            bottom.saturating_sub(top).max(1),
+        )
+    }
+}
+
+/// Get the size of the window's webview area in logical pixels, which is what clips are in
+pub fn logical_inner_size<R: Runtime>(window: &WebviewWindow<R>) -> Result<LogicalSize<f64>, String> {
+    let scale_factor = window.scale_factor().map_err(|e| e.to_string())?;
+    Ok(window
+        .inner_size()
+        .map_err(|e| e.to_string())?
+        .to_logical::<f64>(scale_factor))
+}
+
+/// Capture a screenshot of the webview, or of the `clip` region of it
+pub fn capture<R: Runtime>(
+    window: &WebviewWindow<R>,
+    format: &str,
+    quality: Option<u8>,
+    clip: Option<ClipRect>,
+) -> Result<String, String> {
     #[cfg(target_os = "macos")]
     {
-        macos::capture(window, format, quality)
+        macos::capture(window, format, quality, clip)
     }
 
     #[cfg(target_os = "windows")]
     {
-        windows::capture(window, format, quality)
+        windows::capture(window, format, quality, clip)
     }
 
     #[cfg(target_os = "linux")]
     {
-        linux::capture(window, format, quality)
+        linux::capture(window, format, quality, clip)
     }
 
     #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
     {
-        let _ = (window, format, quality);
+        let _ = (window, format, quality, clip);
         Err("Screenshot not supported on this platform".to_string())
     }
 }
+
+/// Re-encode a PNG as JPEG with the platform's image encoder
+pub fn png_to_jpeg(png: &[u8], quality: u8) -> Result<Vec<u8>, String> {
+    #[cfg(target_os = "macos")]
+    {
+        macos::convert_png_to_jpeg(png, quality)
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        windows::png_to_jpeg(png, quality)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::png_to_jpeg(png, quality)
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+    {
+        let _ = (png, quality);
+        Err("JPEG encoding not supported on this platform".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLIP: ClipRect = ClipRect {
+        x: 100.0,
+        y: 50.0,
+        width: 400.0,
+        height: 300.0,
+    };
+
+    #[test]
+    fn clamp_keeps_rectangles_inside_the_viewport() {
+        assert_eq!(CLIP.clamp_to(800.0, 600.0), Some(CLIP));
+    }
+
+    #[test]
+    fn clamp_trims_rectangles_to_the_viewport() {
+        let clip = ClipRect {
+            x: -20.0,
+            y: 500.0,
+            width: 100.0,
+            height: 300.0,
+        };
+        assert_eq!(
+            clip.clamp_to(800.0, 600.0),
+            Some(ClipRect {
+                x: 0.0,
+                y: 500.0,
+                width: 80.0,
+                height: 100.0
+            })
+        );
+    }
+
+    #[test]
+    fn clamp_rejects_rectangles_outside_the_viewport() {
+        assert_eq!(CLIP.clamp_to(90.0, 600.0), None);
+    }
+
+    #[test]
+    fn to_pixels_scales_and_rounds_outwards() {
+        assert_eq!(CLIP.to_pixels(2.0, 1600, 1200), (200, 100, 800, 600));
+
+        let clip = ClipRect {
+            x: 0.5,
+            y: 0.5,
+            width: 1.0,
+            height: 1.0,
+        };
+        assert_eq!(clip.to_pixels(1.0, 10, 10), (0, 0, 2, 2));
+    }
+
+    #[test]
+    fn to_pixels_stays_inside_the_image() {
+        assert_eq!(CLIP.to_pixels(2.0, 900, 500), (200, 100, 700, 400));
+    }
+}
@@ -0,0 +1,236 @@
+//! Drawing a text label onto screenshots, to tell captures apart in an archive
+
+use ab_glyph::{point, Font, FontRef, Glyph, PxScale, ScaleFont};
+
+use super::composite::RgbaImage;
+
+/// Font the text is drawn in, bundled so watermarks look the same on every platform
+const FONT: &[u8] = include_bytes!("fonts/DejaVuSansMono-Bold.ttf");
+
+/// Text height in logical pixels
+const FONT_SIZE: f32 = 14.0;
+
+/// Space between the label and the image edges, in logical pixels
+const MARGIN: f32 = 8.0;
+
+/// Space between the text and the edges of its backdrop, in logical pixels
+const PADDING: f32 = 4.0;
+
+const TEXT_COLOR: [u8; 3] = [255, 255, 255];
+
+/// Backdrop behind the text, so it reads on light and dark pages alike
+const BACKDROP_COLOR: [u8; 3] = [0, 0, 0];
+
+/// How opaque the backdrop is compared to the text
+const BACKDROP_OPACITY: f32 = 0.6;
+
+/// Corner or edge of the image the label goes in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Position {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Center,
+}
+
+impl Position {
+    pub const NAMES: &'static [&'static str] = &["top_left", "top_right", "bottom_left", "bottom_right", "center"];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "top_left" => Some(Self::TopLeft),
+            "top_right" => Some(Self::TopRight),
+            "bottom_left" => Some(Self::BottomLeft),
+            "bottom_right" => Some(Self::BottomRight),
+            "center" => Some(Self::Center),
+            _ => None,
+        }
+    }
+}
+
+/// A text label to draw onto a screenshot
+#[derive(Debug, Clone, PartialEq)]
+pub struct Watermark {
+    pub text: String,
+    pub position: Position,
+    /// From 0 (invisible) to 1 (opaque)
+    pub opacity: f32,
+}
+
+/// Draw the watermark onto the image. `scale` is the image's pixels per logical pixel, so the
+/// label has the same size relative to the page on every display.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss,
+    clippy::cast_possible_wrap
+)]
+pub fn draw(image: &mut RgbaImage, watermark: &Watermark, scale: f32) -> Result<(), String> {
+    let font = FontRef::try_from_slice(FONT).map_err(|e| format!("Failed to load the watermark font: {e}"))?;
+    let font_size = FONT_SIZE * scale;
+    let (glyphs, text_width) = layout(&font, &watermark.text, font_size);
+    let scaled = font.as_scaled(PxScale::from(font_size));
+    let text_height = scaled.ascent() - scaled.descent();
+
+    let margin = MARGIN * scale;
+    let padding = PADDING * scale;
+    let box_width = padding.mul_add(2.0, text_width);
+    let box_height = padding.mul_add(2.0, text_height);
+    let (image_width, image_height) = (image.width as f32, image.height as f32);
+    let left = match watermark.position {
+        Position::TopLeft | Position::BottomLeft => margin,
+        Position::TopRight | Position::BottomRight => image_width - margin - box_width,
+        Position::Center => (image_width - box_width) / 2.0,
+    };
+    let top = match watermark.position {
+        Position::TopLeft | Position::TopRight => margin,
+        Position::BottomLeft | Position::BottomRight => image_height - margin - box_height,
+        Position::Center => (image_height - box_height) / 2.0,
+    };
+
+    // The label is clipped if the image is too small for it
+    let (left, top) = (left.max(0.0).round() as i64, top.max(0.0).round() as i64);
+    let backdrop_opacity = watermark.opacity * BACKDROP_OPACITY;
+    for y in top..top + box_height.round() as i64 {
+        for x in left..left + box_width.round() as i64 {
+            blend(image, x, y, BACKDROP_COLOR, backdrop_opacity);
+        }
+    }
+
+    let origin_x = left as f32 + padding;
+    let origin_y = top as f32 + padding;
+    for glyph in glyphs {
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue; // Spaces have no outline
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|x, y, coverage| {
+            let px = (origin_x + bounds.min.x).round() as i64 + i64::from(x);
+            let py = (origin_y + bounds.min.y).round() as i64 + i64::from(y);
+            blend(image, px, py, TEXT_COLOR, coverage * watermark.opacity);
+        });
+    }
+
+    Ok(())
+}
+
+/// Lay the text out on one line from the origin, returning its glyphs and width
+fn layout(font: &FontRef<'_>, text: &str, font_size: f32) -> (Vec<Glyph>, f32) {
+    let scaled = font.as_scaled(PxScale::from(font_size));
+    let mut glyphs = Vec::new();
+    let mut caret = 0.0;
+    let mut previous = None;
+    for character in text.chars() {
+        let id = scaled.glyph_id(character);
+        if let Some(previous) = previous {
+            caret += scaled.kern(previous, id);
+        }
+        glyphs.push(id.with_scale_and_position(font_size, point(caret, scaled.ascent())));
+        caret += scaled.h_advance(id);
+        previous = Some(id);
+    }
+    (glyphs, caret)
+}
+
+/// Paint a color over a pixel with the given opacity, ignoring pixels outside the image
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn blend(image: &mut RgbaImage, x: i64, y: i64, color: [u8; 3], opacity: f32) {
+    let (Ok(x), Ok(y)) = (u32::try_from(x), u32::try_from(y)) else {
+        return;
+    };
+    if x >= image.width || y >= image.height {
+        return;
+    }
+    let alpha = opacity.clamp(0.0, 1.0);
+    let i = (y as usize * image.width as usize + x as usize) * 4;
+    for (channel, value) in image.pixels[i..i + 3].iter_mut().zip(color) {
+        *channel = f32::from(value)
+            .mul_add(alpha, f32::from(*channel) * (1.0 - alpha))
+            .round() as u8;
+    }
+    let coverage = (alpha * 255.0).round() as u8;
+    image.pixels[i + 3] = image.pixels[i + 3].max(coverage);
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn gray(width: u32, height: u32) -> RgbaImage {
+        RgbaImage {
+            width,
+            height,
+            pixels: [128, 128, 128, 255].repeat(width as usize * height as usize),
+        }
+    }
+
+    fn watermark(position: Position, opacity: f32) -> Watermark {
+        Watermark {
+            text: "CI Build #1234".to_string(),
+            position,
+            opacity,
+        }
+    }
+
+    /// Bounding box of the pixels that changed, as (min x, min y, max x, max y)
+    fn changed(before: &RgbaImage, after: &RgbaImage) -> Option<(u32, u32, u32, u32)> {
+        let mut bounds: Option<(u32, u32, u32, u32)> = None;
+        for (i, (a, b)) in (0u32..).zip(before.pixels.chunks(4).zip(after.pixels.chunks(4))) {
+            if a != b {
+                let (x, y) = (i % before.width, i / before.width);
+                bounds = Some(bounds.map_or((x, y, x, y), |(x0, y0, x1, y1)| {
+                    (x0.min(x), y0.min(y), x1.max(x), y1.max(y))
+                }));
+            }
+        }
+        bounds
+    }
+
+    #[test]
+    fn draw_puts_the_label_in_the_requested_corner() {
+        let before = gray(400, 200);
+
+        let mut bottom_right = before.clone();
+        draw(&mut bottom_right, &watermark(Position::BottomRight, 0.5), 1.0).unwrap();
+        let (x0, y0, x1, y1) = changed(&before, &bottom_right).unwrap();
+        assert!(x0 > 200 && y0 > 100, "{x0}, {y0}");
+        assert_eq!((x1, y1), (391, 191));
+
+        let mut top_left = before.clone();
+        draw(&mut top_left, &watermark(Position::TopLeft, 0.5), 1.0).unwrap();
+        let (x0, y0, x1, y1) = changed(&before, &top_left).unwrap();
+        assert_eq!((x0, y0), (8, 8));
+        assert!(x1 < 200 && y1 < 100, "{x1}, {y1}");
+    }
+
+    #[test]
+    fn draw_scales_with_the_display() {
+        let before = gray(800, 400);
+        let mut normal = before.clone();
+        draw(&mut normal, &watermark(Position::TopLeft, 1.0), 1.0).unwrap();
+        let mut retina = before.clone();
+        draw(&mut retina, &watermark(Position::TopLeft, 1.0), 2.0).unwrap();
+
+        let (x0, _, x1, _) = changed(&before, &normal).unwrap();
+        let (rx0, _, rx1, _) = changed(&before, &retina).unwrap();
+        assert_eq!(rx0, 2 * x0);
+        assert!((rx1 - rx0).abs_diff(2 * (x1 - x0)) <= 2);
+    }
+
+    #[test]
+    fn draw_with_zero_opacity_leaves_the_image_alone() {
+        let before = gray(200, 100);
+        let mut after = before.clone();
+        draw(&mut after, &watermark(Position::Center, 0.0), 1.0).unwrap();
+        assert_eq!(after, before);
+    }
+
+    #[test]
+    fn draw_clips_labels_wider_than_the_image() {
+        let mut image = gray(20, 10);
+        draw(&mut image, &watermark(Position::BottomRight, 1.0), 1.0).unwrap();
+        assert_ne!(image, gray(20, 10));
+    }
+}
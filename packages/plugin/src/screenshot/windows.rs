@@ -1,8 +1,278 @@
-//! Windows screenshot implementation (stub)
+//! Windows screenshot implementation using `WebView2`'s `CapturePreview`
+//!
+//! This module requires unsafe code to call COM APIs. The unsafe blocks are necessary for:
+//! - Calling `ICoreWebView2.CapturePreview`, which writes a PNG into an `IStream`
+//! - Reading the stream back into a Rust buffer
+//! - Re-encoding the PNG as JPEG with the Windows Imaging Component (WIC)
+//!
+//! `CapturePreview` always captures the whole viewport, so a clip crops the PNG afterwards.
 
+#![allow(unsafe_code)]
+
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+
+use base64::Engine;
 use tauri::{Runtime, WebviewWindow};
+use webview2_com::CapturePreviewCompletedHandler;
+use webview2_com::Microsoft::Web::WebView2::Win32::{
+    ICoreWebView2Controller, COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG,
+};
+use windows::core::{w, PWSTR};
+use windows::Win32::Graphics::Imaging::{
+    CLSID_WICImagingFactory, GUID_ContainerFormatJpeg, GUID_WICPixelFormat24bppBGR, IWICBitmapFrameEncode,
+    IWICImagingFactory, WICBitmapDitherTypeNone, WICBitmapEncoderNoCache, WICBitmapPaletteTypeCustom,
+    WICDecodeMetadataCacheOnDemand,
+};
+use windows::Win32::System::Com::StructuredStorage::{IPropertyBag2, PROPBAG2};
+use windows::Win32::System::Com::{
+    CoCreateInstance, CoInitializeEx, CoUninitialize, IStream, CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED,
+    STREAM_SEEK_SET,
+};
+use windows::Win32::System::Variant::VARIANT;
+use windows::Win32::UI::Shell::SHCreateMemStream;
+
+use super::composite;
+use super::ClipRect;
+
+/// Size of the chunks image streams are read in
+const READ_CHUNK_BYTES: u32 = 64 * 1024;
+
+/// Capture screenshot on Windows using native `WebView2` API
+pub fn capture<R: Runtime>(
+    window: &WebviewWindow<R>,
+    format: &str,
+    quality: Option<u8>,
+    clip: Option<ClipRect>,
+) -> Result<String, String> {
+    // Check if window is visible
+    if !window.is_visible().unwrap_or(false) {
+        return Err("Window is not visible. Cannot capture screenshot of hidden window.".to_string());
+    }
+
+    if window.is_minimized().unwrap_or(false) {
+        return Err("Window is minimized. Cannot capture screenshot of minimized window.".to_string());
+    }
+
+    let format_lower = format.to_lowercase();
+    let jpeg_quality = (format_lower == "jpeg" || format_lower == "jpg").then(|| quality.unwrap_or(80));
+    let viewport_width = super::logical_inner_size(window)?.width;
+    // Clipped captures are cropped as PNG, then encoded
+    let capture_jpeg_quality = if clip.is_some() { None } else { jpeg_quality };
+
+    // Create channel for async result
+    let (tx, rx) = mpsc::channel::<Result<Vec<u8>, String>>();
+    let tx: ResultSender = Arc::new(Mutex::new(Some(tx)));
+
+    // Use Tauri's with_webview to access the platform-specific webview
+    window
+        .with_webview(move |webview| {
+            // Safety: Tauri guarantees the controller is valid when the with_webview callback
+            // runs, and both the callback and the completion handler run on the UI thread that
+            // owns the webview, so the stream never crosses threads.
+            if let Err(e) = unsafe { start_capture(&webview.controller(), capture_jpeg_quality, Arc::clone(&tx)) } {
+                send_once(&tx, Err(e));
+            }
+        })
+        .map_err(|e| format!("Failed to access webview: {e}"))?;
+
+    // Wait for result with timeout
+    let mut data = match rx.recv_timeout(Duration::from_secs(10)) {
+        Ok(result) => result?,
+        Err(_) => return Err("Screenshot capture timed out after 10 seconds.".to_string()),
+    };
+    if let Some(clip) = clip {
+        data = crop_png(&data, clip, viewport_width)?;
+        if let Some(quality) = jpeg_quality {
+            data = png_to_jpeg(&data, quality)?;
+        }
+    }
+
+    Ok(base64::engine::general_purpose::STANDARD.encode(data))
+}
+
+/// Crop a PNG of a viewport `viewport_width` logical pixels wide to the clip
+fn crop_png(png: &[u8], clip: ClipRect, viewport_width: f64) -> Result<Vec<u8>, String> {
+    let image = composite::decode_png(png)?;
+    let scale = f64::from(image.width) / viewport_width;
+    let (x, y, width, height) = clip.to_pixels(scale, image.width, image.height);
+    composite::encode_png(&image.crop(x, y, width, height))
+}
+
+/// Channel end the capture result is sent on, taken by whichever path finishes first
+type ResultSender = Arc<Mutex<Option<mpsc::Sender<Result<Vec<u8>, String>>>>>;
+
+/// Send the capture result unless one was already sent
+fn send_once(tx: &ResultSender, result: Result<Vec<u8>, String>) {
+    let sender = {
+        let Ok(mut guard) = tx.lock() else {
+            return; // Mutex poisoned, can't do anything
+        };
+        guard.take()
+    };
+    if let Some(tx) = sender {
+        let _ = tx.send(result);
+    }
+}
+
+/// Start `CapturePreview` into a memory stream. The completion handler sends the image bytes,
+/// re-encoded as JPEG if `jpeg_quality` is set.
+///
+/// Safety: Must be called on the webview's UI thread
+unsafe fn start_capture(
+    controller: &ICoreWebView2Controller,
+    jpeg_quality: Option<u8>,
+    tx: ResultSender,
+) -> Result<(), String> {
+    let core = controller
+        .CoreWebView2()
+        .map_err(|e| format!("Failed to get the WebView2 core: {e}"))?;
+    let stream =
+        SHCreateMemStream(None).ok_or_else(|| "Failed to create a memory stream for the screenshot.".to_string())?;
+
+    let handler_stream = stream.clone();
+    let handler = CapturePreviewCompletedHandler::create(Box::new(move |result| {
+        let data = result
+            .map_err(|e| format!("WebView2 CapturePreview failed: {e}"))
+            .and_then(|()| match jpeg_quality {
+                Some(quality) => convert_png_stream_to_jpeg(&handler_stream, quality),
+                None => read_stream(&handler_stream),
+            });
+        send_once(&tx, data);
+        Ok(())
+    }));
+
+    core.CapturePreview(COREWEBVIEW2_CAPTURE_PREVIEW_IMAGE_FORMAT_PNG, &stream, &handler)
+        .map_err(|e| format!("Failed to start WebView2 CapturePreview: {e}"))
+}
+
+/// Re-encode PNG bytes as JPEG using WIC, on any thread
+pub fn png_to_jpeg(png: &[u8], quality: u8) -> Result<Vec<u8>, String> {
+    // Safety: COM is initialized on this thread for the conversion (or already was, possibly in
+    // another apartment, which WIC works in too), and the stream never leaves this function.
+    unsafe {
+        let initialized = CoInitializeEx(None, COINIT_MULTITHREADED).is_ok();
+        let result = SHCreateMemStream(Some(png))
+            .ok_or_else(|| "Failed to create a memory stream for the PNG.".to_string())
+            .and_then(|stream| convert_png_stream_to_jpeg(&stream, quality));
+        if initialized {
+            CoUninitialize();
+        }
+        result
+    }
+}
+
+/// Read an `IStream` from the start into a byte vector
+///
+/// Safety: Must be called on the thread that owns the stream
+unsafe fn read_stream(stream: &IStream) -> Result<Vec<u8>, String> {
+    stream
+        .Seek(0, STREAM_SEEK_SET, None)
+        .map_err(|e| format!("Failed to rewind the screenshot stream: {e}"))?;
+
+    let mut data = Vec::new();
+    let mut chunk = vec![0u8; READ_CHUNK_BYTES as usize];
+    loop {
+        let mut read = 0u32;
+        stream
+            .Read(
+                chunk.as_mut_ptr().cast(),
+                READ_CHUNK_BYTES,
+                Some(std::ptr::addr_of_mut!(read)),
+            )
+            .ok()
+            .map_err(|e| format!("Failed to read the screenshot stream: {e}"))?;
+        if read == 0 {
+            break;
+        }
+        data.extend_from_slice(&chunk[..read as usize]);
+    }
+
+    if data.is_empty() {
+        return Err("Screenshot stream is empty. The webview may be empty.".to_string());
+    }
+    Ok(data)
+}
+
+/// Re-encode a PNG stream as JPEG using WIC
+///
+/// Safety: Must be called on a thread with COM initialized (the webview's UI thread)
+unsafe fn convert_png_stream_to_jpeg(png_stream: &IStream, quality: u8) -> Result<Vec<u8>, String> {
+    png_stream
+        .Seek(0, STREAM_SEEK_SET, None)
+        .map_err(|e| format!("Failed to rewind the screenshot stream: {e}"))?;
+
+    let factory: IWICImagingFactory = CoCreateInstance(&CLSID_WICImagingFactory, None, CLSCTX_INPROC_SERVER)
+        .map_err(|e| format!("Failed to create WIC imaging factory: {e}"))?;
+
+    // Decode the PNG and convert it to a pixel format JPEG supports
+    let decoder = factory
+        .CreateDecoderFromStream(png_stream, std::ptr::null(), WICDecodeMetadataCacheOnDemand)
+        .map_err(|e| format!("Failed to decode PNG: {e}"))?;
+    let frame = decoder.GetFrame(0).map_err(|e| format!("Failed to decode PNG: {e}"))?;
+    let converter = factory
+        .CreateFormatConverter()
+        .map_err(|e| format!("Failed to create pixel format converter: {e}"))?;
+    converter
+        .Initialize(
+            &frame,
+            &GUID_WICPixelFormat24bppBGR,
+            WICBitmapDitherTypeNone,
+            None,
+            0.0,
+            WICBitmapPaletteTypeCustom,
+        )
+        .map_err(|e| format!("Failed to convert pixel format: {e}"))?;
+
+    let jpeg_stream =
+        SHCreateMemStream(None).ok_or_else(|| "Failed to create a memory stream for the JPEG.".to_string())?;
+    let encoder = factory
+        .CreateEncoder(&GUID_ContainerFormatJpeg, std::ptr::null())
+        .map_err(|e| format!("Failed to create JPEG encoder: {e}"))?;
+    encoder
+        .Initialize(&jpeg_stream, WICBitmapEncoderNoCache)
+        .map_err(|e| format!("Failed to initialize JPEG encoder: {e}"))?;
+
+    let mut frame_encode: Option<IWICBitmapFrameEncode> = None;
+    let mut options: Option<IPropertyBag2> = None;
+    encoder
+        .CreateNewFrame(&mut frame_encode, &mut options)
+        .map_err(|e| format!("Failed to create JPEG frame: {e}"))?;
+    let frame_encode = frame_encode.ok_or_else(|| "JPEG encoder returned no frame.".to_string())?;
+
+    // Set JPEG quality (0.0 to 1.0)
+    if let Some(options) = &options {
+        let name = PROPBAG2 {
+            pstrName: PWSTR(w!("ImageQuality").as_ptr().cast_mut()),
+            ..Default::default()
+        };
+        let value = VARIANT::from(f32::from(quality.min(100)) / 100.0);
+        options
+            .Write(1, &name, &value)
+            .map_err(|e| format!("Failed to set JPEG quality: {e}"))?;
+    }
+    frame_encode
+        .Initialize(options.as_ref())
+        .map_err(|e| format!("Failed to initialize JPEG frame: {e}"))?;
+
+    let (mut width, mut height) = (0u32, 0u32);
+    frame
+        .GetSize(&mut width, &mut height)
+        .map_err(|e| format!("Failed to get image size: {e}"))?;
+    frame_encode
+        .SetSize(width, height)
+        .map_err(|e| format!("Failed to set JPEG size: {e}"))?;
+    let mut pixel_format = GUID_WICPixelFormat24bppBGR;
+    frame_encode
+        .SetPixelFormat(&mut pixel_format)
+        .map_err(|e| format!("Failed to set JPEG pixel format: {e}"))?;
+    frame_encode
+        .WriteSource(&converter, std::ptr::null())
+        .map_err(|e| format!("Failed to encode JPEG: {e}"))?;
+    frame_encode
+        .Commit()
+        .map_err(|e| format!("Failed to encode JPEG: {e}"))?;
+    encoder.Commit().map_err(|e| format!("Failed to encode JPEG: {e}"))?;
 
-/// Capture screenshot on Windows (not yet implemented)
-pub fn capture<R: Runtime>(_window: &WebviewWindow<R>, _format: &str, _quality: Option<u8>) -> Result<String, String> {
-    Err("Screenshot not implemented on Windows yet. This feature is planned for a future release.".to_string())
+    read_stream(&jpeg_stream)
 }
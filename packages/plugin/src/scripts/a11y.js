@@ -0,0 +1,159 @@
+// Shared accessibility helpers: roles, accessible names, and unique selectors.
+// Prepended to scripts that need them (dom-snapshot.js, accessibility-find.js, selector.js).
+window.__tauriMcpA11y = (function() {
+  'use strict';
+
+  function getRole(element) {
+    return element.getAttribute('role') || getImplicitRole(element);
+  }
+
+  function getImplicitRole(element) {
+    const roleMap = {
+      'A': element.href ? 'link' : null,
+      'BUTTON': 'button',
+      'INPUT': getInputRole(element),
+      'SELECT': element.multiple || element.size > 1 ? 'listbox' : 'combobox',
+      'OPTION': 'option',
+      'TEXTAREA': 'textbox',
+      'IMG': 'img',
+      'DIALOG': 'dialog',
+      'PROGRESS': 'progressbar',
+      'METER': 'meter',
+      'HR': 'separator',
+      'FIELDSET': 'group',
+      'NAV': 'navigation',
+      'MAIN': 'main',
+      'HEADER': 'banner',
+      'FOOTER': 'contentinfo',
+      'ARTICLE': 'article',
+      'ASIDE': 'complementary',
+      'SECTION': 'region',
+      'FORM': 'form',
+      'TABLE': 'table',
+      'UL': 'list',
+      'OL': 'list',
+      'LI': 'listitem',
+      'TR': 'row',
+      'TH': 'columnheader',
+      'TD': 'cell',
+      'H1': 'heading',
+      'H2': 'heading',
+      'H3': 'heading',
+      'H4': 'heading',
+      'H5': 'heading',
+      'H6': 'heading',
+    };
+    return roleMap[element.tagName] || null;
+  }
+
+  function getInputRole(input) {
+    const typeRoles = {
+      'checkbox': 'checkbox',
+      'radio': 'radio',
+      'range': 'slider',
+      'number': 'spinbutton',
+      'button': 'button',
+      'submit': 'button',
+      'reset': 'button',
+      'image': 'button',
+      'search': 'searchbox',
+    };
+    return typeRoles[input.type] || 'textbox';
+  }
+
+  function getAccessibleName(element) {
+    return element.getAttribute('aria-label') ||
+      element.getAttribute('aria-labelledby') && getLabelledByText(element) ||
+      element.getAttribute('alt') ||
+      element.getAttribute('title') ||
+      element.innerText?.trim().slice(0, 100) ||
+      null;
+  }
+
+  function getLabelledByText(element) {
+    const id = element.getAttribute('aria-labelledby');
+    const labelElement = document.getElementById(id);
+    return labelElement?.innerText?.trim() || null;
+  }
+
+  function escapeCssIdentifier(id) {
+    // Use CSS.escape if available, otherwise implement a simple escaper
+    if (typeof CSS !== 'undefined' && CSS.escape) {
+      return CSS.escape(id);
+    }
+    // Simple escaper for special characters in CSS identifiers
+    return id.replace(/([^\w-])/g, '\\$1');
+  }
+
+  // Inside a shadow tree or a frame the selector is relative to the tree or the frame's document,
+  // so it's chained onto the host's or frame's selector with `>>>`
+  function getUniqueSelector(element) {
+    const root = element.getRootNode();
+    const selector = getTreeSelector(element);
+    // Frames have their own ShadowRoot class, so the node type tells shadow roots apart
+    if (root.nodeType === Node.DOCUMENT_FRAGMENT_NODE) {
+      return `${getUniqueSelector(root.host)} >>> ${selector}`;
+    }
+    const frame = root !== document ? root.defaultView?.frameElement : null;
+    return frame ? `${getUniqueSelector(frame)} >>> ${selector}` : selector;
+  }
+
+  function getTreeSelector(element) {
+    if (element.id) {
+      return '#' + escapeCssIdentifier(element.id);
+    }
+    if (element.dataset.testid) {
+      return `[data-testid="${element.dataset.testid}"]`;
+    }
+
+    const path = [];
+    let current = element;
+
+    while (current && current !== current.ownerDocument.body) {
+      let selector = current.tagName.toLowerCase();
+
+      if (current.id) {
+        path.unshift('#' + escapeCssIdentifier(current.id));
+        break;
+      }
+
+      // Add class if available for more specific selection
+      if (current.className && typeof current.className === 'string') {
+        const classes = current.className.trim().split(/\s+/).filter(Boolean);
+        if (classes.length > 0) {
+          selector += '.' + classes.map(escapeCssIdentifier).join('.');
+        }
+      }
+
+      const siblings = current.parentNode?.children || [];
+      const sameTag = Array.from(siblings).filter(s => s.tagName === current.tagName);
+
+      if (sameTag.length > 1) {
+        const index = sameTag.indexOf(current) + 1;
+        selector += `:nth-of-type(${index})`;
+      }
+
+      path.unshift(selector);
+      current = current.parentElement;
+    }
+
+    return path.join(' > ');
+  }
+
+  // Use the browser's accessible name computation when available (AOM), otherwise approximate it
+  function computeAccessibleName(element) {
+    if (typeof element.computedName === 'string' && element.computedName) {
+      return element.computedName;
+    }
+    return getAccessibleName(element);
+  }
+
+  return {
+    getRole,
+    getImplicitRole,
+    getAccessibleName,
+    computeAccessibleName,
+    getUniqueSelector,
+    escapeCssIdentifier,
+  };
+})();
@@ -0,0 +1,80 @@
+// Accessibility query script: find elements by ARIA role and accessible name
+window.__tauriMcpAccessibilityFind = function(args) {
+  'use strict';
+
+  const { getRole, computeAccessibleName, getUniqueSelector } = window.__tauriMcpA11y;
+  const { role, name, exact = true, includeHidden = false } = args;
+
+  if (!role && !name) {
+    throw new Error("Provide 'role', 'name', or both.");
+  }
+
+  const wantedRole = role ? role.toLowerCase() : null;
+  const wantedName = name ? normalize(name) : null;
+  const results = [];
+
+  for (const element of document.body.querySelectorAll('*')) {
+    const elementRole = getRole(element);
+    if (wantedRole && (elementRole || '').toLowerCase() !== wantedRole) continue;
+
+    const elementName = computeAccessibleName(element);
+    if (wantedName && !matchesName(elementName, wantedName)) continue;
+
+    if (!includeHidden && isHidden(element)) continue;
+
+    results.push({
+      selector: getUniqueSelector(element),
+      role: elementRole,
+      name: elementName,
+      description: getDescription(element),
+      state: getState(element),
+    });
+  }
+
+  return results;
+
+  function normalize(text) {
+    return String(text).replace(/\s+/g, ' ').trim().toLowerCase();
+  }
+
+  function matchesName(elementName, wanted) {
+    if (!elementName) return false;
+    const actual = normalize(elementName);
+    return exact ? actual === wanted : actual.includes(wanted);
+  }
+
+  function isHidden(element) {
+    if (element.closest('[aria-hidden="true"]')) return true;
+    const style = window.getComputedStyle(element);
+    return style.display === 'none' || style.visibility === 'hidden';
+  }
+
+  function getDescription(element) {
+    const ids = element.getAttribute('aria-describedby');
+    if (ids) {
+      const text = ids.split(/\s+/)
+        .map(id => document.getElementById(id)?.innerText?.trim())
+        .filter(Boolean)
+        .join(' ');
+      if (text) return text;
+    }
+    return element.getAttribute('aria-description') || null;
+  }
+
+  function getState(element) {
+    const state = {};
+    const disabled = element.disabled || element.getAttribute('aria-disabled') === 'true';
+    if (disabled) state.disabled = true;
+
+    const checked = element.getAttribute('aria-checked');
+    if (element.checked || checked === 'true') state.checked = true;
+    else if (checked === 'mixed' || element.indeterminate) state.checked = 'mixed';
+
+    if (element.selected || element.getAttribute('aria-selected') === 'true') state.selected = true;
+    if (element.hasAttribute('aria-expanded')) state.expanded = element.getAttribute('aria-expanded') === 'true';
+    if (element.hasAttribute('aria-pressed')) state.pressed = element.getAttribute('aria-pressed') === 'true';
+    if (element.required || element.getAttribute('aria-required') === 'true') state.required = true;
+    if (document.activeElement === element) state.focused = true;
+    return state;
+  }
+};
@@ -0,0 +1,44 @@
+// Active element script: the focused element, with its value length and text selection
+window.__tauriMcpActiveElement = function() {
+  'use strict';
+
+  const { getUniqueSelector } = window.__tauriMcpA11y;
+
+  // Look inside shadow roots, where document.activeElement only reports the host
+  let active = document.activeElement;
+  while (active && active.shadowRoot && active.shadowRoot.activeElement) {
+    active = active.shadowRoot.activeElement;
+  }
+  const documentHasFocus = document.hasFocus();
+  if (!active || active === document.body || active === document.documentElement) {
+    return { activeElement: null, documentHasFocus };
+  }
+
+  const hasValue = 'value' in active && typeof active.value === 'string';
+  return {
+    activeElement: {
+      tagName: active.tagName.toLowerCase(),
+      id: active.id || null,
+      selector: getUniqueSelector(active),
+      type: active.tagName === 'INPUT' ? (active.type || 'text').toLowerCase() : null,
+      valueLength: hasValue ? active.value.length : null,
+      selection: selectionOf(active),
+    },
+    documentHasFocus,
+  };
+
+  // Where the caret or selection is in a text field, or null for elements without one
+  function selectionOf(element) {
+    try {
+      // Number, email, and similar inputs throw, or give null, instead of a selection
+      if (typeof element.selectionStart !== 'number') return null;
+      return {
+        start: element.selectionStart,
+        end: element.selectionEnd,
+        direction: element.selectionDirection,
+      };
+    } catch {
+      return null;
+    }
+  }
+};
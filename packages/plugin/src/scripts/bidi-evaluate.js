@@ -0,0 +1,115 @@
+// script.evaluate for the WebDriver BiDi endpoint: evaluate an expression in the page's global
+// scope, like the console does, and serialize the value as BiDi's RemoteValue. There are no
+// handles, so objects always come back by value, and ones met again inside themselves come back
+// without it.
+window.__tauriMcpBidiEvaluate = async function(expression, awaitPromise, maxObjectDepth, context) {
+  'use strict';
+
+  const path = new Set();
+
+  function serialize(value, depth) {
+    if (value === undefined) return { type: 'undefined' };
+    if (value === null) return { type: 'null' };
+    switch (typeof value) {
+      case 'string':
+      case 'boolean':
+        return { type: typeof value, value };
+      case 'number':
+        return { type: 'number', value: numberValue(value) };
+      case 'bigint':
+        return { type: 'bigint', value: String(value) };
+      case 'symbol':
+      case 'function':
+        return { type: typeof value };
+    }
+
+    if (value === window) return { type: 'window', value: { context } };
+    if (value instanceof Node) return { type: 'node', value: nodeValue(value) };
+    if (value instanceof Promise) return { type: 'promise' };
+    if (value instanceof Error) return { type: 'error' };
+    if (value instanceof WeakMap) return { type: 'weakmap' };
+    if (value instanceof WeakSet) return { type: 'weakset' };
+    if (value instanceof ArrayBuffer) return { type: 'arraybuffer' };
+    if (ArrayBuffer.isView(value)) return { type: 'typedarray' };
+    if (value instanceof RegExp) return { type: 'regexp', value: { pattern: value.source, flags: value.flags } };
+    if (value instanceof Date) {
+      return { type: 'date', value: Number.isNaN(value.getTime()) ? 'Invalid Date' : value.toISOString() };
+    }
+
+    const remote = { type: containerType(value) };
+    if (path.has(value) || (maxObjectDepth !== null && depth >= maxObjectDepth)) return remote;
+    path.add(value);
+    try {
+      remote.value = containerValue(value, remote.type, depth + 1);
+    } finally {
+      path.delete(value);
+    }
+    return remote;
+  }
+
+  function numberValue(value) {
+    if (Object.is(value, -0)) return '-0';
+    if (Number.isNaN(value)) return 'NaN';
+    if (value === Infinity) return 'Infinity';
+    if (value === -Infinity) return '-Infinity';
+    return value;
+  }
+
+  function containerType(value) {
+    if (Array.isArray(value)) return 'array';
+    if (value instanceof Map) return 'map';
+    if (value instanceof Set) return 'set';
+    if (value instanceof NodeList) return 'nodelist';
+    if (value instanceof HTMLCollection) return 'htmlcollection';
+    return 'object';
+  }
+
+  function containerValue(value, type, depth) {
+    switch (type) {
+      case 'array':
+      case 'set':
+      case 'nodelist':
+      case 'htmlcollection':
+        return Array.from(value, (item) => serialize(item, depth));
+      case 'map':
+        return Array.from(value, ([key, item]) => [
+          typeof key === 'string' ? key : serialize(key, depth),
+          serialize(item, depth),
+        ]);
+      default:
+        return Object.entries(value).map(([key, item]) => [key, serialize(item, depth)]);
+    }
+  }
+
+  // BiDi's NodeProperties, without children or a shared ID to find the node again
+  function nodeValue(node) {
+    const properties = { nodeType: node.nodeType, childNodeCount: node.childNodes.length };
+    if (node instanceof Element) {
+      properties.localName = node.localName;
+      properties.namespaceURI = node.namespaceURI;
+      properties.attributes = Object.fromEntries(Array.from(node.attributes, (attr) => [attr.name, attr.value]));
+      if (node.shadowRoot) properties.mode = 'open';
+    } else if (node.nodeValue !== null) {
+      properties.nodeValue = node.nodeValue;
+    }
+    return properties;
+  }
+
+  try {
+    // An indirect eval runs in the global scope, so declarations stay on the page
+    let value = (0, eval)(expression);
+    if (awaitPromise) value = await value;
+    return { type: 'success', result: serialize(value, 0) };
+  } catch (error) {
+    return {
+      type: 'exception',
+      exceptionDetails: {
+        text: String(error),
+        lineNumber: 0,
+        columnNumber: 0,
+        exception: serialize(error, 0),
+        stackTrace: { callFrames: [] },
+      },
+    };
+  }
+};
@@ -0,0 +1,33 @@
+// Runtime.evaluate for the CDP endpoint: evaluate an expression in the page's global scope, like
+// the console does, and describe the value the way CDP's RemoteObject does. Promises are awaited,
+// and values are always returned by value, as there are no object handles to return instead.
+window.__tauriMcpCdpEvaluate = async function(expression) {
+  'use strict';
+
+  // An indirect eval runs in the global scope, so declarations stay on the page
+  const value = await (0, eval)(expression);
+  const type = typeof value;
+  const remote = { type };
+  if (value === null) {
+    remote.subtype = 'null';
+  } else if (Array.isArray(value)) {
+    remote.subtype = 'array';
+  } else if (value instanceof Node) {
+    remote.subtype = 'node';
+  } else if (value instanceof Error) {
+    remote.subtype = 'error';
+  }
+
+  if (type === 'undefined') return remote;
+  if (type === 'function' || type === 'symbol' || type === 'bigint' || remote.subtype === 'node' || remote.subtype === 'error') {
+    // Not JSON, so only the description comes back
+    remote.description = value instanceof Node ? value.nodeName.toLowerCase() : String(value);
+    return remote;
+  }
+  if (type === 'number' && !Number.isFinite(value)) {
+    remote.unserializableValue = String(value);
+    return remote;
+  }
+  remote.value = value;
+  return remote;
+};
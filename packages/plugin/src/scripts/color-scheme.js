@@ -0,0 +1,119 @@
+// Color scheme emulation: make the page behave as if the system preferred a dark or light scheme
+(function() {
+  'use strict';
+
+  const SCHEMES = ['dark', 'light', 'no-preference'];
+  const STYLE_ID = '__tauri-mcp-color-scheme';
+  const META_ID = '__tauri-mcp-color-scheme-meta';
+  const QUERY_PATTERN = /^\(\s*prefers-color-scheme\s*:\s*([a-z-]+)\s*\)$/;
+  const RULE_PATTERN = /prefers-color-scheme\s*:\s*([a-z-]+)/;
+
+  // Lives on window so repeated injections share the patched matchMedia and its query lists
+  const state = window.__tauriMcpColorSchemeState || (window.__tauriMcpColorSchemeState = {
+    scheme: null,
+    originalMatchMedia: window.matchMedia.bind(window),
+    queryLists: [],
+  });
+
+  function queryMatches(value) {
+    return state.scheme === value;
+  }
+
+  if (window.matchMedia.__tauriMcpPatched !== true) {
+    const patched = function(query) {
+      const list = state.originalMatchMedia(query);
+      const match = QUERY_PATTERN.exec(String(query).trim().toLowerCase());
+      if (!match) return list;
+
+      const value = match[1];
+      const realMatches = Object.getOwnPropertyDescriptor(MediaQueryList.prototype, 'matches');
+      Object.defineProperty(list, 'matches', {
+        configurable: true,
+        get: () => state.scheme === null ? realMatches.get.call(list) : queryMatches(value),
+      });
+      state.queryLists.push({ ref: typeof WeakRef === 'function' ? new WeakRef(list) : { deref: () => list }, value });
+      return list;
+    };
+    patched.__tauriMcpPatched = true;
+    window.matchMedia = patched;
+  }
+
+  // Copy the rules of matching @media blocks into the override style so they apply unconditionally
+  function collectRules(rules, out) {
+    for (const rule of rules) {
+      if (rule.media && rule.cssRules) {
+        const match = RULE_PATTERN.exec(rule.media.mediaText.toLowerCase());
+        if (match && queryMatches(match[1])) {
+          for (const inner of rule.cssRules) out.push(inner.cssText);
+        } else {
+          collectRules(rule.cssRules, out);
+        }
+      }
+    }
+  }
+
+  function applyStyles() {
+    document.getElementById(STYLE_ID)?.remove();
+    document.getElementById(META_ID)?.remove();
+
+    const rules = [];
+    let skippedStyleSheets = 0;
+    for (const sheet of document.styleSheets) {
+      try {
+        collectRules(sheet.cssRules, rules);
+      } catch (e) {
+        // Cross-origin stylesheets can't be read
+        skippedStyleSheets++;
+      }
+    }
+
+    const colorScheme = state.scheme === 'no-preference' ? 'normal' : state.scheme;
+    const style = document.createElement('style');
+    style.id = STYLE_ID;
+    style.textContent = `:root { color-scheme: ${colorScheme} !important; }\n@media all {\n${rules.join('\n')}\n}`;
+    (document.head || document.documentElement).appendChild(style);
+
+    const meta = document.createElement('meta');
+    meta.id = META_ID;
+    meta.name = 'color-scheme';
+    meta.content = colorScheme;
+    (document.head || document.documentElement).appendChild(meta);
+
+    return { rulesApplied: rules.length, skippedStyleSheets };
+  }
+
+  // Tell listeners on prefers-color-scheme queries that the answer may have changed
+  function notifyListeners() {
+    let notified = 0;
+    state.queryLists = state.queryLists.filter(({ ref }) => ref.deref() !== undefined);
+    for (const { ref, value } of state.queryLists) {
+      const list = ref.deref();
+      const init = { matches: queryMatches(value), media: list.media };
+      const event = typeof MediaQueryListEvent === 'function'
+        ? new MediaQueryListEvent('change', init)
+        : Object.assign(new Event('change'), init);
+      list.dispatchEvent(event);
+      notified++;
+    }
+    return notified;
+  }
+
+  window.__tauriMcpColorSchemeSet = function(args) {
+    const { scheme } = args;
+    if (!SCHEMES.includes(scheme)) {
+      throw new Error(`Invalid scheme: '${scheme}'. Use 'dark', 'light', or 'no-preference'.`);
+    }
+
+    state.scheme = scheme;
+    const { rulesApplied, skippedStyleSheets } = applyStyles();
+    const listenersNotified = notifyListeners();
+
+    return {
+      scheme,
+      matchesDark: window.matchMedia('(prefers-color-scheme: dark)').matches,
+      rulesApplied,
+      skippedStyleSheets,
+      listenersNotified,
+    };
+  };
+})();
@@ -1,68 +1,341 @@
-// DOM snapshot script for capturing accessibility tree or structure tree
-window.__tauriMcpDomSnapshot = function(type, selector) {
+// DOM snapshot script for capturing accessibility tree or structure tree. Both descend into open
+// shadow roots and mark their hosts with shadowRoot: true.
+//
+// options.maxDepth and options.maxNodes cut the tree breadth-first, so the top of it is always
+// complete. Where nodes are left out, a "…truncated (N more nodes)" marker takes their place, and
+// the result is { snapshot, truncated: true, totalNodes, shownNodes } instead of a plain string.
+//
+// With options.format 'json', the snapshot is a tree of { tag, role, name, attrs, children } nodes
+// built from the same walk, with a node's truncated count where its children were cut.
+//
+// options.includeRects adds each shown element's bounding rect and whether it's visible.
+//
+// options.computedStyles lists CSS properties whose computed values are added to each shown element.
+//
+// options.interactiveOnly keeps only the accessibility nodes with interactive roles, and the
+// nodes they're under for context, while options.roles does the same for the roles listed. The
+// matching nodes get a ref, like e12, that selectors can target with { ref }. Each such snapshot
+// makes the refs of earlier ones stale, except those it hands out again.
+//
+// options.keepTree makes the result { snapshot, tree } with the JSON tree too, for dom_diff to keep.
+//
+// Same-origin iframes show their document's tree under the iframe node, which has the frame's URL,
+// down to options.maxFrameDepth frames deep (1 by default). Cross-origin frames can't be read, so
+// they're opaque nodes with their src. Selectors of nodes in frames go through the frame with `>>>`.
+//
+// The html and markdown formats serialize the scoped element with window.__tauriMcpSerialize,
+// whatever the type, cut by the same limits. Output longer than options.maxLength characters is
+// cut too, and the result then also has the full totalLength.
+window.__tauriMcpDomSnapshot = function(type, selector, options) {
   'use strict';
 
-  const root = selector ? document.querySelector(selector) : document.body;
+  const { getRole, getAccessibleName, getUniqueSelector } = window.__tauriMcpA11y;
+  const { query, describe, hasClosedShadowRoot, refFor, newRefGeneration, frameDocument, viewportRect } =
+    window.__tauriMcpSelector;
+
+  const CLOSED_SHADOW_NOTE = "Closed shadow root, its contents can't be inspected";
+  const CROSS_ORIGIN_NOTE = "Cross-origin frame, its contents can't be inspected";
+  const maxDepth = options?.maxDepth ?? Infinity;
+  const maxNodes = options?.maxNodes ?? Infinity;
+  const format = options?.format ?? 'yaml';
+  const json = format === 'json';
+  const includeRects = options?.includeRects === true;
+  const computedStyles = options?.computedStyles ?? [];
+  const keepTree = options?.keepTree === true;
+  // The html and markdown formats serialize the page's own DOM, so they stay out of frames
+  const maxFrameDepth = format === 'html' || format === 'markdown' ? 0 : options?.maxFrameDepth ?? 1;
+  // Roles an agent can act on
+  const INTERACTIVE_ROLES = new Set([
+    'button', 'checkbox', 'combobox', 'link', 'listbox', 'menuitem', 'menuitemcheckbox', 'menuitemradio',
+    'option', 'radio', 'searchbox', 'slider', 'spinbutton', 'switch', 'tab', 'textbox', 'treeitem',
+  ]);
+  const roleFilter = options?.interactiveOnly === true
+    ? INTERACTIVE_ROLES
+    : options?.roles?.length > 0 ? new Set(options.roles.map((role) => role.toLowerCase())) : null;
+  // How far outside the viewport an element still counts as visible, in CSS pixels
+  const NEAR_VIEWPORT_PX = 100;
+  // Longest html or markdown output when no maxLength is given, in characters
+  const DEFAULT_MAX_LENGTH = 100000;
+
+  const root = selector ? query(selector, document, { pierceShadow: options?.pierceShadow === true }) : document.body;
   if (!root) {
-    throw new Error(`Element not found: ${selector}`);
+    throw new Error(`Element not found: ${describe(selector)}`);
   }
 
-  if (type === 'accessibility') {
-    return captureAccessibilityTree(root);
+  if (format === 'html' || format === 'markdown') {
+    const roots = [collectStructureTree(root)];
+    const shownNodes = limit(roots);
+    return serialize(roots[0], shownNodes);
+  } else if (type === 'accessibility') {
+    const roots = collectAccessibilityTree(root);
+    const shownNodes = limit(roots);
+    assignRefs(roots);
+    measure(roots);
+    const snapshot = json ? toJson(roots[0], accessibilityNode) : formatAccessibilityAsYaml(roots);
+    return result(snapshot, roots, shownNodes, () => toJson(singleRoot(roots), accessibilityNode));
   } else if (type === 'structure') {
-    return captureStructureTree(root);
+    const roots = [collectStructureTree(root)];
+    const shownNodes = limit(roots);
+    measure(roots);
+    const snapshot = json ? toJson(roots[0], structureNode) : formatStructureTree(roots[0], '', true, true);
+    return result(snapshot, roots, shownNodes, () => toJson(roots[0], structureNode));
   } else {
     throw new Error(`Unknown snapshot type: ${type}. Use 'accessibility' or 'structure'.`);
   }
 
-  function captureAccessibilityTree(element) {
-    const result = [];
-    const walker = document.createTreeWalker(
-      element,
-      NodeFilter.SHOW_ELEMENT,
-      {
-        acceptNode: (node) => {
-          // Skip hidden elements
-          const style = window.getComputedStyle(node);
-          if (style.display === 'none' || style.visibility === 'hidden') {
-            return NodeFilter.FILTER_REJECT;
-          }
-          return NodeFilter.FILTER_ACCEPT;
+  // A plain string unless the limits left nodes out or the tree is kept, where tree() gives the
+  // JSON tree of a snapshot in another format
+  function result(snapshot, roots, shownNodes, tree) {
+    const totalNodes = roots.reduce((sum, node) => sum + node.size, 0);
+    const truncation = shownNodes === totalNodes ? {} : { truncated: true, totalNodes, shownNodes };
+    if (keepTree) return { snapshot, ...(json ? {} : { tree: tree() }), ...truncation };
+    if (shownNodes === totalNodes) return snapshot;
+    return { snapshot, ...truncation };
+  }
+
+  // The scoped element as HTML or Markdown, as a plain string unless the limits or maxLength cut it
+  function serialize(tree, shownNodes) {
+    const nodes = new Map();
+    const index = (node) => {
+      nodes.set(node.element, node);
+      node.children.forEach(index);
+    };
+    index(tree);
+    const limits = {
+      isCut: (element) => nodes.get(element)?.shown === false,
+      cutCount: (element) => hiddenCount(nodes.get(element)?.children ?? []),
+    };
+
+    const { toHtml, toMarkdown } = window.__tauriMcpSerialize;
+    let text = format === 'html' ? toHtml(root, limits) : toMarkdown(root, limits);
+    if (format === 'markdown' && shownNodes < tree.size) {
+      text += `\n\n${truncatedMarker(tree.size - shownNodes)}`;
+    }
+
+    const maxLength = options?.maxLength ?? DEFAULT_MAX_LENGTH;
+    const snapshot = text.length > maxLength ? cut(text, maxLength) : text;
+    if (snapshot === text && shownNodes === tree.size) return text;
+    const result = { snapshot, truncated: true, totalNodes: tree.size, shownNodes };
+    if (snapshot !== text) result.totalLength = text.length;
+    return result;
+  }
+
+  // Cut text to at most maxLength characters, at the end of a tag or line if there's one, with a
+  // marker after it
+  function cut(text, maxLength) {
+    const boundary = text.lastIndexOf(format === 'html' ? '>' : '\n', maxLength - 1);
+    const end = boundary > 0 ? boundary + (format === 'html' ? 1 : 0) : maxLength;
+    const marker = `…truncated (${text.length - end} more characters)`;
+    return text.slice(0, end) + (format === 'html' ? `<!-- ${marker} -->` : `\n\n${marker}`);
+  }
+
+  // The accessibility nodes under the scoped element as one tree, like the JSON format collects
+  // them, without changing what was measured or cut
+  function singleRoot(roots) {
+    if (roots.length === 1 && roots[0].element === root) return roots[0];
+    return { ...node(root, roots), shown: true };
+  }
+
+  function node(element, children) {
+    const size = children.reduce((sum, child) => sum + child.size, 1);
+    return { element, children, size, shown: false };
+  }
+
+  // Mark the nodes to show, level by level, so a cut only ever drops the deepest and last ones.
+  // Returns how many were marked.
+  function limit(roots) {
+    const queue = roots.map((root) => ({ node: root, depth: 0 }));
+    let shown = 0;
+    for (let i = 0; i < queue.length && shown < maxNodes; i++) {
+      const { node, depth } = queue[i];
+      node.shown = true;
+      shown++;
+      if (depth < maxDepth) {
+        for (const child of node.children) {
+          queue.push({ node: child, depth: depth + 1 });
         }
       }
-    );
+    }
+    return shown;
+  }
+
+  // How many nodes under these ones were left out, or 0 if none
+  function hiddenCount(nodes) {
+    return nodes.reduce((sum, node) => (node.shown ? sum : sum + node.size), 0);
+  }
+
+  function truncatedMarker(count) {
+    return `…truncated (${count} more ${count === 1 ? 'node' : 'nodes'})`;
+  }
+
+  // Give the shown nodes that matched the role filter their refs, in a new generation
+  function assignRefs(roots) {
+    if (!roleFilter) return;
+    newRefGeneration();
+    const visit = (node) => {
+      if (!node.shown) return;
+      if (node.matched) {
+        node.ref = refFor(node.element, getRole(node.element), getAccessibleName(node.element));
+      }
+      node.children.forEach(visit);
+    };
+    roots.forEach(visit);
+  }
 
-    let node = walker.currentNode;
-    while (node) {
-      const info = getAccessibilityInfo(node);
-      if (info) {
-        result.push(info);
+  // Read the rects and styles of all shown elements before emitting anything, so the layout is
+  // only computed once however big the tree is
+  function measure(roots) {
+    if (!includeRects && computedStyles.length === 0) return;
+    const viewportWidth = window.innerWidth;
+    const viewportHeight = window.innerHeight;
+    const visit = (node) => {
+      if (!node.shown) return;
+      if (!isShadowRoot(node.element)) {
+        const style = window.getComputedStyle(node.element);
+        if (includeRects) {
+          const rect = viewportRect(node.element);
+          node.rect = {
+            x: Math.round(rect.x),
+            y: Math.round(rect.y),
+            width: Math.round(rect.width),
+            height: Math.round(rect.height),
+          };
+          node.visible = rect.width > 0 && rect.height > 0 &&
+            style.display !== 'none' && style.visibility !== 'hidden' &&
+            rect.right >= -NEAR_VIEWPORT_PX && rect.bottom >= -NEAR_VIEWPORT_PX &&
+            rect.left <= viewportWidth + NEAR_VIEWPORT_PX && rect.top <= viewportHeight + NEAR_VIEWPORT_PX;
+        }
+        if (computedStyles.length > 0) {
+          node.styles = Object.fromEntries(computedStyles.map((name) => [name, style.getPropertyValue(name)]));
+        }
       }
-      node = walker.nextNode();
+      node.children.forEach(visit);
+    };
+    roots.forEach(visit);
+  }
+
+  // A node and its shown children as JSON, where describe gives its tag, role, name, and attrs
+  function toJson(node, describe) {
+    const result = describe(node.element);
+    if (node.ref) result.ref = node.ref;
+    if (node.rect) {
+      result.rect = node.rect;
+      result.visible = node.visible;
     }
+    if (node.styles) result.styles = node.styles;
+    result.children = node.children.filter((child) => child.shown).map((child) => toJson(child, describe));
+    const hidden = hiddenCount(node.children);
+    if (hidden > 0) result.truncated = hidden;
+    return result;
+  }
 
-    return formatAccessibilityAsYaml(result);
+  function accessibilityNode(element) {
+    const { tag, role, name, ...attrs } = getAccessibilityInfo(element);
+    if (attrs.shadowRoot === 'closed') attrs.note = CLOSED_SHADOW_NOTE;
+    if (attrs.crossOrigin) attrs.note = CROSS_ORIGIN_NOTE;
+    return { tag, role: role ?? null, name: name ?? null, attrs };
   }
 
-  function formatAccessibilityAsYaml(items) {
-    if (items.length === 0) return '';
+  function structureNode(element) {
+    if (isShadowRoot(element)) {
+      return { tag: '#shadow-root', role: null, name: null, attrs: {} };
+    }
+    const attrs = {};
+    if (element.id) attrs.id = element.id;
+    const classes = typeof element.className === 'string' ? element.className.trim() : '';
+    if (classes) attrs.class = classes.split(/\s+/).join(' ');
+    if (element.dataset.testid) attrs['data-testid'] = element.dataset.testid;
+    const shadowRoot = shadowRootState(element);
+    if (shadowRoot) attrs.shadowRoot = shadowRoot;
+    if (shadowRoot === 'closed') attrs.note = CLOSED_SHADOW_NOTE;
+    const frame = frameInfo(element);
+    if (frame) Object.assign(attrs, frame);
+    if (frame?.crossOrigin) attrs.note = CROSS_ORIGIN_NOTE;
+    return { tag: element.tagName.toLowerCase(), role: null, name: null, attrs };
+  }
 
-    const lines = [];
-    for (const item of items) {
-      lines.push(`- tag: ${item.tag}`);
-      if (item.role) lines.push(`  role: ${item.role}`);
-      if (item.name) lines.push(`  name: ${yamlEscape(item.name)}`);
-      if (item.value !== undefined) lines.push(`  value: ${yamlEscape(item.value)}`);
-      if (item.disabled) lines.push(`  disabled: true`);
-      if (item.checked) lines.push(`  checked: true`);
-      if (item.selected) lines.push(`  selected: true`);
-      if (item.expanded !== undefined) lines.push(`  expanded: ${item.expanded}`);
-      if (item.pressed !== undefined) lines.push(`  pressed: ${item.pressed}`);
-      if (item.selector) lines.push(`  selector: ${yamlEscapeSelector(item.selector)}`);
+  // Nodes are the elements with a role, a name, a shadow root, or a frame, nested under the closest
+  // such ancestor. With a role filter, only nodes with a matching role and those above them are
+  // kept, and cross-origin frames, which could have any. JSON needs a single root, so there the
+  // scoped element is always one.
+  function collectAccessibilityTree(element) {
+    const visit = (el, frameDepth) => {
+      // Skip hidden elements
+      const style = window.getComputedStyle(el);
+      if (style.display === 'none' || style.visibility === 'hidden') {
+        return [];
+      }
+
+      // The shadow tree first, since it's what renders, and a frame's document rather than its
+      // fallback content
+      const body = frameBody(el, frameDepth);
+      const children = body
+        ? visit(body, frameDepth + 1)
+        : [...(el.shadowRoot?.children || []), ...el.children].flatMap((child) => visit(child, frameDepth));
+      if (!isAccessible(el)) return children;
+      const matched = roleFilter !== null && roleFilter.has((getRole(el) || '').toLowerCase());
+      if (roleFilter && !matched && children.length === 0 && !frameInfo(el)?.crossOrigin) return [];
+      return [{ ...node(el, children), matched }];
+    };
+    const roots = visit(element, 0);
+    if (json && roots[0]?.element !== element) {
+      return [node(element, roots)];
     }
+    return roots;
+  }
+
+  // A flat list in document order, with a marker after the last shown node under a cut parent
+  function formatAccessibilityAsYaml(roots) {
+    const lines = [];
+    const visit = (nodes) => {
+      for (const node of nodes) {
+        if (!node.shown) continue;
+        pushAccessibilityItem(lines, getAccessibilityInfo(node.element));
+        if (node.ref) lines.push(`  ref: ${node.ref}`);
+        if (node.rect) {
+          const { x, y, width, height } = node.rect;
+          lines.push(`  rect: { x: ${x}, y: ${y}, width: ${width}, height: ${height} }`);
+          lines.push(`  visible: ${node.visible}`);
+        }
+        if (node.styles) {
+          lines.push('  styles:');
+          for (const [name, value] of Object.entries(node.styles)) {
+            lines.push(`    ${name}: ${yamlEscape(value)}`);
+          }
+        }
+        visit(node.children);
+      }
+      const hidden = hiddenCount(nodes);
+      if (hidden > 0) lines.push(`- ${truncatedMarker(hidden)}`);
+    };
+    visit(roots);
     return lines.join('\n');
   }
 
+  function pushAccessibilityItem(lines, item) {
+    lines.push(`- tag: ${item.tag}`);
+    if (item.role) lines.push(`  role: ${item.role}`);
+    if (item.name) lines.push(`  name: ${yamlEscape(item.name)}`);
+    if (item.value !== undefined) lines.push(`  value: ${yamlEscape(item.value)}`);
+    if (item.disabled) lines.push(`  disabled: true`);
+    if (item.checked) lines.push(`  checked: true`);
+    if (item.selected) lines.push(`  selected: true`);
+    if (item.expanded !== undefined) lines.push(`  expanded: ${item.expanded}`);
+    if (item.pressed !== undefined) lines.push(`  pressed: ${item.pressed}`);
+    if (item.shadowRoot === true) lines.push(`  shadowRoot: true`);
+    if (item.shadowRoot === 'closed') {
+      lines.push(`  shadowRoot: closed`);
+      lines.push(`  note: ${yamlEscape(CLOSED_SHADOW_NOTE)}`);
+    }
+    if (item.frame) lines.push(`  frame: ${yamlEscape(item.frame)}`);
+    if (item.crossOrigin) {
+      lines.push(`  src: ${yamlEscape(item.src)}`);
+      lines.push(`  crossOrigin: true`);
+      lines.push(`  note: ${yamlEscape(CROSS_ORIGIN_NOTE)}`);
+    }
+    if (item.selector) lines.push(`  selector: ${yamlEscapeSelector(item.selector)}`);
+  }
+
   function yamlEscape(value) {
     if (value === '') return '""';
     if (value === null || value === undefined) return '""';
@@ -82,12 +355,16 @@ window.__tauriMcpDomSnapshot = function(type, selector) {
     return selector;
   }
 
+  // Shadow hosts and frames are kept so the boundary shows
+  function isAccessible(element) {
+    return Boolean(getRole(element) || getAccessibleName(element) || shadowRootState(element) || frameInfo(element));
+  }
+
   function getAccessibilityInfo(element) {
-    const role = element.getAttribute('role') || getImplicitRole(element);
+    const role = getRole(element);
     const name = getAccessibleName(element);
     const value = getAccessibleValue(element);
-
-    if (!role && !name) return null;
+    const shadowRoot = shadowRootState(element);
 
     const info = { tag: element.tagName.toLowerCase() };
     if (role) info.role = role;
@@ -100,6 +377,8 @@ window.__tauriMcpDomSnapshot = function(type, selector) {
     if (element.selected) info.selected = true;
     if (element.getAttribute('aria-expanded')) info.expanded = element.getAttribute('aria-expanded') === 'true';
     if (element.getAttribute('aria-pressed')) info.pressed = element.getAttribute('aria-pressed') === 'true';
+    if (shadowRoot) info.shadowRoot = shadowRoot;
+    Object.assign(info, frameInfo(element));
 
     // Include selector for targeting
     info.selector = getUniqueSelector(element);
@@ -107,62 +386,28 @@ window.__tauriMcpDomSnapshot = function(type, selector) {
     return info;
   }
 
-  function getImplicitRole(element) {
-    const roleMap = {
-      'A': element.href ? 'link' : null,
-      'BUTTON': 'button',
-      'INPUT': getInputRole(element),
-      'SELECT': 'combobox',
-      'TEXTAREA': 'textbox',
-      'IMG': 'img',
-      'NAV': 'navigation',
-      'MAIN': 'main',
-      'HEADER': 'banner',
-      'FOOTER': 'contentinfo',
-      'ARTICLE': 'article',
-      'ASIDE': 'complementary',
-      'SECTION': 'region',
-      'FORM': 'form',
-      'TABLE': 'table',
-      'UL': 'list',
-      'OL': 'list',
-      'LI': 'listitem',
-      'H1': 'heading',
-      'H2': 'heading',
-      'H3': 'heading',
-      'H4': 'heading',
-      'H5': 'heading',
-      'H6': 'heading',
-    };
-    return roleMap[element.tagName] || null;
+  // Frames have their own ShadowRoot class, so the node type tells shadow roots apart
+  function isShadowRoot(node) {
+    return node.nodeType === Node.DOCUMENT_FRAGMENT_NODE;
   }
 
-  function getInputRole(input) {
-    const typeRoles = {
-      'checkbox': 'checkbox',
-      'radio': 'radio',
-      'range': 'slider',
-      'button': 'button',
-      'submit': 'button',
-      'reset': 'button',
-      'search': 'searchbox',
-    };
-    return typeRoles[input.type] || 'textbox';
+  // { frame: url } for a same-origin frame, { src, crossOrigin: true } for one scripts can't read,
+  // or null for other elements
+  function frameInfo(element) {
+    if (element.tagName !== 'IFRAME' && element.tagName !== 'FRAME') return null;
+    const content = frameDocument(element);
+    return content ? { frame: content.URL } : { src: element.getAttribute('src') ?? '', crossOrigin: true };
   }
 
-  function getAccessibleName(element) {
-    return element.getAttribute('aria-label') ||
-      element.getAttribute('aria-labelledby') && getLabelledByText(element) ||
-      element.getAttribute('alt') ||
-      element.getAttribute('title') ||
-      element.innerText?.trim().slice(0, 100) ||
-      null;
+  // The body of a same-origin frame's document, if the snapshot goes that many frames deep
+  function frameBody(element, frameDepth) {
+    return frameDepth < maxFrameDepth ? frameDocument(element)?.body ?? null : null;
   }
 
-  function getLabelledByText(element) {
-    const id = element.getAttribute('aria-labelledby');
-    const labelElement = document.getElementById(id);
-    return labelElement?.innerText?.trim() || null;
+  // true for an open shadow root, 'closed' for a closed one, null for none
+  function shadowRootState(element) {
+    if (element.shadowRoot) return true;
+    return hasClosedShadowRoot(element) ? 'closed' : null;
   }
 
   function getAccessibleValue(element) {
@@ -175,42 +420,39 @@ window.__tauriMcpDomSnapshot = function(type, selector) {
     return undefined;
   }
 
-  function captureStructureTree(element) {
-    return formatStructureTree(element, '', true);
+  // Nodes are all the elements, plus a #shadow-root for each open shadow root
+  function collectStructureTree(element, frameDepth = 0) {
+    const body = frameBody(element, frameDepth);
+    if (body) return node(element, [collectStructureTree(body, frameDepth + 1)]);
+    const children = element.shadowRoot ? [element.shadowRoot, ...element.children] : Array.from(element.children);
+    return node(element, children.map((child) => collectStructureTree(child, frameDepth)));
   }
 
-  function formatStructureTree(element, prefix, isLast, depth = 0) {
-    if (depth > 20) return ''; // Prevent infinite recursion
-
-    const lines = [];
-    const nodeStr = formatNodeString(element);
-
-    if (depth === 0) {
-      // Root element has no prefix
-      lines.push(nodeStr);
-    } else {
-      const connector = isLast ? '└─ ' : '├─ ';
-      lines.push(prefix + connector + nodeStr);
+  // Open shadow roots show as a #shadow-root child ahead of the host's light DOM children
+  function formatStructureTree(node, prefix, isLast, isRoot) {
+    const element = node.element;
+    let nodeStr = isShadowRoot(element) ? '#shadow-root' : formatNodeString(element);
+    if (node.rect) {
+      const { x, y, width, height } = node.rect;
+      nodeStr += ` [rect: ${x},${y} ${width}x${height}]`;
+      if (!node.visible) nodeStr += ' [visible: false]';
     }
-
-    const children = Array.from(element.children);
-    const childCount = children.length;
-
-    for (let i = 0; i < childCount; i++) {
-      const child = children[i];
-      const isLastChild = i === childCount - 1;
-      let childPrefix;
-
-      if (depth === 0) {
-        childPrefix = '';
-      } else {
-        childPrefix = prefix + (isLast ? '   ' : '│  ');
-      }
-
-      const childLines = formatStructureTree(child, childPrefix, isLastChild, depth + 1);
-      if (childLines) {
-        lines.push(childLines);
-      }
+    if (node.styles) {
+      const declarations = Object.entries(node.styles).map(([name, value]) => `${name}: ${value}`);
+      nodeStr += ` {${declarations.join('; ')}}`;
+    }
+    // Root element has no prefix
+    const lines = [isRoot ? nodeStr : prefix + (isLast ? '└─ ' : '├─ ') + nodeStr];
+    const childPrefix = isRoot ? '' : prefix + (isLast ? '   ' : '│  ');
+
+    const shown = node.children.filter((child) => child.shown);
+    const hidden = hiddenCount(node.children);
+    shown.forEach((child, i) => {
+      const isLastChild = i === shown.length - 1 && hidden === 0;
+      lines.push(formatStructureTree(child, childPrefix, isLastChild, false));
+    });
+    if (hidden > 0) {
+      lines.push(childPrefix + '└─ ' + truncatedMarker(hidden));
     }
 
     return lines.join('\n');
@@ -237,57 +479,20 @@ window.__tauriMcpDomSnapshot = function(type, selector) {
       str += ' @' + element.dataset.testid;
     }
 
-    return str;
-  }
-
-  function escapeCssIdentifier(id) {
-    // Use CSS.escape if available, otherwise implement a simple escaper
-    if (typeof CSS !== 'undefined' && CSS.escape) {
-      return CSS.escape(id);
+    const shadowRoot = shadowRootState(element);
+    if (shadowRoot === true) {
+      str += ' [shadowRoot: true]';
+    } else if (shadowRoot === 'closed') {
+      str += ` [shadowRoot: closed] (${CLOSED_SHADOW_NOTE})`;
     }
-    // Simple escaper for special characters in CSS identifiers
-    return id.replace(/([^\w-])/g, '\\$1');
-  }
 
-  function getUniqueSelector(element) {
-    if (element.id) {
-      return '#' + escapeCssIdentifier(element.id);
+    const frame = frameInfo(element);
+    if (frame?.crossOrigin) {
+      str += ` [frame: ${frame.src}, cross-origin] (${CROSS_ORIGIN_NOTE})`;
+    } else if (frame) {
+      str += ` [frame: ${frame.frame}]`;
     }
-    if (element.dataset.testid) {
-      return `[data-testid="${element.dataset.testid}"]`;
-    }
-
-    const path = [];
-    let current = element;
 
-    while (current && current !== document.body) {
-      let selector = current.tagName.toLowerCase();
-
-      if (current.id) {
-        path.unshift('#' + escapeCssIdentifier(current.id));
-        break;
-      }
-
-      // Add class if available for more specific selection
-      if (current.className && typeof current.className === 'string') {
-        const classes = current.className.trim().split(/\s+/).filter(Boolean);
-        if (classes.length > 0) {
-          selector += '.' + classes.map(escapeCssIdentifier).join('.');
-        }
-      }
-
-      const siblings = current.parentElement?.children || [];
-      const sameTag = Array.from(siblings).filter(s => s.tagName === current.tagName);
-
-      if (sameTag.length > 1) {
-        const index = sameTag.indexOf(current) + 1;
-        selector += `:nth-of-type(${index})`;
-      }
-
-      path.unshift(selector);
-      current = current.parentElement;
-    }
-
-    return path.join(' > ');
+    return str;
   }
 };
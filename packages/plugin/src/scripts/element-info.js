@@ -0,0 +1,58 @@
+// Element info script: where an element is, whether it can be seen and clicked, and selected
+// computed styles and attributes. Needs selector.js for CSS strings and selector objects.
+window.__tauriMcpElementInfo = function(args) {
+  'use strict';
+
+  const { getUniqueSelector } = window.__tauriMcpA11y;
+  const { query, queryAll, describe, containsDeep, elementFromPoint, viewportRect, isVisible } = window.__tauriMcpSelector;
+
+  const { selector, styles = [], all = false, pierceShadow = false } = args;
+
+  if (all) {
+    return queryAll(selector, document, { pierceShadow }).map(info);
+  }
+  const element = query(selector, document, { pierceShadow });
+  if (!element) {
+    throw new Error(`Element not found: ${describe(selector)}`);
+  }
+  return info(element);
+
+  function info(element) {
+    // In the top-level viewport, also for elements in frames
+    const rect = viewportRect(element);
+    const style = window.getComputedStyle(element);
+    const centerX = rect.left + rect.width / 2;
+    const centerY = rect.top + rect.height / 2;
+    const centerInViewport = centerX >= 0 && centerY >= 0 && centerX < window.innerWidth && centerY < window.innerHeight;
+    const topmost = centerInViewport ? elementFromPoint(centerX, centerY) : null;
+
+    return {
+      tagName: element.tagName.toLowerCase(),
+      selector: getUniqueSelector(element),
+      boundingClientRect: {
+        x: rect.x,
+        y: rect.y,
+        width: rect.width,
+        height: rect.height,
+        top: rect.top,
+        right: rect.right,
+        bottom: rect.bottom,
+        left: rect.left,
+      },
+      inViewport: rect.top >= 0 && rect.left >= 0 && rect.bottom <= window.innerHeight && rect.right <= window.innerWidth,
+      partiallyInViewport: rect.bottom > 0 && rect.right > 0 && rect.top < window.innerHeight && rect.left < window.innerWidth,
+      visibility: {
+        visible: isVisible(element),
+        display: style.display,
+        visibility: style.visibility,
+        opacity: parseFloat(style.opacity),
+      },
+      disabled: element.matches(':disabled') || element.closest('[aria-disabled="true"]') !== null,
+      // Null when the center is outside the viewport, where nothing can be hit
+      topmostAtCenter: topmost ? getUniqueSelector(topmost) : null,
+      occluded: centerInViewport ? !topmost || !containsDeep(element, topmost) : null,
+      styles: Object.fromEntries(styles.map((name) => [name, style.getPropertyValue(name)])),
+      attributes: Object.fromEntries(Array.from(element.attributes, (attribute) => [attribute.name, attribute.value])),
+    };
+  }
+};
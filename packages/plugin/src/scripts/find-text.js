@@ -0,0 +1,134 @@
+// Find text script: where the page shows some text. Walks the visible text nodes of the document
+// with a TreeWalker and searches their text, so a match can span inline elements like
+// "Payment <b>failed</b>". Text in different blocks is kept apart, and whitespace in a plain
+// query matches any run of whitespace. Needs a11y.js and selector.js.
+window.__tauriMcpFindText = function(args) {
+  'use strict';
+
+  const { getUniqueSelector } = window.__tauriMcpA11y;
+  const { isVisible } = window.__tauriMcpSelector;
+
+  const { query, regex = false, caseSensitive = false, maxResults = 20 } = args;
+
+  // Elements whose text isn't shown
+  const SKIPPED = 'head, script, style, noscript, template';
+
+  // How many characters of context an excerpt shows on each side of the match
+  const EXCERPT_CONTEXT = 40;
+
+  // Put between the text of different blocks, so no match spans two of them
+  const BLOCK_SEPARATOR = '\u0000';
+
+  let pattern;
+  try {
+    const source = regex ? query : escapeRegex(query).replace(/\s+/g, '\\s+');
+    pattern = new RegExp(source, caseSensitive ? 'g' : 'gi');
+  } catch (error) {
+    throw new Error(`Invalid regex: ${error.message}`);
+  }
+
+  const { text, segments } = collectText(document.body || document.documentElement);
+  const matches = [];
+  let total = 0;
+  for (let match = pattern.exec(text); match; match = pattern.exec(text)) {
+    if (match[0] === '') {
+      // Empty matches find nothing to show, and would match again at the same place
+      pattern.lastIndex += 1;
+      continue;
+    }
+    total += 1;
+    if (matches.length < maxResults) {
+      matches.push(describeMatch(match.index, match.index + match[0].length));
+    }
+  }
+
+  return { matches, total, truncated: total > matches.length };
+
+  function escapeRegex(string) {
+    return string.replace(/[.*+?^${}()|[\]\\]/g, '\\$&');
+  }
+
+  // The visible text as one string, with where each text node's text is in it
+  function collectText(root) {
+    const visible = new Map();
+    const blocks = new Map();
+    const walker = document.createTreeWalker(root, NodeFilter.SHOW_TEXT, {
+      acceptNode: (node) => (isShown(node.parentElement, visible) ? NodeFilter.FILTER_ACCEPT : NodeFilter.FILTER_REJECT),
+    });
+
+    let collected = '';
+    let lastBlock = null;
+    const collectedSegments = [];
+    for (let node = walker.nextNode(); node; node = walker.nextNode()) {
+      const block = blockOf(node.parentElement, blocks);
+      if (lastBlock && block !== lastBlock) collected += BLOCK_SEPARATOR;
+      lastBlock = block;
+      collectedSegments.push({ node, start: collected.length, end: collected.length + node.data.length });
+      collected += node.data;
+    }
+    return { text: collected, segments: collectedSegments };
+  }
+
+  // Whether an element's text can be seen, remembering the answer for its other text nodes
+  function isShown(element, cache) {
+    if (!element) return false;
+    if (!cache.has(element)) {
+      cache.set(element, !element.closest(SKIPPED) && isVisible(element));
+    }
+    return cache.get(element);
+  }
+
+  // The nearest ancestor that isn't laid out inline, which text in the same line shares. Inline
+  // blocks like buttons count as blocks, as their text doesn't run on into the next one's.
+  function blockOf(element, cache) {
+    if (!cache.has(element)) {
+      let block = element;
+      while (block.parentElement && ['inline', 'contents'].includes(window.getComputedStyle(block).display)) {
+        block = block.parentElement;
+      }
+      cache.set(element, block);
+    }
+    return cache.get(element);
+  }
+
+  function describeMatch(start, end) {
+    const range = document.createRange();
+    const first = segments.find((segment) => segment.end > start);
+    const last = segments.findLast((segment) => segment.start < end);
+    range.setStart(first.node, Math.max(0, start - first.start));
+    range.setEnd(last.node, Math.min(end, last.end) - last.start);
+
+    const container = range.commonAncestorContainer;
+    const element = container.nodeType === Node.ELEMENT_NODE ? container : container.parentElement;
+    const rect = range.getBoundingClientRect();
+    return {
+      text: clean(text.slice(start, end)),
+      selector: getUniqueSelector(element),
+      tagName: element.tagName.toLowerCase(),
+      excerpt: excerpt(start, end),
+      rect: {
+        x: Math.round(rect.x),
+        y: Math.round(rect.y),
+        width: Math.round(rect.width),
+        height: Math.round(rect.height),
+      },
+      inViewport: rect.bottom > 0 && rect.right > 0 && rect.top < window.innerHeight && rect.left < window.innerWidth,
+    };
+  }
+
+  // The match in its block's text around it, marked with **
+  function excerpt(start, end) {
+    const blockStart = text.lastIndexOf(BLOCK_SEPARATOR, start) + 1;
+    const separator = text.indexOf(BLOCK_SEPARATOR, end);
+    const blockEnd = separator === -1 ? text.length : separator;
+    const from = Math.max(blockStart, start - EXCERPT_CONTEXT);
+    const to = Math.min(blockEnd, end + EXCERPT_CONTEXT);
+    const before = (from > blockStart ? '…' : '') + clean(text.slice(from, start)).trimStart();
+    const after = clean(text.slice(end, to)).trimEnd() + (to < blockEnd ? '…' : '');
+    return `${before}**${clean(text.slice(start, end))}**${after}`;
+  }
+
+  function clean(string) {
+    return string.replaceAll(BLOCK_SEPARATOR, ' ').replace(/\s+/g, ' ');
+  }
+};
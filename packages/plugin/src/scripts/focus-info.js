@@ -0,0 +1,65 @@
+// Focus inspection script: the focused element and the keyboard tab order
+window.__tauriMcpFocusInfo = function() {
+  'use strict';
+
+  const { getRole, computeAccessibleName, getUniqueSelector } = window.__tauriMcpA11y;
+
+  const candidates = document.querySelectorAll(
+    '[tabindex], a[href], button, input, select, textarea, [contenteditable]:not([contenteditable="false"])'
+  );
+
+  // Positive tabindex values come first (ascending), then tabindex 0 in document order.
+  // Array.prototype.sort is stable, so document order is kept within each tabindex.
+  const tabOrder = Array.from(candidates)
+    .filter(isTabbable)
+    .sort((a, b) => sortKey(a) - sortKey(b))
+    .map((element, index) => ({ index, ...describe(element) }));
+
+  const active = document.activeElement;
+  const hasFocus = active && active !== document.body && active !== document.documentElement;
+
+  return {
+    activeElement: hasFocus ? {
+      ...describe(active),
+      tagName: active.tagName.toLowerCase(),
+      id: active.id || null,
+      name: active.getAttribute('name'),
+      accessibleName: computeAccessibleName(active),
+      tabOrderIndex: tabOrder.findIndex(entry => entry.selector === getUniqueSelector(active)),
+    } : null,
+    documentHasFocus: document.hasFocus(),
+    tabOrder,
+  };
+
+  function describe(element) {
+    return {
+      selector: getUniqueSelector(element),
+      type: getType(element),
+      role: getRole(element),
+      tabIndex: element.tabIndex,
+      value: 'value' in element && typeof element.value === 'string' ? element.value : null,
+    };
+  }
+
+  function getType(element) {
+    if (element.tagName === 'INPUT') return (element.type || 'text').toLowerCase();
+    return element.tagName.toLowerCase();
+  }
+
+  function sortKey(element) {
+    // tabindex 0 sorts after every positive value
+    return element.tabIndex > 0 ? element.tabIndex : Number.MAX_SAFE_INTEGER;
+  }
+
+  function isTabbable(element) {
+    if (element.tabIndex < 0) return false;
+    if (element.disabled) return false;
+    if (element.tagName === 'INPUT' && element.type === 'hidden') return false;
+    if (element.closest('[inert]')) return false;
+
+    const style = window.getComputedStyle(element);
+    if (style.visibility === 'hidden' || style.display === 'none') return false;
+    // Elements inside a display:none ancestor have no layout boxes
+    return element.getClientRects().length > 0;
+  }
+};
@@ -0,0 +1,72 @@
+// Form field enumeration script: structured view of form control state
+window.__tauriMcpFormFields = function(args) {
+  'use strict';
+
+  const { getUniqueSelector } = window.__tauriMcpA11y;
+  const { formSelector } = args;
+
+  let root = document;
+  if (formSelector) {
+    root = document.querySelector(formSelector);
+    if (!root) {
+      throw new Error(`Form not found: ${formSelector}`);
+    }
+  }
+
+  // form.elements also includes controls associated via the form="" attribute
+  const controls = root instanceof HTMLFormElement
+    ? Array.from(root.elements)
+    : Array.from(root.querySelectorAll('input, select, textarea, button, output, fieldset, object'));
+
+  return controls
+    .filter(element => element.tagName !== 'FIELDSET' && element.tagName !== 'OBJECT')
+    .map(describe);
+
+  function describe(element) {
+    const field = {
+      selector: getUniqueSelector(element),
+      type: getType(element),
+      name: element.name || null,
+      id: element.id || null,
+      value: getValue(element),
+      checked: isCheckable(element) ? element.checked : null,
+      options: null,
+      disabled: element.disabled === true,
+      required: element.required === true,
+      validationMessage: element.validationMessage || null,
+    };
+
+    if (element.tagName === 'SELECT') {
+      field.options = Array.from(element.options).map(option => ({
+        value: option.value,
+        text: option.text.trim(),
+        selected: option.selected,
+        disabled: option.disabled,
+      }));
+    }
+
+    return field;
+  }
+
+  function getType(element) {
+    if (element.tagName === 'TEXTAREA') return 'textarea';
+    if (element.tagName === 'OUTPUT') return 'output';
+    if (element.tagName === 'SELECT') return element.multiple ? 'select-multiple' : 'select-one';
+    return (element.type || element.tagName).toLowerCase();
+  }
+
+  function isCheckable(element) {
+    return element.tagName === 'INPUT' && (element.type === 'checkbox' || element.type === 'radio');
+  }
+
+  function getValue(element) {
+    if (element.tagName === 'SELECT' && element.multiple) {
+      return Array.from(element.selectedOptions).map(option => option.value);
+    }
+    // Never expose file paths, just the chosen file names
+    if (element.tagName === 'INPUT' && element.type === 'file') {
+      return Array.from(element.files || []).map(file => file.name);
+    }
+    return element.value ?? null;
+  }
+};
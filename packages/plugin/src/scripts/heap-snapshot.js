@@ -0,0 +1,37 @@
+// Heap usage summary: what the engine exposes about JS memory use
+window.__tauriMcpHeapSnapshot = async function() {
+  'use strict';
+
+  // Chromium (WebView2) only
+  const memory = performance.memory;
+  if (memory) {
+    return {
+      supported: true,
+      source: 'performance.memory',
+      usedJSHeapSize: memory.usedJSHeapSize,
+      totalJSHeapSize: memory.totalJSHeapSize,
+      jsHeapSizeLimit: memory.jsHeapSizeLimit,
+    };
+  }
+
+  // Chromium in cross-origin isolated pages; may wait for the next garbage collection
+  if (typeof performance.measureUserAgentSpecificMemory === 'function' && window.crossOriginIsolated) {
+    const result = await performance.measureUserAgentSpecificMemory();
+    return {
+      supported: true,
+      source: 'performance.measureUserAgentSpecificMemory',
+      usedJSHeapSize: result.bytes,
+      totalJSHeapSize: null,
+      jsHeapSizeLimit: null,
+    };
+  }
+
+  return {
+    supported: false,
+    source: null,
+    usedJSHeapSize: null,
+    totalJSHeapSize: null,
+    jsHeapSizeLimit: null,
+    reason: 'This webview does not expose JS heap size (performance.memory is only available in Chromium-based webviews)',
+  };
+};
@@ -0,0 +1,113 @@
+// Highlight script: outlines an element with an overlay, so people watching can see what an
+// agent is looking at. Needs selector.js for CSS strings and selector objects.
+//
+// Overlays are fixed-position and ignore the pointer, so they never get in the way of clicks.
+// They follow the element as the page scrolls or the element resizes, and remove themselves
+// after `durationMs`.
+(function() {
+  'use strict';
+
+  const ATTRIBUTE = 'data-tauri-mcp-highlight';
+  const DEFAULT_COLOR = '#e8336d';
+  const DEFAULT_DURATION_MS = 2000;
+
+  // Cleanup functions of the overlays on the page, kept across calls so highlight_clear finds them
+  const active = window.__tauriMcpHighlights || (window.__tauriMcpHighlights = new Set());
+
+  window.__tauriMcpHighlight = function(args) {
+    const { getUniqueSelector } = window.__tauriMcpA11y;
+    const { query, describe } = window.__tauriMcpSelector;
+
+    const { selector, durationMs = DEFAULT_DURATION_MS, color = DEFAULT_COLOR, label, pierceShadow = false } = args;
+
+    if (!CSS.supports('color', color)) {
+      throw new Error(`Invalid color: ${color}. Use a CSS color like 'red' or '#e8336d'.`);
+    }
+
+    const element = query(selector, document, { pierceShadow });
+    if (!element) {
+      throw new Error(`Element not found: ${describe(selector)}`);
+    }
+
+    const overlay = document.createElement('div');
+    overlay.setAttribute(ATTRIBUTE, '');
+    overlay.style.cssText = [
+      'position: fixed',
+      'box-sizing: border-box',
+      'pointer-events: none',
+      'z-index: 2147483647',
+      `outline: 2px solid ${color}`,
+      'outline-offset: 1px',
+      'margin: 0',
+      'padding: 0',
+    ].join('; ');
+
+    if (label !== undefined && label !== null && label !== '') {
+      const tag = document.createElement('span');
+      tag.textContent = String(label);
+      tag.style.cssText = [
+        'position: absolute',
+        'left: -3px',
+        'bottom: 100%',
+        'margin-bottom: 3px',
+        'padding: 1px 6px',
+        `background: ${color}`,
+        'color: #fff',
+        'font: bold 12px/16px system-ui, sans-serif',
+        'border-radius: 3px',
+        'white-space: nowrap',
+      ].join('; ');
+      overlay.appendChild(tag);
+    }
+
+    // Keep the overlay on the element's box, at most once per frame
+    let frame = 0;
+    const place = () => {
+      frame = 0;
+      const rect = element.getBoundingClientRect();
+      overlay.style.left = `${rect.left}px`;
+      overlay.style.top = `${rect.top}px`;
+      overlay.style.width = `${rect.width}px`;
+      overlay.style.height = `${rect.height}px`;
+      overlay.style.display = element.isConnected ? '' : 'none';
+    };
+    const schedule = () => {
+      if (!frame) frame = requestAnimationFrame(place);
+    };
+
+    place();
+    document.documentElement.appendChild(overlay);
+
+    // Scroll events don't bubble, so listen in the capture phase to catch scrolling containers too
+    window.addEventListener('scroll', schedule, { capture: true, passive: true });
+    window.addEventListener('resize', schedule, { passive: true });
+    const resizeObserver = typeof ResizeObserver === 'function' ? new ResizeObserver(schedule) : null;
+    if (resizeObserver) resizeObserver.observe(element);
+
+    const remove = () => {
+      if (!active.delete(remove)) return;
+      clearTimeout(timer);
+      if (frame) cancelAnimationFrame(frame);
+      window.removeEventListener('scroll', schedule, { capture: true });
+      window.removeEventListener('resize', schedule);
+      if (resizeObserver) resizeObserver.disconnect();
+      overlay.remove();
+    };
+    const timer = setTimeout(remove, durationMs);
+    active.add(remove);
+
+    const rect = element.getBoundingClientRect();
+    return {
+      highlighted: getUniqueSelector(element),
+      rect: { x: rect.x, y: rect.y, width: rect.width, height: rect.height },
+      durationMs,
+      label: label ?? null,
+    };
+  };
+
+  window.__tauriMcpHighlightClear = function() {
+    const cleared = active.size;
+    for (const remove of Array.from(active)) remove();
+    return { cleared };
+  };
+})();
@@ -1,46 +1,857 @@
-// UI interaction script for click, type, scroll operations
+// UI interaction script for click, hover, touch gestures, type, clear, scroll, key press, upload, and sequence operations
 window.__tauriMcpInteract = function(args) {
   'use strict';
 
-  const { action, selector, x, y, text, scrollX, scrollY } = args;
+  const { query, describe, containsDeep, elementFromPoint, viewportRect } = window.__tauriMcpSelector;
 
-  // Find target element
-  let element = null;
-  if (selector) {
-    element = document.querySelector(selector);
-    if (!element) {
-      throw new Error(`Element not found: ${selector}`);
+  const MODIFIERS = {
+    ctrl: { key: 'Control', code: 'ControlLeft', flag: 'ctrlKey' },
+    control: { key: 'Control', code: 'ControlLeft', flag: 'ctrlKey' },
+    shift: { key: 'Shift', code: 'ShiftLeft', flag: 'shiftKey' },
+    alt: { key: 'Alt', code: 'AltLeft', flag: 'altKey' },
+    option: { key: 'Alt', code: 'AltLeft', flag: 'altKey' },
+    meta: { key: 'Meta', code: 'MetaLeft', flag: 'metaKey' },
+    cmd: { key: 'Meta', code: 'MetaLeft', flag: 'metaKey' },
+    command: { key: 'Meta', code: 'MetaLeft', flag: 'metaKey' },
+  };
+
+  const NAMED_KEYS = {
+    enter: { key: 'Enter', code: 'Enter' },
+    tab: { key: 'Tab', code: 'Tab' },
+    escape: { key: 'Escape', code: 'Escape' },
+    esc: { key: 'Escape', code: 'Escape' },
+    backspace: { key: 'Backspace', code: 'Backspace' },
+    delete: { key: 'Delete', code: 'Delete' },
+    del: { key: 'Delete', code: 'Delete' },
+    space: { key: ' ', code: 'Space' },
+    arrowup: { key: 'ArrowUp', code: 'ArrowUp' },
+    up: { key: 'ArrowUp', code: 'ArrowUp' },
+    arrowdown: { key: 'ArrowDown', code: 'ArrowDown' },
+    down: { key: 'ArrowDown', code: 'ArrowDown' },
+    arrowleft: { key: 'ArrowLeft', code: 'ArrowLeft' },
+    left: { key: 'ArrowLeft', code: 'ArrowLeft' },
+    arrowright: { key: 'ArrowRight', code: 'ArrowRight' },
+    right: { key: 'ArrowRight', code: 'ArrowRight' },
+    home: { key: 'Home', code: 'Home' },
+    end: { key: 'End', code: 'End' },
+    pageup: { key: 'PageUp', code: 'PageUp' },
+    pagedown: { key: 'PageDown', code: 'PageDown' },
+    insert: { key: 'Insert', code: 'Insert' },
+    numpadadd: { key: '+', code: 'NumpadAdd' },
+    numpadsubtract: { key: '-', code: 'NumpadSubtract' },
+    numpadmultiply: { key: '*', code: 'NumpadMultiply' },
+    numpaddivide: { key: '/', code: 'NumpadDivide' },
+    numpaddecimal: { key: '.', code: 'NumpadDecimal' },
+    numpadenter: { key: 'Enter', code: 'NumpadEnter' },
+  };
+  for (let digit = 0; digit <= 9; digit++) {
+    NAMED_KEYS[`numpad${digit}`] = { key: String(digit), code: `Numpad${digit}` };
+  }
+
+  const PUNCTUATION_CODES = {
+    ' ': 'Space', '-': 'Minus', '=': 'Equal', '[': 'BracketLeft', ']': 'BracketRight',
+    '\\': 'Backslash', ';': 'Semicolon', "'": 'Quote', ',': 'Comma', '.': 'Period',
+    '/': 'Slash', '`': 'Backquote',
+  };
+
+  // Characters typed with shift on a US layout, by the key they're on
+  const SHIFTED_CODES = {
+    '!': 'Digit1', '@': 'Digit2', '#': 'Digit3', '$': 'Digit4', '%': 'Digit5', '^': 'Digit6',
+    '&': 'Digit7', '*': 'Digit8', '(': 'Digit9', ')': 'Digit0', '_': 'Minus', '+': 'Equal',
+    '{': 'BracketLeft', '}': 'BracketRight', '|': 'Backslash', ':': 'Semicolon', '"': 'Quote',
+    '<': 'Comma', '>': 'Period', '?': 'Slash', '~': 'Backquote',
+  };
+
+  // Legacy keyCode values by code, for keys whose keyCode doesn't follow from the code's name
+  const KEY_CODES = {
+    Backspace: 8, Tab: 9, Enter: 13, NumpadEnter: 13, ShiftLeft: 16, ControlLeft: 17, AltLeft: 18,
+    Escape: 27, Space: 32, PageUp: 33, PageDown: 34, End: 35, Home: 36, ArrowLeft: 37, ArrowUp: 38,
+    ArrowRight: 39, ArrowDown: 40, Insert: 45, Delete: 46, MetaLeft: 91, NumpadMultiply: 106,
+    NumpadAdd: 107, NumpadSubtract: 109, NumpadDecimal: 110, NumpadDivide: 111, Semicolon: 186,
+    Equal: 187, Comma: 188, Minus: 189, Period: 190, Slash: 191, Backquote: 192, BracketLeft: 219,
+    Backslash: 220, BracketRight: 221, Quote: 222,
+  };
+
+  // How long a tap holds, and a long press by default
+  const TAP_HOLD_MS = 50;
+  const DEFAULT_LONGPRESS_HOLD_MS = 800;
+
+  // Touch pointers get their own IDs, apart from the mouse's 1
+  const TOUCH_POINTER_ID_BASE = 2;
+
+  // Most repetitions a single press allows
+  const MAX_REPEAT = 100;
+
+  // ARIA roles whose state lives in aria-checked
+  const TOGGLE_ROLES = ['checkbox', 'switch', 'radio', 'menuitemcheckbox', 'menuitemradio'];
+
+  // Selector targets are waited for unless the request turns it off
+  const DEFAULT_WAIT_TIMEOUT_MS = 2000;
+  const WAIT_POLL_INTERVAL_MS = 50;
+
+  // What each action waits for on top of the element existing. Actions that work on hidden
+  // elements (like styled file inputs and checkboxes) only wait for it to exist.
+  const READINESS_CHECKS = {
+    click: ['visible', 'notCovered'],
+    double_click: ['visible', 'notCovered'],
+    dblclick: ['visible', 'notCovered'],
+    rightclick: ['visible', 'notCovered'],
+    hover: ['visible', 'notCovered'],
+    tap: ['visible', 'notCovered'],
+    longpress: ['visible', 'notCovered'],
+    swipe: ['visible'],
+    pinch: ['visible'],
+    type: ['visible'],
+    clear: ['visible'],
+    scroll: ['visible'],
+    set_value: ['visible'],
+    setValue: ['visible'],
+    select: ['visible'],
+    press: ['visible'],
+    keyboard_sequence: ['visible'],
+    focus: ['visible'],
+  };
+
+  // Scrolls are done when scrollend fires, or when the position stops changing for a few polls
+  const SCROLL_POLL_INTERVAL_MS = 16;
+  const SCROLL_STABLE_POLLS = 5;
+  const SCROLL_SETTLE_MS = 3000;
+
+  if (args.action === 'sequence') {
+    return doSequence(args.actions);
+  }
+  return runAction(args);
+
+  // Run actions in order within this one evaluation, stopping at the first failure
+  async function doSequence(actions) {
+    if (!Array.isArray(actions) || actions.length === 0) {
+      throw new Error("Missing 'actions' argument for sequence action. Provide actions like [{ action: 'click', selector: '#name' }].");
+    }
+
+    const results = [];
+    for (const [step, stepArgs] of actions.entries()) {
+      const { action, delayMs } = stepArgs;
+      try {
+        if (delayMs > 0) {
+          await new Promise((resolve) => setTimeout(resolve, delayMs));
+        }
+        // Each step looks its element up again, so re-renders between steps don't leave it detached
+        const result = await runAction(stepArgs);
+        results.push({ step, action, success: true, result });
+      } catch (error) {
+        results.push({ step, action, success: false, error: error.message });
+        return {
+          success: false,
+          steps: results,
+          message: `Step ${step} (${action}) failed, ${step} of ${actions.length} step(s) done: ${error.message}`
+        };
+      }
+    }
+
+    return {
+      success: true,
+      steps: results,
+      message: `Ran ${actions.length} action(s)`
+    };
+  }
+
+  // Find the target element and run a single action, once more if the element was replaced
+  // while acting on it
+  async function runAction(actionArgs) {
+    const { action, selector, x: givenX, y: givenY, coordinateSpace, origin, text, scrollX, scrollY, deltaX, deltaY, intoView, block, inline, behavior, value, checked, label, index, keys, key, modifiers, repeat, from, to, steps, durationMs, holdMs, center, startDistance, endDistance, files, waitForSelector, waitTimeoutMs, pierceShadow, native, preventScroll } = actionArgs;
+    const queryOptions = { pierceShadow: pierceShadow === true };
+    const coordinates = toViewport(givenX, givenY, coordinateSpace, origin, queryOptions);
+    const { x, y } = coordinates ? { x: coordinates.viewportX, y: coordinates.viewportY } : {};
+
+    // Find target element, waiting for a selector to match a ready element unless told not to
+    const locate = async () => {
+      if (selector && waitForSelector !== false) {
+        return waitForElement(selector, queryOptions, action, x, y, waitTimeoutMs ?? DEFAULT_WAIT_TIMEOUT_MS);
+      }
+      if (selector) {
+        const el = query(selector, document, queryOptions);
+        if (!el) {
+          throw new Error(`Element not found: ${describe(selector)}`);
+        }
+        return { element: el, wait: null };
+      }
+      if (x !== undefined && y !== undefined) {
+        const el = elementFromPoint(x, y);
+        if (!el) {
+          throw new Error(`No element at coordinates (${x}, ${y})`);
+        }
+        return { element: el, wait: null };
+      }
+      return { element: null, wait: null };
+    };
+
+    let { element, wait } = await locate();
+    if (native === true) {
+      const aim = withCoordinates(aimNative(element));
+      return wait ? { ...aim, wait: { ...wait, retried: false } } : aim;
+    }
+
+    let result;
+    let retried = false;
+    try {
+      result = await perform(element);
+    } catch (error) {
+      // Frameworks re-render by swapping nodes, so look the selector up again if ours is gone
+      if (!selector || !element || element.isConnected) throw error;
+      ({ element, wait } = await locate());
+      retried = true;
+      result = await perform(element);
+    }
+
+    result = withCoordinates(result);
+    if (wait) return { ...result, wait: { ...wait, retried } };
+    return retried ? { ...result, retried } : result;
+
+    // Report how given coordinates were converted, so offsets are easy to debug
+    function withCoordinates(actionResult) {
+      return coordinates ? { ...actionResult, coordinates } : actionResult;
+    }
+
+    // With native: true the plugin sends real OS input, so only find where it should go, and
+    // listen for the event it should cause so the plugin can tell whether it reached the page
+    function aimNative(el) {
+      switch (action) {
+        case 'click': {
+          const point = nativePoint(el, x, y, 'click');
+          listenForNative(el, 'click');
+          return { point, target: getElementDescription(el), devicePixelRatio: window.devicePixelRatio };
+        }
+
+        case 'type':
+          if (!el) {
+            throw new Error("No element specified for type. Provide 'selector'.");
+          }
+          // The OS types into whatever has focus
+          el.focus();
+          listenForNative(el, 'keydown');
+          return { point: null, target: getElementDescription(el), devicePixelRatio: window.devicePixelRatio };
+
+        case 'scroll': {
+          // The wheel scrolls whatever is under the cursor
+          const point = el
+            ? nativePoint(el, undefined, undefined, 'scroll')
+            : { x: window.innerWidth / 2, y: window.innerHeight / 2 };
+          listenForNative(el || window, 'wheel');
+          return { point, target: el ? getElementDescription(el) : 'the page', devicePixelRatio: window.devicePixelRatio };
+        }
+
+        default:
+          throw new Error(`Native input works with 'click', 'type', and 'scroll', not '${action}'.`);
+      }
+    }
+
+    function perform(el) {
+      switch (action) {
+        case 'click':
+          return doClick(el, x, y);
+
+        case 'double_click':
+        case 'dblclick':
+          return doDoubleClick(el, x, y);
+
+        case 'rightclick':
+          return doRightClick(el, x, y);
+
+        case 'hover':
+          return doHover(el, x, y);
+
+        case 'drag':
+          return doDrag(from, to, steps ?? 10, durationMs ?? 300, queryOptions);
+
+        case 'tap':
+          return doTap(el, x, y);
+
+        case 'longpress':
+          return doLongPress(el, x, y, holdMs ?? DEFAULT_LONGPRESS_HOLD_MS);
+
+        case 'swipe':
+          return doSwipe(from, to, steps ?? 10, durationMs ?? 300, queryOptions);
+
+        case 'pinch':
+          return doPinch(el, center, startDistance, endDistance, steps ?? 10, durationMs ?? 300, queryOptions);
+
+        case 'type':
+          return doType(el, text);
+
+        case 'clear':
+          return doClear(el);
+
+        case 'scroll':
+          return doScroll(el, deltaX ?? scrollX, deltaY ?? scrollY, { to, intoView, block, inline, behavior, queryOptions });
+
+        case 'set_value':
+        case 'setValue':
+          return doSetValue(el, value);
+
+        case 'select':
+          return doSelect(el, value, label, index);
+
+        case 'set_checked':
+        case 'setChecked':
+          return doSetChecked(el, checked);
+
+        case 'upload':
+          return doUpload(el, files);
+
+        case 'keyboard_sequence':
+          return doKeyboardSequence(el, keys);
+
+        case 'press':
+          return doPress(el, key, modifiers, repeat);
+
+        case 'focus':
+          return doFocus(el, preventScroll);
+
+        case 'blur':
+          return doBlur(el);
+
+        default:
+          throw new Error(`Unknown action: ${action}. Use 'click', 'dblclick', 'rightclick', 'hover', 'drag', 'tap', 'longpress', 'swipe', 'pinch', 'type', 'clear', 'scroll', 'set_value', 'set_checked', 'select', 'upload', 'press', 'keyboard_sequence', 'focus', 'blur', or 'sequence'.`);
+      }
+    }
+  }
+
+  // Convert x and y to the CSS viewport coordinates events use. Device pixels (like a
+  // screenshot's) are divided by devicePixelRatio, page coordinates are offset by the scroll
+  // position, and with an origin they're offsets from that element's top left corner.
+  function toViewport(givenX, givenY, space = 'css-viewport', origin, queryOptions) {
+    if (givenX === undefined || givenY === undefined) return null;
+
+    const devicePixelRatio = window.devicePixelRatio || 1;
+    const scale = space === 'device' ? devicePixelRatio : 1;
+    let left = 0;
+    let top = 0;
+    let originDescription = null;
+    if (origin) {
+      const originEl = query(origin, document, queryOptions);
+      if (!originEl) {
+        throw new Error(`Origin element not found: ${describe(origin)}`);
+      }
+      const rect = viewportRect(originEl);
+      left = rect.left;
+      top = rect.top;
+      originDescription = getElementDescription(originEl);
+    } else if (space === 'css-page') {
+      left = -window.scrollX;
+      top = -window.scrollY;
+    }
+
+    return {
+      x: givenX,
+      y: givenY,
+      space,
+      origin: originDescription,
+      devicePixelRatio,
+      viewportX: left + givenX / scale,
+      viewportY: top + givenY / scale,
+    };
+  }
+
+  // Poll until the selector matches an element that passes the action's readiness checks
+  async function waitForElement(selector, queryOptions, action, clientX, clientY, timeoutMs) {
+    const wanted = READINESS_CHECKS[action] || [];
+    const start = Date.now();
+    for (;;) {
+      const el = query(selector, document, queryOptions);
+      const checks = { exists: el !== null };
+      for (const name of wanted) {
+        checks[name] = el !== null && readinessCheck(el, name, clientX, clientY);
+      }
+
+      const waitedMs = Date.now() - start;
+      // null means the check couldn't run (like the covering check for an off-screen element)
+      if (Object.values(checks).every((passed) => passed !== false)) {
+        return { element: el, wait: { waitedMs, checks } };
+      }
+      if (waitedMs >= timeoutMs) {
+        if (!el) {
+          throw new Error(`Element not found: ${describe(selector)} (waited ${waitedMs}ms)`);
+        }
+        const failed = Object.keys(checks).filter((name) => checks[name] === false);
+        const cover = checks.notCovered === false ? coveringElement(el, clientX, clientY) : null;
+        throw new Error(`Element not ready after ${waitedMs}ms: ${getElementDescription(el)} failed ${failed.join(', ')}${cover ? ` (covered by ${getElementDescription(cover)})` : ''}. Pass waitForSelector: false to act on it anyway.`);
+      }
+      await new Promise((resolve) => setTimeout(resolve, WAIT_POLL_INTERVAL_MS));
+    }
+  }
+
+  // true if the element passes the check, false if not, null if it can't be checked
+  function readinessCheck(el, name, clientX, clientY) {
+    if (name === 'visible') {
+      const rect = el.getBoundingClientRect();
+      const style = window.getComputedStyle(el);
+      return rect.width > 0 && rect.height > 0 && style.display !== 'none' && style.visibility !== 'hidden'
+        && (typeof el.checkVisibility !== 'function' || el.checkVisibility());
+    }
+    if (name === 'notCovered') {
+      if (!inViewport(actionPoint(el, clientX, clientY))) {
+        return null;
+      }
+      return coveringElement(el, clientX, clientY) === null;
+    }
+    return true;
+  }
+
+  // The element a real pointer would hit instead of this one, or null if it would hit this one
+  function coveringElement(el, clientX, clientY) {
+    const point = actionPoint(el, clientX, clientY);
+    const hit = elementFromPoint(point.x, point.y);
+    return hit === null || containsDeep(el, hit) ? null : hit;
+  }
+
+  function inViewport(point) {
+    return point.x >= 0 && point.y >= 0 && point.x < window.innerWidth && point.y < window.innerHeight;
+  }
+
+  // Where native pointer input aims. Unlike DOM events, OS input goes to whatever is on top at
+  // the point, so the element is scrolled into view and must not be covered.
+  function nativePoint(el, clientX, clientY, actionName) {
+    let point = pointerTarget(el, clientX, clientY, actionName);
+    if (!inViewport(point)) {
+      el.scrollIntoView({ block: 'center', inline: 'center', behavior: 'instant' });
+      point = pointerTarget(el, clientX, clientY, actionName);
+      if (!inViewport(point)) {
+        throw new Error(`Point (${Math.round(point.x)}, ${Math.round(point.y)}) on ${getElementDescription(el)} is outside the viewport, so native input can't reach it`);
+      }
     }
-  } else if (x !== undefined && y !== undefined) {
-    element = document.elementFromPoint(x, y);
-    if (!element) {
-      throw new Error(`No element at coordinates (${x}, ${y})`);
+    const cover = coveringElement(el, clientX, clientY);
+    if (cover) {
+      throw new Error(`${getElementDescription(el)} is covered by ${getElementDescription(cover)}, so native input would hit that instead`);
     }
+    return point;
   }
 
-  switch (action) {
-    case 'click':
-      return doClick(element, x, y, false);
+  // Remember whether the event arrives, for the plugin to read once it has sent the input
+  function listenForNative(target, type) {
+    const record = { type, received: false, trusted: null };
+    window.__tauriMcpNativeEvent = record;
+    target.addEventListener(type, (event) => {
+      record.received = true;
+      record.trusted = event.isTrusted;
+    }, { once: true, capture: true });
+  }
+
+  // Where pointer actions aim: the given point, or the element's center, in the top-level viewport
+  // even for elements in frames
+  function actionPoint(el, clientX, clientY) {
+    const rect = viewportRect(el);
+    return {
+      x: clientX ?? (rect.left + rect.width / 2),
+      y: clientY ?? (rect.top + rect.height / 2),
+    };
+  }
 
-    case 'double_click':
-      return doClick(element, x, y, true);
+  function doClick(el, clientX, clientY) {
+    const point = pointerTarget(el, clientX, clientY, 'click');
 
-    case 'type':
-      return doType(element, text);
+    movePointer(el, point);
+    pressButton(el, point, 0, 1);
 
-    case 'scroll':
-      return doScroll(element, scrollX, scrollY);
+    // Focus if focusable
+    if (typeof el.focus === 'function') {
+      el.focus();
+    }
 
-    default:
-      throw new Error(`Unknown action: ${action}. Use 'click', 'double_click', 'type', or 'scroll'.`);
+    return { success: true, message: `Clicked ${getElementDescription(el)}`, ...hitReport(el, point) };
   }
 
-  function doClick(el, clientX, clientY, isDouble) {
+  function doDoubleClick(el, clientX, clientY) {
+    const point = pointerTarget(el, clientX, clientY, 'dblclick');
+
+    // Real double-clicks send two clicks first, with detail counting up
+    movePointer(el, point);
+    pressButton(el, point, 0, 1);
+    pressButton(el, point, 0, 2);
+    el.dispatchEvent(new MouseEvent('dblclick', mouseInit(point, 0, 0, 2)));
+
+    if (typeof el.focus === 'function') {
+      el.focus();
+    }
+
+    return { success: true, message: `Double-clicked ${getElementDescription(el)}`, ...hitReport(el, point) };
+  }
+
+  function doRightClick(el, clientX, clientY) {
+    const point = pointerTarget(el, clientX, clientY, 'rightclick');
+
+    movePointer(el, point);
+    dispatchPointer(el, 'pointerdown', mouseInit(point, 2, 2, 1));
+    el.dispatchEvent(new MouseEvent('mousedown', mouseInit(point, 2, 2, 1)));
+    const opened = el.dispatchEvent(new MouseEvent('contextmenu', mouseInit(point, 2, 2, 1)));
+    dispatchPointer(el, 'pointerup', mouseInit(point, 2, 0, 1));
+    el.dispatchEvent(new MouseEvent('mouseup', mouseInit(point, 2, 0, 1)));
+
+    return {
+      success: true,
+      message: `Right-clicked ${getElementDescription(el)}`,
+      // True if the page called preventDefault(), usually to show its own menu
+      defaultPrevented: !opened,
+      ...hitReport(el, point)
+    };
+  }
+
+  function doHover(el, clientX, clientY) {
+    const point = pointerTarget(el, clientX, clientY, 'hover');
+
+    movePointer(el, point);
+
+    return { success: true, message: `Hovered ${getElementDescription(el)}`, ...hitReport(el, point) };
+  }
+
+  // Press at `from`, move through interpolated points, and release at `to`. Draggable sources
+  // get the HTML5 drag events instead of moves, like in a browser.
+  async function doDrag(fromSpec, toSpec, stepCount, duration, queryOptions) {
+    const start = resolveDragPoint(fromSpec, 'from', queryOptions);
+    const end = resolveDragPoint(toSpec, 'to', queryOptions);
+    const source = start.element;
+    if (!source) {
+      throw new Error(`No element at the drag start (${start.x}, ${start.y})`);
+    }
+
+    movePointer(source, start);
+    dispatchPointer(source, 'pointerdown', mouseInit(start, 0, 1, 1));
+    source.dispatchEvent(new MouseEvent('mousedown', mouseInit(start, 0, 1, 1)));
+
+    const draggable = findDraggable(source);
+    let dataTransfer = null;
+    let dataTransferSet = false;
+    if (draggable) {
+      dataTransfer = createDataTransfer();
+      const started = draggable.dispatchEvent(dragEvent('dragstart', start, dataTransfer));
+      dataTransferSet = dataTransfer !== null && dataTransfer.types.length > 0;
+      if (!started) {
+        // Canceling dragstart cancels the drag, so moves go out as plain pointer events
+        dataTransfer = null;
+      }
+    }
+
+    let dragOver = null;
+    let dropAllowed = false;
+    for (let i = 1; i <= stepCount; i++) {
+      await new Promise((resolve) => setTimeout(resolve, duration / stepCount));
+      const point = {
+        x: start.x + (end.x - start.x) * (i / stepCount),
+        y: start.y + (end.y - start.y) * (i / stepCount),
+      };
+      const hit = elementFromPoint(point.x, point.y) || document.documentElement;
+
+      if (dataTransfer) {
+        draggable.dispatchEvent(dragEvent('drag', point, dataTransfer));
+        if (hit !== dragOver) {
+          hit.dispatchEvent(dragEvent('dragenter', point, dataTransfer));
+          if (dragOver) dragOver.dispatchEvent(dragEvent('dragleave', point, dataTransfer));
+          dragOver = hit;
+        }
+        // Drop targets cancel dragover to accept the drop
+        dropAllowed = !hit.dispatchEvent(dragEvent('dragover', point, dataTransfer));
+      } else {
+        movePointer(hit, point, 1);
+      }
+    }
+
+    const endHit = elementFromPoint(end.x, end.y) || document.documentElement;
+    let dropped = false;
+    if (dataTransfer) {
+      if (dropAllowed && dragOver) {
+        dragOver.dispatchEvent(dragEvent('drop', end, dataTransfer));
+        dropped = true;
+      } else if (dragOver) {
+        dragOver.dispatchEvent(dragEvent('dragleave', end, dataTransfer));
+      }
+      draggable.dispatchEvent(dragEvent('dragend', end, dataTransfer));
+    } else {
+      dispatchPointer(endHit, 'pointerup', mouseInit(end, 0, 0, 1));
+      endHit.dispatchEvent(new MouseEvent('mouseup', mouseInit(end, 0, 0, 1)));
+    }
+
+    return {
+      success: true,
+      message: `Dragged ${getElementDescription(source)} to (${Math.round(end.x)}, ${Math.round(end.y)})`,
+      from: { x: start.x, y: start.y },
+      to: { x: end.x, y: end.y },
+      steps: stepCount,
+      html5Drag: dataTransfer !== null,
+      dataTransferSet,
+      dataTypes: dataTransfer ? Array.from(dataTransfer.types) : [],
+      dropped,
+      ...hitReport(endHit, end),
+    };
+  }
+
+  // A drag endpoint is a selector (its center) or { x, y }
+  function resolveDragPoint(spec, name, queryOptions) {
+    if (spec && typeof spec.x === 'number' && typeof spec.y === 'number') {
+      return { x: spec.x, y: spec.y, element: elementFromPoint(spec.x, spec.y) };
+    }
+    if (typeof spec === 'string' || (spec && typeof spec === 'object')) {
+      const el = query(spec, document, queryOptions);
+      if (!el) {
+        throw new Error(`Element not found for '${name}': ${describe(spec)}`);
+      }
+      const point = pointerTarget(el, undefined, undefined, 'drag');
+      return { ...point, element: el };
+    }
+    throw new Error(`'${name}' must be a selector or { x, y }.`);
+  }
+
+  // Links and images are draggable by default, other elements with draggable="true"
+  function findDraggable(el) {
+    for (let node = el; node && node.nodeType === Node.ELEMENT_NODE; node = node.parentElement) {
+      if (node.draggable) return node;
+    }
+    return null;
+  }
+
+  function createDataTransfer() {
+    try {
+      return new DataTransfer();
+    } catch {
+      return null;
+    }
+  }
+
+  function dragEvent(type, point, dataTransfer) {
+    const init = { ...mouseInit(point, 0, type === 'dragend' || type === 'drop' ? 0 : 1, 0), dataTransfer };
+    if (typeof DragEvent === 'function') {
+      return new DragEvent(type, init);
+    }
+    const event = new MouseEvent(type, init);
+    Object.defineProperty(event, 'dataTransfer', { value: dataTransfer });
+    return event;
+  }
+
+  // Touch down and up at one point, then the mouse events and click browsers send after a tap
+  // unless the page cancels the touch
+  async function doTap(el, clientX, clientY) {
+    const point = pointerTarget(el, clientX, clientY, 'tap');
+    const touch = touchDispatcher(el);
+    const finger = { id: 0, ...point };
+
+    touch.down([finger]);
+    await sleep(TAP_HOLD_MS);
+    touch.up([finger]);
+
+    const clicked = !touch.defaultPrevented;
+    if (clicked) {
+      el.dispatchEvent(new MouseEvent('mousedown', mouseInit(point, 0, 1, 1)));
+      el.dispatchEvent(new MouseEvent('mouseup', mouseInit(point, 0, 0, 1)));
+      el.dispatchEvent(new MouseEvent('click', mouseInit(point, 0, 0, 1)));
+      if (typeof el.focus === 'function') {
+        el.focus();
+      }
+    }
+
+    return touch.result({
+      success: true,
+      message: `Tapped ${getElementDescription(el)}`,
+      clicked,
+      ...hitReport(el, point),
+    });
+  }
+
+  async function doLongPress(el, clientX, clientY, hold) {
+    const point = pointerTarget(el, clientX, clientY, 'longpress');
+    const touch = touchDispatcher(el);
+    const finger = { id: 0, ...point };
+
+    touch.down([finger]);
+    await sleep(hold);
+    touch.up([finger]);
+
+    return touch.result({
+      success: true,
+      message: `Long-pressed ${getElementDescription(el)} for ${hold}ms`,
+      holdMs: hold,
+      ...hitReport(el, point),
+    });
+  }
+
+  // One finger from `from` to `to`, moving in steps
+  async function doSwipe(fromSpec, toSpec, stepCount, duration, queryOptions) {
+    const start = resolveDragPoint(fromSpec, 'from', queryOptions);
+    const end = resolveDragPoint(toSpec, 'to', queryOptions);
+    if (!start.element) {
+      throw new Error(`No element at the swipe start (${start.x}, ${start.y})`);
+    }
+    // Touches stay targeted at the element they started on
+    const touch = touchDispatcher(start.element);
+
+    touch.down([{ id: 0, x: start.x, y: start.y }]);
+    let point = start;
+    for (let i = 1; i <= stepCount; i++) {
+      await sleep(duration / stepCount);
+      point = {
+        x: start.x + (end.x - start.x) * (i / stepCount),
+        y: start.y + (end.y - start.y) * (i / stepCount),
+      };
+      touch.move([{ id: 0, ...point }]);
+    }
+    touch.up([{ id: 0, ...point }]);
+
+    return touch.result({
+      success: true,
+      message: `Swiped ${getElementDescription(start.element)} from (${Math.round(start.x)}, ${Math.round(start.y)}) to (${Math.round(end.x)}, ${Math.round(end.y)})`,
+      from: { x: start.x, y: start.y },
+      to: { x: end.x, y: end.y },
+      steps: stepCount,
+    });
+  }
+
+  // Two fingers on either side of the center, moving apart or together horizontally
+  async function doPinch(el, centerSpec, startDistance, endDistance, stepCount, duration, queryOptions) {
+    if (!(startDistance > 0) || !(endDistance > 0)) {
+      throw new Error("The 'pinch' action needs 'startDistance' and 'endDistance' greater than 0.");
+    }
+    const centerPoint = centerSpec != null
+      ? resolveDragPoint(centerSpec, 'center', queryOptions)
+      : { ...pointerTarget(el, undefined, undefined, 'pinch'), element: el };
+    const target = centerPoint.element;
+    if (!target) {
+      throw new Error(`No element at the pinch center (${centerPoint.x}, ${centerPoint.y})`);
+    }
+    const touch = touchDispatcher(target);
+    const fingers = (distance) => [
+      { id: 0, x: centerPoint.x - distance / 2, y: centerPoint.y },
+      { id: 1, x: centerPoint.x + distance / 2, y: centerPoint.y },
+    ];
+
+    touch.down(fingers(startDistance));
+    let distance = startDistance;
+    for (let i = 1; i <= stepCount; i++) {
+      await sleep(duration / stepCount);
+      distance = startDistance + (endDistance - startDistance) * (i / stepCount);
+      touch.move(fingers(distance));
+    }
+    touch.up(fingers(distance));
+
+    return touch.result({
+      success: true,
+      message: `Pinched ${getElementDescription(target)} from ${startDistance}px to ${endDistance}px apart`,
+      center: { x: centerPoint.x, y: centerPoint.y },
+      startDistance,
+      endDistance,
+      scale: endDistance / startDistance,
+      steps: stepCount,
+    });
+  }
+
+  // Send the pointer events (with pointerType "touch") and touch events of fingers touching the
+  // target. Webviews that can't construct Touch objects, like desktop WebKit, only get the
+  // pointer events.
+  function touchDispatcher(target) {
+    const touchEvents = canConstructTouch(target);
+    const active = new Map();
+    let prevented = false;
+
+    const dispatch = (event) => {
+      if (!target.dispatchEvent(event)) prevented = true;
+    };
+    const pointer = (type, finger, button, buttons) => {
+      if (typeof PointerEvent !== 'function') return;
+      const bubbles = type !== 'pointerenter' && type !== 'pointerleave';
+      dispatch(new PointerEvent(type, {
+        ...mouseInit(finger, button, buttons, 0),
+        bubbles,
+        cancelable: bubbles,
+        pointerId: TOUCH_POINTER_ID_BASE + finger.id,
+        pointerType: 'touch',
+        isPrimary: finger.id === 0,
+        width: 1,
+        height: 1,
+        pressure: buttons ? 0.5 : 0,
+      }));
+    };
+    const touchList = (fingers) => fingers.map((finger) => new Touch({
+      identifier: finger.id,
+      target,
+      clientX: finger.x,
+      clientY: finger.y,
+      screenX: window.screenX + finger.x,
+      screenY: window.screenY + finger.y,
+      pageX: finger.x + window.scrollX,
+      pageY: finger.y + window.scrollY,
+      radiusX: 1,
+      radiusY: 1,
+      force: 0.5,
+    }));
+    const touch = (type, changed) => {
+      if (!touchEvents) return;
+      const touches = touchList([...active.values()]);
+      dispatch(new TouchEvent(type, {
+        bubbles: true,
+        cancelable: true,
+        composed: true,
+        view: window,
+        touches,
+        targetTouches: touches,
+        changedTouches: touchList(changed),
+      }));
+    };
+
+    return {
+      get defaultPrevented() {
+        return prevented;
+      },
+      down(fingers) {
+        for (const finger of fingers) {
+          active.set(finger.id, finger);
+          pointer('pointerover', finger, 0, 1);
+          pointer('pointerenter', finger, 0, 1);
+          pointer('pointerdown', finger, 0, 1);
+        }
+        touch('touchstart', fingers);
+      },
+      move(fingers) {
+        for (const finger of fingers) {
+          active.set(finger.id, finger);
+          pointer('pointermove', finger, -1, 1);
+        }
+        touch('touchmove', fingers);
+      },
+      up(fingers) {
+        for (const finger of fingers) {
+          active.delete(finger.id);
+          pointer('pointerup', finger, 0, 0);
+          pointer('pointerout', finger, 0, 0);
+          pointer('pointerleave', finger, 0, 0);
+        }
+        touch('touchend', fingers);
+      },
+      // Add what was sent to a gesture's result
+      result(gesture) {
+        const result = { ...gesture, touchEvents, defaultPrevented: prevented };
+        if (!touchEvents) {
+          result.note = "This webview can't construct TouchEvent, so only pointer events with pointerType 'touch' were sent";
+        }
+        return result;
+      },
+    };
+  }
+
+  function canConstructTouch(target) {
+    try {
+      new TouchEvent('touchstart', { touches: [new Touch({ identifier: 0, target })] });
+      return true;
+    } catch {
+      return false;
+    }
+  }
+
+  function sleep(ms) {
+    return new Promise((resolve) => setTimeout(resolve, ms));
+  }
+
+  // Check that the element can receive pointer events and get the point to use
+  function pointerTarget(el, clientX, clientY, actionName) {
     if (!el) {
-      throw new Error("No element specified for click. Provide 'selector' or 'x'/'y' coordinates.");
+      throw new Error(`No element specified for ${actionName}. Provide 'selector' or 'x'/'y' coordinates.`);
     }
 
-    // Check if element is visible and clickable
     const rect = el.getBoundingClientRect();
     if (rect.width === 0 || rect.height === 0) {
       throw new Error(`Element is not visible (zero size): ${getElementDescription(el)}`);
@@ -55,34 +866,93 @@ window.__tauriMcpInteract = function(args) {
       throw new Error(`Element has pointer-events: none: ${getElementDescription(el)}`);
     }
 
-    // Calculate click position
-    const clickX = clientX ?? (rect.left + rect.width / 2);
-    const clickY = clientY ?? (rect.top + rect.height / 2);
+    return actionPoint(el, clientX, clientY);
+  }
 
-    // Create and dispatch events
-    const eventInit = {
+  function mouseInit(point, button, buttons, detail) {
+    return {
       bubbles: true,
       cancelable: true,
-      clientX: clickX,
-      clientY: clickY,
-      button: 0,
+      composed: true,
+      view: window,
+      clientX: point.x,
+      clientY: point.y,
+      screenX: window.screenX + point.x,
+      screenY: window.screenY + point.y,
+      button,
+      buttons,
+      detail,
     };
+  }
+
+  function dispatchPointer(el, type, init) {
+    if (typeof PointerEvent !== 'function') return true;
+    return el.dispatchEvent(new PointerEvent(type, { ...init, pointerId: 1, pointerType: 'mouse', isPrimary: true }));
+  }
+
+  function pressButton(el, point, button, detail) {
+    const buttons = button === 2 ? 2 : 1;
+    dispatchPointer(el, 'pointerdown', mouseInit(point, button, buttons, detail));
+    el.dispatchEvent(new MouseEvent('mousedown', mouseInit(point, button, buttons, detail)));
+    dispatchPointer(el, 'pointerup', mouseInit(point, button, 0, detail));
+    el.dispatchEvent(new MouseEvent('mouseup', mouseInit(point, button, 0, detail)));
+    el.dispatchEvent(new MouseEvent('click', mouseInit(point, button, 0, detail)));
+  }
+
+  // Send the over/out/enter/leave events a real pointer would, remembering where the
+  // pointer is between calls so the previously hovered element gets its leave events.
+  // Synthetic events don't trigger CSS :hover.
+  function movePointer(el, point, buttons = 0) {
+    const previous = window.__tauriMcpPointerElement;
+    const init = mouseInit(point, 0, buttons, 0);
+    const nonBubbling = { ...init, bubbles: false, cancelable: false };
 
-    el.dispatchEvent(new MouseEvent('mousedown', eventInit));
-    el.dispatchEvent(new MouseEvent('mouseup', eventInit));
-    el.dispatchEvent(new MouseEvent('click', eventInit));
+    if (previous !== el) {
+      if (previous && previous.isConnected) {
+        dispatchPointer(previous, 'pointerout', init);
+        previous.dispatchEvent(new MouseEvent('mouseout', { ...init, relatedTarget: el }));
+        for (const node of ancestorsOf(previous).filter((node) => !containsDeep(node, el))) {
+          dispatchPointer(node, 'pointerleave', nonBubbling);
+          node.dispatchEvent(new MouseEvent('mouseleave', { ...nonBubbling, relatedTarget: el }));
+        }
+      }
 
-    if (isDouble) {
-      el.dispatchEvent(new MouseEvent('dblclick', eventInit));
+      const from = previous && previous.isConnected ? previous : null;
+      dispatchPointer(el, 'pointerover', init);
+      el.dispatchEvent(new MouseEvent('mouseover', { ...init, relatedTarget: from }));
+      // Outermost first, like the browser
+      for (const node of ancestorsOf(el).filter((node) => !from || !containsDeep(node, from)).reverse()) {
+        dispatchPointer(node, 'pointerenter', nonBubbling);
+        node.dispatchEvent(new MouseEvent('mouseenter', { ...nonBubbling, relatedTarget: from }));
+      }
+      window.__tauriMcpPointerElement = el;
     }
 
-    // Focus if focusable
-    if (typeof el.focus === 'function') {
-      el.focus();
+    dispatchPointer(el, 'pointermove', init);
+    el.dispatchEvent(new MouseEvent('mousemove', init));
+  }
+
+  // The element itself and its ancestors, innermost first, with shadow hosts standing in for
+  // their shadow roots
+  function ancestorsOf(el) {
+    const nodes = [];
+    let node = el;
+    while (node && node.nodeType === Node.ELEMENT_NODE) {
+      nodes.push(node);
+      node = node.parentElement || node.parentNode?.host;
     }
+    return nodes;
+  }
 
-    const actionType = isDouble ? 'Double-clicked' : 'Clicked';
-    return { success: true, message: `${actionType} ${getElementDescription(el)}` };
+  // What a real pointer at this point would hit, which differs from the target when it's covered
+  function hitReport(el, point) {
+    const hit = elementFromPoint(point.x, point.y);
+    return {
+      x: point.x,
+      y: point.y,
+      target: hit ? { tag: hit.tagName.toLowerCase(), id: hit.id || null, classes: Array.from(hit.classList) } : null,
+      hitsTarget: hit !== null && containsDeep(el, hit),
+    };
   }
 
   function doType(el, inputText) {
@@ -123,7 +993,7 @@ window.__tauriMcpInteract = function(args) {
 
     if (isInput) {
       // Clear existing value and set new one
-      el.value = inputText;
+      setNativeValue(el, inputText);
       el.dispatchEvent(new Event('input', { bubbles: true }));
       el.dispatchEvent(new Event('change', { bubbles: true }));
     } else {
@@ -136,25 +1006,793 @@ window.__tauriMcpInteract = function(args) {
     return { success: true, message: `Typed "${truncated}${inputText.length > 20 ? '...' : ''}" into ${getElementDescription(el)}` };
   }
 
-  function doScroll(el, deltaX, deltaY) {
-    const target = el || document.documentElement;
+  // Empty a text field the way deleting its selected contents would, so frameworks see the edit
+  function doClear(el) {
+    if (!el) {
+      throw new Error("No element specified for clear. Provide 'selector'.");
+    }
+
+    const isInput = el.tagName === 'INPUT' || el.tagName === 'TEXTAREA';
+    if (!isInput && !el.isContentEditable) {
+      throw new Error(`Element does not accept text input: ${getElementDescription(el)}`);
+    }
+    if (el.tagName === 'INPUT') {
+      const nonTextTypes = ['checkbox', 'radio', 'file', 'submit', 'reset', 'button', 'image', 'hidden', 'range', 'color'];
+      if (nonTextTypes.includes(el.type)) {
+        throw new Error(`Input type '${el.type}' has no text to clear: ${getElementDescription(el)}`);
+      }
+    }
+    if (el.disabled) {
+      throw new Error(`Element is disabled: ${getElementDescription(el)}`);
+    }
+    if (el.readOnly) {
+      throw new Error(`Element is read-only: ${getElementDescription(el)}`);
+    }
+
+    const contents = () => (isInput ? el.value : el.textContent);
+    const previousLength = contents().length;
+    el.focus();
+    if (previousLength === 0) {
+      return { success: true, cleared: false, previousLength, message: `${getElementDescription(el)} was already empty` };
+    }
 
-    if (deltaX === undefined && deltaY === undefined) {
-      throw new Error("Missing 'scrollX' or 'scrollY' argument for scroll action.");
+    // Select everything, like Ctrl+A
+    if (isInput) {
+      el.select();
+    } else {
+      const range = document.createRange();
+      range.selectNodeContents(el);
+      const selection = window.getSelection();
+      selection.removeAllRanges();
+      selection.addRange(range);
     }
 
-    target.scrollBy({
-      left: deltaX || 0,
-      top: deltaY || 0,
-      behavior: 'smooth'
+    // Rich text editors cancel beforeinput and delete the contents themselves
+    const init = { inputType: 'deleteContentBackward', bubbles: true, composed: true };
+    const allowed = el.dispatchEvent(new InputEvent('beforeinput', { ...init, cancelable: true }));
+    if (allowed) {
+      if (isInput) {
+        setNativeValue(el, '');
+      } else {
+        window.getSelection().deleteFromDocument();
+      }
+      el.dispatchEvent(new InputEvent('input', init));
+      if (isInput) {
+        el.dispatchEvent(new Event('change', { bubbles: true }));
+      }
+    }
+
+    const remaining = contents();
+    if (remaining.length > 0) {
+      const why = allowed ? 'an input handler put text back' : "a beforeinput handler canceled the deletion and didn't delete it";
+      throw new Error(`${getElementDescription(el)} still has ${remaining.length} character(s) after clearing: ${why}`);
+    }
+
+    return { success: true, cleared: true, previousLength, message: `Cleared ${previousLength} character(s) from ${getElementDescription(el)}` };
+  }
+
+  async function doScroll(el, byX, byY, { to, intoView, block, inline, behavior, queryOptions }) {
+    const byDelta = byX != null || byY != null;
+    const modes = [byDelta, to != null, intoView != null].filter(Boolean).length;
+    if (modes !== 1) {
+      throw new Error("Provide one of 'deltaX'/'deltaY' (or 'scrollX'/'scrollY'), 'to', or 'intoView' for scroll action.");
+    }
+
+    // Without a selector, scroll the page
+    const container = el || document.scrollingElement || document.documentElement;
+    const isPage = !el || container === document.scrollingElement || container === document.documentElement;
+    const options = { behavior: behavior || 'smooth' };
+    const before = { top: container.scrollTop, left: container.scrollLeft };
+
+    let child = null;
+    let description;
+    if (intoView != null) {
+      child = query(intoView, el || document, queryOptions);
+      if (!child) {
+        throw new Error(`Element not found${el ? ` in ${getElementDescription(el)}` : ''}: ${describe(intoView)}`);
+      }
+      child.scrollIntoView({ ...options, block: block || 'start', inline: inline || 'nearest' });
+      description = `Scrolled ${getElementDescription(child)} into view`;
+    } else if (to != null) {
+      container.scrollTo({ ...options, top: to.top ?? before.top, left: to.left ?? before.left });
+      description = `Scrolled ${isPage ? 'page' : getElementDescription(container)} to (${to.left ?? before.left}, ${to.top ?? before.top})`;
+    } else {
+      container.scrollBy({ ...options, left: byX || 0, top: byY || 0 });
+      description = `Scrolled ${isPage ? 'page' : getElementDescription(container)} by (${byX || 0}, ${byY || 0})`;
+    }
+
+    await waitForScrollEnd(isPage ? window : container, () => {
+      const rect = child ? child.getBoundingClientRect() : null;
+      return [container.scrollTop, container.scrollLeft, rect?.top, rect?.left].join(',');
+    });
+
+    const moved = container.scrollTop !== before.top || container.scrollLeft !== before.left;
+    const style = window.getComputedStyle(isPage ? document.documentElement : container);
+    return {
+      success: true,
+      scrollTop: container.scrollTop,
+      scrollLeft: container.scrollLeft,
+      scrollHeight: container.scrollHeight,
+      scrollWidth: container.scrollWidth,
+      clientHeight: container.clientHeight,
+      clientWidth: container.clientWidth,
+      moved,
+      overflowX: style.overflowX,
+      overflowY: style.overflowY,
+      message: moved || child ? description : `${description}, but the position didn't change`
+    };
+  }
+
+  // Resolve when the scroll ends: on scrollend, or once the position holds still
+  function waitForScrollEnd(eventTarget, readPosition) {
+    return new Promise((resolve) => {
+      const start = Date.now();
+      let last = readPosition();
+      let stablePolls = 0;
+      let timer = null;
+
+      const finish = () => {
+        clearTimeout(timer);
+        eventTarget.removeEventListener('scrollend', finish);
+        resolve();
+      };
+      const poll = () => {
+        const current = readPosition();
+        stablePolls = current === last ? stablePolls + 1 : 0;
+        last = current;
+        if (stablePolls >= SCROLL_STABLE_POLLS || Date.now() - start >= SCROLL_SETTLE_MS) {
+          finish();
+        } else {
+          timer = setTimeout(poll, SCROLL_POLL_INTERVAL_MS);
+        }
+      };
+
+      eventTarget.addEventListener('scrollend', finish);
+      timer = setTimeout(poll, SCROLL_POLL_INTERVAL_MS);
+    });
+  }
+
+  function doSetValue(el, newValue) {
+    if (!el) {
+      throw new Error("No element specified for set_value. Provide 'selector'.");
+    }
+
+    if (newValue === undefined || newValue === null) {
+      throw new Error("Missing 'value' argument for set_value action.");
+    }
+
+    const isInput = el.tagName === 'INPUT' || el.tagName === 'TEXTAREA';
+    if (el.tagName === 'SELECT') {
+      throw new Error(`Use the 'select' action for select elements: ${getElementDescription(el)}`);
+    }
+    if (!isInput) {
+      throw new Error(`set_value only supports input and textarea elements: ${getElementDescription(el)}`);
+    }
+    if (el.type === 'checkbox' || el.type === 'radio') {
+      throw new Error(`Use 'click' to toggle ${el.type} inputs: ${getElementDescription(el)}`);
+    }
+    if (el.type === 'file') {
+      throw new Error(`File inputs can't be set from a script: ${getElementDescription(el)}`);
+    }
+
+    if (el.disabled) {
+      throw new Error(`Element is disabled: ${getElementDescription(el)}`);
+    }
+    if (el.readOnly) {
+      throw new Error(`Element is read-only: ${getElementDescription(el)}`);
+    }
+
+    if (el.type === 'range' || el.type === 'number') {
+      checkNumericValue(el, newValue);
+    }
+
+    el.focus();
+    setNativeValue(el, String(newValue));
+    el.dispatchEvent(new Event('input', { bubbles: true }));
+    el.dispatchEvent(new Event('change', { bubbles: true }));
+
+    // The browser may normalize the value (for example, "5.0" becomes "5")
+    return {
+      success: true,
+      value: el.value,
+      message: `Set ${getElementDescription(el)} to ${el.value}`
+    };
+  }
+
+  function checkNumericValue(el, newValue) {
+    if (newValue === '') {
+      throw new Error("Missing 'value' argument for set_value action.");
+    }
+
+    const numeric = Number(newValue);
+    if (!Number.isFinite(numeric)) {
+      throw new Error(`Value '${newValue}' is not a number.`);
+    }
+
+    // Range inputs default to [0, 100], number inputs are unbounded unless set
+    const min = el.min !== '' ? Number(el.min) : (el.type === 'range' ? 0 : -Infinity);
+    const max = el.max !== '' ? Number(el.max) : (el.type === 'range' ? 100 : Infinity);
+    if (numeric < min || numeric > max) {
+      throw new Error(`Value ${numeric} is outside the allowed range [${el.min || min}, ${el.max || max}]: ${getElementDescription(el)}`);
+    }
+
+    const step = el.step === 'any' ? null : Number(el.step || 1);
+    if (step && Number.isFinite(step) && step > 0) {
+      const base = Number.isFinite(min) ? min : 0;
+      const offset = (numeric - base) / step;
+      // Tolerate floating point noise like 0.30000000000000004
+      if (Math.abs(offset - Math.round(offset)) > 1e-9) {
+        throw new Error(`Value ${numeric} does not match step ${step} (base ${base}): ${getElementDescription(el)}`);
+      }
+    }
+  }
+
+  // Frameworks like React override `value` on the element to track changes, so assigning it
+  // directly looks like no change to them. The prototype's setter goes around that.
+  function setNativeValue(el, newValue) {
+    const proto = el.tagName === 'TEXTAREA'
+      ? window.HTMLTextAreaElement.prototype
+      : el.tagName === 'SELECT'
+        ? window.HTMLSelectElement.prototype
+        : window.HTMLInputElement.prototype;
+    const descriptor = Object.getOwnPropertyDescriptor(proto, 'value');
+    if (descriptor && descriptor.set) {
+      descriptor.set.call(el, newValue);
+    } else {
+      el.value = newValue;
+    }
+  }
+
+  async function doSetChecked(el, wanted) {
+    if (!el) {
+      throw new Error("No element specified for set_checked. Provide 'selector'.");
+    }
+    if (typeof wanted !== 'boolean') {
+      throw new Error("Missing 'checked' argument for set_checked action. Provide true or false.");
+    }
+
+    const toggle = toggleOf(el);
+    if (!toggle) {
+      throw new Error(`Not a toggleable element: ${getElementDescription(el)}. Use a checkbox, a radio, their label, or an element with role="checkbox" or "switch" and aria-checked.`);
+    }
+    if (toggle.disabled()) {
+      throw new Error(`Element is disabled: ${getElementDescription(el)}`);
+    }
+
+    const state = (isChecked) => (isChecked ? 'checked' : 'unchecked');
+    if (toggle.read() === wanted) {
+      return {
+        success: true,
+        changed: false,
+        checked: wanted,
+        message: `${getElementDescription(el)} is already ${state(wanted)}`
+      };
+    }
+    if (!wanted && toggle.kind === 'radio') {
+      throw new Error(`Radio buttons can't be unchecked by clicking, check another option in the group instead: ${getElementDescription(el)}`);
+    }
+
+    // Click like a user so framework handlers run, then give them a moment to update the state
+    let attempts = 0;
+    while (attempts < 2 && toggle.read() !== wanted) {
+      attempts++;
+      clickToggle(el);
+      await new Promise((resolve) => setTimeout(resolve, 0));
+    }
+
+    if (toggle.read() !== wanted) {
+      throw new Error(`State did not change after click: ${getElementDescription(el)} is still ${state(!wanted)} after ${attempts} clicks.`);
+    }
+    return {
+      success: true,
+      changed: true,
+      checked: wanted,
+      attempts,
+      message: `${wanted ? 'Checked' : 'Unchecked'} ${getElementDescription(el)}`
+    };
+  }
+
+  // How to read the checked state of a checkbox, radio, their label, or an ARIA toggle
+  function toggleOf(el) {
+    const input = el.tagName === 'LABEL' ? el.control : el;
+    if (input && input.tagName === 'INPUT' && (input.type === 'checkbox' || input.type === 'radio')) {
+      return { kind: input.type, read: () => input.checked, disabled: () => input.disabled };
+    }
+
+    const role = el.getAttribute('role');
+    if (TOGGLE_ROLES.includes(role) && el.hasAttribute('aria-checked')) {
+      return {
+        kind: role,
+        read: () => el.getAttribute('aria-checked') === 'true',
+        disabled: () => el.getAttribute('aria-disabled') === 'true',
+      };
+    }
+    return null;
+  }
+
+  function clickToggle(el) {
+    try {
+      doClick(el);
+    } catch (error) {
+      // Styled toggles often hide the native input, which still takes a click without a pointer
+      if (el.tagName !== 'INPUT') throw error;
+      el.click();
+    }
+  }
+
+  function doSelect(el, byValue, byLabel, byIndex) {
+    if (!el) {
+      throw new Error("No element specified for select. Provide 'selector'.");
+    }
+    if (el.tagName !== 'SELECT') {
+      throw new Error(`select only supports select elements, use set_value for inputs: ${getElementDescription(el)}`);
+    }
+    if (el.disabled) {
+      throw new Error(`Element is disabled: ${getElementDescription(el)}`);
+    }
+
+    const [key, wanted] = byValue !== undefined && byValue !== null
+      ? ['value', byValue]
+      : byLabel !== undefined && byLabel !== null
+        ? ['label', byLabel]
+        : ['index', byIndex];
+    if (wanted === undefined || wanted === null) {
+      throw new Error("Missing 'value', 'label', or 'index' argument for select action.");
+    }
+
+    const wantedList = Array.isArray(wanted) ? wanted : [wanted];
+    if (wantedList.length > 1 && !el.multiple) {
+      throw new Error(`Only multi-selects take several options: ${getElementDescription(el)}`);
+    }
+
+    const options = Array.from(el.options);
+    const matches = wantedList.map((item) => {
+      const option = options.find((opt, i) => {
+        if (key === 'value') return opt.value === String(item);
+        if (key === 'label') return opt.label.trim() === String(item).trim();
+        return i === Number(item);
+      });
+      if (!option) {
+        const available = options.map((opt) => JSON.stringify(opt.value)).join(', ');
+        throw new Error(`No option with ${key} ${JSON.stringify(item)} in ${getElementDescription(el)}. Available values: ${available}`);
+      }
+      if (option.disabled) {
+        throw new Error(`Option ${JSON.stringify(option.value)} is disabled: ${getElementDescription(el)}`);
+      }
+      return option;
+    });
+
+    el.focus();
+    if (el.multiple) {
+      for (const option of options) {
+        option.selected = matches.includes(option);
+      }
+    } else {
+      setNativeValue(el, matches[0].value);
+    }
+    el.dispatchEvent(new Event('input', { bubbles: true }));
+    el.dispatchEvent(new Event('change', { bubbles: true }));
+
+    const selected = options
+      .filter((option) => option.selected)
+      .map((option) => ({ value: option.value, label: option.label, index: option.index }));
+    return {
+      success: true,
+      value: el.multiple ? selected.map((option) => option.value) : el.value,
+      selected,
+      message: `Selected ${selected.map((option) => JSON.stringify(option.label)).join(', ')} in ${getElementDescription(el)}`
+    };
+  }
+
+  function doUpload(el, fileList) {
+    if (!el) {
+      throw new Error("No element specified for upload. Provide 'selector'.");
+    }
+    if (el.tagName !== 'INPUT' || el.type !== 'file') {
+      throw new Error(`upload only supports <input type="file"> elements: ${getElementDescription(el)}`);
+    }
+    if (el.disabled) {
+      throw new Error(`Element is disabled: ${getElementDescription(el)}`);
+    }
+    if (!Array.isArray(fileList) || fileList.length === 0) {
+      throw new Error("Missing 'files' argument for upload action.");
+    }
+    if (fileList.length > 1 && !el.multiple) {
+      throw new Error(`Only inputs with the multiple attribute take several files: ${getElementDescription(el)}`);
+    }
+
+    // Inputs only accept a FileList, and DataTransfer is the one way to build one
+    const dataTransfer = new DataTransfer();
+    for (const file of fileList) {
+      const binary = atob(file.contentsBase64);
+      const bytes = new Uint8Array(binary.length);
+      for (let i = 0; i < binary.length; i++) {
+        bytes[i] = binary.charCodeAt(i);
+      }
+      dataTransfer.items.add(new File([bytes], file.name, { type: file.mimeType || '' }));
+    }
+
+    el.files = dataTransfer.files;
+    el.dispatchEvent(new Event('input', { bubbles: true }));
+    el.dispatchEvent(new Event('change', { bubbles: true }));
+
+    const uploaded = Array.from(el.files).map((file) => ({
+      name: file.name,
+      size: file.size,
+      type: file.type,
+      lastModified: file.lastModified,
+    }));
+    return {
+      success: true,
+      files: uploaded,
+      message: `Uploaded ${uploaded.map((file) => JSON.stringify(file.name)).join(', ')} to ${getElementDescription(el)}`
+    };
+  }
+
+  function doKeyboardSequence(el, keyList) {
+    if (!Array.isArray(keyList) || keyList.length === 0) {
+      throw new Error("Missing 'keys' argument for keyboard_sequence action. Provide an array like ['ctrl', 'a'].");
+    }
+
+    // Without a selector, send keys to whatever has focus
+    const target = el || document.activeElement || document.body;
+    if (el && typeof el.focus === 'function') {
+      el.focus();
+    }
+
+    // Modifiers apply to the next non-modifier key, then are released
+    const held = { ctrlKey: false, shiftKey: false, altKey: false, metaKey: false };
+    const heldKeys = [];
+
+    for (const name of keyList) {
+      if (typeof name !== 'string' || name === '') {
+        throw new Error(`Invalid key: ${JSON.stringify(name)}. Keys must be non-empty strings.`);
+      }
+
+      const modifier = MODIFIERS[name.toLowerCase()];
+      if (modifier) {
+        held[modifier.flag] = true;
+        heldKeys.push(modifier);
+        dispatchKey(target, 'keydown', modifier, held);
+        continue;
+      }
+
+      const keyInfo = resolveKey(name);
+      // Uppercase letters and shifted symbols imply shift, like a real keyboard
+      const flags = { ...held, shiftKey: held.shiftKey || Boolean(keyInfo.shift) };
+
+      const allowed = dispatchKey(target, 'keydown', keyInfo, flags);
+      if (keyInfo.key.length === 1 || keyInfo.key === 'Enter') {
+        dispatchKey(target, 'keypress', keyInfo, flags);
+      }
+      if (allowed) {
+        applyDefaultAction(target, keyInfo.key, flags);
+      }
+      dispatchKey(target, 'keyup', keyInfo, flags);
+
+      releaseModifiers(target, held, heldKeys);
+    }
+
+    // Trailing modifiers with no key after them
+    releaseModifiers(target, held, heldKeys);
+
+    return {
+      success: true,
+      message: `Sent ${keyList.length} key(s) to ${getElementDescription(target)}`
+    };
+  }
+
+  function releaseModifiers(target, held, heldKeys) {
+    while (heldKeys.length > 0) {
+      const modifier = heldKeys.pop();
+      held[modifier.flag] = false;
+      dispatchKey(target, 'keyup', modifier, held);
+    }
+  }
+
+  // The focused element, looking inside shadow roots, or null if nothing is
+  function deepActiveElement() {
+    let active = document.activeElement;
+    while (active && active.shadowRoot && active.shadowRoot.activeElement) {
+      active = active.shadowRoot.activeElement;
+    }
+    return active === document.body || active === document.documentElement ? null : active;
+  }
+
+  // Why an element didn't take focus, after focus() left `active` focused instead
+  function focusFailureReason(el, active) {
+    if (el.disabled) return 'it is disabled';
+    if (el.closest('[inert]')) return 'it is inert';
+    if (el.tabIndex < 0 && !el.hasAttribute('tabindex')) {
+      return 'it is not focusable. Use a focusable element, or give it a tabindex';
+    }
+    const style = window.getComputedStyle(el);
+    if (style.visibility === 'hidden' || style.display === 'none') return 'it is hidden';
+    return active
+      ? `a focus handler moved focus to ${getElementDescription(active)}`
+      : 'a focus handler moved focus away';
+  }
+
+  function doFocus(el, preventScroll) {
+    if (!el) {
+      throw new Error("No element specified for focus. Provide 'selector'.");
+    }
+
+    const previous = deepActiveElement();
+    el.focus({ preventScroll: preventScroll === true });
+
+    // Focus delegated into the element's shadow root counts
+    const active = deepActiveElement();
+    if (!active || !containsDeep(el, active)) {
+      throw new Error(`Focus didn't stick on ${getElementDescription(el)}: ${focusFailureReason(el, active)}`);
+    }
+
+    return {
+      success: true,
+      focused: getElementDescription(active),
+      previouslyFocused: previous ? getElementDescription(previous) : null,
+      alreadyFocused: previous === active,
+      message: `Focused ${getElementDescription(el)}`
+    };
+  }
+
+  // Blur the element, or whatever is focused without one
+  function doBlur(el) {
+    const active = deepActiveElement();
+    const target = el || active;
+    if (!target) {
+      return { success: true, wasFocused: false, activeElement: null, message: 'Nothing was focused' };
+    }
+
+    const wasFocused = active !== null && containsDeep(target, active);
+    if (wasFocused) {
+      // Blurring a container doesn't blur the focused element inside it
+      active.blur();
+      const after = deepActiveElement();
+      if (after && containsDeep(target, after)) {
+        throw new Error(`Blur didn't stick on ${getElementDescription(target)}: a blur or focusout handler focused it again`);
+      }
+    }
+
+    const after = deepActiveElement();
+    return {
+      success: true,
+      wasFocused,
+      activeElement: after ? getElementDescription(after) : null,
+      message: wasFocused ? `Blurred ${getElementDescription(target)}` : `${getElementDescription(target)} wasn't focused`
+    };
+  }
+
+  function doPress(el, keyName, modifierNames = [], repeatCount = 1) {
+    if (typeof keyName !== 'string' || keyName === '') {
+      throw new Error("Missing 'key' argument for press action. Provide a key like 'Escape', 'ArrowDown', or 's'.");
+    }
+    if (!Array.isArray(modifierNames)) {
+      throw new Error("'modifiers' must be an array like ['Meta', 'Shift'].");
+    }
+    if (!Number.isInteger(repeatCount) || repeatCount < 1 || repeatCount > MAX_REPEAT) {
+      throw new Error(`'repeat' must be an integer from 1 to ${MAX_REPEAT}.`);
+    }
+
+    const held = modifierNames.map((name) => {
+      const modifier = typeof name === 'string' && MODIFIERS[name.toLowerCase()];
+      if (!modifier) {
+        throw new Error(`Unknown modifier: ${JSON.stringify(name)}. Use 'Ctrl', 'Shift', 'Alt', or 'Meta'.`);
+      }
+      return modifier;
     });
+    const keyInfo = MODIFIERS[keyName.toLowerCase()] || resolveKey(keyName);
+
+    const flags = { ctrlKey: false, shiftKey: false, altKey: false, metaKey: false };
+    for (const modifier of held) {
+      flags[modifier.flag] = true;
+    }
+    flags.shiftKey = flags.shiftKey || Boolean(keyInfo.shift);
+    // Pressing a modifier on its own sets its own flag, like a real keyboard
+    if (keyInfo.flag) {
+      flags[keyInfo.flag] = true;
+    }
+    // Browsers don't send keypress for shortcuts or non-character keys
+    const sendsKeypress = (keyInfo.key.length === 1 || keyInfo.key === 'Enter') && !flags.ctrlKey && !flags.metaKey;
+
+    if (el && typeof el.focus === 'function') {
+      el.focus();
+    }
+
+    let prevented = 0;
+    for (let i = 0; i < repeatCount; i++) {
+      // Focus can move between presses (like with Tab), and keys go wherever it is
+      const target = document.activeElement || document.body;
+      const down = { ctrlKey: false, shiftKey: false, altKey: false, metaKey: false };
+      for (const modifier of held) {
+        down[modifier.flag] = true;
+        dispatchKey(target, 'keydown', modifier, down);
+      }
 
+      let allowed = dispatchKey(target, 'keydown', keyInfo, flags);
+      if (allowed && sendsKeypress) {
+        allowed = dispatchKey(target, 'keypress', keyInfo, flags);
+      }
+      if (allowed) {
+        applyDefaultAction(target, keyInfo.key, flags);
+      } else {
+        prevented++;
+      }
+      dispatchKey(target, 'keyup', keyInfo, keyInfo.flag ? { ...flags, [keyInfo.flag]: down[keyInfo.flag] } : flags);
+
+      for (const modifier of [...held].reverse()) {
+        down[modifier.flag] = false;
+        dispatchKey(target, 'keyup', modifier, down);
+      }
+    }
+
+    const focused = document.activeElement || document.body;
+    const combo = [...held.map((modifier) => modifier.key), keyInfo.key].join('+');
     return {
       success: true,
-      message: `Scrolled ${el ? getElementDescription(el) : 'page'} by (${deltaX || 0}, ${deltaY || 0})`
+      key: keyInfo.key,
+      code: keyInfo.code,
+      keyCode: keyCodeFor(keyInfo.code),
+      modifiers: held.map((modifier) => modifier.key),
+      repeat: repeatCount,
+      defaultPrevented: prevented > 0,
+      preventedCount: prevented,
+      focused: getElementDescription(focused),
+      message: `Pressed ${combo}${repeatCount > 1 ? ` ${repeatCount} times` : ''} in ${getElementDescription(focused)}`
     };
   }
 
+  function dispatchKey(target, type, keyInfo, flags) {
+    const { key, code } = keyInfo;
+    const event = new KeyboardEvent(type, {
+      key,
+      code,
+      location: keyLocation(code),
+      bubbles: true,
+      cancelable: true,
+      composed: true,
+      ctrlKey: flags.ctrlKey,
+      shiftKey: flags.shiftKey,
+      altKey: flags.altKey,
+      metaKey: flags.metaKey,
+    });
+
+    // The constructor ignores the legacy fields, but plenty of handlers still read them
+    const charCode = type === 'keypress' ? (key === 'Enter' ? 13 : key.charCodeAt(0)) : 0;
+    const keyCode = type === 'keypress' ? charCode : keyCodeFor(code);
+    Object.defineProperty(event, 'keyCode', { get: () => keyCode });
+    Object.defineProperty(event, 'which', { get: () => keyCode });
+    Object.defineProperty(event, 'charCode', { get: () => charCode });
+
+    // Returns false if a handler called preventDefault()
+    return target.dispatchEvent(event);
+  }
+
+  function keyCodeFor(code) {
+    if (KEY_CODES[code] !== undefined) return KEY_CODES[code];
+    if (/^Key[A-Z]$/.test(code)) return code.charCodeAt(3);
+    if (/^Digit[0-9]$/.test(code)) return code.charCodeAt(5);
+    if (/^Numpad[0-9]$/.test(code)) return 96 + Number(code.slice(6));
+    if (/^F([1-9]|1[0-2])$/.test(code)) return 111 + Number(code.slice(1));
+    return 0;
+  }
+
+  function keyLocation(code) {
+    if (code.startsWith('Numpad')) return KeyboardEvent.DOM_KEY_LOCATION_NUMPAD;
+    if (/^(Shift|Control|Alt|Meta)Left$/.test(code)) return KeyboardEvent.DOM_KEY_LOCATION_LEFT;
+    return KeyboardEvent.DOM_KEY_LOCATION_STANDARD;
+  }
+
+  function resolveKey(name) {
+    const named = NAMED_KEYS[name.toLowerCase()];
+    if (named) return named;
+
+    if (name.length === 1) {
+      const shifted = SHIFTED_CODES[name];
+      if (shifted) {
+        return { key: name, code: shifted, shift: true };
+      }
+      return { key: name, code: codeForCharacter(name), shift: name !== name.toLowerCase() };
+    }
+
+    const fnMatch = /^f([1-9]|1[0-2])$/i.exec(name);
+    if (fnMatch) {
+      return { key: `F${fnMatch[1]}`, code: `F${fnMatch[1]}` };
+    }
+
+    throw new Error(`Unknown key: '${name}'. Use single characters or names like 'enter', 'tab', 'escape', 'backspace', 'delete', 'arrowup', 'numpad5', 'f5', 'ctrl', 'shift', 'alt', 'meta'.`);
+  }
+
+  function codeForCharacter(char) {
+    if (/^[a-z]$/i.test(char)) return `Key${char.toUpperCase()}`;
+    if (/^[0-9]$/.test(char)) return `Digit${char}`;
+    return PUNCTUATION_CODES[char] || '';
+  }
+
+  // Synthetic key events don't move focus or edit text, so emulate Tab and the basics for text fields
+  function applyDefaultAction(target, key, flags) {
+    if (key === 'Tab' && !flags.ctrlKey && !flags.metaKey && !flags.altKey) {
+      moveFocus(target, flags.shiftKey);
+      return;
+    }
+
+    const isTextField = (target.tagName === 'INPUT' || target.tagName === 'TEXTAREA') &&
+      typeof target.selectionStart === 'number';
+    if (!isTextField || target.disabled || target.readOnly) return;
+
+    const value = target.value;
+    const start = target.selectionStart;
+    const end = target.selectionEnd;
+    const shortcut = flags.ctrlKey || flags.metaKey;
+
+    if (shortcut && key.toLowerCase() === 'a') {
+      target.select();
+      return;
+    }
+    if (shortcut || flags.altKey) return;
+
+    let next = null;
+    let caret = start;
+    if (key.length === 1) {
+      next = value.slice(0, start) + key + value.slice(end);
+      caret = start + 1;
+    } else if (key === 'Backspace') {
+      if (start !== end) {
+        next = value.slice(0, start) + value.slice(end);
+      } else if (start > 0) {
+        next = value.slice(0, start - 1) + value.slice(end);
+        caret = start - 1;
+      }
+    } else if (key === 'Delete') {
+      if (start !== end) {
+        next = value.slice(0, start) + value.slice(end);
+      } else if (start < value.length) {
+        next = value.slice(0, start) + value.slice(start + 1);
+      }
+    } else if (key === 'Enter' && target.tagName === 'TEXTAREA') {
+      next = value.slice(0, start) + '\n' + value.slice(end);
+      caret = start + 1;
+    }
+
+    if (next !== null) {
+      target.value = next;
+      target.setSelectionRange(caret, caret);
+      target.dispatchEvent(new Event('input', { bubbles: true }));
+    }
+  }
+
+  // Focus the next (or previous) element in tab order, wrapping around at the ends
+  function moveFocus(from, backwards) {
+    const candidates = document.querySelectorAll(
+      '[tabindex], a[href], button, input, select, textarea, [contenteditable]:not([contenteditable="false"])'
+    );
+    // Same order as focus_info: positive tabindex ascending, then tabindex 0 in document order
+    const order = Array.from(candidates)
+      .filter(isTabbable)
+      .sort((a, b) => tabSortKey(a) - tabSortKey(b));
+    if (order.length === 0) return;
+
+    const index = order.indexOf(from);
+    const next = index === -1
+      ? order[backwards ? order.length - 1 : 0]
+      : order[(index + (backwards ? -1 : 1) + order.length) % order.length];
+    next.focus();
+  }
+
+  function tabSortKey(el) {
+    return el.tabIndex > 0 ? el.tabIndex : Number.MAX_SAFE_INTEGER;
+  }
+
+  function isTabbable(el) {
+    if (el.tabIndex < 0 || el.disabled) return false;
+    if (el.tagName === 'INPUT' && el.type === 'hidden') return false;
+    if (el.closest('[inert]')) return false;
+
+    const style = window.getComputedStyle(el);
+    if (style.visibility === 'hidden' || style.display === 'none') return false;
+    return el.getClientRects().length > 0;
+  }
+
   function getElementDescription(el) {
     if (el.id) return `#${el.id}`;
     if (el.dataset.testid) return `[data-testid="${el.dataset.testid}"]`;
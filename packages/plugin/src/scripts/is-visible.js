@@ -0,0 +1,68 @@
+// Visibility check script: CSS visibility plus how much of the element is in the viewport
+window.__tauriMcpIsVisible = async function(args) {
+  'use strict';
+
+  const { selector } = args;
+
+  const element = document.querySelector(selector);
+  if (!element) {
+    throw new Error(`Element not found: ${selector}`);
+  }
+
+  const hiddenReason = getHiddenReason(element);
+  const ratio = await getIntersectionRatio(element);
+  const inViewport = ratio >= 1;
+  const partiallyInViewport = ratio > 0;
+
+  let reason = hiddenReason;
+  if (!reason && !partiallyInViewport) reason = 'outside viewport';
+  else if (!reason && !inViewport) reason = 'partially outside viewport';
+
+  return {
+    visible: hiddenReason === null,
+    inViewport,
+    partiallyInViewport,
+    intersectionRatio: ratio,
+    reason,
+  };
+
+  // Returns why the element can't be seen or interacted with, or null if it can
+  function getHiddenReason(target) {
+    if (!target.isConnected) return 'not attached to the document';
+
+    // display and opacity on an ancestor hide the element too, so walk up the tree
+    for (let current = target; current; current = current.parentElement) {
+      const style = window.getComputedStyle(current);
+      const where = current === target ? '' : ` on ancestor ${describe(current)}`;
+      if (style.display === 'none') return `display: none${where}`;
+      if (parseFloat(style.opacity) === 0) return `opacity: 0${where}`;
+    }
+
+    // visibility and pointer-events are inherited, so the element's own computed value is enough
+    const style = window.getComputedStyle(target);
+    if (style.visibility === 'hidden' || style.visibility === 'collapse') return `visibility: ${style.visibility}`;
+    if (style.pointerEvents === 'none') return 'pointer-events: none';
+
+    const rect = target.getBoundingClientRect();
+    if (rect.width === 0 || rect.height === 0) return 'zero size';
+
+    return null;
+  }
+
+  function describe(node) {
+    const id = node.id ? `#${node.id}` : '';
+    return `<${node.tagName.toLowerCase()}${id}>`;
+  }
+
+  // IntersectionObserver reports the initial intersection right after observe()
+  function getIntersectionRatio(target) {
+    return new Promise(resolve => {
+      const observer = new IntersectionObserver(entries => {
+        observer.disconnect();
+        const entry = entries[entries.length - 1];
+        resolve(entry.isIntersecting ? Math.round(entry.intersectionRatio * 1000) / 1000 : 0);
+      });
+      observer.observe(target);
+    });
+  }
+};
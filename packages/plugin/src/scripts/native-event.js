@@ -0,0 +1,21 @@
+// Wait for the event a native interact action listens for, to tell whether the OS input the
+// plugin sent reached the page
+window.__tauriMcpNativeEventReceived = async function(timeoutMs) {
+  'use strict';
+
+  const POLL_INTERVAL_MS = 20;
+
+  const record = window.__tauriMcpNativeEvent;
+  const start = Date.now();
+  while (record && !record.received && Date.now() - start < timeoutMs) {
+    await new Promise((resolve) => setTimeout(resolve, POLL_INTERVAL_MS));
+  }
+  delete window.__tauriMcpNativeEvent;
+
+  return {
+    type: record ? record.type : null,
+    received: record ? record.received : false,
+    trusted: record ? record.trusted : null,
+    waitedMs: Date.now() - start,
+  };
+};
@@ -0,0 +1,63 @@
+// Screenshot masking: hide elements that mustn't end up in screenshots, like passwords, and show
+// them again once the capture is done
+(function() {
+  'use strict';
+
+  const ATTRIBUTE = 'data-tauri-mcp-mask';
+  const STYLE_CLASS = '__tauri-mcp-screenshot-mask';
+  const REPAINT_TIMEOUT_MS = 200;
+  // visibility keeps the layout, so the rest of the screenshot looks as it would unmasked.
+  // Descendants are hidden too, since a child with visibility: visible would show through.
+  const RULE = `[${ATTRIBUTE}], [${ATTRIBUTE}] * { visibility: hidden !important; }`;
+
+  // Hide every element the selectors match, and resolve once the page has repainted
+  window.__tauriMcpMask = async function(selectors) {
+    const { queryAll } = window.__tauriMcpSelector;
+    window.__tauriMcpUnmask();
+
+    const elements = new Set();
+    for (const selector of selectors) {
+      for (const element of queryAll(selector)) {
+        elements.add(element);
+      }
+    }
+
+    // A document stylesheet doesn't reach into shadow roots, so each root gets its own
+    const roots = new Set([document]);
+    for (const element of elements) {
+      element.setAttribute(ATTRIBUTE, '');
+      roots.add(element.getRootNode());
+    }
+    window.__tauriMcpMaskedElements = [...elements];
+    for (const root of roots) {
+      const style = document.createElement('style');
+      style.className = STYLE_CLASS;
+      style.textContent = RULE;
+      (root === document ? document.head || document.documentElement : root).appendChild(style);
+    }
+
+    // The style applies on the next frame, and is painted by the one after. Hidden pages don't
+    // run frames, so don't wait for them forever.
+    await new Promise((resolve) => {
+      requestAnimationFrame(() => requestAnimationFrame(resolve));
+      setTimeout(resolve, REPAINT_TIMEOUT_MS);
+    });
+    return { maskedElements: elements.size };
+  };
+
+  // Undo __tauriMcpMask
+  window.__tauriMcpUnmask = function() {
+    const roots = new Set([document]);
+    for (const element of window.__tauriMcpMaskedElements || []) {
+      element.removeAttribute(ATTRIBUTE);
+      roots.add(element.getRootNode());
+    }
+    for (const root of roots) {
+      for (const style of root.querySelectorAll(`.${STYLE_CLASS}`)) {
+        style.remove();
+      }
+    }
+    window.__tauriMcpMaskedElements = [];
+    return true;
+  };
+})();
@@ -0,0 +1,96 @@
+// Scroll position scripts: read and set the scroll position of the page or an element
+(function() {
+  'use strict';
+
+  const BEHAVIORS = ['smooth', 'instant'];
+  const BLOCKS = ['start', 'center', 'end', 'nearest'];
+
+  // Smooth scrolling has no completion event, so poll until the position stops changing
+  const POLL_INTERVAL_MS = 16;
+  const STABLE_POLLS = 3;
+  const MAX_SETTLE_MS = 3000;
+
+  function findElement(selector) {
+    const element = document.querySelector(selector);
+    if (!element) {
+      throw new Error(`Element not found: ${selector}`);
+    }
+    return element;
+  }
+
+  function describe(element) {
+    if (!element) {
+      const root = document.scrollingElement || document.documentElement;
+      return {
+        scrollX: window.scrollX,
+        scrollY: window.scrollY,
+        scrollWidth: root.scrollWidth,
+        scrollHeight: root.scrollHeight,
+        clientWidth: root.clientWidth,
+        clientHeight: root.clientHeight,
+      };
+    }
+    return {
+      scrollX: element.scrollLeft,
+      scrollY: element.scrollTop,
+      scrollWidth: element.scrollWidth,
+      scrollHeight: element.scrollHeight,
+      clientWidth: element.clientWidth,
+      clientHeight: element.clientHeight,
+    };
+  }
+
+  // Position of every scroll container that may move, so nested containers count too
+  function snapshotPositions() {
+    const positions = [window.scrollX, window.scrollY];
+    for (const element of document.querySelectorAll('*')) {
+      if (element.scrollTop || element.scrollLeft) {
+        positions.push(element.scrollTop, element.scrollLeft);
+      }
+    }
+    return positions.join(',');
+  }
+
+  async function waitForScrollToSettle() {
+    const start = Date.now();
+    let last = snapshotPositions();
+    let stablePolls = 0;
+
+    while (stablePolls < STABLE_POLLS && Date.now() - start < MAX_SETTLE_MS) {
+      await new Promise(resolve => setTimeout(resolve, POLL_INTERVAL_MS));
+      const current = snapshotPositions();
+      stablePolls = current === last ? stablePolls + 1 : 0;
+      last = current;
+    }
+  }
+
+  window.__tauriMcpScrollInfo = function(args) {
+    return describe(args.selector ? findElement(args.selector) : null);
+  };
+
+  window.__tauriMcpScrollTo = async function(args) {
+    const { x, y, selector, behavior = 'instant', block = 'start' } = args;
+
+    if (!BEHAVIORS.includes(behavior)) {
+      throw new Error(`Invalid behavior: '${behavior}'. Use 'smooth' or 'instant'.`);
+    }
+
+    if (selector) {
+      if (!BLOCKS.includes(block)) {
+        throw new Error(`Invalid block: '${block}'. Use 'start', 'center', 'end', or 'nearest'.`);
+      }
+      findElement(selector).scrollIntoView({ behavior, block });
+    } else if (x !== undefined || y !== undefined) {
+      window.scrollTo({
+        left: x ?? window.scrollX,
+        top: y ?? window.scrollY,
+        behavior,
+      });
+    } else {
+      throw new Error("Provide 'x' and/or 'y', or a 'selector' to scroll into view.");
+    }
+
+    await waitForScrollToSettle();
+    return describe(null);
+  };
+})();
@@ -0,0 +1,401 @@
+// Shared selector resolution: CSS strings, or { css }, { xpath }, { text, exact }, { role, name, exact },
+// and { ref } objects. Prepended, after a11y.js, to scripts that take selectors (interact.js,
+// dom-snapshot.js, wait-for.js).
+//
+// `a >>> b` in CSS matches b anywhere inside a, including in open shadow roots at any depth, and
+// in the document of a when it's a same-origin iframe. The `pierceShadow` option makes CSS, text,
+// and role selectors search open shadow roots everywhere. Closed shadow roots and cross-origin
+// frames can't be searched; XPath never crosses shadow or frame boundaries.
+//
+// Refs like "e12" are handed out by dom_snapshot with interactiveOnly or roles. They're kept on
+// window, so they last until the page reloads, and hold their elements weakly. Each snapshot that
+// hands out refs starts a generation, and only the refs of the latest one resolve: the others fail
+// with a STALE_REF error naming the element's role and name when it got the ref.
+//
+// It also has the visibility check element_info and find_text share.
+window.__tauriMcpSelector = (function() {
+  'use strict';
+
+  const { getRole, computeAccessibleName } = window.__tauriMcpA11y;
+
+  // How many matches an ambiguous selector error shows
+  const MAX_SNIPPETS = 3;
+
+  // How much of each match's outerHTML an ambiguous selector error shows
+  const SNIPPET_LENGTH = 120;
+
+  // Elements whose text isn't page content
+  const SKIPPED_TAGS = new Set(['HEAD', 'SCRIPT', 'STYLE', 'NOSCRIPT', 'TEMPLATE']);
+
+  // Input types whose label is their value
+  const BUTTON_INPUT_TYPES = new Set(['button', 'submit', 'reset']);
+
+  // How many refs are kept before the least recently handed out ones are dropped
+  const MAX_REFS = 5000;
+
+  // Each script run gets a fresh copy of this module, so the refs live on window. `entries` maps
+  // each ref to { element, generation, role, name }, in the order they were last handed out.
+  const refs = window.__tauriMcpRefs || (window.__tauriMcpRefs = {
+    next: 1,
+    generation: 0,
+    entries: new Map(),
+    ids: new WeakMap(),
+  });
+
+  // The element a selector matches, or null. A CSS string takes the first match, like
+  // querySelector. Selector objects must match exactly one element.
+  function query(selector, root = document, options = {}) {
+    if (typeof selector === 'string') {
+      return queryCss(selector, root, options.pierceShadow === true)[0] || null;
+    }
+    const matches = queryAll(selector, root, options);
+    if (matches.length > 1) {
+      throw new Error(ambiguousMessage(selector, matches));
+    }
+    return matches[0] || null;
+  }
+
+  // All elements a selector matches, in document order
+  function queryAll(selector, root = document, { pierceShadow = false } = {}) {
+    if (typeof selector === 'string') {
+      return queryCss(selector, root, pierceShadow === true);
+    }
+    const { css, xpath, text, role, ref } = selector || {};
+    if (typeof css === 'string') {
+      return queryCss(css, root, pierceShadow === true);
+    }
+    if (typeof xpath === 'string') {
+      return queryXPath(xpath, root);
+    }
+    if (typeof text === 'string') {
+      return queryText(text, selector.exact === true, root, pierceShadow === true);
+    }
+    if (typeof role === 'string') {
+      return queryRole(role, selector.name, selector.exact === true, root, pierceShadow === true);
+    }
+    if (typeof ref === 'string') {
+      return queryRef(ref, root);
+    }
+    throw new Error(`Invalid selector: ${JSON.stringify(selector)}. Use a CSS string, or { css }, { xpath }, { text }, { role, name }, or { ref }.`);
+  }
+
+  // A short form of the selector for messages, like text="Save" or role=button[name="Save"]
+  function describe(selector) {
+    if (typeof selector === 'string') return selector;
+    const { css, xpath, text, role, name, exact, ref } = selector || {};
+    if (typeof css === 'string') return `css=${css}`;
+    if (typeof xpath === 'string') return `xpath=${xpath}`;
+    if (typeof text === 'string') return `text=${JSON.stringify(text)}${exact ? ' (exact)' : ''}`;
+    if (typeof role === 'string') {
+      return `role=${role}${typeof name === 'string' ? `[name=${JSON.stringify(name)}${exact ? ' (exact)' : ''}]` : ''}`;
+    }
+    if (typeof ref === 'string') return `ref=${ref}`;
+    return JSON.stringify(selector);
+  }
+
+  // Each `>>>` part is searched for inside the previous part's matches, shadow roots included, or
+  // in their documents for frames
+  function queryCss(css, root, pierceShadow) {
+    const parts = css.split('>>>').map((part) => part.trim());
+    if (parts.some((part) => part === '')) {
+      throw new Error(`Invalid selector: ${css}. Put a CSS selector on both sides of '>>>'.`);
+    }
+
+    let matches = [];
+    for (const [i, part] of parts.entries()) {
+      const deep = pierceShadow || i > 0;
+      if (deep) {
+        // Throws on invalid CSS even when there's nothing to match against
+        document.createDocumentFragment().querySelector(part);
+      }
+      const scopes = i === 0 ? [root] : matches.map((match) => frameDocument(match) || match);
+      const found = new Set();
+      for (const scope of scopes) {
+        const candidates = deep
+          ? descendants(scope).filter((element) => element.matches(part))
+          : scope.querySelectorAll(part);
+        for (const element of candidates) found.add(element);
+      }
+      matches = Array.from(found);
+    }
+    return matches;
+  }
+
+  // Elements below `node` in tree order, with each open shadow tree before its host's children.
+  // `descend` can return false to leave out an element and everything below it.
+  function descendants(node, descend = () => true) {
+    const elements = [];
+    const visit = (parent) => {
+      if (parent.shadowRoot) visitChildren(parent.shadowRoot);
+      visitChildren(parent);
+    };
+    const visitChildren = (parent) => {
+      for (const child of parent.children) {
+        if (!descend(child)) continue;
+        elements.push(child);
+        visit(child);
+      }
+    };
+    visit(node);
+    return elements;
+  }
+
+  // Whether `node` is `ancestor` or inside it, across shadow and frame boundaries
+  function containsDeep(ancestor, node) {
+    for (let current = node; current; current = current.parentNode || current.host || current.defaultView?.frameElement) {
+      if (current === ancestor) return true;
+    }
+    return false;
+  }
+
+  // The document of a same-origin iframe or frame, or null for other elements and cross-origin
+  // frames, whose documents scripts can't read
+  function frameDocument(element) {
+    if (element.tagName !== 'IFRAME' && element.tagName !== 'FRAME') return null;
+    try {
+      return element.contentDocument;
+    } catch {
+      return null;
+    }
+  }
+
+  // Where a frame's content starts in the viewport of the document the frame is in
+  function frameContentOffset(frame) {
+    const rect = frame.getBoundingClientRect();
+    const style = frame.ownerDocument.defaultView.getComputedStyle(frame);
+    return {
+      x: rect.left + frame.clientLeft + (parseFloat(style.paddingLeft) || 0),
+      y: rect.top + frame.clientTop + (parseFloat(style.paddingTop) || 0),
+    };
+  }
+
+  // The element's bounding rect in the top-level viewport, adding the offsets of the frames it's in
+  function viewportRect(element) {
+    const rect = element.getBoundingClientRect();
+    let x = rect.left;
+    let y = rect.top;
+    let frame = element.ownerDocument === document ? null : element.ownerDocument.defaultView?.frameElement;
+    while (frame) {
+      const offset = frameContentOffset(frame);
+      x += offset.x;
+      y += offset.y;
+      frame = frame.ownerDocument === document ? null : frame.ownerDocument.defaultView?.frameElement;
+    }
+    return new DOMRect(x, y, rect.width, rect.height);
+  }
+
+  // The innermost element at a point, looking into open shadow roots where
+  // document.elementFromPoint stops at the host, and into same-origin frames
+  function elementFromPoint(x, y) {
+    let hit = document.elementFromPoint(x, y);
+    for (;;) {
+      while (hit && hit.shadowRoot) {
+        const inner = hit.shadowRoot.elementFromPoint(x, y);
+        if (!inner || inner === hit) break;
+        hit = inner;
+      }
+      const content = hit && frameDocument(hit);
+      if (!content) return hit;
+      const offset = frameContentOffset(hit);
+      x -= offset.x;
+      y -= offset.y;
+      const inner = content.elementFromPoint(x, y);
+      if (!inner) return hit;
+      hit = inner;
+    }
+  }
+
+  // Closed shadow roots are invisible to scripts, so shadow-roots.js records their hosts as
+  // they're attached. Declarative closed roots from HTML aren't recorded.
+  function hasClosedShadowRoot(element) {
+    const hosts = window.__tauriMcpClosedShadowHosts;
+    return !element.shadowRoot && hosts !== undefined && hosts.has(element);
+  }
+
+  function queryXPath(xpath, root) {
+    let snapshot;
+    try {
+      snapshot = document.evaluate(xpath, root, null, XPathResult.ORDERED_NODE_SNAPSHOT_TYPE, null);
+    } catch (error) {
+      throw new Error(`Invalid XPath: ${xpath} (${error.message})`);
+    }
+    const elements = [];
+    for (let i = 0; i < snapshot.snapshotLength; i++) {
+      const node = snapshot.snapshotItem(i);
+      if (node.nodeType === Node.ELEMENT_NODE) elements.push(node);
+    }
+    return elements;
+  }
+
+  // The innermost rendered elements whose text matches. Without `exact`, the text is a
+  // case-insensitive substring; with it, the whole text must be equal. Both normalize whitespace.
+  function queryText(text, exact, root, pierceShadow) {
+    const wanted = exact ? normalize(text) : normalize(text).toLowerCase();
+    const matches = (element) => {
+      const actual = normalize(elementText(element));
+      return exact ? actual === wanted : actual.toLowerCase().includes(wanted);
+    };
+
+    const candidates = renderedElements(root, pierceShadow).filter(matches);
+    // Candidates are in tree order, so an element's matching descendants come right after it.
+    // A host's textContent leaves out its shadow tree, so its shadow matches stay separate.
+    return candidates.filter((element, i) => !(candidates[i + 1] && containsDeep(element, candidates[i + 1])));
+  }
+
+  // Rendered elements with the role, explicit or implicit, and an accessible name matching `name`
+  // like `text` matches text
+  function queryRole(role, name, exact, root, pierceShadow) {
+    const wantedRole = role.toLowerCase();
+    const wantedName = typeof name === 'string' ? normalize(name) : null;
+    return renderedElements(root, pierceShadow).filter((element) => {
+      if ((getRole(element) || '').toLowerCase() !== wantedRole) return false;
+      if (wantedName === null) return true;
+      const actual = normalize(computeAccessibleName(element) || '');
+      return exact ? actual === wantedName : actual.toLowerCase().includes(wantedName.toLowerCase());
+    });
+  }
+
+  // The element a ref was handed out for, if it's under root. Fails rather than matching nothing,
+  // so an action doesn't wait for an element that can't come back.
+  function queryRef(ref, root) {
+    const entry = refs.entries.get(ref);
+    if (!entry) {
+      const number = /^e([1-9][0-9]*)$/.exec(ref)?.[1];
+      if (number && Number(number) < refs.next) {
+        throw new Error(`STALE_REF: ref ${ref} was dropped, by refs_clear or to keep under ${MAX_REFS} refs. Take a new dom_snapshot to get current refs.`);
+      }
+      throw new Error(`Unknown ref: ${ref}. Refs come from dom_snapshot with interactiveOnly or roles, and are forgotten when the page reloads.`);
+    }
+    const element = entry.element.deref();
+    if (!element || !element.isConnected) {
+      throw new Error(`STALE_REF: ref ${ref} (${lastKnown(entry)}) was removed from the page. Take a new dom_snapshot to get current refs.`);
+    }
+    if (entry.generation < refs.generation) {
+      throw new Error(`STALE_REF: ref ${ref} (${lastKnown(entry)}) is from an older dom_snapshot. Use the refs of the latest one.`);
+    }
+    return containsDeep(root, element) ? [element] : [];
+  }
+
+  // What the element of a ref was when it got the ref, like `button "Save"`
+  function lastKnown({ role, name }) {
+    return name ? `${role || 'no role'} ${JSON.stringify(name)}` : role || 'no role';
+  }
+
+  // Start a new generation of refs, making the refs handed out so far stale
+  function newRefGeneration() {
+    refs.generation++;
+  }
+
+  // The element's ref in the current generation, handing out a new one if it has none. An element
+  // keeps its ref across snapshots, as long as the ref isn't dropped.
+  function refFor(element, role, name) {
+    let ref = refs.ids.get(element);
+    if (ref === undefined || !refs.entries.has(ref)) {
+      ref = `e${refs.next++}`;
+      refs.ids.set(element, ref);
+    }
+    // Delete first so the Map keeps the refs in the order they were last handed out
+    refs.entries.delete(ref);
+    refs.entries.set(ref, {
+      element: typeof WeakRef === 'function' ? new WeakRef(element) : { deref: () => element },
+      generation: refs.generation,
+      role,
+      name,
+    });
+    for (const oldest of refs.entries.keys()) {
+      if (refs.entries.size <= MAX_REFS) break;
+      refs.entries.delete(oldest);
+    }
+    return ref;
+  }
+
+  // Forget all refs, returning how many there were. Refs handed out later don't reuse their
+  // numbers, so the forgotten ones stay stale.
+  function clearRefs() {
+    const cleared = refs.entries.size;
+    refs.entries.clear();
+    refs.ids = new WeakMap();
+    refs.generation++;
+    return { cleared };
+  }
+
+  function renderedElements(root, pierceShadow) {
+    const start = root.nodeType === Node.DOCUMENT_NODE ? root.body : root;
+    if (!start) return [];
+    let elements;
+    if (pierceShadow) {
+      elements = [start, ...descendants(start, (element) => !SKIPPED_TAGS.has(element.tagName))];
+    } else {
+      elements = [];
+      const walker = document.createTreeWalker(start, NodeFilter.SHOW_ELEMENT, {
+        acceptNode: (node) => (SKIPPED_TAGS.has(node.tagName) ? NodeFilter.FILTER_REJECT : NodeFilter.FILTER_ACCEPT),
+      });
+      for (let node = walker.currentNode; node; node = walker.nextNode()) elements.push(node);
+    }
+    return elements.filter((element) => !isHidden(element));
+  }
+
+  function isHidden(element) {
+    if (element.closest('[aria-hidden="true"]')) return true;
+    // Options have no box of their own, so they're as visible as their list
+    const target = element.tagName === 'OPTION' || element.tagName === 'OPTGROUP'
+      ? element.closest('select, datalist') || element
+      : element;
+    if (typeof target.checkVisibility === 'function') {
+      return !target.checkVisibility({ visibilityProperty: true });
+    }
+    const style = window.getComputedStyle(target);
+    return style.display === 'none' || style.visibility === 'hidden';
+  }
+
+  // Whether an element can be seen: it has a size, and neither it nor an ancestor is hidden with
+  // display or opacity, nor it with visibility. checkVisibility accounts for the ancestors.
+  function isVisible(element) {
+    const rect = element.getBoundingClientRect();
+    if (!element.isConnected || rect.width === 0 || rect.height === 0) return false;
+    if (typeof element.checkVisibility === 'function') {
+      return element.checkVisibility({ opacityProperty: true, visibilityProperty: true });
+    }
+    for (let current = element; current; current = current.parentElement) {
+      const style = window.getComputedStyle(current);
+      if (style.display === 'none' || parseFloat(style.opacity) === 0) return false;
+    }
+    const visibility = window.getComputedStyle(element).visibility;
+    return visibility !== 'hidden' && visibility !== 'collapse';
+  }
+
+  function elementText(element) {
+    if (element.tagName === 'INPUT' && BUTTON_INPUT_TYPES.has(element.type)) {
+      return element.value;
+    }
+    return element.textContent || '';
+  }
+
+  function normalize(text) {
+    return String(text).replace(/\s+/g, ' ').trim();
+  }
+
+  function ambiguousMessage(selector, matches) {
+    const snippets = matches.slice(0, MAX_SNIPPETS).map((element) => {
+      const html = normalize(element.outerHTML);
+      return `  ${html.length > SNIPPET_LENGTH ? html.slice(0, SNIPPET_LENGTH) + '…' : html}`;
+    });
+    const more = matches.length > MAX_SNIPPETS ? `\n  ...and ${matches.length - MAX_SNIPPETS} more` : '';
+    return `Selector ${describe(selector)} is ambiguous: it matches ${matches.length} elements. Make it more specific. Matches:\n${snippets.join('\n')}${more}`;
+  }
+
+  return {
+    query,
+    queryAll,
+    describe,
+    containsDeep,
+    elementFromPoint,
+    frameDocument,
+    viewportRect,
+    isVisible,
+    hasClosedShadowRoot,
+    refFor,
+    newRefGeneration,
+    clearRefs,
+  };
+})();
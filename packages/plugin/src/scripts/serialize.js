@@ -0,0 +1,247 @@
+// HTML and Markdown serialization of a subtree, for dom_snapshot's html and markdown formats.
+//
+// Both take the scoped element and `limits`, where limits.isCut(element) says whether maxDepth or
+// maxNodes left an element out, and limits.cutCount(element) how many nodes were left out under
+// it. Open shadow roots are included: in HTML as declarative <template shadowrootmode> elements,
+// and in Markdown in place of the host's light DOM, the way they render.
+window.__tauriMcpSerialize = (function() {
+  'use strict';
+
+  const VOID_TAGS = new Set([
+    'AREA', 'BASE', 'BR', 'COL', 'EMBED', 'HR', 'IMG', 'INPUT', 'LINK', 'META', 'SOURCE', 'TRACK', 'WBR',
+  ]);
+  // Elements whose text isn't parsed as HTML, so it's written as is
+  const RAW_TEXT_TAGS = new Set(['STYLE', 'XMP', 'IFRAME', 'NOEMBED', 'NOFRAMES', 'NOSCRIPT']);
+  // Attributes that can hold a javascript: URL
+  const URL_ATTRIBUTES = new Set(['href', 'src', 'action', 'formaction', 'xlink:href', 'data']);
+
+  const BLOCK_TAGS = new Set([
+    'ADDRESS', 'ARTICLE', 'ASIDE', 'BLOCKQUOTE', 'BODY', 'DD', 'DETAILS', 'DIALOG', 'DIV', 'DL', 'DT',
+    'FIELDSET', 'FIGCAPTION', 'FIGURE', 'FOOTER', 'FORM', 'H1', 'H2', 'H3', 'H4', 'H5', 'H6', 'HEADER',
+    'HR', 'LI', 'MAIN', 'NAV', 'OL', 'P', 'PRE', 'SECTION', 'SUMMARY', 'TABLE', 'UL',
+  ]);
+  const UNRENDERED_TAGS = new Set(['SCRIPT', 'STYLE', 'TEMPLATE', 'NOSCRIPT', 'HEAD', 'META', 'LINK']);
+  // Stands in for <br> until whitespace is collapsed
+  const LINE_BREAK = '\u2028';
+
+  // The element's outer HTML without scripts, inline event handlers, or javascript: URLs, and
+  // with a comment where the limits left nodes out
+  function toHtml(element, limits) {
+    const source = element.tagName === 'TEMPLATE' ? element.content : element;
+    let inner = element.shadowRoot && !limits.isCut(element.shadowRoot) ? toHtml(element.shadowRoot, limits) : '';
+    const rawText = RAW_TEXT_TAGS.has(element.tagName);
+    for (const child of source.childNodes) {
+      if (child.nodeType === Node.TEXT_NODE) {
+        inner += rawText ? child.data : escapeText(child.data);
+      } else if (child.nodeType === Node.ELEMENT_NODE && child.tagName !== 'SCRIPT' && !limits.isCut(child)) {
+        inner += toHtml(child, limits);
+      }
+    }
+    const cut = limits.cutCount(element);
+    if (cut > 0) inner += `<!-- …truncated (${cut} more ${cut === 1 ? 'node' : 'nodes'}) -->`;
+
+    if (element instanceof ShadowRoot) {
+      return `<template shadowrootmode="${element.mode}">${inner}</template>`;
+    }
+    const tag = element.tagName.toLowerCase();
+    const attributes = [...element.attributes]
+      .filter(isSafeAttribute)
+      .map((attribute) => ` ${attribute.name}="${escapeAttribute(attribute.value)}"`)
+      .join('');
+    return VOID_TAGS.has(element.tagName) ? `<${tag}${attributes}>` : `<${tag}${attributes}>${inner}</${tag}>`;
+  }
+
+  function isSafeAttribute(attribute) {
+    const name = attribute.name.toLowerCase();
+    if (name.startsWith('on')) return false;
+    return !URL_ATTRIBUTES.has(name) || isSafeUrl(attribute.value);
+  }
+
+  function isSafeUrl(url) {
+    // Browsers ignore whitespace and control characters in the scheme
+    return !/^javascript:/i.test(url.replace(/[\u0000-\u0020]/g, ''));
+  }
+
+  function escapeText(text) {
+    return text.replace(/&/g, '&amp;').replace(/</g, '&lt;').replace(/>/g, '&gt;');
+  }
+
+  function escapeAttribute(value) {
+    return value.replace(/&/g, '&amp;').replace(/"/g, '&quot;');
+  }
+
+  // Headings, paragraphs, lists, links, tables, and the like as Markdown, leaving out what isn't
+  // rendered
+  function toMarkdown(element, limits) {
+    const blocks = BLOCK_TAGS.has(element.tagName)
+      ? markdownBlock(element, limits)
+      : [collapse(markdownInline(element, limits))].filter(Boolean);
+    return blocks.join('\n\n');
+  }
+
+  function isLeftOut(element, limits) {
+    if (UNRENDERED_TAGS.has(element.tagName) || limits.isCut(element)) return true;
+    return element.hidden || window.getComputedStyle(element).display === 'none';
+  }
+
+  // Child nodes as rendered: a host's shadow tree instead of its light DOM, and a slot's
+  // assigned nodes
+  function renderedChildren(element, limits) {
+    if (element.shadowRoot) {
+      return limits.isCut(element.shadowRoot) ? [] : [...element.shadowRoot.childNodes];
+    }
+    if (element.tagName === 'SLOT') {
+      const assigned = element.assignedNodes({ flatten: true });
+      if (assigned.length > 0) return assigned;
+    }
+    return [...element.childNodes];
+  }
+
+  // Blocks of a container, where runs of text and inline elements between blocks are paragraphs
+  function markdownBlocks(element, limits) {
+    const blocks = [];
+    let run = '';
+    const endRun = () => {
+      const text = collapse(run);
+      if (text) blocks.push(text);
+      run = '';
+    };
+    for (const child of renderedChildren(element, limits)) {
+      if (child.nodeType === Node.TEXT_NODE) {
+        run += escapeMarkdown(child.data);
+      } else if (child.nodeType === Node.ELEMENT_NODE && !isLeftOut(child, limits)) {
+        if (BLOCK_TAGS.has(child.tagName)) {
+          endRun();
+          blocks.push(...markdownBlock(child, limits));
+        } else {
+          run += markdownInline(child, limits);
+        }
+      }
+    }
+    endRun();
+    return blocks;
+  }
+
+  function markdownBlock(element, limits) {
+    const tag = element.tagName;
+    if (/^H[1-6]$/.test(tag)) {
+      const text = inlineText(element, limits);
+      return text ? [`${'#'.repeat(Number(tag[1]))} ${text}`] : [];
+    }
+    switch (tag) {
+      case 'P': {
+        const text = inlineText(element, limits);
+        return text ? [text] : [];
+      }
+      case 'HR':
+        return ['---'];
+      case 'PRE':
+        return ['```\n' + element.textContent.replace(/\n$/, '') + '\n```'];
+      case 'UL':
+      case 'OL':
+        return [markdownList(element, limits)].filter(Boolean);
+      case 'TABLE':
+        return [markdownTable(element, limits)].filter(Boolean);
+      case 'BLOCKQUOTE': {
+        const quoted = markdownBlocks(element, limits).map((block) => block.replace(/^/gm, '> '));
+        return quoted.length > 0 ? [quoted.join('\n>\n')] : [];
+      }
+      default:
+        return markdownBlocks(element, limits);
+    }
+  }
+
+  // Items as "- " or "1. " lines, with nested lists and later paragraphs indented under them
+  function markdownList(list, limits) {
+    const ordered = list.tagName === 'OL';
+    let number = Number.parseInt(list.getAttribute('start') ?? '1', 10) || 1;
+    const lines = [];
+    for (const item of list.children) {
+      if (item.tagName !== 'LI' || isLeftOut(item, limits)) continue;
+      const marker = ordered ? `${number++}. ` : '- ';
+      const [first = '', ...rest] = markdownBlocks(item, limits).join('\n').split('\n');
+      lines.push(marker + first, ...rest.map((line) => (line ? ' '.repeat(marker.length) + line : line)));
+    }
+    return lines.join('\n');
+  }
+
+  // The first row is the header, as Markdown tables need one
+  function markdownTable(table, limits) {
+    const rows = [...table.rows]
+      .filter((row) => !isLeftOut(row, limits))
+      .map((row) => [...row.cells]
+        .filter((cell) => !isLeftOut(cell, limits))
+        .map((cell) => inlineText(cell, limits).replace(/\|/g, '\\|').replace(/ *\n/g, '<br>')));
+    if (rows.length === 0) return '';
+    const width = Math.max(1, ...rows.map((cells) => cells.length));
+    const line = (cells) => `| ${Array.from({ length: width }, (_, i) => cells[i] ?? '').join(' | ')} |`;
+    return [line(rows[0]), line(Array(width).fill('---')), ...rows.slice(1).map(line)].join('\n');
+  }
+
+  function inlineText(element, limits) {
+    return collapse(markdownInlineChildren(element, limits));
+  }
+
+  function markdownInlineChildren(element, limits) {
+    let text = '';
+    for (const child of renderedChildren(element, limits)) {
+      if (child.nodeType === Node.TEXT_NODE) {
+        text += escapeMarkdown(child.data);
+      } else if (child.nodeType === Node.ELEMENT_NODE && !isLeftOut(child, limits)) {
+        // Blocks inside inline content, like a div in a link, only separate words
+        text += BLOCK_TAGS.has(child.tagName)
+          ? ` ${markdownInlineChildren(child, limits)} `
+          : markdownInline(child, limits);
+      }
+    }
+    return text;
+  }
+
+  function markdownInline(element, limits) {
+    const text = () => inlineText(element, limits);
+    switch (element.tagName) {
+      case 'BR':
+        return LINE_BREAK;
+      case 'A': {
+        const label = text();
+        const href = element.getAttribute('href');
+        return href && label && isSafeUrl(href) ? `[${label}](${href})` : label;
+      }
+      case 'STRONG':
+      case 'B': {
+        const label = text();
+        return label ? `**${label}**` : '';
+      }
+      case 'EM':
+      case 'I': {
+        const label = text();
+        return label ? `*${label}*` : '';
+      }
+      case 'CODE':
+        return element.textContent ? '`' + element.textContent + '`' : '';
+      case 'IMG': {
+        const alt = escapeMarkdown(element.getAttribute('alt') ?? '');
+        const src = element.getAttribute('src');
+        return src && isSafeUrl(src) ? `![${alt}](${src})` : alt;
+      }
+      default:
+        return markdownInlineChildren(element, limits);
+    }
+  }
+
+  // Collapse whitespace the way the browser renders it, keeping line breaks from <br>
+  function collapse(text) {
+    return text
+      .replace(/[ \t\n\r\f]+/g, ' ')
+      .split(LINE_BREAK)
+      .map((line) => line.trim())
+      .join('  \n')
+      .trim();
+  }
+
+  function escapeMarkdown(text) {
+    return text.replace(/([\\`*_[\]<])/g, '\\$1');
+  }
+
+  return { toHtml, toMarkdown };
+})();
@@ -0,0 +1,19 @@
+// Record hosts of closed shadow roots, which scripts can't otherwise tell apart from plain
+// elements, so snapshots can mark them as opaque. Runs before page scripts as an init script.
+(function() {
+  'use strict';
+
+  if (window.__tauriMcpClosedShadowHosts) return;
+
+  const hosts = new WeakSet();
+  Object.defineProperty(window, '__tauriMcpClosedShadowHosts', { value: hosts });
+
+  const attachShadow = Element.prototype.attachShadow;
+  Element.prototype.attachShadow = function(init) {
+    const root = attachShadow.call(this, init);
+    if (init && init.mode === 'closed') {
+      hosts.add(this);
+    }
+    return root;
+  };
+})();
@@ -0,0 +1,41 @@
+// Table data extraction script: headers and rows of a <table> as plain text
+window.__tauriMcpTableData = function(args) {
+  'use strict';
+
+  const { selector, includeHtml } = args;
+
+  const table = document.querySelector(selector);
+  if (!table) {
+    throw new Error(`Table not found: ${selector}`);
+  }
+  if (!(table instanceof HTMLTableElement)) {
+    throw new Error(`Element is not a <table>: ${selector} is a <${table.tagName.toLowerCase()}>`);
+  }
+
+  let headerRow = table.tHead?.rows[0] || null;
+  let bodyRows = Array.from(table.tBodies).flatMap(body => Array.from(body.rows));
+
+  // Tables without a <thead> often use a first row of <th> cells as the header
+  if (!headerRow && bodyRows.length > 0 && isHeaderRow(bodyRows[0])) {
+    headerRow = bodyRows.shift();
+  }
+
+  return {
+    headers: headerRow ? Array.from(headerRow.cells).map(getText) : [],
+    // row.cells includes <th> row headers, so columns stay aligned with the headers
+    rows: bodyRows.map(row => Array.from(row.cells).map(describeCell)),
+  };
+
+  function isHeaderRow(row) {
+    return row.cells.length > 0 && Array.from(row.cells).every(cell => cell.tagName === 'TH');
+  }
+
+  function getText(cell) {
+    return (cell.innerText ?? cell.textContent ?? '').trim();
+  }
+
+  function describeCell(cell) {
+    if (!includeHtml) return getText(cell);
+    return { text: getText(cell), html: cell.innerHTML.trim() };
+  }
+};
@@ -0,0 +1,82 @@
+// Viewport emulation: override the page's viewport meta tag and devicePixelRatio
+(function() {
+  'use strict';
+
+  const META_ID = '__tauri-mcp-viewport';
+
+  // The window resize lands asynchronously, so poll until the page sees the new size
+  const POLL_INTERVAL_MS = 16;
+  const MAX_RESIZE_MS = 2000;
+
+  // Lives on window so repeated injections share the saved devicePixelRatio property
+  const state = window.__tauriMcpViewportState || (window.__tauriMcpViewportState = {
+    mobile: false,
+    deviceScaleFactor: null,
+    originalRatio: Object.getOwnPropertyDescriptor(window, 'devicePixelRatio'),
+  });
+
+  function setMobile(mobile) {
+    let meta = document.getElementById(META_ID);
+    if (!mobile) {
+      if (meta) meta.remove();
+      return;
+    }
+    if (!meta) {
+      meta = document.createElement('meta');
+      meta.id = META_ID;
+      meta.name = 'viewport';
+      // The last viewport meta tag wins, so append after any the page has
+      (document.head || document.documentElement).appendChild(meta);
+    }
+    meta.content = 'width=device-width, initial-scale=1';
+  }
+
+  function setDeviceScaleFactor(factor) {
+    if (factor == null) {
+      if (state.originalRatio) {
+        Object.defineProperty(window, 'devicePixelRatio', state.originalRatio);
+      } else {
+        delete window.devicePixelRatio;
+      }
+      return;
+    }
+    Object.defineProperty(window, 'devicePixelRatio', {
+      configurable: true,
+      enumerable: true,
+      get: () => factor,
+    });
+  }
+
+  function describe() {
+    return {
+      width: window.innerWidth,
+      height: window.innerHeight,
+      deviceScaleFactor: window.devicePixelRatio,
+      mobile: state.mobile,
+      emulated: state.mobile || state.deviceScaleFactor != null,
+    };
+  }
+
+  async function waitForSize(width, height) {
+    const start = Date.now();
+    while ((window.innerWidth !== width || window.innerHeight !== height) && Date.now() - start < MAX_RESIZE_MS) {
+      await new Promise(resolve => setTimeout(resolve, POLL_INTERVAL_MS));
+    }
+  }
+
+  window.__tauriMcpViewportGet = function() {
+    return describe();
+  };
+
+  window.__tauriMcpViewportSet = async function(args) {
+    const { width, height, deviceScaleFactor = null, mobile = false } = args;
+
+    state.mobile = mobile;
+    state.deviceScaleFactor = deviceScaleFactor;
+    setMobile(mobile);
+    setDeviceScaleFactor(deviceScaleFactor);
+
+    await waitForSize(width, height);
+    return describe();
+  };
+})();
@@ -1,121 +1,439 @@
-// Wait-for script for waiting on conditions
+// Wait-for script for waiting on conditions. Rechecks on DOM mutations, and for visibility
+// conditions also when the element's intersection with the viewport changes, like when it
+// shrinks to zero size or gets display: none.
+//
+// `all` and `any` wait for several conditions at once, under one overall timeout, and report
+// how each one went.
+//
+// Results say how long the wait took and how often the condition was checked, and timeout errors
+// say what the last check saw, to tell slow conditions from ones that were never going to be met.
 window.__tauriMcpWaitFor = async function(args) {
   'use strict';
 
-  const { type, value, timeout = 5000 } = args;
+  const { query, queryAll, describe } = window.__tauriMcpSelector;
+  // Resolved on the Rust side from `timeoutMs` or the deprecated `timeout`. The script's own
+  // timeout is a bit longer, so the timeout error always comes from here.
+  const { type, timeoutMs: timeout = 5000 } = args;
+
+  // How often to recheck when shadow roots are searched, since mutations in them don't reach
+  // the observer on document.body
+  const SHADOW_POLL_INTERVAL_MS = 100;
+
+  const TYPES = "'selector', 'text', 'visible', 'hidden', 'detached', 'function', 'all', 'any', 'console', 'windowCount', or 'tauriEvent'";
 
   if (!type) {
-    throw new Error("Missing 'type' argument. Use 'selector', 'text', 'visible', or 'hidden'.");
+    throw new Error(`Missing 'type' argument. Use ${TYPES}.`);
   }
 
-  if (!value) {
-    throw new Error("Missing 'value' argument.");
+  if (type === 'all' || type === 'any') {
+    return waitForCompound(type, args.conditions);
   }
+  return waitFor(args, new AbortController().signal, newStats());
 
-  // Check immediately first - element might already exist
-  const immediate = checkCondition(type, value);
-  if (immediate.satisfied) {
-    return { success: true, message: immediate.message };
-  }
-  if (immediate.error) {
-    throw new Error(immediate.error);
+  // How a wait is going, updated as it runs: checks so far, what the last one saw, and when the
+  // DOM last changed
+  function newStats() {
+    return { started: performance.now(), polls: 0, state: null, watchingDom: false, lastMutation: null };
   }
 
-  return new Promise((resolve, reject) => {
-    let observer;
-    let timeoutId;
+  // Wait for one condition. Rejects with an error that has `timedOut` set when the time is up,
+  // and stops waiting when `signal` aborts.
+  function waitFor(condition, signal, stats) {
+    const { type: conditionType, value } = condition;
+    const queryOptions = { pierceShadow: condition.pierceShadow === true };
+
+    if (conditionType === 'function') {
+      return waitForPredicate(condition, signal, stats);
+    }
 
-    const cleanup = () => {
-      if (observer) observer.disconnect();
-      if (timeoutId) clearTimeout(timeoutId);
+    if (!value) {
+      return Promise.reject(new Error("Missing 'value' argument."));
+    }
+
+    const check = () => {
+      stats.polls++;
+      const result = checkCondition(conditionType, value, queryOptions);
+      stats.state = result.state || null;
+      return result;
     };
 
-    // Set up timeout
-    timeoutId = setTimeout(() => {
-      cleanup();
-      reject(new Error(getTimeoutMessage(type, value, timeout)));
-    }, timeout);
+    // Check immediately first - element might already exist
+    const immediate = check();
+    if (immediate.satisfied) {
+      return Promise.resolve(success(condition, immediate, stats));
+    }
+    if (immediate.error) {
+      return Promise.reject(new Error(immediate.error));
+    }
+
+    return new Promise((resolve, reject) => {
+      let observer;
+      let intersectionObserver;
+      let observed = null;
+      let timeoutId;
+      let pollId;
+
+      const cleanup = () => {
+        if (observer) observer.disconnect();
+        if (intersectionObserver) intersectionObserver.disconnect();
+        if (timeoutId) clearTimeout(timeoutId);
+        if (pollId) clearInterval(pollId);
+        signal.removeEventListener('abort', abort);
+      };
+      const abort = () => {
+        cleanup();
+        reject(new Error('Cancelled'));
+      };
+      signal.addEventListener('abort', abort);
 
-    // Set up MutationObserver
-    observer = new MutationObserver(() => {
-      const result = checkCondition(type, value);
-      if (result.satisfied) {
+      // Set up timeout
+      timeoutId = setTimeout(() => {
         cleanup();
-        resolve({ success: true, message: result.message });
+        reject(timedOut(`${getTimeoutMessage(conditionType, value, timeout)} (${progress(stats)})`));
+      }, timeout);
+
+      const recheck = () => {
+        let result;
+        try {
+          result = check();
+        } catch (error) {
+          // Like an ambiguous selector object, which waiting won't fix
+          cleanup();
+          reject(error);
+          return;
+        }
+        if (result.satisfied) {
+          cleanup();
+          resolve(success(condition, result, stats));
+        } else if (intersectionObserver && result.element !== observed) {
+          // Follow the element the selector matches now, in case it was replaced
+          intersectionObserver.disconnect();
+          observed = result.element || null;
+          if (observed) intersectionObserver.observe(observed);
+        }
+      };
+
+      // Size and display changes that come from stylesheets don't show up as mutations
+      if ((conditionType === 'visible' || conditionType === 'hidden') && typeof IntersectionObserver === 'function') {
+        intersectionObserver = new IntersectionObserver(recheck);
+        observed = immediate.element || null;
+        if (observed) intersectionObserver.observe(observed);
       }
-    });
 
-    observer.observe(document.body, {
-      childList: true,
-      subtree: true,
-      attributes: true,
-      characterData: type === 'text'
+      // Set up MutationObserver
+      observer = new MutationObserver(() => {
+        stats.lastMutation = performance.now();
+        recheck();
+      });
+      observer.observe(document.body, {
+        childList: true,
+        subtree: true,
+        attributes: true,
+        characterData: conditionType === 'text'
+      });
+      stats.watchingDom = true;
+      if (queryOptions.pierceShadow) {
+        pollId = setInterval(recheck, SHADOW_POLL_INTERVAL_MS);
+      }
     });
-  });
+  }
+
+  // Wait for all or any of the conditions, started together. The result and the timeout error
+  // both say which conditions were met and when, so partial progress is visible.
+  async function waitForCompound(kind, conditions) {
+    if (!Array.isArray(conditions) || conditions.length === 0) {
+      throw new Error(`'${kind}' needs a non-empty 'conditions' array.`);
+    }
+
+    const started = performance.now();
+    const controller = new AbortController();
+    const stats = conditions.map(() => newStats());
+    const outcomes = conditions.map((condition) => ({ type: condition.type, met: false }));
+
+    const runs = conditions.map((condition, i) =>
+      waitFor({ pierceShadow: args.pierceShadow, ...condition }, controller.signal, stats[i]).then(
+        ({ success: _, ...result }) => {
+          outcomes[i] = { type: condition.type, met: true, ...result };
+        },
+        (error) => {
+          if (!controller.signal.aborted && !error.timedOut) outcomes[i].error = error.message;
+          error.conditionIndex = i;
+          throw error;
+        }
+      )
+    );
+
+    const totalPolls = () => stats.reduce((sum, { polls }) => sum + polls, 0);
+    try {
+      await (kind === 'all' ? Promise.all(runs) : Promise.any(runs));
+    } catch (error) {
+      // For `all` the first failure, for `any` an AggregateError once all of them failed
+      const failures = kind === 'all' ? [error] : error.errors;
+      const failure = failures.find((e) => !e.timedOut) || failures[0];
+      controller.abort();
+      const headline = failure.timedOut
+        ? `Timeout after ${timeout}ms waiting for ${kind} of ${conditions.length} conditions (waited ${elapsed(started)}ms over ${totalPolls()} polls)`
+        : `conditions[${failure.conditionIndex}] failed: ${failure.message}`;
+      throw new Error(`${headline}\n${summarize(conditions, outcomes, stats)}`);
+    }
+    controller.abort();
+
+    const met = outcomes.filter((outcome) => outcome.met).length;
+    return {
+      success: true,
+      message: kind === 'all' ? `All ${conditions.length} conditions met` : `${met} of ${conditions.length} conditions met`,
+      elapsedMs: elapsed(started),
+      polls: totalPolls(),
+      condition: `${kind} of ${conditions.length} conditions`,
+      detail: outcomes.map((outcome, i) => `${describeCondition(conditions[i])}: ${outcome.met ? outcome.detail : 'not met'}`).join('; '),
+      conditions: outcomes,
+    };
+  }
+
+  // One line per condition: what it waited for and how it went
+  function summarize(conditions, outcomes, stats) {
+    return conditions
+      .map((condition, i) => {
+        const outcome = outcomes[i];
+        const lastSeen = stats[i].state ? ` (${stats[i].state})` : '';
+        const state = outcome.met ? `met after ${outcome.elapsedMs}ms` : outcome.error || `not met${lastSeen}`;
+        return `- ${describeCondition(condition)}: ${state}`;
+      })
+      .join('\n');
+  }
+
+  function describeCondition(condition) {
+    switch (condition.type) {
+      case 'function':
+        return 'function';
+      case 'text':
+        return `text '${condition.value}'`;
+      default:
+        return `${condition.type} '${describe(condition.value)}'`;
+    }
+  }
+
+  function elapsed(started) {
+    return Math.round(performance.now() - started);
+  }
+
+  // For timeout errors: how long and how often it checked, what it saw last, and when the DOM
+  // last changed, which tells a page that's still changing from one that settled
+  function progress(stats) {
+    const parts = [`waited ${elapsed(stats.started)}ms over ${stats.polls} polls`];
+    if (stats.state) parts.push(stats.state);
+    if (stats.lastMutation !== null) {
+      parts.push(`last DOM mutation ${elapsed(stats.lastMutation)}ms ago`);
+    } else if (stats.watchingDom) {
+      parts.push('no DOM mutations');
+    }
+    return parts.join('; ');
+  }
+
+  function timedOut(message) {
+    const error = new Error(message);
+    error.timedOut = true;
+    return error;
+  }
 
   function getTimeoutMessage(conditionType, conditionValue, timeoutMs) {
+    const target = conditionType === 'text' ? conditionValue : describe(conditionValue);
     switch (conditionType) {
       case 'selector':
-        return `Timeout after ${timeoutMs}ms waiting for '${conditionValue}' to appear`;
+        return `Timeout after ${timeoutMs}ms waiting for '${target}' to appear`;
       case 'text':
         return `Timeout after ${timeoutMs}ms waiting for text '${conditionValue}' to appear`;
       case 'visible':
-        return `Timeout after ${timeoutMs}ms waiting for '${conditionValue}' to become visible`;
+        return `Timeout after ${timeoutMs}ms waiting for '${target}' to become visible`;
       case 'hidden':
-        return `Timeout after ${timeoutMs}ms waiting for '${conditionValue}' to disappear`;
+        return `Timeout after ${timeoutMs}ms waiting for '${target}' to disappear`;
+      case 'detached':
+        return `Timeout after ${timeoutMs}ms waiting for '${target}' to be removed`;
       default:
-        return `Timeout after ${timeoutMs}ms waiting for ${conditionType}: ${conditionValue}`;
+        return `Timeout after ${timeoutMs}ms waiting for ${conditionType}: ${target}`;
+    }
+  }
+
+  // Runs the predicate every `pollIntervalMs` until it returns something truthy, and returns that.
+  // A predicate that throws is retried, since what it reads may not exist yet.
+  async function waitForPredicate(condition, signal, stats) {
+    const { script, args: predicateArgs = null, pollIntervalMs = 100 } = condition;
+    const AsyncFunction = Object.getPrototypeOf(async function() {}).constructor;
+    let predicate;
+    try {
+      predicate = new AsyncFunction('args', script);
+    } catch (error) {
+      // Waiting won't fix a syntax error
+      throw new Error(`Invalid predicate script: ${error.message}`);
+    }
+
+    const deadline = Date.now() + timeout;
+    const stillRunning = Symbol('still running');
+    while (!signal.aborted) {
+      stats.polls++;
+      try {
+        // A predicate that never settles times out here too, rather than in the script timeout
+        let timer;
+        const result = await Promise.race([
+          predicate(predicateArgs),
+          new Promise((resolve) => {
+            timer = setTimeout(() => resolve(stillRunning), Math.max(deadline - Date.now(), 0));
+          }),
+        ]).finally(() => clearTimeout(timer));
+        if (result === stillRunning) {
+          stats.state = 'predicate was still running';
+          throw timedOut(`Timeout after ${timeout}ms waiting for the predicate to return a truthy value (${progress(stats)})`);
+        }
+        if (result) {
+          return {
+            ...success(condition, { message: 'Predicate returned a truthy value', detail: `predicate returned ${preview(result)}` }, stats),
+            value: result,
+          };
+        }
+        stats.state = `predicate last returned ${preview(result)}`;
+      } catch (error) {
+        if (error.timedOut) throw error;
+        stats.state = `predicate last threw: ${error.message || String(error)}`;
+      }
+
+      const remaining = deadline - Date.now();
+      if (remaining <= 0) {
+        throw timedOut(`Timeout after ${timeout}ms waiting for the predicate to return a truthy value (${progress(stats)})`);
+      }
+      await new Promise((resolve) => setTimeout(resolve, Math.min(pollIntervalMs, remaining)));
     }
+    throw new Error('Cancelled');
   }
 
-  function checkCondition(conditionType, conditionValue) {
+  // A short form of a value for messages
+  function preview(value) {
+    let text;
+    try {
+      text = JSON.stringify(value);
+    } catch {
+      text = undefined;
+    }
+    if (text === undefined) text = String(value);
+    return text.length > 100 ? `${text.slice(0, 100)}...` : text;
+  }
+
+  // The result of a met condition. `detail` is what the last check saw.
+  function success(condition, result, stats) {
+    const payload = {
+      success: true,
+      message: result.message,
+      elapsedMs: elapsed(stats.started),
+      polls: stats.polls,
+      condition: describeCondition(condition),
+      detail: result.detail,
+    };
+    if (result.reason !== undefined) payload.reason = result.reason;
+    return payload;
+  }
+
+  // `detail` says what a check that was met saw, and `state` what one that wasn't saw.
+  // `element` is what the selector matches now, for the intersection observer to follow.
+  function checkCondition(conditionType, conditionValue, queryOptions) {
     switch (conditionType) {
       case 'selector': {
-        const el = document.querySelector(conditionValue);
+        const el = query(conditionValue, document, queryOptions);
         if (el) {
-          return { satisfied: true, message: `Found element matching '${conditionValue}'` };
+          return {
+            satisfied: true,
+            message: `Found element matching '${describe(conditionValue)}'`,
+            detail: `selector matched ${plural(queryAll(conditionValue, document, queryOptions).length, 'element')}`,
+          };
         }
-        return { satisfied: false };
+        return { satisfied: false, state: 'selector matched 0 elements' };
       }
 
       case 'text': {
-        const found = document.body.innerText.includes(conditionValue);
-        if (found) {
-          return { satisfied: true, message: `Found text '${conditionValue}'` };
+        const pageText = document.body.innerText;
+        const index = pageText.indexOf(conditionValue);
+        if (index !== -1) {
+          return {
+            satisfied: true,
+            message: `Found text '${conditionValue}'`,
+            detail: `text found at character ${index} of ${pageText.length}`,
+          };
         }
-        return { satisfied: false };
+        return { satisfied: false, state: `text not in the page's ${plural(pageText.length, 'character')}` };
       }
 
       case 'visible': {
-        const el = document.querySelector(conditionValue);
-        if (el && isVisible(el)) {
-          return { satisfied: true, message: `Element '${conditionValue}' is visible` };
+        const el = query(conditionValue, document, queryOptions);
+        const reason = el ? hiddenReason(el) : null;
+        if (el && reason === null) {
+          return { satisfied: true, message: `Element '${describe(conditionValue)}' is visible`, detail: `element is ${box(el)}` };
         }
-        return { satisfied: false };
+        const state = el ? `element is hidden (${reason})` : 'selector matched 0 elements';
+        return { satisfied: false, element: el, state };
       }
 
+      // Hidden also counts an element that's gone, so waiting for a spinner to go away works
+      // whether the app hides it or removes it
       case 'hidden': {
-        const el = document.querySelector(conditionValue);
-        if (!el || !isVisible(el)) {
-          return { satisfied: true, message: `Element '${conditionValue}' is hidden or removed` };
+        const el = query(conditionValue, document, queryOptions);
+        const reason = el ? hiddenReason(el) : 'removed';
+        if (reason === 'removed') {
+          return { satisfied: true, message: `Element '${describe(conditionValue)}' is removed`, reason, detail: 'element is removed' };
+        }
+        if (reason !== null) {
+          return {
+            satisfied: true,
+            message: `Element '${describe(conditionValue)}' is hidden (${reason})`,
+            reason,
+            detail: `element is hidden (${reason})`,
+          };
         }
-        return { satisfied: false };
+        return { satisfied: false, element: el, state: `element is visible, ${box(el)}` };
+      }
+
+      case 'detached': {
+        const el = query(conditionValue, document, queryOptions);
+        if (!el) {
+          return {
+            satisfied: true,
+            message: `Element '${describe(conditionValue)}' is removed`,
+            reason: 'removed',
+            detail: 'element is removed',
+          };
+        }
+        const reason = hiddenReason(el);
+        return { satisfied: false, state: `element is still in the DOM${reason ? ` (hidden: ${reason})` : ''}` };
       }
 
       default:
-        return { satisfied: false, error: `Unknown wait type '${conditionType}'. Use 'selector', 'text', 'visible', or 'hidden'.` };
+        return { satisfied: false, error: `Unknown wait type '${conditionType}'. Use ${TYPES}.` };
     }
   }
 
-  function isVisible(element) {
+  function plural(count, noun) {
+    return `${count} ${noun}${count === 1 ? '' : 's'}`;
+  }
+
+  // Size and position of an element's box, like '120x40 at (16, 200)'
+  function box(element) {
     const rect = element.getBoundingClientRect();
-    if (rect.width === 0 || rect.height === 0) return false;
+    return `${Math.round(rect.width)}x${Math.round(rect.height)} at (${Math.round(rect.left)}, ${Math.round(rect.top)})`;
+  }
+
+  // Why the element can't be seen, or null if it can: 'removed', 'display: none',
+  // 'visibility: hidden', 'opacity: 0', or 'zero size'
+  function hiddenReason(element) {
+    if (!element.isConnected) return 'removed';
 
     const style = window.getComputedStyle(element);
-    if (style.display === 'none') return false;
-    if (style.visibility === 'hidden') return false;
-    if (style.opacity === '0') return false;
+    // An ancestor's display: none leaves no box, which offsetParent and the rect both show
+    if (style.display === 'none' || (element.offsetParent === null && style.position !== 'fixed' && element !== document.body)) {
+      return 'display: none';
+    }
+    if (style.visibility === 'hidden' || style.visibility === 'collapse') return 'visibility: hidden';
+    if (style.opacity === '0') return 'opacity: 0';
+
+    const rect = element.getBoundingClientRect();
+    if (rect.width === 0 || rect.height === 0) return 'zero size';
 
-    return true;
+    return null;
   }
 };
@@ -0,0 +1,530 @@
+//! Per-connection event subscriptions.
+//!
+//! Clients send a `subscribe` request with a `topic` to have events pushed over the same
+//! WebSocket as event frames (they have an `event` field instead of an `id`), and
+//! `unsubscribe` to stop them. The `console` topic streams console entries, the `windows`
+//! topic streams windows opening, closing, gaining or losing focus, moving, and resizing, and
+//! the `navigation` topic streams page loads starting and finishing.
+//! `event_subscribe` and `event_unsubscribe` do the same for the app's own Tauri events.
+//! Subscriptions end when the connection closes. The gRPC `Subscribe` call streams the same
+//! events, with one subscription per call.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use futures_util::SinkExt;
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, EventId, Listener, Manager, Runtime};
+use tokio::sync::broadcast::{self, error::RecvError};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::debug;
+
+use crate::console::{parse_levels, ConsoleEntry, ConsoleQuery, ConsoleStore, STREAM_HIGH_WATER_MARK};
+use crate::navigation::{Navigation, NavigationEvent};
+use crate::websocket::WsWriter;
+use crate::windows::{LifecycleEvent, WindowRegistry, WINDOW_EVENT_KINDS};
+
+/// Request command that starts a subscription
+pub const SUBSCRIBE_COMMAND: &str = "subscribe";
+
+/// Request command that stops a subscription
+pub const UNSUBSCRIBE_COMMAND: &str = "unsubscribe";
+
+/// Topic that streams console entries as they're captured
+pub const CONSOLE_TOPIC: &str = "console";
+
+/// Event sent when a console subscriber fell too far behind and entries were skipped
+pub const CONSOLE_DROPPED_EVENT: &str = "console_dropped";
+
+/// Topic that streams window lifecycle events
+pub const WINDOWS_TOPIC: &str = "windows";
+
+/// Event frame name for window lifecycle events
+pub const WINDOW_EVENT: &str = "window_event";
+
+/// Event sent when a windows subscriber fell too far behind and events were skipped
+pub const WINDOW_EVENTS_DROPPED_EVENT: &str = "window_events_dropped";
+
+/// Topic that streams page loads, with event frames of the same name
+pub const NAVIGATION_TOPIC: &str = "navigation";
+
+/// Event sent when a navigation subscriber fell too far behind and events were skipped
+pub const NAVIGATION_DROPPED_EVENT: &str = "navigation_dropped";
+
+/// Topics `subscribe` accepts
+const TOPICS: &[&str] = &[CONSOLE_TOPIC, WINDOWS_TOPIC, NAVIGATION_TOPIC];
+
+/// Request command that starts forwarding a Tauri event
+pub const EVENT_SUBSCRIBE_COMMAND: &str = "event_subscribe";
+
+/// Request command that stops forwarding a Tauri event
+pub const EVENT_UNSUBSCRIBE_COMMAND: &str = "event_unsubscribe";
+
+/// Event frame name for forwarded Tauri events
+pub const TAURI_EVENT: &str = "tauri_event";
+
+/// Event frame pushed to subscribed clients.
+#[derive(Debug, Serialize)]
+pub struct Event {
+    /// Event name (`console`, `window_event`, `navigation`, `tauri_event`, or one of the `_dropped`
+    /// events)
+    pub event: &'static str,
+    /// Event payload
+    pub data: Value,
+}
+
+/// Where a connection's events go
+#[derive(Debug, Clone)]
+pub enum EventSink {
+    /// A WebSocket connection, as event frames
+    WebSocket(WsWriter),
+    /// A channel, for transports that frame events themselves
+    #[cfg_attr(not(feature = "grpc"), allow(dead_code))]
+    Channel(mpsc::Sender<Event>),
+}
+
+/// Active subscriptions of one connection, by topic or Tauri event name. Dropping it stops all
+/// streams and removes the Tauri event listeners.
+#[derive(Debug, Default)]
+pub struct Subscriptions {
+    tasks: HashMap<String, JoinHandle<()>>,
+    listeners: HashMap<String, TauriEventListener>,
+}
+
+/// A Tauri event listener forwarding to the connection
+struct TauriEventListener {
+    id: EventId,
+    /// Removes the listener from the app (the app handle's runtime type is erased here)
+    unlisten: Box<dyn Fn(EventId) + Send + Sync>,
+    task: JoinHandle<()>,
+}
+
+impl TauriEventListener {
+    fn stop(self) {
+        (self.unlisten)(self.id);
+        self.task.abort();
+    }
+}
+
+impl fmt::Debug for TauriEventListener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TauriEventListener")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Subscriptions {
+    /// Start streaming a topic to the connection, replacing any existing subscription to it.
+    pub fn subscribe<R: Runtime>(
+        &mut self,
+        app: &AppHandle<R>,
+        args: &Value,
+        sink: &EventSink,
+    ) -> Result<Value, String> {
+        let topic = topic_arg(args)?;
+
+        let task = match topic {
+            CONSOLE_TOPIC => {
+                let filter = ConsoleFilter::parse(app, args)?;
+                let store = app
+                    .try_state::<ConsoleStore>()
+                    .ok_or("Console capture not initialized")?;
+                tokio::spawn(stream_console(store.subscribe(), filter, sink.clone()))
+            }
+            WINDOWS_TOPIC => {
+                let kinds = window_event_kinds_arg(args)?;
+                let windows = app
+                    .try_state::<WindowRegistry>()
+                    .ok_or("Window registry not initialized")?;
+                tokio::spawn(stream_windows(windows.subscribe(), kinds, sink.clone()))
+            }
+            NAVIGATION_TOPIC => {
+                let window_label = window_label_arg(app, args)?;
+                let navigation = app
+                    .try_state::<Navigation>()
+                    .ok_or("Navigation tracking not initialized")?;
+                tokio::spawn(stream_navigation(navigation.subscribe(), window_label, sink.clone()))
+            }
+            _ => return Err(format!("Unknown topic: '{topic}'. Available: {}", TOPICS.join(", "))),
+        };
+
+        if let Some(previous) = self.tasks.insert(topic.to_string(), task) {
+            previous.abort();
+        }
+
+        Ok(json!({ "topic": topic, "subscribed": true }))
+    }
+
+    /// Stop streaming a topic. Unsubscribing from an inactive topic is not an error.
+    pub fn unsubscribe(&mut self, args: &Value) -> Result<Value, String> {
+        let topic = topic_arg(args)?;
+        let was_subscribed = self.tasks.remove(topic).map(|task| task.abort()).is_some();
+
+        Ok(json!({ "topic": topic, "subscribed": false, "wasSubscribed": was_subscribed }))
+    }
+
+    /// Forward a Tauri event emitted anywhere in the app to the connection as `tauri_event` frames.
+    /// Subscribing to the same event again is a no-op.
+    pub fn subscribe_event<R: Runtime>(
+        &mut self,
+        app: &AppHandle<R>,
+        args: &Value,
+        sink: &EventSink,
+    ) -> Result<Value, String> {
+        let event = event_arg(args)?;
+        if self.listeners.contains_key(event) {
+            return Ok(json!({ "event": event, "subscribed": true }));
+        }
+
+        // The listener callback is sync, so hand payloads to a task that writes them in order
+        let (tx, rx) = mpsc::channel(STREAM_HIGH_WATER_MARK);
+        let name = event.to_string();
+        let id = app.listen_any(event, move |tauri_event| {
+            let payload = serde_json::from_str(tauri_event.payload())
+                .unwrap_or_else(|_| Value::String(tauri_event.payload().to_string()));
+            if tx.try_send(json!({ "event": name, "payload": payload })).is_err() {
+                debug!("Dropping '{name}' event for a subscriber that fell behind");
+            }
+        });
+
+        let app = app.clone();
+        let listener = TauriEventListener {
+            id,
+            unlisten: Box::new(move |id| app.unlisten(id)),
+            task: tokio::spawn(stream_tauri_events(rx, sink.clone())),
+        };
+        self.listeners.insert(event.to_string(), listener);
+
+        Ok(json!({ "event": event, "subscribed": true }))
+    }
+
+    /// Stop forwarding a Tauri event. Unsubscribing from an event that isn't forwarded is not an error.
+    pub fn unsubscribe_event(&mut self, args: &Value) -> Result<Value, String> {
+        let event = event_arg(args)?;
+        let was_subscribed = self.listeners.remove(event).map(TauriEventListener::stop).is_some();
+
+        Ok(json!({ "event": event, "subscribed": false, "wasSubscribed": was_subscribed }))
+    }
+}
+
+impl Drop for Subscriptions {
+    fn drop(&mut self) {
+        for task in self.tasks.values() {
+            task.abort();
+        }
+        for (_, listener) in self.listeners.drain() {
+            listener.stop();
+        }
+    }
+}
+
+/// Which console entries a subscriber wants
+#[derive(Debug)]
+struct ConsoleFilter {
+    /// Only entries from this window (all windows if `None`)
+    window_label: Option<String>,
+    /// Level filter
+    query: ConsoleQuery,
+}
+
+impl ConsoleFilter {
+    fn parse<R: Runtime>(app: &AppHandle<R>, args: &Value) -> Result<Self, String> {
+        let window_label = window_label_arg(app, args)?;
+        let levels = args.get("levels").map(parse_levels).transpose()?;
+        let query = ConsoleQuery::parse(None, None, levels, false)?;
+
+        Ok(Self { window_label, query })
+    }
+
+    fn matches(&self, entry: &ConsoleEntry) -> bool {
+        self.window_label
+            .as_ref()
+            .map_or(true, |label| *label == entry.window_label)
+            && self.query.matches(entry)
+    }
+}
+
+/// Push console entries to the client until it disconnects or unsubscribes
+async fn stream_console(mut rx: broadcast::Receiver<ConsoleEntry>, filter: ConsoleFilter, sink: EventSink) {
+    loop {
+        let event = match rx.recv().await {
+            Ok(entry) if filter.matches(&entry) => {
+                let Ok(data) = serde_json::to_value(&entry) else {
+                    continue;
+                };
+                Event {
+                    event: CONSOLE_TOPIC,
+                    data,
+                }
+            }
+            Ok(_) => continue,
+            // Counted before level filtering, since skipped entries are gone
+            Err(RecvError::Lagged(dropped)) => Event {
+                event: CONSOLE_DROPPED_EVENT,
+                data: json!({ "dropped": dropped }),
+            },
+            Err(RecvError::Closed) => break,
+        };
+
+        if !send_event(&sink, event).await {
+            break;
+        }
+    }
+}
+
+/// Push window lifecycle events of the wanted kinds to the client until it disconnects or
+/// unsubscribes
+async fn stream_windows(mut rx: broadcast::Receiver<LifecycleEvent>, kinds: Vec<String>, sink: EventSink) {
+    loop {
+        let event = match rx.recv().await {
+            Ok(lifecycle) if kinds.iter().any(|kind| kind == lifecycle.event) => {
+                let Ok(data) = serde_json::to_value(&lifecycle) else {
+                    continue;
+                };
+                Event {
+                    event: WINDOW_EVENT,
+                    data,
+                }
+            }
+            Ok(_) => continue,
+            Err(RecvError::Lagged(dropped)) => Event {
+                event: WINDOW_EVENTS_DROPPED_EVENT,
+                data: json!({ "dropped": dropped }),
+            },
+            Err(RecvError::Closed) => break,
+        };
+
+        if !send_event(&sink, event).await {
+            break;
+        }
+    }
+}
+
+/// Push page loads to the client until it disconnects or unsubscribes, from one window if
+/// `window_label` is set
+async fn stream_navigation(
+    mut rx: broadcast::Receiver<NavigationEvent>,
+    window_label: Option<String>,
+    sink: EventSink,
+) {
+    loop {
+        let event = match rx.recv().await {
+            Ok(navigation)
+                if window_label
+                    .as_ref()
+                    .map_or(true, |label| *label == navigation.window_label) =>
+            {
+                let Ok(data) = serde_json::to_value(&navigation) else {
+                    continue;
+                };
+                Event {
+                    event: NAVIGATION_TOPIC,
+                    data,
+                }
+            }
+            Ok(_) => continue,
+            Err(RecvError::Lagged(dropped)) => Event {
+                event: NAVIGATION_DROPPED_EVENT,
+                data: json!({ "dropped": dropped }),
+            },
+            Err(RecvError::Closed) => break,
+        };
+
+        if !send_event(&sink, event).await {
+            break;
+        }
+    }
+}
+
+/// Push forwarded Tauri events to the client until it disconnects or unsubscribes
+async fn stream_tauri_events(mut rx: mpsc::Receiver<Value>, sink: EventSink) {
+    while let Some(data) = rx.recv().await {
+        let event = Event {
+            event: TAURI_EVENT,
+            data,
+        };
+        if !send_event(&sink, event).await {
+            break;
+        }
+    }
+}
+
+/// Send an event, returning false if the connection is gone
+async fn send_event(sink: &EventSink, event: Event) -> bool {
+    match sink {
+        EventSink::WebSocket(write) => {
+            let Ok(text) = serde_json::to_string(&event) else {
+                return true;
+            };
+            let mut w = write.write().await;
+            w.send(Message::Text(text.into())).await.is_ok()
+        }
+        EventSink::Channel(tx) => tx.send(event).await.is_ok(),
+    }
+}
+
+/// Get the required `topic` argument
+fn topic_arg(args: &Value) -> Result<&str, String> {
+    args.get("topic")
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("Missing required 'topic' argument. Available: {}", TOPICS.join(", ")))
+}
+
+/// Get the optional `windowId` argument, checking the window exists
+fn window_label_arg<R: Runtime>(app: &AppHandle<R>, args: &Value) -> Result<Option<String>, String> {
+    let window_label = args.get("windowId").and_then(Value::as_str).map(String::from);
+    if let Some(label) = &window_label {
+        if app.get_webview_window(label).is_none() {
+            return Err(format!("Window '{label}' not found"));
+        }
+    }
+    Ok(window_label)
+}
+
+/// Get the optional `events` argument of the `windows` topic: the lifecycle events to stream,
+/// all of them by default
+fn window_event_kinds_arg(args: &Value) -> Result<Vec<String>, String> {
+    let invalid = |value: &Value| {
+        format!(
+            "'events' must be an array of {}, got: {value}",
+            WINDOW_EVENT_KINDS.join(", ")
+        )
+    };
+    match args.get("events") {
+        None | Some(Value::Null) => Ok(WINDOW_EVENT_KINDS.iter().map(ToString::to_string).collect()),
+        Some(Value::Array(kinds)) => kinds
+            .iter()
+            .map(|kind| {
+                kind.as_str()
+                    .filter(|kind| WINDOW_EVENT_KINDS.contains(kind))
+                    .map(String::from)
+                    .ok_or_else(|| invalid(kind))
+            })
+            .collect(),
+        Some(value) => Err(invalid(value)),
+    }
+}
+
+/// Get the required `event` argument, checking it's a valid Tauri event name
+pub fn event_arg(args: &Value) -> Result<&str, String> {
+    let event = args
+        .get("event")
+        .and_then(Value::as_str)
+        .ok_or("Missing required 'event' argument")?;
+
+    // Tauri panics on invalid names, so check them up front
+    let valid = !event.is_empty()
+        && event
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '-' | '/' | ':' | '_'));
+    if valid {
+        Ok(event)
+    } else {
+        Err(format!(
+            "Invalid event name: '{event}'. Use letters, digits, '-', '/', ':', and '_'."
+        ))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_event_kinds_default_to_all() {
+        assert_eq!(window_event_kinds_arg(&json!({})).unwrap(), WINDOW_EVENT_KINDS);
+        assert_eq!(
+            window_event_kinds_arg(&json!({ "events": ["created", "closed"] })).unwrap(),
+            ["created", "closed"]
+        );
+
+        let err = window_event_kinds_arg(&json!({ "events": ["opened"] })).unwrap_err();
+        assert!(
+            err.contains("'events' must be an array of created, closed, focused"),
+            "{err}"
+        );
+        assert!(err.contains("got: \"opened\""), "{err}");
+        assert!(window_event_kinds_arg(&json!({ "events": "created" })).is_err());
+    }
+
+    #[test]
+    fn window_event_frame_has_kind_label_and_data() {
+        let lifecycle = LifecycleEvent {
+            event: "moved",
+            label: "settings".to_string(),
+            data: json!({ "x": 100, "y": 50 }),
+        };
+        let event = Event {
+            event: WINDOW_EVENT,
+            data: serde_json::to_value(&lifecycle).unwrap(),
+        };
+
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({
+                "event": "window_event",
+                "data": { "event": "moved", "label": "settings", "data": { "x": 100, "y": 50 } }
+            })
+        );
+    }
+
+    #[test]
+    fn event_serializes_name_and_data() {
+        let event = Event {
+            event: CONSOLE_DROPPED_EVENT,
+            data: json!({ "dropped": 12 }),
+        };
+
+        let parsed = serde_json::to_value(&event).unwrap();
+        assert_eq!(parsed["event"], "console_dropped");
+        assert_eq!(parsed["data"]["dropped"], 12);
+        assert!(parsed.get("id").is_none());
+    }
+
+    #[test]
+    fn topic_arg_is_required() {
+        let err = topic_arg(&json!({})).unwrap_err();
+        assert!(err.contains("Missing required 'topic'"));
+        assert_eq!(topic_arg(&json!({ "topic": "console" })).unwrap(), "console");
+    }
+
+    #[test]
+    fn event_arg_accepts_tauri_event_names() {
+        assert_eq!(
+            event_arg(&json!({ "event": "my-app://progress_1" })).unwrap(),
+            "my-app://progress_1"
+        );
+    }
+
+    #[test]
+    fn event_arg_rejects_invalid_names() {
+        assert!(event_arg(&json!({})).unwrap_err().contains("Missing required 'event'"));
+        assert!(event_arg(&json!({ "event": "" }))
+            .unwrap_err()
+            .contains("Invalid event name"));
+        assert!(event_arg(&json!({ "event": "has space" }))
+            .unwrap_err()
+            .contains("Invalid event name"));
+    }
+
+    #[test]
+    fn unsubscribe_inactive_event_reports_it() {
+        let mut subscriptions = Subscriptions::default();
+        let result = subscriptions
+            .unsubscribe_event(&json!({ "event": "my-app://progress" }))
+            .unwrap();
+        assert_eq!(result["wasSubscribed"], false);
+    }
+
+    #[test]
+    fn unsubscribe_inactive_topic_reports_it() {
+        let mut subscriptions = Subscriptions::default();
+        let result = subscriptions.unsubscribe(&json!({ "topic": "console" })).unwrap();
+        assert_eq!(result["wasSubscribed"], false);
+    }
+}
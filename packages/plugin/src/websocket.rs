@@ -1,21 +1,52 @@
 //! WebSocket server for MCP communication.
 //!
 //! Handles JSON-RPC-like requests from the MCP server and routes them to command handlers.
+//! Clients can also subscribe to topics to receive pushed event frames (see [`crate::subscriptions`]).
+//!
+//! A request with `"stream": true` in its args gets a large response as a series of
+//! `stream_chunk` frames followed by a `stream_end` frame, instead of one big text frame. The
+//! client joins the chunks' `data` in order and parses the result as the response.
+//!
+//! Each connection runs its requests one at a time, in the order they arrived. Requests wait in a
+//! queue of `Builder::request_queue_depth` entries, and get a "Server busy" error right away when
+//! it's full. Requests with the same `dedup_key` as one already queued or running skip the queue
+//! and get a copy of its response (see [`crate::dedup`]).
+//!
+//! When the server stops listening, on shutdown or for `server_restart` (see [`crate::restart`]),
+//! each open connection stops reading, answers the requests it already took, and closes.
 
 use std::net::SocketAddr;
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, PoisonError};
+use std::time::{Duration, Instant};
 
-use futures_util::{SinkExt, StreamExt};
+use futures_util::stream::SplitSink;
+use futures_util::{future, SinkExt, StreamExt};
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Runtime};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 use tokio::net::{TcpListener, TcpStream};
-use tokio::sync::{broadcast, oneshot, RwLock};
-use tokio::time::interval;
-use tokio_tungstenite::tungstenite::Message;
-use tracing::{debug, error, info};
+use tokio::sync::mpsc::error::TrySendError;
+use tokio::sync::{broadcast, mpsc, oneshot, watch, Mutex, RwLock};
+use tokio::time::{interval, MissedTickBehavior};
+use tokio_tungstenite::tungstenite::handshake::server::{
+    ErrorResponse, Request as HandshakeRequest, Response as HandshakeResponse,
+};
+use tokio_tungstenite::tungstenite::http::StatusCode;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+use tokio_tungstenite::tungstenite::protocol::{CloseFrame, WebSocketConfig};
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::WebSocketStream;
+use tracing::{debug, error, info, info_span, warn, Instrument, Span};
+use uuid::Uuid;
 
 use crate::commands;
+use crate::config::PluginConfig;
+use crate::dedup::{Dedup, InFlightRequests, Leader};
+use crate::origins::AllowedOrigins;
+use crate::restart;
+use crate::subscriptions::{self, EventSink, Subscriptions};
+
+/// Write half of a connection, shared by the response path, keep-alive pings, and subscriptions
+pub type WsWriter = Arc<RwLock<SplitSink<WebSocketStream<TcpStream>, Message>>>;
 
 /// Incoming request from the MCP server.
 ///
@@ -29,10 +60,13 @@ pub struct Request {
     /// Command-specific arguments
     #[serde(default)]
     pub args: serde_json::Value,
+    /// Requests with the same key share one run while it's in flight (see [`crate::dedup`])
+    #[serde(default)]
+    pub dedup_key: Option<String>,
 }
 
 /// Response sent back to the MCP server.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Response {
     /// Matches the request ID
     pub id: String,
@@ -47,12 +81,15 @@ pub struct Response {
     /// Info about the window that handled the request
     #[serde(skip_serializing_if = "Option::is_none", rename = "windowContext")]
     pub window_context: Option<WindowContext>,
+    /// ID of the connection the response went out on, set when it's sent
+    #[serde(skip_serializing_if = "Option::is_none", rename = "sessionId")]
+    pub session_id: Option<String>,
 }
 
 /// Metadata about the window that handled the request.
 ///
 /// Included in successful responses to help identify which window was used.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct WindowContext {
     /// Label of the window that handled the request
     #[serde(rename = "windowLabel")]
@@ -62,20 +99,136 @@ pub struct WindowContext {
     pub total_windows: usize,
 }
 
+/// Frame carrying part of a streamed response.
+///
+/// Chunks are slices of the serialized [`Response`], numbered from 0 in the order they're sent.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamFrame<'a> {
+    StreamChunk {
+        id: &'a str,
+        chunk: usize,
+        data: &'a str,
+    },
+    /// Sent after the last chunk
+    StreamEnd {
+        id: &'a str,
+        chunks: usize,
+    },
+}
+
 /// Server state shared across connections
 pub struct ServerState<R: Runtime> {
     pub app: AppHandle<R>,
+    /// Requests with a `dedup_key` that are queued or running, across all connections
+    pub in_flight: Arc<InFlightRequests>,
+    /// Becomes true when the server stops listening, so connections wind down
+    pub closing: watch::Receiver<bool>,
 }
 
-const PING_INTERVAL: Duration = Duration::from_secs(30);
-const DEFAULT_COMMAND_TIMEOUT_SECS: u64 = 10;
+/// When a connection last answered a ping, shared by its ping task and its reader.
+///
+/// Counts from when the connection opened, so a new connection has a whole grace period.
+struct LastPong {
+    connected_at: Instant,
+    /// When the last pong came, if one did
+    received_at: std::sync::Mutex<Option<Instant>>,
+}
+
+impl LastPong {
+    fn new() -> Self {
+        Self {
+            connected_at: Instant::now(),
+            received_at: std::sync::Mutex::new(None),
+        }
+    }
+
+    fn record(&self) {
+        *self.received_at.lock().unwrap_or_else(PoisonError::into_inner) = Some(Instant::now());
+    }
+
+    fn last(&self) -> Option<Instant> {
+        *self.received_at.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Time since the last pong, or since the connection opened if none came yet
+    fn elapsed(&self) -> Duration {
+        self.last().unwrap_or(self.connected_at).elapsed()
+    }
+
+    /// Whether a pong came after `instant`
+    fn received_since(&self, instant: Instant) -> bool {
+        self.last().is_some_and(|received_at| received_at > instant)
+    }
+}
+
+/// A request waiting in a connection's queue
+struct QueuedRequest {
+    request: Request,
+    /// Set if the request has a `dedup_key` and others may wait for its response
+    leader: Option<Leader>,
+}
+
+/// Ping the client every `ping_interval` until a ping can't be sent or pongs stop coming.
+///
+/// Sends to a peer that went away can keep succeeding for a while, since they only fill buffers,
+/// so missing pongs are what tells a dead connection apart. Gives up after two intervals without
+/// a pong, or after `ping_timeout` without the pong to the last ping if that's set.
+async fn keep_alive(write: WsWriter, peer: SocketAddr, config: PluginConfig, last_pong: Arc<LastPong>) {
+    let mut ticker = interval(config.ping_interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        let silence = last_pong.elapsed();
+        if silence > config.ping_interval * 2 {
+            info!("No pong from {peer} in {}ms, closing connection", silence.as_millis());
+            break;
+        }
+        let sent_at = Instant::now();
+        if let Err(e) = write.write().await.send(Message::Ping(vec![].into())).await {
+            info!("Failed to ping {peer}: {e}");
+            return;
+        }
+        if let Some(ping_timeout) = config.ping_timeout {
+            tokio::time::sleep(ping_timeout).await;
+            if !last_pong.received_since(sent_at) {
+                info!(
+                    "No pong from {peer} within {}ms, closing connection",
+                    ping_timeout.as_millis()
+                );
+                break;
+            }
+        }
+    }
+    let _ = write.write().await.send(Message::Close(None)).await;
+}
+
+/// Longest an open connection gets to answer the requests it took once the server stops listening
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Largest `data` in a `stream_chunk` frame, in bytes. Responses that fit in one chunk are sent as
+/// a plain response even if streaming was asked for.
+const STREAM_CHUNK_SIZE: usize = 256 * 1024;
 
-/// Get command timeout from `TAURI_MCP_TIMEOUT` env var (in ms) or default to 10s
-fn get_command_timeout() -> Duration {
-    std::env::var("TAURI_MCP_TIMEOUT")
-        .ok()
-        .and_then(|v| v.parse::<u64>().ok())
-        .map_or(Duration::from_secs(DEFAULT_COMMAND_TIMEOUT_SECS), Duration::from_millis)
+/// Event emitted to the app once the WebSocket server is listening, with `{ address, port }`
+pub const READY_EVENT: &str = "tauri-mcp://ready";
+
+/// Address the WebSocket server is listening on, managed as app state once it's bound.
+///
+/// With `Builder::port(0)`, this is how the app finds the port the OS picked:
+/// `app.state::<McpServerAddr>().get()`. It changes when `server_restart` moves the server.
+#[derive(Debug)]
+pub struct McpServerAddr(std::sync::RwLock<SocketAddr>);
+
+impl McpServerAddr {
+    /// The address the server is listening on
+    pub fn get(&self) -> SocketAddr {
+        *self.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn set(&self, addr: SocketAddr) {
+        *self.0.write().unwrap_or_else(PoisonError::into_inner) = addr;
+    }
 }
 
 /// Handle for shutting down the WebSocket server gracefully.
@@ -100,6 +253,12 @@ impl ShutdownHandle {
         // Ignore error if no receivers (server already stopped)
         let _ = self.sender.send(());
     }
+
+    /// Get a receiver for a server started after earlier signals, like one `server_restart` starts
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<()> {
+        self.sender.subscribe()
+    }
 }
 
 impl Default for ShutdownHandle {
@@ -113,17 +272,48 @@ pub async fn start_server<R: Runtime>(
     app: AppHandle<R>,
     port: u16,
     host: &str,
-    ready_tx: oneshot::Sender<()>,
-    mut shutdown_rx: broadcast::Receiver<()>,
+    ready_tx: oneshot::Sender<SocketAddr>,
+    shutdown_rx: broadcast::Receiver<()>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let addr = format!("{host}:{port}");
-    let listener = TcpListener::bind(&addr).await?;
+    let listener = listen(&app, port, host).await?;
+
+    // Signal that we're ready
+    let _ = ready_tx.send(listener.local_addr()?);
+
+    serve(app, listener, shutdown_rx).await;
+    Ok(())
+}
+
+/// Bind the server's listener, and tell the app the address it's on
+pub async fn listen<R: Runtime>(app: &AppHandle<R>, port: u16, host: &str) -> std::io::Result<TcpListener> {
+    let listener = TcpListener::bind(format!("{host}:{port}")).await?;
+    let addr = listener.local_addr()?;
     info!("WebSocket server listening on {addr}");
 
-    let state = Arc::new(ServerState { app });
+    // Let the app find the address, which has the actual port when port 0 was asked for
+    match app.try_state::<McpServerAddr>() {
+        Some(current) => current.set(addr),
+        None => {
+            app.manage(McpServerAddr(std::sync::RwLock::new(addr)));
+        }
+    }
+    if let Err(e) = app.emit(
+        READY_EVENT,
+        serde_json::json!({ "address": addr.to_string(), "port": addr.port() }),
+    ) {
+        warn!("Failed to emit {READY_EVENT}: {e}");
+    }
+    Ok(listener)
+}
 
-    // Signal that we're ready
-    let _ = ready_tx.send(());
+/// Accept connections until the shutdown signal, then let the open ones wind down
+pub async fn serve<R: Runtime>(app: AppHandle<R>, listener: TcpListener, mut shutdown_rx: broadcast::Receiver<()>) {
+    let (closing_tx, closing) = watch::channel(false);
+    let state = Arc::new(ServerState {
+        app,
+        in_flight: Arc::default(),
+        closing,
+    });
 
     loop {
         tokio::select! {
@@ -152,108 +342,353 @@ pub async fn start_server<R: Runtime>(
         }
     }
 
+    // Dropping the listener frees the address, while open connections finish their requests
+    drop(listener);
+    closing_tx.send_replace(true);
     info!("WebSocket server shut down gracefully");
-    Ok(())
 }
 
+/// Serve a connection under a new session ID, which its log lines and responses carry
 async fn handle_connection<R: Runtime>(
     stream: TcpStream,
     peer: SocketAddr,
     state: Arc<ServerState<R>>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let session_id: Arc<str> = Arc::from(Uuid::new_v4().to_string());
+    let span = info_span!("connection", session = %session_id, %peer);
+    serve_connection(stream, peer, state, session_id).instrument(span).await
+}
+
+/// Accept a connection's WebSocket handshake, refusing browsers from origins that aren't allowed
+#[allow(clippy::result_large_err)] // The error is the HTTP response tungstenite sends back
+pub async fn accept<R: Runtime>(
+    stream: TcpStream,
+    peer: SocketAddr,
+    app: &AppHandle<R>,
+    ws_config: WebSocketConfig,
+) -> Result<WebSocketStream<TcpStream>, WsError> {
+    let origins = app.try_state::<AllowedOrigins>().map(|origins| origins.inner().clone());
+    let check_origin = |request: &HandshakeRequest, response: HandshakeResponse| {
+        let origin = request.headers().get("origin").and_then(|origin| origin.to_str().ok());
+        if origins.as_ref().map_or(true, |origins| origins.allows(origin)) {
+            return Ok(response);
+        }
+        warn!(
+            "Refused connection from {peer}: origin '{}' isn't allowed",
+            origin.unwrap_or_default()
+        );
+        let mut refusal = ErrorResponse::new(Some("Origin not allowed".to_string()));
+        *refusal.status_mut() = StatusCode::FORBIDDEN;
+        Err(refusal)
+    };
+    tokio_tungstenite::accept_hdr_async_with_config(stream, check_origin, Some(ws_config)).await
+}
+
+async fn serve_connection<R: Runtime>(
+    stream: TcpStream,
+    peer: SocketAddr,
+    state: Arc<ServerState<R>>,
+    session_id: Arc<str>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     info!("New connection from {peer}");
 
-    let ws_stream = tokio_tungstenite::accept_async(stream).await?;
+    let config = commands::plugin_config(&state.app);
+
+    // Oversized messages fail when their header arrives, before their payload is buffered
+    let ws_config = WebSocketConfig::default()
+        .max_message_size(Some(config.max_message_bytes))
+        .max_frame_size(Some(config.max_message_bytes));
+    let ws_stream = accept(stream, peer, &state.app, ws_config).await?;
     let (write, read) = ws_stream.split();
-    let write = Arc::new(RwLock::new(write));
-
-    // Ping task for keep-alive
-    let write_ping = Arc::clone(&write);
-    let ping_task = tokio::spawn(async move {
-        let mut ticker = interval(PING_INTERVAL);
-        loop {
-            ticker.tick().await;
-            let mut w = write_ping.write().await;
-            if w.send(Message::Ping(vec![].into())).await.is_err() {
-                break;
-            }
-        }
-    });
+    let write: WsWriter = Arc::new(RwLock::new(write));
+
+    // Ping task for keep-alive. It ends when a ping can't be sent or pongs stop coming, which
+    // closes the connection.
+    let last_pong = Arc::new(LastPong::new());
+    let mut ping_task =
+        tokio::spawn(keep_alive(Arc::clone(&write), peer, config, Arc::clone(&last_pong)).instrument(Span::current()));
+
+    // Requests queue up here and run one at a time, in the order they arrived
+    let queue_depth = config.request_queue_depth.max(1);
+    let (queue_tx, queue_rx) = mpsc::channel::<QueuedRequest>(queue_depth);
+    let mut request_task = tokio::spawn(
+        run_requests(
+            queue_rx,
+            Arc::clone(&state),
+            Arc::clone(&write),
+            Arc::clone(&session_id),
+        )
+        .instrument(Span::current()),
+    );
 
     // Message handling
     let write_msg = Arc::clone(&write);
-    let message_task = read.for_each(|msg| {
-        let write = Arc::clone(&write_msg);
-        let state = Arc::clone(&state);
-        async move {
-            match msg {
-                Ok(Message::Text(text)) => {
-                    debug!("Received: {text}");
-                    let response = handle_request(&text, &state).await;
-                    let response_text =
-                        serde_json::to_string(&response).unwrap_or_else(|e| format!(r#"{{"error":"{e}"}}"#));
-                    let mut w = write.write().await;
-                    if let Err(e) = w.send(Message::Text(response_text.into())).await {
-                        error!("Failed to send response: {e}");
+    // The rest of an oversized message is still unread, so stop reading after one
+    let mut oversized = false;
+    let message_task = read
+        .take_while(move |msg| {
+            let keep_reading = !oversized;
+            oversized = matches!(msg, Err(WsError::Capacity(_)));
+            future::ready(keep_reading)
+        })
+        .for_each(|msg| {
+            let write = Arc::clone(&write_msg);
+            let state = Arc::clone(&state);
+            let queue_tx = queue_tx.clone();
+            let last_pong = Arc::clone(&last_pong);
+            let session_id = Arc::clone(&session_id);
+            async move {
+                match msg {
+                    Ok(Message::Text(text)) => {
+                        debug!("Received: {text}");
+                        let request: Request = match serde_json::from_str(&text) {
+                            Ok(r) => r,
+                            Err(e) => {
+                                let response = error_response(String::new(), format!("Invalid request JSON: {e}"));
+                                reply(&write, &session_id, response, false).await;
+                                return;
+                            }
+                        };
+
+                        let leader = match request.dedup_key.clone().map(|key| state.in_flight.join_or_lead(key)) {
+                            Some(Dedup::Join(receiver)) => {
+                                // Answer when the identical request does, without holding up the queue
+                                let joined = reply_joined(receiver, request, write, session_id);
+                                tokio::spawn(joined.instrument(Span::current()));
+                                return;
+                            }
+                            Some(Dedup::Lead(leader)) => Some(leader),
+                            None => None,
+                        };
+
+                        match queue_tx.try_send(QueuedRequest { request, leader }) {
+                            Ok(()) => {}
+                            Err(TrySendError::Full(queued)) => {
+                                // Answer right away rather than leaving the client waiting for a timeout
+                                let response = error_response(queued.request.id, "Server busy, queue full".to_string());
+                                reply(&write, &session_id, response, false).await;
+                            }
+                            Err(TrySendError::Closed(_)) => error!("Request queue of {peer} closed"),
+                        }
+                    }
+                    Ok(Message::Pong(_)) => {
+                        debug!("Received pong from {peer}");
+                        last_pong.record();
                     }
+                    Ok(Message::Close(_)) => info!("Client {peer} disconnected"),
+                    Err(WsError::Capacity(e)) => {
+                        info!("Closing connection from {peer}: {e}");
+                        let close = CloseFrame {
+                            code: CloseCode::Size,
+                            reason: e.to_string().into(),
+                        };
+                        let _ = write.write().await.send(Message::Close(Some(close))).await;
+                    }
+                    Err(e) => error!("WebSocket error: {e}"),
+                    _ => {}
                 }
-                Ok(Message::Pong(_)) => debug!("Received pong from {peer}"),
-                Ok(Message::Close(_)) => info!("Client {peer} disconnected"),
-                Err(e) => error!("WebSocket error: {e}"),
-                _ => {}
             }
-        }
-    });
-
-    message_task.await;
+        });
+
+    // The connection ends when the client goes away or stops answering pings, or when the server
+    // stops listening
+    let mut closing = state.closing.clone();
+    let server_stopped = tokio::select! {
+        () = message_task => false,
+        _ = &mut ping_task => false,
+        _ = closing.wait_for(|closing| *closing) => true,
+    };
+    if server_stopped {
+        // Take no more requests, but answer the ones already taken before closing
+        drop(queue_tx);
+        wind_down(peer, &write, &mut request_task).await;
+    }
     ping_task.abort();
+    // Skip queued requests nobody is waiting for, and stop streaming to this connection
+    request_task.abort();
 
     info!("Connection closed from {peer}");
     Ok(())
 }
 
-async fn handle_request<R: Runtime>(text: &str, state: &ServerState<R>) -> Response {
-    let request: Request = match serde_json::from_str(text) {
-        Ok(r) => r,
-        Err(e) => {
-            return Response {
-                id: String::new(),
-                success: false,
-                data: None,
-                error: Some(format!("Invalid request JSON: {e}")),
-                window_context: None,
-            };
+/// Wait for a connection's taken requests to be answered, then close it
+async fn wind_down(peer: SocketAddr, write: &WsWriter, request_task: &mut tokio::task::JoinHandle<()>) {
+    info!("Server stopped listening, closing connection from {peer} once its requests are answered");
+    if tokio::time::timeout(DRAIN_TIMEOUT, request_task).await.is_err() {
+        warn!(
+            "Requests from {peer} took over {}s after the server stopped, dropping them",
+            DRAIN_TIMEOUT.as_secs()
+        );
+    }
+    let close = CloseFrame {
+        code: CloseCode::Away,
+        reason: "Server stopped listening".into(),
+    };
+    let _ = write.write().await.send(Message::Close(Some(close))).await;
+}
+
+/// Run a connection's queued requests one at a time, in the order they arrived
+async fn run_requests<R: Runtime>(
+    mut queue: mpsc::Receiver<QueuedRequest>,
+    state: Arc<ServerState<R>>,
+    write: WsWriter,
+    session_id: Arc<str>,
+) {
+    // Dropped with the task when the connection closes, which ends the subscriptions
+    let subscriptions = Mutex::new(Subscriptions::default());
+    let events = EventSink::WebSocket(Arc::clone(&write));
+    while let Some(QueuedRequest { request, leader }) = queue.recv().await {
+        let stream = stream_arg(&request.args);
+        let response = handle_request(request, &state, &subscriptions, &events).await;
+        if let Some(leader) = leader {
+            leader.finish(&response);
         }
+        reply(&write, &session_id, response, stream).await;
+    }
+}
+
+/// Send a response with the connection's session ID, logging failures since there's no one
+/// else to tell
+async fn reply(write: &WsWriter, session_id: &str, mut response: Response, stream: bool) {
+    response.session_id = Some(session_id.to_string());
+    let response_text = serde_json::to_string(&response).unwrap_or_else(|e| format!(r#"{{"error":"{e}"}}"#));
+    if let Err(e) = send_response(write, &response.id, response_text, stream).await {
+        error!("Failed to send response: {e}");
+    }
+}
+
+/// Answer a request with the response of the in-flight request it joined, under its own ID
+async fn reply_joined(
+    mut receiver: broadcast::Receiver<Response>,
+    request: Request,
+    write: WsWriter,
+    session_id: Arc<str>,
+) {
+    let response = match receiver.recv().await {
+        Ok(response) => Response {
+            id: request.id,
+            ..response
+        },
+        Err(_) => error_response(
+            request.id,
+            "The request with the same dedup_key was dropped before it finished".to_string(),
+        ),
     };
+    let stream = stream_arg(&request.args);
+    reply(&write, &session_id, response, stream).await;
+}
+
+/// Send a serialized response, as stream frames if the client asked for them and it's large
+async fn send_response(
+    write: &WsWriter,
+    id: &str,
+    response_text: String,
+    stream: bool,
+) -> Result<(), tokio_tungstenite::tungstenite::Error> {
+    if !stream || response_text.len() <= STREAM_CHUNK_SIZE {
+        return write.write().await.send(Message::Text(response_text.into())).await;
+    }
+
+    let chunks = split_chunks(&response_text, STREAM_CHUNK_SIZE);
+    let frames = chunks
+        .iter()
+        .enumerate()
+        .map(|(chunk, data)| StreamFrame::StreamChunk { id, chunk, data })
+        .chain(std::iter::once(StreamFrame::StreamEnd {
+            id,
+            chunks: chunks.len(),
+        }));
+    for frame in frames {
+        let Ok(text) = serde_json::to_string(&frame) else {
+            continue;
+        };
+        // Take the lock per frame, so pings and event frames can go out in between
+        write.write().await.send(Message::Text(text.into())).await?;
+    }
+    Ok(())
+}
+
+/// Split text into pieces of at most `size` bytes, without splitting characters
+fn split_chunks(text: &str, size: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let mut end = size.min(rest.len());
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = tail;
+    }
+    chunks
+}
+
+/// Failed response for a request that never reached a command
+const fn error_response(id: String, error: String) -> Response {
+    Response {
+        id,
+        success: false,
+        data: None,
+        error: Some(error),
+        window_context: None,
+        session_id: None,
+    }
+}
+
+/// Whether the request asks for its response to be streamed
+fn stream_arg(args: &serde_json::Value) -> bool {
+    args.get("stream").and_then(serde_json::Value::as_bool).unwrap_or(false)
+}
 
+/// Run a request and build its response
+async fn handle_request<R: Runtime>(
+    request: Request,
+    state: &ServerState<R>,
+    subscriptions: &Mutex<Subscriptions>,
+    events: &EventSink,
+) -> Response {
     let id = request.id.clone();
 
-    // Execute command with timeout
-    let timeout = get_command_timeout();
-    let result = tokio::time::timeout(timeout, commands::execute(&state.app, request)).await;
+    let result = match request.command.as_str() {
+        // Subscriptions belong to the connection, so they're handled here rather than in `commands`
+        subscriptions::SUBSCRIBE_COMMAND => subscriptions
+            .lock()
+            .await
+            .subscribe(&state.app, &request.args, events)
+            .map(|data| (data, None)),
+        subscriptions::UNSUBSCRIBE_COMMAND => subscriptions
+            .lock()
+            .await
+            .unsubscribe(&request.args)
+            .map(|data| (data, None)),
+        subscriptions::EVENT_SUBSCRIBE_COMMAND => subscriptions
+            .lock()
+            .await
+            .subscribe_event(&state.app, &request.args, events)
+            .map(|data| (data, None)),
+        subscriptions::EVENT_UNSUBSCRIBE_COMMAND => subscriptions
+            .lock()
+            .await
+            .unsubscribe_event(&request.args)
+            .map(|data| (data, None)),
+        // Restarting stops the server this request came through, so it's handled here too
+        restart::RESTART_COMMAND => restart::restart(&state.app, &request.args)
+            .await
+            .map(|data| (data, None)),
+        _ => commands::execute_with_timeout(&state.app, request).await,
+    };
 
     match result {
-        Ok(Ok((data, context))) => Response {
+        Ok((data, context)) => Response {
             id,
             success: true,
             data: Some(data),
             error: None,
             window_context: context,
+            session_id: None,
         },
-        Ok(Err(e)) => Response {
-            id,
-            success: false,
-            data: None,
-            error: Some(e),
-            window_context: None,
-        },
-        Err(_) => Response {
-            id,
-            success: false,
-            data: None,
-            error: Some(format!("Command timed out after {}ms", timeout.as_millis())),
-            window_context: None,
-        },
+        Err(e) => error_response(id, e),
     }
 }
 
@@ -263,6 +698,31 @@ mod tests {
     use super::*;
     use serde_json::json;
 
+    #[test]
+    fn a_new_connection_has_not_answered_its_first_ping() {
+        let last_pong = LastPong::new();
+        // The first ping goes out as soon as the connection opens
+        assert!(!last_pong.received_since(last_pong.connected_at));
+        assert!(!last_pong.received_since(Instant::now()));
+
+        // A pong from before a ping doesn't answer it, even within the same millisecond
+        last_pong.record();
+        assert!(!last_pong.received_since(Instant::now()));
+    }
+
+    #[test]
+    fn last_pong_tracks_pongs_after_a_ping() {
+        let last_pong = LastPong::new();
+        std::thread::sleep(Duration::from_millis(5));
+        let ping_sent = Instant::now();
+        assert!(!last_pong.received_since(ping_sent));
+        assert!(last_pong.elapsed() >= Duration::from_millis(5));
+
+        last_pong.record();
+        assert!(last_pong.received_since(ping_sent));
+        assert!(last_pong.elapsed() < Duration::from_millis(5));
+    }
+
     // Request deserialization tests
 
     #[test]
@@ -350,6 +810,7 @@ mod tests {
             data: Some(json!({"title": "My app"})),
             error: None,
             window_context: None,
+            session_id: None,
         };
 
         let json = serde_json::to_string(&response).unwrap();
@@ -370,6 +831,7 @@ mod tests {
             data: None,
             error: Some("Element not found: .submit-btn".to_string()),
             window_context: None,
+            session_id: None,
         };
 
         let json = serde_json::to_string(&response).unwrap();
@@ -392,6 +854,7 @@ mod tests {
                 window_label: "main".to_string(),
                 total_windows: 2,
             }),
+            session_id: Some("5f0c1a2e-5d7b-4c39-9a51-3c2f8e4b7d10".to_string()),
         };
 
         let json = serde_json::to_string(&response).unwrap();
@@ -399,6 +862,7 @@ mod tests {
 
         assert_eq!(parsed["windowContext"]["windowLabel"], "main");
         assert_eq!(parsed["windowContext"]["totalWindows"], 2);
+        assert_eq!(parsed["sessionId"], "5f0c1a2e-5d7b-4c39-9a51-3c2f8e4b7d10");
     }
 
     #[test]
@@ -409,6 +873,7 @@ mod tests {
             data: None,
             error: None,
             window_context: None,
+            session_id: None,
         };
 
         let json = serde_json::to_string(&response).unwrap();
@@ -420,6 +885,71 @@ mod tests {
         assert!(parsed.get("windowContext").is_none());
     }
 
+    // Streaming tests
+
+    #[test]
+    fn split_chunks_keeps_order_and_size() {
+        let text = "a".repeat(10);
+        let chunks = split_chunks(&text, 4);
+        assert_eq!(chunks, vec!["aaaa", "aaaa", "aa"]);
+        assert_eq!(chunks.concat(), text);
+        assert!(split_chunks("", 4).is_empty());
+    }
+
+    #[test]
+    fn split_chunks_never_splits_characters() {
+        // Each character is 3 bytes, so a 4-byte chunk only fits one
+        let text = "\u{4e2d}\u{6587}\u{5b57}";
+        let chunks = split_chunks(text, 4);
+        assert_eq!(chunks, vec!["\u{4e2d}", "\u{6587}", "\u{5b57}"]);
+    }
+
+    #[test]
+    fn stream_frames_serialize_with_type() {
+        let chunk = StreamFrame::StreamChunk {
+            id: "req_1",
+            chunk: 2,
+            data: "iVBOR",
+        };
+        assert_eq!(
+            serde_json::to_value(&chunk).unwrap(),
+            json!({"type": "stream_chunk", "id": "req_1", "chunk": 2, "data": "iVBOR"})
+        );
+        let end = StreamFrame::StreamEnd { id: "req_1", chunks: 3 };
+        assert_eq!(
+            serde_json::to_value(&end).unwrap(),
+            json!({"type": "stream_end", "id": "req_1", "chunks": 3})
+        );
+    }
+
+    #[test]
+    fn error_response_has_no_data() {
+        let response = error_response("req_7".to_string(), "Server busy, queue full".to_string());
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(
+            json,
+            json!({"id": "req_7", "success": false, "error": "Server busy, queue full"})
+        );
+    }
+
+    #[test]
+    fn request_parses_dedup_key() {
+        let request: Request =
+            serde_json::from_str(r#"{"id": "req_1", "command": "window_list", "dedup_key": "windows"}"#).unwrap();
+        assert_eq!(request.dedup_key.as_deref(), Some("windows"));
+
+        let request: Request = serde_json::from_str(r#"{"id": "req_2", "command": "window_list"}"#).unwrap();
+        assert_eq!(request.dedup_key, None);
+    }
+
+    #[test]
+    fn stream_arg_defaults_to_false() {
+        assert!(stream_arg(&json!({"stream": true})));
+        assert!(!stream_arg(&json!({"stream": "yes"})));
+        assert!(!stream_arg(&json!({})));
+        assert!(!stream_arg(&json!(null)));
+    }
+
     // WindowContext serialization tests
 
     #[test]
@@ -453,6 +983,7 @@ mod tests {
             data: Some(json!({"width": 800, "height": 600})),
             error: None,
             window_context: None,
+            session_id: None,
         };
 
         let response_json = serde_json::to_string(&response).unwrap();
@@ -482,6 +1013,7 @@ mod tests {
             data: Some(json!(large_string)),
             error: None,
             window_context: None,
+            session_id: None,
         };
 
         let json = serde_json::to_string(&response).unwrap();
@@ -0,0 +1,355 @@
+//! Window lookup by label, alias, or glob pattern.
+//!
+//! `windowId` can be an exact label, an alias registered with
+//! [`Builder::window_alias`](crate::Builder::window_alias), or a glob pattern like `main-*`
+//! (`*` matches any run of characters, `?` one character). When several windows match, the
+//! focused one wins, then the one created first.
+//!
+//! The registry also broadcasts window lifecycle events, for clients subscribed to the
+//! `windows` topic (see [`crate::subscriptions`]).
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tauri::WindowEvent;
+use tokio::sync::broadcast;
+
+/// Lifecycle events a window can have, as named in `window_event` frames
+pub const WINDOW_EVENT_KINDS: &[&str] = &["created", "closed", "focused", "blurred", "moved", "resized"];
+
+/// How many lifecycle events a subscriber can fall behind before it misses some
+const LIFECYCLE_HIGH_WATER_MARK: usize = 256;
+
+/// Window aliases and creation order, managed as app state
+#[derive(Debug)]
+pub struct WindowRegistry {
+    /// Alias to label (or pattern)
+    aliases: HashMap<String, String>,
+    /// Labels of open webviews, oldest first
+    order: Mutex<Vec<String>>,
+    /// Lifecycle events, for subscribed connections
+    lifecycle: broadcast::Sender<LifecycleEvent>,
+}
+
+impl Default for WindowRegistry {
+    fn default() -> Self {
+        Self::new([])
+    }
+}
+
+/// A window opening, closing, gaining or losing focus, moving, or resizing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LifecycleEvent {
+    /// One of [`WINDOW_EVENT_KINDS`]
+    pub event: &'static str,
+    /// Label of the window
+    pub label: String,
+    /// The new position or size in physical pixels for `moved` and `resized`, empty otherwise
+    pub data: Value,
+}
+
+impl LifecycleEvent {
+    /// The lifecycle event for a Tauri window event, or `None` for events that aren't about the
+    /// window's lifecycle, like file drops
+    pub fn from_window_event(label: &str, event: &WindowEvent) -> Option<Self> {
+        let (kind, data) = match event {
+            WindowEvent::Destroyed => ("closed", json!({})),
+            WindowEvent::Focused(true) => ("focused", json!({})),
+            WindowEvent::Focused(false) => ("blurred", json!({})),
+            WindowEvent::Moved(position) => ("moved", json!({ "x": position.x, "y": position.y })),
+            WindowEvent::Resized(size) => ("resized", json!({ "width": size.width, "height": size.height })),
+            _ => return None,
+        };
+        Some(Self {
+            event: kind,
+            label: label.to_string(),
+            data,
+        })
+    }
+}
+
+/// A window that a query can resolve to
+#[derive(Debug, Clone, Copy)]
+pub struct Candidate<'a> {
+    /// Window label
+    pub label: &'a str,
+    /// Whether the window has focus
+    pub focused: bool,
+}
+
+impl WindowRegistry {
+    /// Create a registry with the given aliases
+    pub fn new(aliases: impl IntoIterator<Item = (String, String)>) -> Self {
+        Self {
+            aliases: aliases.into_iter().collect(),
+            order: Mutex::default(),
+            lifecycle: broadcast::channel(LIFECYCLE_HIGH_WATER_MARK).0,
+        }
+    }
+
+    /// Record a new webview, so it sorts after the existing ones
+    pub fn created(&self, label: &str) {
+        let mut order = self.lock();
+        if !order.iter().any(|known| known == label) {
+            order.push(label.to_string());
+        }
+        drop(order);
+        self.broadcast(LifecycleEvent {
+            event: "created",
+            label: label.to_string(),
+            data: json!({}),
+        });
+    }
+
+    /// Labels of the open webviews, oldest first
+    pub fn labels(&self) -> Vec<String> {
+        self.lock().clone()
+    }
+
+    /// Forget a destroyed window
+    pub fn destroyed(&self, label: &str) {
+        self.lock().retain(|known| known != label);
+    }
+
+    /// Handle a Tauri window event: forget destroyed windows, and tell subscribers about
+    /// lifecycle changes
+    pub fn window_event(&self, label: &str, event: &WindowEvent) {
+        if matches!(event, WindowEvent::Destroyed) {
+            self.destroyed(label);
+        }
+        if let Some(lifecycle) = LifecycleEvent::from_window_event(label, event) {
+            self.broadcast(lifecycle);
+        }
+    }
+
+    /// Receive lifecycle events from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<LifecycleEvent> {
+        self.lifecycle.subscribe()
+    }
+
+    fn broadcast(&self, event: LifecycleEvent) {
+        // No receivers just means nobody is subscribed
+        let _ = self.lifecycle.send(event);
+    }
+
+    /// Pick the window a `windowId` refers to, or the default window if `None`.
+    ///
+    /// The default is the focused window, then the one created first.
+    pub fn resolve<'a>(&self, query: Option<&str>, candidates: &[Candidate<'a>]) -> Result<&'a str, String> {
+        if candidates.is_empty() {
+            return Err("No windows available".to_string());
+        }
+
+        let Some(query) = query else {
+            return Ok(self.pick(candidates.iter()).unwrap_or(candidates[0].label));
+        };
+
+        if let Some(exact) = candidates.iter().find(|candidate| candidate.label == query) {
+            return Ok(exact.label);
+        }
+
+        let pattern = self.aliases.get(query).map_or(query, String::as_str);
+        let matches = candidates
+            .iter()
+            .filter(|candidate| glob_match(pattern, candidate.label));
+
+        self.pick(matches).ok_or_else(|| {
+            let mut available = candidates.iter().map(|candidate| candidate.label).collect::<Vec<_>>();
+            available.sort_unstable();
+            let mut message = format!("Window '{query}' not found. Available: {}", available.join(", "));
+            if !self.aliases.is_empty() {
+                let mut aliases = self.aliases.keys().map(String::as_str).collect::<Vec<_>>();
+                aliases.sort_unstable();
+                let _ = write!(message, ". Aliases: {}", aliases.join(", "));
+            }
+            message
+        })
+    }
+
+    /// The focused candidate, or else the oldest. Labels the registry hasn't seen come last,
+    /// sorted by label.
+    fn pick<'a, 'b>(&self, candidates: impl Iterator<Item = &'b Candidate<'a>>) -> Option<&'a str>
+    where
+        'a: 'b,
+    {
+        let order = self.lock();
+        let position = |label: &str| order.iter().position(|known| known == label).unwrap_or(usize::MAX);
+        candidates
+            .min_by(|a, b| {
+                b.focused
+                    .cmp(&a.focused)
+                    .then_with(|| position(a.label).cmp(&position(b.label)))
+                    .then_with(|| a.label.cmp(b.label))
+            })
+            .map(|candidate| candidate.label)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<String>> {
+        self.order.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Match a label against a glob pattern with `*` (any run of characters) and `?` (one character).
+/// A pattern without wildcards only matches itself.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+
+    // Greedy matching with backtracking to the last `*`
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use tauri::{PhysicalPosition, PhysicalSize, Theme};
+
+    fn candidates<'a>(labels: &[&'a str], focused: Option<&str>) -> Vec<Candidate<'a>> {
+        labels
+            .iter()
+            .map(|label| Candidate {
+                label,
+                focused: Some(*label) == focused,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn glob_match_handles_wildcards() {
+        assert!(glob_match("main-*", "main-tab-1"));
+        assert!(glob_match("*-tab-?", "main-tab-1"));
+        assert!(glob_match("*", "anything"));
+        assert!(glob_match("main", "main"));
+        assert!(!glob_match("main", "main-tab-1"));
+        assert!(!glob_match("main-?", "main-10"));
+        assert!(!glob_match("settings-*", "main-tab-1"));
+    }
+
+    #[test]
+    fn resolve_prefers_exact_label() {
+        let registry = WindowRegistry::new([("main-*".to_string(), "settings".to_string())]);
+        let windows = candidates(&["main-*", "settings"], None);
+        assert_eq!(registry.resolve(Some("main-*"), &windows).unwrap(), "main-*");
+    }
+
+    #[test]
+    fn resolve_follows_aliases() {
+        let registry = WindowRegistry::new([("editor".to_string(), "main-tab-*".to_string())]);
+        let windows = candidates(&["about", "main-tab-1"], None);
+        assert_eq!(registry.resolve(Some("editor"), &windows).unwrap(), "main-tab-1");
+    }
+
+    #[test]
+    fn resolve_glob_prefers_focused_then_oldest() {
+        let registry = WindowRegistry::default();
+        registry.created("main-tab-2");
+        registry.created("main-tab-1");
+        let windows = candidates(&["main-tab-1", "main-tab-2", "about"], None);
+        assert_eq!(registry.resolve(Some("main-*"), &windows).unwrap(), "main-tab-2");
+
+        let windows = candidates(&["main-tab-1", "main-tab-2", "about"], Some("main-tab-1"));
+        assert_eq!(registry.resolve(Some("main-*"), &windows).unwrap(), "main-tab-1");
+
+        // A focused window that doesn't match the pattern doesn't count
+        let windows = candidates(&["main-tab-1", "main-tab-2", "about"], Some("about"));
+        assert_eq!(registry.resolve(Some("main-*"), &windows).unwrap(), "main-tab-2");
+    }
+
+    #[test]
+    fn resolve_default_uses_creation_order() {
+        let registry = WindowRegistry::default();
+        registry.created("second");
+        registry.created("first");
+        registry.destroyed("second");
+        let windows = candidates(&["second", "first"], None);
+        assert_eq!(registry.resolve(None, &windows).unwrap(), "first");
+    }
+
+    #[test]
+    fn lifecycle_events_from_window_events() {
+        let moved = LifecycleEvent::from_window_event("main", &WindowEvent::Moved(PhysicalPosition::new(10, -20)));
+        assert_eq!(
+            moved,
+            Some(LifecycleEvent {
+                event: "moved",
+                label: "main".to_string(),
+                data: json!({ "x": 10, "y": -20 }),
+            })
+        );
+
+        let resized = LifecycleEvent::from_window_event("main", &WindowEvent::Resized(PhysicalSize::new(800, 600)));
+        assert_eq!(resized.unwrap().data, json!({ "width": 800, "height": 600 }));
+
+        let kind = |event: &WindowEvent| LifecycleEvent::from_window_event("main", event).map(|e| e.event);
+        assert_eq!(kind(&WindowEvent::Focused(true)), Some("focused"));
+        assert_eq!(kind(&WindowEvent::Focused(false)), Some("blurred"));
+        assert_eq!(kind(&WindowEvent::Destroyed), Some("closed"));
+        assert_eq!(kind(&WindowEvent::ThemeChanged(Theme::Dark)), None);
+    }
+
+    #[test]
+    fn registry_broadcasts_lifecycle_events() {
+        let registry = WindowRegistry::default();
+        let mut rx = registry.subscribe();
+        registry.created("settings");
+        registry.window_event("settings", &WindowEvent::Focused(true));
+        registry.window_event("settings", &WindowEvent::Destroyed);
+
+        let events = std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|event| (event.event, event.label))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            events,
+            [
+                ("created", "settings".to_string()),
+                ("focused", "settings".to_string()),
+                ("closed", "settings".to_string()),
+            ]
+        );
+
+        // The closed window is forgotten
+        registry.created("main");
+        let windows = candidates(&["settings", "main"], None);
+        assert_eq!(registry.resolve(None, &windows).unwrap(), "main");
+    }
+
+    #[test]
+    fn resolve_reports_labels_and_aliases() {
+        let registry = WindowRegistry::new([("editor".to_string(), "main".to_string())]);
+        let windows = candidates(&["main", "about"], None);
+        let err = registry.resolve(Some("settings-*"), &windows).unwrap_err();
+        assert_eq!(
+            err,
+            "Window 'settings-*' not found. Available: about, main. Aliases: editor"
+        );
+
+        let err = registry.resolve(None, &[]).unwrap_err();
+        assert_eq!(err, "No windows available");
+    }
+}
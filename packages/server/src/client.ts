@@ -13,6 +13,8 @@ export interface PluginRequest {
   id: string;
   command: string;
   args?: Record<string, unknown>;
+  /** Requests with the same key share one run while the first is in flight */
+  dedup_key?: string;
 }
 
 export interface PluginResponse {
@@ -20,6 +22,8 @@ export interface PluginResponse {
   success: boolean;
   data?: unknown;
   error?: string;
+  /** ID the plugin gave this connection, the same in its log lines */
+  sessionId?: string;
   windowContext?: {
     windowLabel: string;
     totalWindows: number;
@@ -27,10 +31,20 @@ export interface PluginResponse {
   };
 }
 
+/**
+ * Frames a streamed response arrives in, for requests sent with `stream: true`.
+ * Chunks are slices of the serialized response, to be joined in order.
+ */
+type StreamFrame =
+  | { type: "stream_chunk"; id: string; chunk: number; data: string }
+  | { type: "stream_end"; id: string; chunks: number };
+
 interface PendingRequest {
   resolve: (response: PluginResponse) => void;
   reject: (error: Error) => void;
   timeout: ReturnType<typeof setTimeout>;
+  /** Chunks of a streamed response received so far */
+  chunks: string[];
 }
 
 interface ClientState {
@@ -42,6 +56,8 @@ interface ClientState {
   shouldReconnect: boolean;
   pingInterval: ReturnType<typeof setInterval> | null;
   reconnectTimeout: ReturnType<typeof setTimeout> | null;
+  /** Session ID from the latest response, reset when the connection closes */
+  sessionId: string | null;
 }
 
 // ============================================================================
@@ -71,19 +87,55 @@ const buildWebSocketUrl = (host: string, port: number): string =>
 const generateRequestId = (): string =>
   `req_${Date.now()}_${Math.random().toString(36).slice(2, 11)}`;
 
+const resolvePending = (id: string, response: PluginResponse): void => {
+  const pending = clientState?.pendingRequests.get(id);
+  if (!pending) return;
+  clearTimeout(pending.timeout);
+  clientState?.pendingRequests.delete(id);
+  if (clientState && response.sessionId) clientState.sessionId = response.sessionId;
+  pending.resolve(response);
+};
+
+const handleStreamFrame = (frame: StreamFrame): void => {
+  const pending = clientState?.pendingRequests.get(frame.id);
+  if (!pending) return;
+
+  if (frame.type === "stream_chunk") {
+    pending.chunks[frame.chunk] = frame.data;
+    return;
+  }
+
+  const { chunks } = pending;
+  clearTimeout(pending.timeout);
+  clientState?.pendingRequests.delete(frame.id);
+  const received = chunks.filter((chunk) => chunk !== undefined).length;
+  if (received !== frame.chunks) {
+    pending.reject(
+      new Error(`Streamed response was incomplete: got ${received} of ${frame.chunks} chunks`)
+    );
+    return;
+  }
+  let response: PluginResponse;
+  try {
+    response = JSON.parse(chunks.join("")) as PluginResponse;
+  } catch {
+    pending.reject(new Error("Streamed response wasn't valid JSON"));
+    return;
+  }
+  if (clientState && response.sessionId) clientState.sessionId = response.sessionId;
+  pending.resolve(response);
+};
+
 const handleMessage = (data: WebSocket.Data): void => {
   if (!clientState) return;
 
   try {
-    const message = JSON.parse(data.toString()) as PluginResponse;
-
-    if (message.id && clientState.pendingRequests.has(message.id)) {
-      const pending = clientState.pendingRequests.get(message.id);
-      if (pending) {
-        clearTimeout(pending.timeout);
-        clientState.pendingRequests.delete(message.id);
-        pending.resolve(message);
-      }
+    const message = JSON.parse(data.toString()) as PluginResponse | StreamFrame;
+
+    if ("type" in message && (message.type === "stream_chunk" || message.type === "stream_end")) {
+      handleStreamFrame(message);
+    } else if (message.id && clientState.pendingRequests.has(message.id)) {
+      resolvePending(message.id, message as PluginResponse);
     }
   } catch {
     // Failed to parse message - ignore
@@ -107,6 +159,7 @@ const handleClose = (): void => {
   }
 
   clientState.ws = null;
+  clientState.sessionId = null;
 
   // Auto-reconnect if enabled
   if (
@@ -178,6 +231,7 @@ export const connect = async (
       shouldReconnect: true,
       pingInterval: null,
       reconnectTimeout: null,
+      sessionId: null,
     };
 
     ws.on("open", () => {
@@ -244,14 +298,23 @@ export const getConnectionInfo = (): {
   connected: boolean;
   host: string | null;
   port: number | null;
+  sessionId: string | null;
 } => ({
   connected: isConnected(),
   host: clientState?.host ?? null,
   port: clientState?.port ?? null,
+  sessionId: clientState?.sessionId ?? null,
 });
 
+/**
+ * Read-only commands that are slow enough to be worth running once when the same request is sent
+ * again before the first one finished.
+ */
+const DEDUPLICATED_COMMANDS = new Set(["screenshot", "screenshot_composite", "dom_snapshot", "window_list"]);
+
 /**
  * Send a command to the plugin and wait for response.
+ * Large responses come back in chunks if `args.stream` is true, and are put back together here.
  */
 export const sendCommand = async (
   command: string,
@@ -266,6 +329,9 @@ export const sendCommand = async (
 
   const id = generateRequestId();
   const request: PluginRequest = { id, command, args };
+  if (DEDUPLICATED_COMMANDS.has(command)) {
+    request.dedup_key = `${command}:${JSON.stringify(args ?? {})}`;
+  }
 
   return new Promise((resolve, reject) => {
     const timeout = setTimeout(() => {
@@ -273,7 +339,7 @@ export const sendCommand = async (
       reject(new Error(`Request timed out after ${timeoutMs}ms`));
     }, timeoutMs);
 
-    clientState!.pendingRequests.set(id, { resolve, reject, timeout });
+    clientState!.pendingRequests.set(id, { resolve, reject, timeout, chunks: [] });
 
     clientState!.ws!.send(JSON.stringify(request), (error) => {
       if (error) {
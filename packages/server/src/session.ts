@@ -20,6 +20,8 @@ export interface SessionStatus {
   app: string | null;
   host: string | null;
   port: number | null;
+  /** Plugin's ID for the connection, to find its log lines */
+  sessionId: string | null;
 }
 
 interface SessionState {
@@ -85,6 +87,16 @@ export const startSession = async (
   }
 };
 
+/**
+ * Reconnect to the same app on another port, after tauri_server_restart moved its server.
+ * The plugin closes the old connection once it has answered, so this doesn't wait for that.
+ */
+export const moveSession = async (port: number): Promise<void> => {
+  const { host } = getConnectionInfo();
+  disconnect();
+  await connect(host ?? "localhost", port);
+};
+
 /**
  * Stop the current session.
  */
@@ -109,6 +121,7 @@ export const getSessionStatus = (): SessionStatus => {
     app: connectionInfo.connected ? (sessionState.appName ?? "Tauri App") : null,
     host: connectionInfo.host,
     port: connectionInfo.port,
+    sessionId: connectionInfo.sessionId,
   };
 };
 
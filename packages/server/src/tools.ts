@@ -10,6 +10,7 @@ import {
   stopSession,
   getSessionStatus,
   ensureSession,
+  moveSession,
 } from "./session.js";
 
 // ============================================================================
@@ -42,6 +43,22 @@ export interface ToolDefinition {
 // Schemas
 // ============================================================================
 
+/** A CSS selector string, or an object picking elements by CSS, XPath, text, ARIA role and name, or snapshot ref */
+const selectorSchema = z.union([
+  z.string(),
+  z.object({ css: z.string() }).strict(),
+  z.object({ xpath: z.string() }).strict(),
+  z.object({ text: z.string(), exact: z.boolean().optional() }).strict(),
+  z.object({ role: z.string(), name: z.string().optional(), exact: z.boolean().optional() }).strict(),
+  z.object({ ref: z.string() }).strict(),
+]);
+
+/** How selector objects match, for argument descriptions */
+const SELECTOR_HELP =
+  "a CSS selector, or { css }, { xpath }, { text, exact }, { role, name, exact }, or { ref } from tauri_dom_snapshot. " +
+  "Text and names match case-insensitive substrings unless exact; selector objects must match exactly one element. " +
+  "In CSS, 'host >>> inner' matches inner inside host's open shadow roots";
+
 const sessionSchema = z.object({
   action: z
     .enum(["start", "stop", "status"])
@@ -50,6 +67,20 @@ const sessionSchema = z.object({
   port: z.number().optional().describe("Port number (default: 9223)"),
 });
 
+const serverRestartSchema = z.object({
+  port: z
+    .number()
+    .int()
+    .min(0)
+    .max(65535)
+    .optional()
+    .describe("Port to listen on instead, or 0 to let the OS pick a free one (default: the current port)"),
+  host: z
+    .string()
+    .optional()
+    .describe("Address to bind instead, like localhost or 0.0.0.0 (default: the current one)"),
+});
+
 const screenshotSchema = z.object({
   format: z
     .enum(["png", "jpeg"])
@@ -60,17 +91,282 @@ const screenshotSchema = z.object({
     .min(0)
     .max(100)
     .optional()
-    .describe("JPEG quality 0-100 (only for jpeg format)"),
+    .describe("JPEG quality 0-100 (for jpeg format, or the JPEG that preferSmaller compares against)"),
+  preferSmaller: z
+    .boolean()
+    .optional()
+    .describe("Capture as PNG, also encode as JPEG (quality 85 by default), and return whichever is smaller"),
+  clip: z
+    .object({ x: z.number(), y: z.number(), width: z.number().positive(), height: z.number().positive() })
+    .optional()
+    .describe("Capture only this region of the webview, in logical (CSS) pixels; clamped to the viewport"),
+  maskSelectors: z
+    .array(selectorSchema)
+    .optional()
+    .describe(`Elements to hide while capturing, like passwords or personal data, each ${SELECTOR_HELP}`),
+  watermark: z
+    .object({
+      text: z.string().min(1).max(200).describe("Label to draw, like 'CI Build #1234'"),
+      position: z
+        .enum(["top_left", "top_right", "bottom_left", "bottom_right", "center"])
+        .optional()
+        .describe("Where to draw the label (default: bottom_right)"),
+      opacity: z.number().min(0).max(1).optional().describe("Label opacity from 0 to 1 (default: 0.7)"),
+    })
+    .optional()
+    .describe("Text label drawn onto the image, to identify it in an archive of screenshots"),
   windowId: z.string().optional().describe("Target window label"),
 });
 
+const screenshotCompositeSchema = z.object({
+  padding: z
+    .number()
+    .int()
+    .min(0)
+    .max(200)
+    .optional()
+    .describe("Width of the border drawn around each window, in pixels (default: 0)"),
+});
+
+/** Shared description of the pierceShadow argument */
+const pierceShadowSchema = z
+  .boolean()
+  .optional()
+  .describe("Search open shadow roots with CSS, text, and role selectors (default: false). XPath never does");
+
 const domSnapshotSchema = z.object({
   type: z
     .enum(["accessibility", "structure"])
     .describe(
       "Snapshot type: accessibility (roles, names, states) or structure (tags, IDs, classes)"
     ),
-  selector: z.string().optional().describe("CSS selector to scope the snapshot"),
+  selector: selectorSchema.optional().describe(`Element to scope the snapshot to: ${SELECTOR_HELP}`),
+  pierceShadow: pierceShadowSchema,
+  maxDepth: z
+    .number()
+    .int()
+    .min(0)
+    .optional()
+    .describe("Deepest level to include, where 0 is the top (default: unlimited)"),
+  maxNodes: z
+    .number()
+    .int()
+    .min(1)
+    .optional()
+    .describe(
+      "Most nodes to include, filled in level by level so the top of the tree is complete (default: unlimited)"
+    ),
+  maxFrameDepth: z
+    .number()
+    .int()
+    .min(0)
+    .optional()
+    .describe(
+      "How many levels of same-origin iframes to include, where 0 leaves their content out. " +
+        "Cross-origin iframes are always opaque nodes with their src (default: 1)"
+    ),
+  format: z
+    .enum(["yaml", "json", "html", "markdown"])
+    .optional()
+    .describe(
+      "Output format (default: yaml). json gives a tree of { tag, role, name, attrs, children } nodes. " +
+        "html gives the element's HTML without scripts or event handlers, to reproduce a bug standalone, " +
+        "and markdown its headings, text, lists, links, and tables; both ignore type."
+    ),
+  maxLength: z
+    .number()
+    .int()
+    .min(1)
+    .optional()
+    .describe("Longest html or markdown output in characters, cut beyond that (default: 100000)"),
+  includeRects: z
+    .boolean()
+    .optional()
+    .describe(
+      "Add each node's bounding rect in CSS pixels and whether it's visible, to find click targets without element_info (default: false). Makes the snapshot about twice as big."
+    ),
+  computedStyles: z
+    .array(z.string())
+    .max(20)
+    .optional()
+    .describe(
+      'CSS properties whose computed values to add to each node, like ["display", "position", "z-index", "overflow"] (at most 20). Use with type structure to see why an element isn\'t visible.'
+    ),
+  interactiveOnly: z
+    .boolean()
+    .optional()
+    .describe(
+      "Only include elements an agent can act on, like buttons, links, textboxes, checkboxes, comboboxes, menu items, " +
+        "and tabs, under the nodes that contain them for context. Each gets a ref, like e12, that tauri_interact " +
+        "takes as selector { ref: 'e12' }. Only the latest snapshot's refs work. For type accessibility in yaml or " +
+        "json (default: false)."
+    ),
+  roles: z
+    .array(z.string())
+    .optional()
+    .describe(
+      'Like interactiveOnly, but for the ARIA roles listed, like ["heading", "button"]. Use one or the other.'
+    ),
+  store: z
+    .boolean()
+    .optional()
+    .describe(
+      "Keep the snapshot in the app and return its snapshotId, to pass to tauri_dom_diff as baselineId (default: false)"
+    ),
+  pageSize: z
+    .number()
+    .int()
+    .min(1)
+    .optional()
+    .describe(
+      "Split the snapshot into pages of this many nodes and return the first, with a nextPage token for the next one. " +
+        "For snapshots too big for one response. yaml and json only; json pages list nodes with their depth."
+    ),
+  page: z
+    .string()
+    .optional()
+    .describe(
+      "nextPage token from the previous page, to get the next page of the same snapshot. The other options are ignored."
+    ),
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const domDiffSchema = domSnapshotSchema.omit({ format: true, maxLength: true, pageSize: true, page: true }).extend({
+  type: domSnapshotSchema.shape.type
+    .optional()
+    .describe("Snapshot type, like dom_snapshot's (default: the baseline's with baselineId, else accessibility)"),
+  baseline: z
+    .union([z.string(), z.record(z.unknown())])
+    .optional()
+    .describe("A snapshot dom_snapshot returned with format json, to compare the DOM with"),
+  baselineId: z
+    .string()
+    .optional()
+    .describe(
+      "snapshotId of a snapshot kept with store: true, to compare with instead of sending it back. Snapshot options default to the ones it was taken with."
+    ),
+  maxChanges: z.number().int().min(1).optional().describe("Most changes to list (default: 100)"),
+  store: z
+    .boolean()
+    .optional()
+    .describe("Keep the current snapshot too and return its snapshotId, for the next diff (default: false)"),
+});
+
+const refsClearSchema = z.object({
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const accessibilityFindSchema = z.object({
+  role: z.string().optional().describe("ARIA role, like button or textbox"),
+  name: z.string().optional().describe("Accessible name to match"),
+  exact: z
+    .boolean()
+    .optional()
+    .describe("Require an exact name match (default: true). False matches substrings."),
+  includeHidden: z
+    .boolean()
+    .optional()
+    .describe("Include hidden elements (default: false)"),
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const formFieldsSchema = z.object({
+  formSelector: z
+    .string()
+    .optional()
+    .describe("CSS selector of the form to inspect (default: whole page)"),
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const tableDataSchema = z.object({
+  selector: z.string().describe("CSS selector of the <table> element"),
+  includeHtml: z
+    .boolean()
+    .optional()
+    .describe("Return each cell as { text, html } instead of plain text (default: false)"),
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const focusInfoSchema = z.object({
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const activeElementSchema = z.object({
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const isVisibleSchema = z.object({
+  selector: z.string().describe("CSS selector for the element to check"),
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const elementInfoSchema = z.object({
+  selector: selectorSchema.describe(`Element to describe: ${SELECTOR_HELP}`),
+  styles: z
+    .array(z.string())
+    .optional()
+    .describe("Computed style properties to return, like ['display', 'visibility', 'z-index'] (default: none)"),
+  all: z
+    .boolean()
+    .optional()
+    .describe("Return an array with every element the selector matches, instead of one element (default: false)"),
+  pierceShadow: pierceShadowSchema,
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const findTextSchema = z.object({
+  query: z.string().min(1).describe("Text to find, or a regex with regex: true"),
+  regex: z.boolean().optional().describe("Treat query as a JavaScript regex (default: false)"),
+  caseSensitive: z.boolean().optional().describe("Match case (default: false)"),
+  maxResults: z.number().int().min(1).optional().describe("Most matches to return (default: 20)"),
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const highlightSchema = z.object({
+  selector: selectorSchema.describe(`Element to outline: ${SELECTOR_HELP}`),
+  durationMs: z
+    .number()
+    .int()
+    .min(1)
+    .max(60000)
+    .optional()
+    .describe("How long the outline stays, in milliseconds (default: 2000)"),
+  color: z.string().optional().describe("CSS color of the outline and label (default: '#e8336d')"),
+  label: z
+    .union([z.string(), z.number()])
+    .optional()
+    .describe("Text shown above the outline, like a step number"),
+  pierceShadow: pierceShadowSchema,
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const highlightClearSchema = z.object({
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const scrollInfoSchema = z.object({
+  selector: z
+    .string()
+    .optional()
+    .describe("CSS selector of a scrollable element (default: the page)"),
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const scrollToSchema = z.object({
+  x: z.number().optional().describe("Horizontal page position in pixels"),
+  y: z.number().optional().describe("Vertical page position in pixels"),
+  behavior: z
+    .enum(["smooth", "instant"])
+    .optional()
+    .describe("Scroll behavior (default: instant). Smooth scrolling is awaited."),
+  selector: z
+    .string()
+    .optional()
+    .describe("CSS selector of an element to scroll into view (alternative to x/y)"),
+  block: z
+    .enum(["start", "center", "end", "nearest"])
+    .optional()
+    .describe("Where to align the element in the viewport (default: start)"),
   windowId: z.string().optional().describe("Target window label"),
 });
 
@@ -81,108 +377,836 @@ const executeJsSchema = z.object({
 
 const consoleLogsSchema = z.object({
   filter: z.string().optional().describe("Regex to filter messages"),
-  since: z.string().optional().describe("ISO timestamp to filter by time"),
+  since: z
+    .union([z.string(), z.number().int().min(0)])
+    .optional()
+    .describe(
+      "ISO timestamp, or the seq of the last entry seen. When polling, pass the last seq so no entry is missed or repeated",
+    ),
+  levels: z
+    .array(z.string())
+    .optional()
+    .describe(
+      'Only include these levels: log, info, warn, error, debug, uncaught (e.g. ["error", "uncaught"])',
+    ),
   clear: z
     .boolean()
     .optional()
     .describe("Clear logs after reading (default: false)"),
-  windowId: z.string().optional().describe("Target window label"),
+  navigation: z
+    .enum(["current", "all"])
+    .optional()
+    .describe(
+      'Which page loads to include: "current" (since the last reload or navigation) or "all" (default)',
+    ),
+  offset: z
+    .number()
+    .int()
+    .min(0)
+    .optional()
+    .describe("Skip this many matching entries (implies paged)"),
+  limit: z
+    .number()
+    .int()
+    .min(0)
+    .optional()
+    .describe("Return at most this many matching entries (implies paged)"),
+  paged: z
+    .boolean()
+    .optional()
+    .describe(
+      "Return { entries, total, dropped, oldestTimestamp } instead of a plain array (default: false)"
+    ),
+  allWindows: z
+    .boolean()
+    .optional()
+    .describe("Merge logs from all windows, including recently closed ones (same as windowId \"*\")"),
+  windowId: z
+    .string()
+    .optional()
+    .describe('Target window label, or "*" for all windows'),
 });
 
-const windowListSchema = z.object({});
+const consoleExportSchema = consoleLogsSchema.omit({ paged: true }).extend({
+  savePath: z
+    .string()
+    .optional()
+    .describe(
+      "Absolute path to write the export to. Without it, the content is returned inline (up to 1 MB)",
+    ),
+  format: z
+    .enum(["jsonl", "text"])
+    .optional()
+    .describe("jsonl: one JSON entry per line (default). text: one readable line per entry"),
+});
+
+const windowListSchema = z.object({
+  sort: z
+    .enum(["label", "title", "focused_first"])
+    .optional()
+    .describe("Sort order, ties broken by label (default: unsorted, in no stable order)"),
+  filter: z
+    .object({
+      visible: z.boolean().optional().describe("Only visible (true) or hidden (false) windows"),
+    })
+    .optional()
+    .describe("Only windows matching all given properties"),
+});
 
 const windowInfoSchema = z.object({
   windowId: z.string().optional().describe("Window label (default: focused)"),
 });
 
-const windowResizeSchema = z.object({
-  width: z.number().describe("New width in pixels"),
-  height: z.number().describe("New height in pixels"),
-  windowId: z.string().optional().describe("Target window label"),
-});
+const windowResizeSchema = z.object({
+  width: z.number().describe("New width in pixels"),
+  height: z.number().describe("New height in pixels"),
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const viewportSetSchema = z.object({
+  width: z.number().int().positive().describe("Viewport width in CSS pixels"),
+  height: z.number().int().positive().describe("Viewport height in CSS pixels"),
+  deviceScaleFactor: z
+    .number()
+    .positive()
+    .optional()
+    .describe("Value reported as devicePixelRatio (default: the display's)"),
+  mobile: z
+    .boolean()
+    .optional()
+    .describe("Emulate a mobile viewport meta tag (default: false)"),
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const viewportGetSchema = z.object({
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const colorSchemeSetSchema = z.object({
+  scheme: z
+    .enum(["dark", "light", "no-preference"])
+    .describe("prefers-color-scheme value to emulate"),
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const heapSnapshotSchema = z.object({
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+const emitEventSchema = z.object({
+  event: z.string().describe('Event name, for example "my-app://download-finished"'),
+  payload: z.unknown().optional().describe("JSON payload for the event"),
+  window: z
+    .string()
+    .optional()
+    .describe("Only emit to this window's listeners (default: all listeners)"),
+});
+
+const notifySchema = z.object({
+  title: z.string().describe("Notification title"),
+  body: z.string().optional().describe("Notification body"),
+  icon: z.string().optional().describe("Icon name or path (default: the app icon)"),
+});
+
+const menuClickSchema = z.object({
+  menu_id: z
+    .string()
+    .optional()
+    .describe('ID or label of the submenu the item is in, like "file" or "File" (default: search all menus)'),
+  item_id: z.string().describe('ID or label of the menu item, like "save" or "Save"'),
+});
+
+const menuEmitSchema = z.object({
+  item_id: z.string().describe("Menu item ID to send the menu event for"),
+  menu_id: z.string().optional().describe("Echoed back in the result"),
+});
+
+const dragPointSchema = z.union([selectorSchema, z.object({ x: z.number(), y: z.number() })]);
+
+const interactStepSchema = z.object({
+  action: z
+    .enum([
+      "click",
+      "double_click",
+      "dblclick",
+      "rightclick",
+      "hover",
+      "drag",
+      "type",
+      "scroll",
+      "set_value",
+      "setValue",
+      "set_checked",
+      "setChecked",
+      "select",
+      "upload",
+      "press",
+      "keyboard_sequence",
+      "focus",
+      "blur",
+      "clear",
+      "tap",
+      "longpress",
+      "swipe",
+      "pinch",
+    ])
+    .describe("Interaction type"),
+  selector: selectorSchema.optional().describe(`Target element: ${SELECTOR_HELP}`),
+  x: z.number().optional().describe("X coordinate (alternative to selector), in coordinateSpace"),
+  y: z.number().optional().describe("Y coordinate (alternative to selector), in coordinateSpace"),
+  coordinateSpace: z
+    .enum(["css-viewport", "css-page", "device"])
+    .optional()
+    .describe(
+      "What x and y are measured in: CSS pixels from the viewport's top left (default), CSS pixels from the page's " +
+        "top left (scrolled content), or device pixels like a screenshot's"
+    ),
+  origin: selectorSchema
+    .optional()
+    .describe(`Element that x and y are offsets from, measured from its top left corner: ${SELECTOR_HELP}`),
+  text: z.string().optional().describe("Text to type (for type action)"),
+  scrollX: z.number().optional().describe("Horizontal scroll amount (alias of deltaX)"),
+  scrollY: z.number().optional().describe("Vertical scroll amount (alias of deltaY)"),
+  deltaX: z.number().optional().describe("Scroll the container (selector, default: page) right by this much"),
+  deltaY: z.number().optional().describe("Scroll the container (selector, default: page) down by this much"),
+  intoView: selectorSchema
+    .optional()
+    .describe("Selector of an element to scroll into view, searched within the selector's container (for scroll action)"),
+  block: z
+    .enum(["start", "center", "end", "nearest"])
+    .optional()
+    .describe("Vertical alignment for intoView (default: start)"),
+  inline: z
+    .enum(["start", "center", "end", "nearest"])
+    .optional()
+    .describe("Horizontal alignment for intoView (default: nearest)"),
+  behavior: z.enum(["smooth", "instant", "auto"]).optional().describe("Scroll behavior (default: smooth)"),
+  value: z
+    .union([z.number(), z.string(), z.array(z.string())])
+    .optional()
+    .describe("Value for an input (set_value action), or option value(s) to pick (select action)"),
+  checked: z
+    .boolean()
+    .optional()
+    .describe("Whether the checkbox, radio, or switch should end up checked (for set_checked action)"),
+  label: z
+    .union([z.string(), z.array(z.string())])
+    .optional()
+    .describe("Option label(s) to pick (for select action)"),
+  index: z
+    .union([z.number().int().min(0), z.array(z.number().int().min(0))])
+    .optional()
+    .describe("Option index(es) to pick (for select action)"),
+  files: z
+    .array(
+      z.object({
+        name: z.string().describe("File name"),
+        mimeType: z.string().optional().describe("MIME type, like 'image/png' (default: none)"),
+        contentsBase64: z.string().describe("File contents, base64-encoded"),
+      })
+    )
+    .optional()
+    .describe("Files to put on a file input (for upload action). Total size is capped at 10 MB by default."),
+  from: dragPointSchema.optional().describe("Drag or swipe start: selector (its center) or { x, y } (for drag and swipe actions)"),
+  to: z
+    .union([dragPointSchema, z.object({ top: z.number().optional(), left: z.number().optional() })])
+    .optional()
+    .describe(
+      "Drag or swipe end: selector (its center) or { x, y } (for drag and swipe actions). Scroll position: { top, left } (for scroll action)"
+    ),
+  steps: z.number().int().min(1).optional().describe("Intermediate pointer positions for drag, swipe, and pinch (default: 10)"),
+  durationMs: z.number().int().min(0).optional().describe("How long the drag, swipe, or pinch takes in ms (default: 300)"),
+  holdMs: z.number().int().min(0).max(10000).optional().describe("How long the finger stays down (for longpress action, default: 800)"),
+  center: dragPointSchema
+    .optional()
+    .describe("Pinch center: selector (its center) or { x, y } (for pinch action, default: the selector's center)"),
+  startDistance: z.number().positive().optional().describe("Distance between the fingers when the pinch starts, in CSS pixels"),
+  endDistance: z
+    .number()
+    .positive()
+    .optional()
+    .describe("Distance between the fingers when the pinch ends, in CSS pixels (smaller zooms out, larger zooms in)"),
+  keys: z
+    .array(z.string())
+    .optional()
+    .describe(
+      "Keys to press in order, like ['ctrl', 'a', 'delete', 'H', 'i'] (for keyboard_sequence action). Modifiers apply to the next key."
+    ),
+  key: z
+    .string()
+    .optional()
+    .describe("Key to press, like 'Escape', 'Enter', 'ArrowDown', 'Tab', 'Numpad5', 'F5', or 's' (for press action)"),
+  modifiers: z
+    .array(z.string())
+    .optional()
+    .describe("Modifiers held during the press, like ['Meta', 'Shift'] (for press action)"),
+  repeat: z.number().int().min(1).max(100).optional().describe("How many times to press the key (default: 1)"),
+  preventScroll: z
+    .boolean()
+    .optional()
+    .describe("Focus without scrolling the element into view (for focus action, default: false)"),
+  waitForSelector: z
+    .boolean()
+    .optional()
+    .describe("Wait for the selector to match a visible, uncovered element before acting (default: true)"),
+  waitTimeoutMs: z
+    .number()
+    .int()
+    .min(0)
+    .max(30000)
+    .optional()
+    .describe("How long to wait for the selector in ms (default: 2000)"),
+  pierceShadow: pierceShadowSchema,
+});
+
+const interactSchema = interactStepSchema.extend({
+  action: z
+    .enum([...interactStepSchema.shape.action.options, "sequence"])
+    .describe("Interaction type, or 'sequence' to run several actions in one request"),
+  actions: z
+    .array(
+      interactStepSchema.extend({
+        delayMs: z.number().int().min(0).max(5000).optional().describe("Pause before this step in ms (default: 0)"),
+      })
+    )
+    .min(1)
+    .max(50)
+    .optional()
+    .describe("Actions to run in order, stopping at the first failure (for sequence action)"),
+  native: z
+    .boolean()
+    .optional()
+    .describe(
+      "Send real OS mouse and keyboard input instead of DOM events (click, type, scroll only). " +
+        "The app must opt in with Builder::native_input(true); macOS and Windows only"
+    ),
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+// A condition checked in the page, which all and any can combine
+const pageConditionSchema = z.object({
+  type: z.enum(["selector", "text", "visible", "hidden", "detached", "function"]),
+  value: selectorSchema.optional(),
+  script: z.string().optional(),
+  args: z.unknown().optional(),
+  pollIntervalMs: z.number().int().min(10).max(10000).optional(),
+  pierceShadow: pierceShadowSchema,
+});
+
+const waitForSchema = z.object({
+  type: z
+    .enum([
+      "selector",
+      "text",
+      "visible",
+      "hidden",
+      "detached",
+      "function",
+      "all",
+      "any",
+      "console",
+      "windowCount",
+      "tauriEvent",
+    ])
+    .describe("Condition type to wait for"),
+  value: selectorSchema
+    .optional()
+    .describe(
+      `Text to wait for (text type), or the element: ${SELECTOR_HELP}. Only used by the text and element types`
+    ),
+  script: z
+    .string()
+    .optional()
+    .describe(
+      "Predicate for the function type, like 'store.getState().sync.done'. Runs until it returns a truthy value, " +
+        "which is returned. Can use await, and read the args argument as args"
+    ),
+  args: z.unknown().optional().describe("JSON value the predicate can read as args (function type)"),
+  pollIntervalMs: z
+    .number()
+    .int()
+    .min(10)
+    .max(10000)
+    .optional()
+    .describe("How often the predicate runs, in milliseconds (function type, default: 100)"),
+  conditions: z
+    .array(pageConditionSchema)
+    .min(1)
+    .optional()
+    .describe(
+      "Conditions for the all and any types, each like { type: 'selector', value: '.row' }, waited for together " +
+        "under the one timeout. Can't be all, any, console, windowCount, or tauriEvent"
+    ),
+  textContains: z.string().optional().describe("Text the console entry's message contains (console type)"),
+  regex: z.string().optional().describe("Regex the console entry's message matches (console type)"),
+  level: z
+    .union([z.string(), z.array(z.string())])
+    .optional()
+    .describe("Console level or levels to match, like 'info' or ['error', 'uncaught'] (console type)"),
+  includeHistory: z
+    .boolean()
+    .optional()
+    .describe("Also match entries logged before the wait started (console type, default: false)"),
+  atLeast: z.number().int().min(0).optional().describe("Smallest number of open windows (windowCount type)"),
+  exactly: z.number().int().min(0).optional().describe("Exact number of open windows (windowCount type)"),
+  event: z.string().optional().describe("Tauri event to wait for, like 'sync:done' (tauriEvent type)"),
+  timeoutMs: z.number().int().min(0).optional().describe("Timeout in milliseconds (default: 5000)"),
+  timeout: z.number().int().min(0).optional().describe("Deprecated: use timeoutMs"),
+  pierceShadow: pierceShadowSchema,
+  windowId: z.string().optional().describe("Target window label"),
+});
+
+// ============================================================================
+// Tool handlers
+// ============================================================================
+
+const handleSession: ToolHandler = async (args) => {
+  const { action, host, port } = sessionSchema.parse(args);
+
+  switch (action) {
+    case "start":
+      return await startSession(host, port);
+    case "stop":
+      return stopSession();
+    case "status":
+      return JSON.stringify(getSessionStatus(), null, 2);
+  }
+};
+
+const handleServerRestart: ToolHandler = async (args) => {
+  ensureSession();
+  const { port, host } = serverRestartSchema.parse(args);
+
+  const response = await sendCommand("server_restart", { port, host });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Server restart failed");
+  }
+
+  // The plugin closes this connection once it has answered, so follow the server
+  const data = response.data as { port: number };
+  await moveSession(data.port);
+  return JSON.stringify(data, null, 2);
+};
+
+const handleScreenshot: ToolHandler = async (args) => {
+  ensureSession();
+  const { format, quality, preferSmaller, clip, maskSelectors, watermark, windowId } = screenshotSchema.parse(args);
+
+  const response = await sendCommand("screenshot", {
+    format: format ?? "png",
+    quality,
+    preferSmaller,
+    clip,
+    maskSelectors,
+    watermark,
+    windowId,
+    stream: true,
+  });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Screenshot failed");
+  }
+
+  if (preferSmaller || clip || maskSelectors) {
+    const { image, ...details } = response.data as { image: string; formatChosen?: "png" | "jpeg" };
+    const chosen = details.formatChosen ?? format;
+
+    // Return the image, plus which format won and the sizes compared, the clip applied, and how
+    // many elements were masked
+    return [
+      {
+        type: "image",
+        data: image.replace(/^data:image\/\w+;base64,/, ""),
+        mimeType: chosen === "jpeg" ? "image/jpeg" : "image/png",
+      },
+      { type: "text", text: JSON.stringify(details, null, 2) },
+    ];
+  }
+
+  const data = response.data as string;
+
+  // Return as image content
+  return {
+    type: "image",
+    data: data.replace(/^data:image\/\w+;base64,/, ""),
+    mimeType: format === "jpeg" ? "image/jpeg" : "image/png",
+  };
+};
+
+const handleScreenshotComposite: ToolHandler = async (args) => {
+  ensureSession();
+  const { padding } = screenshotCompositeSchema.parse(args);
+
+  const response = await sendCommand("screenshot_composite", { padding, stream: true });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Composite screenshot failed");
+  }
+
+  const { image, ...layout } = response.data as { image: string };
+
+  // Return the image, plus where each window is in it
+  return [
+    { type: "image", data: image.replace(/^data:image\/\w+;base64,/, ""), mimeType: "image/png" },
+    { type: "text", text: JSON.stringify(layout, null, 2) },
+  ];
+};
+
+const handleDomSnapshot: ToolHandler = async (args) => {
+  ensureSession();
+  const {
+    type,
+    selector,
+    pierceShadow,
+    maxDepth,
+    maxNodes,
+    maxFrameDepth,
+    format,
+    maxLength,
+    includeRects,
+    computedStyles,
+    interactiveOnly,
+    roles,
+    store,
+    pageSize,
+    page,
+    windowId,
+  } = domSnapshotSchema.parse(args);
+
+  const response = await sendCommand("dom_snapshot", {
+    type,
+    selector,
+    pierceShadow,
+    maxDepth,
+    maxNodes,
+    maxFrameDepth,
+    format,
+    maxLength,
+    includeRects,
+    computedStyles,
+    interactiveOnly,
+    roles,
+    store,
+    pageSize,
+    page,
+    windowId,
+    stream: true,
+  });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "DOM snapshot failed");
+  }
+
+  const result = response.data;
+  if (typeof result === "string") {
+    return result;
+  }
+  // The limits cut the tree, the snapshot was stored, or it's a page of it
+  const { snapshot, truncated, totalNodes, shownNodes, totalLength, snapshotId, page: pageNumber, pages, nextPage } =
+    result as {
+      snapshot?: unknown;
+      truncated?: boolean;
+      totalNodes?: number;
+      shownNodes?: number;
+      totalLength?: number;
+      snapshotId?: string;
+      page?: number;
+      pages?: number;
+      nextPage?: string;
+    };
+  if (typeof snapshot !== "string") {
+    return JSON.stringify(result, null, 2);
+  }
+  const notes: string[] = [];
+  if (totalLength !== undefined) {
+    notes.push(
+      `(Truncated: cut at ${snapshot.length} of ${totalLength} characters. Narrow the selector or raise maxLength to see more.)`
+    );
+  } else if (truncated === true) {
+    notes.push(
+      `(Truncated: showing ${shownNodes} of ${totalNodes} nodes. Narrow the selector or raise maxDepth/maxNodes to see more.)`
+    );
+  }
+  if (snapshotId !== undefined) {
+    notes.push(`(Stored as ${snapshotId}. Pass it to tauri_dom_diff as baselineId to see what changed.)`);
+  }
+  if (pageNumber !== undefined) {
+    notes.push(
+      nextPage === undefined
+        ? `(Page ${pageNumber} of ${pages}, the last.)`
+        : `(Page ${pageNumber} of ${pages}. Pass page: "${nextPage}" for the next one.)`
+    );
+  }
+  return [snapshot, ...notes].join("\n\n");
+};
+
+const handleDomDiff: ToolHandler = async (args) => {
+  ensureSession();
+  const {
+    baseline,
+    baselineId,
+    type,
+    selector,
+    pierceShadow,
+    maxDepth,
+    maxNodes,
+    maxFrameDepth,
+    includeRects,
+    computedStyles,
+    interactiveOnly,
+    roles,
+    maxChanges,
+    store,
+    windowId,
+  } = domDiffSchema.parse(args);
+
+  const response = await sendCommand("dom_diff", {
+    baseline,
+    baselineId,
+    type,
+    selector,
+    pierceShadow,
+    maxDepth,
+    maxNodes,
+    maxFrameDepth,
+    includeRects,
+    computedStyles,
+    interactiveOnly,
+    roles,
+    maxChanges,
+    store,
+    windowId,
+  });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "DOM diff failed");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleRefsClear: ToolHandler = async (args) => {
+  ensureSession();
+  const { windowId } = refsClearSchema.parse(args);
+
+  const response = await sendCommand("refs_clear", {
+    windowId,
+  });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to clear refs");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleAccessibilityFind: ToolHandler = async (args) => {
+  ensureSession();
+  const { role, name, exact, includeHidden, windowId } =
+    accessibilityFindSchema.parse(args);
+
+  const response = await sendCommand("accessibility_find", {
+    role,
+    name,
+    exact,
+    includeHidden,
+    windowId,
+  });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Accessibility query failed");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleFormFields: ToolHandler = async (args) => {
+  ensureSession();
+  const { formSelector, windowId } = formFieldsSchema.parse(args);
+
+  const response = await sendCommand("form_fields", {
+    formSelector,
+    windowId,
+  });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to list form fields");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleTableData: ToolHandler = async (args) => {
+  ensureSession();
+  const { selector, includeHtml, windowId } = tableDataSchema.parse(args);
+
+  const response = await sendCommand("table_data", {
+    selector,
+    includeHtml,
+    windowId,
+  });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to read table");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleFocusInfo: ToolHandler = async (args) => {
+  ensureSession();
+  const { windowId } = focusInfoSchema.parse(args);
+
+  const response = await sendCommand("focus_info", {
+    windowId,
+  });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to get focus info");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleActiveElement: ToolHandler = async (args) => {
+  ensureSession();
+  const { windowId } = activeElementSchema.parse(args);
+
+  const response = await sendCommand("active_element", {
+    windowId,
+  });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to get the active element");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleIsVisible: ToolHandler = async (args) => {
+  ensureSession();
+  const { selector, windowId } = isVisibleSchema.parse(args);
+
+  const response = await sendCommand("is_visible", {
+    selector,
+    windowId,
+  });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Visibility check failed");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleElementInfo: ToolHandler = async (args) => {
+  ensureSession();
+  const { selector, styles, all, pierceShadow, windowId } = elementInfoSchema.parse(args);
+
+  const response = await sendCommand("element_info", {
+    selector,
+    styles,
+    all,
+    pierceShadow,
+    windowId,
+  });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to get element info");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleFindText: ToolHandler = async (args) => {
+  ensureSession();
+  const { query, regex, caseSensitive, maxResults, windowId } = findTextSchema.parse(args);
+
+  const response = await sendCommand("find_text", {
+    query,
+    regex,
+    caseSensitive,
+    maxResults,
+    windowId,
+  });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to find text");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleHighlight: ToolHandler = async (args) => {
+  ensureSession();
+  const { selector, durationMs, color, label, pierceShadow, windowId } = highlightSchema.parse(args);
+
+  const response = await sendCommand("highlight", {
+    selector,
+    durationMs,
+    color,
+    label,
+    pierceShadow,
+    windowId,
+  });
 
-const interactSchema = z.object({
-  action: z
-    .enum(["click", "double_click", "type", "scroll"])
-    .describe("Interaction type"),
-  selector: z.string().optional().describe("CSS selector for target element"),
-  x: z.number().optional().describe("X coordinate (alternative to selector)"),
-  y: z.number().optional().describe("Y coordinate (alternative to selector)"),
-  text: z.string().optional().describe("Text to type (for type action)"),
-  scrollX: z.number().optional().describe("Horizontal scroll amount"),
-  scrollY: z.number().optional().describe("Vertical scroll amount"),
-  windowId: z.string().optional().describe("Target window label"),
-});
+  if (!response.success) {
+    throw new Error(response.error ?? "Highlight failed");
+  }
 
-const waitForSchema = z.object({
-  type: z
-    .enum(["selector", "text", "visible", "hidden"])
-    .describe("Condition type to wait for"),
-  value: z.string().describe("Selector or text to wait for"),
-  timeout: z
-    .number()
-    .optional()
-    .describe("Timeout in milliseconds (default: 5000)"),
-  windowId: z.string().optional().describe("Target window label"),
-});
+  return JSON.stringify(response.data, null, 2);
+};
 
-// ============================================================================
-// Tool handlers
-// ============================================================================
+const handleHighlightClear: ToolHandler = async (args) => {
+  ensureSession();
+  const { windowId } = highlightClearSchema.parse(args);
 
-const handleSession: ToolHandler = async (args) => {
-  const { action, host, port } = sessionSchema.parse(args);
+  const response = await sendCommand("highlight_clear", {
+    windowId,
+  });
 
-  switch (action) {
-    case "start":
-      return await startSession(host, port);
-    case "stop":
-      return stopSession();
-    case "status":
-      return JSON.stringify(getSessionStatus(), null, 2);
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to clear highlights");
   }
+
+  return JSON.stringify(response.data, null, 2);
 };
 
-const handleScreenshot: ToolHandler = async (args) => {
+const handleScrollInfo: ToolHandler = async (args) => {
   ensureSession();
-  const { format, quality, windowId } = screenshotSchema.parse(args);
+  const { selector, windowId } = scrollInfoSchema.parse(args);
 
-  const response = await sendCommand("screenshot", {
-    format: format ?? "png",
-    quality,
+  const response = await sendCommand("scroll_info", {
+    selector,
     windowId,
   });
 
   if (!response.success) {
-    throw new Error(response.error ?? "Screenshot failed");
+    throw new Error(response.error ?? "Failed to get scroll info");
   }
 
-  const data = response.data as string;
-
-  // Return as image content
-  return {
-    type: "image",
-    data: data.replace(/^data:image\/\w+;base64,/, ""),
-    mimeType: format === "jpeg" ? "image/jpeg" : "image/png",
-  };
+  return JSON.stringify(response.data, null, 2);
 };
 
-const handleDomSnapshot: ToolHandler = async (args) => {
+const handleScrollTo: ToolHandler = async (args) => {
   ensureSession();
-  const { type, selector, windowId } = domSnapshotSchema.parse(args);
+  const { x, y, behavior, selector, block, windowId } = scrollToSchema.parse(args);
 
-  const response = await sendCommand("dom_snapshot", {
-    type,
+  const response = await sendCommand("scroll_to", {
+    x,
+    y,
+    behavior,
     selector,
+    block,
     windowId,
   });
 
   if (!response.success) {
-    throw new Error(response.error ?? "DOM snapshot failed");
+    throw new Error(response.error ?? "Scroll failed");
   }
 
-  const result = response.data;
-  return typeof result === "string" ? result : JSON.stringify(result, null, 2);
+  return JSON.stringify(response.data, null, 2);
 };
 
 const handleExecuteJs: ToolHandler = async (args) => {
@@ -199,17 +1223,47 @@ const handleExecuteJs: ToolHandler = async (args) => {
   }
 
   const result = response.data;
-  return typeof result === "string" ? result : JSON.stringify(result, null, 2);
+  if (typeof result === "string") {
+    return result;
+  }
+  // The limits cut the tree
+  const { snapshot, truncated, totalNodes, shownNodes } = result as {
+    snapshot?: unknown;
+    truncated?: boolean;
+    totalNodes?: number;
+    shownNodes?: number;
+  };
+  if (truncated === true && typeof snapshot === "string") {
+    return `${snapshot}\n\n(Truncated: showing ${shownNodes} of ${totalNodes} nodes. Narrow the selector or raise maxDepth/maxNodes to see more.)`;
+  }
+  return JSON.stringify(result, null, 2);
 };
 
 const handleConsoleLogs: ToolHandler = async (args) => {
   ensureSession();
-  const { filter, since, clear, windowId } = consoleLogsSchema.parse(args);
+  const {
+    filter,
+    since,
+    levels,
+    clear,
+    navigation,
+    offset,
+    limit,
+    paged,
+    allWindows,
+    windowId,
+  } = consoleLogsSchema.parse(args);
 
   const response = await sendCommand("console_logs", {
     filter,
     since,
+    levels,
     clear,
+    navigation,
+    offset,
+    limit,
+    paged,
+    allWindows,
     windowId,
   });
 
@@ -217,14 +1271,51 @@ const handleConsoleLogs: ToolHandler = async (args) => {
     throw new Error(response.error ?? "Failed to get console logs");
   }
 
-  return response.data as string;
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleConsoleExport: ToolHandler = async (args) => {
+  ensureSession();
+  const {
+    filter,
+    since,
+    levels,
+    clear,
+    navigation,
+    offset,
+    limit,
+    savePath,
+    format,
+    allWindows,
+    windowId,
+  } = consoleExportSchema.parse(args);
+
+  const response = await sendCommand("console_export", {
+    filter,
+    since,
+    levels,
+    clear,
+    navigation,
+    offset,
+    limit,
+    savePath,
+    format,
+    allWindows,
+    windowId,
+  });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to export console logs");
+  }
+
+  return JSON.stringify(response.data, null, 2);
 };
 
 const handleWindowList: ToolHandler = async (args) => {
   ensureSession();
-  windowListSchema.parse(args);
+  const { sort, filter } = windowListSchema.parse(args);
 
-  const response = await sendCommand("window_list", {});
+  const response = await sendCommand("window_list", { sort, filter });
 
   if (!response.success) {
     throw new Error(response.error ?? "Failed to list windows");
@@ -265,19 +1356,200 @@ const handleWindowResize: ToolHandler = async (args) => {
   return `Resized to ${width}x${height}`;
 };
 
+const handleViewportSet: ToolHandler = async (args) => {
+  ensureSession();
+  const { width, height, deviceScaleFactor, mobile, windowId } =
+    viewportSetSchema.parse(args);
+
+  const response = await sendCommand("viewport_set", {
+    width,
+    height,
+    deviceScaleFactor,
+    mobile,
+    windowId,
+  });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to set viewport");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleViewportGet: ToolHandler = async (args) => {
+  ensureSession();
+  const { windowId } = viewportGetSchema.parse(args);
+
+  const response = await sendCommand("viewport_get", { windowId });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to get viewport");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleColorSchemeSet: ToolHandler = async (args) => {
+  ensureSession();
+  const { scheme, windowId } = colorSchemeSetSchema.parse(args);
+
+  const response = await sendCommand("color_scheme_set", { scheme, windowId });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to set color scheme");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleHeapSnapshot: ToolHandler = async (args) => {
+  ensureSession();
+  const { windowId } = heapSnapshotSchema.parse(args);
+
+  const response = await sendCommand("heap_snapshot", { windowId });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to get heap usage");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleEmitEvent: ToolHandler = async (args) => {
+  ensureSession();
+  const { event, payload, window } = emitEventSchema.parse(args);
+
+  const response = await sendCommand("emit_event", { event, payload, window });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to emit event");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleNotify: ToolHandler = async (args) => {
+  ensureSession();
+  const { title, body, icon } = notifySchema.parse(args);
+
+  const response = await sendCommand("notify", { title, body, icon });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to send notification");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleMenuClick: ToolHandler = async (args) => {
+  ensureSession();
+  const { menu_id, item_id } = menuClickSchema.parse(args);
+
+  const response = await sendCommand("menu_click", { menu_id, item_id });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to click menu item");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
+const handleMenuEmit: ToolHandler = async (args) => {
+  ensureSession();
+  const { item_id, menu_id } = menuEmitSchema.parse(args);
+
+  const response = await sendCommand("menu_emit", { item_id, menu_id });
+
+  if (!response.success) {
+    throw new Error(response.error ?? "Failed to emit menu event");
+  }
+
+  return JSON.stringify(response.data, null, 2);
+};
+
 const handleInteract: ToolHandler = async (args) => {
   ensureSession();
-  const { action, selector, x, y, text, scrollX, scrollY, windowId } =
-    interactSchema.parse(args);
+  const {
+    action,
+    selector,
+    x,
+    y,
+    coordinateSpace,
+    origin,
+    text,
+    scrollX,
+    scrollY,
+    deltaX,
+    deltaY,
+    intoView,
+    block,
+    inline,
+    behavior,
+    value,
+    checked,
+    label,
+    index,
+    files,
+    keys,
+    key,
+    modifiers,
+    repeat,
+    preventScroll,
+    from,
+    to,
+    steps,
+    durationMs,
+    holdMs,
+    center,
+    startDistance,
+    endDistance,
+    actions,
+    waitForSelector,
+    waitTimeoutMs,
+    pierceShadow,
+    native,
+    windowId,
+  } = interactSchema.parse(args);
 
   const response = await sendCommand("interact", {
     action,
     selector,
     x,
     y,
+    coordinateSpace,
+    origin,
     text,
-    scroll_x: scrollX,
-    scroll_y: scrollY,
+    scrollX,
+    scrollY,
+    deltaX,
+    deltaY,
+    intoView,
+    block,
+    inline,
+    behavior,
+    value,
+    checked,
+    label,
+    index,
+    files,
+    keys,
+    key,
+    modifiers,
+    repeat,
+    preventScroll,
+    from,
+    to,
+    steps,
+    durationMs,
+    holdMs,
+    center,
+    startDistance,
+    endDistance,
+    actions,
+    waitForSelector,
+    waitTimeoutMs,
+    pierceShadow,
+    native,
     windowId,
   });
 
@@ -285,25 +1557,74 @@ const handleInteract: ToolHandler = async (args) => {
     throw new Error(response.error ?? "Interaction failed");
   }
 
-  return response.data as string;
+  return JSON.stringify(response.data, null, 2);
 };
 
+/** Time on top of a wait's timeout to wait for its response, so the plugin's timeout error comes first */
+const WAIT_FOR_RESPONSE_MARGIN_MS = 5000;
+
 const handleWaitFor: ToolHandler = async (args) => {
   ensureSession();
-  const { type, value, timeout, windowId } = waitForSchema.parse(args);
-
-  const response = await sendCommand("wait_for", {
+  const {
     type,
     value,
-    timeout: timeout ?? 5000,
+    script,
+    args: predicateArgs,
+    pollIntervalMs,
+    conditions,
+    textContains,
+    regex,
+    level,
+    includeHistory,
+    atLeast,
+    exactly,
+    event,
+    timeoutMs,
+    timeout,
+    pierceShadow,
     windowId,
-  });
+  } = waitForSchema.parse(args);
+
+  const waitTimeoutMs = timeoutMs ?? timeout ?? 5000;
+  const response = await sendCommand(
+    "wait_for",
+    {
+      type,
+      value,
+      script,
+      args: predicateArgs,
+      pollIntervalMs,
+      conditions,
+      textContains,
+      regex,
+      level,
+      includeHistory,
+      atLeast,
+      exactly,
+      event,
+      timeoutMs: waitTimeoutMs,
+      pierceShadow,
+      windowId,
+    },
+    // Long waits would otherwise hit the request timeout before the condition's own
+    waitTimeoutMs + WAIT_FOR_RESPONSE_MARGIN_MS
+  );
 
   if (!response.success) {
     throw new Error(response.error ?? "Wait condition not met");
   }
 
-  return response.data as string;
+  // How long it took and how often the condition was checked, with what the wait returned: the
+  // predicate's result, console entry, event payload, or how each of several conditions went
+  const { success: _, ...data } = response.data as {
+    success: boolean;
+    message: string;
+    elapsedMs: number;
+    polls: number;
+    condition: string;
+    detail: string;
+  };
+  return JSON.stringify(data, null, 2);
 };
 
 // ============================================================================
@@ -322,25 +1643,174 @@ export const tools: ToolDefinition[] = [
     schema: sessionSchema,
     handler: handleSession,
   },
+  {
+    name: "tauri_server_restart",
+    description:
+      "Move the plugin's WebSocket server to another port or bind address without restarting the app, " +
+      "and reconnect the session to it. Open connections finish the requests they sent and are closed. " +
+      "If the new address can't be bound, the server stays where it was. " +
+      "The app has to opt in with Builder::server_restart(true).",
+    schema: serverRestartSchema,
+    handler: handleServerRestart,
+  },
   {
     name: "tauri_screenshot",
     description:
       "Capture a screenshot of the Tauri app's webview. " +
       "Returns the image as base64. " +
       "Supports PNG (default) or JPEG format with quality setting. " +
-      "Note: Screenshots are only supported on macOS. Windows and Linux return an error.",
+      "Use preferSmaller to get whichever of PNG and JPEG is smaller for the content. " +
+      "Use clip: { x, y, width, height } (CSS pixels) to capture only part of the viewport. " +
+      "Use maskSelectors to hide elements like password fields or personal data from the image; they keep their " +
+      "space in the layout, and the result tells how many elements were masked. " +
+      "Use watermark: { text, position, opacity } to label the image, like with a CI build number.",
     schema: screenshotSchema,
     handler: handleScreenshot,
   },
+  {
+    name: "tauri_screenshot_composite",
+    description:
+      "Capture all visible windows and composite them into one PNG laid out as they are on the desktop. " +
+      "Use padding to draw a border around each window. " +
+      "Also returns each window's rectangle in the image, and windows that couldn't be captured.",
+    schema: screenshotCompositeSchema,
+    handler: handleScreenshotComposite,
+  },
   {
     name: "tauri_dom_snapshot",
     description:
       "Get a structured snapshot of the DOM for AI consumption. " +
       "Type 'accessibility': roles, names, states, aria attributes. Good for understanding UI semantics. " +
-      "Type 'structure': tag names, IDs, classes, data-testid. Good for writing selectors.",
+      "Type 'structure': tag names, IDs, classes, data-testid. Good for writing selectors. " +
+      "Both descend into open shadow roots and mark hosts with shadowRoot: true; closed shadow roots are marked as opaque. " +
+      "To plan actions, use interactiveOnly: true and target elements by the refs it returns. " +
+      "For very large pages, pageSize returns the snapshot in pages.",
     schema: domSnapshotSchema,
     handler: handleDomSnapshot,
   },
+  {
+    name: "tauri_dom_diff",
+    description:
+      "Show what changed in the DOM since an earlier snapshot, like after a click. " +
+      "Compare with a JSON snapshot from tauri_dom_snapshot, or one it kept with store: true by its snapshotId. " +
+      "Lists added and removed nodes and changed names, states, attributes, rects, and styles, each with its path.",
+    schema: domDiffSchema,
+    handler: handleDomDiff,
+  },
+  {
+    name: "tauri_refs_clear",
+    description:
+      "Forget the element refs tauri_dom_snapshot handed out, so selectors with them fail with STALE_REF " +
+      "instead of acting on an element you no longer mean.",
+    schema: refsClearSchema,
+    handler: handleRefsClear,
+  },
+  {
+    name: "tauri_accessibility_find",
+    description:
+      "Find elements by ARIA role and accessible name, like role 'button' and name 'Submit'. " +
+      "Returns each match's selector, role, name, description, and state. " +
+      "More resilient to DOM changes than CSS selectors.",
+    schema: accessibilityFindSchema,
+    handler: handleAccessibilityFind,
+  },
+  {
+    name: "tauri_form_fields",
+    description:
+      "List form controls in a form or the whole page. " +
+      "Returns each field's selector, type, name, id, value, checked state, select options, " +
+      "disabled and required flags, and validation message.",
+    schema: formFieldsSchema,
+    handler: handleFormFields,
+  },
+  {
+    name: "tauri_table_data",
+    description:
+      "Extract a table's contents as { headers, rows }. " +
+      "Headers come from the <thead> (or a leading row of <th> cells), rows from the table body. " +
+      "Set includeHtml to also get each cell's HTML, for cells with icons or formatting.",
+    schema: tableDataSchema,
+    handler: handleTableData,
+  },
+  {
+    name: "tauri_focus_info",
+    description:
+      "Get the focused element (selector, type, role, tabIndex, value) and the keyboard tab order. " +
+      "The tab order lists tabbable elements sorted the way the browser walks them: " +
+      "positive tabindex first, then document order. Useful for keyboard accessibility testing.",
+    schema: focusInfoSchema,
+    handler: handleFocusInfo,
+  },
+  {
+    name: "tauri_active_element",
+    description:
+      "Get the focused element (tag, id, selector, type) with its value length and text selection range, " +
+      "looking inside shadow roots. Lighter than tauri_focus_info, for checking focus and caret position " +
+      "between interactions.",
+    schema: activeElementSchema,
+    handler: handleActiveElement,
+  },
+  {
+    name: "tauri_is_visible",
+    description:
+      "Check whether an element is visible: returns visible, inViewport, partiallyInViewport, and a reason. " +
+      "visible covers display, visibility, opacity, pointer-events, and zero size. " +
+      "reason explains why it isn't visible, like 'display: none' or 'outside viewport'.",
+    schema: isVisibleSchema,
+    handler: handleIsVisible,
+  },
+  {
+    name: "tauri_element_info",
+    description:
+      "Get where an element is and whether it can be clicked, without a full DOM snapshot: " +
+      "boundingClientRect, inViewport, partiallyInViewport, visibility (visible, display, visibility, opacity), " +
+      "disabled, topmostAtCenter with occluded (true when another element covers its center), " +
+      "the requested computed styles, and all attributes. Pass all: true to describe every match.",
+    schema: elementInfoSchema,
+    handler: handleElementInfo,
+  },
+  {
+    name: "tauri_find_text",
+    description:
+      "Find where the page shows some text, like an error message, much cheaper than a DOM snapshot. " +
+      "Searches visible text only, skipping scripts, styles, and hidden elements, and matches can span inline " +
+      "elements. Returns each match's element selector, an excerpt with the match in **, its bounding rect, " +
+      "and whether it's in the viewport, plus the total number of matches.",
+    schema: findTextSchema,
+    handler: handleFindText,
+  },
+  {
+    name: "tauri_scroll_info",
+    description:
+      "Get the scroll position and size of the page or a scrollable element: " +
+      "scrollX, scrollY, scrollWidth, scrollHeight, clientWidth, clientHeight.",
+    schema: scrollInfoSchema,
+    handler: handleScrollInfo,
+  },
+  {
+    name: "tauri_scroll_to",
+    description:
+      "Scroll the page to an x/y position, or scroll an element into view with selector and block. " +
+      "Waits until scrolling settles (including smooth scrolling), then returns the page's scroll info. " +
+      "Useful for triggering lazy-loaded content.",
+    schema: scrollToSchema,
+    handler: handleScrollTo,
+  },
+  {
+    name: "tauri_highlight",
+    description:
+      "Outline an element on screen, with an optional label like a step number, so a person watching can see " +
+      "what you're about to interact with. The outline follows the element when the page scrolls, never blocks " +
+      "clicks, and disappears after durationMs.",
+    schema: highlightSchema,
+    handler: handleHighlight,
+  },
+  {
+    name: "tauri_highlight_clear",
+    description: "Remove all outlines added by tauri_highlight before their time runs out.",
+    schema: highlightClearSchema,
+    handler: handleHighlightClear,
+  },
   {
     name: "tauri_execute_js",
     description:
@@ -354,16 +1824,32 @@ export const tools: ToolDefinition[] = [
     name: "tauri_console_logs",
     description:
       "Get captured console logs from the webview. " +
-      "Includes timestamp, level, and message. " +
-      "Supports filtering by regex and timestamp.",
+      "Includes timestamp, level, message, and window label. " +
+      "Logs are kept per window and survive page reloads; each entry has a navigationId that increases with every page load. " +
+      "Use windowId \"*\" to merge logs from all windows, including recently closed ones. " +
+      "Use navigation \"current\" to only get output since the last reload. " +
+      "Also captures uncaught errors and unhandled promise rejections (level \"uncaught\", with stack and source location). " +
+      "Supports filtering by regex, timestamp, and level. " +
+      "Use offset/limit to page through large buffers; the paged result includes the total match count " +
+      "and how many entries were dropped from the full buffer.",
     schema: consoleLogsSchema,
     handler: handleConsoleLogs,
   },
+  {
+    name: "tauri_console_export",
+    description:
+      "Export captured console logs, for example to attach to a bug report. " +
+      "Takes the same filters as tauri_console_logs. " +
+      "Writes JSONL or text (with timestamps, levels, window labels, and navigation IDs) to savePath " +
+      "and returns the path and entry count, or returns the content inline if no savePath is given.",
+    schema: consoleExportSchema,
+    handler: handleConsoleExport,
+  },
   {
     name: "tauri_window_list",
     description:
       "List all windows in the Tauri application. " +
-      "Returns window labels, titles, and focused state.",
+      "Returns window labels, titles, URLs, and focused and visible state. Can sort and filter by visibility.",
     schema: windowListSchema,
     handler: handleWindowList,
   },
@@ -381,13 +1867,116 @@ export const tools: ToolDefinition[] = [
     schema: windowResizeSchema,
     handler: handleWindowResize,
   },
+  {
+    name: "tauri_viewport_set",
+    description:
+      "Emulate a viewport for responsive testing. " +
+      "Resizes the window to the given CSS pixel size, and optionally overrides devicePixelRatio " +
+      "and adds a mobile viewport meta tag. The overrides last until the next page load. " +
+      "Returns the effective viewport.",
+    schema: viewportSetSchema,
+    handler: handleViewportSet,
+  },
+  {
+    name: "tauri_viewport_get",
+    description:
+      "Get the effective viewport: CSS size, devicePixelRatio, whether emulation is active, " +
+      "and the window's logical size and native scale factor.",
+    schema: viewportGetSchema,
+    handler: handleViewportGet,
+  },
+  {
+    name: "tauri_color_scheme_set",
+    description:
+      "Emulate a prefers-color-scheme value (dark, light, or no-preference) to test theming. " +
+      "Applies the page's matching @media (prefers-color-scheme) rules, sets the color-scheme property and meta tag, " +
+      "and makes matchMedia report the emulated value, firing change events on query lists created after the first call. " +
+      "Lasts until the next page load.",
+    schema: colorSchemeSetSchema,
+    handler: handleColorSchemeSet,
+  },
+  {
+    name: "tauri_heap_snapshot",
+    description:
+      "Get JS heap usage (usedJSHeapSize, totalJSHeapSize, jsHeapSizeLimit) to check a flow for memory leaks. " +
+      "Only Chromium-based webviews (WebView2 on Windows) expose these; elsewhere supported is false and the sizes are null.",
+    schema: heapSnapshotSchema,
+    handler: handleHeapSnapshot,
+  },
+  {
+    name: "tauri_emit_event",
+    description:
+      "Emit a Tauri event into the app as if the backend sent it, to test event-driven UI " +
+      "(for example, simulate a download finishing). Both frontend and Rust listeners receive it. " +
+      "Pass window to only reach that window's listeners.",
+    schema: emitEventSchema,
+    handler: handleEmitEvent,
+  },
+  {
+    name: "tauri_notify",
+    description:
+      "Send a system notification from the app, to test notification flows. " +
+      "Needs the plugin's notifications feature and tauri-plugin-notification registered in the app. " +
+      "Returns sent: false with an error when the notification couldn't be shown.",
+    schema: notifySchema,
+    handler: handleNotify,
+  },
+  {
+    name: "tauri_menu_click",
+    description:
+      "Click an item in the app or window menu, found by ID or label, to automate menu-driven features " +
+      "without keyboard shortcuts. Disabled items aren't clicked (clicked: false), and check items toggle. " +
+      "The app must register its menu handler with tauri_mcp::on_menu_event.",
+    schema: menuClickSchema,
+    handler: handleMenuClick,
+  },
+  {
+    name: "tauri_menu_emit",
+    description:
+      "Send a menu event for an item ID straight to the app's menu handler, without looking the item up. " +
+      "Use for items outside the menu bar, like tray menus. " +
+      "The app must register its menu handler with tauri_mcp::on_menu_event.",
+    schema: menuEmitSchema,
+    handler: handleMenuEmit,
+  },
   {
     name: "tauri_interact",
     description:
-      "Perform UI interactions: click, double_click, type, or scroll. " +
-      "Target by CSS selector or coordinates. " +
+      "Perform UI interactions: click, dblclick, rightclick, hover, drag, type, scroll, set_value, set_checked, select, upload, press, " +
+      "keyboard_sequence, focus, blur, clear, tap, longpress, swipe, pinch, or sequence. " +
+      "Target by selector or coordinates. Selectors are CSS strings or objects: { css }, { xpath }, " +
+      "{ text: 'Save changes' }, { role: 'button', name: 'Save' } (implicit ARIA roles count), " +
+      "or { ref: 'e12' } from the latest tauri_dom_snapshot with interactiveOnly or roles (older refs fail with STALE_REF); " +
+      "objects that match several elements fail and list the matches. " +
+      "Selector targets are waited for (up to waitTimeoutMs, default 2000) until they exist and, for pointer actions, " +
+      "are visible and not covered; the result's wait field tells how long it took and which checks passed. " +
+      "Pointer actions report the element actually under the pointer (target, hitsTarget) to catch covered elements. " +
+      "Coordinates are CSS viewport pixels unless coordinateSpace says 'device' (screenshot pixels) or 'css-page', " +
+      "or origin makes them offsets from an element; the result's coordinates field shows the viewport point used. " +
       "For 'type' action, provide the text to type. " +
-      "For 'scroll' action, provide scrollX and/or scrollY amounts.",
+      "For 'clear' action, provide a selector for an input, textarea, or contenteditable; it deletes the contents with " +
+      "beforeinput/input events frameworks notice, and fails if the field isn't empty afterwards. " +
+      "For 'drag' action, provide from and to (selectors or { x, y }); draggable sources get HTML5 drag events. " +
+      "Touch gestures send touch events and pointer events with pointerType 'touch': 'tap' and 'longpress' (holdMs) " +
+      "take a selector or coordinates, 'swipe' takes from and to, and 'pinch' takes a center, startDistance, and endDistance. " +
+      "For 'scroll' action, provide deltaX/deltaY, to: { top, left }, or intoView (a child selector); " +
+      "selector picks the scroll container (default: page). It waits for the scroll to end and returns the final scrollTop/scrollLeft and whether it moved. " +
+      "For 'set_value' action, provide the value for any input or textarea; it works with framework-controlled inputs. " +
+      "For 'set_checked' action, provide checked: true or false for a checkbox, radio, label, or role=switch element; " +
+      "it clicks only if the state differs and verifies the change. " +
+      "For 'select' action, provide value, label, or index (arrays for multi-selects). " +
+      "For 'upload' action, provide files with base64 contents for a file input. " +
+      "For 'focus' action, provide a selector (and optionally preventScroll); it fails if focus doesn't stick, " +
+      "like on a disabled or unfocusable element. For 'blur' action, the selector's element (or whatever is focused) " +
+      "loses focus, which runs blur handlers like form validation. " +
+      "For 'press' action, provide a key and optional modifiers and repeat, for shortcuts like Cmd+S or Tab focus traversal; " +
+      "the result tells whether the page prevented the default. " +
+      "For 'keyboard_sequence' action, provide keys to dispatch as keydown/keypress/keyup events. " +
+      "For 'sequence' action, provide actions (each like a single interaction, plus an optional delayMs) to run in " +
+      "one request, like focus, clear, type, and press Enter; it stops at the first failure and returns per-step results. " +
+      "Set native: true to click, type, or scroll with real OS input, for code that checks event.isTrusted, :active " +
+      "styles, native scrolling, or IME; it moves the real cursor, so the app has to opt in. " +
+      "The result's inputMode says whether native or synthetic (DOM event) input was used.",
     schema: interactSchema,
     handler: handleInteract,
   },
@@ -395,7 +1984,15 @@ export const tools: ToolDefinition[] = [
     name: "tauri_wait_for",
     description:
       "Wait for a condition to be true. " +
-      "Types: selector (element exists), text (text appears), visible (element visible), hidden (element hidden). " +
+      "Types: selector (element exists), text (text appears), visible (element visible), " +
+      "hidden (element hidden or removed, saying which: display: none, visibility: hidden, opacity: 0, zero size, or removed), " +
+      "detached (element no longer in the DOM), function (script returns a truthy value, which is returned), " +
+      "all or any (of the page conditions in conditions, reporting when each was met), " +
+      "console (a console entry matching textContains or regex and level is logged, which is returned), " +
+      "windowCount (atLeast or exactly that many windows are open), " +
+      "tauriEvent (the app emits event, whose payload is returned). " +
+      "Element types take a CSS selector or a selector object like { text: 'Saved' } or { role: 'dialog' }. " +
+      "Returns elapsedMs, polls, and what the last check saw; timeout errors say the same. " +
       "Default timeout: 5000ms.",
     schema: waitForSchema,
     handler: handleWaitFor,
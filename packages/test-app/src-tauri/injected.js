@@ -0,0 +1,2 @@
+// Injected with Builder::inject_js_file, after the inline script from Builder::inject_js
+window.__testAppInjected.push("file");
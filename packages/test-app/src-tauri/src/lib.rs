@@ -1,10 +1,57 @@
 //! Test application for tauri-mcp plugin
 
+use tauri::menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::{AppHandle, Emitter, Runtime};
+
+/// Build the app menu that the menu integration tests click through
+fn build_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
+    let file = Submenu::with_id_and_items(
+        app,
+        "file",
+        "&File",
+        true,
+        &[
+            &MenuItem::with_id(app, "new-todo", "&New todo", true, None::<&str>)?,
+            &MenuItem::with_id(app, "export", "Export", false, None::<&str>)?,
+            &CheckMenuItem::with_id(app, "compact-mode", "Compact mode", true, false, None::<&str>)?,
+        ],
+    )?;
+    let edit = Submenu::with_items(
+        app,
+        "Edit",
+        true,
+        &[
+            &PredefinedMenuItem::copy(app, None)?,
+            &PredefinedMenuItem::paste(app, None)?,
+        ],
+    )?;
+    Menu::with_items(app, &[&file, &edit])
+}
+
+/// Tell the frontend which menu item was activated
+fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, event: MenuEvent) {
+    let _ = app.emit("test-app://menu", event.id().as_ref());
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
-        .plugin(tauri_mcp::init())
+        .plugin(
+            tauri_mcp::Builder::new()
+                .window_alias("primary", "mai*")
+                .cdp_compat(true)
+                .bidi_compat(true)
+                .inject_js("window.__testAppInjected = [document.readyState];")
+                .inject_js_file(concat!(env!("CARGO_MANIFEST_DIR"), "/injected.js"))
+                .build(),
+        )
+        .menu(build_menu)
+        .on_menu_event(handle_menu_event)
+        .setup(|app| {
+            tauri_mcp::on_menu_event(app.handle(), handle_menu_event);
+            Ok(())
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -1,5 +1,6 @@
 import App from "./App.svelte";
 import { mount } from "svelte";
+import "./shadow-elements";
 
 const app = mount(App, {
   target: document.getElementById("app")!,
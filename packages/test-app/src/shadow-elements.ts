@@ -0,0 +1,47 @@
+// Custom elements with shadow roots, for testing selectors and snapshots that cross shadow
+// boundaries: a settings panel with a toggle nested two shadow trees deep, and a widget whose
+// shadow root is closed.
+
+class McpToggle extends HTMLElement {
+  private on = false;
+
+  constructor() {
+    super();
+    const root = this.attachShadow({ mode: "open" });
+    root.innerHTML = `<button type="button" data-testid="notifications-toggle" aria-pressed="false">Notifications</button>`;
+    const button = root.querySelector("button")!;
+    button.addEventListener("click", () => {
+      this.on = !this.on;
+      button.setAttribute("aria-pressed", String(this.on));
+      this.dispatchEvent(new CustomEvent("toggle-change", { detail: this.on, bubbles: true, composed: true }));
+    });
+  }
+}
+
+class McpSettingsPanel extends HTMLElement {
+  constructor() {
+    super();
+    const root = this.attachShadow({ mode: "open" });
+    root.innerHTML = `
+      <h2>Settings</h2>
+      <mcp-toggle></mcp-toggle>
+      <p data-testid="settings-status">Notifications are off</p>
+    `;
+    const status = root.querySelector("p")!;
+    root.addEventListener("toggle-change", (event) => {
+      status.textContent = `Notifications are ${(event as CustomEvent<boolean>).detail ? "on" : "off"}`;
+    });
+  }
+}
+
+class McpSealedWidget extends HTMLElement {
+  constructor() {
+    super();
+    const root = this.attachShadow({ mode: "closed" });
+    root.innerHTML = `<button type="button">Sealed action</button>`;
+  }
+}
+
+customElements.define("mcp-toggle", McpToggle);
+customElements.define("mcp-settings-panel", McpSettingsPanel);
+customElements.define("mcp-sealed-widget", McpSealedWidget);
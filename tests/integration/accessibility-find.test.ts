@@ -0,0 +1,72 @@
+/**
+ * Integration tests for tauri_accessibility_find tool.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+interface AccessibilityMatch {
+  selector: string;
+  role: string | null;
+  name: string | null;
+  description: string | null;
+  state: Record<string, unknown>;
+}
+
+describe("tauri_accessibility_find", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+  });
+
+  afterAll(() => {
+    disconnect();
+  });
+
+  it("should find elements by role and name", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("accessibility_find", {
+      role: "button",
+      name: "Open about window",
+    });
+    expect(response.success).toBe(true);
+
+    const matches = response.data as AccessibilityMatch[];
+    expect(matches.length).toBe(1);
+    expect(matches[0].role).toBe("button");
+    expect(matches[0].selector).toBeTruthy();
+  });
+
+  it("should find elements by role only", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("accessibility_find", {
+      role: "button",
+    });
+    expect(response.success).toBe(true);
+    expect((response.data as AccessibilityMatch[]).length).toBeGreaterThan(1);
+  });
+
+  it("should require role or name", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("accessibility_find", {});
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("'role' or 'name'");
+  });
+});
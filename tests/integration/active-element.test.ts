@@ -0,0 +1,126 @@
+/**
+ * Integration tests for tauri_active_element, and the focus and blur interact actions.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+interface ActiveElement {
+  activeElement: {
+    tagName: string;
+    id: string | null;
+    selector: string;
+    type: string | null;
+    valueLength: number | null;
+    selection: { start: number; end: number; direction: string } | null;
+  } | null;
+  documentHasFocus: boolean;
+}
+
+describe("tauri_active_element", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("active-element-test")?.remove();
+        const container = document.createElement("div");
+        container.id = "active-element-test";
+        container.innerHTML = \`
+          <input id="ae-email" value="user@example.com">
+          <span id="ae-error"></span>
+          <button id="ae-disabled" disabled>Disabled</button>
+          <div id="ae-plain">Not focusable</div>
+          <input id="ae-checkbox" type="checkbox">
+        \`;
+        document.body.appendChild(container);
+        // Validate on blur, like forms do
+        document.getElementById("ae-email").addEventListener("blur", (event) => {
+          document.getElementById("ae-error").textContent = event.target.value.includes("@") ? "ok" : "invalid";
+        });
+        return true;
+      `,
+    });
+  });
+
+  afterAll(async () => {
+    if (!(await skipIfAppNotAvailable())) {
+      await sendCommand("execute_js", {
+        script: `document.getElementById("active-element-test")?.remove(); return true;`,
+      });
+    }
+    disconnect();
+  });
+
+  it("should report the focused element's value length and selection", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `const input = document.getElementById("ae-email"); input.focus(); input.setSelectionRange(0, 4); return true;`,
+    });
+    const response = await sendCommand("active_element", {});
+    expect(response.success).toBe(true);
+    const data = response.data as ActiveElement;
+    expect(data.activeElement).toMatchObject({
+      tagName: "input",
+      id: "ae-email",
+      selector: "#ae-email",
+      type: "text",
+      valueLength: 16,
+      selection: { start: 0, end: 4 },
+    });
+
+    await sendCommand("execute_js", { script: `document.getElementById("ae-checkbox").focus(); return true;` });
+    const checkbox = (await sendCommand("active_element", {})).data as ActiveElement;
+    expect(checkbox.activeElement?.selection).toBeNull();
+  });
+
+  it("should focus elements and fail when focus doesn't stick", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const focused = await sendCommand("interact", { action: "focus", selector: "#ae-email", preventScroll: true });
+    expect(focused.success).toBe(true);
+    expect((focused.data as { focused: string }).focused).toBe("#ae-email");
+
+    const disabled = await sendCommand("interact", { action: "focus", selector: "#ae-disabled" });
+    expect(disabled.success).toBe(false);
+    expect(disabled.error).toContain("it is disabled");
+
+    const plain = await sendCommand("interact", { action: "focus", selector: "#ae-plain" });
+    expect(plain.success).toBe(false);
+    expect(plain.error).toContain("it is not focusable");
+  });
+
+  it("should blur elements so blur handlers run", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("interact", { action: "focus", selector: "#ae-email" });
+    const blurred = await sendCommand("interact", { action: "blur", selector: "#ae-email" });
+    expect(blurred.success).toBe(true);
+    expect((blurred.data as { wasFocused: boolean }).wasFocused).toBe(true);
+
+    const error = await sendCommand("execute_js", { script: `document.getElementById("ae-error").textContent` });
+    expect(error.data).toBe("ok");
+    const active = (await sendCommand("active_element", {})).data as ActiveElement;
+    expect(active.activeElement).toBeNull();
+
+    const again = await sendCommand("interact", { action: "blur" });
+    expect(again.success).toBe(true);
+    expect((again.data as { wasFocused: boolean }).wasFocused).toBe(false);
+  });
+});
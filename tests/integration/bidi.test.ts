@@ -0,0 +1,152 @@
+/**
+ * Integration tests for the WebDriver BiDi endpoint, which the test-app turns on with bidi_compat(true).
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import type WebSocket from "ws";
+import { openBidiConnection, sendCommand, skipIfAppNotAvailable } from "./setup.js";
+
+interface BidiAnswer {
+  type: "success" | "error";
+  id: number;
+  result?: Record<string, unknown>;
+  error?: string;
+  message?: string;
+}
+
+describe("WebDriver BiDi endpoint", () => {
+  let bidi: WebSocket | null = null;
+  let nextId = 1;
+  let context = "main";
+
+  const send = (method: string, params: Record<string, unknown> = {}): Promise<BidiAnswer> => {
+    const id = nextId++;
+    return new Promise((resolve) => {
+      const onMessage = (data: WebSocket.RawData) => {
+        const answer = JSON.parse(data.toString()) as BidiAnswer;
+        if (answer.id !== id) return;
+        bidi?.off("message", onMessage);
+        resolve(answer);
+      };
+      bidi?.on("message", onMessage);
+      bidi?.send(JSON.stringify({ id, method, params }));
+    });
+  };
+
+  const evaluate = (expression: string) =>
+    send("script.evaluate", { expression, awaitPromise: true, target: { context } });
+
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    bidi = await openBidiConnection();
+  });
+
+  afterAll(() => {
+    bidi?.close();
+  });
+
+  it("should list windows as browsing contexts", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const session = await send("session.new", { capabilities: {} });
+    expect(session.type).toBe("success");
+
+    const tree = await send("browsingContext.getTree");
+    const contexts = tree.result?.contexts as Array<{ context: string; url: string; parent: string | null }>;
+    expect(contexts.length).toBeGreaterThan(0);
+    expect(contexts.every((c) => c.parent === null && typeof c.url === "string")).toBe(true);
+    context = contexts.find((c) => c.context === "main")?.context ?? contexts[0].context;
+  });
+
+  it("should evaluate scripts as remote values", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const sum = await evaluate("Promise.resolve(1 + 2)");
+    expect(sum.result).toEqual({ type: "success", result: { type: "number", value: 3 }, realm: context });
+
+    const object = await evaluate("({ list: [1, 'a'] })");
+    expect(object.result?.result).toEqual({
+      type: "object",
+      value: [["list", { type: "array", value: [{ type: "number", value: 1 }, { type: "string", value: "a" }] }]],
+    });
+
+    const thrown = await evaluate("throw new Error('boom')");
+    expect(thrown.type).toBe("success");
+    expect(thrown.result).toMatchObject({ type: "exception", exceptionDetails: { text: "Error: boom" } });
+
+    const missing = await send("script.evaluate", {
+      expression: "1",
+      awaitPromise: true,
+      target: { context: "no-such-window" },
+    });
+    expect(missing.error).toBe("no such frame");
+  });
+
+  it("should take screenshots and perform actions", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const screenshot = await send("browsingContext.captureScreenshot", { context });
+    expect(screenshot.result?.data).toMatch(/^iVBOR/);
+
+    await sendCommand("execute_js", {
+      script: `
+        const input = document.createElement('input');
+        input.id = 'bidi-input';
+        input.style.cssText = 'position: fixed; top: 0; left: 0; width: 100px; height: 20px; z-index: 99999';
+        document.body.appendChild(input);
+      `,
+    });
+    try {
+      const typed = await send("input.performActions", {
+        context,
+        actions: [
+          {
+            type: "pointer",
+            id: "mouse",
+            actions: [
+              { type: "pointerMove", x: 10, y: 10 },
+              { type: "pointerDown", button: 0 },
+              { type: "pointerUp", button: 0 },
+            ],
+          },
+          {
+            type: "key",
+            id: "keyboard",
+            actions: [
+              { type: "keyDown", value: "h" },
+              { type: "keyUp", value: "h" },
+              { type: "keyDown", value: "i" },
+              { type: "keyUp", value: "i" },
+            ],
+          },
+        ],
+      });
+      expect(typed.type).toBe("success");
+
+      const value = await evaluate("document.getElementById('bidi-input').value");
+      expect(value.result?.result).toEqual({ type: "string", value: "hi" });
+    } finally {
+      await sendCommand("execute_js", { script: "document.getElementById('bidi-input')?.remove()" });
+    }
+  });
+
+  it("should refuse commands it doesn't translate", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const answer = await send("browsingContext.navigate", { context, url: "about:blank" });
+    expect(answer.error).toBe("unknown command");
+    expect(answer.message).toContain("script.evaluate");
+  });
+});
@@ -0,0 +1,88 @@
+/**
+ * Integration tests for the CDP endpoint, which the test-app turns on with cdp_compat(true).
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import type WebSocket from "ws";
+import { openCdpConnection, skipIfAppNotAvailable } from "./setup.js";
+
+interface CdpAnswer {
+  id: number;
+  result?: Record<string, unknown>;
+  error?: { code: number; message: string };
+}
+
+describe("CDP endpoint", () => {
+  let cdp: WebSocket | null = null;
+  let nextId = 1;
+
+  const send = (method: string, params: Record<string, unknown> = {}): Promise<CdpAnswer> => {
+    const id = nextId++;
+    return new Promise((resolve) => {
+      const onMessage = (data: WebSocket.RawData) => {
+        const answer = JSON.parse(data.toString()) as CdpAnswer;
+        if (answer.id !== id) return;
+        cdp?.off("message", onMessage);
+        resolve(answer);
+      };
+      cdp?.on("message", onMessage);
+      cdp?.send(JSON.stringify({ id, method, params }));
+    });
+  };
+
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    cdp = await openCdpConnection();
+  });
+
+  afterAll(() => {
+    cdp?.close();
+  });
+
+  it("should evaluate expressions with Runtime.evaluate", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const sum = await send("Runtime.evaluate", { expression: "Promise.resolve(1 + 2)" });
+    expect(sum.result?.result).toEqual({ type: "number", value: 3 });
+
+    const nothing = await send("Runtime.evaluate", { expression: "undefined" });
+    expect(nothing.result?.result).toEqual({ type: "undefined" });
+
+    const thrown = await send("Runtime.evaluate", { expression: "throw new Error('boom')" });
+    expect(thrown.error).toBeUndefined();
+    expect(thrown.result?.exceptionDetails).toMatchObject({ exception: { description: "boom" } });
+  });
+
+  it("should translate screenshots, the document, and mouse events", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const screenshot = await send("Page.captureScreenshot", { format: "png" });
+    expect(screenshot.result?.data).toMatch(/^iVBOR/);
+
+    const document = await send("DOM.getDocument", { depth: 2 });
+    const root = document.result?.root as { nodeName: string; children: Array<{ nodeName: string; children: unknown[] }> };
+    expect(root.nodeName).toBe("#document");
+    expect(root.children[0].nodeName).toBe("HTML");
+    expect(root.children[0].children.length).toBeGreaterThan(0);
+
+    const moved = await send("Input.dispatchMouseEvent", { type: "mouseMoved", x: 5, y: 5 });
+    expect(moved.error).toBeUndefined();
+  });
+
+  it("should refuse methods it doesn't translate", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const answer = await send("Target.getTargets");
+    expect(answer.error?.code).toBe(-32601);
+    expect(answer.error?.message).toContain("Runtime.evaluate");
+  });
+});
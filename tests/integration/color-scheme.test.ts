@@ -0,0 +1,124 @@
+/**
+ * Integration tests for tauri_color_scheme_set tool.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+interface ColorSchemeResult {
+  scheme: string;
+  matchesDark: boolean;
+  rulesApplied: number;
+  skippedStyleSheets: number;
+  listenersNotified: number;
+}
+
+describe("tauri_color_scheme_set", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("color-scheme-test")?.remove();
+        const container = document.createElement("div");
+        container.id = "color-scheme-test";
+        container.innerHTML = \`
+          <style>
+            #scheme-box { width: 10px; }
+            @media (prefers-color-scheme: dark) { #scheme-box { width: 20px; } }
+          </style>
+          <div id="scheme-box"></div>
+        \`;
+        document.body.appendChild(container);
+        return true;
+      `,
+    });
+  });
+
+  afterAll(async () => {
+    // Reloading drops the emulation
+    await sendCommand("execute_js", {
+      script: "setTimeout(() => location.reload(), 0); return true;",
+    });
+    disconnect();
+  });
+
+  it("should apply dark mode rules and report the scheme", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("color_scheme_set", { scheme: "dark" });
+    expect(response.success).toBe(true);
+
+    const result = response.data as ColorSchemeResult;
+    expect(result.scheme).toBe("dark");
+    expect(result.matchesDark).toBe(true);
+    expect(result.rulesApplied).toBeGreaterThanOrEqual(1);
+
+    const width = await sendCommand("execute_js", {
+      script: "return document.getElementById('scheme-box').getBoundingClientRect().width",
+    });
+    expect(width.data).toBe(20);
+  });
+
+  it("should notify matchMedia listeners when the scheme changes", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        window.__schemeChanges = [];
+        const list = window.matchMedia("(prefers-color-scheme: dark)");
+        list.addEventListener("change", (event) => window.__schemeChanges.push(event.matches));
+        window.__schemeList = list;
+        return true;
+      `,
+    });
+
+    const response = await sendCommand("color_scheme_set", { scheme: "light" });
+    expect(response.success).toBe(true);
+    expect((response.data as ColorSchemeResult).matchesDark).toBe(false);
+
+    const changes = await sendCommand("execute_js", {
+      script: "return window.__schemeChanges",
+    });
+    expect(changes.data).toEqual([false]);
+
+    const width = await sendCommand("execute_js", {
+      script: "return document.getElementById('scheme-box').getBoundingClientRect().width",
+    });
+    expect(width.data).toBe(10);
+  });
+
+  it("should reject an unknown scheme", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("color_scheme_set", { scheme: "sepia" });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("Invalid scheme");
+  });
+
+  it("should require a scheme", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("color_scheme_set", {});
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("scheme");
+  });
+});
@@ -0,0 +1,103 @@
+/**
+ * Integration tests for tauri_console_export tool.
+ */
+
+import { readFileSync, rmSync } from "node:fs";
+import { tmpdir } from "node:os";
+import { join } from "node:path";
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+interface ExportResult {
+  path?: string;
+  content?: string;
+  count: number;
+  format: string;
+}
+
+describe("tauri_console_export", () => {
+  const uniqueId = `export-test-${Date.now()}`;
+  const savePath = join(tmpdir(), `${uniqueId}.log`);
+
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+
+    await sendCommand("execute_js", {
+      script: `console.log("${uniqueId} one"); console.warn("${uniqueId} two"); return true;`,
+    });
+  });
+
+  afterAll(() => {
+    rmSync(savePath, { force: true });
+    disconnect();
+  });
+
+  it("should return JSONL inline without a save path", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("console_export", { filter: uniqueId });
+    expect(response.success).toBe(true);
+
+    const result = response.data as ExportResult;
+    expect(result.count).toBe(2);
+    expect(result.format).toBe("jsonl");
+
+    const lines = result.content!.trim().split("\n").map((line) => JSON.parse(line));
+    expect(lines.map((entry) => entry.level)).toEqual(["log", "warn"]);
+    expect(lines[0]).toHaveProperty("navigationId");
+    expect(lines[0]).toHaveProperty("windowLabel");
+  });
+
+  it("should write filtered text to a file", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("console_export", {
+      filter: uniqueId,
+      levels: ["warn"],
+      format: "text",
+      savePath,
+    });
+    expect(response.success).toBe(true);
+
+    const result = response.data as ExportResult;
+    expect(result.path).toBe(savePath);
+    expect(result.count).toBe(1);
+
+    const content = readFileSync(savePath, "utf8");
+    expect(content).toMatch(new RegExp(`^\\[.+\\] \\[warn\\] \\[.+ #\\d+\\] ${uniqueId} two\\n$`));
+  });
+
+  it("should refuse a relative save path", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("console_export", { savePath: "logs/session.log" });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("absolute");
+  });
+
+  it("should reject an unknown format", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("console_export", { format: "csv" });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("format");
+  });
+});
@@ -0,0 +1,123 @@
+/**
+ * Tests for how the console capture script serializes logged values.
+ * Runs the capture script's formatter in the webview via execute_js.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+/** Format the given JS argument expressions like a captured console entry */
+const format = async (argsSource: string): Promise<string> => {
+  const response = await sendCommand("execute_js", {
+    script: `return window.__tauriMcpConsole.format(${argsSource});`,
+  });
+  expect(response.success).toBe(true);
+  return response.data as string;
+};
+
+describe("console capture serialization", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+  });
+
+  afterAll(() => {
+    disconnect();
+  });
+
+  it("should keep strings as-is and JSON-encode objects", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    expect(await format(`"count:", { a: 1, b: [true, null] }`)).toBe('count: {"a":1,"b":[true,null]}');
+  });
+
+  it("should mark circular references", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const message = await format(`(() => { const o = { name: "loop" }; o.self = o; return o; })()`);
+    expect(message).toBe('{"name":"loop","self":"[Circular]"}');
+  });
+
+  it("should serialize errors with name, message, and stack", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const parsed = JSON.parse(await format(`new TypeError("bad input")`)) as Record<string, string>;
+    expect(parsed.name).toBe("TypeError");
+    expect(parsed.message).toBe("bad input");
+    expect(typeof parsed.stack).toBe("string");
+  });
+
+  it("should summarize DOM nodes", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const message = await format(
+      `(() => { const el = document.createElement("div"); el.id = "box"; el.className = "a b"; return el; })()`
+    );
+    expect(message).toBe("<div#box.a.b>");
+  });
+
+  it("should serialize Map and Set as arrays", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    expect(await format(`new Map([["k", 1]]), new Set([1, 2])`)).toBe('[["k",1]] [1,2]');
+  });
+
+  it("should cap serialization depth", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    // Default depth is 4
+    const message = await format(`{ a: { b: { c: { d: { e: 1 } } } } }`);
+    expect(message).toBe('{"a":{"b":{"c":{"d":"[Object]"}}}}');
+  });
+
+  it("should truncate oversized entries with a marker", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    // Default limit is 8 KB
+    const message = await format(`"x".repeat(10000)`);
+    expect(message).toBe(`${"x".repeat(8192)}… [truncated 1808 bytes]`);
+  });
+
+  it("should store serialized objects in captured entries", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const uniqueId = `serialize-test-${Date.now()}`;
+    await sendCommand("execute_js", {
+      script: `const o = { id: "${uniqueId}" }; o.self = o; console.log(o); return true;`,
+    });
+
+    const response = await sendCommand("console_logs", { filter: uniqueId });
+    expect(response.success).toBe(true);
+    const entries = response.data as { message: string }[];
+    expect(entries.map((entry) => entry.message)).toEqual([`{"id":"${uniqueId}","self":"[Circular]"}`]);
+  });
+});
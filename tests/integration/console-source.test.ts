@@ -0,0 +1,80 @@
+/**
+ * Integration tests for finding the source location of console calls in stack traces.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+async function parseSource(stack: string): Promise<unknown> {
+  const response = await sendCommand("execute_js", {
+    script: `return window.__tauriMcpConsole.parseSource(${JSON.stringify(stack)});`,
+  });
+  expect(response.success).toBe(true);
+  return response.data;
+}
+
+describe("console source location", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+  });
+
+  afterAll(() => {
+    disconnect();
+  });
+
+  it("should skip capture script frames in Chromium stacks", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const stack = [
+      "Error",
+      "    at parseSource (tauri-mcp-console-capture.js:170:20)",
+      "    at captureLog (tauri-mcp-console-capture.js:184:22)",
+      "    at console.log (tauri-mcp-console-capture.js:230:35)",
+      "    at handleClick (http://localhost:1420/assets/chunk-ABC.js:1:48231)",
+    ].join("\n");
+
+    expect(await parseSource(stack)).toEqual({
+      url: "http://localhost:1420/assets/chunk-ABC.js",
+      line: 1,
+      column: 48231,
+    });
+  });
+
+  it("should skip capture script and native frames in WebKit stacks", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const stack = [
+      "captureLog@tauri-mcp-console-capture.js:184:22",
+      "log@tauri-mcp-console-capture.js:230:35",
+      "[native code]",
+      "@tauri://localhost/src/main.ts:12:9",
+    ].join("\n");
+
+    expect(await parseSource(stack)).toEqual({
+      url: "tauri://localhost/src/main.ts",
+      line: 12,
+      column: 9,
+    });
+  });
+
+  it("should return null when only capture script frames are present", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    expect(await parseSource("captureLog@tauri-mcp-console-capture.js:1:1")).toBeNull();
+  });
+});
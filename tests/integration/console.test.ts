@@ -6,8 +6,10 @@ import { describe, it, expect, beforeAll, afterAll } from "vitest";
 import {
   connect,
   disconnect,
+  onEvent,
   sendCommand,
   skipIfAppNotAvailable,
+  type PluginEvent,
 } from "./setup.js";
 
 describe("tauri_console_logs", () => {
@@ -35,14 +37,15 @@ describe("tauri_console_logs", () => {
     // Retrieve all logs
     const allLogsResponse = await sendCommand("console_logs", {});
     expect(allLogsResponse.success).toBe(true);
-    expect(typeof allLogsResponse.data).toBe("string");
+    expect(Array.isArray(allLogsResponse.data)).toBe(true);
 
     // Filter logs by our unique message
     const filteredResponse = await sendCommand("console_logs", {
       filter: uniqueId,
     });
     expect(filteredResponse.success).toBe(true);
-    expect((filteredResponse.data as string)).toContain(uniqueId);
+    const entries = filteredResponse.data as { message: string }[];
+    expect(entries.some((entry) => entry.message === uniqueId)).toBe(true);
   });
 
   it("should clear logs when requested", async (ctx) => {
@@ -66,6 +69,300 @@ describe("tauri_console_logs", () => {
     expect(afterClearResponse.success).toBe(true);
   });
 
+  it("should tag entries with the window label and keep them across reloads", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const uniqueId = `reload-test-${Date.now()}`;
+    await sendCommand("execute_js", {
+      script: `console.log("${uniqueId}")`,
+    });
+
+    // Reload the page; logs are stored on the Rust side so they survive
+    await sendCommand("execute_js", {
+      script: "setTimeout(() => location.reload(), 0); return true;",
+    });
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+
+    const response = await sendCommand("console_logs", { filter: uniqueId });
+    expect(response.success).toBe(true);
+
+    const entries = response.data as { message: string; windowLabel: string }[];
+    expect(entries.length).toBe(1);
+    expect(entries[0].message).toBe(uniqueId);
+    expect(entries[0].windowLabel).toBe(response.windowContext?.windowLabel);
+  });
+
+  it("should tag entries with a navigation ID and filter to the current page load", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const before = `nav-before-${Date.now()}`;
+    const after = `nav-after-${Date.now()}`;
+    await sendCommand("execute_js", {
+      script: `console.log("${before}")`,
+    });
+    await sendCommand("execute_js", {
+      script: "setTimeout(() => location.reload(), 0); return true;",
+    });
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await sendCommand("execute_js", {
+      script: `console.log("${after}")`,
+    });
+
+    const filter = `${before}|${after}`;
+    const all = await sendCommand("console_logs", { filter, navigation: "all" });
+    expect(all.success).toBe(true);
+    const entries = all.data as { message: string; navigationId: number }[];
+    expect(entries.map((entry) => entry.message)).toEqual([before, after]);
+    expect(entries[1].navigationId).toBeGreaterThan(entries[0].navigationId);
+
+    const current = await sendCommand("console_logs", { filter, navigation: "current" });
+    expect(current.success).toBe(true);
+    expect((current.data as { message: string }[]).map((entry) => entry.message)).toEqual([after]);
+  });
+
+  it("should reject an invalid navigation value", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("console_logs", { navigation: "previous" });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("navigation");
+  });
+
+  it("should capture uncaught errors and unhandled rejections", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const uniqueId = `uncaught-test-${Date.now()}`;
+    await sendCommand("execute_js", {
+      script: `
+        setTimeout(() => { throw new Error("${uniqueId}-error"); }, 0);
+        Promise.reject(new Error("${uniqueId}-rejection"));
+        console.log("${uniqueId}-log");
+        return true;
+      `,
+    });
+    await new Promise((resolve) => setTimeout(resolve, 200));
+
+    const response = await sendCommand("console_logs", {
+      filter: uniqueId,
+      levels: ["uncaught"],
+    });
+    expect(response.success).toBe(true);
+
+    const entries = response.data as { level: string; message: string; stack?: string }[];
+    expect(entries.length).toBe(2);
+    expect(entries.every((entry) => entry.level === "uncaught")).toBe(true);
+    expect(entries.some((entry) => entry.message.includes(`${uniqueId}-error`))).toBe(true);
+    expect(entries.some((entry) => entry.message.includes(`${uniqueId}-rejection`))).toBe(true);
+    expect(entries.every((entry) => typeof entry.stack === "string")).toBe(true);
+  });
+
+  it("should stream console entries to subscribers with level filtering", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const received: PluginEvent[] = [];
+    const removeListener = onEvent((event) => received.push(event));
+
+    try {
+      const subscribeResponse = await sendCommand("subscribe", {
+        topic: "console",
+        levels: ["warn", "error"],
+      });
+      expect(subscribeResponse.success).toBe(true);
+
+      const uniqueId = `stream-test-${Date.now()}`;
+      await sendCommand("execute_js", {
+        script: `
+          console.debug("${uniqueId}-debug");
+          console.warn("${uniqueId}-warn");
+          console.error("${uniqueId}-error");
+          return true;
+        `,
+      });
+      await new Promise((resolve) => setTimeout(resolve, 200));
+
+      const messages = received
+        .filter((event) => event.event === "console")
+        .map((event) => (event.data as { message: string }).message)
+        .filter((message) => message.startsWith(uniqueId));
+      expect(messages).toEqual([`${uniqueId}-warn`, `${uniqueId}-error`]);
+    } finally {
+      await sendCommand("unsubscribe", { topic: "console" });
+      removeListener();
+    }
+  });
+
+  it("should stop streaming after unsubscribe", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const received: PluginEvent[] = [];
+    const removeListener = onEvent((event) => received.push(event));
+
+    await sendCommand("subscribe", { topic: "console" });
+    const unsubscribeResponse = await sendCommand("unsubscribe", { topic: "console" });
+    expect(unsubscribeResponse.success).toBe(true);
+    expect((unsubscribeResponse.data as { wasSubscribed: boolean }).wasSubscribed).toBe(true);
+
+    await sendCommand("execute_js", {
+      script: `console.log("after-unsubscribe-${Date.now()}")`,
+    });
+    await new Promise((resolve) => setTimeout(resolve, 200));
+    removeListener();
+
+    expect(received.length).toBe(0);
+  });
+
+  it("should reject an unknown subscription topic", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("subscribe", { topic: "nope" });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("Unknown topic");
+  });
+
+  it("should paginate filtered logs with metadata", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const uniqueId = `page-test-${Date.now()}`;
+    await sendCommand("execute_js", {
+      script: `for (let i = 0; i < 5; i++) console.log("${uniqueId} " + i); return true;`,
+    });
+
+    const response = await sendCommand("console_logs", {
+      filter: uniqueId,
+      offset: 1,
+      limit: 2,
+    });
+    expect(response.success).toBe(true);
+
+    const page = response.data as {
+      entries: { message: string }[];
+      total: number;
+      dropped: number;
+      oldestTimestamp: string | null;
+    };
+    expect(page.entries.map((entry) => entry.message)).toEqual([`${uniqueId} 1`, `${uniqueId} 2`]);
+    expect(page.total).toBe(5);
+    expect(typeof page.dropped).toBe("number");
+    expect(typeof page.oldestTimestamp).toBe("string");
+  });
+
+  it("should wrap results when paged is set without offset or limit", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("console_logs", { paged: true });
+    expect(response.success).toBe(true);
+    expect(Array.isArray((response.data as { entries: unknown[] }).entries)).toBe(true);
+  });
+
+  it("should return rapid-fire entries exactly once when polling by seq", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const uniqueId = `seq-test-${Date.now()}`;
+    await sendCommand("execute_js", {
+      script: `console.log("${uniqueId} a"); return true;`,
+    });
+
+    const first = await sendCommand("console_logs", { filter: uniqueId });
+    const firstEntries = first.data as { message: string; seq: number; timestampMs: number }[];
+    expect(firstEntries.map((entry) => entry.message)).toEqual([`${uniqueId} a`]);
+    const lastSeq = firstEntries[0].seq;
+
+    // Likely logged within the same millisecond
+    await sendCommand("execute_js", {
+      script: `console.log("${uniqueId} b"); console.log("${uniqueId} c"); return true;`,
+    });
+
+    const second = await sendCommand("console_logs", { filter: uniqueId, since: lastSeq });
+    const secondEntries = second.data as { message: string; seq: number; timestampMs: number }[];
+    expect(secondEntries.map((entry) => entry.message)).toEqual([`${uniqueId} b`, `${uniqueId} c`]);
+    expect(secondEntries[1].seq).toBeGreaterThan(secondEntries[0].seq);
+    expect(secondEntries[0].timestampMs).toBeGreaterThan(0);
+  });
+
+  it("should reject a non-numeric, non-string since", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("console_logs", { since: true });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("since");
+  });
+
+  it("should reject a negative offset", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("console_logs", { offset: -1 });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("offset");
+  });
+
+  it("should reject a non-array levels filter", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("console_logs", { levels: "error" });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("levels");
+  });
+
+  it("should merge logs from all windows with windowId \"*\"", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const uniqueId = `all-windows-${Date.now()}`;
+    const logged = await sendCommand("execute_js", {
+      script: `console.log("${uniqueId}"); return true;`,
+    });
+
+    for (const args of [{ windowId: "*" }, { allWindows: true }]) {
+      const response = await sendCommand("console_logs", { ...args, filter: uniqueId });
+      expect(response.success).toBe(true);
+
+      const entries = response.data as { message: string; windowLabel: string }[];
+      expect(entries.length).toBe(1);
+      expect(entries[0].windowLabel).toBe(logged.windowContext?.windowLabel);
+    }
+  });
+
   it("should fail for non-existent window", async (ctx) => {
     if (await skipIfAppNotAvailable()) {
       ctx.skip();
@@ -0,0 +1,65 @@
+/**
+ * Integration tests for sharing one run between requests with the same dedup_key.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+// Counts its runs, and takes long enough for a second request to arrive while it runs
+const COUNTING_SCRIPT = `
+  window.__dedupRuns = (window.__dedupRuns ?? 0) + 1;
+  const run = window.__dedupRuns;
+  await new Promise((resolve) => setTimeout(resolve, 300));
+  return run;
+`;
+
+describe("request deduplication", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+    await sendCommand("execute_js", { script: "window.__dedupRuns = 0; return true;" });
+  });
+
+  afterAll(async () => {
+    if (!(await skipIfAppNotAvailable())) {
+      await sendCommand("execute_js", { script: "delete window.__dedupRuns; return true;" });
+    }
+    disconnect();
+  });
+
+  it("should run identical in-flight requests once", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const [first, second] = await Promise.all([
+      sendCommand("execute_js", { script: COUNTING_SCRIPT }, undefined, "dedup-test"),
+      sendCommand("execute_js", { script: COUNTING_SCRIPT }, undefined, "dedup-test"),
+    ]);
+    expect(first.success).toBe(true);
+    expect(second.success).toBe(true);
+    expect(first.id).not.toBe(second.id);
+    expect(second.data).toBe(first.data);
+
+    const runs = await sendCommand("execute_js", { script: "window.__dedupRuns" });
+    expect(runs.data).toBe(1);
+  });
+
+  it("should run again once the first request finished", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const before = (await sendCommand("execute_js", { script: "window.__dedupRuns" })).data as number;
+    await sendCommand("execute_js", { script: COUNTING_SCRIPT }, undefined, "dedup-test-sequential");
+    const again = await sendCommand("execute_js", { script: COUNTING_SCRIPT }, undefined, "dedup-test-sequential");
+    expect(again.data).toBe(before + 2);
+  });
+});
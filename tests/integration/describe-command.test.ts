@@ -0,0 +1,61 @@
+/**
+ * Integration tests for argument schemas and describe_command.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import { connect, disconnect, sendCommand, skipIfAppNotAvailable } from "./setup.js";
+
+interface CommandDescription {
+  command: string;
+  description: string;
+  args: { name: string; type: string; required: boolean; description: string }[];
+}
+
+describe("describe_command", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+  });
+
+  afterAll(() => {
+    disconnect();
+  });
+
+  it("should list commands and describe one's arguments", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const list = await sendCommand("describe_command", {});
+    expect(list.success).toBe(true);
+    const commands = (list.data as { commands: { command: string }[] }).commands.map((entry) => entry.command);
+    expect(commands).toContain("dom_snapshot");
+    expect(commands).toContain("interact");
+
+    const response = await sendCommand("describe_command", { command: "execute_js" });
+    expect(response.success).toBe(true);
+    const { args } = response.data as CommandDescription;
+    expect(args.find((arg) => arg.name === "script")).toMatchObject({ type: "string", required: true });
+
+    const unknown = await sendCommand("describe_command", { command: "nope" });
+    expect(unknown.success).toBe(false);
+    expect(unknown.error).toContain("No schema for 'nope'");
+  });
+
+  it("should reject args that don't match the schema before running the command", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const wrongType = await sendCommand("dom_snapshot", { maxDepth: "3" });
+    expect(wrongType.success).toBe(false);
+    expect(wrongType.error).toMatch(/^INVALID_ARGUMENT: 'maxDepth' must be an integer, got: "3"/);
+
+    const missing = await sendCommand("execute_js", {});
+    expect(missing.success).toBe(false);
+    expect(missing.error).toContain("INVALID_ARGUMENT: Missing required 'script' argument");
+    expect(missing.error).toContain('describe_command with { "command": "execute_js" }');
+  });
+});
@@ -10,6 +10,19 @@ import {
   skipIfAppNotAvailable,
 } from "./setup.js";
 
+interface SnapshotNode {
+  tag: string;
+  role: string | null;
+  name: string | null;
+  attrs: Record<string, unknown>;
+  rect?: { x: number; y: number; width: number; height: number };
+  visible?: boolean;
+  styles?: Record<string, string>;
+  ref?: string;
+  children: SnapshotNode[];
+  truncated?: number;
+}
+
 describe("tauri_dom_snapshot", () => {
   beforeAll(async () => {
     if (await skipIfAppNotAvailable()) return;
@@ -51,6 +64,609 @@ describe("tauri_dom_snapshot", () => {
     expect(typeof scopedResponse.data).toBe("string");
   });
 
+  it("should descend into open shadow roots and mark closed ones", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const structure = await sendCommand("dom_snapshot", { type: "structure", selector: "[data-testid=shadow-demo]" });
+    expect(structure.success).toBe(true);
+    const tree = structure.data as string;
+    expect(tree).toContain("mcp-settings-panel @settings-panel [shadowRoot: true]");
+    expect(tree).toContain("mcp-toggle [shadowRoot: true]");
+    expect(tree).toContain("#shadow-root");
+    expect(tree).toContain("button @notifications-toggle");
+    expect(tree).toContain("mcp-sealed-widget @sealed-widget [shadowRoot: closed]");
+    expect(tree).not.toContain("Sealed action");
+
+    const accessibility = await sendCommand("dom_snapshot", {
+      type: "accessibility",
+      selector: "[data-testid=shadow-demo]",
+    });
+    expect(accessibility.success).toBe(true);
+    const yaml = accessibility.data as string;
+    expect(yaml).toContain("shadowRoot: true");
+    expect(yaml).toContain("name: Notifications");
+    expect(yaml).toContain(
+      'selector: "[data-testid=\\"settings-panel\\"] >>> mcp-toggle >>> [data-testid=\\"notifications-toggle\\"]"'
+    );
+    expect(yaml).toContain("shadowRoot: closed");
+    expect(yaml).toContain('note: "Closed shadow root, its contents can\'t be inspected"');
+
+    // Scoping to an element inside a shadow root
+    const scoped = await sendCommand("dom_snapshot", {
+      type: "structure",
+      selector: "[data-testid=settings-status]",
+      pierceShadow: true,
+    });
+    expect(scoped.success).toBe(true);
+    expect(scoped.data).toBe("p @settings-status");
+  });
+
+  it("should cut big trees breadth-first with maxNodes and maxDepth", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        const list = document.createElement("ul");
+        list.id = "snapshot-big-list";
+        for (let i = 0; i < 10000; i++) {
+          const item = document.createElement("li");
+          item.textContent = "Item " + i;
+          list.appendChild(item);
+        }
+        document.body.appendChild(list);
+        return true;
+      `,
+    });
+
+    try {
+      const byNodes = await sendCommand("dom_snapshot", {
+        type: "structure",
+        selector: "#snapshot-big-list",
+        maxNodes: 4,
+      });
+      expect(byNodes.success).toBe(true);
+      expect(byNodes.data).toEqual({
+        snapshot: "ul#snapshot-big-list\n├─ li\n├─ li\n├─ li\n└─ …truncated (9997 more nodes)",
+        truncated: true,
+        totalNodes: 10001,
+        shownNodes: 4,
+      });
+
+      const byDepth = await sendCommand("dom_snapshot", {
+        type: "accessibility",
+        selector: "#snapshot-big-list",
+        maxDepth: 0,
+      });
+      expect(byDepth.success).toBe(true);
+      const data = byDepth.data as { snapshot: string; truncated: boolean; totalNodes: number; shownNodes: number };
+      expect(data.truncated).toBe(true);
+      expect(data.totalNodes).toBe(10001);
+      expect(data.shownNodes).toBe(1);
+      expect(data.snapshot).toContain("role: list");
+      expect(data.snapshot).toContain("- …truncated (10000 more nodes)");
+
+      // Limits the tree fits in leave the snapshot a plain string
+      const untouched = await sendCommand("dom_snapshot", {
+        type: "structure",
+        selector: "[data-testid=settings-status]",
+        pierceShadow: true,
+        maxNodes: 1,
+      });
+      expect(untouched.data).toBe("p @settings-status");
+
+      const invalid = await sendCommand("dom_snapshot", { type: "structure", maxNodes: 0 });
+      expect(invalid.success).toBe(false);
+      expect(invalid.error).toContain("'maxNodes' must be an integer of at least 1");
+    } finally {
+      await sendCommand("execute_js", {
+        script: `document.getElementById("snapshot-big-list")?.remove(); return true;`,
+      });
+    }
+  });
+
+  it("should match the fixture page in YAML and JSON", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const selector = "[data-testid=snapshot-fixture]";
+
+    const yaml = await sendCommand("dom_snapshot", { type: "accessibility", selector });
+    expect(yaml.success).toBe(true);
+    expect(yaml.data).toBe(
+      [
+        "- tag: section",
+        "  role: region",
+        "  name: Snapshot fixture",
+        '  selector: "[data-testid=\\"snapshot-fixture\\"]"',
+        "- tag: h2",
+        "  role: heading",
+        "  name: Fixture heading",
+        '  selector: "#snapshot-fixture-title"',
+        "- tag: nav",
+        "  role: navigation",
+        "  name: Fixture links",
+        '  selector: "[data-testid=\\"fixture-nav\\"]"',
+        "- tag: a",
+        "  role: link",
+        "  name: Docs",
+        '  selector: "[data-testid=\\"fixture-link\\"]"',
+        "- tag: div",
+        "  role: button",
+        "  name: Bold",
+        "  pressed: true",
+        '  selector: "[data-testid=\\"fixture-toggle\\"]"',
+      ].join("\n")
+    );
+
+    const json = await sendCommand("dom_snapshot", { type: "accessibility", selector, format: "json" });
+    expect(json.success).toBe(true);
+    expect(json.data).toEqual({
+      tag: "section",
+      role: "region",
+      name: "Snapshot fixture",
+      attrs: { selector: '[data-testid="snapshot-fixture"]' },
+      children: [
+        { tag: "h2", role: "heading", name: "Fixture heading", attrs: { selector: "#snapshot-fixture-title" }, children: [] },
+        {
+          tag: "nav",
+          role: "navigation",
+          name: "Fixture links",
+          attrs: { selector: '[data-testid="fixture-nav"]' },
+          children: [
+            { tag: "a", role: "link", name: "Docs", attrs: { selector: '[data-testid="fixture-link"]' }, children: [] },
+          ],
+        },
+        {
+          tag: "div",
+          role: "button",
+          name: "Bold",
+          attrs: { pressed: true, selector: '[data-testid="fixture-toggle"]' },
+          children: [],
+        },
+      ],
+    });
+
+    const structure = await sendCommand("dom_snapshot", { type: "structure", selector });
+    expect(structure.data).toBe(
+      [
+        "section @snapshot-fixture",
+        "├─ h2#snapshot-fixture-title",
+        "├─ nav @fixture-nav",
+        "│  └─ a @fixture-link",
+        "├─ div @fixture-toggle",
+        "└─ div",
+      ].join("\n")
+    );
+
+    const structureJson = await sendCommand("dom_snapshot", { type: "structure", selector, format: "json" });
+    const leaf = (tag: string, attrs: Record<string, string> = {}) => ({ tag, role: null, name: null, attrs, children: [] });
+    expect(structureJson.data).toEqual({
+      tag: "section",
+      role: null,
+      name: null,
+      attrs: { "data-testid": "snapshot-fixture" },
+      children: [
+        leaf("h2", { id: "snapshot-fixture-title" }),
+        { ...leaf("nav", { "data-testid": "fixture-nav" }), children: [leaf("a", { "data-testid": "fixture-link" })] },
+        leaf("div", { "data-testid": "fixture-toggle" }),
+        leaf("div"),
+      ],
+    });
+
+    // Limits mark cut nodes in JSON too
+    const cut = await sendCommand("dom_snapshot", { type: "structure", selector, format: "json", maxDepth: 0 });
+    const data = cut.data as { snapshot: SnapshotNode; truncated: boolean; totalNodes: number };
+    expect(data.truncated).toBe(true);
+    expect(data.totalNodes).toBe(6);
+    expect(data.snapshot.children).toEqual([]);
+    expect(data.snapshot.truncated).toBe(5);
+
+    const invalid = await sendCommand("dom_snapshot", { type: "structure", format: "xml" });
+    expect(invalid.success).toBe(false);
+    expect(invalid.error).toContain("'format' must be");
+  });
+
+  it("should add rects and visibility with includeRects", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const selector = "[data-testid=snapshot-fixture]";
+    await sendCommand("execute_js", {
+      script: `document.querySelector("[data-testid=snapshot-fixture]").scrollIntoView({ block: "start" }); return true;`,
+    });
+
+    const json = await sendCommand("dom_snapshot", { type: "structure", selector, format: "json", includeRects: true });
+    expect(json.success).toBe(true);
+    const tree = json.data as SnapshotNode;
+    expect(tree.visible).toBe(true);
+    expect(tree.rect?.width).toBeGreaterThan(0);
+    const [heading, , toggle, hidden] = tree.children;
+    expect(heading.visible).toBe(true);
+    expect(Number.isInteger(heading.rect?.y)).toBe(true);
+    expect(heading.rect!.y).toBeGreaterThanOrEqual(tree.rect!.y);
+
+    // The rect matches what element_info reports for the same node
+    const info = await sendCommand("element_info", { selector: "[data-testid=fixture-toggle]" });
+    const box = (info.data as { boundingClientRect: { x: number; y: number } }).boundingClientRect;
+    expect(toggle.rect?.x).toBe(Math.round(box.x));
+    expect(toggle.rect?.y).toBe(Math.round(box.y));
+
+    expect(hidden).toMatchObject({ rect: { x: 0, y: 0, width: 0, height: 0 }, visible: false });
+
+    const text = await sendCommand("dom_snapshot", { type: "structure", selector, includeRects: true });
+    expect(text.data as string).toMatch(/^section @snapshot-fixture \[rect: -?\d+,-?\d+ \d+x\d+\]/);
+    expect(text.data as string).toContain("└─ div [rect: 0,0 0x0] [visible: false]");
+
+    const yaml = await sendCommand("dom_snapshot", { type: "accessibility", selector, includeRects: true });
+    expect(yaml.data as string).toMatch(/  rect: \{ x: -?\d+, y: -?\d+, width: \d+, height: \d+ \}\n  visible: true/);
+
+    // Off by default
+    const plain = await sendCommand("dom_snapshot", { type: "structure", selector });
+    expect(plain.data as string).not.toContain("[rect:");
+  });
+
+  it("should add computed styles with computedStyles", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const selector = "[data-testid=snapshot-fixture]";
+    const computedStyles = ["display", "visibility", "--unset-custom-property"];
+    const json = await sendCommand("dom_snapshot", { type: "structure", selector, format: "json", computedStyles });
+    expect(json.success).toBe(true);
+    const tree = json.data as SnapshotNode;
+    expect(tree.styles).toEqual({ display: "block", visibility: "visible", "--unset-custom-property": "" });
+    const hidden = tree.children[3];
+    expect(hidden.styles?.display).toBe("none");
+
+    const text = await sendCommand("dom_snapshot", { type: "structure", selector, computedStyles: ["display"] });
+    expect(text.data as string).toContain("└─ div {display: none}");
+
+    const yaml = await sendCommand("dom_snapshot", { type: "accessibility", selector, computedStyles: ["display"] });
+    expect(yaml.data as string).toContain("  styles:\n    display: block");
+
+    const invalid = await sendCommand("dom_snapshot", { type: "structure", selector, computedStyles: ["zIndex"] });
+    expect(invalid.success).toBe(false);
+    expect(invalid.error).toContain("Use names as written in CSS");
+  });
+
+  it("should serialize to HTML and Markdown", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const selector = "[data-testid=snapshot-fixture]";
+    await sendCommand("execute_js", {
+      script: `document.querySelector("[data-testid=fixture-toggle]").setAttribute("onclick", "alert(1)"); return true;`,
+    });
+    try {
+      const html = await sendCommand("dom_snapshot", { type: "structure", selector, format: "html" });
+      expect(html.success).toBe(true);
+      const markup = html.data as string;
+      expect(markup).toMatch(/^<section[^>]*data-testid="snapshot-fixture"/);
+      expect(markup).toMatch(/<h2[^>]*>Fixture heading<\/h2>/);
+      expect(markup).toContain('aria-pressed="true"');
+      expect(markup).not.toContain("onclick");
+    } finally {
+      await sendCommand("execute_js", {
+        script: `document.querySelector("[data-testid=fixture-toggle]").removeAttribute("onclick"); return true;`,
+      });
+    }
+
+    const markdown = await sendCommand("dom_snapshot", { type: "structure", selector, format: "markdown" });
+    expect(markdown.success).toBe(true);
+    const text = markdown.data as string;
+    expect(text).toContain("## Fixture heading");
+    expect(text).toContain("[Docs](#snapshot-fixture-title)");
+    expect(text).toContain("Bold");
+    expect(text).not.toContain("Not rendered");
+
+    const cut = await sendCommand("dom_snapshot", { type: "structure", selector, format: "html", maxLength: 60 });
+    expect(cut.success).toBe(true);
+    const { snapshot, truncated, totalLength } = cut.data as { snapshot: string; truncated: boolean; totalLength: number };
+    expect(truncated).toBe(true);
+    expect(totalLength).toBe(markup.length);
+    expect(snapshot).toMatch(/<!-- …truncated \(\d+ more characters\) -->$/);
+
+    const rejected = await sendCommand("dom_snapshot", { type: "structure", selector, format: "html", includeRects: true });
+    expect(rejected.success).toBe(false);
+  });
+
+  it("should keep interactive elements with refs that interact accepts", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const selector = "[data-testid=snapshot-fixture]";
+    const response = await sendCommand("dom_snapshot", {
+      type: "accessibility",
+      selector,
+      format: "json",
+      interactiveOnly: true,
+    });
+    expect(response.success).toBe(true);
+    const tree = response.data as SnapshotNode;
+    // The heading isn't interactive; the nav stays for context around the link
+    expect(tree.children.map((child) => child.role)).toEqual(["navigation", "button"]);
+    const [nav, toggle] = tree.children;
+    expect(nav.ref).toBeUndefined();
+    expect(nav.children[0].ref).toMatch(/^e\d+$/);
+    expect(toggle.ref).toMatch(/^e\d+$/);
+
+    // The same element keeps its ref
+    const again = await sendCommand("dom_snapshot", { type: "accessibility", selector, interactiveOnly: true });
+    expect(again.success).toBe(true);
+    expect(again.data as string).toContain(`ref: ${toggle.ref}`);
+
+    const focus = await sendCommand("interact", { action: "focus", selector: { ref: toggle.ref } });
+    expect(focus.success).toBe(true);
+    const active = await sendCommand("execute_js", { script: "return document.activeElement.dataset.testid" });
+    expect(active.data).toBe("fixture-toggle");
+
+    const headings = await sendCommand("dom_snapshot", {
+      type: "accessibility",
+      selector,
+      format: "json",
+      roles: ["heading"],
+    });
+    expect(headings.success).toBe(true);
+    const [heading] = (headings.data as SnapshotNode).children;
+    expect(heading.name).toBe("Fixture heading");
+    expect(heading.ref).toMatch(/^e\d+$/);
+
+    const unknown = await sendCommand("interact", { action: "click", selector: { ref: "e999999" } });
+    expect(unknown.success).toBe(false);
+    expect(unknown.error).toContain("Unknown ref: e999999");
+
+    const both = await sendCommand("dom_snapshot", { type: "accessibility", interactiveOnly: true, roles: ["link"] });
+    expect(both.success).toBe(false);
+  });
+
+  it("should fail with STALE_REF for refs of removed elements and older snapshots", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const render = (label: string) =>
+      sendCommand("execute_js", {
+        script: `
+          let probe = document.querySelector("[data-testid=stale-ref-probe]");
+          if (!probe) {
+            probe = document.createElement("div");
+            probe.dataset.testid = "stale-ref-probe";
+            document.body.append(probe);
+          }
+          probe.innerHTML = "<button>${label}</button>";
+        `,
+      });
+    const snapshot = async () => {
+      const response = await sendCommand("dom_snapshot", {
+        type: "accessibility",
+        selector: "[data-testid=stale-ref-probe]",
+        format: "json",
+        interactiveOnly: true,
+      });
+      expect(response.success).toBe(true);
+      return (response.data as SnapshotNode).children[0].ref as string;
+    };
+
+    try {
+      await render("Probe");
+      const ref = await snapshot();
+      const info = await sendCommand("element_info", { selector: { ref } });
+      expect(info.success).toBe(true);
+
+      // A re-render replaces the button, so its ref is stale rather than pointing at the new one
+      await render("Probe again");
+      const removed = await sendCommand("interact", { action: "click", selector: { ref } });
+      expect(removed.success).toBe(false);
+      expect(removed.error).toContain(`STALE_REF: ref ${ref} (button "Probe") was removed`);
+
+      const fresh = await snapshot();
+      expect(fresh).not.toBe(ref);
+      const focus = await sendCommand("interact", { action: "focus", selector: { ref: fresh } });
+      expect(focus.success).toBe(true);
+
+      // A later snapshot that doesn't hand the ref out again makes it stale too
+      await sendCommand("dom_snapshot", { type: "accessibility", roles: ["heading"] });
+      const older = await sendCommand("element_info", { selector: { ref: fresh } });
+      expect(older.success).toBe(false);
+      expect(older.error).toContain("is from an older dom_snapshot");
+
+      const current = await snapshot();
+      expect(current).toBe(fresh);
+      const cleared = await sendCommand("refs_clear", {});
+      expect(cleared.success).toBe(true);
+      expect((cleared.data as { cleared: number }).cleared).toBeGreaterThan(0);
+      const forgotten = await sendCommand("interact", { action: "focus", selector: { ref: current } });
+      expect(forgotten.success).toBe(false);
+      expect(forgotten.error).toContain(`STALE_REF: ref ${current} was dropped`);
+    } finally {
+      await sendCommand("execute_js", {
+        script: 'document.querySelector("[data-testid=stale-ref-probe]")?.remove()',
+      });
+    }
+  });
+
+  it("should return a snapshot in pages of pageSize nodes", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const selector = "[data-testid=snapshot-fixture]";
+    const whole = await sendCommand("dom_snapshot", { type: "structure", selector });
+    expect(whole.success).toBe(true);
+
+    const pages: string[] = [];
+    let response = await sendCommand("dom_snapshot", { type: "structure", selector, pageSize: 2 });
+    for (;;) {
+      expect(response.success).toBe(true);
+      const { snapshot, page, pages: count, nextPage } = response.data as {
+        snapshot: string;
+        page: number;
+        pages: number;
+        nextPage?: string;
+      };
+      pages.push(snapshot);
+      expect(page).toBe(pages.length);
+      if (nextPage === undefined) {
+        expect(count).toBe(pages.length);
+        break;
+      }
+      response = await sendCommand("dom_snapshot", { page: nextPage });
+    }
+    expect(pages.length).toBeGreaterThan(1);
+    expect(pages.join("\n")).toBe(whole.data);
+
+    const json = await sendCommand("dom_snapshot", { type: "accessibility", selector, format: "json", pageSize: 1 });
+    expect(json.success).toBe(true);
+    const first = json.data as { snapshot: Array<SnapshotNode & { depth: number }> };
+    expect(first.snapshot).toHaveLength(1);
+    expect(first.snapshot[0].depth).toBe(0);
+
+    const gone = await sendCommand("dom_snapshot", { page: "pages-0:1" });
+    expect(gone.success).toBe(false);
+    expect(gone.error).toContain("is gone");
+  });
+
+  it("should descend into same-origin iframes", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        const frame = document.createElement("iframe");
+        frame.dataset.testid = "snapshot-frame";
+        frame.srcdoc = "<button id=inside>Inside</button>";
+        document.body.append(frame);
+      `,
+    });
+    // srcdoc frames load asynchronously
+    await new Promise((resolve) => setTimeout(resolve, 500));
+
+    try {
+      const selector = "[data-testid=snapshot-frame]";
+      const structure = await sendCommand("dom_snapshot", { type: "structure", selector });
+      expect(structure.success).toBe(true);
+      expect(structure.data as string).toContain("[frame: about:srcdoc]");
+      expect(structure.data as string).toContain("button#inside");
+
+      const response = await sendCommand("dom_snapshot", {
+        type: "accessibility",
+        selector,
+        format: "json",
+        interactiveOnly: true,
+      });
+      expect(response.success).toBe(true);
+      const frame = response.data as SnapshotNode;
+      expect(frame.attrs.frame).toBe("about:srcdoc");
+      const button = JSON.stringify(frame).match(/"ref":"(e\d+)"/);
+      expect(button).not.toBeNull();
+
+      // Refs and >>> selectors both reach into the frame
+      const byRef = await sendCommand("element_info", { selector: { ref: button?.[1] } });
+      expect(byRef.success).toBe(true);
+      const byCss = await sendCommand("interact", {
+        action: "focus",
+        selector: `${selector} >>> #inside`,
+      });
+      expect(byCss.success).toBe(true);
+
+      const shallow = await sendCommand("dom_snapshot", { type: "structure", selector, maxFrameDepth: 0 });
+      expect(shallow.success).toBe(true);
+      expect(shallow.data as string).not.toContain("button#inside");
+    } finally {
+      await sendCommand("execute_js", {
+        script: 'document.querySelector("[data-testid=snapshot-frame]")?.remove()',
+      });
+    }
+  });
+
+  it("should diff against a stored or returned baseline with dom_diff", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const selector = "[data-testid=snapshot-fixture]";
+    const stored = await sendCommand("dom_snapshot", { type: "structure", selector, store: true });
+    expect(stored.success).toBe(true);
+    const { snapshot, snapshotId } = stored.data as { snapshot: string; snapshotId: string };
+    expect(snapshot).toMatch(/^section @snapshot-fixture/);
+    const baseline = await sendCommand("dom_snapshot", { type: "accessibility", selector, format: "json" });
+
+    await sendCommand("execute_js", {
+      script: `
+        const fixture = document.querySelector("[data-testid=snapshot-fixture]");
+        const note = document.createElement("p");
+        note.dataset.testid = "fixture-note";
+        note.textContent = "Added";
+        fixture.prepend(note);
+        const toggle = fixture.querySelector("[data-testid=fixture-toggle]");
+        toggle.setAttribute("aria-pressed", "false");
+        toggle.className = "off";
+        fixture.querySelector("h2").textContent = "Changed heading";
+        return true;
+      `,
+    });
+
+    try {
+      // The stored snapshot's options are reused
+      const byId = await sendCommand("dom_diff", { baselineId: snapshotId });
+      expect(byId.success).toBe(true);
+      const diff = byId.data as {
+        changed: boolean;
+        summary: { added: number; removed: number; modified: number };
+        added: { path: string }[];
+        modified: { path: string; changes: Record<string, { before: unknown; after: unknown }> }[];
+      };
+      expect(diff.changed).toBe(true);
+      expect(diff.summary).toEqual({ added: 1, removed: 0, modified: 1 });
+      expect(diff.added[0].path).toBe('section[data-testid="snapshot-fixture"] > p[data-testid="fixture-note"]');
+      expect(diff.modified[0].changes["attrs.class"]).toEqual({ before: null, after: "off" });
+
+      const byValue = await sendCommand("dom_diff", { baseline: baseline.data, maxChanges: 1 });
+      expect(byValue.success).toBe(true);
+      const limited = byValue.data as { summary: { modified: number }; truncated?: boolean };
+      // The heading's name and the toggle's pressed state changed
+      expect(limited.summary.modified).toBe(2);
+      expect(limited.truncated).toBe(true);
+
+      const unknown = await sendCommand("dom_diff", { baselineId: "snapshot-0" });
+      expect(unknown.success).toBe(false);
+      expect(unknown.error).toContain("Unknown baselineId");
+    } finally {
+      await sendCommand("execute_js", {
+        script: `
+          const fixture = document.querySelector("[data-testid=snapshot-fixture]");
+          fixture.querySelector("[data-testid=fixture-note]").remove();
+          const toggle = fixture.querySelector("[data-testid=fixture-toggle]");
+          toggle.setAttribute("aria-pressed", "true");
+          toggle.removeAttribute("class");
+          fixture.querySelector("h2").textContent = "Fixture heading";
+          return true;
+        `,
+      });
+    }
+  });
+
   it("should fail for non-existent window", async (ctx) => {
     if (await skipIfAppNotAvailable()) {
       ctx.skip();
@@ -0,0 +1,137 @@
+/**
+ * Integration tests for tauri_element_info tool.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+interface ElementInfo {
+  tagName: string;
+  selector: string;
+  boundingClientRect: { x: number; y: number; width: number; height: number };
+  inViewport: boolean;
+  partiallyInViewport: boolean;
+  visibility: { visible: boolean; display: string; visibility: string; opacity: number };
+  disabled: boolean;
+  topmostAtCenter: string | null;
+  occluded: boolean | null;
+  styles: Record<string, string>;
+  attributes: Record<string, string>;
+}
+
+describe("tauri_element_info", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("element-info-test")?.remove();
+        const container = document.createElement("div");
+        container.id = "element-info-test";
+        container.innerHTML = \`
+          <button id="ei-save" class="ei-button" data-testid="save"
+            style="position: fixed; left: 20px; top: 20px; width: 100px; height: 30px; z-index: 3">Save</button>
+          <button id="ei-covered" class="ei-button" disabled
+            style="position: fixed; left: 20px; top: 80px; width: 100px; height: 30px">Covered</button>
+          <div id="ei-overlay" style="position: fixed; left: 0; top: 70px; width: 200px; height: 60px; z-index: 10"></div>
+          <span id="ei-faded" style="opacity: 0">Faded</span>
+        \`;
+        document.body.appendChild(container);
+        return true;
+      `,
+    });
+  });
+
+  afterAll(async () => {
+    if (!(await skipIfAppNotAvailable())) {
+      await sendCommand("execute_js", {
+        script: `document.getElementById("element-info-test")?.remove(); return true;`,
+      });
+    }
+    disconnect();
+  });
+
+  it("should return geometry, state, styles, and attributes", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("element_info", {
+      selector: { role: "button", name: "Save" },
+      styles: ["z-index", "position"],
+    });
+    expect(response.success).toBe(true);
+    const info = response.data as ElementInfo;
+    expect(info).toMatchObject({
+      tagName: "button",
+      selector: "#ei-save",
+      boundingClientRect: { x: 20, y: 20, width: 100, height: 30 },
+      inViewport: true,
+      visibility: { visible: true, visibility: "visible", opacity: 1 },
+      disabled: false,
+      topmostAtCenter: "#ei-save",
+      occluded: false,
+      styles: { "z-index": "3", position: "fixed" },
+    });
+    expect(info.attributes).toMatchObject({ id: "ei-save", class: "ei-button", "data-testid": "save" });
+  });
+
+  it("should report elements covered by others", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("element_info", { selector: "#ei-covered" });
+    expect(response.success).toBe(true);
+    const info = response.data as ElementInfo;
+    expect(info.disabled).toBe(true);
+    expect(info.occluded).toBe(true);
+    expect(info.topmostAtCenter).toBe("#ei-overlay");
+    expect(info.styles).toEqual({});
+  });
+
+  it("should report hidden elements as not visible", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const info = (await sendCommand("element_info", { selector: "#ei-faded" })).data as ElementInfo;
+    expect(info.visibility.visible).toBe(false);
+    expect(info.visibility.opacity).toBe(0);
+  });
+
+  it("should describe every match with all", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("element_info", { selector: ".ei-button", all: true });
+    expect(response.success).toBe(true);
+    const infos = response.data as ElementInfo[];
+    expect(infos.map((info) => info.selector)).toEqual(["#ei-save", "#ei-covered"]);
+  });
+
+  it("should fail for missing elements", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("element_info", { selector: "#ei-missing" });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("Element not found: #ei-missing");
+
+    const none = await sendCommand("element_info", { selector: "#ei-missing", all: true });
+    expect(none.data).toEqual([]);
+  });
+});
@@ -0,0 +1,93 @@
+/**
+ * Integration tests for tauri_emit_event tool.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+describe("tauri_emit_event", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+  });
+
+  afterAll(() => {
+    disconnect();
+  });
+
+  it("should deliver the event and payload to frontend listeners", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const event = `test-app://emit-${Date.now()}`;
+    await sendCommand("execute_js", {
+      script: `
+        window.__emitted = [];
+        await window.__TAURI__.event.listen("${event}", (e) => window.__emitted.push(e.payload));
+        return true;
+      `,
+    });
+
+    const response = await sendCommand("emit_event", {
+      event,
+      payload: { file: "report.pdf", size: 42 },
+    });
+    expect(response.success).toBe(true);
+
+    // Events are delivered asynchronously
+    await new Promise((resolve) => setTimeout(resolve, 200));
+    const received = await sendCommand("execute_js", {
+      script: "return window.__emitted",
+    });
+    expect(received.data).toEqual([{ file: "report.pdf", size: 42 }]);
+  });
+
+  it("should emit to a specific window", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const list = await sendCommand("window_list", {});
+    const label = (list.data as { label: string }[])[0].label;
+
+    const response = await sendCommand("emit_event", {
+      event: "test-app://targeted",
+      window: label,
+    });
+    expect(response.success).toBe(true);
+    expect((response.data as { window: string }).window).toBe(label);
+  });
+
+  it("should reject an invalid event name", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("emit_event", { event: "not valid!" });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("Failed to emit");
+  });
+
+  it("should fail for a non-existent window", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("emit_event", {
+      event: "test-app://targeted",
+      window: "nonexistent-window-12345",
+    });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("not found");
+  });
+});
@@ -0,0 +1,97 @@
+/**
+ * Integration tests for event_subscribe and event_unsubscribe.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  onEvent,
+  sendCommand,
+  skipIfAppNotAvailable,
+  type PluginEvent,
+} from "./setup.js";
+
+interface TauriEventData {
+  event: string;
+  payload: unknown;
+}
+
+describe("event_subscribe and event_unsubscribe", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+  });
+
+  afterAll(() => {
+    disconnect();
+  });
+
+  it("should push Tauri events emitted by the frontend and backend", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const event = `test-app://progress-${Date.now()}`;
+    const received: TauriEventData[] = [];
+    const stop = onEvent((frame: PluginEvent) => {
+      if (frame.event === "tauri_event") received.push(frame.data as TauriEventData);
+    });
+
+    try {
+      const subscribed = await sendCommand("event_subscribe", { event });
+      expect(subscribed.success).toBe(true);
+
+      await sendCommand("execute_js", {
+        script: `await window.__TAURI__.event.emit("${event}", { percent: 40 }); return true;`,
+      });
+      await sendCommand("emit_event", { event, payload: { percent: 100 } });
+      await new Promise((resolve) => setTimeout(resolve, 300));
+
+      expect(received).toEqual([
+        { event, payload: { percent: 40 } },
+        { event, payload: { percent: 100 } },
+      ]);
+    } finally {
+      stop();
+    }
+  });
+
+  it("should stop pushing after event_unsubscribe", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const event = `test-app://stopped-${Date.now()}`;
+    const received: TauriEventData[] = [];
+    const stop = onEvent((frame: PluginEvent) => {
+      if (frame.event === "tauri_event") received.push(frame.data as TauriEventData);
+    });
+
+    try {
+      await sendCommand("event_subscribe", { event });
+      const unsubscribed = await sendCommand("event_unsubscribe", { event });
+      expect(unsubscribed.success).toBe(true);
+      expect((unsubscribed.data as { wasSubscribed: boolean }).wasSubscribed).toBe(true);
+
+      await sendCommand("emit_event", { event, payload: 1 });
+      await new Promise((resolve) => setTimeout(resolve, 300));
+      expect(received).toEqual([]);
+    } finally {
+      stop();
+    }
+  });
+
+  it("should reject an invalid event name", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("event_subscribe", { event: "not valid!" });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("Invalid event name");
+  });
+});
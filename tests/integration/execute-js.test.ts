@@ -88,4 +88,16 @@ describe("tauri_execute_js", () => {
     expect(windowResponse.success).toBe(false);
     expect(windowResponse.error?.toLowerCase()).toContain("not found");
   });
+
+  it("should run scripts from inject_js and inject_js_file before the page's own", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    // The test app injects an inline script, then one from a file
+    const response = await sendCommand("execute_js", { script: "window.__testAppInjected" });
+    expect(response.success).toBe(true);
+    expect(response.data).toEqual(["loading", "file"]);
+  });
 });
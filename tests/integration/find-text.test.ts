@@ -0,0 +1,107 @@
+/**
+ * Integration tests for tauri_find_text tool.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+interface FindTextResult {
+  matches: Array<{
+    text: string;
+    selector: string;
+    tagName: string;
+    excerpt: string;
+    rect: { x: number; y: number; width: number; height: number };
+    inViewport: boolean;
+  }>;
+  total: number;
+  truncated: boolean;
+}
+
+describe("tauri_find_text", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("find-text-test")?.remove();
+        const container = document.createElement("div");
+        container.id = "find-text-test";
+        container.innerHTML = \`
+          <p id="ft-alert" style="position: fixed; left: 10px; top: 10px; margin: 0">Zorblax
+            <b>payment</b> declined, try another card</p>
+          <p id="ft-hidden" style="display: none">Zorblax hidden</p>
+          <script>// Zorblax in a script</script>
+          <button>Zorblax one</button><button>Zorblax two</button>
+        \`;
+        document.body.appendChild(container);
+        return true;
+      `,
+    });
+  });
+
+  afterAll(async () => {
+    if (!(await skipIfAppNotAvailable())) {
+      await sendCommand("execute_js", {
+        script: `document.getElementById("find-text-test")?.remove(); return true;`,
+      });
+    }
+    disconnect();
+  });
+
+  it("should find visible text across inline elements", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("find_text", { query: "zorblax payment" });
+    expect(response.success).toBe(true);
+    const result = response.data as FindTextResult;
+    expect(result.total).toBe(1);
+    expect(result.matches[0]).toMatchObject({
+      text: "Zorblax payment",
+      selector: "#ft-alert",
+      tagName: "p",
+      excerpt: "**Zorblax payment** declined, try another card",
+      inViewport: true,
+    });
+    expect(result.matches[0].rect.width).toBeGreaterThan(0);
+  });
+
+  it("should skip hidden text and keep blocks apart", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const all = (await sendCommand("find_text", { query: "Zorblax", caseSensitive: true })).data as FindTextResult;
+    expect(all.total).toBe(3);
+    expect(all.matches.map((match) => match.tagName)).toEqual(["p", "button", "button"]);
+
+    const joined = (await sendCommand("find_text", { query: "oneZorblax" })).data as FindTextResult;
+    expect(joined.total).toBe(0);
+
+    const limited = (await sendCommand("find_text", { query: "zorblax (one|two)", regex: true, maxResults: 1 }))
+      .data as FindTextResult;
+    expect(limited).toMatchObject({ total: 2, truncated: true });
+    expect(limited.matches).toHaveLength(1);
+  });
+
+  it("should fail for invalid regexes", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("find_text", { query: "(", regex: true });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("Invalid regex");
+  });
+});
@@ -0,0 +1,115 @@
+/**
+ * Integration tests for tauri_focus_info tool.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+interface FocusEntry {
+  selector: string;
+  type: string;
+  role: string | null;
+  tabIndex: number;
+  value: string | null;
+}
+
+interface FocusInfo {
+  activeElement: (FocusEntry & { tagName: string; id: string | null; tabOrderIndex: number }) | null;
+  documentHasFocus: boolean;
+  tabOrder: (FocusEntry & { index: number })[];
+}
+
+describe("tauri_focus_info", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("focus-info-test")?.remove();
+        const container = document.createElement("div");
+        container.id = "focus-info-test";
+        container.innerHTML = \`
+          <button id="fi-third">Third</button>
+          <input id="fi-second" tabindex="2" value="two">
+          <a id="fi-first" href="#" tabindex="1">First</a>
+          <button id="fi-disabled" disabled>Disabled</button>
+          <button id="fi-skipped" tabindex="-1">Skipped</button>
+          <button id="fi-hidden" style="display: none">Hidden</button>
+        \`;
+        document.body.appendChild(container);
+        return true;
+      `,
+    });
+  });
+
+  afterAll(async () => {
+    if (!(await skipIfAppNotAvailable())) {
+      await sendCommand("execute_js", {
+        script: `document.getElementById("focus-info-test")?.remove(); return true;`,
+      });
+    }
+    disconnect();
+  });
+
+  it("should report the focused element", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `document.getElementById("fi-second").focus(); return true;`,
+    });
+
+    const response = await sendCommand("focus_info", {});
+    expect(response.success).toBe(true);
+
+    const info = response.data as FocusInfo;
+    expect(info.activeElement?.selector).toBe("#fi-second");
+    expect(info.activeElement?.tagName).toBe("input");
+    expect(info.activeElement?.type).toBe("text");
+    expect(info.activeElement?.role).toBe("textbox");
+    expect(info.activeElement?.tabIndex).toBe(2);
+    expect(info.activeElement?.value).toBe("two");
+  });
+
+  it("should order positive tabindex first and skip untabbable elements", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("focus_info", {});
+    expect(response.success).toBe(true);
+
+    const info = response.data as FocusInfo;
+    const selectors = info.tabOrder.map((entry) => entry.selector);
+
+    expect(selectors.slice(0, 2)).toEqual(["#fi-first", "#fi-second"]);
+    expect(selectors).toContain("#fi-third");
+    expect(selectors).not.toContain("#fi-disabled");
+    expect(selectors).not.toContain("#fi-skipped");
+    expect(selectors).not.toContain("#fi-hidden");
+  });
+
+  it("should report no active element when nothing is focused", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `document.activeElement?.blur(); return true;`,
+    });
+
+    const response = await sendCommand("focus_info", {});
+    expect(response.success).toBe(true);
+    expect((response.data as FocusInfo).activeElement).toBeNull();
+  });
+});
@@ -0,0 +1,125 @@
+/**
+ * Integration tests for tauri_form_fields tool.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+interface FormField {
+  selector: string;
+  type: string;
+  name: string | null;
+  id: string | null;
+  value: string | string[] | null;
+  checked: boolean | null;
+  options: { value: string; text: string; selected: boolean; disabled: boolean }[] | null;
+  disabled: boolean;
+  required: boolean;
+  validationMessage: string | null;
+}
+
+describe("tauri_form_fields", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+
+    // The test app has no <form>, so inject one for these tests
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("form-fields-test")?.remove();
+        const form = document.createElement("form");
+        form.id = "form-fields-test";
+        form.innerHTML = \`
+          <input id="ff-email" name="email" type="email" required>
+          <input id="ff-agree" name="agree" type="checkbox" checked>
+          <select id="ff-color" name="color">
+            <option value="red">Red</option>
+            <option value="blue" selected>Blue</option>
+          </select>
+          <textarea id="ff-notes" name="notes" disabled>Hello</textarea>
+        \`;
+        document.body.appendChild(form);
+        return true;
+      `,
+    });
+  });
+
+  afterAll(async () => {
+    if (!(await skipIfAppNotAvailable())) {
+      await sendCommand("execute_js", {
+        script: `document.getElementById("form-fields-test")?.remove(); return true;`,
+      });
+    }
+    disconnect();
+  });
+
+  it("should list fields of a specific form", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("form_fields", {
+      formSelector: "#form-fields-test",
+    });
+    expect(response.success).toBe(true);
+
+    const fields = response.data as FormField[];
+    expect(fields.map((field) => field.id)).toEqual([
+      "ff-email",
+      "ff-agree",
+      "ff-color",
+      "ff-notes",
+    ]);
+
+    const [email, agree, color, notes] = fields;
+    expect(email.type).toBe("email");
+    expect(email.required).toBe(true);
+    expect(email.value).toBe("");
+    expect(email.validationMessage).toBeTruthy();
+    expect(email.checked).toBeNull();
+
+    expect(agree.checked).toBe(true);
+
+    expect(color.type).toBe("select-one");
+    expect(color.value).toBe("blue");
+    expect(color.options?.map((option) => option.value)).toEqual(["red", "blue"]);
+
+    expect(notes.type).toBe("textarea");
+    expect(notes.disabled).toBe(true);
+    expect(notes.value).toBe("Hello");
+  });
+
+  it("should list fields of the whole document without a selector", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("form_fields", {});
+    expect(response.success).toBe(true);
+
+    const fields = response.data as FormField[];
+    const selectors = fields.map((field) => field.selector);
+    expect(selectors).toContain('[data-testid="todo-input"]');
+    expect(fields.some((field) => field.id === "ff-email")).toBe(true);
+  });
+
+  it("should fail for a missing form", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("form_fields", {
+      formSelector: "#does-not-exist",
+    });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("Form not found");
+  });
+});
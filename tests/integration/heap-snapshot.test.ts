@@ -0,0 +1,50 @@
+/**
+ * Integration tests for tauri_heap_snapshot tool.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+interface HeapSnapshot {
+  supported: boolean;
+  source: string | null;
+  usedJSHeapSize: number | null;
+  totalJSHeapSize: number | null;
+  jsHeapSizeLimit: number | null;
+  reason?: string;
+}
+
+describe("tauri_heap_snapshot", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+  });
+
+  afterAll(() => {
+    disconnect();
+  });
+
+  it("should report heap sizes or why they're unavailable", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("heap_snapshot", {});
+    expect(response.success).toBe(true);
+
+    const snapshot = response.data as HeapSnapshot;
+    if (snapshot.supported) {
+      expect(snapshot.usedJSHeapSize).toBeGreaterThan(0);
+      expect(snapshot.source).toBeTruthy();
+    } else {
+      expect(snapshot.usedJSHeapSize).toBeNull();
+      expect(snapshot.reason).toContain("heap");
+    }
+  });
+});
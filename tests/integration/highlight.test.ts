@@ -0,0 +1,152 @@
+/**
+ * Integration tests for tauri_highlight and tauri_highlight_clear tools.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+interface OverlayBox {
+  left: number;
+  top: number;
+  width: number;
+  height: number;
+  pointerEvents: string;
+  label: string | null;
+}
+
+// The overlays on the page, with their position and label
+const overlays = async (): Promise<OverlayBox[]> => {
+  const response = await sendCommand("execute_js", {
+    script: `
+      return Array.from(document.querySelectorAll("[data-tauri-mcp-highlight]"), (overlay) => {
+        const rect = overlay.getBoundingClientRect();
+        return {
+          left: rect.left,
+          top: rect.top,
+          width: rect.width,
+          height: rect.height,
+          pointerEvents: getComputedStyle(overlay).pointerEvents,
+          label: overlay.textContent || null,
+        };
+      });
+    `,
+  });
+  return response.data as OverlayBox[];
+};
+
+describe("tauri_highlight", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("highlight-test")?.remove();
+        const container = document.createElement("div");
+        container.id = "highlight-test";
+        container.style.cssText = "position: fixed; left: 0; top: 0; width: 300px; height: 200px; overflow: auto";
+        container.innerHTML = \`
+          <div style="height: 100px"></div>
+          <button id="hl-target" style="width: 120px; height: 40px">Target</button>
+          <div style="height: 1000px"></div>
+        \`;
+        document.body.appendChild(container);
+        window.__highlightClicks = 0;
+        document.getElementById("hl-target").addEventListener("click", () => window.__highlightClicks++);
+        return true;
+      `,
+    });
+  });
+
+  afterAll(async () => {
+    if (!(await skipIfAppNotAvailable())) {
+      await sendCommand("highlight_clear", {});
+      await sendCommand("execute_js", {
+        script: `document.getElementById("highlight-test")?.remove(); delete window.__highlightClicks; return true;`,
+      });
+    }
+    disconnect();
+  });
+
+  it("should outline the element without blocking clicks", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("highlight", { selector: "#hl-target", label: 1, durationMs: 10000 });
+    expect(response.success).toBe(true);
+    expect(response.data).toMatchObject({ highlighted: "#hl-target", durationMs: 10000, label: 1 });
+
+    const [overlay] = await overlays();
+    expect(overlay).toMatchObject({ width: 120, height: 40, pointerEvents: "none", label: "1" });
+
+    const click = await sendCommand("interact", { action: "click", selector: "#hl-target" });
+    expect(click.success).toBe(true);
+    const clicks = await sendCommand("execute_js", { script: "window.__highlightClicks" });
+    expect(clicks.data).toBe(1);
+
+    await sendCommand("highlight_clear", {});
+  });
+
+  it("should follow the element when its container scrolls", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("highlight", { selector: "#hl-target", durationMs: 10000 });
+    const [before] = await overlays();
+
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("highlight-test").scrollTop = 50;
+        await new Promise((resolve) => requestAnimationFrame(() => requestAnimationFrame(resolve)));
+        return true;
+      `,
+    });
+    const [after] = await overlays();
+    expect(after.top).toBe(before.top - 50);
+
+    await sendCommand("highlight_clear", {});
+  });
+
+  it("should remove overlays after the duration or when cleared", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("highlight", { selector: "#hl-target", durationMs: 200 });
+    await sendCommand("highlight", { selector: "#hl-target", durationMs: 10000, label: "kept" });
+    expect(await overlays()).toHaveLength(2);
+
+    await new Promise((resolve) => setTimeout(resolve, 400));
+    const remaining = await overlays();
+    expect(remaining.map((overlay) => overlay.label)).toEqual(["kept"]);
+
+    const cleared = await sendCommand("highlight_clear", {});
+    expect(cleared.data).toEqual({ cleared: 1 });
+    expect(await overlays()).toHaveLength(0);
+  });
+
+  it("should reject missing elements and invalid colors", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const missing = await sendCommand("highlight", { selector: "#hl-missing" });
+    expect(missing.success).toBe(false);
+    expect(missing.error).toContain("Element not found: #hl-missing");
+
+    const color = await sendCommand("highlight", { selector: "#hl-target", color: "not-a-color" });
+    expect(color.success).toBe(false);
+    expect(color.error).toContain("Invalid color");
+  });
+});
@@ -10,6 +10,42 @@ import {
   skipIfAppNotAvailable,
 } from "./setup.js";
 
+interface PointerResult {
+  target: { tag: string; id: string | null; classes: string[] } | null;
+  hitsTarget: boolean;
+  defaultPrevented?: boolean;
+}
+
+/** Add a todo through the UI and return its unique text */
+async function addTodo(label: string): Promise<string> {
+  const text = `${label} ${Date.now()}`;
+  await sendCommand("interact", {
+    action: "type",
+    selector: '[data-testid="todo-input"]',
+    text,
+  });
+  await sendCommand("interact", {
+    action: "click",
+    selector: '[data-testid="add-button"]',
+  });
+  await sendCommand("wait_for", {
+    type: "text",
+    value: text,
+    timeoutMs: 5000,
+  });
+  return text;
+}
+
+/** The todo added last is at the end of the list */
+const LAST_TODO_TEXT = '[data-testid="todo-item"]:last-child [data-testid="todo-text"]';
+
+async function countElements(selector: string): Promise<number> {
+  const response = await sendCommand("execute_js", {
+    script: `return document.querySelectorAll(${JSON.stringify(selector)}).length`,
+  });
+  return response.data as number;
+}
+
 describe("tauri_interact", () => {
   beforeAll(async () => {
     if (await skipIfAppNotAvailable()) return;
@@ -93,6 +129,1213 @@ describe("tauri_interact", () => {
     expect(response.success).toBe(true);
   });
 
+  it("should scroll nested containers and report the final position", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        const box = document.createElement("div");
+        box.id = "mcp-test-scroll";
+        box.innerHTML = \`
+          <div id="mcp-test-scroll-list" style="height: 100px; overflow: auto">
+            \${Array.from({ length: 50 }, (_, i) => \`<div id="mcp-test-row-\${i}" style="height: 20px">Row \${i}</div>\`).join("")}
+          </div>
+          <div id="mcp-test-scroll-hidden" style="height: 100px; overflow: hidden">
+            <div style="height: 1000px"></div>
+          </div>
+        \`;
+        document.body.appendChild(box);
+        return true;
+      `,
+    });
+
+    try {
+      const by = await sendCommand("interact", {
+        action: "scroll",
+        selector: "#mcp-test-scroll-list",
+        deltaY: 200,
+        behavior: "instant",
+      });
+      expect(by.success).toBe(true);
+      expect(by.data).toMatchObject({ scrollTop: 200, moved: true });
+
+      const to = await sendCommand("interact", {
+        action: "scroll",
+        selector: "#mcp-test-scroll-list",
+        to: { top: 40 },
+      });
+      expect(to.data).toMatchObject({ scrollTop: 40, moved: true });
+
+      const intoView = await sendCommand("interact", {
+        action: "scroll",
+        selector: "#mcp-test-scroll-list",
+        intoView: "#mcp-test-row-30",
+        block: "start",
+        behavior: "instant",
+      });
+      expect(intoView.success).toBe(true);
+      expect((intoView.data as { scrollTop: number }).scrollTop).toBe(600);
+
+      const hidden = await sendCommand("interact", {
+        action: "scroll",
+        selector: "#mcp-test-scroll-hidden",
+        deltaY: 200,
+      });
+      expect(hidden.success).toBe(true);
+      expect(hidden.data).toMatchObject({ scrollTop: 0, moved: false, overflowY: "hidden" });
+
+      const missing = await sendCommand("interact", {
+        action: "scroll",
+        selector: "#mcp-test-scroll-list",
+        intoView: "#mcp-test-row-999",
+      });
+      expect(missing.success).toBe(false);
+      expect(missing.error).toContain("Element not found in #mcp-test-scroll-list");
+    } finally {
+      await sendCommand("execute_js", {
+        script: 'document.getElementById("mcp-test-scroll")?.remove(); return true;',
+      });
+    }
+  });
+
+  it("should set values on range inputs", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        const slider = document.createElement("input");
+        slider.type = "range";
+        slider.id = "mcp-test-slider";
+        slider.min = "0";
+        slider.max = "10";
+        slider.step = "2";
+        document.body.appendChild(slider);
+        return true;
+      `,
+    });
+
+    try {
+      const response = await sendCommand("interact", {
+        action: "set_value",
+        selector: "#mcp-test-slider",
+        value: 4,
+      });
+      expect(response.success).toBe(true);
+      expect((response.data as { value: string }).value).toBe("4");
+
+      // Off-step and out-of-range values are rejected
+      const offStep = await sendCommand("interact", {
+        action: "set_value",
+        selector: "#mcp-test-slider",
+        value: 3,
+      });
+      expect(offStep.success).toBe(false);
+      expect(offStep.error).toContain("step");
+
+      const outOfRange = await sendCommand("interact", {
+        action: "set_value",
+        selector: "#mcp-test-slider",
+        value: 20,
+      });
+      expect(outOfRange.success).toBe(false);
+      expect(outOfRange.error).toContain("outside the allowed range");
+    } finally {
+      await sendCommand("execute_js", {
+        script: 'document.getElementById("mcp-test-slider")?.remove(); return true;',
+      });
+    }
+  });
+
+  it("should select options by value, label, or index", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        const box = document.createElement("div");
+        box.id = "mcp-test-select";
+        box.innerHTML = \`
+          <select id="mcp-test-size">
+            <option value="s">Small</option>
+            <option value="m">Medium</option>
+            <option value="l">Large</option>
+          </select>
+          <select id="mcp-test-tags" multiple>
+            <option value="a">Alpha</option>
+            <option value="b">Beta</option>
+            <option value="c">Gamma</option>
+          </select>
+        \`;
+        document.body.appendChild(box);
+        window.__mcpTestChanges = 0;
+        document.getElementById("mcp-test-size").addEventListener("change", () => window.__mcpTestChanges++);
+        return true;
+      `,
+    });
+
+    try {
+      const byValue = await sendCommand("interact", { action: "select", selector: "#mcp-test-size", value: "m" });
+      expect(byValue.success).toBe(true);
+      expect((byValue.data as { value: string }).value).toBe("m");
+
+      const byLabel = await sendCommand("interact", { action: "select", selector: "#mcp-test-size", label: "Large" });
+      expect((byLabel.data as { value: string }).value).toBe("l");
+
+      const byIndex = await sendCommand("interact", { action: "select", selector: "#mcp-test-size", index: 0 });
+      expect((byIndex.data as { value: string }).value).toBe("s");
+
+      const changes = await sendCommand("execute_js", { script: "return window.__mcpTestChanges" });
+      expect(changes.data).toBe(3);
+
+      const multi = await sendCommand("interact", {
+        action: "select",
+        selector: "#mcp-test-tags",
+        value: ["a", "c"],
+      });
+      expect(multi.success).toBe(true);
+      expect((multi.data as { value: string[] }).value).toEqual(["a", "c"]);
+
+      const missing = await sendCommand("interact", { action: "select", selector: "#mcp-test-size", value: "xl" });
+      expect(missing.success).toBe(false);
+      expect(missing.error).toContain('Available values: "s", "m", "l"');
+    } finally {
+      await sendCommand("execute_js", {
+        script: 'document.getElementById("mcp-test-select")?.remove(); delete window.__mcpTestChanges; return true;',
+      });
+    }
+  });
+
+  it("should check and uncheck checkboxes and switches", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        const box = document.createElement("div");
+        box.id = "mcp-test-toggles";
+        box.innerHTML = \`
+          <input type="checkbox" id="mcp-test-agree">
+          <div id="mcp-test-switch" role="switch" aria-checked="false" tabindex="0">Dark mode</div>
+          <div id="mcp-test-dead-switch" role="switch" aria-checked="false">Broken</div>
+          <div id="mcp-test-plain">Not a toggle</div>
+        \`;
+        document.body.appendChild(box);
+        const toggle = document.getElementById("mcp-test-switch");
+        toggle.addEventListener("click", () => {
+          toggle.setAttribute("aria-checked", String(toggle.getAttribute("aria-checked") !== "true"));
+        });
+        return true;
+      `,
+    });
+
+    try {
+      const checked = await sendCommand("interact", {
+        action: "setChecked",
+        selector: "#mcp-test-agree",
+        checked: true,
+      });
+      expect(checked.success).toBe(true);
+      expect((checked.data as { changed: boolean }).changed).toBe(true);
+
+      const again = await sendCommand("interact", { action: "setChecked", selector: "#mcp-test-agree", checked: true });
+      expect((again.data as { changed: boolean }).changed).toBe(false);
+
+      const toggled = await sendCommand("interact", {
+        action: "set_checked",
+        selector: "#mcp-test-switch",
+        checked: true,
+      });
+      expect(toggled.success).toBe(true);
+      const state = await sendCommand("execute_js", {
+        script: 'return document.getElementById("mcp-test-switch").getAttribute("aria-checked")',
+      });
+      expect(state.data).toBe("true");
+
+      const dead = await sendCommand("interact", {
+        action: "set_checked",
+        selector: "#mcp-test-dead-switch",
+        checked: true,
+      });
+      expect(dead.success).toBe(false);
+      expect(dead.error).toContain("State did not change after click");
+
+      const plain = await sendCommand("interact", {
+        action: "set_checked",
+        selector: "#mcp-test-plain",
+        checked: true,
+      });
+      expect(plain.success).toBe(false);
+      expect(plain.error).toContain("Not a toggleable element");
+
+      const missing = await sendCommand("interact", {
+        action: "set_checked",
+        selector: "#mcp-test-nope",
+        checked: true,
+      });
+      expect(missing.success).toBe(false);
+      expect(missing.error).toContain("Element not found");
+    } finally {
+      await sendCommand("execute_js", {
+        script: 'document.getElementById("mcp-test-toggles")?.remove(); return true;',
+      });
+    }
+  });
+
+  it("should set input values through the native setter", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    // Mimic React's value tracking, which shadows the value property on the element
+    await sendCommand("execute_js", {
+      script: `
+        const input = document.createElement("input");
+        input.id = "mcp-test-controlled";
+        document.body.appendChild(input);
+        window.__mcpTestTracked = [];
+        const native = Object.getOwnPropertyDescriptor(HTMLInputElement.prototype, "value");
+        Object.defineProperty(input, "value", {
+          configurable: true,
+          get() { return native.get.call(this); },
+          set(v) { window.__mcpTestTracked.push(v); native.set.call(this, v); },
+        });
+        return true;
+      `,
+    });
+
+    try {
+      const response = await sendCommand("interact", {
+        action: "setValue",
+        selector: "#mcp-test-controlled",
+        value: "hello@example.com",
+      });
+      expect(response.success).toBe(true);
+      expect((response.data as { value: string }).value).toBe("hello@example.com");
+
+      // The tracker didn't see the write, so the framework sees the input event as a change
+      const tracked = await sendCommand("execute_js", { script: "return window.__mcpTestTracked" });
+      expect(tracked.data).toEqual([]);
+    } finally {
+      await sendCommand("execute_js", {
+        script:
+          'document.getElementById("mcp-test-controlled")?.remove(); delete window.__mcpTestTracked; return true;',
+      });
+    }
+  });
+
+  it("should upload files to file inputs", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        const box = document.createElement("div");
+        box.id = "mcp-test-upload";
+        box.innerHTML = \`
+          <input id="mcp-test-file" type="file">
+          <input id="mcp-test-files" type="file" multiple>
+        \`;
+        document.body.appendChild(box);
+        window.__mcpTestUploadEvents = [];
+        const input = document.getElementById("mcp-test-file");
+        input.addEventListener("input", () => window.__mcpTestUploadEvents.push("input"));
+        input.addEventListener("change", () => window.__mcpTestUploadEvents.push("change"));
+        return true;
+      `,
+    });
+
+    try {
+      const single = await sendCommand("interact", {
+        action: "upload",
+        selector: "#mcp-test-file",
+        files: [{ name: "notes.txt", mimeType: "text/plain", contentsBase64: "aGVsbG8=" }],
+      });
+      expect(single.success).toBe(true);
+      const files = (single.data as { files: Array<{ name: string; size: number; type: string }> }).files;
+      expect(files).toHaveLength(1);
+      expect(files[0]).toMatchObject({ name: "notes.txt", size: 5, type: "text/plain" });
+
+      const contents = await sendCommand("execute_js", {
+        script: 'return await document.getElementById("mcp-test-file").files[0].text()',
+      });
+      expect(contents.data).toBe("hello");
+
+      const events = await sendCommand("execute_js", { script: "return window.__mcpTestUploadEvents" });
+      expect(events.data).toEqual(["input", "change"]);
+
+      const multi = await sendCommand("interact", {
+        action: "upload",
+        selector: "#mcp-test-files",
+        files: [
+          { name: "a.txt", contentsBase64: "YQ==" },
+          { name: "b.bin", mimeType: "application/octet-stream", contentsBase64: "AAEC" },
+        ],
+      });
+      expect(multi.success).toBe(true);
+      expect((multi.data as { files: unknown[] }).files).toHaveLength(2);
+
+      const tooMany = await sendCommand("interact", {
+        action: "upload",
+        selector: "#mcp-test-file",
+        files: [
+          { name: "a.txt", contentsBase64: "YQ==" },
+          { name: "b.txt", contentsBase64: "Yg==" },
+        ],
+      });
+      expect(tooMany.success).toBe(false);
+      expect(tooMany.error).toContain("multiple");
+
+      const notFileInput = await sendCommand("interact", {
+        action: "upload",
+        selector: "body",
+        files: [{ name: "a.txt", contentsBase64: "YQ==" }],
+      });
+      expect(notFileInput.success).toBe(false);
+      expect(notFileInput.error).toContain('upload only supports <input type="file">');
+
+      const invalid = await sendCommand("interact", {
+        action: "upload",
+        selector: "#mcp-test-file",
+        files: [{ name: "a.txt", contentsBase64: "not base64!" }],
+      });
+      expect(invalid.success).toBe(false);
+      expect(invalid.error).toContain("not valid base64");
+    } finally {
+      await sendCommand("execute_js", {
+        script:
+          'document.getElementById("mcp-test-upload")?.remove(); delete window.__mcpTestUploadEvents; return true;',
+      });
+    }
+  });
+
+  it("should press keys with modifiers and report prevented defaults", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        const box = document.createElement("div");
+        box.id = "mcp-test-press";
+        box.innerHTML = \`
+          <input id="mcp-test-press-first">
+          <input id="mcp-test-press-second">
+          <button id="mcp-test-press-third">Third</button>
+        \`;
+        document.body.appendChild(box);
+        window.__mcpTestKeys = [];
+        box.addEventListener("keydown", (e) => {
+          const { key, code, keyCode, location, metaKey: meta, shiftKey: shift } = e;
+          window.__mcpTestKeys.push({ key, code, keyCode, location, meta, shift });
+          if (e.key === "s" && e.metaKey) e.preventDefault();
+        });
+        return true;
+      `,
+    });
+
+    try {
+      const save = await sendCommand("interact", {
+        action: "press",
+        selector: "#mcp-test-press-first",
+        key: "s",
+        modifiers: ["Meta"],
+      });
+      expect(save.success).toBe(true);
+      expect(save.data).toMatchObject({ key: "s", code: "KeyS", keyCode: 83, defaultPrevented: true });
+
+      // The prevented shortcut didn't type into the input
+      const value = await sendCommand("execute_js", {
+        script: 'return document.getElementById("mcp-test-press-first").value',
+      });
+      expect(value.data).toBe("");
+
+      const tab = await sendCommand("interact", {
+        action: "press",
+        selector: "#mcp-test-press-first",
+        key: "Tab",
+        repeat: 2,
+      });
+      expect(tab.success).toBe(true);
+      expect(tab.data).toMatchObject({ defaultPrevented: false, focused: '#mcp-test-press-third "Third"' });
+
+      const back = await sendCommand("interact", { action: "press", key: "Tab", modifiers: ["Shift"] });
+      expect((back.data as { focused: string }).focused).toBe("#mcp-test-press-second");
+
+      await sendCommand("execute_js", { script: "window.__mcpTestKeys = []; return true;" });
+      for (const key of ["Space", "?", ";", "Numpad5", "NumpadAdd", "F5"]) {
+        await sendCommand("interact", { action: "press", selector: "#mcp-test-press-second", key });
+      }
+      const keys = await sendCommand("execute_js", {
+        script: "return window.__mcpTestKeys.filter((k) => k.key !== 'Meta' && k.key !== 'Shift')",
+      });
+      expect(keys.data).toEqual([
+        { key: " ", code: "Space", keyCode: 32, location: 0, meta: false, shift: false },
+        { key: "?", code: "Slash", keyCode: 191, location: 0, meta: false, shift: true },
+        { key: ";", code: "Semicolon", keyCode: 186, location: 0, meta: false, shift: false },
+        { key: "5", code: "Numpad5", keyCode: 101, location: 3, meta: false, shift: false },
+        { key: "+", code: "NumpadAdd", keyCode: 107, location: 3, meta: false, shift: false },
+        { key: "F5", code: "F5", keyCode: 116, location: 0, meta: false, shift: false },
+      ]);
+
+      const unknown = await sendCommand("interact", { action: "press", key: "s", modifiers: ["Hyper"] });
+      expect(unknown.success).toBe(false);
+      expect(unknown.error).toContain("Unknown modifier");
+    } finally {
+      await sendCommand("execute_js", {
+        script: 'document.getElementById("mcp-test-press")?.remove(); delete window.__mcpTestKeys; return true;',
+      });
+    }
+  });
+
+  it("should send keyboard sequences with modifiers", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        const input = document.createElement("input");
+        input.id = "mcp-test-keys";
+        input.value = "old text";
+        window.__mcpTestKeys = [];
+        input.addEventListener("keydown", (e) => {
+          window.__mcpTestKeys.push(\`\${e.ctrlKey ? "ctrl+" : ""}\${e.key}:\${e.code}\`);
+        });
+        document.body.appendChild(input);
+        return true;
+      `,
+    });
+
+    try {
+      const response = await sendCommand("interact", {
+        action: "keyboard_sequence",
+        selector: "#mcp-test-keys",
+        keys: ["ctrl", "a", "delete", "H", "i"],
+      });
+      expect(response.success).toBe(true);
+
+      const result = await sendCommand("execute_js", {
+        script: `({
+          value: document.getElementById("mcp-test-keys").value,
+          keys: window.__mcpTestKeys,
+        })`,
+      });
+      const data = result.data as { value: string; keys: string[] };
+      expect(data.value).toBe("Hi");
+      expect(data.keys).toContain("ctrl+a:KeyA");
+      expect(data.keys).toContain("H:KeyH");
+    } finally {
+      await sendCommand("execute_js", {
+        script:
+          'document.getElementById("mcp-test-keys")?.remove(); delete window.__mcpTestKeys; return true;',
+      });
+    }
+  });
+
+  it("should run action sequences in one request and stop at the first failure", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    // Clicking the field re-renders it, like a framework swapping the node between steps
+    await sendCommand("execute_js", {
+      script: `
+        const box = document.createElement("div");
+        box.id = "mcp-test-sequence";
+        box.innerHTML = '<input id="mcp-test-sequence-input" value="old">';
+        document.body.appendChild(box);
+        window.__mcpTestSubmitted = null;
+        box.addEventListener("click", () => {
+          const fresh = document.createElement("input");
+          fresh.id = "mcp-test-sequence-input";
+          fresh.value = document.getElementById("mcp-test-sequence-input").value;
+          box.replaceChildren(fresh);
+        }, { once: true });
+        box.addEventListener("keydown", (event) => {
+          if (event.key === "Enter") window.__mcpTestSubmitted = event.target.value;
+        });
+        return true;
+      `,
+    });
+
+    try {
+      const response = await sendCommand("interact", {
+        action: "sequence",
+        actions: [
+          { action: "click", selector: "#mcp-test-sequence-input" },
+          { action: "set_value", selector: "#mcp-test-sequence-input", value: "" },
+          { action: "type", selector: "#mcp-test-sequence-input", text: "Ada" },
+          { action: "press", selector: "#mcp-test-sequence-input", key: "Enter", delayMs: 20 },
+        ],
+      });
+      expect(response.success).toBe(true);
+      const data = response.data as { success: boolean; steps: { step: number; success: boolean }[] };
+      expect(data.success).toBe(true);
+      expect(data.steps.map((step) => step.success)).toEqual([true, true, true, true]);
+
+      const submitted = await sendCommand("execute_js", { script: "return window.__mcpTestSubmitted" });
+      expect(submitted.data).toBe("Ada");
+
+      const failed = await sendCommand("interact", {
+        action: "sequence",
+        actions: [
+          { action: "click", selector: "#mcp-test-sequence-input" },
+          { action: "click", selector: "#mcp-test-nope" },
+          { action: "press", key: "Enter" },
+        ],
+      });
+      const failedData = failed.data as { success: boolean; steps: { step: number; error?: string }[] };
+      expect(failedData.success).toBe(false);
+      expect(failedData.steps).toHaveLength(2);
+      expect(failedData.steps[1].error).toContain("Element not found: #mcp-test-nope");
+
+      const nested = await sendCommand("interact", {
+        action: "sequence",
+        actions: [{ action: "sequence", actions: [{ action: "click", selector: "#mcp-test-sequence-input" }] }],
+      });
+      expect(nested.success).toBe(false);
+      expect(nested.error).toContain("can't be nested");
+    } finally {
+      await sendCommand("execute_js", {
+        script:
+          'document.getElementById("mcp-test-sequence")?.remove(); delete window.__mcpTestSubmitted; return true;',
+      });
+    }
+  });
+
+  it("should reveal hover-only UI and report the element under the pointer", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await addTodo("Hover me");
+
+    const hover = await sendCommand("interact", {
+      action: "hover",
+      selector: LAST_TODO_TEXT,
+    });
+    expect(hover.success).toBe(true);
+    const hit = hover.data as PointerResult;
+    expect(hit.hitsTarget).toBe(true);
+    expect(hit.target?.tag).toBe("span");
+    expect(hit.target?.classes).toContain("todo-text");
+    expect(await countElements('[data-testid="todo-hover-hint"]')).toBe(1);
+
+    // Moving the pointer away sends the leave events
+    await sendCommand("interact", { action: "hover", selector: "h1" });
+    expect(await countElements('[data-testid="todo-hover-hint"]')).toBe(0);
+  });
+
+  it("should open the context menu on right-click", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const text = await addTodo("Right-click me");
+
+    const rightClick = await sendCommand("interact", {
+      action: "rightclick",
+      selector: LAST_TODO_TEXT,
+    });
+    expect(rightClick.success).toBe(true);
+    expect((rightClick.data as PointerResult).defaultPrevented).toBe(true);
+    expect(await countElements('[data-testid="context-menu"]')).toBe(1);
+
+    const del = await sendCommand("interact", {
+      action: "click",
+      selector: '[data-testid="context-delete"]',
+    });
+    expect(del.success).toBe(true);
+    const gone = await sendCommand("execute_js", {
+      script: `
+        for (let i = 0; i < 50; i++) {
+          const texts = [...document.querySelectorAll('[data-testid="todo-text"]')].map((el) => el.textContent);
+          if (!texts.includes(${JSON.stringify(text)})) return true;
+          await new Promise((resolve) => setTimeout(resolve, 100));
+        }
+        return false;
+      `,
+    });
+    expect(gone.data).toBe(true);
+  });
+
+  it("should double-click and right-click at coordinates", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        const box = document.createElement("div");
+        box.id = "mcp-test-pointer";
+        box.className = "pointer-box";
+        box.style.cssText = "position: fixed; left: 0; top: 0; width: 80px; height: 80px; z-index: 1000";
+        window.__mcpTestPointer = [];
+        for (const type of ["click", "dblclick", "contextmenu"]) {
+          box.addEventListener(type, (e) => window.__mcpTestPointer.push(\`\${type}:\${e.button}:\${e.detail}\`));
+        }
+        document.body.appendChild(box);
+        return true;
+      `,
+    });
+
+    try {
+      const dblclick = await sendCommand("interact", { action: "dblclick", x: 40, y: 40 });
+      expect(dblclick.success).toBe(true);
+      expect((dblclick.data as PointerResult).target).toEqual({
+        tag: "div",
+        id: "mcp-test-pointer",
+        classes: ["pointer-box"],
+      });
+
+      const rightClick = await sendCommand("interact", { action: "rightclick", x: 40, y: 40 });
+      expect(rightClick.success).toBe(true);
+
+      const events = await sendCommand("execute_js", { script: "return window.__mcpTestPointer" });
+      expect(events.data).toEqual(["click:0:1", "click:0:2", "dblclick:0:2", "contextmenu:2:1"]);
+    } finally {
+      await sendCommand("execute_js", {
+        script:
+          'document.getElementById("mcp-test-pointer")?.remove(); delete window.__mcpTestPointer; return true;',
+      });
+    }
+  });
+
+  it("should drag with interpolated pointer moves", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        const track = document.createElement("div");
+        track.id = "mcp-test-track";
+        track.style.cssText = "position: fixed; left: 0; top: 0; width: 300px; height: 40px; z-index: 1000";
+        track.innerHTML = '<div id="mcp-test-thumb" style="width: 20px; height: 40px"></div>';
+        window.__mcpTestDrag = { moves: [], up: null };
+        track.addEventListener("pointerdown", () => {
+          const onMove = (e) => window.__mcpTestDrag.moves.push(Math.round(e.clientX));
+          document.addEventListener("pointermove", onMove);
+          document.addEventListener("pointerup", (e) => {
+            document.removeEventListener("pointermove", onMove);
+            window.__mcpTestDrag.up = Math.round(e.clientX);
+          }, { once: true });
+        });
+        document.body.appendChild(track);
+        return true;
+      `,
+    });
+
+    try {
+      const response = await sendCommand("interact", {
+        action: "drag",
+        from: "#mcp-test-thumb",
+        to: { x: 250, y: 20 },
+        steps: 5,
+        durationMs: 100,
+      });
+      expect(response.success).toBe(true);
+      const data = response.data as { from: { x: number }; to: { x: number }; html5Drag: boolean };
+      expect(data.from.x).toBe(10);
+      expect(data.to.x).toBe(250);
+      expect(data.html5Drag).toBe(false);
+
+      const result = await sendCommand("execute_js", { script: "return window.__mcpTestDrag" });
+      expect(result.data).toEqual({ moves: [58, 106, 154, 202, 250], up: 250 });
+    } finally {
+      await sendCommand("execute_js", {
+        script: 'document.getElementById("mcp-test-track")?.remove(); delete window.__mcpTestDrag; return true;',
+      });
+    }
+  });
+
+  it("should send HTML5 drag events for draggable sources", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        const box = document.createElement("div");
+        box.id = "mcp-test-dnd";
+        box.style.cssText = "position: fixed; left: 0; top: 0; z-index: 1000";
+        box.innerHTML = \`
+          <div id="mcp-test-card" draggable="true" style="width: 60px; height: 30px">Card</div>
+          <div id="mcp-test-zone" style="width: 120px; height: 60px">Zone</div>
+        \`;
+        document.body.appendChild(box);
+        window.__mcpTestDropped = null;
+        const card = document.getElementById("mcp-test-card");
+        const zone = document.getElementById("mcp-test-zone");
+        card.addEventListener("dragstart", (e) => e.dataTransfer.setData("text/plain", "card-1"));
+        zone.addEventListener("dragover", (e) => e.preventDefault());
+        zone.addEventListener("drop", (e) => { window.__mcpTestDropped = e.dataTransfer.getData("text/plain"); });
+        return true;
+      `,
+    });
+
+    try {
+      const response = await sendCommand("interact", {
+        action: "drag",
+        from: "#mcp-test-card",
+        to: "#mcp-test-zone",
+      });
+      expect(response.success).toBe(true);
+      expect(response.data).toMatchObject({
+        html5Drag: true,
+        dataTransferSet: true,
+        dataTypes: ["text/plain"],
+        dropped: true,
+      });
+
+      const dropped = await sendCommand("execute_js", { script: "return window.__mcpTestDropped" });
+      expect(dropped.data).toBe("card-1");
+    } finally {
+      await sendCommand("execute_js", {
+        script: 'document.getElementById("mcp-test-dnd")?.remove(); delete window.__mcpTestDropped; return true;',
+      });
+    }
+  });
+
+  it("should wait for late and covered elements before acting", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    // The button shows up late, and an overlay covers it for a while after that
+    await sendCommand("execute_js", {
+      script: `
+        const box = document.createElement("div");
+        box.id = "mcp-test-wait";
+        box.style.cssText = "position: fixed; top: 10px; left: 10px; width: 120px; height: 40px;";
+        document.body.appendChild(box);
+        window.__mcpTestClicks = 0;
+        setTimeout(() => {
+          box.innerHTML = '<button id="mcp-test-wait-btn" style="width: 100%; height: 100%">Save</button>' +
+            '<div id="mcp-test-wait-overlay" style="position: absolute; inset: 0; background: rgba(0,0,0,0.3)"></div>';
+          document.getElementById("mcp-test-wait-btn").addEventListener("click", () => window.__mcpTestClicks++);
+          setTimeout(() => document.getElementById("mcp-test-wait-overlay")?.remove(), 300);
+        }, 200);
+        return true;
+      `,
+    });
+
+    try {
+      const response = await sendCommand("interact", { action: "click", selector: "#mcp-test-wait-btn" });
+      expect(response.success).toBe(true);
+      const wait = (response.data as { wait: { waitedMs: number; checks: Record<string, boolean>; retried: boolean } })
+        .wait;
+      expect(wait.waitedMs).toBeGreaterThanOrEqual(400);
+      expect(wait.checks).toEqual({ exists: true, visible: true, notCovered: true });
+      expect(wait.retried).toBe(false);
+
+      const clicks = await sendCommand("execute_js", { script: "return window.__mcpTestClicks" });
+      expect(clicks.data).toBe(1);
+
+      // Cover it again for good
+      await sendCommand("execute_js", {
+        script: `
+          const overlay = document.createElement("div");
+          overlay.id = "mcp-test-wait-overlay";
+          overlay.style.cssText = "position: absolute; inset: 0;";
+          document.getElementById("mcp-test-wait").appendChild(overlay);
+          return true;
+        `,
+      });
+      const covered = await sendCommand("interact", {
+        action: "click",
+        selector: "#mcp-test-wait-btn",
+        waitTimeoutMs: 200,
+      });
+      expect(covered.success).toBe(false);
+      expect(covered.error).toContain("failed notCovered (covered by #mcp-test-wait-overlay)");
+
+      const forced = await sendCommand("interact", {
+        action: "click",
+        selector: "#mcp-test-wait-btn",
+        waitForSelector: false,
+      });
+      expect(forced.success).toBe(true);
+      expect((forced.data as { hitsTarget: boolean }).hitsTarget).toBe(false);
+    } finally {
+      await sendCommand("execute_js", {
+        script: 'document.getElementById("mcp-test-wait")?.remove(); delete window.__mcpTestClicks; return true;',
+      });
+    }
+  });
+
+  it("should target elements by XPath, text, and role selectors", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        const box = document.createElement("div");
+        box.id = "mcp-test-selectors";
+        box.innerHTML = \`
+          <button id="mcp-test-sel-save">Save   changes</button>
+          <button id="mcp-test-sel-draft">Save draft</button>
+          <span role="button" aria-label="Close panel" id="mcp-test-sel-close">x</span>
+        \`;
+        document.body.appendChild(box);
+        window.__mcpTestClicked = [];
+        box.addEventListener("click", (e) => window.__mcpTestClicked.push(e.target.id));
+        return true;
+      `,
+    });
+
+    try {
+      const byText = await sendCommand("interact", { action: "click", selector: { text: "save changes" } });
+      expect(byText.success).toBe(true);
+
+      const byRole = await sendCommand("interact", {
+        action: "click",
+        selector: { role: "button", name: "Close panel", exact: true },
+      });
+      expect(byRole.success).toBe(true);
+
+      const byXPath = await sendCommand("interact", {
+        action: "click",
+        selector: { xpath: "//div[@id='mcp-test-selectors']/button[2]" },
+      });
+      expect(byXPath.success).toBe(true);
+
+      const clicked = await sendCommand("execute_js", { script: "return window.__mcpTestClicked" });
+      expect(clicked.data).toEqual(["mcp-test-sel-save", "mcp-test-sel-close", "mcp-test-sel-draft"]);
+
+      const ambiguous = await sendCommand("interact", {
+        action: "click",
+        selector: { text: "Save" },
+        waitTimeoutMs: 0,
+      });
+      expect(ambiguous.success).toBe(false);
+      expect(ambiguous.error).toContain("matches 2 elements");
+      expect(ambiguous.error).toContain('<button id="mcp-test-sel-draft">Save draft</button>');
+
+      const snapshot = await sendCommand("dom_snapshot", {
+        type: "structure",
+        selector: { role: "button", name: "Save draft" },
+      });
+      expect(snapshot.success).toBe(true);
+      expect(snapshot.data).toContain("mcp-test-sel-draft");
+
+      const waited = await sendCommand("wait_for", { type: "visible", value: { text: "Save draft", exact: true } });
+      expect(waited.success).toBe(true);
+
+      const invalid = await sendCommand("interact", { action: "click", selector: { css: "a", text: "b" } });
+      expect(invalid.success).toBe(false);
+      expect(invalid.error).toContain("exactly one of css, xpath, text, role");
+    } finally {
+      await sendCommand("execute_js", {
+        script: 'document.getElementById("mcp-test-selectors")?.remove(); delete window.__mcpTestClicked; return true;',
+      });
+    }
+  });
+
+  it("should reach into nested shadow roots", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const status = async () => {
+      const response = await sendCommand("execute_js", {
+        script: `return document.querySelector("mcp-settings-panel").shadowRoot
+          .querySelector("[data-testid=settings-status]").textContent`,
+      });
+      return response.data;
+    };
+    const before = await status();
+
+    // Two shadow trees down, with the >>> combinator
+    const chained = await sendCommand("interact", {
+      action: "click",
+      selector: "mcp-settings-panel >>> mcp-toggle >>> button",
+    });
+    expect(chained.success).toBe(true);
+    expect((chained.data as { hitsTarget: boolean }).hitsTarget).toBe(true);
+    expect(await status()).not.toBe(before);
+
+    // Role selectors only look into shadow roots with pierceShadow
+    const shallow = await sendCommand("interact", {
+      action: "click",
+      selector: { role: "button", name: "Notifications" },
+      waitTimeoutMs: 0,
+    });
+    expect(shallow.success).toBe(false);
+    expect(shallow.error).toContain("Element not found");
+
+    const pierced = await sendCommand("interact", {
+      action: "click",
+      selector: { role: "button", name: "Notifications" },
+      pierceShadow: true,
+    });
+    expect(pierced.success).toBe(true);
+    expect(await status()).toBe(before);
+
+    const waited = await sendCommand("wait_for", {
+      type: "visible",
+      value: { text: before as string, exact: true },
+      pierceShadow: true,
+    });
+    expect(waited.success).toBe(true);
+
+    // Closed shadow roots stay out of reach
+    const closed = await sendCommand("interact", {
+      action: "click",
+      selector: { text: "Sealed action" },
+      pierceShadow: true,
+      waitTimeoutMs: 0,
+    });
+    expect(closed.success).toBe(false);
+  });
+
+  it("should send touch gestures as pointer and touch events", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("touch-test")?.remove();
+        const target = document.createElement("div");
+        target.id = "touch-test";
+        target.style.cssText = "position: fixed; left: 50px; top: 50px; width: 300px; height: 200px; background: #eee; z-index: 9999";
+        window.__touchEvents = [];
+        for (const type of ["pointerdown", "pointermove", "pointerup", "touchstart", "touchmove", "touchend", "click"]) {
+          target.addEventListener(type, (e) => window.__touchEvents.push({
+            type,
+            pointerType: e.pointerType ?? null,
+            touches: e.touches ? e.touches.length : null,
+          }));
+        }
+        document.body.appendChild(target);
+        return true;
+      `,
+    });
+    const events = async () => {
+      const response = await sendCommand("execute_js", {
+        script: "const events = window.__touchEvents; window.__touchEvents = []; return events;",
+      });
+      return response.data as { type: string; pointerType: string | null; touches: number | null }[];
+    };
+
+    const tap = await sendCommand("interact", { action: "tap", selector: "#touch-test" });
+    expect(tap.success).toBe(true);
+    const tapData = tap.data as { clicked: boolean; touchEvents: boolean; note?: string };
+    expect(tapData.clicked).toBe(true);
+    const tapEvents = await events();
+    expect(tapEvents.filter((e) => e.type.startsWith("pointer")).every((e) => e.pointerType === "touch")).toBe(true);
+    expect(tapEvents.map((e) => e.type)).toContain("click");
+    if (tapData.touchEvents) {
+      expect(tapEvents.map((e) => e.type)).toContain("touchstart");
+    } else {
+      expect(tapData.note).toContain("only pointer events");
+    }
+
+    const longpress = await sendCommand("interact", { action: "longpress", selector: "#touch-test", holdMs: 300 });
+    expect(longpress.success).toBe(true);
+    expect((longpress.data as { holdMs: number }).holdMs).toBe(300);
+    expect((await events()).map((e) => e.type)).not.toContain("click");
+
+    const swipe = await sendCommand("interact", {
+      action: "swipe",
+      from: { x: 300, y: 150 },
+      to: { x: 100, y: 150 },
+      steps: 5,
+      durationMs: 100,
+    });
+    expect(swipe.success).toBe(true);
+    expect((await events()).filter((e) => e.type === "pointermove")).toHaveLength(5);
+
+    const pinch = await sendCommand("interact", {
+      action: "pinch",
+      center: "#touch-test",
+      startDistance: 100,
+      endDistance: 200,
+      steps: 4,
+    });
+    expect(pinch.success).toBe(true);
+    const pinchData = pinch.data as { scale: number; touchEvents: boolean };
+    expect(pinchData.scale).toBe(2);
+    const pinchEvents = await events();
+    // Two fingers, each moving at every step
+    expect(pinchEvents.filter((e) => e.type === "pointermove")).toHaveLength(8);
+    if (pinchData.touchEvents) {
+      expect(pinchEvents.find((e) => e.type === "touchmove")?.touches).toBe(2);
+    }
+
+    await sendCommand("execute_js", { script: `document.getElementById("touch-test").remove(); return true;` });
+  });
+
+  it("should convert device, page, and element-relative coordinates to the viewport", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("coordinates-test")?.remove();
+        const target = document.createElement("div");
+        target.id = "coordinates-test";
+        target.style.cssText = "position: fixed; left: 100px; top: 120px; width: 80px; height: 60px; background: #ccc; z-index: 9999";
+        target.addEventListener("click", (e) => { window.__coordinateClick = [e.clientX, e.clientY]; });
+        document.body.appendChild(target);
+        return true;
+      `,
+    });
+    const dpr = (await sendCommand("execute_js", { script: "window.devicePixelRatio" })).data as number;
+
+    interface Coordinates {
+      space: string;
+      origin: string | null;
+      viewportX: number;
+      viewportY: number;
+    }
+    const click = async (args: Record<string, unknown>) => {
+      const response = await sendCommand("interact", { action: "click", ...args });
+      expect(response.success).toBe(true);
+      const clicked = await sendCommand("execute_js", { script: "window.__coordinateClick" });
+      return { coordinates: (response.data as { coordinates: Coordinates }).coordinates, clicked: clicked.data };
+    };
+
+    const viewport = await click({ x: 110, y: 130 });
+    expect(viewport.coordinates).toMatchObject({ space: "css-viewport", viewportX: 110, viewportY: 130 });
+    expect(viewport.clicked).toEqual([110, 130]);
+
+    const device = await click({ x: 120 * dpr, y: 140 * dpr, coordinateSpace: "device" });
+    expect(device.coordinates).toMatchObject({ space: "device", viewportX: 120, viewportY: 140 });
+    expect(device.clicked).toEqual([120, 140]);
+
+    const relative = await click({ origin: "#coordinates-test", x: 30, y: 40 });
+    expect(relative.coordinates).toMatchObject({ origin: "#coordinates-test", viewportX: 130, viewportY: 160 });
+    expect(relative.clicked).toEqual([130, 160]);
+
+    const scroll = (await sendCommand("execute_js", { script: "[window.scrollX, window.scrollY]" })).data as number[];
+    const page = await click({ x: 140 + scroll[0], y: 150 + scroll[1], coordinateSpace: "css-page" });
+    expect(page.coordinates).toMatchObject({ viewportX: 140, viewportY: 150 });
+
+    const missing = await sendCommand("interact", { action: "click", origin: "#no-such-origin", x: 1, y: 1 });
+    expect(missing.success).toBe(false);
+    expect(missing.error).toContain("Origin element not found");
+
+    await sendCommand("execute_js", { script: `document.getElementById("coordinates-test").remove(); return true;` });
+  });
+
+  it("should clear inputs and contenteditable elements with input events", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("clear-test")?.remove();
+        const container = document.createElement("div");
+        container.id = "clear-test";
+        container.innerHTML = '<input id="clear-input" value="old text"><div id="clear-editable" contenteditable="true">Some <b>rich</b> text</div>';
+        document.body.appendChild(container);
+        window.__clearEvents = [];
+        for (const el of container.children) {
+          el.addEventListener("beforeinput", (e) => window.__clearEvents.push("beforeinput:" + e.inputType));
+          el.addEventListener("input", (e) => window.__clearEvents.push("input:" + e.inputType));
+        }
+        return true;
+      `,
+    });
+
+    const result = await sendCommand("interact", {
+      action: "sequence",
+      actions: [
+        { action: "clear", selector: "#clear-input" },
+        { action: "type", selector: "#clear-input", text: "new" },
+        { action: "clear", selector: "#clear-editable" },
+      ],
+    });
+    expect(result.success).toBe(true);
+    const steps = (result.data as { steps: { result: { cleared?: boolean; previousLength?: number } }[] }).steps;
+    expect(steps[0].result).toMatchObject({ cleared: true, previousLength: 8 });
+    expect(steps[2].result).toMatchObject({ cleared: true, previousLength: 14 });
+
+    const state = await sendCommand("execute_js", {
+      script: `[document.getElementById("clear-input").value, document.getElementById("clear-editable").textContent, window.__clearEvents]`,
+    });
+    const [value, editable, events] = state.data as [string, string, string[]];
+    expect(value).toBe("new");
+    expect(editable).toBe("");
+    expect(events).toContain("beforeinput:deleteContentBackward");
+    expect(events).toContain("input:deleteContentBackward");
+
+    const empty = await sendCommand("interact", { action: "clear", selector: "#clear-editable" });
+    expect((empty.data as { cleared: boolean }).cleared).toBe(false);
+
+    const button = await sendCommand("interact", { action: "clear", selector: "body" });
+    expect(button.success).toBe(false);
+    expect(button.error).toContain("does not accept text input");
+
+    await sendCommand("execute_js", { script: `document.getElementById("clear-test").remove(); return true;` });
+  });
+
+  it("should report the input mode and keep native input behind the opt-in", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const synthetic = await sendCommand("interact", { action: "click", selector: "body" });
+    expect(synthetic.success).toBe(true);
+    expect((synthetic.data as { inputMode: string }).inputMode).toBe("synthetic");
+
+    // The test app doesn't call Builder::native_input(true)
+    const native = await sendCommand("interact", { action: "click", selector: "body", native: true });
+    expect(native.success).toBe(false);
+    expect(native.error).toContain("native_input(true)");
+
+    const unsupported = await sendCommand("interact", { action: "hover", selector: "body", native: true });
+    expect(unsupported.success).toBe(false);
+  });
+
+  it("should reject pointer actions without a target", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const noTarget = await sendCommand("interact", { action: "hover" });
+    expect(noTarget.success).toBe(false);
+    expect(noTarget.error).toContain("needs a 'selector'");
+
+    const halfCoordinates = await sendCommand("interact", { action: "rightclick", x: 10 });
+    expect(halfCoordinates.success).toBe(false);
+    expect(halfCoordinates.error).toContain("'x' and 'y' must be given together");
+  });
+
   it("should fail for non-existent elements or windows", async (ctx) => {
     if (await skipIfAppNotAvailable()) {
       ctx.skip();
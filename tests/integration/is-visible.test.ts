@@ -0,0 +1,109 @@
+/**
+ * Integration tests for tauri_is_visible tool.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+interface Visibility {
+  visible: boolean;
+  inViewport: boolean;
+  partiallyInViewport: boolean;
+  intersectionRatio: number;
+  reason: string | null;
+}
+
+const check = async (selector: string): Promise<Visibility> => {
+  const response = await sendCommand("is_visible", { selector });
+  expect(response.success).toBe(true);
+  return response.data as Visibility;
+};
+
+describe("tauri_is_visible", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("is-visible-test")?.remove();
+        const container = document.createElement("div");
+        container.id = "is-visible-test";
+        container.innerHTML = \`
+          <div id="iv-shown" style="position: fixed; top: 0; left: 0; width: 20px; height: 20px">A</div>
+          <div id="iv-none" style="display: none">B</div>
+          <div style="opacity: 0"><span id="iv-transparent-child">C</span></div>
+          <div id="iv-hidden" style="visibility: hidden">D</div>
+          <div id="iv-no-pointer" style="pointer-events: none">E</div>
+          <div id="iv-offscreen" style="position: fixed; top: -100px; left: 0; width: 20px; height: 20px">F</div>
+          <div id="iv-partial" style="position: fixed; top: -10px; left: 0; width: 20px; height: 20px">G</div>
+        \`;
+        document.body.appendChild(container);
+        return true;
+      `,
+    });
+  });
+
+  afterAll(async () => {
+    if (!(await skipIfAppNotAvailable())) {
+      await sendCommand("execute_js", {
+        script: `document.getElementById("is-visible-test")?.remove(); return true;`,
+      });
+    }
+    disconnect();
+  });
+
+  it("should report a visible element in the viewport", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const result = await check("#iv-shown");
+    expect(result).toMatchObject({ visible: true, inViewport: true, partiallyInViewport: true, reason: null });
+  });
+
+  it("should explain CSS reasons for hidden elements", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    expect((await check("#iv-none")).reason).toBe("display: none");
+    expect((await check("#iv-transparent-child")).reason).toContain("opacity: 0 on ancestor");
+    expect((await check("#iv-hidden")).reason).toBe("visibility: hidden");
+    expect((await check("#iv-no-pointer")).reason).toBe("pointer-events: none");
+    expect((await check("#iv-none")).visible).toBe(false);
+  });
+
+  it("should detect elements outside and partly outside the viewport", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const offscreen = await check("#iv-offscreen");
+    expect(offscreen).toMatchObject({ visible: true, inViewport: false, partiallyInViewport: false });
+    expect(offscreen.reason).toBe("outside viewport");
+
+    const partial = await check("#iv-partial");
+    expect(partial).toMatchObject({ visible: true, inViewport: false, partiallyInViewport: true });
+    expect(partial.reason).toBe("partially outside viewport");
+  });
+
+  it("should fail for a missing element", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("is_visible", { selector: "#does-not-exist" });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("Element not found");
+  });
+});
@@ -0,0 +1,115 @@
+/**
+ * Integration tests for tauri_menu_click and tauri_menu_emit tools.
+ *
+ * The test app has a File menu (new-todo, a disabled export item, and a compact-mode check
+ * item) and emits "test-app://menu" with the item ID from its menu handler.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+interface MenuResult {
+  clicked: boolean;
+  menu_id: string | null;
+  item_id: string;
+  reason?: string;
+}
+
+/** Wait for the menu handler to report the given item */
+async function waitForMenuEvent(itemId: string): Promise<boolean> {
+  const response = await sendCommand("execute_js", {
+    script: `
+      for (let i = 0; i < 20; i++) {
+        if (window.__menuEvents.includes(${JSON.stringify(itemId)})) return true;
+        await new Promise((resolve) => setTimeout(resolve, 50));
+      }
+      return false;
+    `,
+  });
+  return response.data === true;
+}
+
+describe("tauri_menu_click and tauri_menu_emit", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+
+    await sendCommand("execute_js", {
+      script: `
+        window.__menuEvents = [];
+        await window.__TAURI__.event.listen("test-app://menu", (e) => window.__menuEvents.push(e.payload));
+        return true;
+      `,
+    });
+  });
+
+  afterAll(() => {
+    disconnect();
+  });
+
+  it("should click a menu item by ID and run the app's handler", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("menu_click", { menu_id: "file", item_id: "new-todo" });
+    expect(response.success).toBe(true);
+    expect(response.data as MenuResult).toEqual({ clicked: true, menu_id: "file", item_id: "new-todo" });
+    expect(await waitForMenuEvent("new-todo")).toBe(true);
+  });
+
+  it("should find items by their label", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("menu_click", { menu_id: "File", item_id: "New todo" });
+    expect(response.success).toBe(true);
+    expect((response.data as MenuResult).item_id).toBe("new-todo");
+  });
+
+  it("should not click disabled items", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("menu_click", { menu_id: "file", item_id: "export" });
+    expect(response.success).toBe(true);
+    expect(response.data as MenuResult).toMatchObject({ clicked: false, reason: "disabled" });
+  });
+
+  it("should emit a menu event without looking up the item", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("menu_emit", { item_id: "tray-show" });
+    expect(response.success).toBe(true);
+    expect((response.data as MenuResult).clicked).toBe(true);
+    expect(await waitForMenuEvent("tray-show")).toBe(true);
+  });
+
+  it("should fail for unknown items", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const unknown = await sendCommand("menu_click", { menu_id: "file", item_id: "nonexistent-item" });
+    expect(unknown.success).toBe(false);
+    expect(unknown.error).toContain("not found in menu 'file'");
+
+    const missing = await sendCommand("menu_click", { menu_id: "file" });
+    expect(missing.success).toBe(false);
+    expect(missing.error).toContain("Missing required 'item_id'");
+  });
+});
@@ -0,0 +1,47 @@
+/**
+ * Integration tests for the maximum incoming message size.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  openRawConnection,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+describe("max message size", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+  });
+
+  afterAll(() => {
+    disconnect();
+  });
+
+  it("should close connections that send oversized messages with code 1009", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const info = await sendCommand("server_info", {});
+    const { maxMessageBytes } = info.data as { maxMessageBytes: number };
+
+    const raw = await openRawConnection();
+    const closed = new Promise<{ code: number; reason: string }>((resolve) => {
+      raw.once("close", (code, reason) => resolve({ code, reason: reason.toString() }));
+    });
+    raw.send(JSON.stringify({ id: "too_big", command: "execute_js", args: { script: "x".repeat(maxMessageBytes) } }));
+
+    const { code, reason } = await closed;
+    expect(code).toBe(1009);
+    expect(reason).toContain("Message too long");
+
+    // Other connections keep working
+    const after = await sendCommand("server_info", {});
+    expect(after.success).toBe(true);
+  });
+});
@@ -0,0 +1,79 @@
+/**
+ * Integration tests for the navigation subscription topic.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  onEvent,
+  sendCommand,
+  skipIfAppNotAvailable,
+  type PluginEvent,
+} from "./setup.js";
+
+interface NavigationData {
+  event: "started" | "finished";
+  url: string;
+  windowLabel: string;
+}
+
+describe("navigation subscription", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+  });
+
+  afterAll(() => {
+    disconnect();
+  });
+
+  it("should push page load start and finish", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const info = await sendCommand("window_info", {});
+    const { label } = info.data as { label: string };
+    const received: NavigationData[] = [];
+    const finished = new Promise<void>((resolve) => {
+      const stop = onEvent((frame: PluginEvent) => {
+        if (frame.event !== "navigation") return;
+        const data = frame.data as NavigationData;
+        received.push(data);
+        if (data.event === "finished") {
+          stop();
+          resolve();
+        }
+      });
+    });
+
+    try {
+      const subscribed = await sendCommand("subscribe", { topic: "navigation", windowId: label });
+      expect(subscribed.success).toBe(true);
+
+      await sendCommand("execute_js", { script: "setTimeout(() => location.reload(), 50); return true;" });
+      await Promise.race([finished, new Promise((resolve) => setTimeout(resolve, 10000))]);
+
+      expect(received.map((event) => event.event)).toEqual(["started", "finished"]);
+      for (const event of received) {
+        expect(event.windowLabel).toBe(label);
+        expect(event.url).not.toBe("");
+      }
+    } finally {
+      await sendCommand("unsubscribe", { topic: "navigation" });
+    }
+  });
+
+  it("should reject unknown windows", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("subscribe", { topic: "navigation", windowId: "no-such-window" });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("Window 'no-such-window' not found");
+  });
+});
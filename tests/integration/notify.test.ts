@@ -0,0 +1,61 @@
+/**
+ * Integration tests for tauri_notify tool.
+ *
+ * The test app may be built without the notifications feature or the notification plugin,
+ * so these only check the command's contract.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+describe("tauri_notify", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+  });
+
+  afterAll(() => {
+    disconnect();
+  });
+
+  it("should report whether the notification was sent", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("notify", {
+      title: "tauri-mcp test",
+      body: "Integration test notification",
+    });
+
+    if (!response.success) {
+      // Plugin built without the notifications feature
+      expect(response.error).toContain("notifications");
+      return;
+    }
+
+    const data = response.data as { sent: boolean; error: string | null };
+    expect(typeof data.sent).toBe("boolean");
+    if (data.sent) {
+      expect(data.error).toBeNull();
+    } else {
+      expect(typeof data.error).toBe("string");
+    }
+  });
+
+  it("should require a title", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("notify", { body: "No title" });
+    expect(response.success).toBe(false);
+  });
+});
@@ -48,6 +48,132 @@ describe("tauri_screenshot", () => {
     ).toBe(true);
   });
 
+  it("should return the smaller encoding with preferSmaller", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("screenshot", { preferSmaller: true });
+    expect(response.success).toBe(true);
+    const data = response.data as { image: string; formatChosen: string; pngBytes: number; jpegBytes: number };
+    expect(["png", "jpeg"]).toContain(data.formatChosen);
+    expect(data.image.startsWith(`data:image/${data.formatChosen};base64,`)).toBe(true);
+    expect(data.formatChosen === "jpeg" ? data.jpegBytes : data.pngBytes).toBe(
+      Math.min(data.pngBytes, data.jpegBytes)
+    );
+  });
+
+  it("should capture a clipped region, clamped to the viewport", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("screenshot", { clip: { x: 10, y: 20, width: 100, height: 50 } });
+    expect(response.success).toBe(true);
+    const data = response.data as { image: string; clipped: boolean; clip: Record<string, number> };
+    expect(data.image.startsWith("data:image/png;base64,")).toBe(true);
+    expect(data.clipped).toBe(true);
+    expect(data.clip).toEqual({ x: 10, y: 20, width: 100, height: 50 });
+
+    const viewport = await sendCommand("execute_js", { script: "[window.innerWidth, window.innerHeight]" });
+    const [width] = viewport.data as [number, number];
+    const clamped = await sendCommand("screenshot", {
+      clip: { x: width - 40, y: -10, width: 100, height: 30 },
+    });
+    expect(clamped.success).toBe(true);
+    const clampedClip = (clamped.data as { clip: Record<string, number> }).clip;
+    expect(clampedClip.y).toBe(0);
+    expect(clampedClip.height).toBe(20);
+    expect(clampedClip.x + clampedClip.width).toBeCloseTo(width, 0);
+
+    const outside = await sendCommand("screenshot", { clip: { x: width + 10, y: 0, width: 10, height: 10 } });
+    expect(outside.success).toBe(false);
+    expect(outside.error).toContain("entirely outside");
+  });
+
+  it("should mask elements while capturing and show them again afterwards", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("screenshot", {
+      maskSelectors: [
+        "[data-testid=todo-input]",
+        "[data-testid=settings-panel] >>> [data-testid=settings-status]",
+        ".no-such-element",
+      ],
+    });
+    expect(response.success).toBe(true);
+    const data = response.data as { image: string; maskedElements: number };
+    expect(data.image.startsWith("data:image/png;base64,")).toBe(true);
+    expect(data.maskedElements).toBe(2);
+
+    const after = await sendCommand("execute_js", {
+      script:
+        "[getComputedStyle(document.querySelector('[data-testid=todo-input]')).visibility, " +
+        "document.querySelectorAll('[data-tauri-mcp-mask]').length]",
+    });
+    expect(after.data).toEqual(["visible", 0]);
+
+    const invalid = await sendCommand("screenshot", { maskSelectors: ["#ok", ""] });
+    expect(invalid.success).toBe(false);
+    expect(invalid.error).toContain("'maskSelectors[1]' must be a non-empty selector");
+  });
+
+  it("should draw a watermark in PNG and JPEG screenshots", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const png = await sendCommand("screenshot", { watermark: { text: "CI Build #1234" } });
+    expect(png.success).toBe(true);
+    expect((png.data as string).startsWith("data:image/png;base64,")).toBe(true);
+
+    const jpeg = await sendCommand("screenshot", {
+      format: "jpeg",
+      watermark: { text: "CI Build #1234", position: "top_left", opacity: 0.5 },
+    });
+    expect(jpeg.success).toBe(true);
+    expect((jpeg.data as string).startsWith("data:image/jpeg;base64,")).toBe(true);
+
+    const invalid = await sendCommand("screenshot", { watermark: { text: "CI", position: "middle" } });
+    expect(invalid.success).toBe(false);
+    expect(invalid.error).toContain("Invalid 'watermark.position'");
+  });
+
+  it("should composite all visible windows into one PNG", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("screenshot_composite", { padding: 20 });
+    expect(response.success).toBe(true);
+    const data = response.data as {
+      image: string;
+      width: number;
+      height: number;
+      windows: { label: string; x: number; y: number; width: number; height: number }[];
+    };
+    expect(data.image.startsWith("data:image/png;base64,")).toBe(true);
+    expect(data.windows.length).toBeGreaterThan(0);
+    for (const window of data.windows) {
+      // The padding keeps every window clear of the image edges
+      expect(window.x).toBeGreaterThanOrEqual(20);
+      expect(window.y).toBeGreaterThanOrEqual(20);
+      expect(window.x + window.width + 20).toBeLessThanOrEqual(data.width);
+      expect(window.y + window.height + 20).toBeLessThanOrEqual(data.height);
+    }
+
+    const invalid = await sendCommand("screenshot_composite", { padding: -1 });
+    expect(invalid.success).toBe(false);
+    expect(invalid.error).toContain("'padding' must be an integer");
+  });
+
   it("should fail for non-existent window", async (ctx) => {
     if (await skipIfAppNotAvailable()) {
       ctx.skip();
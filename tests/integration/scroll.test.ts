@@ -0,0 +1,147 @@
+/**
+ * Integration tests for tauri_scroll_info and tauri_scroll_to tools.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+interface ScrollInfo {
+  scrollX: number;
+  scrollY: number;
+  scrollWidth: number;
+  scrollHeight: number;
+  clientWidth: number;
+  clientHeight: number;
+}
+
+describe("tauri_scroll_info and tauri_scroll_to", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+
+    // Make the page and a container tall enough to scroll
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("scroll-test")?.remove();
+        const container = document.createElement("div");
+        container.id = "scroll-test";
+        container.innerHTML = \`
+          <div id="scroll-box" style="height: 100px; overflow: auto">
+            <div style="height: 1000px"></div>
+          </div>
+          <div style="height: 3000px"></div>
+          <div id="scroll-target">Target</div>
+        \`;
+        document.body.appendChild(container);
+        window.scrollTo(0, 0);
+        return true;
+      `,
+    });
+  });
+
+  afterAll(async () => {
+    if (!(await skipIfAppNotAvailable())) {
+      await sendCommand("execute_js", {
+        script: `document.getElementById("scroll-test")?.remove(); window.scrollTo(0, 0); return true;`,
+      });
+    }
+    disconnect();
+  });
+
+  it("should report page scroll info", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("scroll_info", {});
+    expect(response.success).toBe(true);
+
+    const info = response.data as ScrollInfo;
+    expect(info.scrollY).toBe(0);
+    expect(info.scrollHeight).toBeGreaterThan(info.clientHeight);
+  });
+
+  it("should report element scroll info", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `document.getElementById("scroll-box").scrollTop = 250; return true;`,
+    });
+
+    const response = await sendCommand("scroll_info", { selector: "#scroll-box" });
+    expect(response.success).toBe(true);
+
+    const info = response.data as ScrollInfo;
+    expect(info.scrollY).toBe(250);
+    expect(info.scrollHeight).toBe(1000);
+    expect(info.clientHeight).toBe(100);
+  });
+
+  it("should wait for smooth scrolling to finish", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("scroll_to", { y: 1500, behavior: "smooth" });
+    expect(response.success).toBe(true);
+    expect((response.data as ScrollInfo).scrollY).toBe(1500);
+  });
+
+  it("should scroll an element into view", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("scroll_to", { selector: "#scroll-target", block: "start" });
+    expect(response.success).toBe(true);
+
+    const top = await sendCommand("execute_js", {
+      script: `Math.round(document.getElementById("scroll-target").getBoundingClientRect().top)`,
+    });
+    expect(top.data).toBe(0);
+  });
+
+  it("should reject an invalid behavior", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("scroll_to", { y: 0, behavior: "bouncy" });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("Invalid behavior");
+  });
+
+  it("should require a position or selector", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("scroll_to", {});
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("selector");
+  });
+
+  it("should fail for a missing element", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("scroll_info", { selector: "#does-not-exist" });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("Element not found");
+  });
+});
@@ -0,0 +1,38 @@
+/**
+ * Integration tests for server_restart.
+ *
+ * The test-app doesn't opt in, since a restart would drop the connections of the other test
+ * files running at the same time, so this only checks that it's refused.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import { connect, disconnect, sendCommand, skipIfAppNotAvailable } from "./setup.js";
+
+describe("server_restart", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+  });
+
+  afterAll(() => {
+    disconnect();
+  });
+
+  it("should be refused unless the app opts in", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const info = await sendCommand("server_info", {});
+    expect((info.data as { serverRestart: boolean }).serverRestart).toBe(false);
+
+    const response = await sendCommand("server_restart", { port: 0 });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("server_restart is off");
+
+    // The connection is still up
+    const after = await sendCommand("server_info", {});
+    expect(after.success).toBe(true);
+  });
+});
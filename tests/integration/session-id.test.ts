@@ -0,0 +1,56 @@
+/**
+ * Integration tests for per-connection session IDs.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import type { PluginResponse } from "./setup.js";
+import {
+  connect,
+  disconnect,
+  openRawConnection,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+describe("session ID", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+  });
+
+  afterAll(() => {
+    disconnect();
+  });
+
+  it("should tag every response on a connection with the same session ID", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const first = await sendCommand("server_info", {});
+    const failed = await sendCommand("no_such_command", {});
+    expect(first.sessionId).toMatch(/^[0-9a-f-]{36}$/);
+    expect(failed.success).toBe(false);
+    expect(failed.sessionId).toBe(first.sessionId);
+  });
+
+  it("should give each connection its own session ID", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const ours = await sendCommand("server_info", {});
+    const raw = await openRawConnection();
+    const response = new Promise<PluginResponse>((resolve) => {
+      raw.once("message", (data) => resolve(JSON.parse(data.toString()) as PluginResponse));
+    });
+    raw.send(JSON.stringify({ id: "other_connection", command: "server_info", args: {} }));
+
+    const theirs = await response;
+    raw.close();
+    expect(theirs.sessionId).toBeDefined();
+    expect(theirs.sessionId).not.toBe(ours.sessionId);
+  });
+});
@@ -14,6 +14,7 @@ export interface PluginResponse {
   success: boolean;
   data?: unknown;
   error?: string;
+  sessionId?: string;
   windowContext?: {
     windowLabel: string;
     totalWindows: number;
@@ -21,6 +22,20 @@ export interface PluginResponse {
   };
 }
 
+export interface PluginEvent {
+  event: string;
+  data: unknown;
+}
+
+export type EventListener = (event: PluginEvent) => void;
+
+/** Frames a response arrives in when the request has `stream: true` and the response is large */
+export type StreamFrame =
+  | { type: "stream_chunk"; id: string; chunk: number; data: string }
+  | { type: "stream_end"; id: string; chunks: number };
+
+export type StreamFrameListener = (frame: StreamFrame) => void;
+
 // ============================================================================
 // Configuration
 // ============================================================================
@@ -41,8 +56,11 @@ let pendingRequests = new Map<
     resolve: (response: PluginResponse) => void;
     reject: (error: Error) => void;
     timeout: ReturnType<typeof setTimeout>;
+    chunks: string[];
   }
 >();
+const eventListeners = new Set<EventListener>();
+const streamFrameListeners = new Set<StreamFrameListener>();
 let requestCounter = 0;
 let appAvailable: boolean | null = null;
 
@@ -79,6 +97,43 @@ export const isAppAvailable = async (): Promise<boolean> => {
   });
 };
 
+/**
+ * Open a separate connection that the harness doesn't manage, for tests of the protocol itself.
+ * Close it when done.
+ */
+export const openRawConnection = async (): Promise<WebSocket> => {
+  const raw = new WebSocket(`ws://${host}:${port}`);
+  await new Promise<void>((resolve, reject) => {
+    raw.once("open", () => resolve());
+    raw.once("error", reject);
+  });
+  return raw;
+};
+
+/**
+ * Open a connection to the CDP endpoint on the port after the plugin's. Close it when done.
+ */
+export const openCdpConnection = async (): Promise<WebSocket> => {
+  const cdp = new WebSocket(`ws://${host}:${port + 1}`);
+  await new Promise<void>((resolve, reject) => {
+    cdp.once("open", () => resolve());
+    cdp.once("error", reject);
+  });
+  return cdp;
+};
+
+/**
+ * Open a connection to the WebDriver BiDi endpoint two ports after the plugin's. Close it when done.
+ */
+export const openBidiConnection = async (): Promise<WebSocket> => {
+  const bidi = new WebSocket(`ws://${host}:${port + 2}`);
+  await new Promise<void>((resolve, reject) => {
+    bidi.once("open", () => resolve());
+    bidi.once("error", reject);
+  });
+  return bidi;
+};
+
 /**
  * Connect to the test-app. Must be called before sending commands.
  */
@@ -101,7 +156,30 @@ export const connect = async (): Promise<void> => {
 
     ws.on("message", (data: WebSocket.Data) => {
       try {
-        const message = JSON.parse(data.toString()) as PluginResponse;
+        const parsed = JSON.parse(data.toString()) as PluginResponse | PluginEvent | StreamFrame;
+
+        // Event frames from subscriptions have no ID
+        if ("event" in parsed) {
+          for (const listener of eventListeners) listener(parsed);
+          return;
+        }
+
+        // Streamed responses come in chunks, joined into the response at the end
+        if ("type" in parsed) {
+          for (const listener of streamFrameListeners) listener(parsed);
+          const pending = pendingRequests.get(parsed.id);
+          if (!pending) return;
+          if (parsed.type === "stream_chunk") {
+            pending.chunks[parsed.chunk] = parsed.data;
+            return;
+          }
+          clearTimeout(pending.timeout);
+          pendingRequests.delete(parsed.id);
+          pending.resolve(JSON.parse(pending.chunks.join("")) as PluginResponse);
+          return;
+        }
+
+        const message = parsed;
         const pending = pendingRequests.get(message.id);
         if (pending) {
           clearTimeout(pending.timeout);
@@ -140,11 +218,13 @@ export const disconnect = (): void => {
 
 /**
  * Send a command to the plugin and wait for response.
+ * Requests with the same `dedupKey` share one run while the first is in flight.
  */
 export const sendCommand = async (
   command: string,
   args?: Record<string, unknown>,
-  timeoutMs: number = defaultTimeout
+  timeoutMs: number = defaultTimeout,
+  dedupKey?: string
 ): Promise<PluginResponse> => {
   if (!ws || ws.readyState !== WebSocket.OPEN) {
     throw new Error("Not connected to test-app");
@@ -158,9 +238,9 @@ export const sendCommand = async (
       reject(new Error(`Request timed out after ${timeoutMs}ms`));
     }, timeoutMs);
 
-    pendingRequests.set(id, { resolve, reject, timeout });
+    pendingRequests.set(id, { resolve, reject, timeout, chunks: [] });
 
-    ws!.send(JSON.stringify({ id, command, args }), (error) => {
+    ws!.send(JSON.stringify({ id, command, args, dedup_key: dedupKey }), (error) => {
       if (error) {
         clearTimeout(timeout);
         pendingRequests.delete(id);
@@ -170,6 +250,26 @@ export const sendCommand = async (
   });
 };
 
+/**
+ * Listen for event frames pushed by subscriptions. Returns a function that removes the listener.
+ */
+export const onEvent = (listener: EventListener): (() => void) => {
+  eventListeners.add(listener);
+  return () => {
+    eventListeners.delete(listener);
+  };
+};
+
+/**
+ * Listen for the frames of streamed responses. Returns a function that removes the listener.
+ */
+export const onStreamFrame = (listener: StreamFrameListener): (() => void) => {
+  streamFrameListeners.add(listener);
+  return () => {
+    streamFrameListeners.delete(listener);
+  };
+};
+
 /**
  * Helper to skip a test if the app isn't available.
  */
@@ -0,0 +1,88 @@
+/**
+ * Integration tests for streaming large responses in chunks.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  onStreamFrame,
+  sendCommand,
+  skipIfAppNotAvailable,
+  type StreamFrame,
+} from "./setup.js";
+
+describe("streamed responses", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+
+    // Enough content for a snapshot bigger than one chunk
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("streaming-test")?.remove();
+        const container = document.createElement("ul");
+        container.id = "streaming-test";
+        for (let i = 0; i < 20000; i++) {
+          const item = document.createElement("li");
+          item.id = "streaming-item-" + i;
+          item.className = "streaming-item";
+          container.appendChild(item);
+        }
+        document.body.appendChild(container);
+        return true;
+      `,
+    });
+  });
+
+  afterAll(async () => {
+    if (!(await skipIfAppNotAvailable())) {
+      await sendCommand("execute_js", {
+        script: `document.getElementById("streaming-test")?.remove(); return true;`,
+      });
+    }
+    disconnect();
+  });
+
+  it("should stream a large snapshot in chunks with the same result", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const whole = await sendCommand("dom_snapshot", { type: "structure", selector: "#streaming-test" });
+    expect(whole.success).toBe(true);
+
+    const frames: StreamFrame[] = [];
+    const stop = onStreamFrame((frame) => frames.push(frame));
+    const streamed = await sendCommand("dom_snapshot", {
+      type: "structure",
+      selector: "#streaming-test",
+      stream: true,
+    });
+    stop();
+
+    expect(streamed.success).toBe(true);
+    expect(streamed.data).toEqual(whole.data);
+    const chunks = frames.filter((frame) => frame.type === "stream_chunk");
+    expect(chunks.length).toBeGreaterThan(1);
+    expect(chunks.map((frame) => frame.chunk)).toEqual(chunks.map((_, i) => i));
+    expect(frames.at(-1)).toMatchObject({ type: "stream_end", chunks: chunks.length });
+  });
+
+  it("should send small responses whole even when streaming", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const frames: StreamFrame[] = [];
+    const stop = onStreamFrame((frame) => frames.push(frame));
+    const response = await sendCommand("execute_js", { script: "1 + 1", stream: true });
+    stop();
+
+    expect(response.success).toBe(true);
+    expect(response.data).toBe(2);
+    expect(frames).toHaveLength(0);
+  });
+});
@@ -0,0 +1,136 @@
+/**
+ * Integration tests for tauri_table_data tool.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+interface TableData {
+  headers: string[];
+  rows: (string | { text: string; html: string })[][];
+}
+
+describe("tauri_table_data", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+
+    // The test app has no tables, so inject some for these tests
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("table-data-test")?.remove();
+        const container = document.createElement("div");
+        container.id = "table-data-test";
+        container.innerHTML = \`
+          <table id="td-users">
+            <thead><tr><th>Name</th><th>Email</th></tr></thead>
+            <tbody>
+              <tr><td><b>Ada</b></td><td>ada@example.com</td></tr>
+              <tr><td>Linus</td><td>linus@example.com</td></tr>
+            </tbody>
+          </table>
+          <table id="td-no-thead">
+            <tr><th>Key</th><th>Value</th></tr>
+            <tr><th>Color</th><td>Blue</td></tr>
+          </table>
+        \`;
+        document.body.appendChild(container);
+        return true;
+      `,
+    });
+  });
+
+  afterAll(async () => {
+    if (!(await skipIfAppNotAvailable())) {
+      await sendCommand("execute_js", {
+        script: `document.getElementById("table-data-test")?.remove(); return true;`,
+      });
+    }
+    disconnect();
+  });
+
+  it("should read headers and rows as text", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("table_data", { selector: "#td-users" });
+    expect(response.success).toBe(true);
+
+    const table = response.data as TableData;
+    expect(table.headers).toEqual(["Name", "Email"]);
+    expect(table.rows).toEqual([
+      ["Ada", "ada@example.com"],
+      ["Linus", "linus@example.com"],
+    ]);
+  });
+
+  it("should include cell HTML when requested", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("table_data", {
+      selector: "#td-users",
+      includeHtml: true,
+    });
+    expect(response.success).toBe(true);
+
+    const table = response.data as TableData;
+    expect(table.rows[0][0]).toEqual({ text: "Ada", html: "<b>Ada</b>" });
+  });
+
+  it("should use a leading row of th cells as headers without a thead", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("table_data", { selector: "#td-no-thead" });
+    expect(response.success).toBe(true);
+
+    const table = response.data as TableData;
+    expect(table.headers).toEqual(["Key", "Value"]);
+    expect(table.rows).toEqual([["Color", "Blue"]]);
+  });
+
+  it("should fail for a missing table", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("table_data", { selector: "#does-not-exist" });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("Table not found");
+  });
+
+  it("should fail for a non-table element", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("table_data", { selector: "#table-data-test" });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("not a <table>");
+  });
+
+  it("should require a selector", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("table_data", {});
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("selector");
+  });
+});
@@ -0,0 +1,120 @@
+/**
+ * Integration tests for tauri_viewport_set and tauri_viewport_get tools.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  sendCommand,
+  skipIfAppNotAvailable,
+} from "./setup.js";
+
+interface Viewport {
+  width: number;
+  height: number;
+  deviceScaleFactor: number;
+  mobile: boolean;
+  emulated: boolean;
+  windowWidth?: number;
+  windowHeight?: number;
+  nativeScaleFactor?: number;
+}
+
+describe("tauri_viewport_set and tauri_viewport_get", () => {
+  let original: Viewport | undefined;
+
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+
+    const response = await sendCommand("viewport_get", {});
+    original = response.data as Viewport;
+  });
+
+  afterAll(async () => {
+    // Restore the window size and drop the overrides
+    if (original) {
+      await sendCommand("viewport_set", {
+        width: Math.round(original.windowWidth ?? original.width),
+        height: Math.round(original.windowHeight ?? original.height),
+      });
+    }
+    disconnect();
+  });
+
+  it("should report the current viewport", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("viewport_get", {});
+    expect(response.success).toBe(true);
+
+    const viewport = response.data as Viewport;
+    expect(viewport.width).toBeGreaterThan(0);
+    expect(viewport.height).toBeGreaterThan(0);
+    expect(viewport.deviceScaleFactor).toBeGreaterThan(0);
+    expect(viewport.nativeScaleFactor).toBeGreaterThan(0);
+  });
+
+  it("should emulate a mobile viewport", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("viewport_set", {
+      width: 390,
+      height: 600,
+      deviceScaleFactor: 3,
+      mobile: true,
+    });
+    expect(response.success).toBe(true);
+
+    const viewport = response.data as Viewport;
+    expect(viewport.width).toBe(390);
+    expect(viewport.height).toBe(600);
+    expect(viewport.deviceScaleFactor).toBe(3);
+    expect(viewport.mobile).toBe(true);
+    expect(viewport.emulated).toBe(true);
+
+    const media = await sendCommand("execute_js", {
+      script: "return window.matchMedia('(max-width: 400px)').matches",
+    });
+    expect(media.data).toBe(true);
+  });
+
+  it("should undo the overrides when called without them", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("viewport_set", { width: 390, height: 600, deviceScaleFactor: 3, mobile: true });
+    const response = await sendCommand("viewport_set", { width: 800, height: 600 });
+    expect(response.success).toBe(true);
+
+    const viewport = response.data as Viewport;
+    expect(viewport.emulated).toBe(false);
+    expect(viewport.mobile).toBe(false);
+    expect(viewport.deviceScaleFactor).toBe(original?.nativeScaleFactor);
+
+    const meta = await sendCommand("execute_js", {
+      script: "return document.getElementById('__tauri-mcp-viewport') === null",
+    });
+    expect(meta.data).toBe(true);
+  });
+
+  it("should reject a missing width", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("viewport_set", { height: 600 });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("width");
+  });
+});
@@ -30,7 +30,7 @@ describe("tauri_wait_for", () => {
     const selectorResponse = await sendCommand("wait_for", {
       type: "selector",
       value: "body",
-      timeout: 5000,
+      timeoutMs: 5000,
     });
     expect(selectorResponse.success).toBe(true);
 
@@ -38,7 +38,7 @@ describe("tauri_wait_for", () => {
     const visibleResponse = await sendCommand("wait_for", {
       type: "visible",
       value: "body",
-      timeout: 5000,
+      timeoutMs: 5000,
     });
     expect(visibleResponse.success).toBe(true);
   });
@@ -58,7 +58,7 @@ describe("tauri_wait_for", () => {
     const response = await sendCommand("wait_for", {
       type: "text",
       value: "unique-wait-text-12345",
-      timeout: 5000,
+      timeoutMs: 5000,
     });
     expect(response.success).toBe(true);
 
@@ -77,13 +77,331 @@ describe("tauri_wait_for", () => {
     const response = await sendCommand("wait_for", {
       type: "selector",
       value: "#nonexistent-element-that-will-never-appear-12345",
-      timeout: 1000,
+      timeoutMs: 1000,
     });
 
     expect(response.success).toBe(false);
     expect(response.error?.toLowerCase()).toContain("timeout");
   });
 
+  it("should time out with the condition's error, never the script execution timeout", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    // Not a whole number of seconds
+    const selector = await sendCommand("wait_for", { type: "selector", value: "#never-there", timeoutMs: 1500 });
+    expect(selector.error).toMatch(/^Timeout after 1500ms waiting for '#never-there'/);
+
+    // The deprecated `timeout` is in milliseconds too
+    const legacy = await sendCommand("wait_for", { type: "text", value: "never-there-text", timeout: 700 });
+    expect(legacy.error).toMatch(/^Timeout after 700ms waiting for text/);
+
+    const hanging = await sendCommand("wait_for", {
+      type: "function",
+      script: "return new Promise(() => {})",
+      timeoutMs: 500,
+    });
+    expect(hanging.error).toContain("Timeout after 500ms waiting for the predicate");
+    expect(hanging.error).toContain("predicate was still running");
+
+    const compound = await sendCommand("wait_for", {
+      type: "any",
+      conditions: [
+        { type: "selector", value: "#never-there" },
+        { type: "function", script: "return new Promise(() => {})" },
+      ],
+      timeoutMs: 500,
+    });
+    expect(compound.error).toContain("Timeout after 500ms waiting for any of 2 conditions");
+
+    for (const response of [selector, legacy, hanging, compound]) {
+      expect(response.success).toBe(false);
+      expect(response.error).not.toContain("Script execution timeout");
+    }
+  });
+
+  it("should report elapsed time, polls, and what the last check saw", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `setTimeout(() => document.body.insertAdjacentHTML("beforeend", '<div id="stats-test">late</div>'), 300); return true;`,
+    });
+    const response = await sendCommand("wait_for", { type: "selector", value: "#stats-test", timeoutMs: 3000 });
+    expect(response.success).toBe(true);
+    const data = response.data as { elapsedMs: number; polls: number; condition: string; detail: string };
+    expect(data.elapsedMs).toBeGreaterThanOrEqual(250);
+    expect(data.polls).toBeGreaterThan(1);
+    expect(data.condition).toBe("selector '#stats-test'");
+    expect(data.detail).toBe("selector matched 1 element");
+
+    const missing = await sendCommand("wait_for", { type: "selector", value: "#never-there", timeoutMs: 300 });
+    expect(missing.error).toMatch(/^Timeout after 300ms waiting for '#never-there' to appear \(waited \d+ms over \d+ polls; selector matched 0 elements; /);
+
+    await sendCommand("execute_js", { script: `document.getElementById("stats-test")?.remove(); return true;` });
+  });
+
+  it("should say how an element got hidden", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("wait-hidden-test")?.remove();
+        const container = document.createElement("div");
+        container.id = "wait-hidden-test";
+        container.innerHTML = '<div id="wait-spinner" style="width: 20px; height: 20px">spinning</div>';
+        document.body.appendChild(container);
+        // Hidden through a stylesheet, which isn't a mutation of the element
+        const style = document.createElement("style");
+        style.id = "wait-hidden-style";
+        document.head.appendChild(style);
+        setTimeout(() => { style.textContent = "#wait-hidden-test { display: none; }"; }, 200);
+        return true;
+      `,
+    });
+
+    const hidden = await sendCommand("wait_for", { type: "hidden", value: "#wait-spinner", timeoutMs: 3000 });
+    expect(hidden.success).toBe(true);
+    expect(hidden.data).toMatchObject({ reason: "display: none" });
+
+    await sendCommand("execute_js", {
+      script: `document.getElementById("wait-hidden-style").remove(); return true;`,
+    });
+    await sendCommand("execute_js", {
+      script: `
+        const spinner = document.getElementById("wait-spinner");
+        setTimeout(() => { spinner.style.height = "0px"; }, 200);
+        return true;
+      `,
+    });
+    const shrunk = await sendCommand("wait_for", { type: "hidden", value: "#wait-spinner", timeoutMs: 3000 });
+    expect(shrunk.data).toMatchObject({ reason: "zero size" });
+  });
+
+  it("should wait for elements to be removed", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        const spinner = document.getElementById("wait-spinner");
+        spinner.style.display = "none";
+        setTimeout(() => spinner.remove(), 300);
+        return true;
+      `,
+    });
+
+    // Hidden already, but not removed yet
+    const detached = await sendCommand("wait_for", { type: "detached", value: "#wait-spinner", timeoutMs: 3000 });
+    expect(detached.success).toBe(true);
+    expect(detached.data).toMatchObject({ reason: "removed" });
+    const gone = await sendCommand("execute_js", { script: `document.getElementById("wait-spinner") === null` });
+    expect(gone.data).toBe(true);
+
+    const timeout = await sendCommand("wait_for", { type: "detached", value: "body", timeoutMs: 500 });
+    expect(timeout.success).toBe(false);
+    expect(timeout.error).toContain("to be removed");
+
+    await sendCommand("execute_js", { script: `document.getElementById("wait-hidden-test")?.remove(); return true;` });
+  });
+
+  it("should wait for a predicate and return its value", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        window.__waitStore = { sync: { done: false } };
+        setTimeout(() => { window.__waitStore.sync = { done: true, items: 3 }; }, 300);
+        return true;
+      `,
+    });
+
+    const response = await sendCommand("wait_for", {
+      type: "function",
+      script: "window.__waitStore.sync.done && window.__waitStore.sync",
+      pollIntervalMs: 20,
+      timeoutMs: 3000,
+    });
+    expect(response.success).toBe(true);
+    expect(response.data).toMatchObject({ value: { done: true, items: 3 } });
+
+    const withArgs = await sendCommand("wait_for", {
+      type: "function",
+      script: "return window.__waitStore.sync.items >= args.min;",
+      args: { min: 3 },
+    });
+    expect(withArgs.data).toMatchObject({ value: true });
+
+    await sendCommand("execute_js", { script: "delete window.__waitStore; return true;" });
+  });
+
+  it("should fail predicates with syntax errors right away", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const started = Date.now();
+    const syntax = await sendCommand("wait_for", { type: "function", script: "return (", timeoutMs: 5000 });
+    expect(syntax.success).toBe(false);
+    expect(syntax.error).toContain("Invalid predicate script");
+    expect(Date.now() - started).toBeLessThan(2000);
+
+    const throwing = await sendCommand("wait_for", {
+      type: "function",
+      script: "window.__missingStore.ready",
+      timeoutMs: 300,
+    });
+    expect(throwing.success).toBe(false);
+    expect(throwing.error).toContain("predicate last threw");
+  });
+
+  it("should wait for console entries", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const marker = `[app] ready ${Date.now()}`;
+    await sendCommand("execute_js", {
+      script: `setTimeout(() => { console.debug("not yet"); console.info(${JSON.stringify(marker)}); }, 200); return true;`,
+    });
+
+    const response = await sendCommand("wait_for", { type: "console", textContains: marker, level: "info", timeoutMs: 3000 });
+    expect(response.success).toBe(true);
+    expect(response.data).toMatchObject({ entry: { level: "info", message: marker } });
+
+    // Already logged, so only found with includeHistory
+    const history = await sendCommand("wait_for", {
+      type: "console",
+      regex: "^\\[app\\] ready \\d+$",
+      includeHistory: true,
+      timeoutMs: 300,
+    });
+    expect(history.data).toMatchObject({ entry: { message: marker } });
+
+    const missed = await sendCommand("wait_for", { type: "console", textContains: marker, timeoutMs: 300 });
+    expect(missed.success).toBe(false);
+    expect(missed.error).toContain(`Timeout after 300ms waiting for a console entry containing '${marker}'`);
+  });
+
+  it("should wait for all or any of several conditions", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    await sendCommand("execute_js", {
+      script: `
+        document.getElementById("compound-test")?.remove();
+        const container = document.createElement("div");
+        container.id = "compound-test";
+        container.innerHTML = '<span class="compound-spinner">Loading</span>';
+        document.body.appendChild(container);
+        setTimeout(() => {
+          container.innerHTML = '<div class="compound-row">Row</div>';
+        }, 200);
+        return true;
+      `,
+    });
+
+    const all = await sendCommand("wait_for", {
+      type: "all",
+      conditions: [
+        { type: "selector", value: "#compound-test" },
+        { type: "selector", value: ".compound-row" },
+        { type: "hidden", value: ".compound-spinner" },
+      ],
+      timeoutMs: 3000,
+    });
+    expect(all.success).toBe(true);
+    const { conditions } = all.data as { conditions: { met: boolean; elapsedMs: number }[] };
+    expect(conditions.every((condition) => condition.met)).toBe(true);
+    expect(conditions[0].elapsedMs).toBeLessThan(conditions[1].elapsedMs);
+
+    const any = await sendCommand("wait_for", {
+      type: "any",
+      conditions: [
+        { type: "selector", value: ".compound-missing" },
+        { type: "text", value: "Row" },
+      ],
+      timeoutMs: 1000,
+    });
+    expect(any.data).toMatchObject({
+      message: "1 of 2 conditions met",
+      conditions: [{ type: "selector", met: false }, { type: "text", met: true }],
+    });
+
+    const partial = await sendCommand("wait_for", {
+      type: "all",
+      conditions: [
+        { type: "selector", value: ".compound-row" },
+        { type: "selector", value: ".compound-missing" },
+      ],
+      timeoutMs: 300,
+    });
+    expect(partial.success).toBe(false);
+    expect(partial.error).toContain("Timeout after 300ms waiting for all of 2 conditions");
+    expect(partial.error).toMatch(/selector '\.compound-row': met after \d+ms/);
+    expect(partial.error).toContain("selector '.compound-missing': not met");
+
+    const nested = await sendCommand("wait_for", {
+      type: "all",
+      conditions: [{ type: "any", conditions: [{ type: "text", value: "Row" }] }],
+    });
+    expect(nested.error).toContain("can't be nested");
+
+    await sendCommand("execute_js", { script: `document.getElementById("compound-test")?.remove(); return true;` });
+  });
+
+  it("should wait for the window count", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("wait_for", { type: "windowCount", atLeast: 1, timeoutMs: 1000 });
+    expect(response.success).toBe(true);
+    const { count, windows } = response.data as { count: number; windows: string[] };
+    expect(windows).toHaveLength(count);
+
+    const tooMany = await sendCommand("wait_for", { type: "windowCount", exactly: count + 1, timeoutMs: 300 });
+    expect(tooMany.success).toBe(false);
+    expect(tooMany.error).toContain(`Timeout after 300ms waiting for exactly ${count + 1} windows`);
+  });
+
+  it("should wait for Tauri events", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const event = `test-app://sync-done-${Date.now()}`;
+    await sendCommand("execute_js", {
+      script: `setTimeout(() => window.__TAURI__.event.emit("${event}", { items: 3 }), 200); return true;`,
+    });
+
+    const response = await sendCommand("wait_for", { type: "tauriEvent", event, timeoutMs: 3000 });
+    expect(response.success).toBe(true);
+    expect(response.data).toMatchObject({ payload: { items: 3 }, polls: 1, condition: `event '${event}'` });
+
+    const invalid = await sendCommand("wait_for", { type: "tauriEvent", event: "no spaces" });
+    expect(invalid.success).toBe(false);
+    expect(invalid.error).toContain("Invalid event name");
+  });
+
   it("should fail for non-existent window", async (ctx) => {
     if (await skipIfAppNotAvailable()) {
       ctx.skip();
@@ -93,7 +411,7 @@ describe("tauri_wait_for", () => {
     const response = await sendCommand("wait_for", {
       type: "selector",
       value: "body",
-      timeout: 1000,
+      timeoutMs: 1000,
       window_id: "nonexistent-window-12345",
     });
 
@@ -0,0 +1,75 @@
+/**
+ * Integration tests for the windows subscription topic.
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from "vitest";
+import {
+  connect,
+  disconnect,
+  onEvent,
+  sendCommand,
+  skipIfAppNotAvailable,
+  type PluginEvent,
+} from "./setup.js";
+
+interface WindowEventData {
+  event: string;
+  label: string;
+  data: Record<string, number>;
+}
+
+describe("windows subscription", () => {
+  beforeAll(async () => {
+    if (await skipIfAppNotAvailable()) return;
+    await connect();
+  });
+
+  afterAll(() => {
+    disconnect();
+  });
+
+  it("should push resize events for the subscribed kinds only", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const received: WindowEventData[] = [];
+    const stop = onEvent((frame: PluginEvent) => {
+      if (frame.event === "window_event") received.push(frame.data as WindowEventData);
+    });
+
+    const info = await sendCommand("window_info", {});
+    const original = info.data as { label: string; width: number; height: number };
+
+    try {
+      const subscribed = await sendCommand("subscribe", { topic: "windows", events: ["resized"] });
+      expect(subscribed.success).toBe(true);
+
+      await sendCommand("window_resize", { width: original.width + 40, height: original.height + 30 });
+      await new Promise((resolve) => setTimeout(resolve, 500));
+
+      expect(received.length).toBeGreaterThan(0);
+      for (const event of received) {
+        expect(event.event).toBe("resized");
+        expect(event.label).toBe(original.label);
+        expect(event.data.width).toBeGreaterThan(0);
+      }
+    } finally {
+      stop();
+      await sendCommand("unsubscribe", { topic: "windows" });
+      await sendCommand("window_resize", { width: original.width, height: original.height });
+    }
+  });
+
+  it("should reject unknown window event kinds", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const response = await sendCommand("subscribe", { topic: "windows", events: ["opened"] });
+    expect(response.success).toBe(false);
+    expect(response.error).toContain("'events' must be an array of created, closed");
+  });
+});
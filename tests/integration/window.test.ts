@@ -47,6 +47,32 @@ describe("window management", () => {
     }
   });
 
+  it("should sort and filter the window list", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const byLabel = await sendCommand("window_list", { sort: "label" });
+    expect(byLabel.success).toBe(true);
+    const labels = (byLabel.data as Array<{ label: string }>).map((win) => win.label);
+    expect(labels).toEqual([...labels].sort());
+
+    const focusedFirst = await sendCommand("window_list", { sort: "focused_first" });
+    const focused = (focusedFirst.data as Array<{ focused: boolean }>).map((win) => win.focused);
+    expect(focused).toEqual([...focused].sort((a, b) => Number(b) - Number(a)));
+
+    const visible = await sendCommand("window_list", { filter: { visible: true } });
+    expect(visible.success).toBe(true);
+    for (const win of visible.data as Array<{ visible: boolean }>) {
+      expect(win.visible).toBe(true);
+    }
+
+    const invalid = await sendCommand("window_list", { sort: "size" });
+    expect(invalid.success).toBe(false);
+    expect(invalid.error).toContain("Available: label, title, focused_first");
+  });
+
   it("should get window info for default and specific windows", async (ctx) => {
     if (await skipIfAppNotAvailable()) {
       ctx.skip();
@@ -171,4 +197,24 @@ describe("window management", () => {
     expect(resizeResponse.success).toBe(false);
     expect(resizeResponse.error?.toLowerCase()).toContain("not found");
   });
+
+  it("should resolve windows by glob pattern and alias", async (ctx) => {
+    if (await skipIfAppNotAvailable()) {
+      ctx.skip();
+      return;
+    }
+
+    const pattern = await sendCommand("window_info", { windowId: "m?in*" });
+    expect(pattern.success).toBe(true);
+    expect((pattern.data as { label: string }).label).toBe("main");
+
+    // The test app registers window_alias("primary", "mai*")
+    const alias = await sendCommand("window_info", { windowId: "primary" });
+    expect(alias.success).toBe(true);
+    expect((alias.data as { label: string }).label).toBe("main");
+
+    const noMatch = await sendCommand("window_info", { windowId: "settings-*" });
+    expect(noMatch.success).toBe(false);
+    expect(noMatch.error).toContain("Aliases: primary");
+  });
 });